@@ -0,0 +1,11 @@
+// Build script: compiles the gRPC service definition when the `grpc` feature is enabled.
+
+fn main() {
+    #[cfg(feature = "grpc")]
+    {
+        // Use a vendored protoc so builds don't depend on a system install
+        std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path().unwrap());
+        tonic_build::compile_protos("proto/arbishark.proto").unwrap();
+        println!("cargo:rerun-if-changed=proto/arbishark.proto");
+    }
+}
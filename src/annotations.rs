@@ -0,0 +1,245 @@
+//! Market event annotations
+//!
+//! Time windows tagged with what was going on - a debate night, a CPI
+//! release, a game start - declared in `[annotations]` or added through
+//! `POST /api/annotations`, optionally narrowed to some markets or
+//! categories. Every trade entered inside a window carries its tags, and
+//! attribution breaks PnL down by tag (the "event" dimension, with untagged
+//! trades under "none"), so it shows which kinds of event the strategy
+//! bleeds in. Annotations added or removed via the API are journaled.
+
+use crate::config::{AnnotationsConfig, EventWindowConfig};
+use crate::storage::JsonlStore;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A tagged time window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Annotation {
+    pub id: String,
+    pub tag: String,
+    /// Window, unix seconds inclusive
+    pub start: u64,
+    pub end: u64,
+    /// Markets it applies to (empty = every market)
+    pub markets: Vec<String>,
+    /// Categories it applies to, as attribution names them (empty = every category)
+    pub categories: Vec<String>,
+    pub note: String,
+    /// "config" or "api"
+    pub source: String,
+}
+
+impl Annotation {
+    /// Whether a trade in `market_id` (of `category`) at `at` falls inside the window
+    pub fn covers(&self, at: u64, market_id: &str, category: &str) -> bool {
+        at >= self.start && at <= self.end
+            && (self.markets.is_empty() || self.markets.iter().any(|m| m == market_id))
+            && (self.categories.is_empty() || self.categories.iter().any(|c| c == category))
+    }
+}
+
+/// `POST /api/annotations` body
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnnotationRequest {
+    pub tag: String,
+    pub start: u64,
+    pub end: u64,
+    #[serde(default)]
+    pub markets: Vec<String>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub note: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnnotationError {
+    Invalid(String),
+    /// Declared in config, so not removable via the API
+    Configured(String),
+    Unknown(String),
+}
+
+impl std::fmt::Display for AnnotationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Invalid(reason) => write!(f, "invalid annotation: {}", reason),
+            Self::Configured(id) => write!(f, "annotation {} is declared in config", id),
+            Self::Unknown(id) => write!(f, "unknown annotation {}", id),
+        }
+    }
+}
+
+impl std::error::Error for AnnotationError {}
+
+/// Journal record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum AnnotationEvent {
+    Added(Annotation),
+    Removed { id: String },
+}
+
+/// Annotations by id
+#[derive(Debug, Default)]
+pub struct Annotations {
+    annotations: BTreeMap<String, Annotation>,
+    /// Sequence of the last API annotation id
+    seq: u64,
+    journal: Option<JsonlStore>,
+}
+
+impl Annotations {
+    /// The windows declared in config
+    pub fn from_config(config: &AnnotationsConfig) -> Self {
+        let mut annotations = Self::default();
+        for (i, window) in config.events.iter().enumerate() {
+            let id = format!("config-{}", i + 1);
+            annotations.annotations.insert(id.clone(), annotation(id, window, "config"));
+        }
+        annotations
+    }
+
+    /// Persist API changes to `journal`, replaying the earlier ones
+    pub fn with_journal(mut self, journal: JsonlStore) -> Self {
+        let events: Vec<AnnotationEvent> = journal.load().unwrap_or_else(|e| {
+            eprintln!("⚠️ [Annotations] Failed to read annotation journal: {}", e);
+            Vec::new()
+        });
+        for event in events {
+            match event {
+                AnnotationEvent::Added(annotation) => {
+                    self.seq = self.seq.max(api_seq(&annotation.id));
+                    self.annotations.insert(annotation.id.clone(), annotation);
+                }
+                AnnotationEvent::Removed { id } => {
+                    self.annotations.remove(&id);
+                }
+            }
+        }
+        self.journal = Some(journal);
+        self
+    }
+
+    pub fn add(&mut self, request: AnnotationRequest) -> Result<Annotation, AnnotationError> {
+        let tag = request.tag.trim();
+        if tag.is_empty() {
+            return Err(AnnotationError::Invalid("tag is empty".to_string()));
+        }
+        if request.end < request.start {
+            return Err(AnnotationError::Invalid(format!("end {} is before start {}", request.end, request.start)));
+        }
+        self.seq += 1;
+        let window = EventWindowConfig {
+            tag: tag.to_string(),
+            start: request.start,
+            end: request.end,
+            markets: request.markets,
+            categories: request.categories,
+            note: request.note,
+        };
+        let added = annotation(format!("api-{}", self.seq), &window, "api");
+        self.persist(&AnnotationEvent::Added(added.clone()));
+        self.annotations.insert(added.id.clone(), added.clone());
+        Ok(added)
+    }
+
+    pub fn remove(&mut self, id: &str) -> Result<Annotation, AnnotationError> {
+        match self.annotations.get(id) {
+            None => return Err(AnnotationError::Unknown(id.to_string())),
+            Some(a) if a.source == "config" => return Err(AnnotationError::Configured(id.to_string())),
+            Some(_) => {}
+        }
+        self.persist(&AnnotationEvent::Removed { id: id.to_string() });
+        Ok(self.annotations.remove(id).expect("checked above"))
+    }
+
+    /// Tags of the windows a trade in `market_id` (of `category`) at `at` falls in, sorted and deduplicated
+    pub fn tags_at(&self, at: u64, market_id: &str, category: &str) -> Vec<String> {
+        let mut tags: Vec<String> = self.annotations.values()
+            .filter(|a| a.covers(at, market_id, category))
+            .map(|a| a.tag.clone())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    /// Every annotation, by start time
+    pub fn list(&self) -> Vec<Annotation> {
+        let mut list: Vec<Annotation> = self.annotations.values().cloned().collect();
+        list.sort_by(|a, b| (a.start, &a.id).cmp(&(b.start, &b.id)));
+        list
+    }
+
+    fn persist(&self, event: &AnnotationEvent) {
+        if let Some(journal) = &self.journal {
+            if let Err(e) = journal.append(event) {
+                eprintln!("⚠️ [Annotations] Failed to journal annotation change: {}", e);
+            }
+        }
+    }
+}
+
+fn annotation(id: String, window: &EventWindowConfig, source: &str) -> Annotation {
+    Annotation {
+        id,
+        tag: window.tag.clone(),
+        start: window.start,
+        end: window.end,
+        markets: window.markets.clone(),
+        categories: window.categories.clone(),
+        note: window.note.clone(),
+        source: source.to_string(),
+    }
+}
+
+/// Sequence number of an `api-<n>` id (0 for any other)
+fn api_seq(id: &str) -> u64 {
+    id.strip_prefix("api-").and_then(|n| n.parse().ok()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windows_tag_trades_and_survive_restart() {
+        let config = AnnotationsConfig {
+            events: vec![EventWindowConfig {
+                tag: "cpi-release".to_string(),
+                start: 1_000,
+                end: 2_000,
+                markets: Vec::new(),
+                categories: vec!["economics".to_string()],
+                note: String::new(),
+            }],
+        };
+        let dir = std::env::temp_dir().join(format!("arbishark-annotations-{}", std::process::id()));
+        let journal = || JsonlStore::open(&dir.to_string_lossy(), "annotations.jsonl").unwrap();
+        let mut annotations = Annotations::from_config(&config).with_journal(journal());
+
+        let request = |tag: &str, start: u64, end: u64, markets: &[&str]| AnnotationRequest {
+            tag: tag.to_string(), start, end, markets: markets.iter().map(|m| m.to_string()).collect(),
+            categories: Vec::new(), note: String::new(),
+        };
+        let debate = annotations.add(request("debate-night", 1_500, 3_000, &["m-election"])).unwrap();
+        annotations.add(request("game-start", 1_800, 1_900, &[])).unwrap();
+        assert!(annotations.add(request(" ", 0, 1, &[])).is_err());
+        assert!(annotations.add(request("backwards", 5, 1, &[])).is_err());
+
+        assert_eq!(annotations.tags_at(1_850, "m-cpi", "economics"), vec!["cpi-release", "game-start"]);
+        assert_eq!(annotations.tags_at(1_600, "m-election", "politics"), vec!["debate-night"]);
+        assert!(annotations.tags_at(2_500, "m-cpi", "economics").is_empty());
+        assert_eq!(annotations.remove("config-1"), Err(AnnotationError::Configured("config-1".to_string())));
+
+        // API changes are replayed on restart, and new ids continue the sequence
+        annotations.remove("api-2").unwrap();
+        let mut reopened = Annotations::from_config(&config).with_journal(journal());
+        assert_eq!(reopened.list().iter().map(|a| a.id.as_str()).collect::<Vec<_>>(), vec!["config-1", "api-1"]);
+        assert_eq!(reopened.list()[1], debate);
+        assert_eq!(reopened.add(request("late", 1, 2, &[])).unwrap().id, "api-3");
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
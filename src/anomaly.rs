@@ -0,0 +1,161 @@
+//! Self-monitoring for anomalous agent behavior
+//!
+//! Watches the agent's own trading rather than the market: an entry rate far
+//! above its recent baseline, fills consistently worse than the signal
+//! price, or a spend rate that would drain the remaining allowance within
+//! minutes. Any of these points at a logic bug or a broken market, and the
+//! main loop reacts by entering safe mode and alerting.
+
+use crate::config::AnomalyConfig;
+use std::collections::VecDeque;
+
+/// Anomalous behavior detected
+#[derive(Debug, Clone, PartialEq)]
+pub enum Anomaly {
+    /// Entries in the recent window vs. the baseline expectation
+    TradeRate { recent: usize, baseline: f64 },
+    /// Mean fill price vs. signal price (bps, positive = worse) over recent fills
+    AdverseFills { mean_bps: f64, fills: usize },
+    /// Seconds until the remaining allowance is gone at the recent spend rate
+    SpendRate { secs_to_exhaustion: f64 },
+}
+
+impl std::fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TradeRate { recent, baseline } => write!(f, "{} entries in window vs baseline {:.1}", recent, baseline),
+            Self::AdverseFills { mean_bps, fills } => write!(f, "last {} fills averaged {:.0}bps worse than signal", fills, mean_bps),
+            Self::SpendRate { secs_to_exhaustion } => write!(f, "spend rate would exhaust allowance in {:.0}s", secs_to_exhaustion),
+        }
+    }
+}
+
+/// Rolling record of the agent's own entries, fills and spend
+#[derive(Debug)]
+pub struct BehaviorMonitor {
+    config: AnomalyConfig,
+    /// Entry timestamps within the baseline window
+    entries: VecDeque<u64>,
+    /// Fill vs. signal price (bps) of the most recent fills
+    fill_bps: VecDeque<f64>,
+    /// (timestamp, amount) spends within the rate window
+    spends: VecDeque<(u64, f64)>,
+}
+
+impl BehaviorMonitor {
+    pub fn new(config: AnomalyConfig) -> Self {
+        Self { config, entries: VecDeque::new(), fill_bps: VecDeque::new(), spends: VecDeque::new() }
+    }
+
+    /// Count an entry (one bundle)
+    pub fn record_entry(&mut self, now: u64) {
+        self.entries.push_back(now);
+    }
+
+    /// Record a buy fill against the price its signal expected
+    pub fn record_fill(&mut self, signal_price: f64, fill_price: f64) {
+        if signal_price <= 0.0 {
+            return;
+        }
+        self.fill_bps.push_back((fill_price - signal_price) / signal_price * 10_000.0);
+        while self.fill_bps.len() > self.config.fill_sample {
+            self.fill_bps.pop_front();
+        }
+    }
+
+    /// Record allowance spent
+    pub fn record_spend(&mut self, now: u64, amount: f64) {
+        self.spends.push_back((now, amount));
+    }
+
+    fn prune(&mut self, now: u64) {
+        while self.entries.front().is_some_and(|t| now.saturating_sub(*t) >= self.config.baseline_window_secs) {
+            self.entries.pop_front();
+        }
+        while self.spends.front().is_some_and(|(t, _)| now.saturating_sub(*t) >= self.config.rate_window_secs) {
+            self.spends.pop_front();
+        }
+    }
+
+    /// First anomaly found at `now`, if any
+    pub fn check(&mut self, now: u64, remaining_allowance: f64) -> Option<Anomaly> {
+        if !self.config.enabled {
+            return None;
+        }
+        self.prune(now);
+        let window = self.config.rate_window_secs.max(1);
+
+        // Baseline: entries before the recent window, scaled to one window
+        let recent = self.entries.iter().filter(|t| now.saturating_sub(**t) < window).count();
+        let older = self.entries.len() - recent;
+        let baseline_windows = (self.config.baseline_window_secs.saturating_sub(window)).max(window) as f64 / window as f64;
+        let baseline = (older as f64 / baseline_windows).max(self.config.min_baseline_entries);
+        if recent as f64 > self.config.trade_rate_multiple * baseline {
+            return Some(Anomaly::TradeRate { recent, baseline });
+        }
+
+        if self.fill_bps.len() >= self.config.fill_sample {
+            let mean_bps = self.fill_bps.iter().sum::<f64>() / self.fill_bps.len() as f64;
+            if mean_bps > self.config.max_adverse_fill_bps {
+                return Some(Anomaly::AdverseFills { mean_bps, fills: self.fill_bps.len() });
+            }
+        }
+
+        let spent: f64 = self.spends.iter().map(|(_, a)| a).sum();
+        if self.spends.len() >= self.config.min_spends && spent > 0.0 && remaining_allowance > 0.0 {
+            let secs_to_exhaustion = remaining_allowance / (spent / window as f64);
+            if secs_to_exhaustion < self.config.min_secs_to_exhaustion as f64 {
+                return Some(Anomaly::SpendRate { secs_to_exhaustion });
+            }
+        }
+        None
+    }
+
+    /// Forget recorded behavior (after safe mode, so the same burst does not re-trigger)
+    pub fn reset(&mut self) {
+        self.entries.clear();
+        self.fill_bps.clear();
+        self.spends.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_rate_fill_and_spend_anomalies() {
+        let config = AnomalyConfig::default();
+        let mut monitor = BehaviorMonitor::new(config.clone());
+
+        // A steady baseline of one entry per window is fine
+        for i in 0..10 {
+            monitor.record_entry(i * config.rate_window_secs);
+        }
+        let now = 10 * config.rate_window_secs;
+        assert_eq!(monitor.check(now, 100.0), None);
+        // A burst far above it is not
+        for _ in 0..6 {
+            monitor.record_entry(now);
+        }
+        assert!(matches!(monitor.check(now, 100.0), Some(Anomaly::TradeRate { recent: 6, .. })));
+
+        let mut monitor = BehaviorMonitor::new(config.clone());
+        for _ in 0..config.fill_sample {
+            monitor.record_fill(0.50, 0.51);
+        }
+        assert!(matches!(monitor.check(0, 100.0), Some(Anomaly::AdverseFills { .. })));
+        monitor.reset();
+        assert_eq!(monitor.check(0, 100.0), None);
+
+        // $30 in a few seconds against $40 remaining
+        for i in 0..3 {
+            monitor.record_spend(i, 10.0);
+        }
+        assert!(matches!(monitor.check(3, 40.0), Some(Anomaly::SpendRate { .. })));
+
+        let mut off = BehaviorMonitor::new(AnomalyConfig { enabled: false, ..config });
+        off.record_spend(0, 1000.0);
+        assert_eq!(off.check(0, 1.0), None);
+    }
+}
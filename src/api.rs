@@ -1,32 +1,182 @@
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use once_cell::sync::Lazy;
-use warp::Filter;
+use include_dir::{include_dir, Dir};
+use warp::filters::BoxedFilter;
+use warp::{Filter, Reply};
 use serde::Serialize;
 use crate::metamask::{MetaMaskClient, PermissionGrant};
-use crate::positions::PositionManager;
-use tokio::sync::RwLock;
-use crate::types::ArbitrageSignal;
+use crate::positions::{PositionManager, TradeQuery};
+use crate::health::HealthState;
+use crate::orders::OrderRegistry;
+use crate::budgets::StrategyBook;
+use crate::approvals::{ApprovalError, ApprovalQueue};
+use crate::ratelimit::RateLimiter;
+use crate::equity::EquityCurve;
+use crate::watchlist::Watchlist;
+use crate::external::{ExternalError, ExternalInbox, ExternalSignal};
+use crate::intents::{IntentBook, IntentError, IntentUpdate};
+use crate::preview::{PreviewDesk, PreviewError, PreviewRequest};
+use crate::assets::Collateral;
+use crate::routing::Router;
+use crate::reconcile::{ReconcileQuery, Reconciler};
+use crate::resolution::ResolutionTracker;
+use crate::capture::CaptureTracker;
+use crate::perf::{ChannelBacklog, PerfMonitor};
+use crate::portfolio::Portfolio;
+use crate::quoting::QuoteGovernor;
+use crate::synthetic::SyntheticPricer;
+use crate::fleet::Fleet;
+use crate::holdings::{self, Holdings, HoldingsError, ImportLeg, ImportRequest, ImportedHolding};
+use crate::utilization::{CapitalTracker, UtilizationReport};
+use tokio::sync::{broadcast, RwLock};
+use crate::types::{ArbitrageSignal, Market};
+use crate::graphql::DashboardSchema;
+use crate::events::{self, EventBuffer, EventLevel, EventQuery, PendingEvent};
+use crate::audit::AuditQuery;
+use crate::finality::FinalityTracker;
+use crate::annotations::{AnnotationError, AnnotationRequest, Annotations};
+use crate::order_limits::OrderRateLimits;
+use crate::rpc_pool::RpcPool;
+use crate::metrics::{MetricsCollector, WindowStats};
+use futures_util::StreamExt;
 
-// Global log buffer for dashboard
-static LOGS: Lazy<Arc<Mutex<Vec<String>>>> = Lazy::new(|| Arc::new(Mutex::new(Vec::new())));
+// Dashboard bundle embedded at compile time
+static DASHBOARD_ASSETS: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/dashboard");
 
-// Helper to push logs to buffer
+// Messages a lagging broadcast subscriber can fall behind by
+const BROADCAST_CAPACITY: usize = 256;
+
+// Live event feed for streaming consumers (e.g. gRPC StreamEvents)
+static EVENTS: Lazy<broadcast::Sender<String>> = Lazy::new(|| broadcast::channel(BROADCAST_CAPACITY).0);
+
+// Typed updates for the dashboard SSE stream (`/api/stream`), alongside log events
+static STREAM: Lazy<broadcast::Sender<(&'static str, String)>> = Lazy::new(|| broadcast::channel(BROADCAST_CAPACITY).0);
+
+// Previews are answered between engine ticks, so allow for one full tick
+const PREVIEW_TIMEOUT_SECS: u64 = 30;
+
+/// Subscribe to live log events
+#[cfg_attr(not(feature = "grpc"), allow(dead_code))]
+pub fn subscribe_events() -> broadcast::Receiver<String> {
+    EVENTS.subscribe()
+}
+
+// Helper to push logs to the event log (level and component inferred from the message)
 pub fn push_log(msg: &str) {
-    let mut logs = LOGS.lock().unwrap();
-    if logs.last().map_or(true, |last| last != msg) {
-        logs.push(msg.to_string());
-        if logs.len() > 100 {
-            let len = logs.len();
-            logs.drain(0..(len - 100));
-        }
+    log_event(events::infer_level(msg), &events::infer_component(msg), None, msg);
+}
+
+/// Publish a named JSON event on the dashboard SSE stream
+pub fn publish_stream<T: Serialize>(event: &'static str, data: &T) {
+    if let Ok(json) = serde_json::to_string(data) {
+        let _ = STREAM.send((event, json));
     }
 }
 
+/// Record a structured event (queued through `tracing`, never blocks)
+pub fn log_event(level: EventLevel, component: &str, market_id: Option<&str>, msg: &str) {
+    events::emit(level, component, market_id, msg);
+}
+
+/// Move queued events into `events`, publishing each recorded one on the live feed
+pub async fn run_event_log(events: EventBuffer, rx: tokio::sync::mpsc::Receiver<PendingEvent>) {
+    events.drain(rx, |e| {
+        let _ = EVENTS.send(e.message.clone());
+    }).await;
+}
+
 /// API Server State
 #[derive(Clone)]
 pub struct ApiState {
     pub metamask: Arc<MetaMaskClient>,
     pub position_manager: Arc<RwLock<PositionManager>>,
+    /// Markets from the latest scan
+    pub markets: Arc<RwLock<Vec<Market>>>,
+    /// Signals from the latest scan
+    pub signals: Arc<RwLock<Vec<ArbitrageSignal>>>,
+    /// Liveness / readiness state
+    pub health: Arc<HealthState>,
+    /// Capital utilization tracker
+    pub utilization: Arc<RwLock<CapitalTracker>>,
+    /// Resting orders open on the venue
+    pub orders: Arc<std::sync::Mutex<OrderRegistry>>,
+    /// Per-strategy sub-accounts
+    pub strategies: Arc<std::sync::Mutex<StrategyBook>>,
+    /// Signals awaiting human approval
+    pub approvals: Arc<std::sync::Mutex<ApprovalQueue>>,
+    /// Per-client budget for control actions
+    pub rate_limiter: Arc<std::sync::Mutex<RateLimiter>>,
+    /// Sampled account equity history
+    pub equity: Arc<std::sync::Mutex<EquityCurve>>,
+    /// Markets pinned from the dashboard
+    pub watchlist: Arc<std::sync::Mutex<Watchlist>>,
+    /// Signals submitted by external models
+    pub external: Arc<std::sync::Mutex<ExternalInbox>>,
+    /// Trade intents awaiting an external co-signer
+    pub intents: Arc<std::sync::Mutex<IntentBook>>,
+    /// Dry-run cost previews answered by the engine loop
+    pub preview: PreviewDesk,
+    /// Imported outcome tokens, opened as positions by the engine
+    pub holdings: Arc<std::sync::Mutex<Holdings>>,
+    /// Asset the permission, limits and PnL are denominated in
+    pub collateral: Collateral,
+    /// Venue-selection decisions for cross-listed markets
+    pub router: Arc<std::sync::Mutex<Router>>,
+    /// Diffs posted orders against the venue's records
+    pub reconciler: Reconciler,
+    /// Traded markets followed to resolution
+    pub resolutions: Arc<std::sync::Mutex<ResolutionTracker>>,
+    /// Realized PnL vs the edge promised at signal time
+    pub capture: Arc<std::sync::Mutex<CaptureTracker>>,
+    /// Component timings and cache sizes reported by the engine
+    pub perf: PerfMonitor,
+    /// Balances, positions and PnL consolidated across chains
+    pub portfolio: Arc<std::sync::Mutex<Portfolio>>,
+    /// Cancel / replace rules for resting quotes, with per-market counters
+    pub quoting: Arc<std::sync::Mutex<QuoteGovernor>>,
+    /// Violated relationships between markets
+    pub synthetic: Arc<std::sync::Mutex<SyntheticPricer>>,
+    /// Recent structured events, fed from the `tracing` layer
+    pub events: EventBuffer,
+    /// Settlement transactions and their confirmations
+    pub finality: Arc<std::sync::Mutex<FinalityTracker>>,
+    /// Market event windows trades are tagged with
+    pub annotations: Arc<std::sync::Mutex<Annotations>>,
+    /// Order / cancel budgets at the venue and what is left of them
+    pub order_limits: Arc<std::sync::Mutex<OrderRateLimits>>,
+    /// RPC providers per chain and their health
+    pub rpc_pool: RpcPool,
+    /// Win rates, rolling windows and streaks of closed trades
+    pub metrics: MetricsCollector,
+}
+
+/// `POST /api/watchlist` body
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct WatchRequest {
+    pub market_id: String,
+    /// false unpins the market
+    #[serde(default = "default_watch")]
+    pub watch: bool,
+}
+
+fn default_watch() -> bool {
+    true
+}
+
+/// `/api/equity` query parameters (unix seconds, inclusive)
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct EquityQuery {
+    pub from: Option<u64>,
+    pub to: Option<u64>,
+}
+
+/// `/api/pnl` query parameters (bucket `1h` or `1d`, unix seconds inclusive)
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct PnlQuery {
+    #[serde(default)]
+    pub bucket: crate::equity::PnlBucket,
+    pub from: Option<u64>,
+    pub to: Option<u64>,
 }
 
 #[derive(Serialize)]
@@ -39,93 +189,863 @@ pub struct StatsResponse {
     win_rate: f64,
     total_pnl: f64,
     open_positions: usize,
-}
-
-#[derive(Serialize)]
-pub struct TradeResponse {
-    market_id: String,
-    token_id: String,
-    side: String,
-    size: f64,
-    entry_price: f64,
-    entry_time: u64,
+    utilization: UtilizationReport,
+    /// Amounts above are in this collateral
+    collateral: String,
+    usd_price: f64,
+    total_pnl_usd: f64,
+    /// Win rate and average PnL over the rolling windows of closed trades
+    rolling: Vec<WindowStats>,
+    /// Positive = consecutive wins, negative = consecutive losses
+    current_streak: i64,
+    longest_win_streak: u64,
+    longest_loss_streak: u64,
 }
 
 /// Start the API server
-pub async fn start_server(state: ApiState) {
+///
+/// `/api/...` serves the fleet's default agent (this engine), `/api/<agent>/...`
+/// each agent of the fleet and `/api/fleet` the aggregated overview. Serves the
+/// embedded dashboard unless `dashboard_dir` points to an on-disk override.
+pub async fn start_server(fleet: Fleet, dashboard_dir: Option<std::path::PathBuf>, port: u16) {
+    let state = fleet.default_state().clone();
     // CORS configuration
     let cors = warp::cors()
         .allow_any_origin()
-        .allow_headers(vec!["content-type"])
+        .allow_headers(vec!["content-type", "authorization"])
+        .expose_headers(vec!["x-total-count"])
         .allow_methods(vec!["GET", "POST", "OPTIONS"]);
 
+    let api = warp::path("api").and(agent_routes(state.clone()));
+
+    // /api/<agent>/... for every agent running in this process
+    let namespaced = fleet.local()
+        .map(|(agent_id, state)| warp::path("api").and(warp::path(agent_id.clone())).and(agent_routes(state.clone())).boxed())
+        .reduce(|a, b| a.or(b).unify().boxed())
+        .expect("fleet has a default agent");
+
+    // GET /api/fleet
+    // Every agent's stats side by side, plus fleet-wide totals
+    let fleet_route = warp::path!("api" / "fleet")
+        .and(warp::get())
+        .and(with_fleet(fleet.clone()))
+        .and_then(|fleet: Fleet| async move { Ok::<_, warp::Rejection>(warp::reply::json(&fleet.overview().await)) });
+
+    // /api/<agent>/... of agents in other processes, forwarded to their own API
+    let proxy_route = warp::path("api")
+        .and(warp::path::param::<String>())
+        .and(warp::path::tail())
+        .and(warp::method())
+        .and(warp::query::raw().or(warp::any().map(String::new)).unify())
+        .and(warp::header::headers_cloned())
+        .and(warp::body::bytes())
+        .and(with_fleet(fleet.clone()))
+        .and_then(|agent_id: String, tail: warp::path::Tail, method: warp::http::Method, query: String,
+                   headers: warp::http::HeaderMap, body: warp::hyper::body::Bytes, fleet: Fleet| async move {
+            if !fleet.is_remote(&agent_id) {
+                return Err(warp::reject::not_found());
+            }
+            let request = crate::fleet::ProxyRequest { method, path: tail.as_str().to_string(), query, headers, body };
+            Ok(match fleet.proxy(&agent_id, request).await {
+                Ok(response) => response,
+                Err(e) => warp::reply::with_status(
+                    warp::reply::json(&serde_json::json!({"error": e})),
+                    warp::http::StatusCode::BAD_GATEWAY,
+                ).into_response(),
+            })
+        });
+
+    // GET /healthz - liveness (event loop ticking)
+    let healthz_route = warp::path!("healthz")
+        .and(warp::get())
+        .and(with_state(state.clone()))
+        .and_then(handle_healthz);
+
+    // GET /readyz - readiness (data source, permission, config)
+    let readyz_route = warp::path!("readyz")
+        .and(warp::get())
+        .and(with_state(state.clone()))
+        .and_then(handle_readyz);
+
+    // POST /graphql
+    // Envio-style queries over markets, signals, positions, trades and metrics
+    let schema = crate::graphql::build_schema(state.clone());
+    let graphql_route = warp::path!("graphql")
+        .and(warp::post())
+        .and(async_graphql_warp::graphql(schema))
+        .and_then(handle_graphql);
+
+    // GET /graphql - GraphiQL explorer
+    let graphiql_route = warp::path!("graphql")
+        .and(warp::get())
+        .map(|| warp::reply::html(async_graphql::http::GraphiQLSource::build().endpoint("/graphql").finish()));
+
+    // Serve dashboard files at / (embedded bundle or on-disk override)
+    let dashboard = dashboard_filter(dashboard_dir);
+
+    let routes = control_guard(state.clone()).and(api
+        .or(fleet_route)
+        .or(namespaced)
+        .or(proxy_route)
+        .or(healthz_route)
+        .or(readyz_route)
+        .or(graphql_route)
+        .or(graphiql_route)
+        .or(dashboard))
+        .recover(handle_rate_limited)
+        .with(cors);
+
+    println!("🌍 [API] Server starting on http://localhost:{} ({} agents)", port, fleet.len());
+    push_log("🌍 [API] Server started");
+    warp::serve(routes).run(([127, 0, 0, 1], port)).await;
+}
+
+/// One agent's API, relative to `/api/` (or `/api/<agent>/`)
+fn agent_routes(state: ApiState) -> BoxedFilter<(warp::reply::Response,)> {
     // POST /api/permission
     // Receives permission grant from frontend (MetaMask)
-    let permission_route = warp::path!("api" / "permission")
+    let permission_route = warp::path!("permission")
         .and(warp::post())
         .and(warp::body::json())
         .and(with_state(state.clone()))
         .and_then(handle_permission);
 
+    // POST /api/kill
+    // Panic button: revoke trading permission and cancel every resting order
+    let kill_route = warp::path!("kill")
+        .and(warp::post())
+        .and(with_state(state.clone()))
+        .and_then(handle_kill);
+
     // GET /api/stats
     // Returns live stats for dashboard
-    let stats_route = warp::path!("api" / "stats")
+    let stats_route = warp::path!("stats")
         .and(warp::get())
         .and(with_state(state.clone()))
         .and_then(handle_stats);
 
-    // GET /api/trades
-    let trades_route = warp::path!("api" / "trades")
+    // GET /api/metrics
+    // Prometheus exposition: trades, exact and rolling win rates, streak, PnL
+    let metrics_route = warp::path!("metrics")
+        .and(warp::get())
+        .and(with_state(state.clone()))
+        .and_then(|state: ApiState| async move {
+            let body = state.metrics.export_prometheus().await;
+            Ok::<_, warp::Rejection>(warp::reply::with_header(body, "content-type", "text/plain; version=0.0.4"))
+        });
+
+    // GET /api/trades?from=&to=&market=&status=open|closed&sort=asc|desc&limit=&offset=
+    // Total matches before pagination are returned in X-Total-Count
+    let trades_route = warp::path!("trades")
         .and(warp::get())
+        .and(warp::query::<TradeQuery>())
         .and(with_state(state.clone()))
         .and_then(handle_trades);
 
+    // GET /api/strategies
+    // Budget, deployed capital, PnL and enabled / disabled time of each strategy sub-account
+    let strategies_route = warp::path!("strategies")
+        .and(warp::get())
+        .and(with_state(state.clone()))
+        .map(|state: ApiState| warp::reply::json(&state.strategies.lock().unwrap().report(crate::wallet::Wallet::current_timestamp())));
+
+    // POST /api/strategies/:name/enable | /api/strategies/:name/disable
+    // Switch one strategy on or off without touching the others (persisted across restarts)
+    let strategy_toggle_route = warp::path!("strategies" / String / String)
+        .and(warp::post())
+        .and(with_state(state.clone()))
+        .map(|name: String, action: String, state: ApiState| {
+            let enabled = match action.as_str() {
+                "enable" => true,
+                "disable" => false,
+                _ => return warp::reply::with_status(
+                    warp::reply::json(&serde_json::json!({"error": "action must be enable or disable"})),
+                    warp::http::StatusCode::BAD_REQUEST,
+                ),
+            };
+            if !state.strategies.lock().unwrap().set_enabled(&name, enabled, "disabled via API", crate::wallet::Wallet::current_timestamp()) {
+                return warp::reply::with_status(
+                    warp::reply::json(&serde_json::json!({"error": format!("unknown strategy {}", name)})),
+                    warp::http::StatusCode::NOT_FOUND,
+                );
+            }
+            let msg = format!("Strategy {} {}d via API", name, action);
+            log_event(EventLevel::Info, "budget", None, &msg);
+            warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({"strategy": name, "enabled": enabled})),
+                warp::http::StatusCode::OK,
+            )
+        });
+
+    // GET /api/approvals
+    // Signals waiting for a human decision
+    let approvals_route = warp::path!("approvals")
+        .and(warp::get())
+        .and(with_state(state.clone()))
+        .map(|state: ApiState| {
+            let mut queue = state.approvals.lock().unwrap();
+            queue.expire(crate::wallet::Wallet::current_timestamp());
+            warp::reply::json(&queue.pending())
+        });
+
+    // POST /api/approvals/:id/approve | /api/approvals/:id/reject
+    let approval_decide_route = warp::path!("approvals" / String / String)
+        .and(warp::post())
+        .and(with_state(state.clone()))
+        .map(|id: String, action: String, state: ApiState| {
+            let approve = match action.as_str() {
+                "approve" => true,
+                "reject" => false,
+                _ => return warp::reply::with_status(
+                    warp::reply::json(&serde_json::json!({"error": "action must be approve or reject"})),
+                    warp::http::StatusCode::BAD_REQUEST,
+                ),
+            };
+            decide_approval(&state, &id, approve, "dashboard")
+        });
+
+    // POST /api/telegram/callback
+    // Telegram webhook: inline button presses carry `approve:<id>` / `reject:<id>`
+    let telegram_route = warp::path!("telegram" / "callback")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_state(state.clone()))
+        .map(|update: serde_json::Value, state: ApiState| {
+            let data = update["callback_query"]["data"].as_str().unwrap_or_default();
+            match crate::approvals::parse_callback(data) {
+                Some((id, approve)) => decide_approval(&state, id, approve, "telegram"),
+                // Not an approval button: acknowledge so Telegram does not retry
+                None => warp::reply::with_status(warp::reply::json(&serde_json::json!({})), warp::http::StatusCode::OK),
+            }
+        });
+
+    // GET /api/equity?from=&to=
+    // Equity curve (balance + marked positions) with the RiskManager's drawdown
+    let equity_route = warp::path!("equity")
+        .and(warp::get())
+        .and(warp::query::<EquityQuery>())
+        .and(with_state(state.clone()))
+        .map(|query: EquityQuery, state: ApiState| {
+            warp::reply::json(&state.equity.lock().unwrap().report(query.from, query.to))
+        });
+
+    // GET /api/pnl?bucket=1h|1d&from=&to=
+    // Realized / unrealized PnL per bucket from the equity journal, ready to chart
+    let pnl_route = warp::path!("pnl")
+        .and(warp::get())
+        .and(warp::query::<PnlQuery>())
+        .and(with_state(state.clone()))
+        .map(|query: PnlQuery, state: ApiState| {
+            warp::reply::json(&state.equity.lock().unwrap().pnl(query.bucket, query.from, query.to))
+        });
+
+    // POST /api/signals/external (Authorization: Bearer <token>)
+    // Signal from an external model; priced and executed by the engine on its next tick
+    let external_signal_route = warp::path!("signals" / "external")
+        .and(warp::post())
+        .and(warp::header::optional::<String>("authorization"))
+        .and(warp::header::optional::<String>("x-client-id"))
+        .and(warp::addr::remote())
+        .and(warp::body::json())
+        .and(with_state(state.clone()))
+        .and_then(|authorization: Option<String>, client_id: Option<String>, remote: Option<std::net::SocketAddr>,
+                   signal: ExternalSignal, state: ApiState| async move {
+            let listed = state.markets.read().await.iter().any(|m| m.id == signal.market_id);
+            let source = client_label(&peer_address(remote), client_id.as_deref());
+            let mut inbox = state.external.lock().unwrap();
+            let submitted = inbox.authenticate(authorization.as_deref())
+                .and_then(|()| if listed { Ok(()) } else {
+                    Err(ExternalError::Invalid(format!("unknown market {}", signal.market_id)))
+                })
+                .and_then(|()| inbox.submit(signal, &source, crate::wallet::Wallet::current_timestamp()));
+            Ok::<_, warp::Rejection>(match submitted {
+                Ok(record) => {
+                    log_event(EventLevel::Info, "external", Some(&record.signal.market_id),
+                        &format!("📨 [External] {} from {}: {:?} {} up to ${:.4}", record.signal_id, source,
+                            record.signal.side, record.signal.market_id, record.signal.max_price));
+                    warp::reply::with_status(warp::reply::json(&record), warp::http::StatusCode::ACCEPTED)
+                }
+                Err(e) => {
+                    let status = match e {
+                        ExternalError::Disabled => warp::http::StatusCode::FORBIDDEN,
+                        ExternalError::Unauthorized => warp::http::StatusCode::UNAUTHORIZED,
+                        ExternalError::Invalid(_) => warp::http::StatusCode::BAD_REQUEST,
+                        ExternalError::Backlogged(_) => warp::http::StatusCode::SERVICE_UNAVAILABLE,
+                    };
+                    if status == warp::http::StatusCode::UNAUTHORIZED {
+                        log_event(EventLevel::Warn, "external", None, &format!("⛔ [External] Rejected unauthenticated signal from {}", source));
+                    }
+                    warp::reply::with_status(warp::reply::json(&serde_json::json!({"error": e.to_string()})), status)
+                }
+            })
+        });
+
+    // GET /api/signals/external (same bearer token)
+    // Recent external signals and whether they were queued or rejected
+    let external_signals_route = warp::path!("signals" / "external")
+        .and(warp::get())
+        .and(warp::header::optional::<String>("authorization"))
+        .and(with_state(state.clone()))
+        .map(|authorization: Option<String>, state: ApiState| {
+            let inbox = state.external.lock().unwrap();
+            match inbox.authenticate(authorization.as_deref()) {
+                Ok(()) => warp::reply::with_status(warp::reply::json(&inbox.recent()), warp::http::StatusCode::OK),
+                Err(e) => warp::reply::with_status(
+                    warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+                    warp::http::StatusCode::UNAUTHORIZED,
+                ),
+            }
+        });
+
+    // GET /api/intents (Authorization: Bearer <token>)
+    // Proposed trade intents, newest first, with their EIP-712 digest and signatures
+    let intents_route = warp::path!("intents")
+        .and(warp::get())
+        .and(warp::header::optional::<String>("authorization"))
+        .and(with_state(state.clone()))
+        .map(|authorization: Option<String>, state: ApiState| {
+            let book = state.intents.lock().unwrap();
+            match book.authenticate(authorization.as_deref()) {
+                Ok(()) => warp::reply::with_status(warp::reply::json(&book.recent()), warp::http::StatusCode::OK),
+                Err(e) => warp::reply::with_status(
+                    warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+                    intent_status(&e),
+                ),
+            }
+        });
+
+    // POST /api/intents/:id/cosign {"signature": "0x..."} | /executed {"reference"} | /reject {"reason"}
+    // The executing side co-signs an intent's digest, then reports whether it submitted the trade
+    let intent_update_route = warp::path!("intents" / String / String)
+        .and(warp::post())
+        .and(warp::header::optional::<String>("authorization"))
+        .and(warp::body::json())
+        .and(with_state(state.clone()))
+        .map(|intent_id: String, action: String, authorization: Option<String>, update: IntentUpdate, state: ApiState| {
+            let now = crate::wallet::Wallet::current_timestamp();
+            let mut book = state.intents.lock().unwrap();
+            let updated = book.authenticate(authorization.as_deref()).and_then(|()| match action.as_str() {
+                "cosign" => book.co_sign(&intent_id, update.signature.as_deref().unwrap_or_default(), now),
+                "executed" => book.mark_executed(&intent_id, update.reference, now),
+                "reject" => book.reject(&intent_id, update.reason, now),
+                other => Err(IntentError::BadAction(other.to_string())),
+            });
+            match updated {
+                Ok(intent) => {
+                    log_event(EventLevel::Info, "intents", Some(&intent.market_id),
+                        &format!("📝 [Intents] {} is now {:?}", intent.intent_id, intent.status));
+                    warp::reply::with_status(warp::reply::json(&intent), warp::http::StatusCode::OK)
+                }
+                Err(e) => warp::reply::with_status(
+                    warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+                    intent_status(&e),
+                ),
+            }
+        });
+
+    // POST /api/preview {"market_id": "...", "side": "Buy", "size": 5.0}
+    // Cost breakdown and pre-trade checks for a hypothetical trade; nothing is executed
+    let preview_route = warp::path!("preview")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_state(state.clone()))
+        .and_then(|request: PreviewRequest, state: ApiState| async move {
+            let result = state.preview.preview(request, std::time::Duration::from_secs(PREVIEW_TIMEOUT_SECS)).await;
+            Ok::<_, warp::Rejection>(match result {
+                Ok(preview) => warp::reply::with_status(warp::reply::json(&preview), warp::http::StatusCode::OK),
+                Err(e) => {
+                    let status = match e {
+                        PreviewError::Invalid(_) | PreviewError::Engine(_) => warp::http::StatusCode::BAD_REQUEST,
+                        PreviewError::Unavailable => warp::http::StatusCode::SERVICE_UNAVAILABLE,
+                    };
+                    warp::reply::with_status(warp::reply::json(&serde_json::json!({"error": e.to_string()})), status)
+                }
+            })
+        });
+
+    // POST /api/positions/import {"market_id": "...", "cost_basis": {"<token_id>": 0.42}}
+    // Import the wallet's existing outcome tokens in a market; opened as positions on the next tick
+    let import_route = warp::path!("positions" / "import")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_state(state.clone()))
+        .and_then(|request: ImportRequest, state: ApiState| async move {
+            Ok::<_, warp::Rejection>(match import_holdings(&state, request).await {
+                Ok(imported) => {
+                    for holding in &imported {
+                        log_event(EventLevel::Info, "holdings", Some(&holding.market_id),
+                            &format!("📥 [Holdings] Importing {:.2} {} ({}) @ ${:.4} ({:?} basis)",
+                                holding.size, holding.outcome, holding.token_id, holding.cost_basis, holding.basis_source));
+                    }
+                    warp::reply::with_status(warp::reply::json(&imported), warp::http::StatusCode::ACCEPTED)
+                }
+                Err(e) => {
+                    let status = match e {
+                        HoldingsError::NotConfigured => warp::http::StatusCode::SERVICE_UNAVAILABLE,
+                        HoldingsError::Rpc(_) => warp::http::StatusCode::BAD_GATEWAY,
+                        HoldingsError::Invalid(_) => warp::http::StatusCode::BAD_REQUEST,
+                    };
+                    warp::reply::with_status(warp::reply::json(&serde_json::json!({"error": e.to_string()})), status)
+                }
+            })
+        });
+
+    // GET /api/routes
+    // Recent best-execution decisions with the consolidated quote each was based on
+    let routes_route = warp::path!("routes")
+        .and(warp::get())
+        .and(with_state(state.clone()))
+        .map(|state: ApiState| warp::reply::json(&state.router.lock().unwrap().recent()));
+
+    // GET /api/routes/latency
+    // Smoothed round trip per venue, as used for time-critical legs
+    let route_latency_route = warp::path!("routes" / "latency")
+        .and(warp::get())
+        .and(with_state(state.clone()))
+        .map(|state: ApiState| warp::reply::json(&state.router.lock().unwrap().latencies()));
+
+    // GET /api/portfolio
+    // Balances, open notional and unrealized PnL per chain and in total, in USD
+    let portfolio_route = warp::path!("portfolio")
+        .and(warp::get())
+        .and(with_state(state.clone()))
+        .map(|state: ApiState| warp::reply::json(&state.portfolio.lock().unwrap().snapshot()));
+
+    // GET /api/quoting
+    // Per-market quote placements, replacements, cancels and what the anti-flicker rules held back
+    let quoting_route = warp::path!("quoting")
+        .and(warp::get())
+        .and(with_state(state.clone()))
+        .map(|state: ApiState| warp::reply::json(&state.quoting.lock().unwrap().counters()));
+
+    // GET /api/synthetic
+    // Relationships whose implied probability bounds are violated now, with legs and assumption risk
+    let synthetic_route = warp::path!("synthetic")
+        .and(warp::get())
+        .and(with_state(state.clone()))
+        .map(|state: ApiState| warp::reply::json(&state.synthetic.lock().unwrap().active()));
+
+    // GET /api/finality
+    // Settlement transactions, newest first: pending, provisional (below the chain's confirmations), final, dropped or reverted
+    let finality_route = warp::path!("finality")
+        .and(warp::get())
+        .and(with_state(state.clone()))
+        .map(|state: ApiState| warp::reply::json(&state.finality.lock().unwrap().snapshot()));
+
+    // GET /api/annotations
+    // Market event windows (config and API), by start time
+    let annotations_route = warp::path!("annotations")
+        .and(warp::get())
+        .and(with_state(state.clone()))
+        .map(|state: ApiState| warp::reply::json(&state.annotations.lock().unwrap().list()));
+
+    // POST /api/annotations {"tag": "...", "start": unix, "end": unix, "markets": [...], "categories": [...], "note": "..."}
+    // Tag a time window: trades entered inside it carry the tag, and attribution breaks PnL down by it
+    let annotate_route = warp::path!("annotations")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_state(state.clone()))
+        .map(|request: AnnotationRequest, state: ApiState| {
+            match state.annotations.lock().unwrap().add(request) {
+                Ok(annotation) => {
+                    log_event(EventLevel::Info, "annotations", None,
+                        &format!("🏷️ [Annotations] {} tagged {}..{} via API ({})", annotation.tag, annotation.start, annotation.end, annotation.id));
+                    warp::reply::with_status(warp::reply::json(&annotation), warp::http::StatusCode::OK)
+                }
+                Err(e) => warp::reply::with_status(
+                    warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+                    warp::http::StatusCode::BAD_REQUEST,
+                ),
+            }
+        });
+
+    // POST /api/annotations/:id/remove
+    let annotation_remove_route = warp::path!("annotations" / String / "remove")
+        .and(warp::post())
+        .and(with_state(state.clone()))
+        .map(|id: String, state: ApiState| {
+            match state.annotations.lock().unwrap().remove(&id) {
+                Ok(annotation) => {
+                    log_event(EventLevel::Info, "annotations", None,
+                        &format!("🏷️ [Annotations] {} ({}) removed via API", annotation.tag, annotation.id));
+                    warp::reply::with_status(warp::reply::json(&annotation), warp::http::StatusCode::OK)
+                }
+                Err(e) => {
+                    let status = match e {
+                        AnnotationError::Unknown(_) => warp::http::StatusCode::NOT_FOUND,
+                        _ => warp::http::StatusCode::CONFLICT,
+                    };
+                    warp::reply::with_status(warp::reply::json(&serde_json::json!({"error": e.to_string()})), status)
+                }
+            }
+        });
+
+    // GET /api/order_limits
+    // Headroom per order action, globally and per market, with refusals, deferrals and coalesced modifications
+    let order_limits_route = warp::path!("order_limits")
+        .and(warp::get())
+        .and(with_state(state.clone()))
+        .map(|state: ApiState| warp::reply::json(&state.order_limits.lock().unwrap().snapshot(crate::signal_queue::SignalQueue::now_ms())));
+
+    // GET /api/rpc
+    // RPC providers per chain id, in configured order, with health scores, failures and outvoted answers
+    let rpc_route = warp::path!("rpc")
+        .and(warp::get())
+        .and(with_state(state.clone()))
+        .map(|state: ApiState| warp::reply::json(&state.rpc_pool.snapshot()));
+
+    // GET /api/reconcile?from=&to=
+    // Our posted orders vs the venue's open orders and fills in the window, with every mismatch
+    let reconcile_route = warp::path!("reconcile")
+        .and(warp::get())
+        .and(warp::query::<ReconcileQuery>())
+        .and(with_state(state.clone()))
+        .and_then(|query: ReconcileQuery, state: ApiState| async move {
+            let now = crate::wallet::Wallet::current_timestamp();
+            Ok::<_, warp::Rejection>(match state.reconciler.run(&query, now).await {
+                Ok(report) => {
+                    if !report.mismatches.is_empty() {
+                        log_event(EventLevel::Warn, "reconcile", None, &format!("🧾 [Reconcile] {} mismatches with {} over {}..{}",
+                            report.mismatches.len(), report.venue, report.from, report.to));
+                    }
+                    warp::reply::with_status(warp::reply::json(&report), warp::http::StatusCode::OK)
+                }
+                Err(e) => {
+                    let status = match state.reconciler.client {
+                        None => warp::http::StatusCode::SERVICE_UNAVAILABLE,
+                        Some(_) => warp::http::StatusCode::BAD_GATEWAY,
+                    };
+                    warp::reply::with_status(warp::reply::json(&serde_json::json!({"error": e})), status)
+                }
+            })
+        });
+
+    // GET /api/resolutions
+    // Realized vs theoretical return per resolved market, with resolution-surprise stats
+    let resolutions_route = warp::path!("resolutions")
+        .and(warp::get())
+        .and(with_state(state.clone()))
+        .map(|state: ApiState| warp::reply::json(&state.resolutions.lock().unwrap().report()));
+
+    // GET /api/capture
+    // Edge capture ratio overall, over recent trades and per market / strategy
+    let capture_route = warp::path!("capture")
+        .and(warp::get())
+        .and(with_state(state.clone()))
+        .map(|state: ApiState| warp::reply::json(&state.capture.lock().unwrap().report()));
+
+    // GET /api/debug/perf
+    // Runtime metrics, component timings, cache sizes and channel backlogs (`[debug] perf`)
+    let perf_route = warp::path!("debug" / "perf")
+        .and(warp::get())
+        .and(with_state(state.clone()))
+        .and_then(handle_perf);
+
+    // GET /api/watchlist
+    let watchlist_route = warp::path!("watchlist")
+        .and(warp::get())
+        .and(with_state(state.clone()))
+        .map(|state: ApiState| warp::reply::json(&state.watchlist.lock().unwrap().entries()));
+
+    // POST /api/watchlist {"market_id": "...", "watch": true|false}
+    // Pin (or unpin) a market: scanned every tick and always on /api/stream
+    let watch_route = warp::path!("watchlist")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_state(state.clone()))
+        .and_then(|request: WatchRequest, state: ApiState| async move {
+            let listed = state.markets.read().await.iter().any(|m| m.id == request.market_id);
+            let mut watchlist = state.watchlist.lock().unwrap();
+            let result = if !request.watch {
+                Ok(watchlist.unpin(&request.market_id))
+            } else if !listed {
+                return Ok::<_, warp::Rejection>(warp::reply::with_status(
+                    warp::reply::json(&serde_json::json!({"error": format!("unknown market {}", request.market_id)})),
+                    warp::http::StatusCode::NOT_FOUND,
+                ));
+            } else {
+                watchlist.pin(&request.market_id, crate::wallet::Wallet::current_timestamp())
+            };
+            Ok(match result {
+                Ok(changed) => {
+                    if changed {
+                        let action = if request.watch { "pinned" } else { "unpinned" };
+                        log_event(EventLevel::Info, "watchlist", Some(&request.market_id),
+                            &format!("👁️ [Watchlist] {} {} via API", action, request.market_id));
+                    }
+                    warp::reply::with_status(
+                        warp::reply::json(&serde_json::json!({"market_id": request.market_id, "watched": request.watch})),
+                        warp::http::StatusCode::OK,
+                    )
+                }
+                Err(e) => warp::reply::with_status(
+                    warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+                    warp::http::StatusCode::CONFLICT,
+                ),
+            })
+        });
+
+    // GET /api/stream
+    // Server-sent events: `log` lines plus `watch` updates (books and signals of watched markets)
+    let stream_route = warp::path!("stream")
+        .and(warp::get())
+        .map(|| warp::sse::reply(warp::sse::keep_alive().stream(sse_events())));
+
+    // GET /api/ratelimit
+    // Allowed / limited control actions per client
+    let ratelimit_route = warp::path!("ratelimit")
+        .and(warp::get())
+        .and(with_state(state.clone()))
+        .map(|state: ApiState| warp::reply::json(state.rate_limiter.lock().unwrap().stats()));
+
     // GET /api/signals
-    let signals_route = warp::path!("api" / "signals")
+    let signals_route = warp::path!("signals")
         .and(warp::get())
         .and(with_state(state.clone()))
         .and_then(handle_signals);
 
     // GET /api/status
-    let status_route = warp::path!("api" / "status")
+    let status_route = warp::path!("status")
         .and(warp::get())
         .and(with_state(state.clone()))
         .and_then(handle_status);
 
-    // Serve static dashboard files at /
-    let dashboard_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("dashboard");
-    let static_files = warp::fs::dir(dashboard_dir.clone());
-
-    // If root path, serve index.html
-    let index_html = warp::path::end().and(warp::fs::file(dashboard_dir.join("index.html")));
+    // GET /api/audit?permission_id=&market=&from=&to=&format=json|csv
+    // Spending audit trail for reconciliation against the permission grant
+    let audit_route = warp::path!("audit")
+        .and(warp::get())
+        .and(warp::query::<AuditQuery>())
+        .and(with_state(state.clone()))
+        .map(|query: AuditQuery, state: ApiState| {
+            let records = state.metamask.audit_log().query(&query);
+            if query.format.as_deref() == Some("csv") {
+                warp::reply::with_header(crate::audit::to_csv(&records), "content-type", "text/csv").into_response()
+            } else {
+                warp::reply::json(&records).into_response()
+            }
+        });
 
-    // GET /api/logs
-    let logs_route = warp::path!("api" / "logs")
+    // GET /api/logs?level=&component=&market_id=&since=&until=&limit=&offset=
+    let logs_route = warp::path!("logs")
         .and(warp::get())
-        .map(|| {
-            let logs = LOGS.lock().unwrap();
-            warp::reply::json(&*logs)
+        .and(warp::query::<EventQuery>())
+        .and(with_state(state.clone()))
+        .and_then(|query: EventQuery, state: ApiState| async move {
+            Ok::<_, warp::Rejection>(warp::reply::json(&state.events.query(&query).await))
         });
 
-    let routes = permission_route
-        .or(stats_route)
+    // Boxed in groups: a single `.or()` chain this long overflows the compiler's recursion limit
+    let control = permission_route
+        .or(kill_route)
+        .or(strategies_route)
+        .or(strategy_toggle_route)
+        .or(approvals_route)
+        .or(approval_decide_route)
+        .or(telegram_route)
+        .or(ratelimit_route)
+        .map(Reply::into_response)
+        .boxed();
+    let reporting = stats_route
+        .or(metrics_route)
         .or(trades_route)
-        .or(signals_route)
         .or(status_route)
         .or(logs_route)
-        .or(index_html)
-        .or(static_files)
-        .with(cors);
+        .or(audit_route)
+        .or(equity_route)
+        .or(pnl_route)
+        .or(portfolio_route)
+        .or(perf_route)
+        .map(Reply::into_response)
+        .boxed();
+    let signals = signals_route
+        .or(watchlist_route)
+        .or(watch_route)
+        .or(stream_route)
+        .or(external_signal_route)
+        .or(external_signals_route)
+        .or(intents_route)
+        .or(intent_update_route)
+        .or(preview_route)
+        .or(import_route)
+        .map(Reply::into_response)
+        .boxed();
+    let execution = routes_route
+        .or(route_latency_route)
+        .or(quoting_route)
+        .or(synthetic_route)
+        .or(reconcile_route)
+        .or(finality_route)
+        .or(order_limits_route)
+        .or(rpc_route)
+        .or(resolutions_route)
+        .or(capture_route)
+        .map(Reply::into_response)
+        .boxed();
+    let annotations = annotations_route
+        .or(annotate_route)
+        .or(annotation_remove_route)
+        .map(Reply::into_response)
+        .boxed();
 
-    println!("🌍 [API] Server starting on http://localhost:3030");
-    push_log("🌍 [API] Server started");
-    warp::serve(routes).run(([127, 0, 0, 1], 3030)).await;
+    control
+        .or(reporting).unify()
+        .or(signals).unify()
+        .or(execution).unify()
+        .or(annotations).unify()
+        .boxed()
+}
+
+/// Build the dashboard filter: on-disk directory if given, else the embedded bundle
+fn dashboard_filter(dashboard_dir: Option<std::path::PathBuf>) -> BoxedFilter<(warp::reply::Response,)> {
+    match dashboard_dir {
+        Some(dir) => {
+            println!("🗂️ [API] Serving dashboard from {}", dir.display());
+            // If root path, serve index.html
+            let index_html = warp::path::end().and(warp::fs::file(dir.join("index.html")));
+            index_html
+                .or(warp::fs::dir(dir))
+                .unify()
+                .map(|file: warp::fs::File| file.into_response())
+                .boxed()
+        }
+        None => warp::get()
+            .and(warp::path::tail())
+            .and_then(|tail: warp::path::Tail| async move {
+                let path = if tail.as_str().is_empty() { "index.html" } else { tail.as_str() };
+                match DASHBOARD_ASSETS.get_file(path) {
+                    Some(file) => Ok(warp::reply::with_header(
+                        file.contents(),
+                        "content-type",
+                        content_type(path),
+                    ).into_response()),
+                    None => Err(warp::reject::not_found()),
+                }
+            })
+            .boxed(),
+    }
+}
+
+/// Guess content type from file extension
+fn content_type(path: &str) -> &'static str {
+    match path.rsplit('.').next().unwrap_or("") {
+        "html" => "text/html; charset=utf-8",
+        "js" => "application/javascript",
+        "css" => "text/css",
+        "json" => "application/json",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "ico" => "image/x-icon",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Rejection for a client that exhausted its control-action budget
+#[derive(Debug)]
+struct RateLimited {
+    retry_after_ms: u64,
+}
+
+impl warp::reject::Reject for RateLimited {}
+
+/// Rate-limit and audit every control action (POST under /api/); reads pass through
+fn control_guard(state: ApiState) -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    warp::method()
+        .and(warp::path::full())
+        .and(warp::addr::remote())
+        .and(warp::header::optional::<String>("x-client-id"))
+        .and(with_state(state))
+        .and_then(|method: warp::http::Method, path: warp::path::FullPath, remote: Option<std::net::SocketAddr>,
+                   client_id: Option<String>, state: ApiState| async move {
+            if method != warp::http::Method::POST || !path.as_str().starts_with("/api/") {
+                return Ok(());
+            }
+            // Buckets are per address: the client id header is caller-chosen, so only a label
+            let peer = peer_address(remote);
+            let client = client_label(&peer, client_id.as_deref());
+            let now_ms = crate::wallet::Wallet::current_timestamp() * 1000;
+            let checked = state.rate_limiter.lock().unwrap().check(&peer, client_id.as_deref(), now_ms);
+            match checked {
+                Ok(()) => {
+                    log_event(EventLevel::Info, "api", None, &format!("POST {} from {}", path.as_str(), client));
+                    Ok(())
+                }
+                Err(retry_after_ms) => {
+                    log_event(EventLevel::Warn, "api", None,
+                        &format!("⛔ POST {} from {} rate limited (retry in {}ms)", path.as_str(), client, retry_after_ms));
+                    Err(warp::reject::custom(RateLimited { retry_after_ms }))
+                }
+            }
+        })
+        .untuple_one()
+}
+
+/// Remote IP of a request, "unknown" if the transport has none
+fn peer_address(remote: Option<std::net::SocketAddr>) -> String {
+    remote.map_or_else(|| "unknown".to_string(), |addr| addr.ip().to_string())
+}
+
+/// Peer address, with the client id it declares if any, for logs
+fn client_label(peer: &str, client_id: Option<&str>) -> String {
+    match client_id {
+        Some(id) => format!("{} ({})", peer, id),
+        None => peer.to_string(),
+    }
+}
+
+/// 429 with Retry-After for rate-limited control actions
+async fn handle_rate_limited(rejection: warp::Rejection) -> Result<warp::reply::Response, warp::Rejection> {
+    match rejection.find::<RateLimited>() {
+        Some(limited) => {
+            let body = warp::reply::json(&serde_json::json!({"error": "rate limited", "retry_after_ms": limited.retry_after_ms}));
+            let reply = warp::reply::with_status(body, warp::http::StatusCode::TOO_MANY_REQUESTS);
+            Ok(warp::reply::with_header(reply, "retry-after", limited.retry_after_ms.div_ceil(1000).to_string()).into_response())
+        }
+        None => Err(rejection),
+    }
+}
+
+/// Log events and typed stream updates merged into one SSE feed. Lagging
+/// subscribers skip what they missed rather than closing the stream.
+fn sse_events() -> impl futures_util::Stream<Item = Result<warp::sse::Event, std::convert::Infallible>> {
+    let logs = futures_util::stream::unfold(EVENTS.subscribe(), |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(msg) => return Some((warp::sse::Event::default().event("log").data(msg), rx)),
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+    let updates = futures_util::stream::unfold(STREAM.subscribe(), |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok((event, json)) => return Some((warp::sse::Event::default().event(event).data(json), rx)),
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+    futures_util::stream::select(Box::pin(logs), Box::pin(updates)).map(Ok)
+}
+
+fn intent_status(error: &IntentError) -> warp::http::StatusCode {
+    match error {
+        IntentError::Disabled => warp::http::StatusCode::FORBIDDEN,
+        IntentError::Unauthorized => warp::http::StatusCode::UNAUTHORIZED,
+        IntentError::Unknown(_) => warp::http::StatusCode::NOT_FOUND,
+        IntentError::Decided { .. } => warp::http::StatusCode::CONFLICT,
+        IntentError::BadSignature(_) | IntentError::NotCoSigner(_) | IntentError::BadAction(_) => warp::http::StatusCode::BAD_REQUEST,
+    }
 }
 
 fn with_state(state: ApiState) -> impl Filter<Extract = (ApiState,), Error = std::convert::Infallible> + Clone {
     warp::any().map(move || state.clone())
 }
 
+fn with_fleet(fleet: Fleet) -> impl Filter<Extract = (Fleet,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || fleet.clone())
+}
+
 /// Handle permission update from frontend
 async fn handle_permission(
     grant: PermissionGrant, // Frontend sends the grant object directly
@@ -134,14 +1054,118 @@ async fn handle_permission(
     let msg = format!("📥 [API] Received permission grant from Dashboard: {}", grant.permission_id);
     println!("{}", msg);
     push_log(&msg);
+    if let Err(e) = check_grant_token(&state, &grant.token) {
+        push_log(&format!("⚠️ [API] Permission grant refused: {}", e));
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({ "error": e })),
+            warp::http::StatusCode::BAD_REQUEST,
+        ));
+    }
     // Update the MetaMask client
     state.metamask.set_permission(grant).await;
-    Ok(warp::reply::json(&serde_json::json!({ "status": "ok" })))
+    Ok(warp::reply::with_status(warp::reply::json(&serde_json::json!({ "status": "ok" })), warp::http::StatusCode::OK))
+}
+
+/// A grant in another token would put limits in the wrong units
+pub(crate) fn check_grant_token(state: &ApiState, token: &str) -> Result<(), String> {
+    match token.eq_ignore_ascii_case(state.collateral.symbol()) {
+        true => Ok(()),
+        false => Err(format!("grant is in {}, collateral is {}", token, state.collateral.symbol())),
+    }
+}
+
+/// Handle kill switch: stop trading and cancel all open orders
+async fn handle_kill(state: ApiState) -> Result<impl warp::Reply, warp::Rejection> {
+    // No permission means nothing to revoke; orders are cancelled regardless
+    let _ = state.metamask.revoke_permission().await;
+    let cancelled = state.orders.lock().unwrap().cancel_all_orders("kill switch").len();
+    let msg = format!("🛑 [API] Kill switch engaged - permission revoked, {} orders cancelled", cancelled);
+    println!("{}", msg);
+    push_log(&msg);
+    Ok(warp::reply::json(&serde_json::json!({ "status": "ok", "cancelled": cancelled })))
+}
+
+/// Read a market's on-chain balances and queue the untracked part as imports
+async fn import_holdings(state: &ApiState, request: ImportRequest) -> Result<Vec<ImportedHolding>, HoldingsError> {
+    let market = state.markets.read().await.iter().find(|m| m.id == request.market_id).cloned()
+        .ok_or_else(|| HoldingsError::Invalid(format!("unknown market {}", request.market_id)))?;
+    let reader = state.holdings.lock().unwrap().reader();
+    let mut legs = Vec::new();
+    for (i, token_id) in market.clob_token_ids.iter().enumerate() {
+        let balance = reader.balance(token_id).await?;
+        let tracked = state.position_manager.read().await.get_position(token_id).map_or(0.0, |p| p.size);
+        legs.push(ImportLeg {
+            token_id: token_id.clone(),
+            outcome: market.outcomes.get(i).cloned().unwrap_or_default(),
+            balance,
+            tracked,
+            oracle_price: market.outcome_prices.get(i).copied(),
+        });
+    }
+    let now = crate::wallet::Wallet::current_timestamp();
+    let imported = holdings::plan_import(&market.id, &legs, &request.cost_basis, market.get_spread(), now)?;
+    state.holdings.lock().unwrap().import(imported.clone())?;
+    Ok(imported)
+}
+
+/// Record an approval decision from the dashboard or Telegram
+fn decide_approval(state: &ApiState, id: &str, approve: bool, via: &str) -> warp::reply::WithStatus<warp::reply::Json> {
+    let decided = state.approvals.lock().unwrap().decide(id, approve, crate::wallet::Wallet::current_timestamp());
+    match decided {
+        Ok(approval) => {
+            let msg = format!("🙋 [Approval] {} {:?} via {} (${:.2} on {})",
+                approval.approval_id, approval.status, via, approval.notional, approval.market_id);
+            println!("{}", msg);
+            log_event(EventLevel::Info, "approval", Some(&approval.market_id), &msg);
+            warp::reply::with_status(warp::reply::json(&approval), warp::http::StatusCode::OK)
+        }
+        Err(e) => {
+            let status = match e {
+                ApprovalError::Unknown(_) => warp::http::StatusCode::NOT_FOUND,
+                ApprovalError::Decided { .. } => warp::http::StatusCode::CONFLICT,
+            };
+            warp::reply::with_status(warp::reply::json(&serde_json::json!({"error": e.to_string()})), status)
+        }
+    }
+}
+
+/// Handle liveness probe (200 if the loop is ticking, 503 otherwise)
+async fn handle_healthz(state: ApiState) -> Result<impl warp::Reply, warp::Rejection> {
+    let report = state.health.liveness(crate::wallet::Wallet::current_timestamp());
+    Ok(probe_reply(&report))
+}
+
+/// Handle readiness probe (200 if ready to trade, 503 otherwise)
+async fn handle_readyz(state: ApiState) -> Result<impl warp::Reply, warp::Rejection> {
+    let report = state.health.readiness(state.metamask.has_valid_permission().await);
+    Ok(probe_reply(&report))
+}
+
+fn probe_reply(report: &crate::health::ProbeReport) -> warp::reply::WithStatus<warp::reply::Json> {
+    let status = if report.ok {
+        warp::http::StatusCode::OK
+    } else {
+        warp::http::StatusCode::SERVICE_UNAVAILABLE
+    };
+    warp::reply::with_status(warp::reply::json(report), status)
+}
+
+/// Handle GraphQL query
+async fn handle_graphql(
+    (schema, request): (DashboardSchema, async_graphql::Request),
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(async_graphql_warp::GraphQLResponse::from(schema.execute(request).await))
 }
 
 /// Handle stats request
 async fn handle_stats(state: ApiState) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&stats(&state).await))
+}
+
+/// Live stats of one agent (`/api/stats`, and per agent on `/api/fleet`)
+pub(crate) async fn stats(state: &ApiState) -> StatsResponse {
     let perm = state.metamask.get_permission().await;
+    let outcomes = state.metrics.get_metrics().await;
     let pm = state.position_manager.read().await;
 
     let (active, limit, spent) = match perm {
@@ -149,7 +1173,7 @@ async fn handle_stats(state: ApiState) -> Result<impl warp::Reply, warp::Rejecti
         None => (false, 0.0, 0.0),
     };
 
-    let stats = StatsResponse {
+    StatsResponse {
         connected: true,
         permission_active: active,
         daily_limit: limit,
@@ -158,33 +1182,63 @@ async fn handle_stats(state: ApiState) -> Result<impl warp::Reply, warp::Rejecti
         win_rate: pm.win_rate() * 100.0,
         total_pnl: pm.total_pnl(),
         open_positions: pm.get_positions().len(),
-    };
-
-    Ok(warp::reply::json(&stats))
+        utilization: state.utilization.read().await.report(),
+        collateral: state.collateral.symbol().to_string(),
+        usd_price: state.collateral.usd_price,
+        total_pnl_usd: state.collateral.to_usd(pm.total_pnl()),
+        rolling: outcomes.rolling,
+        current_streak: outcomes.current_streak,
+        longest_win_streak: outcomes.longest_win_streak,
+        longest_loss_streak: outcomes.longest_loss_streak,
+    }
 }
 
-async fn handle_trades(state: ApiState) -> Result<impl warp::Reply, warp::Rejection> {
+async fn handle_trades(query: TradeQuery, state: ApiState) -> Result<impl warp::Reply, warp::Rejection> {
     let pm = state.position_manager.read().await;
-    let mut trades = Vec::new();
-    for pos in pm.get_positions() {
-        trades.push(TradeResponse {
-            market_id: pos.market_id.clone(),
-            token_id: pos.token_id.clone(),
-            side: format!("{:?}", pos.side),
-            size: pos.size,
-            entry_price: pos.entry_price,
-            entry_time: pos.entry_time,
-        });
+    let mut page = pm.query_trades(&query);
+    let markets = state.markets.read().await;
+    let greeks = crate::greeks::for_positions(pm.get_positions(), &markets, crate::wallet::Wallet::current_timestamp());
+    drop(pm);
+    let annotations = state.annotations.lock().unwrap();
+    for trade in &mut page.trades {
+        let category = markets.iter().find(|m| m.id == trade.market_id).map_or("other", crate::attribution::categorize);
+        trade.tags = annotations.tags_at(trade.entry_time, &trade.market_id, category);
+        if trade.status == "open" {
+            trade.greeks = greeks.get(&trade.token_id).cloned();
+        }
     }
-    Ok(warp::reply::json(&trades))
+    Ok(warp::reply::with_header(warp::reply::json(&page.trades), "x-total-count", page.total.to_string()))
 }
 
-async fn handle_signals(state: ApiState) -> Result<impl warp::Reply, warp::Rejection> {
+async fn handle_signals(_state: ApiState) -> Result<impl warp::Reply, warp::Rejection> {
     // TODO: Connect to live signals from detector/engine
     Ok(warp::reply::json(&serde_json::json!([])))
 }
 
-async fn handle_status(state: ApiState) -> Result<impl warp::Reply, warp::Rejection> {
+async fn handle_perf(state: ApiState) -> Result<impl warp::Reply, warp::Rejection> {
+    if !state.perf.enabled() {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "perf profiling disabled ([debug] perf)"})),
+            warp::http::StatusCode::NOT_FOUND,
+        ));
+    }
+    let mut report = state.perf.report();
+    report.caches.insert("api_markets".to_string(), state.markets.read().await.len());
+    report.caches.insert("api_signals".to_string(), state.signals.read().await.len());
+    report.caches.insert("event_log".to_string(), state.events.len().await);
+    report.caches.insert("open_orders".to_string(), state.orders.lock().unwrap().open_count());
+    let (queued, capacity) = state.preview.backlog();
+    report.channels = vec![
+        ChannelBacklog { channel: "events".to_string(), queued: EVENTS.len(), capacity: BROADCAST_CAPACITY },
+        ChannelBacklog { channel: "stream".to_string(), queued: STREAM.len(), capacity: BROADCAST_CAPACITY },
+        ChannelBacklog { channel: "preview".to_string(), queued, capacity },
+    ];
+    let (queued, capacity) = state.events.backlog();
+    report.channels.push(ChannelBacklog { channel: "event_log".to_string(), queued, capacity });
+    Ok(warp::reply::with_status(warp::reply::json(&report), warp::http::StatusCode::OK))
+}
+
+async fn handle_status(_state: ApiState) -> Result<impl warp::Reply, warp::Rejection> {
     // TODO: Connect to engine status/errors
     Ok(warp::reply::json(&serde_json::json!({"status": "ok"})))
 }
@@ -0,0 +1,193 @@
+//! Human-in-the-loop execution approval
+//!
+//! With approval mode on, a signal whose bundle notional is above the
+//! threshold is not executed right away: it is queued as pending, announced
+//! (Telegram with approve / reject inline buttons), and only executes once
+//! approved through the dashboard API or the Telegram callback. Requests
+//! that nobody decides within the TTL expire. Approved signals re-enter the
+//! signal queue and are re-validated against fresh books like any other.
+
+use crate::config::ApprovalConfig;
+use crate::types::ArbitrageSignal;
+use serde::Serialize;
+
+/// Decision state of an approval request
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Expired,
+}
+
+/// A signal awaiting (or past) a human decision
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingApproval {
+    pub approval_id: String,
+    pub market_id: String,
+    pub edge: f64,
+    /// Bundle notional (USDC) that triggered the approval
+    pub notional: f64,
+    pub requested_at: u64,
+    pub expires_at: u64,
+    pub status: ApprovalStatus,
+    #[serde(skip)]
+    pub signal: ArbitrageSignal,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApprovalError {
+    Unknown(String),
+    /// Already approved, rejected or expired
+    Decided { approval_id: String, status: ApprovalStatus },
+}
+
+impl std::fmt::Display for ApprovalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unknown(id) => write!(f, "unknown approval {}", id),
+            Self::Decided { approval_id, status } => write!(f, "approval {} is already {:?}", approval_id, status),
+        }
+    }
+}
+
+/// Approval requests, decided ones kept until taken or pruned
+#[derive(Debug)]
+pub struct ApprovalQueue {
+    config: ApprovalConfig,
+    requests: Vec<PendingApproval>,
+    next_id: u64,
+}
+
+impl ApprovalQueue {
+    pub fn new(config: ApprovalConfig) -> Self {
+        Self { config, requests: Vec::new(), next_id: 1 }
+    }
+
+    /// Whether a bundle of this notional must wait for approval
+    pub fn requires_approval(&self, notional: f64) -> bool {
+        self.config.enabled && notional > self.config.notional_threshold
+    }
+
+    /// Queue `signal` for approval. None if its market already has a
+    /// request pending (the newer signal is dropped, not queued twice).
+    pub fn request(&mut self, signal: &ArbitrageSignal, notional: f64, now: u64) -> Option<PendingApproval> {
+        if self.requests.iter().any(|r| r.market_id == signal.market_id && r.status == ApprovalStatus::Pending) {
+            return None;
+        }
+        let approval = PendingApproval {
+            approval_id: format!("apr-{}-{}", now, self.next_id),
+            market_id: signal.market_id.clone(),
+            edge: signal.edge,
+            notional,
+            requested_at: now,
+            expires_at: now + self.config.ttl_secs,
+            status: ApprovalStatus::Pending,
+            signal: signal.clone(),
+        };
+        self.next_id += 1;
+        self.requests.push(approval.clone());
+        Some(approval)
+    }
+
+    /// Approve or reject a pending request
+    pub fn decide(&mut self, approval_id: &str, approve: bool, now: u64) -> Result<PendingApproval, ApprovalError> {
+        self.expire(now);
+        let request = self.requests.iter_mut()
+            .find(|r| r.approval_id == approval_id)
+            .ok_or_else(|| ApprovalError::Unknown(approval_id.to_string()))?;
+        if request.status != ApprovalStatus::Pending {
+            return Err(ApprovalError::Decided { approval_id: approval_id.to_string(), status: request.status });
+        }
+        request.status = if approve { ApprovalStatus::Approved } else { ApprovalStatus::Rejected };
+        Ok(request.clone())
+    }
+
+    /// Expire pending requests past their TTL and return them
+    pub fn expire(&mut self, now: u64) -> Vec<PendingApproval> {
+        let mut expired = Vec::new();
+        for request in &mut self.requests {
+            if request.status == ApprovalStatus::Pending && now >= request.expires_at {
+                request.status = ApprovalStatus::Expired;
+                expired.push(request.clone());
+            }
+        }
+        expired
+    }
+
+    /// Remove and return approved requests for execution; drops other decided ones
+    pub fn take_approved(&mut self) -> Vec<PendingApproval> {
+        let (approved, rest): (Vec<_>, Vec<_>) = std::mem::take(&mut self.requests)
+            .into_iter()
+            .filter(|r| r.status != ApprovalStatus::Rejected && r.status != ApprovalStatus::Expired)
+            .partition(|r| r.status == ApprovalStatus::Approved);
+        self.requests = rest;
+        approved
+    }
+
+    /// Requests still awaiting a decision
+    pub fn pending(&self) -> Vec<PendingApproval> {
+        self.requests.iter().filter(|r| r.status == ApprovalStatus::Pending).cloned().collect()
+    }
+}
+
+/// Parse Telegram inline-button callback data (`approve:<id>` / `reject:<id>`)
+pub fn parse_callback(data: &str) -> Option<(&str, bool)> {
+    match data.split_once(':')? {
+        ("approve", id) => Some((id, true)),
+        ("reject", id) => Some((id, false)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Side;
+
+    fn signal(market_id: &str) -> ArbitrageSignal {
+        ArbitrageSignal {
+            market_id: market_id.to_string(),
+            spread: 0.05,
+            edge: 0.05,
+            recommended_side: Side::Buy,
+            yes_price: 0.47,
+            no_price: 0.48,
+            edge_curve: Vec::new(),
+            quote_ages_ms: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_approval_lifecycle() {
+        let config = ApprovalConfig { enabled: true, notional_threshold: 5.0, ttl_secs: 60 };
+        let mut queue = ApprovalQueue::new(config);
+        assert!(!queue.requires_approval(4.0));
+        assert!(queue.requires_approval(6.0));
+
+        let a = queue.request(&signal("m1"), 6.0, 100).unwrap();
+        assert!(queue.request(&signal("m1"), 6.0, 101).is_none());
+        let b = queue.request(&signal("m2"), 8.0, 100).unwrap();
+        let c = queue.request(&signal("m3"), 8.0, 100).unwrap();
+        assert_eq!(queue.pending().len(), 3);
+
+        let callback = format!("approve:{}", a.approval_id);
+        let (id, approve) = parse_callback(&callback).unwrap();
+        assert!(approve);
+        queue.decide(id, approve, 110).unwrap();
+        queue.decide(&b.approval_id, false, 110).unwrap();
+        assert!(matches!(queue.decide(&b.approval_id, true, 111), Err(ApprovalError::Decided { .. })));
+        assert!(matches!(queue.decide("nope", true, 111), Err(ApprovalError::Unknown(_))));
+
+        // Undecided within the TTL expires
+        assert_eq!(queue.expire(160).len(), 1);
+        assert!(matches!(queue.decide(&c.approval_id, true, 161), Err(ApprovalError::Decided { status: ApprovalStatus::Expired, .. })));
+
+        let approved = queue.take_approved();
+        assert_eq!(approved.len(), 1);
+        assert_eq!(approved[0].signal.market_id, "m1");
+        assert!(queue.take_approved().is_empty());
+        assert!(queue.pending().is_empty());
+    }
+}
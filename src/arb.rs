@@ -1,6 +1,6 @@
 #![allow(dead_code)]
 use crate::constraint::ConstraintChecker;
-use crate::types::{ArbitrageSignal, Market};
+use crate::types::{ArbitrageSignal, EdgePoint, Market, OrderBook, Side};
 
 /// Arbitrage detector
 #[derive(Debug)]
@@ -25,6 +25,25 @@ impl ArbitrageDetector {
             .collect()
     }
 
+    /// Attach the edge curve from walking `books` at sizes up to `max_size` per leg
+    pub fn with_edge_curve(&self, signal: &ArbitrageSignal, books: &[OrderBook], fee_rate: f64, max_size: f64, steps: usize) -> ArbitrageSignal {
+        ArbitrageSignal {
+            edge_curve: edge_curve(books, signal.recommended_side, fee_rate, max_size, steps),
+            ..signal.clone()
+        }
+    }
+
+    /// Attach the age of each leg's quote: `now_ms` minus the book's timestamp (ms),
+    /// or `unstamped_age_ms` (the indexer's staleness) for books without one
+    pub fn with_quote_ages(&self, signal: &ArbitrageSignal, books: &[OrderBook], now_ms: u64, unstamped_age_ms: u64) -> ArbitrageSignal {
+        ArbitrageSignal {
+            quote_ages_ms: books.iter()
+                .map(|b| if b.timestamp == 0 { unstamped_age_ms } else { now_ms.saturating_sub(b.timestamp) })
+                .collect(),
+            ..signal.clone()
+        }
+    }
+
     /// Calculate expected profit after costs
     pub fn expected_profit(
         &self,
@@ -50,4 +69,66 @@ impl ArbitrageDetector {
     ) -> bool {
         self.expected_profit(signal, size, fee_rate, slippage) > self.min_profit_threshold
     }
-}
\ No newline at end of file
+}
+/// Smallest size on an edge curve
+const MIN_CURVE_SIZE: f64 = 1.0;
+
+/// Per-unit edge of the bundle at `size` per leg after taker fees (None past the books' depth)
+pub fn edge_at(books: &[OrderBook], side: Side, size: f64, fee_rate: f64) -> Option<f64> {
+    let prices = books.iter().map(|b| b.execution_price(size, side)).collect::<Option<Vec<f64>>>()?;
+    let total: f64 = prices.iter().sum();
+    match side {
+        Side::Buy => Some(1.0 - total * (1.0 + fee_rate)),
+        Side::Sell => Some(total * (1.0 - fee_rate) - 1.0),
+    }
+}
+
+/// Edge at `steps` sizes spaced geometrically up to `max_size`, stopping where the books run out
+pub fn edge_curve(books: &[OrderBook], side: Side, fee_rate: f64, max_size: f64, steps: usize) -> Vec<EdgePoint> {
+    if books.is_empty() || max_size < MIN_CURVE_SIZE || steps == 0 {
+        return Vec::new();
+    }
+    let ratio = if steps > 1 { (max_size / MIN_CURVE_SIZE).powf(1.0 / (steps - 1) as f64) } else { 1.0 };
+    let mut curve = Vec::new();
+    for i in 0..steps {
+        let size = if i + 1 == steps { max_size } else { MIN_CURVE_SIZE * ratio.powi(i as i32) };
+        let Some(edge) = edge_at(books, side, size, fee_rate) else { break };
+        curve.push(EdgePoint { size, edge, profit: edge * size });
+    }
+    curve
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::book;
+
+    /// 0.45 + 0.45 for the first 10, then much worse
+    fn books() -> [OrderBook; 2] {
+        let asks = [(0.45, 10.0), (0.55, 100.0)];
+        [book("y", &[], &asks), book("n", &[], &asks)]
+    }
+
+    #[test]
+    fn test_edge_at_walks_the_books() {
+        let books = books();
+        assert!((edge_at(&books, Side::Buy, 10.0, 0.0).unwrap() - 0.10).abs() < 1e-9);
+        assert!((edge_at(&books, Side::Buy, 20.0, 0.0).unwrap() - 0.0).abs() < 1e-9);
+        assert!((edge_at(&books, Side::Buy, 10.0, 0.02).unwrap() - (1.0 - 0.9 * 1.02)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_edge_curve_walks_the_books() {
+        let curve = edge_curve(&books(), Side::Buy, 0.0, 200.0, 5);
+        // 1, ~3.8, ~14.1, ~53.2 fill; 200 exceeds the depth
+        assert_eq!(curve.len(), 4);
+        assert_eq!(curve[0].size, 1.0);
+        assert!(curve.windows(2).all(|w| w[1].size > w[0].size && w[1].edge <= w[0].edge));
+        assert!(curve[3].profit < 0.0);
+    }
+
+    #[test]
+    fn test_no_curve_below_one_share() {
+        assert!(edge_curve(&books(), Side::Buy, 0.0, 0.5, 5).is_empty());
+    }
+}
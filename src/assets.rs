@@ -0,0 +1,127 @@
+//! Collateral assets
+//!
+//! Venues settle in different collateral: USDC on Polymarket, other stables
+//! on some EVM venues, SOL on Solana. Limits in the config are in USD
+//! (`daily_limit_usdc`, `max_per_tx_usdc`), while the permission, the
+//! wallet and every notional the engine computes are in collateral units.
+//! `Collateral` converts between the two at the configured USD price
+//! (stables default to $1) and between whole units and on-chain base units
+//! with the asset's decimals, so limits and reports stay correct whatever
+//! the venue settles in.
+
+use crate::config::AssetsConfig;
+use serde::Serialize;
+
+/// A token the engine can hold and spend as collateral
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Asset {
+    pub symbol: &'static str,
+    pub decimals: u32,
+    /// Pegged to $1 unless a price is configured
+    pub stable: bool,
+    pub chain: &'static str,
+}
+
+/// Supported collateral assets
+pub const ASSETS: &[Asset] = &[
+    Asset { symbol: "USDC", decimals: 6, stable: true, chain: "evm" },
+    Asset { symbol: "USDC.e", decimals: 6, stable: true, chain: "evm" },
+    Asset { symbol: "USDT", decimals: 6, stable: true, chain: "evm" },
+    Asset { symbol: "DAI", decimals: 18, stable: true, chain: "evm" },
+    Asset { symbol: "SOL", decimals: 9, stable: false, chain: "solana" },
+];
+
+impl Asset {
+    /// Known asset by symbol (case-insensitive)
+    pub fn lookup(symbol: &str) -> Option<&'static Asset> {
+        ASSETS.iter().find(|a| a.symbol.eq_ignore_ascii_case(symbol))
+    }
+
+    /// Whole units → on-chain base units (e.g. 1.5 USDC → 1_500_000)
+    pub fn to_base_units(&self, amount: f64) -> u128 {
+        (amount.max(0.0) * 10f64.powi(self.decimals as i32)).round() as u128
+    }
+
+    pub fn from_base_units(&self, raw: u128) -> f64 {
+        raw as f64 / 10f64.powi(self.decimals as i32)
+    }
+}
+
+/// The collateral the engine trades in, with its USD price
+#[derive(Debug, Clone, Serialize)]
+pub struct Collateral {
+    pub asset: Asset,
+    pub usd_price: f64,
+}
+
+impl Default for Collateral {
+    fn default() -> Self {
+        Self { asset: ASSETS[0].clone(), usd_price: 1.0 }
+    }
+}
+
+impl Collateral {
+    /// Collateral `symbol` priced from `config` (stables default to $1)
+    pub fn from_config(config: &AssetsConfig, symbol: &str) -> Result<Self, String> {
+        let asset = Asset::lookup(symbol).ok_or_else(|| format!("unknown collateral asset {:?} (known: {})", symbol,
+            ASSETS.iter().map(|a| a.symbol).collect::<Vec<_>>().join(", ")))?;
+        let configured = config.usd_prices.iter().find(|(s, _)| s.eq_ignore_ascii_case(asset.symbol)).map(|(_, p)| *p);
+        let usd_price = match (configured, asset.stable) {
+            (Some(price), _) => price,
+            (None, true) => 1.0,
+            (None, false) => return Err(format!("assets.usd_prices.{} is required for non-stable collateral", asset.symbol)),
+        };
+        if !(usd_price.is_finite() && usd_price > 0.0) {
+            return Err(format!("USD price of {} must be positive", asset.symbol));
+        }
+        Ok(Self { asset: asset.clone(), usd_price })
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        self.asset.symbol
+    }
+
+    /// Collateral amount worth `usd`
+    pub fn from_usd(&self, usd: f64) -> f64 {
+        usd / self.usd_price
+    }
+
+    /// USD value of a collateral amount
+    pub fn to_usd(&self, amount: f64) -> f64 {
+        amount * self.usd_price
+    }
+
+    /// `12.5000 SOL ($1875.00)`, or just `$12.50` for USD stables
+    pub fn format(&self, amount: f64) -> String {
+        if self.asset.stable && self.usd_price == 1.0 {
+            format!("${:.2}", amount)
+        } else {
+            format!("{:.4} {} (${:.2})", amount, self.symbol(), self.to_usd(amount))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_decimals_and_usd_conversion() {
+        let usdc = Collateral::from_config(&AssetsConfig::default(), "usdc").unwrap();
+        assert_eq!((usdc.symbol(), usdc.usd_price), ("USDC", 1.0));
+        assert_eq!(usdc.asset.to_base_units(1.5), 1_500_000);
+        assert_eq!(usdc.format(12.5), "$12.50");
+        let dai = Asset::lookup("DAI").unwrap();
+        assert_eq!(dai.to_base_units(2.0), 2_000_000_000_000_000_000);
+        assert_eq!(dai.from_base_units(500_000_000_000_000_000), 0.5);
+
+        assert!(Collateral::from_config(&AssetsConfig::default(), "SOL").is_err());
+        assert!(Collateral::from_config(&AssetsConfig::default(), "DOGE").is_err());
+        let config = AssetsConfig { usd_prices: HashMap::from([("sol".to_string(), 150.0)]) };
+        let sol = Collateral::from_config(&config, "SOL").unwrap();
+        assert_eq!(sol.asset.to_base_units(sol.from_usd(15.0)), 100_000_000);
+        assert_eq!(sol.to_usd(2.0), 300.0);
+        assert_eq!(sol.format(2.0), "2.0000 SOL ($300.00)");
+    }
+}
@@ -0,0 +1,239 @@
+//! Performance attribution
+//!
+//! Breaks PnL, hit rate, predicted vs realized edge and fees down by market,
+//! category, strategy and market event tag (`annotations`; a trade in several
+//! windows counts under each of their tags, an untagged one under "none").
+//! Each UTC day's breakdown is appended to the storage
+//! layer when the day rolls over, so money-losing categories can be found
+//! (and blacklisted) from data rather than anecdotes.
+
+use crate::positions::ExitResult;
+use crate::storage::JsonlStore;
+use crate::types::Market;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const DAY_SECS: u64 = 86400;
+
+/// Keyword → category rules, checked in order against question and slug
+const CATEGORY_RULES: &[(&str, &[&str])] = &[
+    ("crypto", &["bitcoin", "btc", "eth", "ethereum", "solana", "crypto", "token"]),
+    ("politics", &["election", "president", "senate", "congress", "trump", "biden", "vote", "governor"]),
+    ("economics", &["fed", "rate", "inflation", "cpi", "gdp", "recession", "jobs"]),
+    ("sports", &["nfl", "nba", "mlb", "nhl", "super bowl", "champion", "world cup", "match", "game"]),
+];
+
+/// Attribution keys recorded when a position is opened
+#[derive(Debug, Clone)]
+pub struct TradeTag {
+    pub market_id: String,
+    pub category: String,
+    pub strategy: String,
+    /// Edge ($) the signal predicted for this leg
+    pub predicted_edge: f64,
+    /// Tags of the event windows the entry fell in
+    pub events: Vec<String>,
+}
+
+/// Aggregated performance for one key of one dimension
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AttributionRow {
+    /// "market", "category", "strategy" or "event"
+    pub dimension: String,
+    pub key: String,
+    pub trades: u64,
+    pub wins: u64,
+    pub pnl: f64,
+    pub fees: f64,
+    pub predicted_edge: f64,
+    /// Gross PnL (before fees), comparable to the predicted edge
+    pub realized_edge: f64,
+}
+
+impl AttributionRow {
+    pub fn hit_rate(&self) -> f64 {
+        if self.trades == 0 { 0.0 } else { self.wins as f64 / self.trades as f64 }
+    }
+
+    pub fn avg_predicted_edge(&self) -> f64 {
+        if self.trades == 0 { 0.0 } else { self.predicted_edge / self.trades as f64 }
+    }
+
+    pub fn avg_realized_edge(&self) -> f64 {
+        if self.trades == 0 { 0.0 } else { self.realized_edge / self.trades as f64 }
+    }
+
+    fn add(&mut self, exit: &ExitResult, predicted_edge: f64) {
+        self.trades += 1;
+        if exit.pnl > 0.0 {
+            self.wins += 1;
+        }
+        self.pnl += exit.pnl;
+        self.fees += exit.fees;
+        self.predicted_edge += predicted_edge;
+        self.realized_edge += exit.pnl + exit.fees;
+    }
+}
+
+/// One persisted day of attribution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyAttribution {
+    /// Start of the UTC day (unix seconds)
+    pub day: u64,
+    pub rows: Vec<AttributionRow>,
+}
+
+/// Attribution tracker
+#[derive(Debug, Default)]
+pub struct Attribution {
+    /// Tags of open positions by token_id
+    tags: HashMap<String, TradeTag>,
+    /// Day currently being aggregated
+    day: Option<u64>,
+    rows: HashMap<(&'static str, String), AttributionRow>,
+    store: Option<JsonlStore>,
+}
+
+impl Attribution {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Persist each completed day to `store`
+    pub fn with_store(mut self, store: JsonlStore) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Tag a newly opened position
+    pub fn tag_entry(&mut self, token_id: &str, tag: TradeTag) {
+        self.tags.insert(token_id.to_string(), tag);
+    }
+
+    /// Attribute a closed position. Returns the previous day's breakdown if
+    /// this exit rolled the day over.
+    pub fn record_exit(&mut self, exit: &ExitResult) -> Option<DailyAttribution> {
+        let day = exit.exit_time - exit.exit_time % DAY_SECS;
+        let flushed = match self.day {
+            Some(current) if current != day => self.flush(),
+            _ => None,
+        };
+        self.day = Some(day);
+
+        let tag = self.tags.remove(&exit.position.token_id).unwrap_or_else(|| TradeTag {
+            market_id: exit.position.market_id.clone(),
+            category: "unknown".to_string(),
+            strategy: "unknown".to_string(),
+            predicted_edge: 0.0,
+            events: Vec::new(),
+        });
+        let events = match tag.events.is_empty() {
+            true => vec!["none".to_string()],
+            false => tag.events.clone(),
+        };
+        let keys = [("market", tag.market_id.clone()), ("category", tag.category.clone()), ("strategy", tag.strategy.clone())]
+            .into_iter()
+            .chain(events.into_iter().map(|event| ("event", event)));
+        for (dimension, key) in keys {
+            self.rows.entry((dimension, key.clone()))
+                .or_insert_with(|| AttributionRow { dimension: dimension.to_string(), key, ..Default::default() })
+                .add(exit, tag.predicted_edge);
+        }
+        flushed
+    }
+
+    /// Current day's rows for a dimension, worst PnL first
+    pub fn report(&self, dimension: &str) -> Vec<AttributionRow> {
+        let mut rows: Vec<AttributionRow> = self.rows.iter()
+            .filter(|((d, _), _)| *d == dimension)
+            .map(|(_, r)| r.clone())
+            .collect();
+        rows.sort_by(|a, b| a.pnl.total_cmp(&b.pnl));
+        rows
+    }
+
+    /// Close out the current day: persist and reset the aggregates
+    pub fn flush(&mut self) -> Option<DailyAttribution> {
+        let day = self.day?;
+        if self.rows.is_empty() {
+            return None;
+        }
+        let mut rows: Vec<AttributionRow> = self.rows.drain().map(|(_, r)| r).collect();
+        rows.sort_by(|a, b| (&a.dimension, &a.key).cmp(&(&b.dimension, &b.key)));
+        let daily = DailyAttribution { day, rows };
+        if let Some(store) = &self.store {
+            if let Err(e) = store.append(&daily) {
+                eprintln!("⚠️ [Attribution] Failed to persist day {}: {}", day, e);
+            }
+        }
+        Some(daily)
+    }
+}
+
+/// Categorize a market from its question / slug keywords
+pub fn categorize(market: &Market) -> &'static str {
+    let text = format!("{} {}", market.question, market.slug.replace('-', " ")).to_lowercase();
+    let words: Vec<&str> = text.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect();
+    CATEGORY_RULES.iter()
+        .find(|(_, keywords)| keywords.iter().any(|k| {
+            if k.contains(' ') { text.contains(k) } else { words.contains(k) }
+        }))
+        .map_or("other", |(category, _)| category)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::positions::{ExitReason, Position};
+    use crate::types::Side;
+
+    fn exit(token: &str, pnl: f64, exit_time: u64) -> ExitResult {
+        ExitResult {
+            position: Position {
+                market_id: "m1".to_string(),
+                token_id: token.to_string(),
+                side: Side::Buy,
+                size: 10.0,
+                entry_price: 0.5,
+                entry_time: 0,
+                entry_spread: 0.03,
+                strategy: "arb".to_string(),
+            },
+            exit_price: 0.5,
+            exit_time,
+            reason: ExitReason::Timeout,
+            pnl,
+            fees: 0.1,
+        }
+    }
+
+    #[test]
+    fn test_attribution_by_dimension() {
+        let mut attr = Attribution::new();
+        for (token, events) in [("t1", vec!["cpi-release".to_string()]), ("t2", Vec::new())] {
+            attr.tag_entry(token, TradeTag {
+                market_id: "m1".to_string(),
+                category: "crypto".to_string(),
+                strategy: "arb".to_string(),
+                predicted_edge: 0.2,
+                events,
+            });
+        }
+        assert!(attr.record_exit(&exit("t1", 0.5, 100)).is_none());
+        assert!(attr.record_exit(&exit("t2", -0.3, 200)).is_none());
+
+        let cat = attr.report("category");
+        assert_eq!(cat.len(), 1);
+        assert_eq!(cat[0].trades, 2);
+        assert_eq!(cat[0].hit_rate(), 0.5);
+        assert!((cat[0].avg_realized_edge() - 0.2).abs() < 1e-9);
+        let events = attr.report("event");
+        assert_eq!(events.iter().map(|r| (r.key.as_str(), r.pnl)).collect::<Vec<_>>(), vec![("none", -0.3), ("cpi-release", 0.5)]);
+
+        // Next day rolls the previous one over
+        let daily = attr.record_exit(&exit("t3", 0.1, DAY_SECS + 5)).unwrap();
+        assert_eq!(daily.day, 0);
+        assert_eq!(daily.rows.len(), 5, "market, category, strategy and two event tags");
+        assert_eq!(attr.report("strategy")[0].key, "unknown");
+    }
+}
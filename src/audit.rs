@@ -0,0 +1,138 @@
+//! Spending audit log
+//!
+//! Append-only trail of every spend charged against an ERC-7715 permission
+//! grant (timestamp, market, notional, tx hash), persisted to the storage
+//! layer and exported via `/api/audit` so the wallet owner can reconcile
+//! what the agent did against what the permission allowed.
+
+use crate::storage::JsonlStore;
+use serde::{Deserialize, Serialize};
+
+/// What a spend was for (supplied by the caller)
+#[derive(Debug, Clone, Default)]
+pub struct SpendContext {
+    pub market_id: String,
+    pub token_id: String,
+    /// Settlement transaction, if one was submitted on-chain
+    pub tx_hash: Option<String>,
+}
+
+/// One audited spend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpendRecord {
+    pub timestamp: u64,
+    pub permission_id: String,
+    pub market_id: String,
+    pub token_id: String,
+    /// USDC charged against the allowance
+    pub notional: f64,
+    pub tx_hash: Option<String>,
+    /// Allowance state right after this spend
+    pub spent_in_period: f64,
+    pub daily_limit: f64,
+    /// Why the grant refused a spend that had already filled (not charged to the allowance)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overdraft: Option<String>,
+}
+
+/// Audit export filter (`/api/audit` query parameters)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuditQuery {
+    pub permission_id: Option<String>,
+    pub market: Option<String>,
+    /// Unix seconds, inclusive
+    pub from: Option<u64>,
+    pub to: Option<u64>,
+    /// "json" (default) or "csv"
+    pub format: Option<String>,
+}
+
+/// Append-only spend log
+#[derive(Debug, Default)]
+pub struct AuditLog {
+    records: Vec<SpendRecord>,
+    store: Option<JsonlStore>,
+}
+
+impl AuditLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Persist to `store`, restoring the trail already recorded there
+    pub fn with_store(store: JsonlStore) -> std::io::Result<Self> {
+        Ok(Self { records: store.load()?, store: Some(store) })
+    }
+
+    pub fn record(&mut self, record: SpendRecord) {
+        if let Some(store) = &self.store {
+            if let Err(e) = store.append(&record) {
+                eprintln!("⚠️ [Audit] Failed to persist spend record: {}", e);
+            }
+        }
+        self.records.push(record);
+    }
+
+    /// Records matching the query, oldest first
+    pub fn query(&self, q: &AuditQuery) -> Vec<SpendRecord> {
+        self.records.iter()
+            .filter(|r| q.permission_id.as_ref().is_none_or(|id| &r.permission_id == id))
+            .filter(|r| q.market.as_ref().is_none_or(|m| &r.market_id == m))
+            .filter(|r| q.from.is_none_or(|from| r.timestamp >= from))
+            .filter(|r| q.to.is_none_or(|to| r.timestamp <= to))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Render records as CSV
+pub fn to_csv(records: &[SpendRecord]) -> String {
+    let mut out = String::from("timestamp,permission_id,market_id,token_id,notional,tx_hash,spent_in_period,daily_limit,overdraft\n");
+    for r in records {
+        out.push_str(&format!("{},{},{},{},{:.6},{},{:.6},{:.6},{}\n",
+            r.timestamp, r.permission_id, r.market_id, r.token_id, r.notional,
+            r.tx_hash.as_deref().unwrap_or(""), r.spent_in_period, r.daily_limit, r.overdraft.as_deref().unwrap_or("")));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spend(permission_id: &str, market_id: &str, notional: f64, timestamp: u64) -> SpendRecord {
+        SpendRecord {
+            timestamp,
+            permission_id: permission_id.to_string(),
+            market_id: market_id.to_string(),
+            token_id: "t1".to_string(),
+            notional,
+            tx_hash: None,
+            spent_in_period: notional,
+            daily_limit: 10.0,
+            overdraft: None,
+        }
+    }
+
+    #[test]
+    fn test_audit_persist_and_query() {
+        let dir = std::env::temp_dir().join(format!("arbishark_audit_{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+        let store = JsonlStore::open(dir, "audit.jsonl").unwrap();
+        let mut log = AuditLog::with_store(store.clone()).unwrap();
+        log.record(spend("perm-1", "m1", 2.5, 100));
+        log.record(spend("perm-1", "m2", 1.0, 200));
+        log.record(spend("perm-2", "m1", 4.0, 300));
+
+        // Survives a restart
+        let log = AuditLog::with_store(store).unwrap();
+        let perm1 = AuditQuery { permission_id: Some("perm-1".to_string()), ..Default::default() };
+        assert_eq!(log.query(&perm1).iter().map(|r| r.notional).sum::<f64>(), 3.5);
+        let q = AuditQuery { market: Some("m1".to_string()), from: Some(150), ..Default::default() };
+        let rows = log.query(&q);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].permission_id, "perm-2");
+        assert_eq!(to_csv(&rows).lines().count(), 2);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,223 @@
+// Backtest over recorded order books (`arbishark backtest`)
+// Buys full sets clearing the minimum edge on the books in force and holds them to settlement
+
+use crate::arb;
+use crate::config::Config;
+use crate::fees::FeeModel;
+use crate::recorder::BookReplayer;
+use crate::sizing::PositionSizer;
+use crate::types::{Market, OrderBook, Side};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Book timestamps above this are milliseconds
+const MS_CLOCK: u64 = 10_000_000_000;
+
+/// One simulated entry
+#[derive(Debug, Clone, Serialize)]
+pub struct SimTrade {
+    /// Replay clock at the decision (recording units)
+    pub timestamp: u64,
+    pub market_id: String,
+    pub question: String,
+    /// Size per leg
+    pub size: f64,
+    /// Volume-weighted ask per leg
+    pub prices: Vec<f64>,
+    /// Paid for the set, before fees
+    pub cost: f64,
+    /// Taker fees plus gas
+    pub fees: f64,
+    /// Settlement ($1 per set) minus cost and fees
+    pub pnl: f64,
+    /// The books the decision was made on, in outcome order
+    pub books: Vec<OrderBook>,
+}
+
+impl SimTrade {
+    /// Profit per set
+    pub fn edge(&self) -> f64 {
+        if self.size > 0.0 { self.pnl / self.size } else { 0.0 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct EquityPoint {
+    pub timestamp: u64,
+    /// Cumulative PnL ($)
+    pub equity: f64,
+}
+
+/// Trades of one UTC day
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DayStats {
+    /// YYYY-MM-DD
+    pub day: String,
+    pub trades: usize,
+    /// Notional paid ($)
+    pub volume: f64,
+    pub pnl: f64,
+    pub best: f64,
+    pub worst: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BacktestReport {
+    /// Snapshots replayed
+    pub ticks: usize,
+    /// Markets with a recorded book for every outcome
+    pub markets: usize,
+    pub trades: Vec<SimTrade>,
+    pub equity: Vec<EquityPoint>,
+    pub days: Vec<DayStats>,
+}
+
+impl BacktestReport {
+    pub fn total_pnl(&self) -> f64 {
+        self.trades.iter().map(|t| t.pnl).sum()
+    }
+
+    /// Largest drop of the equity curve from a previous peak ($)
+    pub fn max_drawdown(&self) -> f64 {
+        let mut peak = 0.0f64;
+        self.equity.iter().fold(0.0, |dd: f64, p| {
+            peak = peak.max(p.equity);
+            dd.max(peak - p.equity)
+        })
+    }
+
+    pub fn summary_lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("📼 Backtest: {} snapshots, {} markets with every book recorded", self.ticks, self.markets),
+            format!("   {} trades | PnL ${:.4} | max drawdown ${:.4}", self.trades.len(), self.total_pnl(), self.max_drawdown()),
+        ];
+        for day in &self.days {
+            lines.push(format!("   {}  {:>4} trades  volume ${:>10.2}  PnL ${:>9.4}", day.day, day.trades, day.volume, day.pnl));
+        }
+        lines
+    }
+}
+
+/// UTC day of a replay timestamp
+pub fn day_of(timestamp: u64) -> String {
+    let secs = if timestamp > MS_CLOCK { timestamp / 1000 } else { timestamp };
+    chrono::DateTime::from_timestamp(secs as i64, 0)
+        .map(|t| t.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// Replay `replayer`'s timeline over `markets`
+pub fn run(markets: &[Market], replayer: &BookReplayer, config: &Config) -> BacktestReport {
+    let calm = &config.strategy.regimes.calm;
+    let size = PositionSizer::new(config.trading.trade_size, config.trading.max_position_value).unscaled(calm.size_multiplier);
+    let timeline = replayer.timeline();
+    let mut report = BacktestReport { ticks: timeline.len(), ..Default::default() };
+    let tradable: Vec<&Market> = markets.iter()
+        .filter(|m| !m.clob_token_ids.is_empty() && m.clob_token_ids.iter().all(|t| replayer.book_at(t, u64::MAX).is_some()))
+        .collect();
+    report.markets = tradable.len();
+
+    // Market → timestamps of the books its last trade took
+    let mut taken: HashMap<&str, Vec<u64>> = HashMap::new();
+    let mut equity = 0.0;
+    for &now in &timeline {
+        for market in &tradable {
+            let Some(books) = market.clob_token_ids.iter().map(|t| replayer.book_at(t, now).cloned()).collect::<Option<Vec<OrderBook>>>() else {
+                continue;
+            };
+            let stamps: Vec<u64> = books.iter().map(|b| b.timestamp).collect();
+            if taken.get(market.id.as_str()).is_some_and(|t| stamps.iter().zip(t).all(|(s, t)| s <= t)) {
+                continue;
+            }
+            let taker_rate = FeeModel::from_market(market).taker_rate();
+            let Some(edge) = arb::edge_at(&books, Side::Buy, size, taker_rate) else { continue };
+            let gas = config.trading.gas_per_leg_usd * books.len() as f64;
+            if edge - gas / size < calm.min_edge {
+                continue;
+            }
+            let prices: Vec<f64> = books.iter().filter_map(|b| b.execution_price(size, Side::Buy)).collect();
+            let cost = prices.iter().sum::<f64>() * size;
+            let fees = cost * taker_rate + gas;
+            let trade = SimTrade {
+                timestamp: now,
+                market_id: market.id.clone(),
+                question: market.question.clone(),
+                size,
+                prices,
+                cost,
+                fees,
+                pnl: size - cost - fees,
+                books,
+            };
+            equity += trade.pnl;
+            report.equity.push(EquityPoint { timestamp: now, equity });
+            taken.insert(&market.id, stamps);
+            report.trades.push(trade);
+        }
+    }
+
+    let mut days: BTreeMap<String, DayStats> = BTreeMap::new();
+    for trade in &report.trades {
+        let day = days.entry(day_of(trade.timestamp)).or_insert_with_key(|day| DayStats {
+            day: day.clone(),
+            best: f64::NEG_INFINITY,
+            worst: f64::INFINITY,
+            ..Default::default()
+        });
+        day.trades += 1;
+        day.volume += trade.cost;
+        day.pnl += trade.pnl;
+        day.best = day.best.max(trade.pnl);
+        day.worst = day.worst.min(trade.pnl);
+    }
+    report.days = days.into_values().collect();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recorder::{RecordFormat, TickRecorder};
+    use crate::types::PriceLevel;
+
+    #[test]
+    fn test_trades_each_snapshot_once() {
+        let dir = std::env::temp_dir().join(format!("arbishark-backtest-{}", std::process::id()));
+        let book = |token: &str, ask: f64, timestamp: u64| OrderBook {
+            token_id: token.to_string(),
+            bids: vec![PriceLevel { price: ask - 0.02, size: 100.0 }],
+            asks: vec![PriceLevel { price: ask, size: 100.0 }],
+            timestamp,
+        };
+        // Day 1: a 0.10 edge, then NO requoted at the same price; day 2: YES reprices to a 0.05 edge
+        let mut recorder = TickRecorder::open(&dir.to_string_lossy(), RecordFormat::Jsonl).unwrap();
+        for b in [book("yes", 0.45, 1_700_000_000), book("no", 0.45, 1_700_000_000), book("yes", 0.50, 1_700_090_000)] {
+            recorder.record(&b).unwrap();
+        }
+        recorder.record(&book("no", 0.45, 1_700_000_500)).unwrap();
+        recorder.flush().unwrap();
+        let replayer = BookReplayer::from_recording(recorder.path()).unwrap();
+
+        let market: Market = serde_json::from_value(serde_json::json!({
+            "id": "m1", "question": "Will it rain?", "slug": "rain", "outcomes": ["Yes", "No"],
+            "outcome_prices": [0.45, 0.45], "clob_token_ids": ["yes", "no"], "best_bid": null, "best_ask": null,
+            "maker_base_fee": 0, "taker_base_fee": 0, "liquidity": 1000.0, "volume_24hr": 0.0,
+            "active": true, "accepting_orders": true
+        })).unwrap();
+        let mut config = Config::default_config();
+        config.trading.gas_per_leg_usd = 0.0;
+        let report = run(&[market], &replayer, &config);
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(report.ticks, 3);
+        assert_eq!(report.trades.len(), 3, "initial books, NO requoted, YES requoted");
+        let size = report.trades[0].size;
+        assert!((report.trades[0].edge() - 0.10).abs() < 1e-9);
+        assert!((report.trades[2].pnl - 0.05 * size).abs() < 1e-9);
+        assert_eq!(report.trades[2].books[0].timestamp, 1_700_090_000, "keeps the books it was decided on");
+        assert_eq!(report.days.len(), 2);
+        assert_eq!((report.days[0].day.as_str(), report.days[0].trades), ("2023-11-14", 2));
+        assert!((report.equity.last().unwrap().equity - report.total_pnl()).abs() < 1e-9);
+        assert_eq!(report.max_drawdown(), 0.0);
+    }
+}
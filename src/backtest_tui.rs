@@ -0,0 +1,233 @@
+//! Interactive backtest results browser (`arbishark backtest --tui`)
+//!
+//! Three views over a `BacktestReport`: the equity curve, the per-day
+//! breakdown and the simulated trades. Enter on a day jumps to its first
+//! trade; Enter on a trade drills down into it - prices, fees, PnL and the
+//! book ladders it was decided on - so the simulator's choices can be
+//! audited without exporting the results.
+//!
+//! Keys: Tab / ← → switch view, ↑ ↓ select, Enter drill down, Esc back, q quit.
+
+use crate::backtest::{day_of, BacktestReport, SimTrade};
+use crate::types::PriceLevel;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::symbols;
+use ratatui::text::Line;
+use ratatui::widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Paragraph, Row, Table, TableState, Tabs};
+use ratatui::{DefaultTerminal, Frame};
+use std::io;
+
+/// Price levels shown per side of a ladder
+const LADDER_DEPTH: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum View {
+    Equity,
+    Days,
+    Trades,
+}
+
+impl View {
+    const ALL: [View; 3] = [View::Equity, View::Days, View::Trades];
+
+    fn title(&self) -> &'static str {
+        match self {
+            Self::Equity => "Equity",
+            Self::Days => "Days",
+            Self::Trades => "Trades",
+        }
+    }
+}
+
+struct Browser<'a> {
+    report: &'a BacktestReport,
+    view: View,
+    days: TableState,
+    trades: TableState,
+    /// Showing the selected trade in detail
+    detail: bool,
+}
+
+/// Browse `report` until the user quits
+pub fn browse(report: &BacktestReport) -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let result = Browser::new(report).run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl<'a> Browser<'a> {
+    fn new(report: &'a BacktestReport) -> Self {
+        let select_first = |len: usize| TableState::default().with_selected((len > 0).then_some(0));
+        Self {
+            report,
+            view: View::Equity,
+            days: select_first(report.days.len()),
+            trades: select_first(report.trades.len()),
+            detail: false,
+        }
+    }
+
+    fn run(mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            let Event::Key(key) = event::read()? else { continue };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') => return Ok(()),
+                KeyCode::Esc if self.detail => self.detail = false,
+                KeyCode::Esc => return Ok(()),
+                KeyCode::Tab | KeyCode::Right => self.switch(1),
+                KeyCode::BackTab | KeyCode::Left => self.switch(View::ALL.len() - 1),
+                KeyCode::Down | KeyCode::Char('j') => self.step(1),
+                KeyCode::Up | KeyCode::Char('k') => self.step(-1),
+                KeyCode::Enter => self.drill_down(),
+                _ => {}
+            }
+        }
+    }
+
+    fn switch(&mut self, by: usize) {
+        let idx = View::ALL.iter().position(|v| *v == self.view).unwrap_or(0);
+        self.view = View::ALL[(idx + by) % View::ALL.len()];
+        self.detail = false;
+    }
+
+    fn step(&mut self, by: isize) {
+        let (state, len) = match self.view {
+            View::Days => (&mut self.days, self.report.days.len()),
+            View::Trades if !self.detail => (&mut self.trades, self.report.trades.len()),
+            _ => return,
+        };
+        if len == 0 {
+            return;
+        }
+        let at = state.selected().unwrap_or(0) as isize + by;
+        state.select(Some(at.clamp(0, len as isize - 1) as usize));
+    }
+
+    fn drill_down(&mut self) {
+        match self.view {
+            View::Days => {
+                let Some(day) = self.days.selected().and_then(|i| self.report.days.get(i)) else { return };
+                if let Some(first) = self.report.trades.iter().position(|t| day_of(t.timestamp) == day.day) {
+                    self.trades.select(Some(first));
+                    self.view = View::Trades;
+                }
+            }
+            View::Trades => self.detail = self.trades.selected().is_some(),
+            View::Equity => {}
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [tabs, body, footer] = Layout::vertical([Constraint::Length(3), Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let selected = View::ALL.iter().position(|v| *v == self.view).unwrap_or(0);
+        frame.render_widget(
+            Tabs::new(View::ALL.iter().map(|v| v.title()))
+                .select(selected)
+                .highlight_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+                .block(Block::default().borders(Borders::ALL).title(format!(" Backtest: {} trades, PnL ${:.4}, max drawdown ${:.4} ",
+                    self.report.trades.len(), self.report.total_pnl(), self.report.max_drawdown()))),
+            tabs,
+        );
+        match self.view {
+            View::Equity => self.draw_equity(frame, body),
+            View::Days => self.draw_days(frame, body),
+            View::Trades if self.detail => self.draw_trade(frame, body),
+            View::Trades => self.draw_trades(frame, body),
+        }
+        frame.render_widget(Paragraph::new("Tab/←→ view  ↑↓ select  Enter drill down  Esc back  q quit")
+            .style(Style::default().fg(Color::DarkGray)), footer);
+    }
+
+    fn draw_equity(&self, frame: &mut Frame, area: Rect) {
+        let points: Vec<(f64, f64)> = self.report.equity.iter().enumerate().map(|(i, p)| (i as f64, p.equity)).collect();
+        let low = points.iter().map(|p| p.1).fold(0.0, f64::min);
+        let high = points.iter().map(|p| p.1).fold(0.0, f64::max).max(low + 1e-9);
+        let span = (points.len().max(1) - 1).max(1) as f64;
+        let dataset = Dataset::default()
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::Cyan))
+            .data(&points);
+        let days = (self.report.days.first(), self.report.days.last());
+        let chart = Chart::new(vec![dataset])
+            .block(Block::default().borders(Borders::ALL).title(" Cumulative PnL by trade "))
+            .x_axis(Axis::default().bounds([0.0, span]).labels(match days {
+                (Some(first), Some(last)) => vec![first.day.clone(), last.day.clone()],
+                _ => Vec::new(),
+            }))
+            .y_axis(Axis::default().bounds([low, high]).labels(vec![format!("{:.2}", low), format!("{:.2}", high)]));
+        frame.render_widget(chart, area);
+    }
+
+    fn draw_days(&mut self, frame: &mut Frame, area: Rect) {
+        let rows = self.report.days.iter().map(|d| Row::new(vec![
+            d.day.clone(),
+            d.trades.to_string(),
+            format!("{:.2}", d.volume),
+            format!("{:.4}", d.pnl),
+            format!("{:.4}", d.best),
+            format!("{:.4}", d.worst),
+        ]));
+        let table = Table::new(rows, [Constraint::Length(12), Constraint::Length(8), Constraint::Length(12),
+                Constraint::Length(12), Constraint::Length(12), Constraint::Length(12)])
+            .header(Row::new(vec!["Day", "Trades", "Volume $", "PnL $", "Best $", "Worst $"]).style(Style::default().add_modifier(Modifier::BOLD)))
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .block(Block::default().borders(Borders::ALL).title(" Per day (Enter: its trades) "));
+        frame.render_stateful_widget(table, area, &mut self.days);
+    }
+
+    fn draw_trades(&mut self, frame: &mut Frame, area: Rect) {
+        let rows = self.report.trades.iter().map(|t| Row::new(vec![
+            t.timestamp.to_string(),
+            t.market_id.clone(),
+            format!("{:.2}", t.size),
+            format!("{:.4}", t.edge()),
+            format!("{:.4}", t.pnl),
+            t.question.clone(),
+        ]));
+        let table = Table::new(rows, [Constraint::Length(14), Constraint::Length(16), Constraint::Length(8),
+                Constraint::Length(8), Constraint::Length(10), Constraint::Min(10)])
+            .header(Row::new(vec!["Time", "Market", "Size", "Edge", "PnL $", "Question"]).style(Style::default().add_modifier(Modifier::BOLD)))
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .block(Block::default().borders(Borders::ALL).title(" Simulated trades (Enter: books at decision) "));
+        frame.render_stateful_widget(table, area, &mut self.trades);
+    }
+
+    fn draw_trade(&self, frame: &mut Frame, area: Rect) {
+        let Some(trade) = self.trades.selected().and_then(|i| self.report.trades.get(i)) else { return };
+        let [summary, ladders] = Layout::vertical([Constraint::Length(7), Constraint::Min(0)]).areas(area);
+        frame.render_widget(Paragraph::new(summary_lines(trade))
+            .block(Block::default().borders(Borders::ALL).title(format!(" {} ", trade.market_id))), summary);
+        let columns = Layout::horizontal(trade.books.iter().map(|_| Constraint::Ratio(1, trade.books.len().max(1) as u32))).split(ladders);
+        for ((book, price), column) in trade.books.iter().zip(&trade.prices).zip(columns.iter()) {
+            let mut lines = vec![Line::from(format!("recorded at {} | filled at {:.4}", book.timestamp, price)), Line::from("")];
+            lines.extend(book.asks.iter().take(LADDER_DEPTH).rev().map(|l| ladder_line("ask", l, Color::Red)));
+            lines.push(Line::from("─".repeat(24)));
+            lines.extend(book.bids.iter().take(LADDER_DEPTH).map(|l| ladder_line("bid", l, Color::Green)));
+            frame.render_widget(Paragraph::new(lines)
+                .block(Block::default().borders(Borders::ALL).title(format!(" {} ", book.token_id))), *column);
+        }
+    }
+}
+
+fn summary_lines(trade: &SimTrade) -> Vec<Line<'static>> {
+    vec![
+        Line::from(trade.question.clone()),
+        Line::from(format!("at {} ({})  size {:.2} per leg", trade.timestamp, day_of(trade.timestamp), trade.size)),
+        Line::from(format!("leg prices {}  → set cost {:.4}", trade.prices.iter().map(|p| format!("{:.4}", p)).collect::<Vec<_>>().join(" + "),
+            trade.prices.iter().sum::<f64>())),
+        Line::from(format!("paid ${:.4}  fees + gas ${:.4}  settles ${:.4}", trade.cost, trade.fees, trade.size)),
+        Line::from(format!("PnL ${:.4}  ({:.4} per set)", trade.pnl, trade.edge())),
+    ]
+}
+
+fn ladder_line(side: &str, level: &PriceLevel, color: Color) -> Line<'static> {
+    Line::styled(format!("{} {:>8.4} × {:>10.2}", side, level.price, level.size), Style::default().fg(color))
+}
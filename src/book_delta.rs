@@ -0,0 +1,218 @@
+//! Order book deltas
+//!
+//! Diffs consecutive snapshots of a book into added / removed / changed
+//! levels and applies such deltas in place, so the streaming cache updates
+//! only what moved instead of replacing both `Vec`s on every message. The
+//! same deltas feed quote add / cancel rates, a microstructure signal for
+//! how actively liquidity is being posted and pulled.
+
+use crate::types::{OrderBook, PriceLevel};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// Levels closer than this are the same price
+const PRICE_EPS: f64 = 1e-9;
+/// Activity samples kept per token
+const MAX_ACTIVITY: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BookSide {
+    Bid,
+    Ask,
+}
+
+/// New state of one price level; `size == 0` removes it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LevelChange {
+    pub side: BookSide,
+    pub price: f64,
+    pub size: f64,
+    /// Size before the change (0 for a new level)
+    #[serde(default)]
+    pub previous: f64,
+}
+
+impl LevelChange {
+    /// Liquidity posted by this change
+    pub fn added(&self) -> f64 {
+        (self.size - self.previous).max(0.0)
+    }
+
+    /// Liquidity pulled (or consumed) by this change
+    pub fn cancelled(&self) -> f64 {
+        (self.previous - self.size).max(0.0)
+    }
+}
+
+/// Changes turning one snapshot of a book into the next
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookDelta {
+    pub token_id: String,
+    pub timestamp: u64,
+    pub changes: Vec<LevelChange>,
+}
+
+impl BookDelta {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// Level-by-level difference between two snapshots of the same token
+pub fn diff(old: &OrderBook, new: &OrderBook) -> BookDelta {
+    let mut changes = Vec::new();
+    for (side, before, after) in [
+        (BookSide::Bid, &old.bids, &new.bids),
+        (BookSide::Ask, &old.asks, &new.asks),
+    ] {
+        for level in after {
+            let previous = find(before, level.price).map_or(0.0, |l| l.size);
+            if (previous - level.size).abs() > PRICE_EPS {
+                changes.push(LevelChange { side, price: level.price, size: level.size, previous });
+            }
+        }
+        for level in before {
+            if find(after, level.price).is_none() {
+                changes.push(LevelChange { side, price: level.price, size: 0.0, previous: level.size });
+            }
+        }
+    }
+    BookDelta { token_id: new.token_id.clone(), timestamp: new.timestamp, changes }
+}
+
+/// Apply a delta in place, keeping bids descending and asks ascending
+pub fn apply(book: &mut OrderBook, delta: &BookDelta) {
+    for change in &delta.changes {
+        let levels = match change.side {
+            BookSide::Bid => &mut book.bids,
+            BookSide::Ask => &mut book.asks,
+        };
+        let existing = levels.iter().position(|l| (l.price - change.price).abs() < PRICE_EPS);
+        match (existing, change.size > 0.0) {
+            (Some(i), true) => levels[i].size = change.size,
+            (Some(i), false) => {
+                levels.remove(i);
+            }
+            (None, true) => {
+                let at = levels.iter().position(|l| match change.side {
+                    BookSide::Bid => l.price < change.price,
+                    BookSide::Ask => l.price > change.price,
+                }).unwrap_or(levels.len());
+                levels.insert(at, PriceLevel { price: change.price, size: change.size });
+            }
+            (None, false) => {}
+        }
+    }
+    book.timestamp = book.timestamp.max(delta.timestamp);
+}
+
+fn find(levels: &[PriceLevel], price: f64) -> Option<&PriceLevel> {
+    levels.iter().find(|l| (l.price - price).abs() < PRICE_EPS)
+}
+
+/// Posted / pulled liquidity per second over a window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct QuoteRates {
+    pub add_rate: f64,
+    pub cancel_rate: f64,
+}
+
+/// Latest book per token, maintained from snapshots and deltas
+#[derive(Debug, Default)]
+pub struct BookCache {
+    books: HashMap<String, OrderBook>,
+    /// (timestamp, added, cancelled) per token
+    activity: HashMap<String, VecDeque<(u64, f64, f64)>>,
+}
+
+impl BookCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, token_id: &str) -> Option<&OrderBook> {
+        self.books.get(token_id)
+    }
+
+    /// Store a full snapshot; returns its delta from the cached book
+    pub fn update_snapshot(&mut self, book: OrderBook) -> BookDelta {
+        let delta = match self.books.get(&book.token_id) {
+            Some(old) => diff(old, &book),
+            None => diff(&OrderBook { bids: Vec::new(), asks: Vec::new(), ..book.clone() }, &book),
+        };
+        self.record(&delta);
+        self.books.insert(book.token_id.clone(), book);
+        delta
+    }
+
+    /// Apply an incremental update (unknown tokens start from an empty book)
+    pub fn apply_delta(&mut self, delta: &BookDelta) {
+        let book = self.books.entry(delta.token_id.clone()).or_insert_with(|| OrderBook {
+            token_id: delta.token_id.clone(),
+            bids: Vec::new(),
+            asks: Vec::new(),
+            timestamp: delta.timestamp,
+        });
+        apply(book, delta);
+        self.record(delta);
+    }
+
+    /// Quote add / cancel rates over the `window_secs` before `now`
+    pub fn quote_rates(&self, token_id: &str, now: u64, window_secs: u64) -> QuoteRates {
+        let Some(samples) = self.activity.get(token_id) else { return QuoteRates::default() };
+        let (added, cancelled) = samples.iter()
+            .filter(|(t, _, _)| now.saturating_sub(*t) < window_secs)
+            .fold((0.0, 0.0), |(a, c), (_, add, cancel)| (a + add, c + cancel));
+        let secs = window_secs.max(1) as f64;
+        QuoteRates { add_rate: added / secs, cancel_rate: cancelled / secs }
+    }
+
+    fn record(&mut self, delta: &BookDelta) {
+        if delta.is_empty() {
+            return;
+        }
+        let added = delta.changes.iter().map(LevelChange::added).sum();
+        let cancelled = delta.changes.iter().map(LevelChange::cancelled).sum();
+        let samples = self.activity.entry(delta.token_id.clone()).or_default();
+        samples.push_back((delta.timestamp, added, cancelled));
+        while samples.len() > MAX_ACTIVITY {
+            samples.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::book;
+
+    fn before() -> OrderBook {
+        OrderBook { timestamp: 1, ..book("t1", &[(0.49, 100.0), (0.48, 200.0)], &[(0.51, 50.0)]) }
+    }
+
+    fn after() -> OrderBook {
+        OrderBook { timestamp: 2, ..book("t1", &[(0.49, 60.0), (0.47, 10.0)], &[(0.50, 30.0), (0.51, 50.0)]) }
+    }
+
+    #[test]
+    fn test_diff_apply_roundtrip() {
+        let delta = diff(&before(), &after());
+        // 0.49 shrank, 0.47 added, 0.48 removed, 0.50 added
+        assert_eq!(delta.changes.len(), 4);
+        let mut patched = before();
+        apply(&mut patched, &delta);
+        assert_eq!(serde_json::to_value(&patched).unwrap(), serde_json::to_value(after()).unwrap());
+    }
+
+    #[test]
+    fn test_quote_rates_from_snapshots() {
+        let mut cache = BookCache::new();
+        cache.update_snapshot(before());
+        cache.update_snapshot(after());
+        let rates = cache.quote_rates("t1", 2, 10);
+        // Posted: 350 initial + 10 + 30; pulled: 40 + 200
+        assert!((rates.add_rate - 39.0).abs() < 1e-9);
+        assert!((rates.cancel_rate - 24.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,271 @@
+// Strategy budgets
+// Virtual sub-accounts per strategy with their own capital, PnL and journaled kill switch
+
+use crate::config::StrategyBudgetConfig;
+use crate::storage::JsonlStore;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Strategy name of the cross-outcome arbitrage in the main loop
+pub const ARB_STRATEGY: &str = "arb";
+
+/// One strategy's sub-account
+#[derive(Debug, Clone, Serialize)]
+pub struct SubAccount {
+    pub strategy: String,
+    /// Budget ($) the strategy may deploy, grown or shrunk by its PnL
+    pub capital: f64,
+    /// Notional currently held in open positions
+    pub deployed: f64,
+    pub realized_pnl: f64,
+    pub trades: u64,
+    pub wins: u64,
+    /// Disable once realized PnL falls to -max_loss
+    pub max_loss: Option<f64>,
+    pub enabled: bool,
+    pub disabled_reason: Option<String>,
+    /// When the strategy was last switched on or off
+    pub switched_at: u64,
+    /// Time spent enabled / disabled, including the current stint as of the report
+    pub enabled_secs: u64,
+    pub disabled_secs: u64,
+}
+
+impl SubAccount {
+    /// Capital not yet deployed
+    pub fn available(&self) -> f64 {
+        (self.capital + self.realized_pnl - self.deployed).max(0.0)
+    }
+
+    fn switch(&mut self, enabled: bool, reason: Option<String>, at: u64) {
+        if enabled != self.enabled {
+            self.close_stint(at);
+            self.switched_at = at;
+        }
+        self.enabled = enabled;
+        self.disabled_reason = reason;
+    }
+
+    /// Book the time since the last switch to the current state
+    fn close_stint(&mut self, at: u64) {
+        let elapsed = at.saturating_sub(self.switched_at);
+        if self.enabled {
+            self.enabled_secs += elapsed;
+        } else {
+            self.disabled_secs += elapsed;
+        }
+    }
+}
+
+/// A strategy switched on or off, as journaled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Switch {
+    strategy: String,
+    enabled: bool,
+    reason: Option<String>,
+    at: u64,
+}
+
+/// Reason a strategy may not open a trade
+#[derive(Debug, Clone, PartialEq)]
+pub enum BudgetError {
+    Unknown(String),
+    Disabled { strategy: String, reason: String },
+    Exhausted { strategy: String, requested: f64, available: f64 },
+}
+
+impl std::fmt::Display for BudgetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unknown(strategy) => write!(f, "Strategy {} has no budget", strategy),
+            Self::Disabled { strategy, reason } => write!(f, "Strategy {} disabled: {}", strategy, reason),
+            Self::Exhausted { strategy, requested, available } => write!(f,
+                "Strategy {} budget exhausted (${:.2} requested, ${:.2} available)", strategy, requested, available),
+        }
+    }
+}
+
+impl std::error::Error for BudgetError {}
+
+/// Per-strategy sub-accounts
+#[derive(Debug, Default)]
+pub struct StrategyBook {
+    accounts: HashMap<String, SubAccount>,
+    journal: Option<JsonlStore>,
+}
+
+impl StrategyBook {
+    pub fn from_config(budgets: &HashMap<String, StrategyBudgetConfig>, now: u64) -> Self {
+        let accounts = budgets.iter().map(|(name, b)| (name.clone(), SubAccount {
+            strategy: name.clone(),
+            capital: b.capital,
+            deployed: 0.0,
+            realized_pnl: 0.0,
+            trades: 0,
+            wins: 0,
+            max_loss: b.max_loss,
+            enabled: b.enabled,
+            disabled_reason: (!b.enabled).then(|| "disabled in config".to_string()),
+            switched_at: now,
+            enabled_secs: 0,
+            disabled_secs: 0,
+        })).collect();
+        Self { accounts, journal: None }
+    }
+
+    /// Journal switches to `journal`, replaying the ones already in it
+    pub fn with_journal(mut self, journal: JsonlStore) -> Self {
+        let switches: Vec<Switch> = journal.load().unwrap_or_else(|e| {
+            eprintln!("⚠️ [Budget] Failed to read strategy switch journal: {}", e);
+            Vec::new()
+        });
+        let mut seen = HashSet::new();
+        for switch in switches {
+            let Some(account) = self.accounts.get_mut(&switch.strategy) else { continue };
+            // Durations count from the first journaled switch
+            if seen.insert(switch.strategy.clone()) {
+                account.switched_at = switch.at;
+            }
+            account.switch(switch.enabled, switch.reason, switch.at);
+        }
+        self.journal = Some(journal);
+        self
+    }
+
+    fn record(&mut self, switch: Switch) {
+        if let Some(journal) = &self.journal {
+            if let Err(e) = journal.append(&switch) {
+                eprintln!("⚠️ [Budget] Failed to journal strategy switch: {}", e);
+            }
+        }
+        if let Some(account) = self.accounts.get_mut(&switch.strategy) {
+            account.switch(switch.enabled, switch.reason, switch.at);
+        }
+    }
+
+    /// Whether the scheduler should run `strategy` (strategies without a sub-account always run)
+    pub fn is_enabled(&self, strategy: &str) -> bool {
+        self.accounts.get(strategy).is_none_or(|a| a.enabled)
+    }
+
+    /// Whether `strategy` may deploy another `notional`
+    pub fn check(&self, strategy: &str, notional: f64) -> Result<(), BudgetError> {
+        if self.accounts.is_empty() {
+            return Ok(());
+        }
+        let account = self.accounts.get(strategy).ok_or_else(|| BudgetError::Unknown(strategy.to_string()))?;
+        if !account.enabled {
+            return Err(BudgetError::Disabled {
+                strategy: strategy.to_string(),
+                reason: account.disabled_reason.clone().unwrap_or_default(),
+            });
+        }
+        if notional > account.available() {
+            return Err(BudgetError::Exhausted {
+                strategy: strategy.to_string(),
+                requested: notional,
+                available: account.available(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Charge a fill against the strategy's budget
+    pub fn allocate(&mut self, strategy: &str, notional: f64) {
+        if let Some(account) = self.accounts.get_mut(strategy) {
+            account.deployed += notional;
+        }
+    }
+
+    /// Release a closed position's notional and book its PnL
+    pub fn settle(&mut self, strategy: &str, notional: f64, pnl: f64, now: u64) {
+        let Some(account) = self.accounts.get_mut(strategy) else { return };
+        account.deployed = (account.deployed - notional).max(0.0);
+        account.realized_pnl += pnl;
+        account.trades += 1;
+        if pnl > 0.0 {
+            account.wins += 1;
+        }
+        if let Some(max_loss) = account.max_loss {
+            if account.enabled && account.realized_pnl <= -max_loss {
+                println!("🛑 [Budget] Strategy {} disabled: lost ${:.2}", strategy, -account.realized_pnl);
+                let reason = Some(format!("loss limit ${:.2} reached", max_loss));
+                self.record(Switch { strategy: strategy.to_string(), enabled: false, reason, at: now });
+            }
+        }
+    }
+
+    /// Enable or disable one strategy; false if it has no sub-account
+    pub fn set_enabled(&mut self, strategy: &str, enabled: bool, reason: &str, now: u64) -> bool {
+        if !self.accounts.contains_key(strategy) {
+            return false;
+        }
+        let reason = (!enabled).then(|| reason.to_string());
+        self.record(Switch { strategy: strategy.to_string(), enabled, reason, at: now });
+        true
+    }
+
+    /// All sub-accounts, by strategy name, with durations up to `now`
+    pub fn report(&self, now: u64) -> Vec<SubAccount> {
+        let mut accounts: Vec<SubAccount> = self.accounts.values().cloned().collect();
+        for account in &mut accounts {
+            account.close_stint(now);
+        }
+        accounts.sort_by(|a, b| a.strategy.cmp(&b.strategy));
+        accounts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_segregation() {
+        let budgets = HashMap::from([
+            ("arb".to_string(), StrategyBudgetConfig { capital: 50.0, max_loss: Some(10.0), enabled: true }),
+            ("momentum".to_string(), StrategyBudgetConfig { capital: 20.0, max_loss: None, enabled: true }),
+        ]);
+        let mut book = StrategyBook::from_config(&budgets, 0);
+        assert!(book.check("arb", 40.0).is_ok());
+        book.allocate("arb", 40.0);
+        assert!(matches!(book.check("arb", 20.0), Err(BudgetError::Exhausted { .. })));
+        assert!(book.check("momentum", 20.0).is_ok());
+        assert!(matches!(book.check("other", 1.0), Err(BudgetError::Unknown(_))));
+
+        // A losing arb is switched off; momentum keeps trading
+        book.settle("arb", 40.0, -12.0, 0);
+        assert!(matches!(book.check("arb", 1.0), Err(BudgetError::Disabled { .. })));
+        assert!(book.check("momentum", 5.0).is_ok());
+        let arb = &book.report(0)[0];
+        assert_eq!(arb.deployed, 0.0);
+        assert_eq!(arb.available(), 38.0);
+
+        assert!(StrategyBook::default().check("anything", 1e9).is_ok());
+    }
+
+    #[test]
+    fn test_kill_switch_survives_restart() {
+        let dir = std::env::temp_dir().join(format!("arbishark_switches_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let journal = JsonlStore::open(dir.to_str().unwrap(), "strategy_switches.jsonl").unwrap();
+        let budgets = HashMap::from([
+            ("arb".to_string(), StrategyBudgetConfig { capital: 50.0, max_loss: None, enabled: true }),
+        ]);
+
+        let mut book = StrategyBook::from_config(&budgets, 100).with_journal(journal.clone());
+        assert!(book.is_enabled("arb") && book.is_enabled("unbudgeted"));
+        assert!(book.set_enabled("arb", false, "disabled via API", 160));
+        assert!(!book.set_enabled("other", false, "disabled via API", 160));
+        assert!(!book.is_enabled("arb"));
+        let arb = &book.report(200)[0];
+        assert_eq!((arb.enabled_secs, arb.disabled_secs), (60, 40));
+
+        // A fresh session starts from config, then the journal turns arb back off
+        let restored = StrategyBook::from_config(&budgets, 500).with_journal(journal);
+        assert!(matches!(restored.check("arb", 1.0), Err(BudgetError::Disabled { reason, .. }) if reason == "disabled via API"));
+        let arb = &restored.report(560)[0];
+        assert_eq!((arb.switched_at, arb.enabled_secs, arb.disabled_secs), (160, 0, 400));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,248 @@
+//! Edge capture ratio
+//!
+//! Compares what each trade actually made (net PnL) with the edge the signal
+//! promised when it was detected, overall, over the most recent trades and
+//! per market and strategy. The ratio is split in two so that slippage from
+//! latency or competition shows up apart from signal quality:
+//! `execution_capture` is the edge still there when the books were
+//! re-checked right before execution vs the detected edge, `realization` is
+//! net PnL vs that execution edge. Their product is the capture ratio.
+
+use crate::positions::ExitResult;
+use crate::storage::JsonlStore;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Trades in the "recent" row
+const RECENT_TRADES: usize = 50;
+
+/// An opened leg and the edge ($) attributed to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureEntry {
+    pub token_id: String,
+    pub market_id: String,
+    pub strategy: String,
+    /// Edge at detection
+    pub signal_edge: f64,
+    /// Edge on the books re-checked right before execution
+    pub execution_edge: f64,
+    pub at: u64,
+}
+
+/// A closed leg
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureRecord {
+    #[serde(flatten)]
+    pub entry: CaptureEntry,
+    /// Net PnL
+    pub realized: f64,
+    pub closed_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum CaptureEvent {
+    Entry(CaptureEntry),
+    Exit { token_id: String, realized: f64, at: u64 },
+}
+
+/// Totals and ratios for one key ("overall", "recent", a market or a strategy)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CaptureRow {
+    pub key: String,
+    pub trades: u64,
+    pub signal_edge: f64,
+    pub execution_edge: f64,
+    pub realized: f64,
+    /// realized / signal_edge
+    pub capture_ratio: Option<f64>,
+    /// execution_edge / signal_edge (decay before the fill)
+    pub execution_capture: Option<f64>,
+    /// realized / execution_edge (after the fill)
+    pub realization: Option<f64>,
+}
+
+fn ratio(numerator: f64, denominator: f64) -> Option<f64> {
+    (denominator > 0.0).then(|| numerator / denominator)
+}
+
+impl CaptureRow {
+    fn from_records<'a>(key: &str, records: impl Iterator<Item = &'a CaptureRecord>) -> Self {
+        let mut row = Self { key: key.to_string(), ..Default::default() };
+        for record in records {
+            row.trades += 1;
+            row.signal_edge += record.entry.signal_edge;
+            row.execution_edge += record.entry.execution_edge;
+            row.realized += record.realized;
+        }
+        row.capture_ratio = ratio(row.realized, row.signal_edge);
+        row.execution_capture = ratio(row.execution_edge, row.signal_edge);
+        row.realization = ratio(row.realized, row.execution_edge);
+        row
+    }
+}
+
+/// `/api/capture`
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptureReport {
+    pub overall: CaptureRow,
+    pub recent: CaptureRow,
+    /// Worst capture first
+    pub by_market: Vec<CaptureRow>,
+    pub by_strategy: Vec<CaptureRow>,
+}
+
+/// Signal-time edge vs realized PnL of every closed leg
+#[derive(Debug, Default)]
+pub struct CaptureTracker {
+    open: HashMap<String, CaptureEntry>,
+    records: Vec<CaptureRecord>,
+    journal: Option<JsonlStore>,
+}
+
+impl CaptureTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Journal to `journal`, replaying the legs already in it
+    pub fn with_journal(mut self, journal: JsonlStore) -> Self {
+        let events: Vec<CaptureEvent> = journal.load().unwrap_or_else(|e| {
+            eprintln!("⚠️ [Capture] Failed to read capture journal: {}", e);
+            Vec::new()
+        });
+        for event in events {
+            self.apply(event);
+        }
+        self.journal = Some(journal);
+        self
+    }
+
+    fn apply(&mut self, event: CaptureEvent) {
+        match event {
+            CaptureEvent::Entry(entry) => {
+                self.open.insert(entry.token_id.clone(), entry);
+            }
+            CaptureEvent::Exit { token_id, realized, at } => {
+                if let Some(entry) = self.open.remove(&token_id) {
+                    self.records.push(CaptureRecord { entry, realized, closed_at: at });
+                }
+            }
+        }
+    }
+
+    fn record(&mut self, event: CaptureEvent) {
+        if let Some(journal) = &self.journal {
+            if let Err(e) = journal.append(&event) {
+                eprintln!("⚠️ [Capture] Failed to journal capture event: {}", e);
+            }
+        }
+        self.apply(event);
+    }
+
+    pub fn record_entry(&mut self, entry: CaptureEntry) {
+        self.record(CaptureEvent::Entry(entry));
+    }
+
+    /// Close the leg of `exit` (legs opened before tracking started are ignored)
+    pub fn record_exit(&mut self, exit: &ExitResult) {
+        if self.open.contains_key(&exit.position.token_id) {
+            self.record(CaptureEvent::Exit { token_id: exit.position.token_id.clone(), realized: exit.pnl, at: exit.exit_time });
+        }
+    }
+
+    pub fn records(&self) -> &[CaptureRecord] {
+        &self.records
+    }
+
+    pub fn overall(&self) -> CaptureRow {
+        CaptureRow::from_records("overall", self.records.iter())
+    }
+
+    pub fn report(&self) -> CaptureReport {
+        let by = |key: fn(&CaptureRecord) -> &str| {
+            let mut groups: BTreeMap<&str, Vec<&CaptureRecord>> = BTreeMap::new();
+            for record in &self.records {
+                groups.entry(key(record)).or_default().push(record);
+            }
+            let mut rows: Vec<CaptureRow> = groups.into_iter()
+                .map(|(k, records)| CaptureRow::from_records(k, records.into_iter()))
+                .collect();
+            rows.sort_by(|a, b| a.capture_ratio.unwrap_or(f64::INFINITY).total_cmp(&b.capture_ratio.unwrap_or(f64::INFINITY)));
+            rows
+        };
+        let recent = &self.records[self.records.len().saturating_sub(RECENT_TRADES)..];
+        CaptureReport {
+            overall: self.overall(),
+            recent: CaptureRow::from_records("recent", recent.iter()),
+            by_market: by(|r| &r.entry.market_id),
+            by_strategy: by(|r| &r.entry.strategy),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::positions::{ExitReason, Position};
+    use crate::types::Side;
+
+    fn entry(token_id: &str, market_id: &str, signal_edge: f64, execution_edge: f64) -> CaptureEntry {
+        CaptureEntry {
+            token_id: token_id.to_string(),
+            market_id: market_id.to_string(),
+            strategy: "arb".to_string(),
+            signal_edge,
+            execution_edge,
+            at: 0,
+        }
+    }
+
+    fn exit(token_id: &str, pnl: f64) -> ExitResult {
+        ExitResult {
+            position: Position {
+                market_id: String::new(),
+                token_id: token_id.to_string(),
+                side: Side::Buy,
+                size: 10.0,
+                entry_price: 0.5,
+                entry_time: 0,
+                entry_spread: 0.03,
+                strategy: "arb".to_string(),
+            },
+            exit_price: 0.5,
+            exit_time: 100,
+            reason: ExitReason::Timeout,
+            pnl,
+            fees: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_capture_ratio_split() {
+        let dir = std::env::temp_dir().join(format!("arbishark_capture_{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+        let journal = JsonlStore::open(dir, "capture.jsonl").unwrap();
+        let mut tracker = CaptureTracker::new().with_journal(journal.clone());
+
+        // m1: half the edge gone before the fill, all of the rest realized
+        tracker.record_entry(entry("a", "m1", 0.40, 0.20));
+        tracker.record_exit(&exit("a", 0.20));
+        // m2: edge intact at the fill, but the trade lost money
+        tracker.record_entry(entry("b", "m2", 0.20, 0.20));
+        tracker.record_exit(&exit("b", -0.10));
+        tracker.record_exit(&exit("untracked", 1.0));
+
+        let report = tracker.report();
+        assert_eq!(report.overall.trades, 2);
+        assert!((report.overall.capture_ratio.unwrap() - 0.10 / 0.60).abs() < 1e-9);
+        let m1 = report.by_market.iter().find(|r| r.key == "m1").unwrap();
+        assert_eq!((m1.execution_capture, m1.realization), (Some(0.5), Some(1.0)));
+        assert_eq!(report.by_market[0].key, "m2", "worst capture first");
+        assert_eq!(report.by_strategy.len(), 1);
+
+        let restored = CaptureTracker::new().with_journal(journal);
+        assert_eq!(restored.records().len(), 2);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
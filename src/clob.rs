@@ -25,7 +25,7 @@ impl ClobClient {
     pub fn new(base_url: &str) -> Self {
         Self {
             base_url: base_url.to_string(),
-            client: reqwest::Client::new(),
+            client: crate::market_client::http_client(),
         }
     }
 
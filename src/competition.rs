@@ -0,0 +1,113 @@
+//! Competition Model
+//!
+//! Simulated adversary for backtests. Real arb opportunities are contested by
+//! faster bots: a competitor targets a configurable fraction of detected
+//! edges and arrives after a sampled latency. If it beats our execution
+//! latency, the edge is gone and we get no fill.
+
+use crate::config::CompetitionConfig;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rand_distr::{Distribution, Normal};
+
+/// Outcome of racing a competitor for one opportunity
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RaceOutcome {
+    /// No competitor targeted this edge
+    Uncontested,
+    /// A competitor targeted it but we arrived first
+    Won { competitor_latency_ms: f64 },
+    /// A competitor arrived first and consumed the edge
+    Lost { competitor_latency_ms: f64 },
+}
+
+/// Capture statistics over a backtest
+#[derive(Debug, Clone, Default)]
+pub struct CompetitionStats {
+    pub opportunities: u64,
+    pub contested: u64,
+    pub lost: u64,
+}
+
+impl CompetitionStats {
+    /// Fraction of detected opportunities we actually got to trade
+    pub fn capture_rate(&self) -> f64 {
+        if self.opportunities == 0 {
+            return 1.0;
+        }
+        (self.opportunities - self.lost) as f64 / self.opportunities as f64
+    }
+}
+
+/// Competitor that races us for detected edges
+#[derive(Debug)]
+pub struct CompetitorModel {
+    take_fraction: f64,
+    latency: Normal<f64>,
+    rng: StdRng,
+    pub stats: CompetitionStats,
+}
+
+impl CompetitorModel {
+    pub fn new(config: &CompetitionConfig) -> Self {
+        let rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Self {
+            take_fraction: config.take_fraction.clamp(0.0, 1.0),
+            latency: Normal::new(config.latency_mean_ms, config.latency_std_ms.max(0.0))
+                .unwrap_or_else(|_| Normal::new(config.latency_mean_ms, 0.0).unwrap()),
+            rng,
+            stats: CompetitionStats::default(),
+        }
+    }
+
+    /// Race for an opportunity given our own execution latency
+    pub fn race(&mut self, our_latency_ms: f64) -> RaceOutcome {
+        self.stats.opportunities += 1;
+        if self.rng.gen::<f64>() >= self.take_fraction {
+            return RaceOutcome::Uncontested;
+        }
+        self.stats.contested += 1;
+        let competitor_latency_ms = self.latency.sample(&mut self.rng).max(0.0);
+        if competitor_latency_ms < our_latency_ms {
+            self.stats.lost += 1;
+            RaceOutcome::Lost { competitor_latency_ms }
+        } else {
+            RaceOutcome::Won { competitor_latency_ms }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_rate() {
+        let config = CompetitionConfig {
+            enabled: true,
+            take_fraction: 0.5,
+            latency_mean_ms: 10.0,
+            latency_std_ms: 0.0,
+            seed: Some(7),
+        };
+
+        // Competitor always faster: we lose roughly the contested half
+        let mut model = CompetitorModel::new(&config);
+        for _ in 0..1000 {
+            model.race(50.0);
+        }
+        let rate = model.stats.capture_rate();
+        assert!(rate > 0.4 && rate < 0.6, "capture rate {}", rate);
+        assert_eq!(model.stats.lost, model.stats.contested);
+
+        // We are faster than the competitor: never lose
+        let mut model = CompetitorModel::new(&config);
+        for _ in 0..100 {
+            assert!(!matches!(model.race(5.0), RaceOutcome::Lost { .. }));
+        }
+        assert_eq!(model.stats.capture_rate(), 1.0);
+    }
+}
@@ -0,0 +1,207 @@
+//! Pre-trade compliance: per-jurisdiction blacklist, notional cap and
+//! trading-hours rules, each evaluation traced rule by rule
+
+use crate::config::{ComplianceConfig, ComplianceRuleConfig};
+use crate::types::Market;
+use crate::windows::{HourRange, WindowError};
+use chrono::{DateTime, Timelike, Utc};
+use serde::Serialize;
+
+#[derive(Debug, Clone)]
+enum RuleKind {
+    /// Markets in these categories, or whose question mentions a keyword
+    Blacklist { categories: Vec<String>, keywords: Vec<String> },
+    /// Cap on notional deployed per market in `category` (None = any)
+    MaxNotional { category: Option<String>, max_notional: f64 },
+    /// Trading allowed only inside these UTC hours for `categories` (empty = all)
+    TradingHours { hours: Vec<HourRange>, categories: Vec<String> },
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    id: String,
+    kind: RuleKind,
+}
+
+/// Outcome of one rule for one trade
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RuleCheck {
+    pub rule_id: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+/// Decision trace of a compliance evaluation
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ComplianceDecision {
+    pub checks: Vec<RuleCheck>,
+}
+
+impl ComplianceDecision {
+    pub fn allowed(&self) -> bool {
+        self.checks.iter().all(|c| c.passed)
+    }
+
+    /// Rules that refused the trade
+    pub fn violations(&self) -> Vec<&RuleCheck> {
+        self.checks.iter().filter(|c| !c.passed).collect()
+    }
+
+    /// One-line trace: `RULE-ID pass, RULE-ID FAIL (detail), ...`
+    pub fn trace(&self) -> String {
+        self.checks.iter()
+            .map(|c| if c.passed { format!("{} pass", c.rule_id) } else { format!("{} FAIL ({})", c.rule_id, c.detail) })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Rules of the configured jurisdiction
+#[derive(Debug, Clone, Default)]
+pub struct Compliance {
+    jurisdiction: String,
+    rules: Vec<Rule>,
+}
+
+impl Compliance {
+    /// Keep the rules that apply to the configured jurisdiction
+    pub fn from_config(config: &ComplianceConfig) -> Result<Self, WindowError> {
+        let mut rules = Vec::new();
+        for rule in &config.rules {
+            let (id, jurisdictions, kind) = match rule {
+                ComplianceRuleConfig::Blacklist { id, jurisdictions, categories, keywords } => (id, jurisdictions,
+                    RuleKind::Blacklist {
+                        categories: categories.clone(),
+                        keywords: keywords.iter().map(|k| k.to_lowercase()).collect(),
+                    }),
+                ComplianceRuleConfig::MaxNotional { id, jurisdictions, category, max_notional } => (id, jurisdictions,
+                    RuleKind::MaxNotional { category: category.clone(), max_notional: *max_notional }),
+                ComplianceRuleConfig::TradingHours { id, jurisdictions, hours_utc, categories } => (id, jurisdictions,
+                    RuleKind::TradingHours {
+                        hours: hours_utc.iter().map(|r| HourRange::parse(r)).collect::<Result<_, _>>()?,
+                        categories: categories.clone(),
+                    }),
+            };
+            if jurisdictions.is_empty() || jurisdictions.iter().any(|j| j.eq_ignore_ascii_case(&config.jurisdiction)) {
+                rules.push(Rule { id: id.clone(), kind });
+            }
+        }
+        Ok(Self { jurisdiction: config.jurisdiction.clone(), rules })
+    }
+
+    pub fn jurisdiction(&self) -> &str {
+        &self.jurisdiction
+    }
+
+    pub fn rule_count(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Evaluate every rule for a trade adding `notional` to a market that
+    /// already has `market_notional` deployed
+    pub fn evaluate(&self, market: &Market, category: &str, market_notional: f64, notional: f64, now: u64) -> ComplianceDecision {
+        let checks = self.rules.iter().map(|rule| {
+            let failure = match &rule.kind {
+                RuleKind::Blacklist { categories, keywords } => {
+                    let question = market.question.to_lowercase();
+                    if categories.iter().any(|c| c == category) {
+                        Some(format!("category {} is blacklisted", category))
+                    } else {
+                        keywords.iter().find(|k| question.contains(k.as_str()))
+                            .map(|k| format!("question mentions {:?}", k))
+                    }
+                }
+                RuleKind::MaxNotional { category: scope, max_notional } => {
+                    let total = market_notional + notional;
+                    (scope.as_deref().is_none_or(|c| c == category) && total > *max_notional)
+                        .then(|| format!("${:.2} in market exceeds ${:.2} cap", total, max_notional))
+                }
+                RuleKind::TradingHours { hours, categories } => {
+                    let time = DateTime::<Utc>::from_timestamp(now as i64, 0).unwrap_or_default();
+                    let minute = time.hour() * 60 + time.minute();
+                    let scoped = categories.is_empty() || categories.iter().any(|c| c == category);
+                    (scoped && !hours.iter().any(|h| h.contains(minute)))
+                        .then(|| format!("{:02}:{:02} UTC outside trading hours", minute / 60, minute % 60))
+                }
+            };
+            RuleCheck { rule_id: rule.id.clone(), passed: failure.is_none(), detail: failure.unwrap_or_default() }
+        }).collect();
+        ComplianceDecision { checks }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn market(question: &str) -> Market {
+        Market { question: question.to_string(), ..testing::market() }
+    }
+
+    /// 2026-03-18 15:00 UTC: inside US hours
+    const OPEN: u64 = 1_773_846_000;
+
+    fn compliance() -> Compliance {
+        let config: ComplianceConfig = toml::from_str(r#"
+            enabled = true
+            jurisdiction = "us"
+
+            [[rules]]
+            kind = "blacklist"
+            id = "US-BLK-1"
+            jurisdictions = ["US"]
+            categories = ["politics"]
+            keywords = ["Assassination"]
+
+            [[rules]]
+            kind = "max_notional"
+            id = "CAP-SPORTS"
+            category = "sports"
+            max_notional = 20.0
+
+            [[rules]]
+            kind = "trading_hours"
+            id = "US-HRS"
+            jurisdictions = ["us"]
+            hours_utc = ["13:30-20:00"]
+
+            [[rules]]
+            kind = "blacklist"
+            id = "EU-BLK"
+            jurisdictions = ["eu"]
+            categories = ["crypto"]
+        "#).unwrap();
+        Compliance::from_config(&config).unwrap()
+    }
+
+    #[test]
+    fn test_rules_scoped_by_jurisdiction_and_traced() {
+        let compliance = compliance();
+        assert_eq!(compliance.rule_count(), 3);
+        let ok = compliance.evaluate(&market("Will BTC hit 100k?"), "crypto", 0.0, 10.0, OPEN);
+        assert!(ok.allowed());
+        assert_eq!(ok.trace(), "US-BLK-1 pass, CAP-SPORTS pass, US-HRS pass");
+    }
+
+    #[test]
+    fn test_blacklisted_keyword_blocks() {
+        let blocked = compliance().evaluate(&market("Assassination attempt by June?"), "other", 0.0, 10.0, OPEN);
+        assert_eq!(blocked.violations()[0].rule_id, "US-BLK-1");
+    }
+
+    #[test]
+    fn test_notional_capped_per_category() {
+        let compliance = compliance();
+        let capped = compliance.evaluate(&market("NBA finals"), "sports", 15.0, 10.0, OPEN);
+        assert_eq!(capped.violations()[0].rule_id, "CAP-SPORTS");
+        assert!(compliance.evaluate(&market("NBA finals"), "sports", 5.0, 10.0, OPEN).allowed());
+    }
+
+    #[test]
+    fn test_closed_outside_trading_hours() {
+        let closed = compliance().evaluate(&market("Will BTC hit 100k?"), "crypto", 0.0, 10.0, OPEN + 6 * 3600);
+        assert_eq!(closed.violations()[0].rule_id, "US-HRS");
+        assert!(closed.trace().contains("US-HRS FAIL (21:00 UTC outside trading hours)"));
+    }
+}
@@ -1,8 +1,16 @@
 //! Configuration module for ArbiShark
 //! 
 //! Loads settings from config.toml instead of hardcoded values.
+//!
+//! The file may define named profiles (`[profile.demo]`, `[profile.live]`)
+//! that override the top-level settings; a profile can also name another
+//! profile to inherit from with `inherits = "..."`. The profile is chosen
+//! with `--profile <name>` or `ARBISHARK_PROFILE`, so paper, sandbox and live
+//! setups share one file instead of drifting copies.
 
+use crate::risk::RiskConfig;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::fs;
 
 /// Root configuration structure
@@ -18,99 +26,1796 @@ pub struct Config {
     #[serde(default)]
     pub safety: SafetyConfig,
     #[serde(default)]
+    pub polling: PollingConfig,
+    #[serde(default)]
+    pub freshness: FreshnessConfig,
+    #[serde(default)]
+    pub grpc: GrpcConfig,
+    #[serde(default)]
+    pub dashboard: DashboardConfig,
+    #[serde(default)]
+    pub fleet: FleetConfig,
+    #[serde(default)]
+    pub storage: StorageConfig,
+    #[serde(default)]
+    pub health: HealthConfig,
+    #[serde(default)]
+    pub competition: CompetitionConfig,
+    #[serde(default)]
+    pub risk: RiskConfig,
+    #[serde(default)]
+    pub quorum: QuorumConfig,
+    #[serde(default)]
+    pub preflight: PreflightConfig,
+    /// Capital budget per strategy name (empty = no per-strategy limits)
+    #[serde(default)]
+    pub strategies: HashMap<String, StrategyBudgetConfig>,
+    #[serde(default)]
+    pub trading_windows: TradingWindowConfig,
+    #[serde(default)]
+    pub coordination: CoordinationConfig,
+    #[serde(default)]
+    pub sweep: SweepConfig,
+    #[serde(default)]
+    pub tax: TaxConfig,
+    #[serde(default)]
+    pub anomaly: AnomalyConfig,
+    #[serde(default)]
+    pub mirror: MirrorConfig,
+    #[serde(default)]
+    pub supervisor: SupervisorConfig,
+    #[serde(default)]
+    pub approval: ApprovalConfig,
+    #[serde(default)]
+    pub api_rate_limit: ApiRateLimitConfig,
+    #[serde(default)]
+    pub equity: EquityConfig,
+    #[serde(default)]
+    pub watchlist: WatchlistConfig,
+    #[serde(default)]
+    pub compliance: ComplianceConfig,
+    #[serde(default)]
+    pub external_signals: ExternalSignalConfig,
+    #[serde(default)]
+    pub treasury: TreasuryConfig,
+    #[serde(default)]
+    pub embeddings: EmbeddingConfig,
+    #[serde(default)]
+    pub holdings: HoldingsConfig,
+    #[serde(default)]
+    pub assets: AssetsConfig,
+    #[serde(default)]
+    pub routing: RoutingConfig,
+    #[serde(default)]
+    pub model_drift: ModelDriftConfig,
+    #[serde(default)]
+    pub decisions: DecisionsConfig,
+    #[serde(default)]
+    pub reconcile: ReconcileConfig,
+    #[serde(default)]
+    pub resolution: ResolutionConfig,
+    #[serde(default)]
+    pub volatility_response: VolatilityResponseConfig,
+    #[serde(default)]
+    pub universe_cache: UniverseCacheConfig,
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+    #[serde(default)]
+    pub edge_curve: EdgeCurveConfig,
+    #[serde(default)]
+    pub debug: DebugConfig,
+    #[serde(default)]
+    pub retention: RetentionConfig,
+    #[serde(default)]
+    pub object_store: ObjectStoreConfig,
+    #[serde(default)]
+    pub pacing: PacingConfig,
+    #[serde(default)]
+    pub fee_tiers: FeeTiersConfig,
+    #[serde(default)]
+    pub maker_taker: MakerTakerConfig,
+    #[serde(default)]
+    pub registry: RegistryConfig,
+    #[serde(default)]
+    pub intents: IntentConfig,
+    #[serde(default)]
+    pub venue_latency: VenueLatencyConfig,
+    #[serde(default)]
+    pub doctor: DoctorConfig,
+    #[serde(default)]
+    pub portfolio: PortfolioConfig,
+    #[serde(default)]
+    pub exit_optimizer: ExitOptimizerConfig,
+    #[serde(default)]
+    pub quoting: QuotingConfig,
+    #[serde(default)]
+    pub synthetic: SyntheticConfig,
+    #[serde(default)]
+    pub working_capital: WorkingCapitalConfig,
+    #[serde(default)]
+    pub remediation: RemediationConfig,
+    #[serde(default)]
+    pub leg_risk: LegRiskConfig,
+    #[serde(default)]
+    pub finality: FinalityConfig,
+    #[serde(default)]
+    pub allowance_fit: AllowanceFitConfig,
+    #[serde(default)]
+    pub slippage_guard: SlippageGuardConfig,
+    #[serde(default)]
+    pub annotations: AnnotationsConfig,
+    #[serde(default)]
+    pub order_rate: OrderRateConfig,
+    #[serde(default)]
+    pub rpc_pool: RpcPoolConfig,
+    #[serde(default)]
+    pub greeks: GreeksConfig,
+    #[serde(default)]
+    pub sandbox: SandboxConfig,
+    #[serde(default)]
     pub mode: Option<String>,
     #[serde(default)]
     pub arbitrum: Option<ArbitrumConfig>,
+    #[serde(default)]
+    pub signer: Option<SignerConfig>,
+    /// Profile the config was resolved with (None = top-level settings)
+    #[serde(skip)]
+    pub profile: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct PermissionConfig {
+    /// Daily spend limit in USD, converted to `token` at its USD price
+    pub daily_limit_usdc: f64,
+    pub duration_days: u32,
+    /// Collateral asset the permission is granted in (see `assets::ASSETS`)
+    pub token: String,
+    /// Contract addresses the agent may call (empty = unrestricted)
+    #[serde(default)]
+    pub allowed_targets: Vec<String>,
+    /// Method names / 4-byte selectors the agent may call (empty = unrestricted)
+    #[serde(default)]
+    pub allowed_methods: Vec<String>,
+    /// Maximum USD value of a single call
+    #[serde(default)]
+    pub max_per_tx_usdc: Option<f64>,
+}
+
+impl PermissionConfig {
+    /// Scope to enforce locally, mirroring the ERC-7715 grant (per-tx cap in collateral units)
+    pub fn scope(&self, collateral: &crate::assets::Collateral) -> crate::permission_guard::PermissionScope {
+        crate::permission_guard::PermissionScope {
+            allowed_targets: self.allowed_targets.clone(),
+            allowed_methods: self.allowed_methods.clone(),
+            max_per_tx: self.max_per_tx_usdc.map(|usd| collateral.from_usd(usd)),
+        }
+    }
+
+    /// The collateral asset `token` names, priced from `assets`
+    pub fn collateral(&self, assets: &AssetsConfig) -> Result<crate::assets::Collateral, String> {
+        crate::assets::Collateral::from_config(assets, &self.token)
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct TradingConfig {
+    pub min_spread_threshold: f64,
+    pub min_profit_threshold: f64,
+    pub trade_size: f64,
+    pub max_position_value: f64,
+    /// Partial fill remainder handling: "chase", "rest" or "abandon"
+    #[serde(default = "default_remainder_policy")]
+    pub remainder_policy: String,
+    /// Max price move (bps) tolerated when chasing a remainder
+    #[serde(default = "default_max_chase_bps")]
+    pub max_chase_bps: u32,
+    /// Good-til-time expiry of resting remainders in seconds (0 = good-til-cancelled)
+    #[serde(default = "default_max_rest_secs")]
+    pub max_rest_secs: u64,
+    /// Resting orders this close to expiry are re-quoted if the signal still
+    /// holds, cancelled otherwise
+    #[serde(default = "default_requote_window_secs")]
+    pub requote_window_secs: u64,
+    /// Concurrently open bundles allowed (0 = unlimited)
+    #[serde(default)]
+    pub max_open_bundles: usize,
+    /// New bundle entries allowed per rolling minute / hour (0 = unlimited)
+    #[serde(default)]
+    pub max_entries_per_minute: usize,
+    #[serde(default)]
+    pub max_entries_per_hour: usize,
+    /// Estimated settlement gas per leg in USD, used by cost previews
+    #[serde(default = "default_gas_per_leg_usd")]
+    pub gas_per_leg_usd: f64,
+}
+
+fn default_remainder_policy() -> String {
+    "abandon".to_string()
+}
+
+fn default_max_chase_bps() -> u32 {
+    50
+}
+
+fn default_max_rest_secs() -> u64 {
+    300
+}
+
+fn default_requote_window_secs() -> u64 {
+    30
+}
+
+fn default_gas_per_leg_usd() -> f64 {
+    0.01
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct TimingConfig {
+    pub poll_interval_secs: u64,
+    pub position_timeout_secs: u64,
+    pub latency_base_ms: u64,
+    pub adverse_selection_std: f64,
+    /// Signals older than this are dropped before execution
+    #[serde(default = "default_signal_ttl_ms")]
+    pub signal_ttl_ms: u64,
+}
+
+fn default_signal_ttl_ms() -> u64 {
+    3000
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ApiConfig {
+    pub gamma_url: String,
+    pub clob_url: String,
+    pub websocket_url: String,
+    pub market_limit: u32,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct LoggingConfig {
+    pub level: String,
+    pub colorize: bool,
+}
+
+/// Strategy configuration for adaptive trading
+#[derive(Debug, Deserialize, Clone)]
+pub struct StrategyConfig {
+    /// Threshold for conservative mode (below this %)
+    pub conservative_threshold: f64,
+    /// Threshold for aggressive mode (above this %)
+    pub aggressive_threshold: f64,
+    /// Minimum edge required in conservative mode
+    pub conservative_min_edge: f64,
+    /// Minimum edge required in normal mode
+    pub normal_min_edge: f64,
+    /// Minimum edge required in aggressive mode
+    pub aggressive_min_edge: f64,
+    /// Edge / size parameters per market regime
+    #[serde(default)]
+    pub regimes: RegimeProfiles,
+}
+
+impl Default for StrategyConfig {
+    fn default() -> Self {
+        Self {
+            conservative_threshold: 0.30,
+            aggressive_threshold: 0.70,
+            conservative_min_edge: 0.05,
+            normal_min_edge: 0.02,
+            aggressive_min_edge: 0.01,
+            regimes: RegimeProfiles::default(),
+        }
+    }
+}
+
+/// Hours and calendar exceptions during which new trades may open
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct TradingWindowConfig {
+    pub enabled: bool,
+    /// UTC ranges like "13:00-01:00" (empty = all day)
+    #[serde(default)]
+    pub hours_utc: Vec<String>,
+    /// TOML file of `[[blackout]]` exceptions per category or market
+    #[serde(default)]
+    pub calendar_file: Option<String>,
+}
+
+/// Virtual sub-account of one strategy
+#[derive(Debug, Deserialize, Clone)]
+pub struct StrategyBudgetConfig {
+    /// Capital ($) the strategy may have deployed at once
+    pub capital: f64,
+    /// Disable the strategy after losing this much ($)
+    #[serde(default)]
+    pub max_loss: Option<f64>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Trading parameters for one market regime
+#[derive(Debug, Deserialize, Clone)]
+pub struct RegimeParams {
+    /// Minimum edge required to trade
+    pub min_edge: f64,
+    /// Multiplier applied to `trading.trade_size`
+    pub size_multiplier: f64,
+}
+
+/// Parameters for each market regime (calm / trending / event-spike)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct RegimeProfiles {
+    pub calm: RegimeParams,
+    pub trending: RegimeParams,
+    pub event_spike: RegimeParams,
+}
+
+impl Default for RegimeProfiles {
+    fn default() -> Self {
+        Self {
+            calm: RegimeParams { min_edge: 0.02, size_multiplier: 1.0 },
+            trending: RegimeParams { min_edge: 0.03, size_multiplier: 0.75 },
+            event_spike: RegimeParams { min_edge: 0.06, size_multiplier: 0.25 },
+        }
+    }
+}
+
+/// Safety configuration for failure handling
+#[derive(Debug, Deserialize, Clone)]
+pub struct SafetyConfig {
+    /// Maximum data delay (ms) before suspending trading
+    pub max_data_delay_ms: u64,
+    /// Maximum consecutive API failures before safe mode
+    pub max_consecutive_failures: u32,
+    /// Cooldown period (seconds) in safe mode
+    pub safe_mode_cooldown_secs: u64,
+    /// Assume zero allowance if permission query fails
+    pub assume_zero_on_perm_error: bool,
+    /// Heartbeat poll interval (seconds) while the daily allowance is exhausted
+    #[serde(default = "default_exhausted_heartbeat_secs")]
+    pub exhausted_heartbeat_secs: u64,
+}
+
+fn default_exhausted_heartbeat_secs() -> u64 {
+    60
+}
+
+impl Default for SafetyConfig {
+    fn default() -> Self {
+        Self {
+            max_data_delay_ms: 5000,
+            max_consecutive_failures: 3,
+            safe_mode_cooldown_secs: 300,
+            assume_zero_on_perm_error: true,
+            exhausted_heartbeat_secs: default_exhausted_heartbeat_secs(),
+        }
+    }
+}
+
+/// Tiered polling configuration (market hotness)
+#[derive(Debug, Deserialize, Clone)]
+pub struct PollingConfig {
+    /// Poll interval (seconds) for hot markets
+    pub fast_interval_secs: u64,
+    /// Poll interval (seconds) for liquid or occasionally active markets
+    pub medium_interval_secs: u64,
+    /// Poll interval (seconds) for quiet markets
+    pub slow_interval_secs: u64,
+    /// Signals within the window required for the fast tier
+    pub hot_signal_count: u32,
+    /// Window (seconds) over which signal activity is counted
+    pub signal_window_secs: u64,
+    /// Minimum liquidity for the medium tier without recent signals
+    pub medium_liquidity: f64,
+    /// How often (seconds) tiers are recomputed
+    pub rebalance_interval_secs: u64,
+    /// Cap on due markets hydrated per tick, best spread/depth first (0 = no cap)
+    #[serde(default)]
+    pub max_scan_markets: usize,
+}
+
+impl Default for PollingConfig {
+    fn default() -> Self {
+        Self {
+            fast_interval_secs: 5,
+            medium_interval_secs: 30,
+            slow_interval_secs: 120,
+            hot_signal_count: 2,
+            signal_window_secs: 600,
+            medium_liquidity: 10000.0,
+            rebalance_interval_secs: 60,
+            max_scan_markets: 0,
+        }
+    }
+}
+
+/// Adaptive polling and edge haircut from indexer data delay
+#[derive(Debug, Deserialize, Clone)]
+pub struct FreshnessConfig {
+    pub enabled: bool,
+    /// Staleness (data delay + latency, ms) tolerated without any adjustment
+    pub grace_ms: u64,
+    /// Extra edge ($) required per second of staleness beyond the grace period
+    pub haircut_per_sec: f64,
+    /// Cap on the edge haircut ($)
+    pub max_haircut: f64,
+    /// Add one base poll interval per this much excess staleness (ms)
+    pub backoff_step_ms: u64,
+    /// Cap on the backed-off poll interval (seconds)
+    pub max_poll_interval_secs: u64,
+    /// Venue name → haircut overrides for quotes from that venue
+    #[serde(default)]
+    pub venues: HashMap<String, VenueFreshnessConfig>,
+}
+
+/// Quote staleness haircut for one venue; unset fields use the `[freshness]` values
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct VenueFreshnessConfig {
+    pub grace_ms: Option<u64>,
+    pub haircut_per_sec: Option<f64>,
+    pub max_haircut: Option<f64>,
+}
+
+impl Default for FreshnessConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            grace_ms: 1000,
+            haircut_per_sec: 0.005,
+            max_haircut: 0.05,
+            backoff_step_ms: 2000,
+            max_poll_interval_secs: 30,
+            venues: HashMap::new(),
+        }
+    }
+}
+
+/// gRPC API configuration (used with the `grpc` feature)
+#[derive(Debug, Deserialize, Clone)]
+#[cfg_attr(not(feature = "grpc"), allow(dead_code))]
+pub struct GrpcConfig {
+    /// Port for the tonic server
+    pub port: u16,
+}
+
+impl Default for GrpcConfig {
+    fn default() -> Self {
+        Self { port: 50051 }
+    }
+}
+
+/// Dashboard serving configuration
+#[derive(Debug, Deserialize, Clone)]
+pub struct DashboardConfig {
+    /// Serve dashboard files from this directory instead of the embedded bundle
+    pub dir: Option<String>,
+    /// Port of the API / dashboard server
+    #[serde(default = "default_api_port")]
+    pub port: u16,
+}
+
+fn default_api_port() -> u16 {
+    3030
+}
+
+impl Default for DashboardConfig {
+    fn default() -> Self {
+        Self { dir: None, port: default_api_port() }
+    }
+}
+
+/// Engine instances served by one API / dashboard
+#[derive(Debug, Deserialize, Clone)]
+pub struct FleetConfig {
+    /// This engine's agent ID (`/api/<agent_id>/...`, and the un-prefixed `/api/...`)
+    pub agent_id: String,
+    /// Engines in other processes, proxied under `/api/<id>/...`
+    pub agents: Vec<FleetAgent>,
+    /// Timeout for requests to remote agents
+    pub timeout_ms: u64,
+}
+
+impl Default for FleetConfig {
+    fn default() -> Self {
+        Self { agent_id: "default".to_string(), agents: Vec::new(), timeout_ms: 5000 }
+    }
+}
+
+/// A remote engine: its agent ID and API base URL
+#[derive(Debug, Deserialize, Clone)]
+pub struct FleetAgent {
+    pub id: String,
+    pub url: String,
+}
+
+/// Local storage configuration
+#[derive(Debug, Deserialize, Clone)]
+pub struct StorageConfig {
+    /// Directory for persisted data (event log, recordings, reports)
+    pub data_dir: String,
+    /// Persist the structured event log across restarts
+    pub persist_events: bool,
+    /// Record every fetched order book for replay and backtests
+    #[serde(default)]
+    pub record_books: bool,
+    /// Recording format: "jsonl" or "columnar" (zstd-compressed blocks)
+    #[serde(default = "default_record_format")]
+    pub record_format: String,
+}
+
+fn default_record_format() -> String {
+    "columnar".to_string()
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            data_dir: "data".to_string(),
+            persist_events: true,
+            record_books: false,
+            record_format: default_record_format(),
+        }
+    }
+}
+
+/// Health probe configuration
+#[derive(Debug, Deserialize, Clone)]
+pub struct HealthConfig {
+    /// Liveness fails if the main loop has not ticked within this many seconds
+    pub max_tick_age_secs: u64,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self { max_tick_age_secs: 120 }
+    }
+}
+
+/// Cross-check order books against a second, independent data source
+#[derive(Debug, Deserialize, Clone)]
+pub struct QuorumConfig {
+    /// Only trade books both sources agree on
+    pub enabled: bool,
+    /// Max absolute top-of-book price difference between sources
+    pub tolerance: f64,
+}
+
+impl Default for QuorumConfig {
+    fn default() -> Self {
+        Self { enabled: false, tolerance: 0.01 }
+    }
+}
+
+/// Single-leader lease per permission across instances sharing `storage.data_dir`
+#[derive(Debug, Deserialize, Clone)]
+pub struct CoordinationConfig {
+    pub enabled: bool,
+    /// Name of this instance (empty = hostname-pid)
+    pub instance_id: String,
+    /// A leader that stops renewing for this long is taken over
+    pub lease_ttl_secs: u64,
+}
+
+impl Default for CoordinationConfig {
+    fn default() -> Self {
+        Self { enabled: false, instance_id: String::new(), lease_ttl_secs: 30 }
+    }
+}
+
+/// Sweep realized profits above working capital to a cold address
+#[derive(Debug, Deserialize, Clone)]
+pub struct SweepConfig {
+    pub enabled: bool,
+    /// Destination of swept USDC
+    pub cold_address: String,
+    /// Sweep once this much realized PnL ($) has not been swept yet
+    pub pnl_threshold: f64,
+    /// USDC kept for trading; only the balance above it is swept
+    pub working_capital_target: f64,
+    /// A sweep not confirmed within this many seconds is failed and retried
+    pub confirm_timeout_secs: u64,
+}
+
+impl Default for SweepConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            cold_address: String::new(),
+            pnl_threshold: 25.0,
+            working_capital_target: 10.0,
+            confirm_timeout_secs: 600,
+        }
+    }
+}
+
+/// Tax lot accounting (`arbishark tax-report <year>`)
+#[derive(Debug, Deserialize, Clone)]
+pub struct TaxConfig {
+    /// Journal acquired / disposed lots to `storage.data_dir`
+    pub enabled: bool,
+    /// Lot matching: "fifo" or "lifo"
+    pub lot_method: String,
+}
+
+impl Default for TaxConfig {
+    fn default() -> Self {
+        Self { enabled: true, lot_method: "fifo".to_string() }
+    }
+}
+
+/// Self-monitoring: safe mode when the agent's own behavior looks wrong
+#[derive(Debug, Deserialize, Clone)]
+pub struct AnomalyConfig {
+    pub enabled: bool,
+    /// Window (seconds) recent entries and spend are measured over
+    pub rate_window_secs: u64,
+    /// Window (seconds) the entry-rate baseline is taken from
+    pub baseline_window_secs: u64,
+    /// Flag when recent entries exceed this multiple of the baseline
+    pub trade_rate_multiple: f64,
+    /// Baseline floor (entries per window) so a quiet history cannot flag the first trades
+    pub min_baseline_entries: f64,
+    /// Number of most recent fills compared against their signal prices
+    pub fill_sample: usize,
+    /// Flag when those fills average this much (bps) worse than the signal
+    pub max_adverse_fill_bps: f64,
+    /// Spends in the window required before projecting exhaustion
+    pub min_spends: usize,
+    /// Flag when the remaining allowance would be gone sooner than this (seconds)
+    pub min_secs_to_exhaustion: u64,
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            rate_window_secs: 300,
+            baseline_window_secs: 3600,
+            trade_rate_multiple: 5.0,
+            min_baseline_entries: 1.0,
+            fill_sample: 5,
+            max_adverse_fill_bps: 100.0,
+            min_spends: 3,
+            min_secs_to_exhaustion: 600,
+        }
+    }
+}
+
+/// Relative-value signals across near-identical markets
+#[derive(Debug, Deserialize, Clone)]
+pub struct MirrorConfig {
+    pub enabled: bool,
+    /// Question similarity (Jaccard over normalized words) to treat two markets as mirrors
+    pub min_similarity: f64,
+    /// Minimum profit per unit of YES(cheap) + NO(rich) to emit a signal
+    pub min_edge: f64,
+    /// Pair markets whose resolution source is unknown
+    pub allow_unknown_source: bool,
+}
+
+impl Default for MirrorConfig {
+    fn default() -> Self {
+        Self { enabled: true, min_similarity: 0.8, min_edge: 0.02, allow_unknown_source: false }
+    }
+}
+
+/// Heartbeat watchdog over the engine loop and server tasks
+#[derive(Debug, Deserialize, Clone)]
+pub struct SupervisorConfig {
+    pub enabled: bool,
+    /// A task whose heartbeat is older than this is cancelled and restarted
+    pub stall_timeout_secs: u64,
+    /// How often heartbeats are checked (and server listeners probed)
+    pub check_interval_secs: u64,
+    /// Delay before a cancelled, exited or panicked task is restarted
+    pub restart_backoff_secs: u64,
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self { enabled: true, stall_timeout_secs: 120, check_interval_secs: 5, restart_backoff_secs: 5 }
+    }
+}
+
+/// Human approval of large executions
+#[derive(Debug, Deserialize, Clone)]
+pub struct ApprovalConfig {
+    pub enabled: bool,
+    /// Bundles above this notional (USDC) wait for approval
+    pub notional_threshold: f64,
+    /// Undecided requests expire after this many seconds
+    pub ttl_secs: u64,
+}
+
+impl Default for ApprovalConfig {
+    fn default() -> Self {
+        Self { enabled: false, notional_threshold: 5.0, ttl_secs: 300 }
+    }
+}
+
+/// Per-client rate limit on API control actions (POST endpoints)
+#[derive(Debug, Deserialize, Clone)]
+pub struct ApiRateLimitConfig {
+    pub enabled: bool,
+    /// Control actions a client may send back to back
+    pub burst: u32,
+    /// Tokens regained per second
+    pub refill_per_sec: f64,
+}
+
+impl Default for ApiRateLimitConfig {
+    fn default() -> Self {
+        Self { enabled: true, burst: 10, refill_per_sec: 0.5 }
+    }
+}
+
+/// Equity curve sampling (`/api/equity`)
+#[derive(Debug, Deserialize, Clone)]
+pub struct EquityConfig {
+    /// Seconds between persisted equity samples
+    pub sample_interval_secs: u64,
+}
+
+impl Default for EquityConfig {
+    fn default() -> Self {
+        Self { sample_interval_secs: 300 }
+    }
+}
+
+/// Dashboard watchlist (`/api/watchlist`)
+#[derive(Debug, Deserialize, Clone)]
+pub struct WatchlistConfig {
+    /// Max pinned markets (0 = unlimited); each is scanned every tick
+    pub max_markets: usize,
+}
+
+impl Default for WatchlistConfig {
+    fn default() -> Self {
+        Self { max_markets: 20 }
+    }
+}
+
+/// Pre-trade compliance rules of one jurisdiction
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ComplianceConfig {
+    pub enabled: bool,
+    /// Rules listing other jurisdictions are ignored
+    #[serde(default)]
+    pub jurisdiction: String,
+    #[serde(default)]
+    pub rules: Vec<ComplianceRuleConfig>,
+}
+
+/// One `[[compliance.rules]]` entry; `jurisdictions` empty = every jurisdiction
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ComplianceRuleConfig {
+    /// No markets in these categories or whose question mentions a keyword
+    Blacklist {
+        id: String,
+        #[serde(default)]
+        jurisdictions: Vec<String>,
+        #[serde(default)]
+        categories: Vec<String>,
+        #[serde(default)]
+        keywords: Vec<String>,
+    },
+    /// Max notional ($) per market of `category` (every category if unset)
+    MaxNotional {
+        id: String,
+        #[serde(default)]
+        jurisdictions: Vec<String>,
+        #[serde(default)]
+        category: Option<String>,
+        max_notional: f64,
+    },
+    /// UTC ranges like "13:30-20:00" for `categories` (empty = all)
+    TradingHours {
+        id: String,
+        #[serde(default)]
+        jurisdictions: Vec<String>,
+        hours_utc: Vec<String>,
+        #[serde(default)]
+        categories: Vec<String>,
+    },
+}
+
+/// Externally generated signals (`POST /api/signals/external`)
+#[derive(Debug, Deserialize, Clone)]
+pub struct ExternalSignalConfig {
+    pub enabled: bool,
+    /// Environment variable holding the bearer token callers must present
+    pub token_env: String,
+    /// Submissions waiting for the engine before new ones are refused
+    pub max_pending: usize,
+}
+
+impl Default for ExternalSignalConfig {
+    fn default() -> Self {
+        Self { enabled: false, token_env: "ARBISHARK_SIGNAL_TOKEN".to_string(), max_pending: 20 }
+    }
+}
+
+/// Idle USDC supplied to a whitelisted yield venue between trades
+#[derive(Debug, Deserialize, Clone)]
+pub struct TreasuryConfig {
+    pub enabled: bool,
+    /// Whitelisted venue name (see `treasury::WHITELISTED_VENUES`)
+    pub venue: String,
+    /// USDC always kept liquid for trading
+    pub liquid_buffer: f64,
+    /// Deposit only after this long without a trade
+    pub idle_secs: u64,
+    /// Smallest and largest single deposit / withdrawal ($)
+    pub min_move: f64,
+    pub max_move: f64,
+    /// Cap on USDC deposited at once
+    pub max_deposited: f64,
+}
+
+impl Default for TreasuryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            venue: "aave-v3-polygon".to_string(),
+            liquid_buffer: 20.0,
+            idle_secs: 900,
+            min_move: 5.0,
+            max_move: 50.0,
+            max_deposited: 100.0,
+        }
+    }
+}
+
+/// Market question embeddings for semantic grouping
+#[derive(Debug, Deserialize, Clone)]
+pub struct EmbeddingConfig {
+    pub enabled: bool,
+    /// "hashing" (local, offline) or "http" (OpenAI-compatible embedding API)
+    pub provider: String,
+    pub url: String,
+    pub model: String,
+    /// Environment variable holding the HTTP API key
+    pub api_key_env: String,
+    /// Vector size of the hashing embedder
+    pub dims: usize,
+    /// Questions per embedding request
+    pub batch_size: usize,
+    /// LSH tables and hyperplanes per table (more tables = better recall)
+    pub lsh_tables: usize,
+    pub lsh_bits: usize,
+    /// Cosine similarity to count as related
+    pub min_similarity: f64,
+    /// Related markets returned per market
+    pub neighbors: usize,
+}
+
+impl Default for EmbeddingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: "hashing".to_string(),
+            url: "https://api.openai.com/v1/embeddings".to_string(),
+            model: "text-embedding-3-small".to_string(),
+            api_key_env: "OPENAI_API_KEY".to_string(),
+            dims: 256,
+            batch_size: 64,
+            lsh_tables: 8,
+            lsh_bits: 8,
+            min_similarity: 0.8,
+            neighbors: 5,
+        }
+    }
+}
+
+/// Model vs reality drift monitor over executed legs
+#[derive(Debug, Deserialize, Clone)]
+pub struct ModelDriftConfig {
+    pub enabled: bool,
+    /// Most recent fills the mean errors are taken over
+    pub window: usize,
+    /// Fills (or exited legs, for edge) required before alerting
+    pub min_samples: usize,
+    /// Bounds on the mean signed error of each metric
+    pub max_price_error_bps: f64,
+    pub max_fee_error_bps: f64,
+    pub max_slippage_error_bps: f64,
+    /// $ per unit
+    pub max_edge_error: f64,
+    pub alert_cooldown_secs: u64,
+    /// Enter safe mode (no new trades) on a drift alert
+    pub safe_mode_on_breach: bool,
+}
+
+impl Default for ModelDriftConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window: 50,
+            min_samples: 10,
+            max_price_error_bps: 50.0,
+            max_fee_error_bps: 20.0,
+            max_slippage_error_bps: 50.0,
+            max_edge_error: 0.02,
+            alert_cooldown_secs: 3600,
+            safe_mode_on_breach: false,
+        }
+    }
+}
+
+/// Per-scan decision records (features + labels) for training signal filters
+#[derive(Debug, Deserialize, Clone)]
+pub struct DecisionsConfig {
+    pub enabled: bool,
+    /// Label each scan with the bundle deviation this many seconds later
+    pub horizons_secs: Vec<u64>,
+}
+
+impl Default for DecisionsConfig {
+    fn default() -> Self {
+        Self { enabled: false, horizons_secs: vec![30, 120, 600] }
+    }
+}
+
+/// Orders / fills reconciliation against the venue (`GET /api/reconcile`)
+#[derive(Debug, Deserialize, Clone)]
+pub struct ReconcileConfig {
+    /// Window reconciled when the query gives no `from`
+    pub window_secs: u64,
+    /// Shares
+    pub size_tolerance: f64,
+    /// Price units ($ per share)
+    pub price_tolerance: f64,
+}
+
+impl Default for ReconcileConfig {
+    fn default() -> Self {
+        Self { window_secs: 86_400, size_tolerance: 0.01, price_tolerance: 0.001 }
+    }
+}
+
+/// Position greeks and the exits they drive (`greeks` module)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct GreeksConfig {
+    /// Close positions on the greeks below
+    pub exit_on_decay: bool,
+    /// Complete sets earning less than this per day left until resolution are sold ($ per set)
+    pub min_edge_per_day: f64,
+    /// Shares beyond a market's complete sets are closed this close to its end date
+    pub unhedged_exit_secs: u64,
+}
+
+impl Default for GreeksConfig {
+    fn default() -> Self {
+        Self { exit_on_decay: false, min_edge_per_day: 0.0, unhedged_exit_secs: 3600 }
+    }
+}
+
+/// JSON-RPC providers per EVM chain, with failover (`rpc_pool` module)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct RpcPoolConfig {
+    /// Chain id → providers tried after the URL of the section using the chain
+    pub providers: HashMap<String, Vec<String>>,
+    /// Providers that must agree on a critical read (balances, allowances)
+    pub cross_check: usize,
+    /// A provider that failed is tried again only after this long
+    pub failure_cooldown_secs: u64,
+    pub timeout_ms: u64,
+}
+
+impl Default for RpcPoolConfig {
+    fn default() -> Self {
+        Self { providers: HashMap::new(), cross_check: 2, failure_cooldown_secs: 30, timeout_ms: 5000 }
+    }
+}
+
+/// Order / cancel rate budgets at the venue (`order_limits` module)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct OrderRateConfig {
+    pub enabled: bool,
+    /// Share of every limit the bot allows itself, leaving room for other clients of the key
+    pub utilization: f64,
+    /// Order placements per API key (CLOB `POST /order`)
+    pub place: Vec<RateWindowConfig>,
+    /// Cancels per API key (CLOB `DELETE /order`)
+    pub cancel: Vec<RateWindowConfig>,
+    /// Placements per market
+    pub market_place: Vec<RateWindowConfig>,
+    /// Cancels per market
+    pub market_cancel: Vec<RateWindowConfig>,
+}
+
+/// At most `limit` actions in any `window_secs`
+#[derive(Debug, Deserialize, Clone)]
+pub struct RateWindowConfig {
+    pub limit: u32,
+    pub window_secs: u64,
+}
+
+impl Default for OrderRateConfig {
+    fn default() -> Self {
+        // Polymarket CLOB: 2400 per 10s burst, 24000 per 10 minutes sustained
+        let venue = || vec![RateWindowConfig { limit: 2_400, window_secs: 10 }, RateWindowConfig { limit: 24_000, window_secs: 600 }];
+        let market = || vec![RateWindowConfig { limit: 30, window_secs: 10 }, RateWindowConfig { limit: 600, window_secs: 600 }];
+        Self {
+            enabled: true,
+            utilization: 0.8,
+            place: venue(),
+            cancel: venue(),
+            market_place: market(),
+            market_cancel: market(),
+        }
+    }
+}
+
+/// Tagged market event windows (`annotations` module)
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct AnnotationsConfig {
+    pub events: Vec<EventWindowConfig>,
+}
+
+/// One `[[annotations.events]]` window
+#[derive(Debug, Deserialize, Clone)]
+pub struct EventWindowConfig {
+    pub tag: String,
+    /// Unix seconds, inclusive
+    pub start: u64,
+    pub end: u64,
+    /// Markets it applies to (empty = every market)
+    #[serde(default)]
+    pub markets: Vec<String>,
+    /// Categories it applies to: crypto, politics, economics, sports or other (empty = every category)
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub note: String,
+}
+
+/// Limit price ceilings on bundle legs (`slippage_guard` module)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct SlippageGuardConfig {
+    /// Edge per set still kept if every leg fills at its ceiling ($)
+    pub min_edge: f64,
+}
+
+impl Default for SlippageGuardConfig {
+    fn default() -> Self {
+        Self { min_edge: 0.0 }
+    }
+}
+
+/// Downsizing a bundle to the allowance left instead of skipping it (`sizing` module)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct AllowanceFitConfig {
+    pub enabled: bool,
+    /// Fraction added to the priced cost of a set, for latency and slippage between sizing and the fills
+    pub headroom: f64,
+}
+
+impl Default for AllowanceFitConfig {
+    fn default() -> Self {
+        Self { enabled: false, headroom: 0.02 }
+    }
+}
+
+/// Settlement finality of submitted transactions (`finality` module)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct FinalityConfig {
+    pub enabled: bool,
+    /// Chain id → confirmations before a settlement is final
+    pub confirmations: HashMap<String, u64>,
+    /// Confirmations on chains not listed
+    pub default_confirmations: u64,
+    /// Chain id → RPC receipts are read from, ahead of the chain's other providers in `[rpc_pool]`
+    pub rpc_urls: HashMap<String, String>,
+    pub poll_secs: u64,
+    /// Not mined this long after submission (or after a reorg took it out) = dropped
+    pub drop_after_secs: u64,
+    pub timeout_ms: u64,
+}
+
+impl Default for FinalityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            confirmations: HashMap::from([("137".to_string(), 128), ("80002".to_string(), 32)]),
+            default_confirmations: 12,
+            rpc_urls: HashMap::new(),
+            poll_secs: 15,
+            drop_after_secs: 600,
+            timeout_ms: 5000,
+        }
+    }
+}
+
+/// Worst-case partial-fill check before a bundle executes (`leg_risk` module)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct LegRiskConfig {
+    pub enabled: bool,
+    /// Cap on the loss of the worst partial fill after its remedy ($)
+    pub max_loss_usd: f64,
+    /// How far the missing legs' asks are assumed to gap up
+    pub gap: f64,
+    /// Skip rather than downsize below this size per leg
+    pub min_size: f64,
+}
+
+impl Default for LegRiskConfig {
+    fn default() -> Self {
+        Self { enabled: false, max_loss_usd: 2.0, gap: 0.05, min_size: 1.0 }
+    }
+}
+
+/// What to do about each class of execution failure (`remediation` module)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct RemediationConfig {
+    pub enabled: bool,
+    /// Retries / reprices of one leg before it is skipped
+    pub max_retries: u32,
+    /// Wait before the first retry of a leg, doubled on each further one (ms)
+    pub retry_backoff_ms: u64,
+    /// Longest wait between retries (ms)
+    pub max_backoff_ms: u64,
+    /// Failure class → "retry" | "reprice" | "unwind" | "halt" | "skip" (unlisted classes keep their default)
+    pub policies: HashMap<String, String>,
+}
+
+impl Default for RemediationConfig {
+    fn default() -> Self {
+        Self { enabled: false, max_retries: 2, retry_backoff_ms: 250, max_backoff_ms: 4_000, policies: HashMap::new() }
+    }
+}
+
+/// Balance actually free to trade or sweep (`working_capital` module)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct WorkingCapitalConfig {
+    /// Cap entry sizes and sweeps at the free balance
+    pub enabled: bool,
+    /// Kept back on top of everything committed ($)
+    pub buffer_usd: f64,
+    /// A bundle's reservation lapses after this long if never released
+    pub in_flight_ttl_secs: u64,
+}
+
+impl Default for WorkingCapitalConfig {
+    fn default() -> Self {
+        Self { enabled: true, buffer_usd: 0.0, in_flight_ttl_secs: 120 }
+    }
+}
+
+/// Signals from declared relationships between markets (`synthetic` module)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct SyntheticConfig {
+    pub enabled: bool,
+    /// `[[relationship]]` tables
+    pub relationships_file: String,
+    /// Smallest edge per unit worth a signal
+    pub min_edge: f64,
+}
+
+impl Default for SyntheticConfig {
+    fn default() -> Self {
+        Self { enabled: false, relationships_file: "relationships.toml".to_string(), min_edge: 0.02 }
+    }
+}
+
+/// Anti-flicker rules for resting quotes (`quoting` module)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct QuotingConfig {
+    pub enabled: bool,
+    /// A quote rests at least this long before it may be cancelled or replaced
+    pub min_rest_secs: u64,
+    /// Replacements per market per minute (0 = unlimited)
+    pub max_requotes_per_min: usize,
+    /// Modifications for a market are released together this long after the first
+    pub batch_window_secs: u64,
+}
+
+impl Default for QuotingConfig {
+    fn default() -> Self {
+        Self { enabled: false, min_rest_secs: 5, max_requotes_per_min: 6, batch_window_secs: 2 }
+    }
 }
 
+/// Exit by selling the leg or completing the bundle, whichever is cheaper (`exit_optimizer` module)
 #[derive(Debug, Deserialize, Clone)]
-pub struct PermissionConfig {
-    pub daily_limit_usdc: f64,
-    pub duration_days: u32,
-    pub token: String,
+#[serde(default)]
+pub struct ExitOptimizerConfig {
+    pub enabled: bool,
+    /// Gas of merging a complete set back into collateral ($)
+    pub merge_cost_usd: f64,
+    /// Completing the bundle must beat selling by this much ($)
+    pub min_improvement_usd: f64,
+}
+
+impl Default for ExitOptimizerConfig {
+    fn default() -> Self {
+        Self { enabled: false, merge_cost_usd: 0.05, min_improvement_usd: 0.01 }
+    }
 }
 
+/// Cross-chain portfolio view and global limits (`portfolio` module)
 #[derive(Debug, Deserialize, Clone)]
-pub struct TradingConfig {
-    pub min_spread_threshold: f64,
-    pub min_profit_threshold: f64,
-    pub trade_size: f64,
-    pub max_position_value: f64,
+#[serde(default)]
+pub struct PortfolioConfig {
+    /// Enforce the global limits (the view is always served)
+    pub enabled: bool,
+    /// Venue name, as in the mapping file → chain it settles on
+    pub venue_chains: HashMap<String, String>,
+    /// Chain → collateral symbol, priced via `[assets]`; the primary chain uses the permission's
+    pub chain_collateral: HashMap<String, String>,
+    /// How often balances on other chains are read
+    pub balance_refresh_secs: u64,
+    /// Cap on open notional summed over every chain ($)
+    pub max_open_notional_usd: Option<f64>,
+    /// No new entries while unrealized losses over every chain exceed this ($)
+    pub max_unrealized_loss_usd: Option<f64>,
 }
 
+impl Default for PortfolioConfig {
+    fn default() -> Self {
+        let pairs = |p: &[(&str, &str)]| p.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        Self {
+            enabled: false,
+            venue_chains: pairs(&[("polymarket", "polygon"), ("sandbox", "polygon"), ("envio", "arbitrum")]),
+            chain_collateral: pairs(&[("polygon", "USDC.e"), ("arbitrum", "USDC"), ("solana", "SOL")]),
+            balance_refresh_secs: 60,
+            max_open_notional_usd: None,
+            max_unrealized_loss_usd: None,
+        }
+    }
+}
+
+/// `arbishark doctor` self-test (`doctor` module)
 #[derive(Debug, Deserialize, Clone)]
-pub struct TimingConfig {
-    pub poll_interval_secs: u64,
-    pub position_timeout_secs: u64,
-    pub latency_base_ms: u64,
-    pub adverse_selection_std: f64,
+#[serde(default)]
+pub struct DoctorConfig {
+    /// Per request
+    pub timeout_ms: u64,
+    /// Slower round trips warn
+    pub max_latency_ms: u64,
+    /// Live markets priced for the dry signal scan
+    pub sample_markets: usize,
+}
+
+impl Default for DoctorConfig {
+    fn default() -> Self {
+        Self { timeout_ms: 5000, max_latency_ms: 1500, sample_markets: 5 }
+    }
 }
 
+/// Per-venue round-trip latency, used to route time-critical legs (`venue_latency` module)
 #[derive(Debug, Deserialize, Clone)]
-pub struct ApiConfig {
+#[serde(default)]
+pub struct VenueLatencyConfig {
+    pub enabled: bool,
+    /// Weight of the newest sample in the smoothed round trip
+    pub smoothing: f64,
+    /// Probe a venue not heard from for this long
+    pub probe_interval_secs: u64,
+    /// Older estimates count as unknown
+    pub max_age_secs: u64,
+    /// Largest price per share given up to reach a faster venue
+    pub price_tolerance: f64,
+}
+
+impl Default for VenueLatencyConfig {
+    fn default() -> Self {
+        Self { enabled: false, smoothing: 0.3, probe_interval_secs: 30, max_age_secs: 300, price_tolerance: 0.005 }
+    }
+}
+
+/// Trade intents published for external co-signing (`intents` module)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct IntentConfig {
+    /// Propose intents instead of executing legs
+    pub enabled: bool,
+    /// Environment variable holding the bearer token the executing side presents
+    pub token_env: String,
+    /// How long an intent may be acted on
+    pub ttl_secs: u64,
+    /// Price cap over the executable price, as a fraction of it
+    pub price_slack: f64,
+    /// POSTed every new intent
+    pub webhook_url: Option<String>,
+    /// Addresses allowed to co-sign (empty = any valid signature)
+    pub co_signers: Vec<String>,
+    /// Chain id in the EIP-712 domain
+    pub chain_id: u64,
+}
+
+impl Default for IntentConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            token_env: "ARBISHARK_INTENT_TOKEN".to_string(),
+            ttl_secs: 120,
+            price_slack: 0.01,
+            webhook_url: None,
+            co_signers: Vec::new(),
+            chain_id: 137,
+        }
+    }
+}
+
+/// Contract address book (`registry` module)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct RegistryConfig {
+    /// Chain id → contract name (usdc, ctf, exchange, stylus) → address, over the built-ins
+    pub overrides: HashMap<String, HashMap<String, String>>,
+    /// Ask the RPC endpoints in use for their chain id at startup
+    pub check_chain_ids: bool,
+    pub timeout_ms: u64,
+}
+
+impl Default for RegistryConfig {
+    fn default() -> Self {
+        Self { overrides: HashMap::new(), check_chain_ids: true, timeout_ms: 3000 }
+    }
+}
+
+/// Per-leg choice between crossing the spread and posting at the touch
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct MakerTakerConfig {
+    pub enabled: bool,
+    /// How long a posted leg may take to fill
+    pub horizon_secs: u64,
+    /// Trade prints the opposing flow rate is measured over
+    pub flow_lookback_secs: u64,
+    /// Below this chance of filling within the horizon the leg crosses
+    pub min_fill_probability: f64,
+    /// Per-share edge at or above which the leg crosses rather than risk missing it
+    pub take_edge: f64,
+    /// Share of the bundle's other legs already filled at which the leg crosses
+    pub max_urgency: f64,
+    pub tick: f64,
+    /// Post one tick inside the touch when the spread allows, instead of joining it
+    pub post_inside: bool,
+}
+
+impl Default for MakerTakerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            horizon_secs: 30,
+            flow_lookback_secs: 300,
+            min_fill_probability: 0.5,
+            take_edge: 0.02,
+            max_urgency: 0.5,
+            tick: 0.01,
+            post_inside: true,
+        }
+    }
+}
+
+/// Venue fee tiers by monthly volume
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct FeeTiersConfig {
+    pub enabled: bool,
+    pub tiers: Vec<FeeTierConfig>,
+}
+
+/// A fee tier, reached once the month's maker + taker volume (USDC) is at least `min_volume`
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct FeeTierConfig {
+    pub min_volume: f64,
+    pub maker_fee_bps: u32,
+    pub taker_fee_bps: u32,
+}
+
+impl Default for FeeTiersConfig {
+    fn default() -> Self {
+        let tier = |min_volume, taker_fee_bps| FeeTierConfig { min_volume, maker_fee_bps: 0, taker_fee_bps };
+        Self { enabled: false, tiers: vec![tier(0.0, 200), tier(100_000.0, 150), tier(1_000_000.0, 100)] }
+    }
+}
+
+/// Rate-of-spend pacing over the allowance period
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct PacingConfig {
+    pub enabled: bool,
+    /// Rolling window the share applies to
+    pub window_secs: u64,
+    /// Minimum share (0-1) of the allowance left at the start of a window that may be spent in it
+    pub max_share: f64,
+}
+
+impl Default for PacingConfig {
+    fn default() -> Self {
+        Self { enabled: false, window_secs: 3600, max_share: 0.25 }
+    }
+}
+
+/// S3-compatible bucket for recordings, reports, exports and archives
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct ObjectStoreConfig {
+    pub enabled: bool,
+    /// e.g. `https://s3.us-east-1.amazonaws.com` or a MinIO URL (env `ARBISHARK_S3_ENDPOINT` overrides)
+    pub endpoint: String,
+    /// Env `ARBISHARK_S3_BUCKET` overrides
+    pub bucket: String,
+    /// Env `ARBISHARK_S3_REGION` overrides
+    pub region: String,
+    /// Key prefix of everything this agent uploads (env `ARBISHARK_S3_PREFIX` overrides)
+    pub prefix: String,
+    pub access_key_env: String,
+    pub secret_key_env: String,
+    /// Upload rotated recording segments (`<data_dir>/segments`, needs `[retention]`)
+    pub recordings: bool,
+    /// Upload daily reports (`<data_dir>/reports`)
+    pub reports: bool,
+    /// Upload exports (`<data_dir>/exports` and the outputs of export commands)
+    pub exports: bool,
+    /// Upload pass interval
+    pub sync_secs: u64,
+}
+
+impl Default for ObjectStoreConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: String::new(),
+            bucket: String::new(),
+            region: "us-east-1".to_string(),
+            prefix: "arbishark/".to_string(),
+            access_key_env: "AWS_ACCESS_KEY_ID".to_string(),
+            secret_key_env: "AWS_SECRET_ACCESS_KEY".to_string(),
+            recordings: true,
+            reports: true,
+            exports: true,
+            sync_secs: 600,
+        }
+    }
+}
+
+/// Rotation, archival and expiry of recordings and time-series journals
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct RetentionConfig {
+    pub enabled: bool,
+    /// Files under `data_dir` subject to retention (never state journals)
+    pub files: Vec<String>,
+    /// Hot files are closed into a dated segment this often
+    pub rotate_secs: u64,
+    /// Segments stay uncompressed under `data_dir/segments` this long
+    pub hot_days: u64,
+    /// Older segments are zstd-compressed here
+    pub archive_dir: String,
+    /// Archives are deleted (locally and in the bucket) after this long; 0 = never
+    pub delete_after_days: u64,
+    /// Retention pass interval
+    pub check_secs: u64,
+    /// Keep the local archive after a successful upload to `[object_store]`
+    pub keep_local: bool,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            files: vec!["books.abk".to_string(), "books.jsonl".to_string(), "events.jsonl".to_string()],
+            rotate_secs: 86_400,
+            hot_days: 7,
+            archive_dir: "data/archive".to_string(),
+            delete_after_days: 90,
+            check_secs: 3_600,
+            keep_local: true,
+        }
+    }
+}
+
+/// Diagnostics endpoints
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct DebugConfig {
+    /// Serve `/api/debug/perf` (component timings, runtime metrics, cache sizes, channel backlogs)
+    pub perf: bool,
+}
+
+/// Size-dependent edge: walk the books and size at the profit-maximizing point
+#[derive(Debug, Deserialize, Clone)]
+pub struct EdgeCurveConfig {
+    pub enabled: bool,
+    /// Sizes sampled between $1 and the risk-scaled trade size
+    pub steps: usize,
+}
+
+impl Default for EdgeCurveConfig {
+    fn default() -> Self {
+        Self { enabled: true, steps: 8 }
+    }
+}
+
+/// Venue status page and error-rate monitoring
+#[derive(Debug, Deserialize, Clone)]
+pub struct MaintenanceConfig {
+    pub enabled: bool,
+    /// Statuspage `summary.json` of the venue (empty = error-rate heuristic only)
+    pub status_url: String,
+    pub poll_secs: u64,
+    /// Stop trading this long before an announced maintenance starts
+    pub lead_secs: u64,
+    /// Window of venue requests the error rate is computed over
+    pub error_window_secs: u64,
+    /// Pause when more than this share of requests in the window failed...
+    pub max_error_rate: f64,
+    /// ...and at least this many were made
+    pub min_requests: usize,
+    /// The venue must look healthy this long before trading resumes
+    pub recovery_secs: u64,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            status_url: "https://status.polymarket.com/api/v2/summary.json".to_string(),
+            poll_secs: 60,
+            lead_secs: 300,
+            error_window_secs: 180,
+            max_error_rate: 0.5,
+            min_requests: 5,
+            recovery_secs: 120,
+        }
+    }
+}
+
+/// Warm start from the last fetched market universe
+#[derive(Debug, Deserialize, Clone)]
+pub struct UniverseCacheConfig {
+    pub enabled: bool,
+    /// Ignore snapshots older than this
+    pub max_age_secs: u64,
+    /// Re-snapshot the live universe at most this often
+    pub save_interval_secs: u64,
+    /// Extra edge ($) required while trading from the snapshot
+    pub stale_edge_haircut: f64,
+}
+
+impl Default for UniverseCacheConfig {
+    fn default() -> Self {
+        Self { enabled: true, max_age_secs: 6 * 3600, save_interval_secs: 300, stale_edge_haircut: 0.01 }
+    }
+}
+
+/// Entry throttles while volatility is elevated (replaces the risk volatility halt)
+#[derive(Debug, Deserialize, Clone)]
+pub struct VolatilityResponseConfig {
+    pub enabled: bool,
+    /// Elevated once PnL volatility reaches this share of `risk.volatility_threshold`
+    pub elevated_at: f64,
+    /// Event-spike markets count as elevated too
+    pub on_event_spike: bool,
+    /// Size multiplier while elevated
+    pub size_multiplier: f64,
+    /// Extra edge ($) required while elevated
+    pub extra_edge: f64,
+    /// Minimum time between signals on one market normally
+    pub signal_cooldown_secs: u64,
+    /// ... and while elevated
+    pub elevated_cooldown_secs: u64,
+}
+
+impl Default for VolatilityResponseConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            elevated_at: 0.75,
+            on_event_spike: true,
+            size_multiplier: 0.5,
+            extra_edge: 0.01,
+            signal_cooldown_secs: 0,
+            elevated_cooldown_secs: 300,
+        }
+    }
+}
+
+/// Resolution outcome tracking of traded markets
+#[derive(Debug, Deserialize, Clone)]
+pub struct ResolutionConfig {
+    pub enabled: bool,
+    /// Gamma markets endpoint (`<url>/<market_id>`)
+    pub markets_url: String,
+    /// Re-check an unresolved market this often
+    pub poll_secs: u64,
+    /// Resolution lookups per engine tick
+    pub max_checks_per_tick: usize,
+}
+
+impl Default for ResolutionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            markets_url: "https://gamma-api.polymarket.com/markets".to_string(),
+            poll_secs: 600,
+            max_checks_per_tick: 5,
+        }
+    }
+}
+
+/// Best-execution routing across venues listing the same market
+#[derive(Debug, Deserialize, Clone)]
+pub struct RoutingConfig {
+    pub enabled: bool,
+    /// Cross-venue mapping file; only `confirmed` pairs are routed
+    pub mapping_file: String,
+    /// Taker fee (bps) per venue name, applied when comparing prices
+    #[serde(default)]
+    pub fee_bps: HashMap<String, u32>,
+}
+
+impl Default for RoutingConfig {
+    fn default() -> Self {
+        Self { enabled: false, mapping_file: "market_mapping.toml".to_string(), fee_bps: HashMap::new() }
+    }
+}
+
+/// USD prices of collateral assets
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct AssetsConfig {
+    /// Symbol → USD price; required for non-stable collateral (e.g. SOL),
+    /// optional for stables (default $1)
+    #[serde(default)]
+    pub usd_prices: HashMap<String, f64>,
+}
+
+/// Importing outcome tokens already held when the bot starts
+#[derive(Debug, Deserialize, Clone)]
+pub struct HoldingsConfig {
+    /// Polygon RPC the conditional token balances are read from
+    pub rpc_url: String,
+    /// Chain the RPC serves; the Conditional Tokens contract is resolved for it in `[registry]`
+    pub chain_id: u64,
+    /// Wallet whose balances are imported (empty = imports disabled)
+    pub owner: String,
+    /// Strategy sub-account imported positions belong to
+    pub strategy: String,
+    pub timeout_ms: u64,
+}
+
+impl Default for HoldingsConfig {
+    fn default() -> Self {
+        Self {
+            rpc_url: "https://polygon-rpc.com".to_string(),
+            chain_id: 137,
+            owner: String::new(),
+            strategy: "imported".to_string(),
+            timeout_ms: 5000,
+        }
+    }
+}
+
+/// Polymarket staging venue used by `mode = "sandbox"`
+#[derive(Debug, Deserialize, Clone)]
+pub struct SandboxConfig {
+    /// Gamma events endpoint (market discovery)
     pub gamma_url: String,
+    /// CLOB base URL (books and signed order submission)
     pub clob_url: String,
-    pub websocket_url: String,
-    pub market_limit: u32,
+    /// Data API trades endpoint
+    pub trades_url: String,
+    /// Chain the CTF Exchange orders are signed for (Polygon Amoy); the
+    /// exchange contract is resolved for it in `[registry]`
+    pub chain_id: u64,
+    /// Env vars holding the sandbox L2 API credentials (values never live in config)
+    pub api_key_env: String,
+    pub api_secret_env: String,
+    pub api_passphrase_env: String,
 }
 
-#[derive(Debug, Deserialize, Clone)]
-pub struct LoggingConfig {
-    pub level: String,
-    pub colorize: bool,
+impl Default for SandboxConfig {
+    fn default() -> Self {
+        Self {
+            gamma_url: "https://gamma-api-staging.polymarket.com/events?limit=20&active=true&closed=false".to_string(),
+            clob_url: "https://clob-staging.polymarket.com".to_string(),
+            trades_url: "https://data-api-staging.polymarket.com/trades".to_string(),
+            chain_id: 80002,
+            api_key_env: "POLY_SANDBOX_API_KEY".to_string(),
+            api_secret_env: "POLY_SANDBOX_API_SECRET".to_string(),
+            api_passphrase_env: "POLY_SANDBOX_API_PASSPHRASE".to_string(),
+        }
+    }
 }
 
-/// Strategy configuration for adaptive trading
+/// Simulate each settlement call with `eth_call` before submitting it
 #[derive(Debug, Deserialize, Clone)]
-pub struct StrategyConfig {
-    /// Threshold for conservative mode (below this %)
-    pub conservative_threshold: f64,
-    /// Threshold for aggressive mode (above this %)
-    pub aggressive_threshold: f64,
-    /// Minimum edge required in conservative mode
-    pub conservative_min_edge: f64,
-    /// Minimum edge required in normal mode
-    pub normal_min_edge: f64,
-    /// Minimum edge required in aggressive mode
-    pub aggressive_min_edge: f64,
+pub struct PreflightConfig {
+    pub enabled: bool,
+    /// JSON-RPC endpoint (empty = the configured Arbitrum Sepolia RPC)
+    pub rpc_url: String,
+    /// Smart Account address the delegated call executes from
+    pub smart_account: String,
+    pub timeout_ms: u64,
+    /// Trade anyway when the RPC itself fails (reverts always refuse)
+    pub fail_open: bool,
 }
 
-impl Default for StrategyConfig {
+impl Default for PreflightConfig {
     fn default() -> Self {
         Self {
-            conservative_threshold: 0.30,
-            aggressive_threshold: 0.70,
-            conservative_min_edge: 0.05,
-            normal_min_edge: 0.02,
-            aggressive_min_edge: 0.01,
+            enabled: false,
+            rpc_url: String::new(),
+            smart_account: String::new(),
+            timeout_ms: 3000,
+            fail_open: false,
         }
     }
 }
 
-/// Safety configuration for failure handling
+/// Simulated competitor for backtests
 #[derive(Debug, Deserialize, Clone)]
-pub struct SafetyConfig {
-    /// Maximum data delay (ms) before suspending trading
-    pub max_data_delay_ms: u64,
-    /// Maximum consecutive API failures before safe mode
-    pub max_consecutive_failures: u32,
-    /// Cooldown period (seconds) in safe mode
-    pub safe_mode_cooldown_secs: u64,
-    /// Assume zero allowance if permission query fails
-    pub assume_zero_on_perm_error: bool,
+pub struct CompetitionConfig {
+    /// Model competition in backtests
+    pub enabled: bool,
+    /// Fraction of detected edges a competitor goes after
+    pub take_fraction: f64,
+    /// Competitor latency distribution (normal, clamped at 0)
+    pub latency_mean_ms: f64,
+    pub latency_std_ms: f64,
+    /// RNG seed for reproducible backtests
+    #[serde(default)]
+    pub seed: Option<u64>,
 }
 
-impl Default for SafetyConfig {
+impl Default for CompetitionConfig {
     fn default() -> Self {
         Self {
-            max_data_delay_ms: 5000,
-            max_consecutive_failures: 3,
-            safe_mode_cooldown_secs: 300,
-            assume_zero_on_perm_error: true,
+            enabled: false,
+            take_fraction: 0.6,
+            latency_mean_ms: 40.0,
+            latency_std_ms: 20.0,
+            seed: None,
         }
     }
 }
 
+/// Signing service configuration
+///
+/// Only environment variable names and file paths live here; key material
+/// never goes in config.toml.
+#[derive(Debug, Deserialize, Clone)]
+pub struct SignerConfig {
+    /// "local", "keystore" or "remote"
+    pub kind: String,
+    /// Env var holding a hex EVM private key (local)
+    pub evm_key_env: Option<String>,
+    /// Env var holding a base58 Solana keypair (local)
+    pub solana_key_env: Option<String>,
+    /// Path to an Ethereum V3 keystore JSON file (keystore)
+    pub keystore_path: Option<String>,
+    /// Env var holding the keystore password (keystore)
+    pub password_env: Option<String>,
+    /// Path to a Solana keypair JSON file (keystore)
+    pub solana_keypair_path: Option<String>,
+    /// Base URL of the signing service (remote)
+    pub remote_url: Option<String>,
+    /// Env var holding a bearer token for the signing service (remote)
+    pub auth_token_env: Option<String>,
+}
+
 /// Arbitrum network configuration
 #[derive(Debug, Deserialize, Clone)]
 pub struct ArbitrumConfig {
@@ -119,8 +1824,12 @@ pub struct ArbitrumConfig {
     pub sepolia_chain_id: u64,
     pub mainnet_chain_id: u64,
     pub envio_endpoint: String,
-    pub usdc_e_address: String,
-    pub demo_contract_address: String,
+    /// Providers tried when `sepolia_rpc` fails
+    #[serde(default)]
+    pub sepolia_fallback_rpcs: Vec<String>,
+    /// Providers tried when `mainnet_rpc` fails
+    #[serde(default)]
+    pub mainnet_fallback_rpcs: Vec<String>,
 }
 
 impl Default for ArbitrumConfig {
@@ -131,8 +1840,8 @@ impl Default for ArbitrumConfig {
             sepolia_chain_id: 421614,
             mainnet_chain_id: 42161,
             envio_endpoint: "https://indexer.bigdevenergy.link/your-project/v1/graphql".to_string(),
-            usdc_e_address: "0xFF970A61A04b1cA14834A43f5dE4533eBDDB5CC8".to_string(),
-            demo_contract_address: "0x0000000000000000000000000000000000000000".to_string(),
+            sepolia_fallback_rpcs: Vec::new(),
+            mainnet_fallback_rpcs: Vec::new(),
         }
     }
 }
@@ -145,11 +1854,39 @@ impl Config {
 
     /// Load configuration from a specific file
     pub fn load_from(path: &str) -> Result<Self, ConfigError> {
+        Self::load_profile_from(path, None)
+    }
+
+    /// Load config.toml resolved with `profile`
+    pub fn load_profile(profile: Option<&str>) -> Result<Self, ConfigError> {
+        Self::load_profile_from("config.toml", profile)
+    }
+
+    /// Load a specific file resolved with `profile`
+    pub fn load_profile_from(path: &str, profile: Option<&str>) -> Result<Self, ConfigError> {
         let contents = fs::read_to_string(path)
             .map_err(|e| ConfigError::FileNotFound(path.to_string(), e.to_string()))?;
-        
-        toml::from_str(&contents)
-            .map_err(|e| ConfigError::ParseError(e.to_string()))
+        Self::parse(&contents, profile)
+    }
+
+    /// Parse config text, applying `profile` over the top-level settings
+    pub fn parse(contents: &str, profile: Option<&str>) -> Result<Self, ConfigError> {
+        let mut root: toml::Table = toml::from_str(contents)
+            .map_err(|e| ConfigError::ParseError(e.to_string()))?;
+        let profiles = match root.remove("profile") {
+            Some(toml::Value::Table(profiles)) => profiles,
+            Some(_) => return Err(ConfigError::Profile("[profile] must be a table of named profiles".to_string())),
+            None => toml::Table::new(),
+        };
+        if let Some(name) = profile {
+            for overlay in profile_chain(&profiles, name)?.into_iter().rev() {
+                merge(&mut root, overlay);
+            }
+        }
+        let mut config: Self = toml::Value::Table(root).try_into()
+            .map_err(|e: toml::de::Error| ConfigError::ParseError(e.to_string()))?;
+        config.profile = profile.map(str::to_string);
+        Ok(config)
     }
 
     /// Create default configuration
@@ -159,18 +1896,30 @@ impl Config {
                 daily_limit_usdc: 10.0,
                 duration_days: 30,
                 token: "USDC".to_string(),
+                allowed_targets: Vec::new(),
+                allowed_methods: Vec::new(),
+                max_per_tx_usdc: None,
             },
             trading: TradingConfig {
                 min_spread_threshold: 0.02,
                 min_profit_threshold: 0.10,
                 trade_size: 5.0,
                 max_position_value: 50.0,
+                remainder_policy: default_remainder_policy(),
+                max_chase_bps: default_max_chase_bps(),
+                max_rest_secs: default_max_rest_secs(),
+                requote_window_secs: default_requote_window_secs(),
+                max_open_bundles: 0,
+                max_entries_per_minute: 0,
+                max_entries_per_hour: 0,
+                gas_per_leg_usd: default_gas_per_leg_usd(),
             },
             timing: TimingConfig {
                 poll_interval_secs: 5,
                 position_timeout_secs: 3600,
                 latency_base_ms: 50,
                 adverse_selection_std: 0.001,
+                signal_ttl_ms: default_signal_ttl_ms(),
             },
             api: ApiConfig {
                 gamma_url: "https://gamma-api.polymarket.com/events".to_string(),
@@ -184,8 +1933,73 @@ impl Config {
             },
             strategy: StrategyConfig::default(),
             safety: SafetyConfig::default(),
+            polling: PollingConfig::default(),
+            freshness: FreshnessConfig::default(),
+            grpc: GrpcConfig::default(),
+            dashboard: DashboardConfig::default(),
+            fleet: FleetConfig::default(),
+            storage: StorageConfig::default(),
+            health: HealthConfig::default(),
+            competition: CompetitionConfig::default(),
+            risk: RiskConfig::default(),
+            quorum: QuorumConfig::default(),
+            preflight: PreflightConfig::default(),
+            strategies: HashMap::new(),
+            trading_windows: TradingWindowConfig::default(),
+            coordination: CoordinationConfig::default(),
+            sweep: SweepConfig::default(),
+            tax: TaxConfig::default(),
+            anomaly: AnomalyConfig::default(),
+            mirror: MirrorConfig::default(),
+            supervisor: SupervisorConfig::default(),
+            approval: ApprovalConfig::default(),
+            api_rate_limit: ApiRateLimitConfig::default(),
+            equity: EquityConfig::default(),
+            watchlist: WatchlistConfig::default(),
+            compliance: ComplianceConfig::default(),
+            external_signals: ExternalSignalConfig::default(),
+            treasury: TreasuryConfig::default(),
+            embeddings: EmbeddingConfig::default(),
+            holdings: HoldingsConfig::default(),
+            assets: AssetsConfig::default(),
+            routing: RoutingConfig::default(),
+            model_drift: ModelDriftConfig::default(),
+            decisions: DecisionsConfig::default(),
+            reconcile: ReconcileConfig::default(),
+            resolution: ResolutionConfig::default(),
+            volatility_response: VolatilityResponseConfig::default(),
+            universe_cache: UniverseCacheConfig::default(),
+            maintenance: MaintenanceConfig::default(),
+            edge_curve: EdgeCurveConfig::default(),
+            debug: DebugConfig::default(),
+            retention: RetentionConfig::default(),
+            object_store: ObjectStoreConfig::default(),
+            pacing: PacingConfig::default(),
+            fee_tiers: FeeTiersConfig::default(),
+            maker_taker: MakerTakerConfig::default(),
+            registry: RegistryConfig::default(),
+            intents: IntentConfig::default(),
+            venue_latency: VenueLatencyConfig::default(),
+            doctor: DoctorConfig::default(),
+            portfolio: PortfolioConfig::default(),
+            exit_optimizer: ExitOptimizerConfig::default(),
+            quoting: QuotingConfig::default(),
+            synthetic: SyntheticConfig::default(),
+            working_capital: WorkingCapitalConfig::default(),
+            remediation: RemediationConfig::default(),
+            leg_risk: LegRiskConfig::default(),
+            finality: FinalityConfig::default(),
+            allowance_fit: AllowanceFitConfig::default(),
+            slippage_guard: SlippageGuardConfig::default(),
+            annotations: AnnotationsConfig::default(),
+            order_rate: OrderRateConfig::default(),
+            rpc_pool: RpcPoolConfig::default(),
+            greeks: GreeksConfig::default(),
+            sandbox: SandboxConfig::default(),
             mode: Some("arbitrum_demo".to_string()),
             arbitrum: Some(ArbitrumConfig::default()),
+            signer: None,
+            profile: None,
         }
     }
 
@@ -194,21 +2008,117 @@ impl Config {
         if self.permission.daily_limit_usdc <= 0.0 {
             return Err("daily_limit_usdc must be positive".to_string());
         }
+        self.permission.collateral(&self.assets)?;
         
+        if self.sweep.enabled && self.sweep.cold_address.is_empty() {
+            return Err("sweep.cold_address required when profit sweep is enabled".to_string());
+        }
+
+        self.validate_supervisor()?;
+
         if let Some(mode) = &self.mode {
             if mode == "arbitrum_demo" && self.arbitrum.is_none() {
                 return Err("arbitrum config required for arbitrum_demo mode".to_string());
             }
+            if mode == "sandbox" && self.signer.is_none() {
+                return Err("signer config required for sandbox mode (orders are signed)".to_string());
+            }
         }
         
         Ok(())
     }
 }
 
+impl Config {
+    /// Longest the engine loop sleeps between heartbeats: the poll interval, its
+    /// staleness backoff, or the slow heartbeat while the allowance is exhausted
+    /// Watchdog settings the engine cannot run under (checked before anything starts)
+    pub fn validate_supervisor(&self) -> Result<(), String> {
+        // The engine beats before each sleep: a watchdog tighter than the longest one restarts a healthy engine
+        let longest_sleep = self.longest_engine_sleep_secs();
+        if self.supervisor.enabled && self.supervisor.stall_timeout_secs <= longest_sleep {
+            return Err(format!("supervisor.stall_timeout_secs ({}) must exceed the engine's longest sleep ({}s)",
+                self.supervisor.stall_timeout_secs, longest_sleep));
+        }
+        Ok(())
+    }
+
+    pub fn longest_engine_sleep_secs(&self) -> u64 {
+        let tick = self.polling.fast_interval_secs.max(1);
+        let backed_off = if self.freshness.enabled { self.freshness.max_poll_interval_secs.max(tick) } else { tick };
+        backed_off
+            .max(self.timing.poll_interval_secs)
+            .max(self.safety.exhausted_heartbeat_secs.max(1))
+    }
+}
+
+/// Env var selecting the profile when `--profile` is not given
+pub const PROFILE_ENV: &str = "ARBISHARK_PROFILE";
+
+/// Remove `--profile <name>` / `--profile=<name>` from `args`, falling back to `ARBISHARK_PROFILE`
+pub fn take_profile_arg(args: &mut Vec<String>) -> Option<String> {
+    let mut profile = None;
+    let mut i = 0;
+    while i < args.len() {
+        if let Some(name) = args[i].strip_prefix("--profile=") {
+            profile = Some(name.to_string());
+            args.remove(i);
+        } else if args[i] == "--profile" && i + 1 < args.len() {
+            profile = Some(args.remove(i + 1));
+            args.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+    profile.or_else(|| std::env::var(PROFILE_ENV).ok().filter(|p| !p.is_empty()))
+}
+
+/// Overlays of profile `name`, most specific first, following `inherits`
+fn profile_chain(profiles: &toml::Table, name: &str) -> Result<Vec<toml::Table>, ConfigError> {
+    let mut chain: Vec<toml::Table> = Vec::new();
+    let mut seen: Vec<String> = Vec::new();
+    let mut next = Some(name.to_string());
+    while let Some(name) = next {
+        if seen.contains(&name) {
+            return Err(ConfigError::Profile(format!("profile inheritance cycle: {} → {}", seen.join(" → "), name)));
+        }
+        let mut overlay = match profiles.get(&name) {
+            Some(toml::Value::Table(t)) => t.clone(),
+            Some(_) => return Err(ConfigError::Profile(format!("profile.{} must be a table", name))),
+            None => {
+                let known = profiles.keys().cloned().collect::<Vec<_>>().join(", ");
+                return Err(ConfigError::Profile(format!("unknown profile {:?} (known: {})", name, known)));
+            }
+        };
+        next = match overlay.remove("inherits") {
+            Some(toml::Value::String(parent)) => Some(parent),
+            Some(_) => return Err(ConfigError::Profile(format!("profile.{}.inherits must be a profile name", name))),
+            None => None,
+        };
+        seen.push(name);
+        chain.push(overlay);
+    }
+    Ok(chain)
+}
+
+/// Deep-merge `overlay` into `base`: tables merge key by key, anything else is replaced
+fn merge(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(overlay_table)) => merge(base_table, overlay_table),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum ConfigError {
     FileNotFound(String, String),
     ParseError(String),
+    /// Unknown profile, bad `inherits` or an inheritance cycle
+    Profile(String),
 }
 
 impl std::fmt::Display for ConfigError {
@@ -216,6 +2126,7 @@ impl std::fmt::Display for ConfigError {
         match self {
             Self::FileNotFound(path, err) => write!(f, "Config file not found: {} ({})", path, err),
             Self::ParseError(err) => write!(f, "Config parse error: {}", err),
+            Self::Profile(err) => write!(f, "Config profile error: {}", err),
         }
     }
 }
@@ -232,4 +2143,47 @@ mod tests {
         assert_eq!(config.permission.daily_limit_usdc, 10.0);
         assert_eq!(config.trading.min_spread_threshold, 0.02);
     }
+
+    #[test]
+    fn test_stall_timeout_must_exceed_engine_sleeps() {
+        let mut config = Config::default_config();
+        assert!(config.validate().is_ok());
+        config.safety.exhausted_heartbeat_secs = 300;
+        assert_eq!(config.longest_engine_sleep_secs(), 300);
+        assert!(config.validate().unwrap_err().contains("stall_timeout_secs"));
+        config.supervisor.stall_timeout_secs = 301;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_profiles_inherit_and_override() {
+        let base = fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/config.toml")).unwrap();
+        let contents = format!("{}\n{}", base, r#"
+[profile.demo.trading]
+trade_size = 1.5
+
+[profile.aggressive]
+inherits = "demo"
+
+[profile.aggressive.trading]
+min_spread_threshold = 0.005
+
+[profile.loop]
+inherits = "loop"
+"#);
+        let top = Config::parse(&contents, None).unwrap();
+        let aggressive = Config::parse(&contents, Some("aggressive")).unwrap();
+        assert_eq!(aggressive.profile.as_deref(), Some("aggressive"));
+        assert_eq!(aggressive.trading.trade_size, 1.5);
+        assert_eq!(aggressive.trading.min_spread_threshold, 0.005);
+        // Everything else comes from the top-level settings
+        assert_eq!(aggressive.trading.max_rest_secs, top.trading.max_rest_secs);
+        assert_eq!(aggressive.permission.daily_limit_usdc, top.permission.daily_limit_usdc);
+        assert!(matches!(Config::parse(&contents, Some("loop")), Err(ConfigError::Profile(_))));
+        assert!(matches!(Config::parse(&contents, Some("missing")), Err(ConfigError::Profile(_))));
+
+        let mut args = vec!["arbishark".to_string(), "--profile".to_string(), "demo".to_string(), "tax-report".to_string()];
+        assert_eq!(take_profile_arg(&mut args).as_deref(), Some("demo"));
+        assert_eq!(args, ["arbishark", "tax-report"]);
+    }
 }
@@ -0,0 +1,195 @@
+// Signal conflict resolution
+// Nets intended exposure per token across signal sources and drops the signals trading against it
+
+use crate::types::{ArbitrageSignal, Side};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Net exposure below this counts as flat
+const EPS: f64 = 1e-9;
+
+/// Where a signal came from
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum SignalSource {
+    Detected,
+    External { signal_id: String },
+    Approved { approval_id: String },
+}
+
+impl SignalSource {
+    /// Resolution order: a human already signed off on approved signals
+    fn priority(&self) -> u8 {
+        match self {
+            Self::Approved { .. } => 2,
+            Self::External { .. } => 1,
+            Self::Detected => 0,
+        }
+    }
+}
+
+impl fmt::Display for SignalSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Detected => write!(f, "detected"),
+            Self::External { signal_id } => write!(f, "external {}", signal_id),
+            Self::Approved { approval_id } => write!(f, "approval {}", approval_id),
+        }
+    }
+}
+
+/// A signal and the exposure it intends to take
+#[derive(Debug, Clone)]
+pub struct SignalIntent {
+    pub source: SignalSource,
+    pub signal: ArbitrageSignal,
+    /// Outcome tokens the signal trades (every leg of the bundle)
+    pub tokens: Vec<String>,
+    /// Intended size per leg
+    pub size: f64,
+}
+
+impl SignalIntent {
+    fn sign(&self) -> f64 {
+        match self.signal.recommended_side {
+            Side::Buy => 1.0,
+            Side::Sell => -1.0,
+        }
+    }
+}
+
+/// A signal that survived netting
+#[derive(Debug, Clone)]
+pub struct KeptSignal {
+    pub source: SignalSource,
+    pub signal: ArbitrageSignal,
+    /// Size per leg left after netting (at most the intended size)
+    pub size: f64,
+    /// Whether netting reduced the size
+    pub capped: bool,
+}
+
+/// A signal removed by netting, and why
+#[derive(Debug, Clone)]
+pub struct DroppedSignal {
+    pub source: SignalSource,
+    pub signal: ArbitrageSignal,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Resolution {
+    /// In resolution order
+    pub kept: Vec<KeptSignal>,
+    pub dropped: Vec<DroppedSignal>,
+}
+
+/// Net the intended exposures of `intents` per token and keep the signals that agree with the net
+pub fn resolve(mut intents: Vec<SignalIntent>) -> Resolution {
+    let mut net: HashMap<String, f64> = HashMap::new();
+    // Sources on each side of a token, for drop reasons
+    let mut sides: HashMap<(String, bool), Vec<String>> = HashMap::new();
+    for intent in &intents {
+        for token in &intent.tokens {
+            *net.entry(token.clone()).or_default() += intent.sign() * intent.size;
+            sides.entry((token.clone(), intent.sign() > 0.0)).or_default().push(intent.source.to_string());
+        }
+    }
+    intents.sort_by(|a, b| b.source.priority().cmp(&a.source.priority()).then(b.signal.edge.total_cmp(&a.signal.edge)));
+
+    let mut remaining = net.clone();
+    let mut kept_markets: HashMap<String, SignalSource> = HashMap::new();
+    let mut resolution = Resolution::default();
+    for intent in intents {
+        let sign = intent.sign();
+        let mut drop = |reason: String| resolution.dropped.push(DroppedSignal {
+            source: intent.source.clone(), signal: intent.signal.clone(), reason,
+        });
+        if let Some(winner) = kept_markets.get(&intent.signal.market_id) {
+            drop(format!("superseded by the {} signal on the same market", winner));
+            continue;
+        }
+        let opposed = intent.tokens.iter().find(|t| net.get(*t).copied().unwrap_or(0.0) * sign <= EPS);
+        if let Some(token) = opposed {
+            let mut opposing = sides.get(&(token.clone(), sign < 0.0)).cloned().unwrap_or_default();
+            opposing.sort();
+            opposing.dedup();
+            drop(format!("opposed by {} - net exposure {:+.2} on token {}",
+                opposing.join(", "), net.get(token).copied().unwrap_or(0.0), token));
+            continue;
+        }
+        let available = intent.tokens.iter().map(|t| remaining.get(t).copied().unwrap_or(0.0) * sign).fold(intent.size, f64::min);
+        if available <= EPS {
+            drop("net exposure already taken by higher-priority signals".to_string());
+            continue;
+        }
+        for token in &intent.tokens {
+            *remaining.entry(token.clone()).or_default() -= sign * available;
+        }
+        kept_markets.insert(intent.signal.market_id.clone(), intent.source.clone());
+        resolution.kept.push(KeptSignal {
+            capped: available + EPS < intent.size,
+            size: available,
+            source: intent.source,
+            signal: intent.signal,
+        });
+    }
+    resolution
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn intent(source: SignalSource, market_id: &str, side: Side, edge: f64, size: f64) -> SignalIntent {
+        SignalIntent {
+            source,
+            signal: ArbitrageSignal {
+                market_id: market_id.to_string(),
+                spread: edge,
+                edge,
+                recommended_side: side,
+                yes_price: 0.45,
+                no_price: 0.45,
+                edge_curve: Vec::new(),
+                quote_ages_ms: Vec::new(),
+            },
+            tokens: vec![format!("{}-yes", market_id), format!("{}-no", market_id)],
+            size,
+        }
+    }
+
+    fn external(id: &str) -> SignalSource {
+        SignalSource::External { signal_id: id.to_string() }
+    }
+
+    #[test]
+    fn test_nets_opposing_signals_per_token() {
+        let resolution = resolve(vec![
+            // m1: a $10 buy against a $4 sell nets to a $6 buy
+            intent(SignalSource::Detected, "m1", Side::Buy, 0.05, 10.0),
+            intent(external("x1"), "m1", Side::Sell, 0.08, 4.0),
+            // m2: exactly offsetting - neither trades
+            intent(SignalSource::Detected, "m2", Side::Buy, 0.05, 10.0),
+            intent(external("x2"), "m2", Side::Sell, 0.03, 10.0),
+            // m3: approval first, the detected duplicate is superseded
+            intent(SignalSource::Detected, "m3", Side::Buy, 0.09, 10.0),
+            intent(SignalSource::Approved { approval_id: "a1".to_string() }, "m3", Side::Buy, 0.04, 10.0),
+            // m4: no conflict
+            intent(SignalSource::Detected, "m4", Side::Buy, 0.02, 10.0),
+        ]);
+
+        let kept: Vec<(&str, f64, bool)> = resolution.kept.iter().map(|k| (k.signal.market_id.as_str(), k.size, k.capped)).collect();
+        assert_eq!(kept, vec![("m3", 10.0, false), ("m1", 6.0, true), ("m4", 10.0, false)]);
+        assert!(matches!(resolution.kept[0].source, SignalSource::Approved { .. }));
+
+        let reason = |market_id: &str, source: &str| resolution.dropped.iter()
+            .find(|d| d.signal.market_id == market_id && d.source.to_string() == source).unwrap().reason.clone();
+        assert_eq!(resolution.dropped.len(), 4);
+        assert_eq!(reason("m1", "external x1"), "opposed by detected - net exposure +6.00 on token m1-yes");
+        assert!(reason("m2", "external x2").starts_with("opposed by detected - net exposure +0.00"));
+        assert!(reason("m2", "detected").starts_with("opposed by external x2 - "));
+        assert_eq!(reason("m3", "detected"), "superseded by the approval a1 signal on the same market");
+    }
+}
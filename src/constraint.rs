@@ -36,6 +36,8 @@ impl ConstraintChecker {
             recommended_side,
             yes_price: market.yes_price(), // Legacy field, might need updating in ArbitrageSignal struct to be generic
             no_price: market.no_price(),   // Legacy field
+            edge_curve: Vec::new(),
+            quote_ages_ms: Vec::new(),
         })
     }
 }
\ No newline at end of file
@@ -0,0 +1,375 @@
+//! Decision records for training signal filters: features of every scanned
+//! market, labelled later with horizon deviations and realized PnL
+
+use crate::config::DecisionsConfig;
+use crate::parquet::{Column, ColumnData};
+use crate::spreads::SpreadStats;
+use crate::storage::JsonlStore;
+use crate::types::Market;
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io;
+
+/// What the engine did with a scanned market
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Decision {
+    /// The detector found no arbitrage
+    NoSignal,
+    /// Detected, but the edge was below the regime threshold plus staleness haircut
+    Filtered,
+    /// Queued for execution but not traded (refused by a check, decayed)
+    Signalled,
+    Traded,
+}
+
+impl Decision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NoSignal => "no_signal",
+            Self::Filtered => "filtered",
+            Self::Signalled => "signalled",
+            Self::Traded => "traded",
+        }
+    }
+}
+
+/// Features known at scan time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionFeatures {
+    /// Sum of outcome prices
+    pub price_sum: f64,
+    /// |price_sum - 1|
+    pub deviation: f64,
+    pub best_bid: Option<f64>,
+    pub best_ask: Option<f64>,
+    pub liquidity: f64,
+    pub volume_24hr: f64,
+    pub taker_fee_bps: u32,
+    /// Quoted spread statistics over the recent window
+    pub median_spread: Option<f64>,
+    pub p90_spread: Option<f64>,
+    pub touch_depth: Option<f64>,
+    pub hour_utc: u32,
+    /// 0 = Monday
+    pub weekday: u32,
+    pub staleness_ms: u64,
+    pub poll_interval_secs: u64,
+}
+
+impl DecisionFeatures {
+    pub fn new(market: &Market, spreads: Option<&SpreadStats>, now: u64, staleness_ms: u64, poll_interval_secs: u64) -> Self {
+        let time = DateTime::<Utc>::from_timestamp(now as i64, 0).unwrap_or_default();
+        Self {
+            price_sum: market.outcome_prices.iter().sum(),
+            deviation: market.get_spread(),
+            best_bid: market.best_bid,
+            best_ask: market.best_ask,
+            liquidity: market.liquidity,
+            volume_24hr: market.volume_24hr,
+            taker_fee_bps: market.taker_base_fee,
+            median_spread: spreads.map(|s| s.median),
+            p90_spread: spreads.map(|s| s.p90),
+            touch_depth: spreads.map(|s| s.avg_touch_depth),
+            hour_utc: time.hour(),
+            weekday: time.weekday().num_days_from_monday(),
+            staleness_ms,
+            poll_interval_secs,
+        }
+    }
+}
+
+/// One scanned market with its labels
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionRecord {
+    pub record_id: String,
+    pub market_id: String,
+    pub timestamp: u64,
+    pub features: DecisionFeatures,
+    pub decision: Decision,
+    /// Detector edge per unit, when a signal was detected
+    pub edge: Option<f64>,
+    /// Bundle deviation observed `horizon` seconds after the scan
+    #[serde(default)]
+    pub deviation_after: BTreeMap<u64, f64>,
+    /// Realized PnL of the resulting trade, summed over its legs
+    #[serde(default)]
+    pub realized_pnl: Option<f64>,
+    /// Why conflict resolution dropped the signal (see `conflicts`)
+    #[serde(default)]
+    pub conflict: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum DecisionEvent {
+    Scan(Box<DecisionRecord>),
+    Traded { record_id: String, at: u64 },
+    Deviation { record_id: String, horizon_secs: u64, deviation: f64 },
+    Realized { record_id: String, pnl: f64 },
+    Conflict { record_id: String, reason: String },
+}
+
+/// A scan still waiting for deviation labels
+#[derive(Debug)]
+struct Pending {
+    record_id: String,
+    market_id: String,
+    timestamp: u64,
+    /// Index of the next horizon to label
+    next: usize,
+}
+
+/// Journals scan decisions and their labels
+#[derive(Debug)]
+pub struct DecisionLog {
+    horizons: Vec<u64>,
+    pending: Vec<Pending>,
+    /// Latest record per market
+    latest: HashMap<String, String>,
+    /// Latest traded record per market (realized PnL is credited here)
+    traded: HashMap<String, String>,
+    journal: JsonlStore,
+}
+
+impl DecisionLog {
+    pub fn new(config: &DecisionsConfig, journal: JsonlStore) -> Self {
+        let mut horizons = config.horizons_secs.clone();
+        horizons.sort_unstable();
+        horizons.dedup();
+        Self { horizons, pending: Vec::new(), latest: HashMap::new(), traded: HashMap::new(), journal }
+    }
+
+    fn append(&self, event: &DecisionEvent) {
+        if let Err(e) = self.journal.append(event) {
+            eprintln!("⚠️ [Decisions] Failed to journal decision record: {}", e);
+        }
+    }
+
+    /// Record the engine's decision on a scanned market
+    pub fn record_scan(&mut self, market_id: &str, features: DecisionFeatures, decision: Decision, edge: Option<f64>, now: u64) {
+        let record_id = format!("{}:{}", market_id, now);
+        self.append(&DecisionEvent::Scan(Box::new(DecisionRecord {
+            record_id: record_id.clone(),
+            market_id: market_id.to_string(),
+            timestamp: now,
+            features,
+            decision,
+            edge,
+            deviation_after: BTreeMap::new(),
+            realized_pnl: None,
+            conflict: None,
+        })));
+        if !self.horizons.is_empty() {
+            self.pending.push(Pending { record_id: record_id.clone(), market_id: market_id.to_string(), timestamp: now, next: 0 });
+        }
+        self.latest.insert(market_id.to_string(), record_id);
+    }
+
+    /// Label pending scans whose horizons have passed with the markets' current deviation
+    pub fn observe(&mut self, markets: &[Market], now: u64) {
+        let deviations: HashMap<&str, f64> = markets.iter().map(|m| (m.id.as_str(), m.get_spread())).collect();
+        let mut labels = Vec::new();
+        for pending in &mut self.pending {
+            let Some(&deviation) = deviations.get(pending.market_id.as_str()) else { continue };
+            while let Some(&horizon) = self.horizons.get(pending.next).filter(|&&h| now >= pending.timestamp + h) {
+                labels.push(DecisionEvent::Deviation { record_id: pending.record_id.clone(), horizon_secs: horizon, deviation });
+                pending.next += 1;
+            }
+        }
+        for label in &labels {
+            self.append(label);
+        }
+        // Done, or the market stopped being listed long ago
+        let max_horizon = self.horizons.last().copied().unwrap_or(0);
+        let horizons = self.horizons.len();
+        self.pending.retain(|p| p.next < horizons && now < p.timestamp + 2 * max_horizon);
+    }
+
+    /// The latest scan of `market_id` led to a fill
+    pub fn mark_traded(&mut self, market_id: &str, now: u64) {
+        let Some(record_id) = self.latest.get(market_id).cloned() else { return };
+        if self.traded.get(market_id) != Some(&record_id) {
+            self.append(&DecisionEvent::Traded { record_id: record_id.clone(), at: now });
+            self.traded.insert(market_id.to_string(), record_id);
+        }
+    }
+
+    /// Conflict resolution dropped the signal from the latest scan of `market_id`
+    pub fn record_conflict(&mut self, market_id: &str, reason: &str) {
+        if let Some(record_id) = self.latest.get(market_id) {
+            let event = DecisionEvent::Conflict { record_id: record_id.clone(), reason: reason.to_string() };
+            self.append(&event);
+        }
+    }
+
+    /// Realized PnL of a leg exited in `market_id`, credited to its traded scan
+    pub fn record_realized(&mut self, market_id: &str, pnl: f64) {
+        if let Some(record_id) = self.traded.get(market_id) {
+            let event = DecisionEvent::Realized { record_id: record_id.clone(), pnl };
+            self.append(&event);
+        }
+    }
+}
+
+/// Join the journal into labelled rows, oldest first
+pub fn load(journal: &JsonlStore) -> io::Result<Vec<DecisionRecord>> {
+    let mut records: Vec<DecisionRecord> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for event in journal.load::<DecisionEvent>()? {
+        match event {
+            DecisionEvent::Scan(record) => {
+                index.insert(record.record_id.clone(), records.len());
+                records.push(*record);
+            }
+            DecisionEvent::Traded { record_id, .. } => {
+                if let Some(&i) = index.get(&record_id) {
+                    records[i].decision = Decision::Traded;
+                }
+            }
+            DecisionEvent::Deviation { record_id, horizon_secs, deviation } => {
+                if let Some(&i) = index.get(&record_id) {
+                    records[i].deviation_after.insert(horizon_secs, deviation);
+                }
+            }
+            DecisionEvent::Realized { record_id, pnl } => {
+                if let Some(&i) = index.get(&record_id) {
+                    *records[i].realized_pnl.get_or_insert(0.0) += pnl;
+                }
+            }
+            DecisionEvent::Conflict { record_id, reason } => {
+                if let Some(&i) = index.get(&record_id) {
+                    records[i].conflict = Some(reason);
+                }
+            }
+        }
+    }
+    Ok(records)
+}
+
+/// Flat feature / label columns, one `deviation_after_<h>s` column per horizon seen
+pub fn columns(records: &[DecisionRecord]) -> Vec<Column> {
+    let text = |f: fn(&DecisionRecord) -> String| ColumnData::Utf8(records.iter().map(f).collect());
+    let int = |f: fn(&DecisionRecord) -> i64| ColumnData::Int64(records.iter().map(f).collect());
+    let num = |f: fn(&DecisionRecord) -> Option<f64>| ColumnData::Double(records.iter().map(f).collect());
+    let mut columns = vec![
+        Column::new("market_id", text(|r| r.market_id.clone())),
+        Column::new("timestamp", int(|r| r.timestamp as i64)),
+        Column::new("price_sum", num(|r| Some(r.features.price_sum))),
+        Column::new("deviation", num(|r| Some(r.features.deviation))),
+        Column::new("best_bid", num(|r| r.features.best_bid)),
+        Column::new("best_ask", num(|r| r.features.best_ask)),
+        Column::new("liquidity", num(|r| Some(r.features.liquidity))),
+        Column::new("volume_24hr", num(|r| Some(r.features.volume_24hr))),
+        Column::new("taker_fee_bps", int(|r| r.features.taker_fee_bps as i64)),
+        Column::new("median_spread", num(|r| r.features.median_spread)),
+        Column::new("p90_spread", num(|r| r.features.p90_spread)),
+        Column::new("touch_depth", num(|r| r.features.touch_depth)),
+        Column::new("hour_utc", int(|r| r.features.hour_utc as i64)),
+        Column::new("weekday", int(|r| r.features.weekday as i64)),
+        Column::new("staleness_ms", int(|r| r.features.staleness_ms as i64)),
+        Column::new("poll_interval_secs", int(|r| r.features.poll_interval_secs as i64)),
+        Column::new("decision", text(|r| r.decision.as_str().to_string())),
+        Column::new("traded", ColumnData::Boolean(records.iter().map(|r| r.decision == Decision::Traded).collect())),
+        Column::new("conflict", text(|r| r.conflict.clone().unwrap_or_default())),
+        Column::new("edge", num(|r| r.edge)),
+    ];
+    let horizons: BTreeSet<u64> = records.iter().flat_map(|r| r.deviation_after.keys().copied()).collect();
+    for horizon in horizons {
+        columns.push(Column::new(format!("deviation_after_{}s", horizon),
+            ColumnData::Double(records.iter().map(|r| r.deviation_after.get(&horizon).copied()).collect())));
+    }
+    columns.push(Column::new("realized_pnl", num(|r| r.realized_pnl)));
+    columns
+}
+
+/// Export the journal to a Parquet file; returns the row count
+pub fn export_parquet(journal: &JsonlStore, path: &str) -> io::Result<usize> {
+    let records = load(journal)?;
+    crate::parquet::write(path, &columns(&records))?;
+    Ok(records.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn market(id: &str, prices: [f64; 2]) -> Market {
+        Market {
+            id: id.to_string(),
+            outcome_prices: prices.to_vec(),
+            best_bid: Some(0.46),
+            best_ask: Some(0.48),
+            ..testing::market()
+        }
+    }
+
+    fn features() -> DecisionFeatures {
+        DecisionFeatures::new(&market("m1", [0.45, 0.50]), None, 0, 1500, 10)
+    }
+
+    fn log(test: &str) -> (DecisionLog, JsonlStore, String) {
+        let dir = std::env::temp_dir().join(format!("arbishark_decisions_{}_{}", test, std::process::id()));
+        let dir = dir.to_str().unwrap().to_string();
+        let journal = JsonlStore::open(&dir, "decisions.jsonl").unwrap();
+        let config = DecisionsConfig { enabled: true, horizons_secs: vec![60, 30] };
+        (DecisionLog::new(&config, journal.clone()), journal, dir)
+    }
+
+    #[test]
+    fn test_timing_features() {
+        assert_eq!((features().hour_utc, features().weekday), (0, 3));
+    }
+
+    #[test]
+    fn test_realized_pnl_joins_the_traded_scan() {
+        let (mut log, journal, dir) = log("realized");
+        log.record_scan("m1", features(), Decision::Signalled, Some(0.05), 0);
+        log.record_scan("m2", features(), Decision::Signalled, Some(0.02), 0);
+        log.record_conflict("m2", "opposed by external x1");
+        log.mark_traded("m1", 5);
+        log.mark_traded("m1", 6);
+        log.record_realized("m1", 0.2);
+        // A later scan of the same market does not take the trade's PnL
+        log.record_scan("m1", DecisionFeatures::new(&market("m1", [0.45, 0.50]), None, 20, 0, 10), Decision::NoSignal, None, 20);
+        log.record_realized("m1", 0.1);
+        log.record_realized("m2", 1.0);
+
+        let rows = load(&journal).unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].decision, Decision::Traded);
+        assert!((rows[0].realized_pnl.unwrap() - 0.3).abs() < 1e-9);
+        assert_eq!((rows[1].realized_pnl, rows[1].conflict.as_deref()), (None, Some("opposed by external x1")));
+        assert_eq!((rows[2].decision, rows[2].realized_pnl), (Decision::NoSignal, None));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_deviation_labelled_at_each_horizon() {
+        let (mut log, journal, dir) = log("horizons");
+        log.record_scan("m1", features(), Decision::Signalled, Some(0.05), 0);
+        log.observe(&[market("m1", [0.48, 0.50])], 40);
+        log.observe(&[market("m1", [0.49, 0.50])], 70);
+
+        let rows = load(&journal).unwrap();
+        assert!((rows[0].deviation_after[&30] - 0.02).abs() < 1e-9);
+        assert!((rows[0].deviation_after[&60] - 0.01).abs() < 1e-9);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_rows_export_to_parquet() {
+        let (mut log, journal, dir) = log("parquet");
+        log.record_scan("m1", features(), Decision::Signalled, Some(0.05), 0);
+        log.observe(&[market("m1", [0.48, 0.50])], 40);
+        log.observe(&[market("m1", [0.49, 0.50])], 70);
+
+        let columns = columns(&load(&journal).unwrap());
+        let names: Vec<&str> = columns.iter().map(|c| c.name.as_str()).collect();
+        assert!(names.ends_with(&["edge", "deviation_after_30s", "deviation_after_60s", "realized_pnl"]));
+        assert_eq!(export_parquet(&journal, &format!("{}/decisions.parquet", dir)).unwrap(), 1);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
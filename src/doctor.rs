@@ -0,0 +1,439 @@
+// Startup self-test for `arbishark doctor`
+// Config, endpoints, permissions, signer, balances and a dry scan; any failure exits non-zero
+
+use crate::arb::ArbitrageDetector;
+use crate::config::Config;
+use crate::market_client::{MarketClient, PolymarketClient};
+use crate::preflight::selector;
+use crate::registry::{self, Contract, Registry};
+use crate::rpc_pool::RpcPool;
+use crate::signer;
+use crate::solana::SolanaManager;
+use serde::Serialize;
+use serde_json::json;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    /// Fail for what the configured mode needs, warn otherwise
+    fn unless_optional(required: bool) -> Self {
+        if required { Self::Fail } else { Self::Warn }
+    }
+
+    fn icon(&self) -> &'static str {
+        match self {
+            Self::Pass => "✅",
+            Self::Warn => "⚠️",
+            Self::Fail => "❌",
+        }
+    }
+}
+
+/// Outcome of one check
+#[derive(Debug, Clone, Serialize)]
+pub struct Check {
+    pub section: &'static str,
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    pub latency_ms: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Report {
+    pub checks: Vec<Check>,
+}
+
+impl Report {
+    fn push(&mut self, section: &'static str, name: &str, status: CheckStatus, detail: impl Into<String>, latency_ms: Option<f64>) {
+        self.checks.push(Check { section, name: name.to_string(), status, detail: detail.into(), latency_ms });
+    }
+
+    fn count(&self, status: CheckStatus) -> usize {
+        self.checks.iter().filter(|c| c.status == status).count()
+    }
+
+    /// No check failed
+    pub fn passed(&self) -> bool {
+        self.count(CheckStatus::Fail) == 0
+    }
+
+    /// Printable report, grouped by section
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut section = "";
+        for check in &self.checks {
+            if check.section != section {
+                section = check.section;
+                lines.push(format!("[{}]", section));
+            }
+            let latency = check.latency_ms.map(|ms| format!(" ({:.0}ms)", ms)).unwrap_or_default();
+            lines.push(format!("  {} {}{}: {}", check.status.icon(), check.name, latency, check.detail));
+        }
+        lines.push(format!("{} - {} passed, {} warnings, {} failed",
+            if self.passed() { "PASS" } else { "FAIL" },
+            self.count(CheckStatus::Pass), self.count(CheckStatus::Warn), self.count(CheckStatus::Fail)));
+        lines
+    }
+}
+
+/// ABI-encoded ERC-20 `balanceOf(owner)` calldata
+pub fn erc20_balance_calldata(owner: &str) -> Result<String, registry::RegistryError> {
+    let owner = registry::checksum(owner)?;
+    let mut data = selector("balanceOf(address)").to_vec();
+    data.extend_from_slice(&[0u8; 12]);
+    data.extend(hex::decode(&owner[2..]).map_err(|_| registry::RegistryError::InvalidAddress(owner.clone()))?);
+    Ok(format!("0x{}", hex::encode(data)))
+}
+
+/// Integer from a JSON-RPC hex quantity or 32-byte word (low 128 bits)
+fn parse_quantity(hex: &str) -> Option<u128> {
+    let digits = hex.trim_start_matches("0x").trim_start_matches('0');
+    if digits.len() > 32 {
+        return None;
+    }
+    u128::from_str_radix(if digits.is_empty() { "0" } else { digits }, 16).ok()
+}
+
+async fn timed<T>(work: impl Future<Output = T>) -> (T, f64) {
+    let started = Instant::now();
+    let result = work.await;
+    (result, started.elapsed().as_secs_f64() * 1000.0)
+}
+
+/// Runs the checks against one config
+pub struct Doctor<'a> {
+    config: &'a Config,
+    client: reqwest::Client,
+    timeout: Duration,
+    /// Providers balances are read (and cross-checked) through
+    rpc_pool: RpcPool,
+    report: Report,
+}
+
+impl<'a> Doctor<'a> {
+    pub fn new(config: &'a Config) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+            timeout: Duration::from_millis(config.doctor.timeout_ms),
+            rpc_pool: RpcPool::from_config(config).with_timeout(Duration::from_millis(config.doctor.timeout_ms)),
+            report: Report::default(),
+        }
+    }
+
+    /// Pass, or warn when slower than `doctor.max_latency_ms`
+    fn reachable(&mut self, section: &'static str, name: &str, detail: String, latency_ms: f64) {
+        let status = match latency_ms > self.config.doctor.max_latency_ms as f64 {
+            true => CheckStatus::Warn,
+            false => CheckStatus::Pass,
+        };
+        let detail = match status {
+            CheckStatus::Warn => format!("{} - slower than {}ms", detail, self.config.doctor.max_latency_ms),
+            _ => detail,
+        };
+        self.report.push(section, name, status, detail, Some(latency_ms));
+    }
+
+    /// Run every check
+    pub async fn run(mut self) -> Report {
+        let config = self.config;
+        let mode = config.mode.as_deref().unwrap_or_default();
+
+        // Config
+        match config.validate() {
+            Ok(()) => self.report.push("config", "validate", CheckStatus::Pass, format!("mode {}", if mode.is_empty() { "polymarket" } else { mode }), None),
+            Err(e) => self.report.push("config", "validate", CheckStatus::Fail, e, None),
+        }
+        let registry = match Registry::from_config(&config.registry) {
+            Ok(registry) => {
+                self.report.push("config", "registry", CheckStatus::Pass, format!("{} contract addresses", registry.entries().count()), None);
+                Some(registry)
+            }
+            Err(e) => {
+                self.report.push("config", "registry", CheckStatus::Fail, e.to_string(), None);
+                None
+            }
+        };
+
+        self.connectivity(mode).await;
+        self.permission(registry.as_ref());
+        let signer = self.signer(mode).await;
+        self.balances(registry.as_ref(), signer.as_deref()).await;
+        self.dry_scan(mode).await;
+        self.report
+    }
+
+    async fn connectivity(&mut self, mode: &str) {
+        let config = self.config;
+        let polymarket = mode != "arbitrum_demo" || config.routing.enabled;
+        for (name, url) in [("gamma", config.api.gamma_url.clone()), ("clob", format!("{}/time", config.api.clob_url.trim_end_matches('/')))] {
+            let (response, ms) = timed(self.client.get(&url).timeout(self.timeout).send()).await;
+            match response {
+                Ok(r) if !r.status().is_server_error() => self.reachable("connectivity", name, format!("{} → {}", url, r.status()), ms),
+                Ok(r) => self.report.push("connectivity", name, CheckStatus::unless_optional(polymarket), format!("{} → {}", url, r.status()), Some(ms)),
+                Err(e) => self.report.push("connectivity", name, CheckStatus::unless_optional(polymarket), format!("{}: {}", url, e), None),
+            }
+        }
+
+        if let Some(arbitrum) = &config.arbitrum {
+            let envio = mode == "arbitrum_demo" || config.routing.enabled;
+            let request = self.client.post(&arbitrum.envio_endpoint).json(&json!({ "query": "{ __typename }" })).timeout(self.timeout).send();
+            let (response, ms) = timed(request).await;
+            match response {
+                Ok(r) if r.status().is_success() => self.reachable("connectivity", "envio", format!("{} → {}", arbitrum.envio_endpoint, r.status()), ms),
+                Ok(r) => self.report.push("connectivity", "envio", CheckStatus::unless_optional(envio), format!("{} → {}", arbitrum.envio_endpoint, r.status()), Some(ms)),
+                Err(e) => self.report.push("connectivity", "envio", CheckStatus::unless_optional(envio), format!("{}: {}", arbitrum.envio_endpoint, e), None),
+            }
+        }
+
+        // (name, url, chain id it must serve, whether the mode needs it)
+        let mut rpcs = vec![("rpc holdings", config.holdings.rpc_url.clone(), config.holdings.chain_id, !config.holdings.owner.is_empty())];
+        if let Some(arbitrum) = &config.arbitrum {
+            let settles = mode == "arbitrum_demo" || config.preflight.enabled;
+            rpcs.push(("rpc arbitrum sepolia", arbitrum.sepolia_rpc.clone(), arbitrum.sepolia_chain_id, settles));
+            rpcs.push(("rpc arbitrum mainnet", arbitrum.mainnet_rpc.clone(), arbitrum.mainnet_chain_id, false));
+        }
+        if !config.preflight.rpc_url.is_empty() {
+            let chain_id = config.arbitrum.as_ref().map_or(registry::ARBITRUM_SEPOLIA, |a| a.sepolia_chain_id);
+            rpcs.push(("rpc preflight", config.preflight.rpc_url.clone(), chain_id, config.preflight.enabled));
+        }
+        // Fallback providers are never required, but must serve the right chain
+        let listed: Vec<String> = rpcs.iter().map(|(_, url, _, _)| url.clone()).collect();
+        for (chain_id, providers) in self.rpc_pool.snapshot() {
+            for provider in providers.into_iter().filter(|p| !listed.contains(&p.url)) {
+                rpcs.push(("rpc fallback", provider.url, chain_id, false));
+            }
+        }
+        for (name, url, expected, required) in rpcs {
+            let (chain_id, ms) = timed(registry::rpc_chain_id(&self.client, &url, self.timeout)).await;
+            match chain_id {
+                Ok(actual) if actual == expected => self.reachable("connectivity", name, format!("{} (chain {})", url, actual), ms),
+                Ok(actual) => self.report.push("connectivity", name, CheckStatus::Fail, format!("{} is chain {}, expected {}", url, actual, expected), Some(ms)),
+                Err(e) => self.report.push("connectivity", name, CheckStatus::unless_optional(required), format!("{}: {}", url, e), None),
+            }
+        }
+
+        let solana_collateral = config.permission.collateral(&config.assets).is_ok_and(|c| c.asset.chain == "solana");
+        let (version, ms) = timed(async {
+            tokio::task::spawn_blocking(|| SolanaManager::new().check_connection().map_err(|e| e.to_string()))
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|r| r)
+        }).await;
+        match version {
+            Ok(version) => self.reachable("connectivity", "solana", format!("devnet {}", version), ms),
+            Err(e) => self.report.push("connectivity", "solana", CheckStatus::unless_optional(solana_collateral), e, None),
+        }
+    }
+
+    fn permission(&mut self, registry: Option<&Registry>) {
+        let config = self.config;
+        let permission = &config.permission;
+        match permission.collateral(&config.assets) {
+            Ok(collateral) => self.report.push("permission", "limits", CheckStatus::Pass,
+                format!("{} per day for {} days ({} max per call)", collateral.format(collateral.from_usd(permission.daily_limit_usdc)),
+                    permission.duration_days,
+                    permission.max_per_tx_usdc.map_or("no".to_string(), |max| collateral.format(collateral.from_usd(max)))), None),
+            Err(e) => self.report.push("permission", "limits", CheckStatus::Fail, e, None),
+        }
+        if permission.max_per_tx_usdc.is_some_and(|max| max < config.trading.trade_size * 0.5) {
+            self.report.push("permission", "per-call cap", CheckStatus::Warn,
+                format!("max_per_tx_usdc is below a minimum two-leg bundle of trade_size {:.2}", config.trading.trade_size), None);
+        }
+        let invalid: Vec<String> = permission.allowed_targets.iter()
+            .filter_map(|t| registry::validate(t).err().map(|e| e.to_string()))
+            .collect();
+        if !invalid.is_empty() {
+            self.report.push("permission", "allowed targets", CheckStatus::Fail, invalid.join("; "), None);
+        }
+        let chain_id = config.arbitrum.as_ref().map_or(registry::ARBITRUM_SEPOLIA, |a| a.sepolia_chain_id);
+        match registry.map(|r| r.resolve(chain_id, Contract::Stylus)) {
+            Some(Ok(venue)) if permission.allowed_targets.is_empty()
+                || permission.allowed_targets.iter().any(|t| t.eq_ignore_ascii_case(venue)) =>
+                self.report.push("permission", "venue contract", CheckStatus::Pass, format!("{} may be called", venue), None),
+            Some(Ok(venue)) => self.report.push("permission", "venue contract", CheckStatus::Fail,
+                format!("{} is not in allowed_targets - every trade would be refused", venue), None),
+            Some(Err(e)) => self.report.push("permission", "venue contract", CheckStatus::Warn, e.to_string(), None),
+            None => {}
+        }
+    }
+
+    async fn signer(&mut self, mode: &str) -> Option<Box<dyn signer::Signer>> {
+        let Some(signer_config) = &self.config.signer else {
+            let status = CheckStatus::unless_optional(mode == "sandbox");
+            self.report.push("signer", "load", status, "no [signer] configured", None);
+            return None;
+        };
+        match signer::from_config(signer_config).await {
+            Ok(s) => {
+                self.report.push("signer", "load", CheckStatus::Pass, format!("{} signer (evm: {}, solana: {})", s.kind(),
+                    s.evm_address().unwrap_or_else(|| "-".to_string()), s.solana_pubkey().unwrap_or_else(|| "-".to_string())), None);
+                Some(s)
+            }
+            Err(e) => {
+                self.report.push("signer", "load", CheckStatus::Fail, e.to_string(), None);
+                None
+            }
+        }
+    }
+
+    async fn balances(&mut self, registry: Option<&Registry>, signer: Option<&dyn signer::Signer>) {
+        let config = self.config;
+        let Ok(collateral) = config.permission.collateral(&config.assets) else { return };
+        let daily_limit = collateral.from_usd(config.permission.daily_limit_usdc);
+        let grade = |balance: f64| match balance {
+            b if b <= 0.0 => CheckStatus::Fail,
+            b if b < daily_limit => CheckStatus::Warn,
+            _ => CheckStatus::Pass,
+        };
+
+        if collateral.asset.chain == "solana" {
+            let Some(pubkey) = signer.and_then(|s| s.solana_pubkey()) else {
+                self.report.push("balances", "collateral", CheckStatus::Warn, "no Solana key to check", None);
+                return;
+            };
+            let lamports = tokio::task::spawn_blocking({
+                let pubkey = pubkey.clone();
+                move || {
+                    let key = pubkey.parse().map_err(|e| format!("{:?}", e))?;
+                    SolanaManager::new().balance(&key).map_err(|e| e.to_string())
+                }
+            }).await.map_err(|e| e.to_string()).and_then(|r| r);
+            match lamports {
+                Ok(lamports) => {
+                    let balance = collateral.asset.from_base_units(lamports as u128);
+                    self.report.push("balances", "collateral", grade(balance),
+                        format!("{} holds {} (daily limit {})", pubkey, collateral.format(balance), collateral.format(daily_limit)), None);
+                }
+                Err(e) => self.report.push("balances", "collateral", CheckStatus::Warn, e, None),
+            }
+            return;
+        }
+
+        let chain_id = config.arbitrum.as_ref().map_or(registry::ARBITRUM_SEPOLIA, |a| a.sepolia_chain_id);
+        let signer_address = signer.and_then(|s| s.evm_address());
+        let holder = Some(config.preflight.smart_account.clone()).filter(|a| !a.is_empty()).or(signer_address.clone());
+        match (holder, registry.map(|r| r.resolve(chain_id, Contract::Usdc))) {
+            (None, _) => self.report.push("balances", "collateral", CheckStatus::Warn,
+                "no preflight.smart_account or signer address to check", None),
+            (_, None) => {}
+            (_, Some(Err(e))) => self.report.push("balances", "collateral", CheckStatus::Warn,
+                format!("{} (add it under [registry.overrides.{}])", e, chain_id), None),
+            (Some(holder), Some(Ok(token))) => {
+                let balance = match erc20_balance_calldata(&holder) {
+                    Ok(data) => self.rpc_pool.call_checked(chain_id, "eth_call", json!([{ "to": token, "data": data }, "latest"])).await.map_err(|e| e.to_string()),
+                    Err(e) => Err(e.to_string()),
+                };
+                match balance.map(|v| v.as_str().and_then(parse_quantity)) {
+                    Ok(Some(raw)) => {
+                        let balance = collateral.asset.from_base_units(raw);
+                        self.report.push("balances", "collateral", grade(balance),
+                            format!("{} holds {} (daily limit {})", holder, collateral.format(balance), collateral.format(daily_limit)), None);
+                    }
+                    Ok(None) => self.report.push("balances", "collateral", CheckStatus::Warn, "unreadable balanceOf result", None),
+                    Err(e) => self.report.push("balances", "collateral", CheckStatus::Warn, format!("chain {}: {}", chain_id, e), None),
+                }
+            }
+        }
+
+        if let Some(address) = signer_address {
+            let balance = self.rpc_pool.call_checked(chain_id, "eth_getBalance", json!([address, "latest"])).await.map_err(|e| e.to_string());
+            match balance.map(|v| v.as_str().and_then(parse_quantity)) {
+                Ok(Some(0)) => self.report.push("balances", "gas", CheckStatus::Warn, format!("{} has no ETH for gas on chain {}", address, chain_id), None),
+                Ok(Some(wei)) => self.report.push("balances", "gas", CheckStatus::Pass,
+                    format!("{} holds {:.6} ETH on chain {}", address, wei as f64 / 1e18, chain_id), None),
+                Ok(None) => self.report.push("balances", "gas", CheckStatus::Warn, "unreadable eth_getBalance result", None),
+                Err(e) => self.report.push("balances", "gas", CheckStatus::Warn, format!("chain {}: {}", chain_id, e), None),
+            }
+        }
+    }
+
+    /// Live markets priced at their book midpoints, run through the detector
+    async fn dry_scan(&mut self, mode: &str) {
+        let config = self.config;
+        let required = mode != "arbitrum_demo";
+        let gamma_url = match config.api.gamma_url.contains('?') {
+            true => config.api.gamma_url.clone(),
+            false => format!("{}?limit={}&active=true&closed=false", config.api.gamma_url, config.api.market_limit),
+        };
+        let client = PolymarketClient {
+            gamma_url,
+            clob_url: format!("{}/book", config.api.clob_url.trim_end_matches('/')),
+            trades_url: String::new(),
+            client: self.client.clone(),
+        };
+        let (markets, ms) = timed(client.get_markets()).await;
+        let mut markets = match markets {
+            Ok(markets) if !markets.is_empty() => markets,
+            Ok(_) => {
+                self.report.push("dry run", "markets", CheckStatus::unless_optional(required), "Gamma returned no markets", Some(ms));
+                return;
+            }
+            Err(e) => {
+                self.report.push("dry run", "markets", CheckStatus::unless_optional(required), e.to_string(), None);
+                return;
+            }
+        };
+        markets.retain(|m| m.active && m.accepting_orders && !m.clob_token_ids.is_empty());
+        markets.truncate(config.doctor.sample_markets);
+        let mut books = 0;
+        let mut book_errors = Vec::new();
+        for market in &mut markets {
+            for (i, token_id) in market.clob_token_ids.clone().iter().enumerate() {
+                match client.get_order_book(token_id).await {
+                    Ok(book) => {
+                        books += 1;
+                        if let (Some(price), Some(mid)) = (market.outcome_prices.get_mut(i), book.midpoint()) {
+                            *price = mid;
+                        }
+                    }
+                    Err(e) => book_errors.push(format!("{}: {}", token_id, e)),
+                }
+            }
+        }
+        let detector = ArbitrageDetector::new(config.trading.min_spread_threshold, config.trading.min_profit_threshold);
+        let signals = detector.scan(&markets);
+        let best = signals.iter().map(|s| s.edge).fold(None, |best: Option<f64>, e| Some(best.map_or(e, |b| b.max(e))));
+        let detail = format!("{} markets, {} books, {} signals{}", markets.len(), books, signals.len(),
+            best.map(|e| format!(" (best edge {:.4})", e)).unwrap_or_default());
+        match book_errors.first() {
+            None => self.report.push("dry run", "signal scan", CheckStatus::Pass, detail, Some(ms)),
+            Some(first) => self.report.push("dry run", "signal scan", CheckStatus::unless_optional(required && books == 0),
+                format!("{} - {} book fetches failed, e.g. {}", detail, book_errors.len(), first), Some(ms)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_and_encoding() {
+        let mut report = Report::default();
+        report.push("connectivity", "gamma", CheckStatus::Pass, "ok", Some(120.4));
+        report.push("connectivity", "envio", CheckStatus::Warn, "slow", Some(2400.0));
+        assert!(report.passed());
+        report.push("permission", "venue contract", CheckStatus::Fail, "not allowed", None);
+        assert!(!report.passed());
+        let lines = report.lines();
+        assert_eq!(lines[0], "[connectivity]");
+        assert_eq!(lines[1], "  ✅ gamma (120ms): ok");
+        assert_eq!(lines[3], "[permission]");
+        assert_eq!(lines.last().unwrap(), "FAIL - 1 passed, 1 warnings, 1 failed");
+
+        let calldata = erc20_balance_calldata("0x4bfb41d5b3570defd03c39a9a4d8de6bd8b8982e").unwrap();
+        assert_eq!(calldata, "0x70a082310000000000000000000000004bfb41d5b3570defd03c39a9a4d8de6bd8b8982e");
+        assert_eq!(parse_quantity("0x00000000000000000000000000000000000000000000000000000000004c4b40"), Some(5_000_000));
+        assert_eq!(parse_quantity("0x0"), Some(0));
+    }
+}
@@ -0,0 +1,367 @@
+//! Market question embeddings for semantic grouping, cached per question and
+//! journaled, with neighbours served from a random-hyperplane LSH index
+
+use crate::config::EmbeddingConfig;
+use crate::mapping::question_tokens_ordered;
+use crate::storage::JsonlStore;
+use crate::types::Market;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+
+/// Hyperplane seed, fixed so the index is reproducible across runs
+const LSH_SEED: u64 = 0x5eed_a11c_e5ee_d5ed;
+
+#[derive(Debug)]
+pub enum EmbeddingError {
+    Http(String),
+    Response(String),
+}
+
+impl std::fmt::Display for EmbeddingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "embedding request failed: {}", e),
+            Self::Response(e) => write!(f, "unexpected embedding response: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for EmbeddingError {}
+
+/// Turns texts into vectors (one per text, same order)
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// Model identity; cached vectors of another model are re-embedded
+    fn model(&self) -> &str;
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError>;
+}
+
+/// Local embedder: unigrams and bigrams of the normalized question hashed
+/// into `dims` signed buckets. Captures shared vocabulary rather than
+/// meaning, but runs offline and deterministically.
+pub struct HashingEmbedder {
+    dims: usize,
+    model: String,
+}
+
+impl HashingEmbedder {
+    pub fn new(dims: usize) -> Self {
+        Self { dims: dims.max(8), model: format!("hashing-{}", dims.max(8)) }
+    }
+
+    fn embed_one(&self, text: &str) -> Vec<f32> {
+        let tokens = question_tokens_ordered(text);
+        let bigrams = tokens.windows(2).map(|w| format!("{} {}", w[0], w[1]));
+        let mut vector = vec![0.0f32; self.dims];
+        for feature in tokens.iter().cloned().chain(bigrams) {
+            let hash = Sha256::digest(feature.as_bytes());
+            let bucket = u64::from_le_bytes(hash[..8].try_into().unwrap()) as usize % self.dims;
+            vector[bucket] += if hash[8] & 1 == 0 { 1.0 } else { -1.0 };
+        }
+        normalize(vector)
+    }
+}
+
+#[async_trait]
+impl Embedder for HashingEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        Ok(texts.iter().map(|t| self.embed_one(t)).collect())
+    }
+}
+
+/// OpenAI-compatible `POST {url}` with `{"model", "input": [...]}`
+pub struct HttpEmbedder {
+    url: String,
+    model: String,
+    api_key: Option<String>,
+    client: reqwest::Client,
+}
+
+impl HttpEmbedder {
+    pub fn new(url: &str, model: &str, api_key: Option<String>) -> Self {
+        Self { url: url.to_string(), model: model.to_string(), api_key, client: reqwest::Client::new() }
+    }
+}
+
+#[async_trait]
+impl Embedder for HttpEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        let mut request = self.client.post(&self.url)
+            .json(&serde_json::json!({ "model": self.model, "input": texts }));
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let resp = request.send().await.map_err(|e| EmbeddingError::Http(e.to_string()))?;
+        if !resp.status().is_success() {
+            return Err(EmbeddingError::Http(format!("status {}", resp.status())));
+        }
+        let json: serde_json::Value = resp.json().await.map_err(|e| EmbeddingError::Response(e.to_string()))?;
+        let data = json["data"].as_array().ok_or_else(|| EmbeddingError::Response("missing data".to_string()))?;
+        if data.len() != texts.len() {
+            return Err(EmbeddingError::Response(format!("{} vectors for {} inputs", data.len(), texts.len())));
+        }
+        data.iter().map(|item| {
+            item["embedding"].as_array()
+                .map(|v| normalize(v.iter().filter_map(|x| x.as_f64()).map(|x| x as f32).collect()))
+                .ok_or_else(|| EmbeddingError::Response("missing embedding".to_string()))
+        }).collect()
+    }
+}
+
+/// Embedder selected by config (`provider = "hashing"` or `"http"`)
+pub fn from_config(config: &EmbeddingConfig) -> Box<dyn Embedder> {
+    match config.provider.as_str() {
+        "http" => Box::new(HttpEmbedder::new(&config.url, &config.model, std::env::var(&config.api_key_env).ok())),
+        _ => Box::new(HashingEmbedder::new(config.dims)),
+    }
+}
+
+fn normalize(mut v: Vec<f32>) -> Vec<f32> {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        v.iter_mut().for_each(|x| *x /= norm);
+    }
+    v
+}
+
+/// Cosine similarity of two unit vectors
+fn cosine(a: &[f32], b: &[f32]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x * y) as f64).sum()
+}
+
+/// Journaled cache entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedVector {
+    market_id: String,
+    model: String,
+    /// sha256 of the question, so a reworded question is re-embedded
+    question_hash: String,
+    vector: Vec<f32>,
+}
+
+fn question_hash(question: &str) -> String {
+    hex::encode(&Sha256::digest(question.as_bytes())[..12])
+}
+
+/// Random-hyperplane LSH over unit vectors
+#[derive(Debug, Default)]
+struct LshIndex {
+    /// planes[table][bit] = hyperplane normal
+    planes: Vec<Vec<Vec<f32>>>,
+    buckets: Vec<HashMap<u64, Vec<usize>>>,
+}
+
+impl LshIndex {
+    fn build(vectors: &[&[f32]], tables: usize, bits: usize) -> Self {
+        let dims = vectors.first().map_or(0, |v| v.len());
+        // xorshift64: deterministic Gaussian-free planes are fine for sign hashing
+        let mut state = LSH_SEED;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state >> 11) as f32 / (1u64 << 53) as f32 * 2.0 - 1.0
+        };
+        let planes: Vec<Vec<Vec<f32>>> = (0..tables)
+            .map(|_| (0..bits.min(64)).map(|_| (0..dims).map(|_| next()).collect()).collect())
+            .collect();
+        let mut index = Self { buckets: vec![HashMap::new(); tables], planes };
+        for (i, v) in vectors.iter().enumerate() {
+            for t in 0..tables {
+                let key = index.key(t, v);
+                index.buckets[t].entry(key).or_default().push(i);
+            }
+        }
+        index
+    }
+
+    fn key(&self, table: usize, v: &[f32]) -> u64 {
+        self.planes[table].iter().enumerate()
+            .fold(0u64, |key, (bit, plane)| if cosine(plane, v) >= 0.0 { key | (1 << bit) } else { key })
+    }
+
+    fn candidates(&self, v: &[f32]) -> HashSet<usize> {
+        (0..self.planes.len())
+            .filter_map(|t| self.buckets[t].get(&self.key(t, v)))
+            .flatten()
+            .copied()
+            .collect()
+    }
+}
+
+/// A semantically related market
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RelatedMarket {
+    pub market_id: String,
+    pub similarity: f64,
+}
+
+/// Result of a universe refresh
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RefreshStats {
+    /// Questions sent to the embedder
+    pub embedded: usize,
+    /// Markets in the index
+    pub indexed: usize,
+}
+
+/// Embedding cache plus ANN index over the current market universe
+#[derive(Debug)]
+pub struct SemanticIndex {
+    config: EmbeddingConfig,
+    cache: HashMap<String, CachedVector>,
+    journal: Option<JsonlStore>,
+    /// Indexed market ids (index positions) and the id set they were built from
+    ids: Vec<String>,
+    index: LshIndex,
+}
+
+impl SemanticIndex {
+    pub fn new(config: EmbeddingConfig) -> Self {
+        Self { config, cache: HashMap::new(), journal: None, ids: Vec::new(), index: LshIndex::default() }
+    }
+
+    /// Journal embeddings to `journal`, loading those already cached there
+    pub fn with_journal(mut self, journal: JsonlStore) -> Self {
+        let cached: Vec<CachedVector> = journal.load().unwrap_or_else(|e| {
+            eprintln!("⚠️ [Embeddings] Failed to read embedding cache: {}", e);
+            Vec::new()
+        });
+        for entry in cached {
+            self.cache.insert(entry.market_id.clone(), entry);
+        }
+        self.journal = Some(journal);
+        self
+    }
+
+    pub fn cached(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Embed questions that are new or changed, then rebuild the index if
+    /// the universe changed. Embedding failures leave the old index serving.
+    pub async fn refresh(&mut self, embedder: &dyn Embedder, markets: &[Market]) -> Result<RefreshStats, EmbeddingError> {
+        let stale: Vec<&Market> = markets.iter()
+            .filter(|m| self.cache.get(&m.id).is_none_or(|c| c.model != embedder.model() || c.question_hash != question_hash(&m.question)))
+            .collect();
+        for batch in stale.chunks(self.config.batch_size.max(1)) {
+            let questions: Vec<String> = batch.iter().map(|m| m.question.clone()).collect();
+            let vectors = embedder.embed(&questions).await?;
+            for (market, vector) in batch.iter().zip(vectors) {
+                let entry = CachedVector {
+                    market_id: market.id.clone(),
+                    model: embedder.model().to_string(),
+                    question_hash: question_hash(&market.question),
+                    vector,
+                };
+                if let Some(journal) = &self.journal {
+                    if let Err(e) = journal.append(&entry) {
+                        eprintln!("⚠️ [Embeddings] Failed to cache embedding: {}", e);
+                    }
+                }
+                self.cache.insert(market.id.clone(), entry);
+            }
+        }
+        let mut ids: Vec<String> = markets.iter().map(|m| m.id.clone()).filter(|id| self.cache.contains_key(id)).collect();
+        ids.sort();
+        if ids != self.ids || !stale.is_empty() {
+            let vectors: Vec<&[f32]> = ids.iter().map(|id| self.cache[id].vector.as_slice()).collect();
+            self.index = LshIndex::build(&vectors, self.config.lsh_tables, self.config.lsh_bits);
+            self.ids = ids;
+        }
+        Ok(RefreshStats { embedded: stale.len(), indexed: self.ids.len() })
+    }
+
+    /// Up to `neighbors` indexed markets at or above `min_similarity` to `market_id`, closest first
+    pub fn related(&self, market_id: &str) -> Vec<RelatedMarket> {
+        let Some(query) = self.cache.get(market_id).filter(|_| self.ids.iter().any(|id| id == market_id)) else {
+            return Vec::new();
+        };
+        let mut related: Vec<RelatedMarket> = self.index.candidates(&query.vector).into_iter()
+            .map(|i| &self.ids[i])
+            .filter(|id| id.as_str() != market_id)
+            .map(|id| RelatedMarket { market_id: id.clone(), similarity: cosine(&query.vector, &self.cache[id].vector) })
+            .filter(|r| r.similarity >= self.config.min_similarity)
+            .collect();
+        related.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+        related.truncate(self.config.neighbors);
+        related
+    }
+
+    /// Related pairs across the indexed universe (each pair once)
+    pub fn groups(&self) -> Vec<(String, RelatedMarket)> {
+        self.ids.iter()
+            .flat_map(|id| self.related(id).into_iter().map(move |r| (id.clone(), r)))
+            .filter(|(id, r)| *id < r.market_id)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn market(id: &str, question: &str) -> Market {
+        Market { id: id.to_string(), question: question.to_string(), ..testing::market() }
+    }
+
+    fn markets() -> Vec<Market> {
+        vec![
+            market("fed-cut", "Will the Fed cut interest rates in March 2026?"),
+            market("fed-cut-2", "Will the Fed cut interest rates in March?"),
+            market("btc", "Will Bitcoin close above $100k on Friday?"),
+        ]
+    }
+
+    fn config() -> EmbeddingConfig {
+        EmbeddingConfig { min_similarity: 0.5, ..Default::default() }
+    }
+
+    #[tokio::test]
+    async fn test_paraphrases_related() {
+        let embedder = HashingEmbedder::new(config().dims);
+        let mut index = SemanticIndex::new(config());
+        assert_eq!(index.refresh(&embedder, &markets()).await.unwrap(), RefreshStats { embedded: 3, indexed: 3 });
+        let related = index.related("fed-cut");
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].market_id, "fed-cut-2");
+        assert!(index.related("btc").is_empty());
+        assert_eq!(index.groups().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_only_reworded_questions_embedded_again() {
+        let embedder = HashingEmbedder::new(config().dims);
+        let mut index = SemanticIndex::new(config());
+        let mut markets = markets();
+        index.refresh(&embedder, &markets).await.unwrap();
+        markets[2].question = "Will Bitcoin close above $120k on Friday?".to_string();
+        assert_eq!(index.refresh(&embedder, &markets).await.unwrap().embedded, 1);
+    }
+
+    #[tokio::test]
+    async fn test_cache_restored_from_journal() {
+        let dir = std::env::temp_dir().join(format!("arbishark_embeddings_{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+        let journal = JsonlStore::open(dir, "embeddings.jsonl").unwrap();
+        let embedder = HashingEmbedder::new(config().dims);
+        let mut index = SemanticIndex::new(config()).with_journal(journal.clone());
+        index.refresh(&embedder, &markets()).await.unwrap();
+
+        let mut restored = SemanticIndex::new(config()).with_journal(journal);
+        assert_eq!(restored.refresh(&embedder, &markets()).await.unwrap().embedded, 0);
+        assert_eq!(restored.related("fed-cut-2")[0].market_id, "fed-cut");
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -1,15 +1,48 @@
 //! Trading Engine Module
 //!
 //! Orchestrates the main trading loop with safety controls and failure handling.
+//! Embedders assemble an engine from the crate's components with
+//! [`EngineBuilder`]: any `MarketClient` venue, any [`Strategy`], and an
+//! optional `RiskManager` that halts and scales entries.
 
-use crate::types::Side;
+use crate::types::{ArbitrageSignal, Market, Side};
 use crate::wallet::Wallet;
 use crate::market_client::MarketClient;
 use crate::arb::ArbitrageDetector;
+use crate::budgets::ARB_STRATEGY;
 use crate::execution::ExecutionEngine;
-use crate::config::SafetyConfig;
+use crate::fees::FeeModel;
+use crate::latency::LatencyModel;
+use crate::config::{SafetyConfig, SlippageGuardConfig};
+use crate::competition::{CompetitorModel, RaceOutcome};
+use crate::risk::RiskManager;
+use crate::sizing::PositionSizer;
+use crate::slippage_guard::LimitCeilings;
 use std::time::{Duration, Instant};
 
+/// Per-leg trade size when none is configured (USDC)
+const DEFAULT_TRADE_SIZE: f64 = 5.0;
+/// Daily limit of the default wallet (USDC)
+const DEFAULT_DAILY_LIMIT: f64 = 10.0;
+
+/// Signal source driving a `TradingEngine`
+pub trait Strategy: Send + Sync {
+    /// Strategy name (budget / attribution key)
+    fn name(&self) -> &str;
+    /// Signals worth trading in the current market snapshot
+    fn scan(&self, markets: &[Market]) -> Vec<ArbitrageSignal>;
+}
+
+impl Strategy for ArbitrageDetector {
+    fn name(&self) -> &str {
+        ARB_STRATEGY
+    }
+
+    fn scan(&self, markets: &[Market]) -> Vec<ArbitrageSignal> {
+        ArbitrageDetector::scan(self, markets)
+    }
+}
+
 /// Agent operational status for monitoring
 #[derive(Debug, Clone, PartialEq)]
 pub enum EngineStatus {
@@ -27,30 +60,41 @@ pub enum EngineStatus {
 pub struct TradingEngine<M: MarketClient + Send + Sync> {
     pub wallet: Wallet,
     pub market_client: M,
-    pub detector: ArbitrageDetector,
+    pub strategy: Box<dyn Strategy>,
     pub execution_engine: ExecutionEngine,
+    /// Halts and scales entries when set
+    pub risk: Option<RiskManager>,
+    sizer: PositionSizer,
     status: EngineStatus,
     consecutive_failures: u32,
     safety_config: SafetyConfig,
     last_data_fetch: Option<Instant>,
+    competitor: Option<CompetitorModel>,
+    slippage_guard: SlippageGuardConfig,
+    gas_per_leg_usd: f64,
 }
 
 impl<M: MarketClient + Send + Sync> TradingEngine<M> {
     pub fn new(
         wallet: Wallet,
         market_client: M,
-        detector: ArbitrageDetector,
+        strategy: impl Strategy + 'static,
         execution_engine: ExecutionEngine,
     ) -> Self {
         Self {
             wallet,
             market_client,
-            detector,
+            strategy: Box::new(strategy),
             execution_engine,
+            risk: None,
+            sizer: PositionSizer::new(DEFAULT_TRADE_SIZE, f64::MAX),
             status: EngineStatus::Running,
             consecutive_failures: 0,
             safety_config: SafetyConfig::default(),
             last_data_fetch: None,
+            competitor: None,
+            slippage_guard: SlippageGuardConfig::default(),
+            gas_per_leg_usd: 0.0,
         }
     }
 
@@ -60,6 +104,24 @@ impl<M: MarketClient + Send + Sync> TradingEngine<M> {
         self
     }
 
+    /// Race a simulated competitor for every detected edge (backtests)
+    pub fn with_competitor(mut self, competitor: CompetitorModel) -> Self {
+        self.competitor = Some(competitor);
+        self
+    }
+
+    /// Edge per set kept if every leg fills at its limit, and the gas each leg costs
+    pub fn with_slippage_guard(mut self, config: SlippageGuardConfig, gas_per_leg_usd: f64) -> Self {
+        self.slippage_guard = config;
+        self.gas_per_leg_usd = gas_per_leg_usd;
+        self
+    }
+
+    /// Competitor model, if competition is simulated
+    pub fn competitor(&self) -> Option<&CompetitorModel> {
+        self.competitor.as_ref()
+    }
+
     /// Get current engine status
     pub fn get_status(&self) -> &EngineStatus {
         &self.status
@@ -105,6 +167,8 @@ impl<M: MarketClient + Send + Sync> TradingEngine<M> {
                 reason: format!("{} consecutive API failures", self.consecutive_failures),
                 until: Instant::now() + cooldown,
             };
+            // Nothing may stay resting while we are not watching it
+            self.execution_engine.cancel_all_orders("circuit breaker");
             return false;
         }
 
@@ -146,21 +210,58 @@ impl<M: MarketClient + Send + Sync> TradingEngine<M> {
                 return Err(e);
             }
         };
-        let signals = self.detector.scan(&markets);
+        if let Some(risk) = &self.risk {
+            if let (true, reason) = risk.should_halt() {
+                println!("🛑 [Engine] Risk halt: {}", reason.unwrap_or_default());
+                return Ok(());
+            }
+        }
+        let signals = self.strategy.scan(&markets);
         for signal in signals {
             if signal.recommended_side == Side::Buy {
+                if let Some(competitor) = &mut self.competitor {
+                    let our_latency_ms = self.execution_engine.latency_model.mean_delay_ms as f64;
+                    if let RaceOutcome::Lost { competitor_latency_ms } = competitor.race(our_latency_ms) {
+                        println!("🏎️ [Engine] Edge on {} taken by competitor ({:.0}ms < {:.0}ms)",
+                            signal.market_id, competitor_latency_ms, our_latency_ms);
+                        continue;
+                    }
+                }
                 if let Some(market) = markets.iter().find(|m| m.id == signal.market_id) {
-                    let size_per_leg = 5.0;
+                    let size_per_leg = match &self.risk {
+                        Some(risk) => match self.sizer.size(1.0, risk) {
+                            Some(size) => size,
+                            None => continue,
+                        },
+                        None => self.sizer.base_size.min(self.sizer.max_position_value),
+                    };
+                    let mut books = Vec::new();
                     for token_id in &market.clob_token_ids {
                         match self.market_client.get_order_book(token_id).await {
-                            Ok(book) => {
-                                self.execution_engine.execute(&book, size_per_leg, Side::Buy, &mut self.wallet);
-                            }
+                            Ok(book) => books.push(book),
                             Err(e) => {
                                 println!("⚠️ [Engine] Order book fetch failed: {}", e);
+                                break;
                             }
                         }
                     }
+                    if books.len() != market.clob_token_ids.len() {
+                        continue;
+                    }
+                    // Every leg goes out with a limit price the set's economics can afford
+                    let ceilings = LimitCeilings::for_bundle(&books, size_per_leg, self.execution_engine.fee_model.taker_rate(),
+                        self.gas_per_leg_usd, self.slippage_guard.min_edge);
+                    let Some(mut ceilings) = ceilings else {
+                        println!("⚠️ [Engine] No limit price on {} keeps ${:.3} of edge per set, not submitting",
+                            market.id, self.slippage_guard.min_edge);
+                        continue;
+                    };
+                    for (leg, book) in books.iter().enumerate() {
+                        match self.execution_engine.execute_with_limit(book, size_per_leg, Side::Buy, &mut self.wallet, Some(ceilings.ceiling(leg))) {
+                            Ok(result) => ceilings.record_fill(leg, result.execution_price),
+                            Err(failure) => println!("⚠️ [Engine] Leg {} failed: {}", book.token_id, failure),
+                        }
+                    }
                 }
             }
         }
@@ -179,3 +280,246 @@ impl<M: MarketClient + Send + Sync> TradingEngine<M> {
         }
     }
 }
+
+/// Reason an `EngineBuilder` could not build
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuildError {
+    MissingClient,
+    MissingStrategy,
+}
+
+impl std::fmt::Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingClient => write!(f, "EngineBuilder needs a market client (with_client)"),
+            Self::MissingStrategy => write!(f, "EngineBuilder needs a strategy (with_strategy)"),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+/// Assembles a `TradingEngine` from the crate's components
+///
+/// ```ignore
+/// let engine = EngineBuilder::new()
+///     .with_client(ArbitrumMarketClient::new(endpoint))
+///     .with_strategy(ArbitrageDetector::new(0.02, 0.10))
+///     .with_risk(RiskManager::new(RiskConfig::default(), 100.0))
+///     .build()?;
+/// ```
+///
+/// The client and strategy are required; the wallet, execution engine and
+/// trade size default to the values in the shipped config.toml.
+pub struct EngineBuilder<M: MarketClient + Send + Sync> {
+    client: Option<M>,
+    strategy: Option<Box<dyn Strategy>>,
+    wallet: Option<Wallet>,
+    execution_engine: Option<ExecutionEngine>,
+    risk: Option<RiskManager>,
+    safety_config: SafetyConfig,
+    competitor: Option<CompetitorModel>,
+    slippage_guard: SlippageGuardConfig,
+    gas_per_leg_usd: f64,
+    trade_size: f64,
+    max_position_value: f64,
+}
+
+impl<M: MarketClient + Send + Sync> Default for EngineBuilder<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M: MarketClient + Send + Sync> EngineBuilder<M> {
+    pub fn new() -> Self {
+        Self {
+            client: None,
+            strategy: None,
+            wallet: None,
+            execution_engine: None,
+            risk: None,
+            safety_config: SafetyConfig::default(),
+            competitor: None,
+            slippage_guard: SlippageGuardConfig::default(),
+            gas_per_leg_usd: 0.0,
+            trade_size: DEFAULT_TRADE_SIZE,
+            max_position_value: f64::MAX,
+        }
+    }
+
+    /// Venue the engine reads markets and books from
+    pub fn with_client(mut self, client: M) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Signal source
+    pub fn with_strategy(mut self, strategy: impl Strategy + 'static) -> Self {
+        self.strategy = Some(Box::new(strategy));
+        self
+    }
+
+    /// Halt on risk limits and scale size by drawdown / volatility
+    pub fn with_risk(mut self, risk: RiskManager) -> Self {
+        self.risk = Some(risk);
+        self
+    }
+
+    pub fn with_wallet(mut self, wallet: Wallet) -> Self {
+        self.wallet = Some(wallet);
+        self
+    }
+
+    pub fn with_execution(mut self, execution_engine: ExecutionEngine) -> Self {
+        self.execution_engine = Some(execution_engine);
+        self
+    }
+
+    pub fn with_safety_config(mut self, config: SafetyConfig) -> Self {
+        self.safety_config = config;
+        self
+    }
+
+    pub fn with_competitor(mut self, competitor: CompetitorModel) -> Self {
+        self.competitor = Some(competitor);
+        self
+    }
+
+    /// Limit prices keep `config.min_edge` per set after `gas_per_leg_usd` (default: no floor, no gas)
+    pub fn with_slippage_guard(mut self, config: SlippageGuardConfig, gas_per_leg_usd: f64) -> Self {
+        self.slippage_guard = config;
+        self.gas_per_leg_usd = gas_per_leg_usd;
+        self
+    }
+
+    /// Per-leg size before risk scaling, capped at `max_position_value`
+    pub fn with_trade_size(mut self, trade_size: f64, max_position_value: f64) -> Self {
+        self.trade_size = trade_size;
+        self.max_position_value = max_position_value;
+        self
+    }
+
+    pub fn build(self) -> Result<TradingEngine<M>, BuildError> {
+        let client = self.client.ok_or(BuildError::MissingClient)?;
+        let strategy = self.strategy.ok_or(BuildError::MissingStrategy)?;
+        let execution_engine = self.execution_engine.unwrap_or_else(|| ExecutionEngine::new(
+            FeeModel { maker_fee_bps: 0, taker_fee_bps: 200 },
+            LatencyModel::new(50, 0.001),
+        ));
+        Ok(TradingEngine {
+            wallet: self.wallet.unwrap_or_else(|| Wallet::new(DEFAULT_DAILY_LIMIT)),
+            market_client: client,
+            strategy,
+            execution_engine,
+            risk: self.risk,
+            sizer: PositionSizer::new(self.trade_size, self.max_position_value),
+            status: EngineStatus::Running,
+            consecutive_failures: 0,
+            safety_config: self.safety_config,
+            last_data_fetch: None,
+            competitor: self.competitor,
+            slippage_guard: self.slippage_guard,
+            gas_per_leg_usd: self.gas_per_leg_usd,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::risk::RiskConfig;
+    use crate::types::{OrderBook, PriceLevel};
+    use async_trait::async_trait;
+    use std::error::Error;
+
+    struct OneMarket;
+
+    #[async_trait]
+    impl MarketClient for OneMarket {
+        async fn get_markets(&self) -> Result<Vec<Market>, Box<dyn Error + Send + Sync>> {
+            Ok(vec![Market {
+                id: "m1".to_string(),
+                question: "Q?".to_string(),
+                slug: "q".to_string(),
+                outcomes: vec!["Yes".to_string(), "No".to_string()],
+                outcome_prices: vec![0.45, 0.45],
+                clob_token_ids: vec!["y".to_string(), "n".to_string()],
+                condition_id: String::new(),
+                best_bid: None,
+                best_ask: None,
+                maker_base_fee: 0,
+                taker_base_fee: 200,
+                liquidity: 1000.0,
+                volume_24hr: 100.0,
+                active: true,
+                accepting_orders: true,
+                resolution_source: String::new(),
+                end_date: None,
+            }])
+        }
+        async fn get_order_book(&self, token_id: &str) -> Result<OrderBook, Box<dyn Error + Send + Sync>> {
+            Ok(OrderBook {
+                token_id: token_id.to_string(),
+                bids: vec![PriceLevel { price: 0.44, size: 100.0 }],
+                asks: vec![PriceLevel { price: 0.45, size: 100.0 }],
+                timestamp: 0,
+            })
+        }
+        async fn stream_quotes(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+            Ok(())
+        }
+    }
+
+    /// Trades every market it sees
+    struct Always;
+
+    impl Strategy for Always {
+        fn name(&self) -> &str {
+            "always"
+        }
+        fn scan(&self, markets: &[Market]) -> Vec<ArbitrageSignal> {
+            markets.iter().map(|m| ArbitrageSignal {
+                market_id: m.id.clone(),
+                spread: 0.1,
+                edge: 1.0,
+                recommended_side: Side::Buy,
+                yes_price: 0.45,
+                no_price: 0.45,
+                edge_curve: Vec::new(),
+                quote_ages_ms: Vec::new(),
+            }).collect()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_builder_assembles_custom_engine() {
+        assert_eq!(EngineBuilder::<OneMarket>::new().with_strategy(Always).build().err(), Some(BuildError::MissingClient));
+
+        let mut engine = EngineBuilder::new()
+            .with_client(OneMarket)
+            .with_strategy(Always)
+            .with_risk(RiskManager::new(RiskConfig::default(), 100.0))
+            .with_wallet(Wallet::new(100.0))
+            .with_trade_size(2.0, 50.0)
+            .build()
+            .unwrap();
+        assert_eq!(engine.strategy.name(), "always");
+        engine.tick().await.unwrap();
+        assert!(engine.wallet.spent_today > 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_engine_submits_nothing_without_a_limit_keeping_the_edge() {
+        // 0.45 + 0.45 plus 2% fees leaves $0.082 per set: a $0.10 floor has no limit price
+        let mut engine = EngineBuilder::new()
+            .with_client(OneMarket)
+            .with_strategy(Always)
+            .with_wallet(Wallet::new(100.0))
+            .with_slippage_guard(SlippageGuardConfig { min_edge: 0.10 }, 0.0)
+            .build()
+            .unwrap();
+        engine.tick().await.unwrap();
+        assert_eq!(engine.wallet.spent_today, 0.0);
+    }
+}
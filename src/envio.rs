@@ -0,0 +1,142 @@
+//! Typed Envio HyperIndex client
+//!
+//! Queries live in `envio/queries/` and are checked against the vendored API
+//! schema (`envio/api.graphql`) at compile time by graphql_client codegen.
+//! Responses decode into generated types, so a missing or renamed field is
+//! a decode error rather than a zero-filled `Market`.
+
+use crate::types::{Market, OrderBook, PriceLevel};
+use graphql_client::{GraphQLQuery, Response};
+use std::time::Duration;
+
+#[derive(GraphQLQuery)]
+#[graphql(schema_path = "envio/api.graphql", query_path = "envio/queries/markets.graphql", response_derives = "Debug")]
+pub struct MarketsQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(schema_path = "envio/api.graphql", query_path = "envio/queries/order_book.graphql", response_derives = "Debug")]
+pub struct OrderBookQuery;
+
+#[allow(dead_code)] // only used by ArbitrumMarketClient::health_check
+#[derive(GraphQLQuery)]
+#[graphql(schema_path = "envio/api.graphql", query_path = "envio/queries/meta.graphql", response_derives = "Debug")]
+pub struct MetaQuery;
+
+/// Envio client errors
+#[derive(Debug)]
+pub enum EnvioError {
+    Request(String),
+    Status(u16),
+    /// Response did not match the vendored schema
+    Decode(String),
+    GraphQl(Vec<String>),
+    /// No data and no errors, or a null where the query needs a value
+    MissingData(&'static str),
+}
+
+impl std::fmt::Display for EnvioError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Request(e) => write!(f, "Envio request failed: {}", e),
+            Self::Status(code) => write!(f, "Envio returned error: {}", code),
+            Self::Decode(e) => write!(f, "Envio response does not match schema: {}", e),
+            Self::GraphQl(errors) => write!(f, "GraphQL errors: {}", errors.join("; ")),
+            Self::MissingData(what) => write!(f, "Envio response missing {}", what),
+        }
+    }
+}
+
+impl std::error::Error for EnvioError {}
+
+/// Run a typed query against an Envio GraphQL endpoint
+pub async fn query<Q: GraphQLQuery>(
+    client: &reqwest::Client,
+    endpoint: &str,
+    variables: Q::Variables,
+    timeout: Duration,
+) -> Result<Q::ResponseData, EnvioError> {
+    let response = client.post(endpoint)
+        .json(&Q::build_query(variables))
+        .timeout(timeout)
+        .send()
+        .await
+        .map_err(|e| EnvioError::Request(e.to_string()))?;
+    if !response.status().is_success() {
+        return Err(EnvioError::Status(response.status().as_u16()));
+    }
+    let body = response.text().await.map_err(|e| EnvioError::Request(e.to_string()))?;
+    decode::<Q>(&body)
+}
+
+/// Decode a GraphQL response body into the query's generated types
+pub fn decode<Q: GraphQLQuery>(body: &str) -> Result<Q::ResponseData, EnvioError> {
+    let response: Response<Q::ResponseData> =
+        serde_json::from_str(body).map_err(|e| EnvioError::Decode(e.to_string()))?;
+    if let Some(errors) = response.errors.filter(|e| !e.is_empty()) {
+        return Err(EnvioError::GraphQl(errors.into_iter().map(|e| e.message).collect()));
+    }
+    response.data.ok_or(EnvioError::MissingData("data"))
+}
+
+impl From<markets_query::MarketsQueryMarkets> for Market {
+    fn from(m: markets_query::MarketsQueryMarkets) -> Self {
+        Market {
+            id: m.id,
+            question: m.question,
+            slug: m.slug,
+            outcomes: m.outcomes,
+            outcome_prices: m.outcome_prices,
+            clob_token_ids: m.clob_token_ids,
+            condition_id: m.condition_id.unwrap_or_default(),
+            best_bid: m.best_bid,
+            best_ask: m.best_ask,
+            maker_base_fee: m.maker_base_fee as u32,
+            taker_base_fee: m.taker_base_fee as u32,
+            liquidity: m.liquidity,
+            volume_24hr: m.volume24hr,
+            active: m.active,
+            accepting_orders: m.accepting_orders,
+            // Not indexed by Envio
+            resolution_source: String::new(),
+            end_date: None,
+        }
+    }
+}
+
+impl From<order_book_query::OrderBookQueryOrderBook> for OrderBook {
+    fn from(ob: order_book_query::OrderBookQueryOrderBook) -> Self {
+        OrderBook {
+            token_id: ob.token_id,
+            bids: ob.bids.into_iter().map(|l| PriceLevel { price: l.price, size: l.size }).collect(),
+            asks: ob.asks.into_iter().map(|l| PriceLevel { price: l.price, size: l.size }).collect(),
+            timestamp: ob.timestamp.max(0) as u64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_against_schema() {
+        let body = r#"{"data": {"markets": [{
+            "id": "m1", "question": "Q?", "slug": "q", "outcomes": ["Yes", "No"],
+            "outcomePrices": [0.48, 0.49], "clobTokenIds": ["y", "n"], "conditionId": null,
+            "bestBid": 0.47, "bestAsk": null, "makerBaseFee": 0, "takerBaseFee": 200,
+            "liquidity": 1500.0, "volume24hr": 320.5, "active": true, "acceptingOrders": true
+        }]}}"#;
+        let data = decode::<MarketsQuery>(body).unwrap();
+        let market: Market = data.markets.into_iter().next().unwrap().into();
+        assert_eq!(market.outcome_prices, vec![0.48, 0.49]);
+        assert_eq!(market.taker_base_fee, 200);
+        assert!(market.best_ask.is_none());
+
+        // A renamed field is an error, not a zero-filled market
+        let drifted = body.replace("outcomePrices", "prices");
+        assert!(matches!(decode::<MarketsQuery>(&drifted), Err(EnvioError::Decode(_))));
+
+        let errors = r#"{"data": null, "errors": [{"message": "field 'markets' not found"}]}"#;
+        assert!(matches!(decode::<MarketsQuery>(errors), Err(EnvioError::GraphQl(_))));
+    }
+}
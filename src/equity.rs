@@ -0,0 +1,224 @@
+//! Equity curve
+//!
+//! Samples account equity (realized balance plus open positions marked to
+//! market) every few minutes and journals each point, so `/api/equity` can
+//! serve the full history across restarts. Drawdown is recorded from the
+//! RiskManager's own status at sampling time, so the curve shows exactly
+//! the drawdown the risk limits act on. The same samples are bucketed into
+//! the realized / unrealized PnL series served on `/api/pnl`.
+
+use crate::risk::RiskStatus;
+use crate::storage::JsonlStore;
+use serde::{Deserialize, Serialize};
+use std::io;
+
+/// One sample of the equity curve
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EquityPoint {
+    pub timestamp: u64,
+    /// RiskManager balance: starting capital plus realized PnL
+    pub balance: f64,
+    /// Mark-to-market PnL of open positions
+    pub unrealized: f64,
+    /// balance + unrealized
+    pub equity: f64,
+    pub peak_balance: f64,
+    /// Drawdown from peak balance as enforced by the RiskManager (%)
+    pub drawdown_percent: f64,
+}
+
+/// `/api/equity` response body
+#[derive(Debug, Clone, Serialize)]
+pub struct EquityReport {
+    pub points: Vec<EquityPoint>,
+    /// Largest drawdown over the returned points (%)
+    pub max_drawdown_percent: f64,
+}
+
+/// Bucket width of the `/api/pnl` series
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PnlBucket {
+    #[default]
+    #[serde(rename = "1h")]
+    Hour,
+    #[serde(rename = "1d")]
+    Day,
+}
+
+impl PnlBucket {
+    pub fn secs(&self) -> u64 {
+        match self {
+            Self::Hour => 3_600,
+            Self::Day => 86_400,
+        }
+    }
+}
+
+/// PnL over one bucket, from the last sample in it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PnlPoint {
+    /// Bucket start (unix seconds, aligned to the bucket width in UTC)
+    pub start: u64,
+    /// Realized within the bucket
+    pub realized: f64,
+    /// Realized since the first journaled sample, at the bucket's close
+    pub cumulative_realized: f64,
+    /// Open positions marked to market at the bucket's close
+    pub unrealized: f64,
+    /// cumulative_realized + unrealized
+    pub total: f64,
+    pub samples: usize,
+}
+
+/// `/api/pnl` response body (buckets without samples are omitted)
+#[derive(Debug, Clone, Serialize)]
+pub struct PnlSeries {
+    pub bucket: PnlBucket,
+    pub points: Vec<PnlPoint>,
+    /// Realized over the returned buckets
+    pub realized: f64,
+}
+
+/// Journaled equity samples
+#[derive(Debug)]
+pub struct EquityCurve {
+    interval_secs: u64,
+    points: Vec<EquityPoint>,
+    journal: Option<JsonlStore>,
+}
+
+impl EquityCurve {
+    pub fn new(interval_secs: u64) -> Self {
+        Self { interval_secs, points: Vec::new(), journal: None }
+    }
+
+    /// Open a journaled curve, loading the points already recorded
+    pub fn open(journal: JsonlStore, interval_secs: u64) -> io::Result<Self> {
+        Ok(Self { interval_secs, points: journal.load()?, journal: Some(journal) })
+    }
+
+    /// Record a point if the sampling interval has elapsed since the last one
+    pub fn sample(&mut self, now: u64, risk: &RiskStatus, unrealized: f64) -> Option<EquityPoint> {
+        if self.points.last().is_some_and(|p| now.saturating_sub(p.timestamp) < self.interval_secs) {
+            return None;
+        }
+        let point = EquityPoint {
+            timestamp: now,
+            balance: risk.current_balance,
+            unrealized,
+            equity: risk.current_balance + unrealized,
+            peak_balance: risk.peak_balance,
+            drawdown_percent: risk.drawdown_percent,
+        };
+        if let Some(journal) = &self.journal {
+            if let Err(e) = journal.append(&point) {
+                eprintln!("⚠️ [Equity] Failed to journal equity point: {}", e);
+            }
+        }
+        self.points.push(point.clone());
+        Some(point)
+    }
+
+    /// Points within `[from, to]` (unix seconds, inclusive)
+    pub fn report(&self, from: Option<u64>, to: Option<u64>) -> EquityReport {
+        let points: Vec<EquityPoint> = self.points.iter()
+            .filter(|p| from.is_none_or(|f| p.timestamp >= f) && to.is_none_or(|t| p.timestamp <= t))
+            .cloned()
+            .collect();
+        let max_drawdown_percent = points.iter().map(|p| p.drawdown_percent).fold(0.0, f64::max);
+        EquityReport { points, max_drawdown_percent }
+    }
+
+    /// Realized / unrealized PnL per `bucket` within `[from, to]`
+    pub fn pnl(&self, bucket: PnlBucket, from: Option<u64>, to: Option<u64>) -> PnlSeries {
+        let width = bucket.secs();
+        let Some(baseline) = self.points.first().map(|p| p.balance) else {
+            return PnlSeries { bucket, points: Vec::new(), realized: 0.0 };
+        };
+        // Realized in a bucket is the balance change since the sample before it, even one before `from`
+        let mut before = baseline;
+        let mut points: Vec<PnlPoint> = Vec::new();
+        for p in &self.points {
+            if from.is_some_and(|f| p.timestamp < f) {
+                before = p.balance;
+                continue;
+            }
+            if to.is_some_and(|t| p.timestamp > t) {
+                break;
+            }
+            let start = p.timestamp / width * width;
+            match points.last_mut() {
+                Some(last) if last.start == start => last.samples += 1,
+                _ => {
+                    if let Some(last) = points.last() {
+                        before = baseline + last.cumulative_realized;
+                    }
+                    points.push(PnlPoint { start, realized: 0.0, cumulative_realized: 0.0, unrealized: 0.0, total: 0.0, samples: 1 });
+                }
+            }
+            let last = points.last_mut().expect("bucket just pushed");
+            last.realized = p.balance - before;
+            last.cumulative_realized = p.balance - baseline;
+            last.unrealized = p.unrealized;
+            last.total = last.cumulative_realized + p.unrealized;
+        }
+        let realized = points.iter().map(|p| p.realized).sum();
+        PnlSeries { bucket, points, realized }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::risk::{RiskConfig, RiskManager};
+
+    #[test]
+    fn test_sampling_drawdown_and_journal() {
+        let dir = std::env::temp_dir().join(format!("arbishark_equity_{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+        let journal = JsonlStore::open(dir, "equity.jsonl").unwrap();
+        let mut curve = EquityCurve::open(journal.clone(), 300).unwrap();
+        let mut risk = RiskManager::new(RiskConfig::default(), 100.0);
+
+        assert!(curve.sample(0, &risk.get_status(), 1.5).is_some());
+        // Within the interval: not sampled
+        assert!(curve.sample(100, &risk.get_status(), 2.0).is_none());
+        risk.record_trade(20.0);
+        risk.record_trade(-12.0);
+        let point = curve.sample(300, &risk.get_status(), -1.0).unwrap();
+        assert_eq!(point.equity, 107.0);
+        assert!((point.drawdown_percent - 10.0).abs() < 1e-9);
+
+        let restored = EquityCurve::open(journal, 300).unwrap();
+        let report = restored.report(None, None);
+        assert_eq!(report.points.len(), 2);
+        assert_eq!(report.points[0].equity, 101.5);
+        assert!((report.max_drawdown_percent - 10.0).abs() < 1e-9);
+        assert_eq!(restored.report(Some(1), None).points.len(), 1);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_pnl_buckets() {
+        let mut curve = EquityCurve::new(60);
+        let mut risk = RiskManager::new(RiskConfig::default(), 100.0);
+        curve.sample(3_000, &risk.get_status(), 0.0);
+        risk.record_trade(5.0);
+        curve.sample(3_500, &risk.get_status(), 1.0);
+        risk.record_trade(-2.0);
+        curve.sample(3_700, &risk.get_status(), -0.5);
+        risk.record_trade(4.0);
+        curve.sample(11_000, &risk.get_status(), 2.0);
+
+        let series = curve.pnl(PnlBucket::Hour, None, None);
+        let buckets: Vec<(u64, f64, f64, usize)> = series.points.iter().map(|p| (p.start, p.realized, p.cumulative_realized, p.samples)).collect();
+        assert_eq!(buckets, vec![(0, 5.0, 5.0, 2), (3_600, -2.0, 3.0, 1), (10_800, 4.0, 7.0, 1)]);
+        assert_eq!(series.points[1].total, 2.5);
+        assert_eq!(series.realized, 7.0);
+
+        // A range starting mid-history still measures realized from the sample before it
+        let series = curve.pnl(PnlBucket::Day, Some(3_600), None);
+        assert_eq!(series.points.len(), 1);
+        assert_eq!((series.points[0].realized, series.points[0].cumulative_realized), (2.0, 7.0));
+    }
+}
@@ -0,0 +1,365 @@
+// Structured event log
+// Events captured from `tracing` without locking the caller, buffered for `/api/logs` and persisted
+
+use crate::storage::JsonlStore;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::{mpsc, RwLock};
+use tracing::field::{Field, Visit};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// Maximum events kept in memory (persisted history is unbounded)
+const MAX_IN_MEMORY: usize = 10_000;
+
+/// Events waiting between the layer and the drain task
+pub const QUEUE_CAPACITY: usize = 4096;
+
+/// Target of events sent with `emit`
+const TARGET: &str = "arbishark::events";
+
+/// Event severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl EventLevel {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "debug" => Some(Self::Debug),
+            "info" => Some(Self::Info),
+            "warn" | "warning" => Some(Self::Warn),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
+
+    /// Level of a `tracing` event (None for trace)
+    fn from_tracing(level: &tracing::Level) -> Option<Self> {
+        match *level {
+            tracing::Level::ERROR => Some(Self::Error),
+            tracing::Level::WARN => Some(Self::Warn),
+            tracing::Level::INFO => Some(Self::Info),
+            tracing::Level::DEBUG => Some(Self::Debug),
+            _ => None,
+        }
+    }
+}
+
+/// A single structured event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEvent {
+    pub id: u64,
+    pub timestamp: u64,
+    pub level: EventLevel,
+    pub component: String,
+    pub market_id: Option<String>,
+    pub message: String,
+}
+
+/// Filter for querying events
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EventQuery {
+    /// Minimum level (inclusive)
+    pub level: Option<String>,
+    pub component: Option<String>,
+    pub market_id: Option<String>,
+    /// Start of time range (unix seconds, inclusive)
+    pub since: Option<u64>,
+    /// End of time range (unix seconds, inclusive)
+    pub until: Option<u64>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+/// In-memory event log with optional persistence
+#[derive(Debug, Default)]
+pub struct EventLog {
+    events: VecDeque<LogEvent>,
+    next_id: u64,
+    store: Option<JsonlStore>,
+}
+
+impl EventLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Attach a store and load persisted history from it
+    pub fn attach_store(&mut self, store: JsonlStore) -> std::io::Result<usize> {
+        let mut loaded: Vec<LogEvent> = store.load()?;
+        let count = loaded.len();
+        if loaded.len() > MAX_IN_MEMORY {
+            loaded.drain(0..loaded.len() - MAX_IN_MEMORY);
+        }
+        self.next_id = loaded.last().map_or(0, |e| e.id + 1).max(self.next_id);
+        let mut events: VecDeque<LogEvent> = loaded.into();
+        events.append(&mut self.events);
+        self.events = events;
+        self.store = Some(store);
+        Ok(count)
+    }
+
+    /// Record an event. Consecutive duplicate messages are collapsed.
+    ///
+    /// Returns false if the event was dropped as a duplicate.
+    pub fn record(&mut self, level: EventLevel, component: &str, market_id: Option<&str>, message: &str, timestamp: u64) -> bool {
+        if self.events.back().is_some_and(|last| last.message == message) {
+            return false;
+        }
+        let event = LogEvent {
+            id: self.next_id,
+            timestamp,
+            level,
+            component: component.to_string(),
+            market_id: market_id.map(|s| s.to_string()),
+            message: message.to_string(),
+        };
+        self.next_id += 1;
+        if let Some(store) = &self.store {
+            if let Err(e) = store.append(&event) {
+                eprintln!("⚠️ [Events] Failed to persist event: {}", e);
+            }
+        }
+        if self.events.len() == MAX_IN_MEMORY {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+        true
+    }
+
+    /// Events held in memory
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Query events (oldest first) with filtering and pagination
+    pub fn query(&self, q: &EventQuery) -> Vec<LogEvent> {
+        let min_level = q.level.as_deref().and_then(EventLevel::parse);
+        self.events.iter()
+            .filter(|e| min_level.is_none_or(|l| e.level >= l))
+            .filter(|e| q.component.as_ref().is_none_or(|c| e.component.eq_ignore_ascii_case(c)))
+            .filter(|e| q.market_id.as_ref().is_none_or(|m| e.market_id.as_ref() == Some(m)))
+            .filter(|e| q.since.is_none_or(|t| e.timestamp >= t))
+            .filter(|e| q.until.is_none_or(|t| e.timestamp <= t))
+            .skip(q.offset.unwrap_or(0))
+            .take(q.limit.unwrap_or(100).min(1000))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Emit an event through `tracing`; recorded once `EventLayer` is installed
+pub fn emit(level: EventLevel, component: &str, market_id: Option<&str>, message: &str) {
+    match level {
+        EventLevel::Debug => tracing::debug!(target: TARGET, component, market_id, "{}", message),
+        EventLevel::Info => tracing::info!(target: TARGET, component, market_id, "{}", message),
+        EventLevel::Warn => tracing::warn!(target: TARGET, component, market_id, "{}", message),
+        EventLevel::Error => tracing::error!(target: TARGET, component, market_id, "{}", message),
+    }
+}
+
+/// An event captured by the layer, numbered once recorded
+#[derive(Debug)]
+pub struct PendingEvent {
+    timestamp: u64,
+    level: EventLevel,
+    component: String,
+    market_id: Option<String>,
+    message: String,
+}
+
+/// Fields of a `tracing` event
+#[derive(Default)]
+struct EventFields {
+    message: Option<String>,
+    component: Option<String>,
+    market_id: Option<String>,
+}
+
+impl Visit for EventFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "message" => self.message = Some(value.to_string()),
+            "component" => self.component = Some(value.to_string()),
+            "market_id" => self.market_id = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = Some(format!("{:?}", value));
+        }
+    }
+}
+
+/// `tracing` layer queueing the crate's events for the `EventBuffer`; never blocks
+pub struct EventLayer {
+    tx: mpsc::Sender<PendingEvent>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl<S: tracing::Subscriber> Layer<S> for EventLayer {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let Some(level) = EventLevel::from_tracing(metadata.level()) else { return };
+        if !metadata.target().starts_with("arbishark") {
+            return;
+        }
+        let mut fields = EventFields::default();
+        event.record(&mut fields);
+        let Some(message) = fields.message else { return };
+        let pending = PendingEvent {
+            timestamp: crate::wallet::Wallet::current_timestamp(),
+            level,
+            component: fields.component.unwrap_or_else(|| infer_component(&message)),
+            market_id: fields.market_id,
+            message,
+        };
+        if self.tx.try_send(pending).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Event log shared by the agent and the API, fed from the layer
+#[derive(Debug, Clone)]
+pub struct EventBuffer {
+    log: Arc<RwLock<EventLog>>,
+    tx: mpsc::Sender<PendingEvent>,
+    dropped: Arc<AtomicU64>,
+}
+
+/// Buffer for the API and the receiver its drain task reads from
+pub fn channel(capacity: usize) -> (EventBuffer, mpsc::Receiver<PendingEvent>) {
+    let (tx, rx) = mpsc::channel(capacity.max(1));
+    (EventBuffer { log: Arc::new(RwLock::new(EventLog::new())), tx, dropped: Arc::new(AtomicU64::new(0)) }, rx)
+}
+
+impl EventBuffer {
+    /// Layer to install on the `tracing` subscriber
+    pub fn layer(&self) -> EventLayer {
+        EventLayer { tx: self.tx.clone(), dropped: self.dropped.clone() }
+    }
+
+    /// Attach a store and load persisted history from it
+    pub async fn attach_store(&self, store: JsonlStore) -> std::io::Result<usize> {
+        self.log.write().await.attach_store(store)
+    }
+
+    pub async fn query(&self, q: &EventQuery) -> Vec<LogEvent> {
+        self.log.read().await.query(q)
+    }
+
+    pub async fn len(&self) -> usize {
+        self.log.read().await.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.log.read().await.is_empty()
+    }
+
+    /// (queued, capacity) of events waiting for the drain task
+    pub fn backlog(&self) -> (usize, usize) {
+        (self.tx.max_capacity() - self.tx.capacity(), self.tx.max_capacity())
+    }
+
+    /// Events dropped because the queue was full
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Record queued events as they arrive, calling `on_record` for each one kept
+    pub async fn drain(&self, mut rx: mpsc::Receiver<PendingEvent>, mut on_record: impl FnMut(&LogEvent)) {
+        let mut batch = Vec::new();
+        while rx.recv_many(&mut batch, QUEUE_CAPACITY).await > 0 {
+            let mut log = self.log.write().await;
+            for e in batch.drain(..) {
+                if log.record(e.level, &e.component, e.market_id.as_deref(), &e.message, e.timestamp) {
+                    if let Some(recorded) = log.events.back() {
+                        on_record(recorded);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Infer level from the message's status emoji
+pub fn infer_level(message: &str) -> EventLevel {
+    if message.contains('❌') || message.contains('🛑') || message.contains('🚨') {
+        EventLevel::Error
+    } else if message.contains('⚠') {
+        EventLevel::Warn
+    } else {
+        EventLevel::Info
+    }
+}
+
+/// Infer component from a `[Tag]` in the message (e.g. "🌍 [API] ..." → "api")
+pub fn infer_component(message: &str) -> String {
+    message.find('[')
+        .and_then(|start| message[start + 1..].find(']').map(|end| &message[start + 1..start + 1 + end]))
+        .filter(|tag| !tag.is_empty() && !tag.contains(' '))
+        .map_or_else(|| "agent".to_string(), |tag| tag.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_filters() {
+        let mut log = EventLog::new();
+        log.record(EventLevel::Info, "api", None, "started", 100);
+        log.record(EventLevel::Warn, "engine", Some("m1"), "slow data", 200);
+        log.record(EventLevel::Error, "engine", Some("m1"), "failed", 300);
+
+        let q = EventQuery { level: Some("warn".to_string()), ..Default::default() };
+        assert_eq!(log.query(&q).len(), 2);
+
+        let q = EventQuery { component: Some("engine".to_string()), since: Some(250), ..Default::default() };
+        let res = log.query(&q);
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].message, "failed");
+
+        assert_eq!(infer_component("🌍 [API] Server started"), "api");
+        assert_eq!(infer_component("Fetching markets"), "agent");
+        assert_eq!(infer_level("⚠️ Failed to fetch markets"), EventLevel::Warn);
+    }
+
+    #[tokio::test]
+    async fn test_layer_feeds_buffer_without_blocking() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let (buffer, rx) = channel(2);
+        let subscriber = tracing_subscriber::registry().with(buffer.layer());
+        tracing::subscriber::with_default(subscriber, || {
+            emit(EventLevel::Warn, "engine", Some("m1"), "⚠️ slow data");
+            tracing::info!("📦 [Plugins] Registered plugin: telegram");
+            // Queue full: dropped rather than waited for
+            emit(EventLevel::Info, "api", None, "started");
+        });
+        assert_eq!(buffer.backlog(), (2, 2));
+        assert_eq!(buffer.dropped(), 1);
+
+        // The drain runs for as long as the buffer lives; stop it once the queue is empty
+        let mut seen = Vec::new();
+        tokio::time::timeout(std::time::Duration::from_millis(100), buffer.drain(rx, |e| seen.push(e.message.clone()))).await.ok();
+        assert_eq!(seen, vec!["⚠️ slow data", "📦 [Plugins] Registered plugin: telegram"]);
+        let events = buffer.query(&EventQuery::default()).await;
+        assert_eq!((events[0].level, events[0].component.as_str(), events[0].market_id.as_deref()), (EventLevel::Warn, "engine", Some("m1")));
+        assert_eq!((events[1].level, events[1].component.as_str()), (EventLevel::Info, "plugins"));
+    }
+}
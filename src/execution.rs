@@ -1,45 +1,188 @@
 use crate::fees::FeeModel;
-use crate::fills::FillModel;
+use crate::fills::{FillModel, PassiveOrder};
 use crate::latency::LatencyModel;
+use crate::orders::OrderRegistry;
+use crate::permission_guard::{ContractCall, PermissionScope};
+use crate::remediation::{ExecutionFailure, FailureClass};
 use crate::types::{ExecutionResult, OrderBook, Side};
 use crate::wallet::Wallet;
+use std::sync::{Arc, Mutex};
 use std::thread;
 
+/// Method invoked on the venue contract for a trade
+pub const TRADE_METHOD: &str = "fillOrder";
+
+/// What to do with the unfilled remainder of a partially filled order
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RemainderPolicy {
+    /// Re-fetch the book and take the remainder if the price moved at most `max_reprice_bps`
+    Chase { max_reprice_bps: u32 },
+    /// Leave the remainder resting at the original price (maker)
+    Rest,
+    /// Drop the remainder
+    Abandon,
+}
+
+impl RemainderPolicy {
+    pub fn parse(policy: &str, max_reprice_bps: u32) -> Self {
+        match policy.to_lowercase().as_str() {
+            "chase" => Self::Chase { max_reprice_bps },
+            "rest" => Self::Rest,
+            _ => Self::Abandon,
+        }
+    }
+}
+
+/// Decision for a remainder under the engine's policy
+#[derive(Debug, Clone)]
+pub enum RemainderDecision {
+    /// Order fully filled
+    Complete,
+    /// Retry the remainder against a fresh book, taking levels up to `limit_price`
+    Chase { remaining: f64, limit_price: f64 },
+    /// Remainder rests as a passive order
+    Rest(PassiveOrder),
+    /// Remainder dropped
+    Abandon { remaining: f64 },
+}
+
 /// Execution simulator
 #[derive(Debug)]
 pub struct ExecutionEngine {
     pub fee_model: FeeModel,
     pub latency_model: LatencyModel,
+    /// Granted scope; calls outside it are never constructed
+    pub scope: PermissionScope,
+    /// Venue contract trades are routed to
+    pub venue_contract: String,
+    /// Handling of partially filled orders
+    pub remainder_policy: RemainderPolicy,
+    /// Resting orders open on the venue
+    pub orders: Option<Arc<Mutex<OrderRegistry>>>,
 }
 
 impl ExecutionEngine {
     pub fn new(fee_model: FeeModel, latency_model: LatencyModel) -> Self {
-        Self { fee_model, latency_model }
+        Self {
+            fee_model,
+            latency_model,
+            scope: PermissionScope::default(),
+            venue_contract: String::new(),
+            remainder_policy: RemainderPolicy::Abandon,
+            orders: None,
+        }
+    }
+
+    /// Track resting orders in a shared registry (kill switch, circuit breaker)
+    pub fn with_order_registry(mut self, orders: Arc<Mutex<OrderRegistry>>) -> Self {
+        self.orders = Some(orders);
+        self
+    }
+
+    /// Cancel every resting order on the venue; returns the number cancelled
+    pub fn cancel_all_orders(&self, reason: &str) -> usize {
+        self.orders.as_ref()
+            .map_or(0, |orders| orders.lock().unwrap().cancel_all_orders(reason).len())
+    }
+
+    /// Set the partial-fill remainder policy
+    pub fn with_remainder_policy(mut self, policy: RemainderPolicy) -> Self {
+        self.remainder_policy = policy;
+        self
+    }
+
+    /// Restrict execution to a permission scope
+    pub fn with_scope(mut self, scope: PermissionScope, venue_contract: &str) -> Self {
+        self.scope = scope;
+        self.venue_contract = venue_contract.to_string();
+        self
+    }
+
+    /// The Smart Account call a trade of `value` USDC would submit
+    pub fn trade_call(&self, value: f64) -> ContractCall {
+        ContractCall {
+            target: self.venue_contract.clone(),
+            method: TRADE_METHOD.to_string(),
+            value,
+        }
     }
 
     /// Simulate order execution
+    ///
+    /// Fills as much as the book allows; `ExecutionResult::remaining_size`
+    /// carries the unfilled part and only the filled notional is charged.
+    /// Failures are classified for remediation.
     pub fn execute(
         &self,
         book: &OrderBook,
         size: f64,
         side: Side,
         wallet: &mut Wallet,
-    ) -> Option<ExecutionResult> {
-        // 1. Calculate initial theoretical price
-        let initial_price = book.execution_price(size, side)?;
+    ) -> Result<ExecutionResult, ExecutionFailure> {
+        self.execute_with_limit(book, size, side, wallet, None)
+    }
+
+    /// Simulate order execution, only taking levels priced within `limit_price`
+    pub fn execute_with_limit(
+        &self,
+        book: &OrderBook,
+        size: f64,
+        side: Side,
+        wallet: &mut Wallet,
+        limit_price: Option<f64>,
+    ) -> Result<ExecutionResult, ExecutionFailure> {
+        self.execute_capped(book, size, side, wallet, limit_price, f64::INFINITY)
+    }
 
-        // 2. Apply latency and adverse selection
-        let (exec_price, delay) = self.latency_model.apply(initial_price);
+    /// Like `execute_with_limit`, refusing a fill that costs more than `max_cost`
+    /// (the allowance left on the active grant)
+    pub fn execute_capped(
+        &self,
+        book: &OrderBook,
+        size: f64,
+        side: Side,
+        wallet: &mut Wallet,
+        limit_price: Option<f64>,
+        max_cost: f64,
+    ) -> Result<ExecutionResult, ExecutionFailure> {
+        // 1. Check fill ratio
+        let filled_size = match limit_price {
+            Some(limit) => FillModel::filled_size_within(book, size, side, limit),
+            None => FillModel::filled_size(book, size, side),
+        };
+        if filled_size <= 0.0 {
+            return Err(ExecutionFailure::new(FailureClass::PriceMoved, match limit_price {
+                Some(limit) => format!("nothing fillable within ${:.4}", limit),
+                None => "book has no liquidity on that side".to_string(),
+            }));
+        }
+
+        // 2. Calculate theoretical price for the fillable size
+        let initial_price = book.execution_price(filled_size, side)
+            .ok_or_else(|| ExecutionFailure::new(FailureClass::PriceMoved, format!("book cannot price {:.2}", filled_size)))?;
+
+        // 3. Apply latency and adverse selection
+        let (mut exec_price, delay) = self.latency_model.apply(initial_price);
 
         // Simulate the delay
         if !delay.is_zero() {
              thread::sleep(delay);
         }
 
-        // 3. Check fill ratio
-        let filled_size = FillModel::filled_size(book, size, side);
-        if filled_size <= 0.0 {
-            return None;
+        // A limit order never fills through its limit: on a book that moved past it,
+        // only the size still within the limit fills and the rest is left as remainder
+        let mut filled_size = filled_size;
+        if let Some(limit) = limit_price {
+            let through = match side {
+                Side::Buy => exec_price > limit,
+                Side::Sell => exec_price < limit,
+            };
+            if through {
+                let drift = exec_price / initial_price;
+                (filled_size, exec_price) = fill_within_limit(book, filled_size, side, limit, drift)
+                    .ok_or_else(|| ExecutionFailure::new(FailureClass::PriceMoved,
+                        format!("price moved to ${:.4}, through the ${:.4} limit", exec_price, limit)))?;
+            }
         }
 
         // 4. Calculate execution metrics
@@ -54,12 +197,21 @@ impl ExecutionEngine {
         // 6. Check permission (ERC-7715)
         if !wallet.check_permission(total_cost) {
             let remaining = wallet.daily_limit - wallet.spent_today;
-            println!("❌ [Smart Account] Permission Denied: Trade value ${:.2} exceeds remaining Daily Allowance (${:.2})", 
-                total_cost, remaining);
-            return None;
+            return Err(ExecutionFailure::new(FailureClass::InsufficientBalance,
+                format!("trade value ${:.2} exceeds remaining daily allowance (${:.2})", total_cost, remaining)));
+        }
+        if total_cost > max_cost {
+            return Err(ExecutionFailure::new(FailureClass::InsufficientBalance,
+                format!("trade value ${:.2} exceeds the grant's remaining allowance (${:.2})", total_cost, max_cost)));
         }
 
-        // 7. Execute via Smart Account
+        // 7. Check grant scope before constructing the call
+        let call = self.trade_call(total_cost);
+        if let Err(violation) = self.scope.check(&call) {
+            return Err(ExecutionFailure::new(FailureClass::PermissionRevert, format!("call refused: {}", violation)));
+        }
+
+        // 8. Execute via Smart Account
         if wallet.record_spend(total_cost) {
             let remaining = wallet.daily_limit - wallet.spent_today;
             println!("✅ [Smart Account] Batch Executed: Swap {:.2} USDC -> Tokens", total_cost);
@@ -78,8 +230,14 @@ impl ExecutionEngine {
             
             wallet.record_trade(true); 
 
-            Some(ExecutionResult {
+            if filled_size < size {
+                println!("   ↳ Partial fill: {:.2}/{:.2} ({:.2} remaining)", filled_size, size, size - filled_size);
+            }
+
+            Ok(ExecutionResult {
                 filed_size: filled_size,
+                requested_size: size,
+                remaining_size: (size - filled_size).max(0.0),
                 execution_price: exec_price,
                 fee_paid: fee,
                 slippage,
@@ -87,11 +245,55 @@ impl ExecutionEngine {
                 success: true,
             })
         } else {
-             None
+             Err(ExecutionFailure::new(FailureClass::InsufficientBalance, format!("spend of ${:.2} not recorded", total_cost)))
+        }
+    }
+
+    /// Decide what to do with a result's unfilled remainder
+    pub fn decide_remainder(&self, result: &ExecutionResult, book: &OrderBook, side: Side) -> RemainderDecision {
+        let remaining = result.remaining_size;
+        if remaining <= f64::EPSILON {
+            return RemainderDecision::Complete;
+        }
+        match self.remainder_policy {
+            RemainderPolicy::Chase { max_reprice_bps } => {
+                let slack = max_reprice_bps as f64 / 10_000.0;
+                let limit_price = match side {
+                    Side::Buy => result.execution_price * (1.0 + slack),
+                    Side::Sell => result.execution_price * (1.0 - slack),
+                };
+                RemainderDecision::Chase { remaining, limit_price }
+            }
+            RemainderPolicy::Rest => {
+                // Rest at the touch on our side of the book
+                let price = match side {
+                    Side::Buy => book.best_bid(),
+                    Side::Sell => book.best_ask(),
+                }.unwrap_or(result.execution_price);
+                RemainderDecision::Rest(PassiveOrder::place(book, side, price, remaining))
+            }
+            RemainderPolicy::Abandon => RemainderDecision::Abandon { remaining },
         }
     }
 }
 
+/// Size and price of the part of `size` that still fills within `limit` once the
+/// book has moved by `drift` (moved price / quoted price); None if none does
+fn fill_within_limit(book: &OrderBook, size: f64, side: Side, limit: f64, drift: f64) -> Option<(f64, f64)> {
+    if drift <= 0.0 {
+        return None;
+    }
+    let size = FillModel::filled_size_within(book, size, side, limit / drift);
+    if size <= 0.0 {
+        return None;
+    }
+    let price = book.execution_price(size, side)? * drift;
+    Some((size, match side {
+        Side::Buy => price.min(limit),
+        Side::Sell => price.max(limit),
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -114,12 +316,56 @@ mod tests {
 
         // 1. Valid trade ($5 cost)
         let res = engine.execute(&book, 10.0, Side::Buy, &mut wallet);
-        assert!(res.is_some());
+        assert!(res.is_ok());
         assert_eq!(wallet.spent_today, 5.0);
 
         // 2. Invalid trade ($6 cost, remaining limit $5)
         let res_fail = engine.execute(&book, 12.0, Side::Buy, &mut wallet);
-        assert!(res_fail.is_none());
+        assert_eq!(res_fail.unwrap_err().class, FailureClass::InsufficientBalance);
         assert_eq!(wallet.spent_today, 5.0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_partial_fill_remainder() {
+        let fee_model = FeeModel { maker_fee_bps: 0, taker_fee_bps: 0 };
+        let engine = ExecutionEngine::new(fee_model, LatencyModel::new(0, 0.0))
+            .with_remainder_policy(RemainderPolicy::Chase { max_reprice_bps: 100 });
+        let mut wallet = Wallet::new(100.0);
+        let book = OrderBook {
+            token_id: "t1".to_string(),
+            bids: vec![],
+            asks: vec![PriceLevel { price: 0.5, size: 4.0 }],
+            timestamp: 0,
+        };
+
+        // Only the filled notional is charged
+        let res = engine.execute(&book, 10.0, Side::Buy, &mut wallet).unwrap();
+        assert_eq!(res.filed_size, 4.0);
+        assert_eq!(res.remaining_size, 6.0);
+        assert_eq!(wallet.spent_today, 2.0);
+
+        match engine.decide_remainder(&res, &book, Side::Buy) {
+            RemainderDecision::Chase { remaining, limit_price } => {
+                assert_eq!(remaining, 6.0);
+                assert!((limit_price - 0.505).abs() < 1e-9);
+            }
+            other => panic!("unexpected decision {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_book_moving_through_the_limit_fills_up_to_it() {
+        let book = OrderBook {
+            token_id: "t1".to_string(),
+            bids: vec![],
+            asks: vec![PriceLevel { price: 0.50, size: 4.0 }, PriceLevel { price: 0.54, size: 10.0 }],
+            timestamp: 0,
+        };
+        // Quoted at 0.5229 for 10; a 4% adverse move puts only the 0.50 level under 0.53
+        let (size, price) = fill_within_limit(&book, 10.0, Side::Buy, 0.53, 1.04).unwrap();
+        assert_eq!(size, 4.0);
+        assert!((price - 0.52).abs() < 1e-12);
+        // Moved past every level: no fill
+        assert!(fill_within_limit(&book, 10.0, Side::Buy, 0.53, 1.10).is_none());
+    }
+}
@@ -0,0 +1,205 @@
+//! Exit path selection: sell the leg or complete the bundle
+//!
+//! A held outcome can be exited two ways. Selling walks the leg's bid,
+//! which is often thin. Buying every complementary outcome instead makes
+//! a complete set that merges back into $1 of collateral per share, so the
+//! leg is worth $1 minus what the complement costs at the asks. Both paths
+//! are priced on the live books for the full size, after taker fees (and
+//! the merge transaction for the bundle), and the exit takes whichever
+//! leaves more. A path the books cannot fill in full is not considered.
+
+use crate::config::ExitOptimizerConfig;
+use crate::positions::{ExitResult, Position};
+use crate::types::{Market, OrderBook, Side};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitPath {
+    /// Sell the leg into its bid
+    Sell,
+    /// Buy the complementary outcomes and merge the set
+    CompleteBundle,
+}
+
+/// One complementary outcome bought to complete the set
+#[derive(Debug, Clone, Serialize)]
+pub struct ComplementLeg {
+    pub token_id: String,
+    /// Volume-weighted ask for the full size
+    pub price: f64,
+}
+
+/// Both exit paths priced for one position
+#[derive(Debug, Clone, Serialize)]
+pub struct ExitPlan {
+    pub market_id: String,
+    pub token_id: String,
+    pub size: f64,
+    pub path: ExitPath,
+    /// Proceeds of selling, net of fees (None if the bid cannot absorb the size)
+    pub sell_value: Option<f64>,
+    pub sell_fees: f64,
+    /// $1 per share minus the complement's cost, fees and merge (None if unfillable)
+    pub bundle_value: Option<f64>,
+    /// Taker fees on the complement plus the merge
+    pub bundle_fees: f64,
+    pub complement: Vec<ComplementLeg>,
+}
+
+impl ExitPlan {
+    /// Value of the chosen path
+    pub fn value(&self) -> Option<f64> {
+        match self.path {
+            ExitPath::Sell => self.sell_value,
+            ExitPath::CompleteBundle => self.bundle_value,
+        }
+    }
+
+    pub fn fees(&self) -> f64 {
+        match self.path {
+            ExitPath::Sell => self.sell_fees,
+            ExitPath::CompleteBundle => self.bundle_fees,
+        }
+    }
+
+    /// Sell after all (e.g. the complement could not be paid for); false if the bid cannot fill
+    pub fn fall_back_to_sell(&mut self) -> bool {
+        if self.sell_value.is_none() {
+            return false;
+        }
+        self.path = ExitPath::Sell;
+        true
+    }
+
+    /// Cost of buying the complement, before fees
+    pub fn complement_cost(&self) -> f64 {
+        self.complement.iter().map(|c| c.price * self.size).sum()
+    }
+
+    /// Restate an exit booked at the mark as executed on the chosen path
+    pub fn apply(&self, exit: &mut ExitResult) {
+        let Some(value) = self.value().filter(|_| self.size > 0.0) else { return };
+        let p = &exit.position;
+        exit.exit_price = (value + self.fees()) / self.size;
+        exit.fees = self.fees();
+        exit.pnl = value - p.entry_price * p.size;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExitOptimizer {
+    config: ExitOptimizerConfig,
+}
+
+impl ExitOptimizer {
+    pub fn new(config: ExitOptimizerConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Price both paths for a long `position` in `market`, with `books` in outcome order.
+    /// None for short positions or when neither path can be filled.
+    pub fn plan(&self, position: &Position, market: &Market, books: &[OrderBook], taker_rate: f64) -> Option<ExitPlan> {
+        if position.side != Side::Buy || position.size <= 0.0 {
+            return None;
+        }
+        let size = position.size;
+        let held = market.clob_token_ids.iter().position(|t| *t == position.token_id)?;
+        let sell_gross = books.get(held)
+            .filter(|b| b.token_id == position.token_id)
+            .and_then(|b| b.execution_price(size, Side::Sell))
+            .map(|price| price * size);
+        let sell_fees = sell_gross.map_or(0.0, |g| g * taker_rate);
+        let sell_value = sell_gross.map(|g| g - sell_fees);
+
+        let complement: Option<Vec<ComplementLeg>> = market.clob_token_ids.iter().enumerate()
+            .filter(|(i, _)| *i != held)
+            .map(|(i, token_id)| {
+                let price = books.get(i).filter(|b| b.token_id == *token_id)?.execution_price(size, Side::Buy)?;
+                Some(ComplementLeg { token_id: token_id.clone(), price })
+            })
+            .collect();
+        let complement = complement.filter(|c| !c.is_empty()).unwrap_or_default();
+        let bundle_fees = complement.iter().map(|c| c.price * size * taker_rate).sum::<f64>() + self.config.merge_cost_usd;
+        let bundle_value = (!complement.is_empty())
+            .then(|| size - complement.iter().map(|c| c.price * size).sum::<f64>() - bundle_fees);
+
+        let path = match (sell_value, bundle_value) {
+            (None, None) => return None,
+            (Some(sell), Some(bundle)) if bundle > sell + self.config.min_improvement_usd => ExitPath::CompleteBundle,
+            (None, Some(_)) => ExitPath::CompleteBundle,
+            _ => ExitPath::Sell,
+        };
+        Some(ExitPlan {
+            market_id: market.id.clone(),
+            token_id: position.token_id.clone(),
+            size,
+            path,
+            sell_value,
+            sell_fees,
+            bundle_value,
+            bundle_fees,
+            complement,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::positions::ExitReason;
+    use crate::testing;
+
+    /// `bid` and `ask` at the touch, and a deep bid at 0.10 below
+    fn book(token_id: &str, bid: (f64, f64), ask: (f64, f64)) -> OrderBook {
+        testing::book(token_id, &[bid, (0.10, 1000.0)], &[ask])
+    }
+
+    fn market() -> Market {
+        Market { clob_token_ids: vec!["yes".to_string(), "no".to_string()], taker_base_fee: 0, ..testing::market() }
+    }
+
+    fn position() -> Position {
+        Position {
+            market_id: "m1".to_string(), token_id: "yes".to_string(), side: Side::Buy, size: 10.0,
+            entry_price: 0.45, entry_time: 0, entry_spread: 0.0, strategy: "arb".to_string(),
+        }
+    }
+
+    fn optimizer() -> ExitOptimizer {
+        ExitOptimizer::new(ExitOptimizerConfig { enabled: true, merge_cost_usd: 0.05, min_improvement_usd: 0.0 })
+    }
+
+    /// Only 2 shares bid at 0.48, the rest at 0.10; NO asks 0.50 deep enough
+    fn thin() -> [OrderBook; 2] {
+        [book("yes", (0.48, 2.0), (0.52, 100.0)), book("no", (0.46, 100.0), (0.50, 100.0))]
+    }
+
+    #[test]
+    fn test_completes_bundle_when_bid_is_thin() {
+        let plan = optimizer().plan(&position(), &market(), &thin(), 0.0).unwrap();
+        assert_eq!(plan.path, ExitPath::CompleteBundle);
+        assert!((plan.sell_value.unwrap() - 1.76).abs() < 1e-9);
+        assert!((plan.bundle_value.unwrap() - 4.95).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_bundle_plan_reprices_the_exit() {
+        let plan = optimizer().plan(&position(), &market(), &thin(), 0.0).unwrap();
+        let mut exit = ExitResult { position: position(), exit_price: 0.5, exit_time: 1, reason: ExitReason::Timeout, pnl: 0.5, fees: 0.0 };
+        plan.apply(&mut exit);
+        assert!((exit.pnl - (4.95 - 4.5)).abs() < 1e-9);
+        assert!((exit.exit_price - 0.5).abs() < 1e-9, "$1 - 0.50 complement");
+    }
+
+    #[test]
+    fn test_sells_into_a_deep_bid() {
+        // A deep bid beats paying the complement's ask
+        let deep = [book("yes", (0.49, 100.0), (0.52, 100.0)), book("no", (0.46, 100.0), (0.55, 100.0))];
+        assert_eq!(optimizer().plan(&position(), &market(), &deep, 0.0).unwrap().path, ExitPath::Sell);
+    }
+}
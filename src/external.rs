@@ -0,0 +1,240 @@
+//! External signal ingestion
+//!
+//! `POST /api/signals/external` lets users pair their own models with this
+//! crate's execution: an authenticated caller submits a market, side, the
+//! max bundle price it is willing to pay and an optional size hint. The API
+//! only checks the request itself and parks it in the inbox; on the next
+//! tick the engine prices it against fresh books, rejects it if the bundle
+//! costs more than the caller's max price, and otherwise queues it as an
+//! ordinary signal - so sizing, risk limits, allowance, compliance, approval
+//! and pre-execution revalidation all apply exactly as for detected signals.
+
+use crate::config::ExternalSignalConfig;
+use crate::types::{ArbitrageSignal, OrderBook, Side};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Decided records kept for `GET /api/signals/external`
+const MAX_RECORDS: usize = 200;
+
+/// Request body of `POST /api/signals/external`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalSignal {
+    pub market_id: String,
+    pub side: Side,
+    /// Highest total price ($ per bundle unit) the caller accepts
+    pub max_price: f64,
+    /// Preferred size per leg; the engine never trades more than this
+    #[serde(default)]
+    pub size_hint: Option<f64>,
+}
+
+/// Where an external signal is in the pipeline
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ExternalStatus {
+    /// Accepted by the API, waiting for the next engine tick
+    Pending,
+    /// Priced against fresh books and handed to the signal queue
+    Queued { edge: f64 },
+    Rejected { reason: String },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExternalRecord {
+    pub signal_id: String,
+    /// Client identity that submitted it
+    pub source: String,
+    pub received_at: u64,
+    pub signal: ExternalSignal,
+    #[serde(flatten)]
+    pub status: ExternalStatus,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExternalError {
+    Disabled,
+    Unauthorized,
+    Invalid(String),
+    /// Too many signals waiting for the engine
+    Backlogged(usize),
+}
+
+impl std::fmt::Display for ExternalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Disabled => write!(f, "external signals are disabled"),
+            Self::Unauthorized => write!(f, "missing or invalid bearer token"),
+            Self::Invalid(e) => write!(f, "invalid signal: {}", e),
+            Self::Backlogged(max) => write!(f, "{} external signals already pending", max),
+        }
+    }
+}
+
+/// Submitted external signals, pending and recently decided
+#[derive(Debug)]
+pub struct ExternalInbox {
+    config: ExternalSignalConfig,
+    /// Shared secret callers present as `Authorization: Bearer <token>`
+    token: Option<String>,
+    records: VecDeque<ExternalRecord>,
+    next_id: u64,
+}
+
+impl ExternalInbox {
+    pub fn new(config: ExternalSignalConfig, token: Option<String>) -> Self {
+        Self { config, token: token.filter(|t| !t.is_empty()), records: VecDeque::new(), next_id: 1 }
+    }
+
+    /// Inbox with the token read from the configured environment variable
+    pub fn from_env(config: ExternalSignalConfig) -> Self {
+        let token = std::env::var(&config.token_env).ok();
+        Self::new(config, token)
+    }
+
+    /// Whether submissions can be accepted (enabled and a token is set)
+    pub fn is_open(&self) -> bool {
+        self.config.enabled && self.token.is_some()
+    }
+
+    /// Check the `Authorization` header value
+    pub fn authenticate(&self, authorization: Option<&str>) -> Result<(), ExternalError> {
+        let Some(token) = self.token.as_deref().filter(|_| self.config.enabled) else {
+            return Err(ExternalError::Disabled);
+        };
+        let presented = authorization.and_then(|h| h.strip_prefix("Bearer ")).unwrap_or_default();
+        // Constant-time comparison so the token cannot be guessed byte by byte
+        let matches = presented.len() == token.len()
+            && presented.bytes().zip(token.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0;
+        if matches { Ok(()) } else { Err(ExternalError::Unauthorized) }
+    }
+
+    /// Validate and park a signal for the engine
+    pub fn submit(&mut self, signal: ExternalSignal, source: &str, now: u64) -> Result<ExternalRecord, ExternalError> {
+        if signal.market_id.is_empty() {
+            return Err(ExternalError::Invalid("market_id is required".to_string()));
+        }
+        if !(signal.max_price.is_finite() && signal.max_price > 0.0) {
+            return Err(ExternalError::Invalid("max_price must be positive".to_string()));
+        }
+        if signal.size_hint.is_some_and(|s| !(s.is_finite() && s > 0.0)) {
+            return Err(ExternalError::Invalid("size_hint must be positive".to_string()));
+        }
+        let pending = self.records.iter().filter(|r| r.status == ExternalStatus::Pending).count();
+        if pending >= self.config.max_pending {
+            return Err(ExternalError::Backlogged(self.config.max_pending));
+        }
+        let record = ExternalRecord {
+            signal_id: format!("ext-{}-{}", now, self.next_id),
+            source: source.to_string(),
+            received_at: now,
+            signal,
+            status: ExternalStatus::Pending,
+        };
+        self.next_id += 1;
+        self.records.push_back(record.clone());
+        while self.records.len() > MAX_RECORDS {
+            self.records.pop_front();
+        }
+        Ok(record)
+    }
+
+    /// Pending signals for this tick (they stay listed until resolved)
+    pub fn pending(&self) -> Vec<ExternalRecord> {
+        self.records.iter().filter(|r| r.status == ExternalStatus::Pending).cloned().collect()
+    }
+
+    pub fn resolve(&mut self, signal_id: &str, status: ExternalStatus) {
+        if let Some(record) = self.records.iter_mut().find(|r| r.signal_id == signal_id) {
+            record.status = status;
+        }
+    }
+
+    /// Most recent first
+    pub fn recent(&self) -> Vec<ExternalRecord> {
+        self.records.iter().rev().cloned().collect()
+    }
+}
+
+/// Price an external signal against fresh books (one per leg) at `size`:
+/// the queue-ready signal, or why it cannot be traded
+pub fn price(signal: &ExternalSignal, books: &[OrderBook], size: f64) -> Result<ArbitrageSignal, String> {
+    if signal.side != Side::Buy {
+        return Err("only buy bundles are executed".to_string());
+    }
+    let prices = books.iter()
+        .map(|b| b.execution_price(size, Side::Buy))
+        .collect::<Option<Vec<f64>>>()
+        .filter(|p| p.len() >= 2)
+        .ok_or_else(|| format!("books too thin for {:.2} per leg", size))?;
+    let total: f64 = prices.iter().sum();
+    if total > signal.max_price {
+        return Err(format!("bundle costs ${:.4}, above max price ${:.4}", total, signal.max_price));
+    }
+    let edge = 1.0 - total;
+    Ok(ArbitrageSignal {
+        market_id: signal.market_id.clone(),
+        spread: edge.abs(),
+        edge,
+        recommended_side: Side::Buy,
+        yes_price: prices[0],
+        no_price: prices[1],
+        edge_curve: Vec::new(),
+        quote_ages_ms: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::book;
+
+    fn books() -> [OrderBook; 2] {
+        [book("y", &[], &[(0.48, 100.0)]), book("n", &[], &[(0.49, 100.0)])]
+    }
+
+    fn signal(side: Side, max_price: f64) -> ExternalSignal {
+        ExternalSignal { market_id: "m1".to_string(), side, max_price, size_hint: Some(5.0) }
+    }
+
+    fn inbox() -> ExternalInbox {
+        let config = ExternalSignalConfig { enabled: true, max_pending: 1, ..Default::default() };
+        ExternalInbox::new(config, Some("s3cret".to_string()))
+    }
+
+    #[test]
+    fn test_bearer_token_required() {
+        let inbox = inbox();
+        assert_eq!(inbox.authenticate(Some("Bearer s3cret")), Ok(()));
+        assert_eq!(inbox.authenticate(Some("Bearer s3cres")), Err(ExternalError::Unauthorized));
+        assert_eq!(inbox.authenticate(None), Err(ExternalError::Unauthorized));
+        assert_eq!(ExternalInbox::new(ExternalSignalConfig::default(), None).authenticate(Some("Bearer ")),
+            Err(ExternalError::Disabled));
+    }
+
+    #[test]
+    fn test_submissions_validated_and_capped() {
+        let mut inbox = inbox();
+        assert!(matches!(inbox.submit(signal(Side::Buy, -1.0), "model", 10), Err(ExternalError::Invalid(_))));
+        inbox.submit(signal(Side::Buy, 0.97), "model", 10).unwrap();
+        assert_eq!(inbox.submit(signal(Side::Buy, 0.97), "model", 11).unwrap_err(), ExternalError::Backlogged(1));
+        assert_eq!(inbox.pending().len(), 1);
+    }
+
+    #[test]
+    fn test_resolved_signals_leave_the_backlog() {
+        let mut inbox = inbox();
+        let record = inbox.submit(signal(Side::Buy, 0.97), "model", 10).unwrap();
+        inbox.resolve(&record.signal_id, ExternalStatus::Queued { edge: 0.03 });
+        assert!(inbox.pending().is_empty());
+        assert_eq!(inbox.recent()[0].status, ExternalStatus::Queued { edge: 0.03 });
+    }
+
+    #[test]
+    fn test_priced_against_the_max_price() {
+        let priced = price(&signal(Side::Buy, 0.97), &books(), 5.0).unwrap();
+        assert!((priced.edge - 0.03).abs() < 1e-9);
+        assert!(price(&signal(Side::Buy, 0.95), &books(), 5.0).unwrap_err().contains("above max price"));
+        assert!(price(&signal(Side::Sell, 1.5), &books(), 5.0).is_err());
+    }
+}
@@ -0,0 +1,236 @@
+//! Venue fee tiers
+//!
+//! Venue fees drop as monthly volume grows, so a flat taker fee overstates
+//! costs (and understates edge) once the bot has traded enough to reach a
+//! cheaper tier. Maker and taker notional are tracked per UTC calendar month
+//! and journaled, so a restart keeps the month's volume; the tier reached by
+//! the month's total volume sets the fee model used for edge math and fill
+//! costs. The report projects, at the month's average daily volume so far,
+//! when the next tier will be reached and whether that happens before the
+//! volume resets at the start of the next month.
+
+use crate::config::{FeeTierConfig, FeeTiersConfig};
+use crate::fees::FeeModel;
+use crate::storage::JsonlStore;
+use crate::types::Market;
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+/// One fill's contribution to the monthly volume
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct VolumeEntry {
+    timestamp: u64,
+    notional: f64,
+    maker: bool,
+}
+
+/// When the next tier is reached at the current daily rate
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TierProjection {
+    pub next_tier: usize,
+    pub next_min_volume: f64,
+    pub volume_needed: f64,
+    /// Average over the elapsed part of the month
+    pub daily_volume: f64,
+    /// None when nothing has traded this month
+    pub reached_at: Option<u64>,
+    /// Whether `reached_at` falls before the monthly reset
+    pub before_reset: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FeeTierReport {
+    pub month: String,
+    pub maker_volume: f64,
+    pub taker_volume: f64,
+    pub tier: usize,
+    pub maker_fee_bps: u32,
+    pub taker_fee_bps: u32,
+    /// None at the top tier
+    pub next: Option<TierProjection>,
+}
+
+/// Start of the UTC month containing `now`, and of the next one
+fn month_bounds(now: u64) -> (u64, u64) {
+    let date = DateTime::<Utc>::from_timestamp(now as i64, 0).unwrap_or_default();
+    let start = Utc.with_ymd_and_hms(date.year(), date.month(), 1, 0, 0, 0).unwrap();
+    let (year, month) = if date.month() == 12 { (date.year() + 1, 1) } else { (date.year(), date.month() + 1) };
+    let next = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).unwrap();
+    (start.timestamp() as u64, next.timestamp() as u64)
+}
+
+#[derive(Debug)]
+pub struct FeeTiers {
+    config: FeeTiersConfig,
+    /// Start of the month the volume belongs to
+    month_start: u64,
+    maker_volume: f64,
+    taker_volume: f64,
+    journal: Option<JsonlStore>,
+}
+
+impl FeeTiers {
+    pub fn new(mut config: FeeTiersConfig, now: u64) -> Self {
+        config.tiers.sort_by(|a, b| a.min_volume.total_cmp(&b.min_volume));
+        Self { config, month_start: month_bounds(now).0, maker_volume: 0.0, taker_volume: 0.0, journal: None }
+    }
+
+    /// Replay this month's volume from `journal` and append new fills to it
+    pub fn with_journal(mut self, journal: JsonlStore) -> Self {
+        let entries: Vec<VolumeEntry> = journal.load().unwrap_or_else(|e| {
+            eprintln!("⚠️ [FeeTiers] Failed to read volume journal: {}", e);
+            Vec::new()
+        });
+        let month_start = self.month_start;
+        for entry in entries.iter().filter(|e| e.timestamp >= month_start) {
+            self.add(entry);
+        }
+        self.journal = Some(journal);
+        self
+    }
+
+    fn add(&mut self, entry: &VolumeEntry) {
+        match entry.maker {
+            true => self.maker_volume += entry.notional,
+            false => self.taker_volume += entry.notional,
+        }
+    }
+
+    /// Start a new month when `now` has left the current one
+    fn roll(&mut self, now: u64) {
+        let start = month_bounds(now).0;
+        if start > self.month_start {
+            self.month_start = start;
+            self.maker_volume = 0.0;
+            self.taker_volume = 0.0;
+            if let Some(journal) = &self.journal {
+                if let Err(e) = journal.clear() {
+                    eprintln!("⚠️ [FeeTiers] Failed to clear volume journal: {}", e);
+                }
+            }
+        }
+    }
+
+    pub fn volume(&self) -> f64 {
+        self.maker_volume + self.taker_volume
+    }
+
+    /// Index of the tier the month's volume has reached
+    pub fn tier(&self) -> usize {
+        if !self.config.enabled {
+            return 0;
+        }
+        self.config.tiers.iter().rposition(|t| self.volume() >= t.min_volume).unwrap_or(0)
+    }
+
+    fn current(&self) -> Option<&FeeTierConfig> {
+        self.config.tiers.get(self.tier())
+    }
+
+    /// Fee model of the current tier (the base tier while disabled)
+    pub fn fee_model(&self) -> FeeModel {
+        self.current()
+            .map(|t| FeeModel { maker_fee_bps: t.maker_fee_bps, taker_fee_bps: t.taker_fee_bps })
+            .unwrap_or(FeeModel { maker_fee_bps: 0, taker_fee_bps: 200 })
+    }
+
+    /// Taker rate for edge math: the tier's when enabled, otherwise the market's base fee
+    pub fn taker_rate(&self, market: &Market) -> f64 {
+        match self.config.enabled {
+            true => self.fee_model().taker_rate(),
+            false => FeeModel::from_market(market).taker_rate(),
+        }
+    }
+
+    /// Count a fill's notional; returns the new tier if this fill moved to another one
+    pub fn record(&mut self, notional: f64, maker: bool, now: u64) -> Option<usize> {
+        if notional <= 0.0 {
+            return None;
+        }
+        let before = self.tier();
+        self.roll(now);
+        let entry = VolumeEntry { timestamp: now, notional, maker };
+        if let Some(journal) = &self.journal {
+            if let Err(e) = journal.append(&entry) {
+                eprintln!("⚠️ [FeeTiers] Failed to journal volume: {}", e);
+            }
+        }
+        self.add(&entry);
+        let after = self.tier();
+        (after != before).then_some(after)
+    }
+
+    /// When the next tier is reached at this month's average daily volume
+    pub fn projection(&self, now: u64) -> Option<TierProjection> {
+        let (start, next_month) = month_bounds(now);
+        let volume = if start > self.month_start { 0.0 } else { self.volume() };
+        let next_tier = self.config.tiers.iter().position(|t| t.min_volume > volume)?;
+        let next_min_volume = self.config.tiers[next_tier].min_volume;
+        let volume_needed = next_min_volume - volume;
+        let elapsed = now.saturating_sub(start).max(1) as f64;
+        let daily_volume = volume / elapsed * 86_400.0;
+        let reached_at = (daily_volume > 0.0).then(|| now + (volume_needed / daily_volume * 86_400.0).ceil() as u64);
+        Some(TierProjection {
+            next_tier,
+            next_min_volume,
+            volume_needed,
+            daily_volume,
+            reached_at,
+            before_reset: reached_at.is_some_and(|t| t < next_month),
+        })
+    }
+
+    pub fn report(&self, now: u64) -> FeeTierReport {
+        let fees = self.fee_model();
+        FeeTierReport {
+            month: DateTime::<Utc>::from_timestamp(self.month_start as i64, 0)
+                .map(|d| d.format("%Y-%m").to_string()).unwrap_or_default(),
+            maker_volume: self.maker_volume,
+            taker_volume: self.taker_volume,
+            tier: self.tier(),
+            maker_fee_bps: fees.maker_fee_bps,
+            taker_fee_bps: fees.taker_fee_bps,
+            next: if self.config.enabled { self.projection(now) } else { None },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tier(min_volume: f64, taker_fee_bps: u32) -> FeeTierConfig {
+        FeeTierConfig { min_volume, maker_fee_bps: 0, taker_fee_bps }
+    }
+
+    #[test]
+    fn test_tiers_follow_monthly_volume() {
+        // 2024-03-01 00:00 UTC
+        let march = 1_709_251_200;
+        let config = FeeTiersConfig { enabled: true, tiers: vec![tier(1_000.0, 150), tier(0.0, 200), tier(10_000.0, 100)] };
+        let mut tiers = FeeTiers::new(config, march);
+        assert_eq!(tiers.fee_model().taker_fee_bps, 200);
+
+        // $600/day over the first two days
+        assert_eq!(tiers.record(600.0, false, march + 3_600), None);
+        assert_eq!(tiers.record(600.0, true, march + 86_400 + 3_600), Some(1));
+        assert_eq!(tiers.fee_model().taker_fee_bps, 150);
+
+        let projection = tiers.projection(march + 2 * 86_400).unwrap();
+        assert_eq!(projection.next_tier, 2);
+        assert!((projection.volume_needed - 8_800.0).abs() < 1e-9);
+        assert!((projection.daily_volume - 600.0).abs() < 1e-9);
+        // 8800 / 600 = 14.67 days more, before April
+        assert_eq!(projection.reached_at, Some(march + 2 * 86_400 + 1_267_200));
+        assert!(projection.before_reset);
+
+        let report = tiers.report(march + 2 * 86_400);
+        assert_eq!(report.month, "2024-03");
+        assert!((report.maker_volume - 600.0).abs() < 1e-9);
+
+        // April starts over at the base tier
+        let april = march + 31 * 86_400;
+        assert_eq!(tiers.record(100.0, false, april), Some(0));
+        assert!((tiers.volume() - 100.0).abs() < 1e-9);
+    }
+}
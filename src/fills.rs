@@ -1,4 +1,5 @@
-use crate::types::{OrderBook, Side};
+use crate::types::{OrderBook, Side, Trade};
+use serde::{Deserialize, Serialize};
 
 /// Fill rate estimator
 #[derive(Debug, Clone)]
@@ -24,4 +25,136 @@ impl FillModel {
         let ratio = Self::estimate_fill_ratio(book, requested_size, side);
         requested_size * ratio
     }
-}
\ No newline at end of file
+
+    /// Filled size taking only levels priced within `limit_price`
+    pub fn filled_size_within(book: &OrderBook, requested_size: f64, side: Side, limit_price: f64) -> f64 {
+        let available: f64 = match side {
+            Side::Buy => book.asks.iter().filter(|l| l.price <= limit_price).map(|l| l.size).sum(),
+            Side::Sell => book.bids.iter().filter(|l| l.price >= limit_price).map(|l| l.size).sum(),
+        };
+        requested_size.min(available)
+    }
+}
+/// Resting (maker) order with FIFO queue position tracking
+///
+/// Queue ahead is taken from the visible size at our price when the order
+/// is placed. Trade flow at our price depletes the queue first; we only
+/// fill once the queue ahead has cleared. Trades through our price clear
+/// the whole queue.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PassiveOrder {
+    pub token_id: String,
+    pub side: Side,
+    pub price: f64,
+    pub size: f64,
+    /// Size resting ahead of us at our price level
+    pub queue_ahead: f64,
+    pub filled: f64,
+}
+
+#[allow(dead_code)]
+impl PassiveOrder {
+    /// Place a resting order, joining the back of the queue at `price`
+    pub fn place(book: &OrderBook, side: Side, price: f64, size: f64) -> Self {
+        Self {
+            token_id: book.token_id.clone(),
+            side,
+            price,
+            size,
+            queue_ahead: Self::level_size(book, side, price),
+            filled: 0.0,
+        }
+    }
+
+    pub fn remaining(&self) -> f64 {
+        (self.size - self.filled).max(0.0)
+    }
+
+    pub fn is_filled(&self) -> bool {
+        self.remaining() <= f64::EPSILON
+    }
+
+    /// Apply a trade print; returns the size filled on our order.
+    ///
+    /// `trade.side` is the aggressor side, so a resting buy is hit by sells.
+    pub fn on_trade(&mut self, trade: &Trade) -> f64 {
+        if trade.token_id != self.token_id || trade.side == self.side || self.is_filled() {
+            return 0.0;
+        }
+        let (at_price, through) = match self.side {
+            Side::Buy => (trade.price == self.price, trade.price < self.price),
+            Side::Sell => (trade.price == self.price, trade.price > self.price),
+        };
+        let mut flow = trade.size;
+        if through {
+            self.queue_ahead = 0.0;
+        } else if at_price {
+            let consumed = flow.min(self.queue_ahead);
+            self.queue_ahead -= consumed;
+            flow -= consumed;
+        } else {
+            return 0.0;
+        }
+        let fill = flow.min(self.remaining());
+        self.filled += fill;
+        fill
+    }
+
+    /// Apply a book snapshot: cancellations can only shrink the queue ahead
+    /// to what's still visible at our level (we assume cancels come from
+    /// behind us, which is the conservative case).
+    pub fn on_book_update(&mut self, book: &OrderBook) {
+        if book.token_id != self.token_id {
+            return;
+        }
+        let visible = Self::level_size(book, self.side, self.price);
+        self.queue_ahead = self.queue_ahead.min(visible);
+    }
+
+    fn level_size(book: &OrderBook, side: Side, price: f64) -> f64 {
+        let levels = match side {
+            Side::Buy => &book.bids,
+            Side::Sell => &book.asks,
+        };
+        levels.iter().filter(|l| l.price == price).map(|l| l.size).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PriceLevel;
+
+    #[test]
+    fn test_queue_position_fill() {
+        let book = OrderBook {
+            token_id: "t1".to_string(),
+            bids: vec![PriceLevel { price: 0.49, size: 100.0 }],
+            asks: vec![PriceLevel { price: 0.51, size: 100.0 }],
+            timestamp: 0,
+        };
+        let mut order = PassiveOrder::place(&book, Side::Buy, 0.49, 20.0);
+        assert_eq!(order.queue_ahead, 100.0);
+
+        let print = |price: f64, size: f64| Trade {
+            id: "x".to_string(),
+            token_id: "t1".to_string(),
+            price,
+            size,
+            side: Side::Sell,
+            timestamp: 0,
+        };
+
+        // Touching our price does not fill while the queue ahead remains
+        assert_eq!(order.on_trade(&print(0.49, 60.0)), 0.0);
+        assert_eq!(order.queue_ahead, 40.0);
+
+        // Queue clears, remainder fills us
+        assert_eq!(order.on_trade(&print(0.49, 50.0)), 10.0);
+
+        // Trade through our price fills the rest
+        assert_eq!(order.on_trade(&print(0.48, 30.0)), 10.0);
+        assert!(order.is_filled());
+    }
+}
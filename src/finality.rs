@@ -0,0 +1,318 @@
+// Settlement finality of submitted transactions
+// Tracked through confirmations and reorgs; dropped or reverted fills corrected out of the order journal
+
+use crate::config::FinalityConfig;
+use crate::reconcile::SubmittedOrder;
+use crate::rpc_pool::RpcPool;
+use crate::storage::JsonlStore;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Where a settlement transaction stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TxStatus {
+    /// Not (or no longer) in a block
+    Pending,
+    /// Mined, below the chain's confirmation threshold
+    Provisional,
+    Final,
+    /// Never mined within the drop window
+    Dropped,
+    /// Mined but reverted
+    Reverted,
+}
+
+impl TxStatus {
+    /// Nothing left to check
+    pub fn is_settled(&self) -> bool {
+        matches!(self, Self::Final | Self::Dropped | Self::Reverted)
+    }
+}
+
+/// A settlement transaction of one of our orders
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedTx {
+    pub tx_hash: String,
+    pub chain_id: u64,
+    pub order_id: String,
+    pub market_id: String,
+    pub token_id: String,
+    /// Share of the order's booked fill this transaction settles (shares)
+    pub size: f64,
+    pub submitted_at: u64,
+    pub status: TxStatus,
+    /// Block it was last seen in
+    pub block: Option<u64>,
+    pub block_hash: Option<String>,
+    pub confirmations: u64,
+    /// Times a reorg moved it out of a block it had been seen in
+    pub reorgs: u32,
+    /// Since when it has been out of any block; the drop window runs from here
+    pub unmined_since: u64,
+    pub updated_at: u64,
+}
+
+/// What one receipt check changed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Transition {
+    Included { block: u64 },
+    Final { block: u64 },
+    /// Left the block it was seen in; `to_block` if already mined again elsewhere
+    Reorged { from_block: u64, to_block: Option<u64> },
+    Dropped,
+    Reverted { block: u64 },
+}
+
+/// The part of a receipt finality needs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Receipt {
+    pub block: u64,
+    pub block_hash: String,
+    pub success: bool,
+}
+
+/// Settlement transactions and their finality
+#[derive(Debug)]
+pub struct FinalityTracker {
+    config: FinalityConfig,
+    txs: BTreeMap<String, TrackedTx>,
+    journal: Option<JsonlStore>,
+}
+
+impl FinalityTracker {
+    pub fn new(config: FinalityConfig) -> Self {
+        Self { config, txs: BTreeMap::new(), journal: None }
+    }
+
+    /// Persist to `journal`, resuming the transactions in it (a transaction's last record wins)
+    pub fn with_journal(mut self, journal: JsonlStore) -> Self {
+        let records: Vec<TrackedTx> = journal.load().unwrap_or_else(|e| {
+            eprintln!("⚠️ [Finality] Failed to read finality journal: {}", e);
+            Vec::new()
+        });
+        self.txs.extend(records.into_iter().map(|tx| (tx.tx_hash.clone(), tx)));
+        self.journal = Some(journal);
+        self
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Confirmations before a settlement on `chain_id` is final
+    pub fn threshold(&self, chain_id: u64) -> u64 {
+        self.config.confirmations.get(&chain_id.to_string()).copied().unwrap_or(self.config.default_confirmations).max(1)
+    }
+
+    /// Track the settlement transactions of `order` (its booked fill split evenly
+    /// across them); hashes already tracked are left alone
+    pub fn track_order(&mut self, order: &SubmittedOrder, tx_hashes: &[String], chain_id: u64, now: u64) {
+        let share = order.expected_fill / tx_hashes.len().max(1) as f64;
+        for tx_hash in tx_hashes {
+            if self.txs.contains_key(tx_hash) {
+                continue;
+            }
+            let tx = TrackedTx {
+                tx_hash: tx_hash.clone(),
+                chain_id,
+                order_id: order.order_id.clone(),
+                market_id: order.market_id.clone(),
+                token_id: order.token_id.clone(),
+                size: share,
+                submitted_at: now,
+                status: TxStatus::Pending,
+                block: None,
+                block_hash: None,
+                confirmations: 0,
+                reorgs: 0,
+                unmined_since: now,
+                updated_at: now,
+            };
+            self.persist(&tx);
+            self.txs.insert(tx_hash.clone(), tx);
+        }
+    }
+
+    /// Apply a receipt check of `tx_hash` with the chain at `head`
+    pub fn observe(&mut self, tx_hash: &str, receipt: Option<Receipt>, head: u64, now: u64) -> Option<Transition> {
+        let drop_after = self.config.drop_after_secs;
+        let threshold = self.threshold(self.txs.get(tx_hash)?.chain_id);
+        let tx = self.txs.get_mut(tx_hash).filter(|tx| !tx.status.is_settled())?;
+        let transition = match receipt {
+            None => match tx.block.take() {
+                Some(from_block) => {
+                    tx.block_hash = None;
+                    tx.confirmations = 0;
+                    tx.reorgs += 1;
+                    tx.unmined_since = now;
+                    tx.status = TxStatus::Pending;
+                    Some(Transition::Reorged { from_block, to_block: None })
+                }
+                None if now >= tx.unmined_since + drop_after => {
+                    tx.status = TxStatus::Dropped;
+                    Some(Transition::Dropped)
+                }
+                None => None,
+            },
+            Some(receipt) => {
+                let moved_from = tx.block.filter(|_| tx.block_hash.as_ref().is_some_and(|h| *h != receipt.block_hash));
+                tx.confirmations = head.saturating_sub(receipt.block) + 1;
+                tx.block = Some(receipt.block);
+                tx.block_hash = Some(receipt.block_hash);
+                let was = tx.status;
+                if !receipt.success {
+                    tx.status = TxStatus::Reverted;
+                    Some(Transition::Reverted { block: receipt.block })
+                } else if let Some(from_block) = moved_from {
+                    tx.reorgs += 1;
+                    tx.status = TxStatus::Provisional;
+                    Some(Transition::Reorged { from_block, to_block: Some(receipt.block) })
+                } else if tx.confirmations >= threshold {
+                    tx.status = TxStatus::Final;
+                    Some(Transition::Final { block: receipt.block })
+                } else {
+                    tx.status = TxStatus::Provisional;
+                    (was == TxStatus::Pending).then_some(Transition::Included { block: receipt.block })
+                }
+            }
+        };
+        tx.updated_at = now;
+        // Confirmations alone are re-read on the next poll; only transitions are journaled
+        if transition.is_some() {
+            let tx = tx.clone();
+            self.persist(&tx);
+        }
+        transition
+    }
+
+    /// Transactions still to be checked
+    pub fn open(&self) -> Vec<TrackedTx> {
+        self.txs.values().filter(|tx| !tx.status.is_settled()).cloned().collect()
+    }
+
+    pub fn get(&self, tx_hash: &str) -> Option<&TrackedTx> {
+        self.txs.get(tx_hash)
+    }
+
+    /// Whether any settlement of `order_id` is not final yet
+    pub fn is_provisional(&self, order_id: &str) -> bool {
+        self.txs.values().any(|tx| tx.order_id == order_id && !tx.status.is_settled())
+    }
+
+    /// Every tracked transaction, newest first (`GET /api/finality`)
+    pub fn snapshot(&self) -> Vec<TrackedTx> {
+        let mut txs: Vec<TrackedTx> = self.txs.values().cloned().collect();
+        txs.sort_by_key(|tx| std::cmp::Reverse(tx.submitted_at));
+        txs
+    }
+
+    fn persist(&self, tx: &TrackedTx) {
+        if let Some(journal) = &self.journal {
+            if let Err(e) = journal.append(tx) {
+                eprintln!("⚠️ [Finality] Failed to journal {}: {}", tx.tx_hash, e);
+            }
+        }
+    }
+}
+
+/// Reads receipts and chain heads over JSON-RPC
+#[derive(Debug, Clone)]
+pub struct ReceiptReader {
+    /// Providers per chain, `[finality] rpc_urls` among them
+    rpc: RpcPool,
+}
+
+impl ReceiptReader {
+    pub fn new(config: &FinalityConfig, rpc: RpcPool) -> Self {
+        Self { rpc: rpc.with_timeout(Duration::from_millis(config.timeout_ms)) }
+    }
+
+    async fn call(&self, chain_id: u64, method: &str, params: Value) -> Result<Value, String> {
+        self.rpc.call(chain_id, method, params).await.map_err(|e| format!("{}: {}", method, e))
+    }
+
+    /// Latest block number
+    pub async fn head(&self, chain_id: u64) -> Result<u64, String> {
+        let result = self.call(chain_id, "eth_blockNumber", json!([])).await?;
+        quantity(&result).ok_or_else(|| format!("eth_blockNumber: unexpected {}", result))
+    }
+
+    /// Receipt of `tx_hash`, None if it is not in a block
+    pub async fn receipt(&self, chain_id: u64, tx_hash: &str) -> Result<Option<Receipt>, String> {
+        let result = self.call(chain_id, "eth_getTransactionReceipt", json!([tx_hash])).await?;
+        if result.is_null() {
+            return Ok(None);
+        }
+        let block = quantity(&result["blockNumber"]).ok_or_else(|| format!("receipt of {} has no block", tx_hash))?;
+        Ok(Some(Receipt {
+            block,
+            block_hash: result["blockHash"].as_str().unwrap_or_default().to_string(),
+            success: quantity(&result["status"]) != Some(0),
+        }))
+    }
+}
+
+/// Hex quantity ("0x1b4")
+fn quantity(value: &Value) -> Option<u64> {
+    u64::from_str_radix(value.as_str()?.trim_start_matches("0x"), 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Side;
+
+    fn order(order_id: &str, expected_fill: f64) -> SubmittedOrder {
+        SubmittedOrder {
+            order_id: order_id.to_string(),
+            market_id: "m1".to_string(),
+            token_id: "t1".to_string(),
+            side: Side::Buy,
+            price: 0.45,
+            size: expected_fill,
+            expected_fill,
+            expires_at: None,
+            timestamp: 100,
+        }
+    }
+
+    #[test]
+    fn test_provisional_until_final_and_reorgs() {
+        let config = FinalityConfig { enabled: true, drop_after_secs: 60, ..Default::default() };
+        let mut tracker = FinalityTracker::new(config);
+        assert_eq!(tracker.threshold(137), 128);
+        assert_eq!(tracker.threshold(1), 12);
+        let hashes = vec!["0xaa".to_string(), "0xbb".to_string()];
+        tracker.track_order(&order("o1", 10.0), &hashes, 137, 100);
+        assert_eq!(tracker.get("0xaa").unwrap().size, 5.0);
+        let receipt = |block: u64, hash: &str| Some(Receipt { block, block_hash: hash.to_string(), success: true });
+
+        // Mined: provisional until 128 confirmations
+        assert_eq!(tracker.observe("0xaa", receipt(1_000, "0x01"), 1_010, 110), Some(Transition::Included { block: 1_000 }));
+        assert_eq!(tracker.get("0xaa").unwrap().confirmations, 11);
+        assert!(tracker.is_provisional("o1"));
+        // A reorg takes it out, then it lands in another block
+        assert_eq!(tracker.observe("0xaa", None, 1_020, 120), Some(Transition::Reorged { from_block: 1_000, to_block: None }));
+        assert_eq!(tracker.get("0xaa").unwrap().status, TxStatus::Pending);
+        assert_eq!(tracker.observe("0xaa", receipt(1_021, "0x02"), 1_021, 130), Some(Transition::Included { block: 1_021 }));
+        assert_eq!(tracker.observe("0xaa", receipt(1_022, "0x03"), 1_030, 140),
+            Some(Transition::Reorged { from_block: 1_021, to_block: Some(1_022) }));
+        assert_eq!(tracker.observe("0xaa", receipt(1_022, "0x03"), 1_149, 150), Some(Transition::Final { block: 1_022 }));
+        assert_eq!(tracker.get("0xaa").unwrap().reorgs, 2);
+        assert_eq!(tracker.observe("0xaa", None, 1_200, 160), None, "final is not re-checked");
+
+        // Never mined: dropped once the window passes
+        assert_eq!(tracker.observe("0xbb", None, 1_000, 159), None);
+        assert_eq!(tracker.observe("0xbb", None, 1_000, 160), Some(Transition::Dropped));
+        assert!(!tracker.is_provisional("o1"));
+        assert!(tracker.open().is_empty());
+
+        tracker.track_order(&order("o2", 4.0), &["0xcc".to_string()], 137, 200);
+        assert_eq!(tracker.observe("0xcc", Some(Receipt { block: 1_300, block_hash: "0x04".to_string(), success: false }), 1_300, 210),
+            Some(Transition::Reverted { block: 1_300 }));
+    }
+}
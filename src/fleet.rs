@@ -0,0 +1,242 @@
+//! Fleet: several engine instances behind one API / dashboard
+//!
+//! Every agent's API is namespaced by its agent ID - `/api/<agent>/stats`,
+//! `/api/<agent>/trades` and so on - while the un-prefixed `/api/...` routes
+//! keep serving the default agent (the engine in this process). Agents in
+//! this process are served straight from their `ApiState`; agents running
+//! as separate processes (different strategies or wallets, each with its own
+//! API port) are listed under `[fleet]` and proxied. `GET /api/fleet` puts
+//! every agent's stats side by side with fleet-wide totals. Proxied replies
+//! are buffered, so `/api/<agent>/stream` only works for local agents.
+
+use crate::api::ApiState;
+use crate::config::{FleetAgent, FleetConfig};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use warp::Reply;
+
+/// Path segments under `/api/` that cannot be agent IDs
+const RESERVED: &[&str] = &["fleet"];
+
+/// Agents served by this API process, keyed by agent ID
+#[derive(Clone)]
+pub struct Fleet {
+    default_agent: String,
+    local: BTreeMap<String, ApiState>,
+    /// Agent ID → base URL of its API
+    remote: Arc<BTreeMap<String, String>>,
+    client: reqwest::Client,
+}
+
+/// A request to forward to a remote agent
+#[derive(Debug)]
+pub struct ProxyRequest {
+    pub method: warp::http::Method,
+    /// Path below `/api/<agent>/`
+    pub path: String,
+    pub query: String,
+    pub headers: warp::http::HeaderMap,
+    pub body: warp::hyper::body::Bytes,
+}
+
+/// One agent on the fleet overview
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentSummary {
+    pub agent_id: String,
+    /// "local" or the remote API URL
+    pub location: String,
+    pub reachable: bool,
+    pub error: Option<String>,
+    /// The agent's `/api/stats`
+    pub stats: Option<serde_json::Value>,
+}
+
+/// Sums over the reachable agents (PnL in USD, since collateral can differ)
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FleetTotals {
+    pub agents: usize,
+    pub reachable: usize,
+    pub total_trades: u64,
+    pub open_positions: u64,
+    pub total_pnl_usd: f64,
+}
+
+/// `GET /api/fleet`
+#[derive(Debug, Clone, Serialize)]
+pub struct FleetOverview {
+    pub default_agent: String,
+    pub totals: FleetTotals,
+    pub agents: Vec<AgentSummary>,
+}
+
+impl FleetOverview {
+    pub fn new(default_agent: &str, agents: Vec<AgentSummary>) -> Self {
+        let mut totals = FleetTotals { agents: agents.len(), ..Default::default() };
+        for stats in agents.iter().filter(|a| a.reachable).filter_map(|a| a.stats.as_ref()) {
+            totals.reachable += 1;
+            totals.total_trades += stats["total_trades"].as_u64().unwrap_or(0);
+            totals.open_positions += stats["open_positions"].as_u64().unwrap_or(0);
+            totals.total_pnl_usd += stats["total_pnl_usd"].as_f64().unwrap_or(0.0);
+        }
+        Self { default_agent: default_agent.to_string(), totals, agents }
+    }
+}
+
+/// Agent IDs must be a single path segment that does not shadow a fleet route
+fn valid_agent_id(agent_id: &str) -> bool {
+    !agent_id.is_empty()
+        && !RESERVED.contains(&agent_id)
+        && agent_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+impl Fleet {
+    /// Fleet of one: this process's engine as the default agent
+    pub fn new(config: &FleetConfig, state: ApiState) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .build()
+            .unwrap_or_default();
+        let mut local = BTreeMap::new();
+        local.insert(config.agent_id.clone(), state);
+        let fleet = Self { default_agent: config.agent_id.clone(), local, remote: Arc::default(), client };
+        fleet.with_remote(&config.agents)
+    }
+
+    /// Another engine running in this process
+    pub fn with_agent(mut self, agent_id: &str, state: ApiState) -> Self {
+        if valid_agent_id(agent_id) && !self.remote.contains_key(agent_id) {
+            self.local.insert(agent_id.to_string(), state);
+        } else {
+            eprintln!("⚠️ [Fleet] Ignoring agent {:?}: invalid or duplicate ID", agent_id);
+        }
+        self
+    }
+
+    /// Engines in other processes, reached through their API
+    pub fn with_remote(mut self, agents: &[FleetAgent]) -> Self {
+        let mut remote = (*self.remote).clone();
+        for agent in agents {
+            if valid_agent_id(&agent.id) && !self.local.contains_key(&agent.id) && !remote.contains_key(&agent.id) {
+                remote.insert(agent.id.clone(), agent.url.trim_end_matches('/').to_string());
+            } else {
+                eprintln!("⚠️ [Fleet] Ignoring agent {:?}: invalid or duplicate ID", agent.id);
+            }
+        }
+        self.remote = Arc::new(remote);
+        self
+    }
+
+    pub fn default_state(&self) -> &ApiState {
+        &self.local[&self.default_agent]
+    }
+
+    pub fn local(&self) -> impl Iterator<Item = (&String, &ApiState)> {
+        self.local.iter()
+    }
+
+    pub fn is_remote(&self, agent_id: &str) -> bool {
+        self.remote.contains_key(agent_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.local.len() + self.remote.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Stats of every agent; unreachable remotes are listed with their error
+    pub async fn overview(&self) -> FleetOverview {
+        let mut agents = Vec::new();
+        for (agent_id, state) in &self.local {
+            agents.push(AgentSummary {
+                agent_id: agent_id.clone(),
+                location: "local".to_string(),
+                reachable: true,
+                error: None,
+                stats: serde_json::to_value(crate::api::stats(state).await).ok(),
+            });
+        }
+        for (agent_id, url) in self.remote.iter() {
+            let stats = self.fetch_stats(url).await;
+            agents.push(AgentSummary {
+                agent_id: agent_id.clone(),
+                location: url.clone(),
+                reachable: stats.is_ok(),
+                error: stats.as_ref().err().cloned(),
+                stats: stats.ok(),
+            });
+        }
+        FleetOverview::new(&self.default_agent, agents)
+    }
+
+    async fn fetch_stats(&self, url: &str) -> Result<serde_json::Value, String> {
+        let resp = self.client.get(format!("{}/api/stats", url)).send().await.map_err(|e| e.to_string())?;
+        if !resp.status().is_success() {
+            return Err(format!("stats returned {}", resp.status()));
+        }
+        resp.json().await.map_err(|e| e.to_string())
+    }
+
+    /// Forward a request to a remote agent's `/api/<path>` and relay its reply
+    pub async fn proxy(&self, agent_id: &str, request: ProxyRequest) -> Result<warp::reply::Response, String> {
+        let url = self.remote.get(agent_id).ok_or_else(|| format!("unknown agent {}", agent_id))?;
+        let mut target = format!("{}/api/{}", url, request.path);
+        if !request.query.is_empty() {
+            target = format!("{}?{}", target, request.query);
+        }
+        let method = reqwest::Method::from_bytes(request.method.as_str().as_bytes()).map_err(|e| e.to_string())?;
+        let mut outgoing = self.client.request(method, &target).body(request.body.to_vec());
+        for name in ["content-type", "authorization", "x-client-id"] {
+            if let Some(value) = request.headers.get(name).and_then(|v| v.to_str().ok()) {
+                outgoing = outgoing.header(name, value);
+            }
+        }
+        let resp = outgoing.send().await.map_err(|e| format!("agent {}: {}", agent_id, e))?;
+        let status = warp::http::StatusCode::from_u16(resp.status().as_u16()).unwrap_or(warp::http::StatusCode::BAD_GATEWAY);
+        let mut relayed = Vec::new();
+        for name in ["content-type", "x-total-count", "retry-after"] {
+            if let Some(value) = resp.headers().get(name).and_then(|v| v.to_str().ok()) {
+                relayed.push((name, value.to_string()));
+            }
+        }
+        let body = resp.bytes().await.map_err(|e| format!("agent {}: {}", agent_id, e))?;
+        let mut response = warp::reply::with_status(body.to_vec(), status).into_response();
+        for (name, value) in relayed {
+            if let Ok(value) = warp::http::HeaderValue::from_str(&value) {
+                response.headers_mut().insert(name, value);
+            }
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent(agent_id: &str, reachable: bool, trades: u64, pnl_usd: f64) -> AgentSummary {
+        AgentSummary {
+            agent_id: agent_id.to_string(),
+            location: "local".to_string(),
+            reachable,
+            error: (!reachable).then(|| "connection refused".to_string()),
+            stats: reachable.then(|| serde_json::json!({"total_trades": trades, "open_positions": 1, "total_pnl_usd": pnl_usd})),
+        }
+    }
+
+    #[test]
+    fn test_overview_totals_and_agent_ids() {
+        let overview = FleetOverview::new("arb", vec![agent("arb", true, 10, 4.5), agent("mm", true, 3, -1.5), agent("down", false, 0, 0.0)]);
+        assert_eq!(overview.totals, FleetTotals { agents: 3, reachable: 2, total_trades: 13, open_positions: 2, total_pnl_usd: 3.0 });
+        assert_eq!(overview.default_agent, "arb");
+
+        assert!(valid_agent_id("wallet-2_arb"));
+        assert!(!valid_agent_id("fleet"));
+        assert!(!valid_agent_id("a/b"));
+        assert!(!valid_agent_id(""));
+    }
+}
@@ -0,0 +1,147 @@
+//! Data-freshness-driven polling and edge haircut
+//!
+//! Turns the indexer's reported staleness (`EnvioHealth` data delay plus
+//! query latency) into a continuous response instead of a healthy/unhealthy
+//! flag: the poll interval backs off while the indexer lags (polling faster
+//! cannot return newer data), and signals must clear an extra edge haircut
+//! that grows with staleness, since a stale quote is more likely to be gone.
+//! Signals carry the age of each quote they were built on; the oldest one
+//! sets the haircut, with the grace period and rate configurable per venue.
+
+use crate::config::FreshnessConfig;
+use crate::market_client::EnvioHealth;
+
+/// Adaptive poll interval and edge haircut from the latest health sample
+#[derive(Debug, Clone)]
+pub struct FreshnessModel {
+    config: FreshnessConfig,
+    last: Option<EnvioHealth>,
+}
+
+impl FreshnessModel {
+    pub fn new(config: FreshnessConfig) -> Self {
+        Self { config, last: None }
+    }
+
+    /// Record a health sample
+    pub fn observe(&mut self, health: EnvioHealth) {
+        self.last = Some(health);
+    }
+
+    /// Latest health sample, if any
+    pub fn last(&self) -> Option<&EnvioHealth> {
+        self.last.as_ref()
+    }
+
+    /// Age (ms) of the data behind a fresh query: indexer delay plus round trip
+    pub fn staleness_ms(&self) -> u64 {
+        self.last.as_ref().map_or(0, |h| h.data_delay_ms + h.latency_ms)
+    }
+
+    /// Staleness (ms) beyond the grace period
+    fn excess_ms(&self) -> u64 {
+        self.staleness_ms().saturating_sub(self.config.grace_ms)
+    }
+
+    /// Extra edge ($) a signal must clear on top of the regime threshold
+    pub fn edge_haircut(&self) -> f64 {
+        self.haircut_at(self.staleness_ms())
+    }
+
+    /// The haircut at a given staleness (ms), e.g. one recorded with a past scan
+    pub fn haircut_at(&self, staleness_ms: u64) -> f64 {
+        self.haircut_with(staleness_ms, self.config.grace_ms, self.config.haircut_per_sec, self.config.max_haircut)
+    }
+
+    /// The haircut for a signal built on quotes from `venue` aged `ages_ms` (the
+    /// oldest counts); without ages, the data behind a fresh query is assumed
+    pub fn quote_haircut(&self, venue: &str, ages_ms: &[u64]) -> f64 {
+        let age_ms = ages_ms.iter().copied().max().unwrap_or_else(|| self.staleness_ms());
+        match self.config.venues.get(venue) {
+            Some(v) => self.haircut_with(age_ms,
+                v.grace_ms.unwrap_or(self.config.grace_ms),
+                v.haircut_per_sec.unwrap_or(self.config.haircut_per_sec),
+                v.max_haircut.unwrap_or(self.config.max_haircut)),
+            None => self.haircut_at(age_ms),
+        }
+    }
+
+    fn haircut_with(&self, staleness_ms: u64, grace_ms: u64, haircut_per_sec: f64, max_haircut: f64) -> f64 {
+        if !self.config.enabled {
+            return 0.0;
+        }
+        let excess_secs = staleness_ms.saturating_sub(grace_ms) as f64 / 1000.0;
+        (excess_secs * haircut_per_sec).min(max_haircut)
+    }
+
+    /// Loop interval (seconds) given the scheduler's base interval
+    ///
+    /// Grows by one base interval per `backoff_step_ms` of excess staleness,
+    /// capped at `max_poll_interval_secs`.
+    pub fn poll_interval_secs(&self, base_secs: u64) -> u64 {
+        if !self.config.enabled || self.config.backoff_step_ms == 0 {
+            return base_secs;
+        }
+        let steps = self.excess_ms() / self.config.backoff_step_ms;
+        let interval = base_secs.saturating_mul(1 + steps);
+        interval.min(self.config.max_poll_interval_secs.max(base_secs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::VenueFreshnessConfig;
+
+    fn health(data_delay_ms: u64, latency_ms: u64) -> EnvioHealth {
+        EnvioHealth {
+            latency_ms,
+            block_number: 1,
+            block_timestamp: 0,
+            data_delay_ms,
+            is_healthy: data_delay_ms < 5000,
+        }
+    }
+
+    #[test]
+    fn test_haircut_and_backoff_scale_with_staleness() {
+        let config = FreshnessConfig::default();
+        let mut model = FreshnessModel::new(config.clone());
+        assert_eq!(model.edge_haircut(), 0.0);
+        assert_eq!(model.poll_interval_secs(5), 5);
+
+        // Within the grace period: no change
+        model.observe(health(config.grace_ms - 200, 100));
+        assert_eq!(model.edge_haircut(), 0.0);
+        assert_eq!(model.poll_interval_secs(5), 5);
+
+        // 3s past grace
+        model.observe(health(config.grace_ms + 2800, 200));
+        assert!((model.edge_haircut() - 3.0 * config.haircut_per_sec).abs() < 1e-9);
+        assert!(model.poll_interval_secs(5) > 5);
+
+        // Far behind: both capped
+        model.observe(health(600_000, 0));
+        assert_eq!(model.edge_haircut(), config.max_haircut);
+        assert_eq!(model.poll_interval_secs(5), config.max_poll_interval_secs);
+
+        let off = FreshnessModel { config: FreshnessConfig { enabled: false, ..config }, last: model.last.clone() };
+        assert_eq!(off.edge_haircut(), 0.0);
+        assert_eq!(off.poll_interval_secs(5), 5);
+    }
+
+    #[test]
+    fn test_quote_haircut_per_venue() {
+        let mut config = FreshnessConfig::default();
+        config.venues.insert("sandbox".to_string(), VenueFreshnessConfig { grace_ms: Some(500), haircut_per_sec: Some(0.02), max_haircut: None });
+        let mut model = FreshnessModel::new(config.clone());
+        model.observe(health(1200, 300));
+
+        // The oldest quote counts: a 4s-old book needs 3s × haircut more edge
+        assert!((model.quote_haircut("polymarket", &[200, 4000]) - 3.0 * config.haircut_per_sec).abs() < 1e-9);
+        assert_eq!(model.quote_haircut("sandbox", &[200, 4000]), config.max_haircut, "3.5s × 0.02 is past the cap");
+        assert!((model.quote_haircut("sandbox", &[1500]) - 0.02).abs() < 1e-9);
+        // Unaged signals fall back to the indexer staleness
+        assert_eq!(model.quote_haircut("polymarket", &[]), model.edge_haircut());
+    }
+}
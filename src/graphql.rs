@@ -0,0 +1,322 @@
+//! GraphQL API Module
+//!
+//! Envio-style query endpoint for the dashboard: markets, signals, positions,
+//! trades and metrics with filtering and `limit`/`offset` pagination.
+
+use crate::api::ApiState;
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Schema, SimpleObject};
+
+pub type DashboardSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Default page size when `limit` is not provided
+const DEFAULT_LIMIT: usize = 50;
+/// Hard cap on page size
+const MAX_LIMIT: usize = 500;
+
+#[derive(SimpleObject)]
+pub struct GqlMarket {
+    pub id: String,
+    pub question: String,
+    pub slug: String,
+    pub outcomes: Vec<String>,
+    pub outcome_prices: Vec<f64>,
+    pub clob_token_ids: Vec<String>,
+    pub spread: f64,
+    pub liquidity: f64,
+    pub volume_24hr: f64,
+    pub active: bool,
+    pub accepting_orders: bool,
+}
+
+#[derive(SimpleObject)]
+pub struct GqlSignal {
+    pub market_id: String,
+    pub spread: f64,
+    pub edge: f64,
+    pub recommended_side: String,
+    pub yes_price: f64,
+    pub no_price: f64,
+}
+
+#[derive(SimpleObject)]
+pub struct GqlPosition {
+    pub market_id: String,
+    pub token_id: String,
+    pub side: String,
+    pub size: f64,
+    pub entry_price: f64,
+    pub entry_time: u64,
+    /// Greeks on current prices, null if the market is not tracked
+    pub breakeven_move: Option<f64>,
+    pub delta: Option<f64>,
+    pub hold_edge: Option<f64>,
+    pub secs_to_resolution: Option<u64>,
+    pub theta_per_day: Option<f64>,
+}
+
+#[derive(SimpleObject)]
+pub struct GqlTrade {
+    pub market_id: String,
+    pub token_id: String,
+    pub side: String,
+    pub size: f64,
+    pub entry_price: f64,
+    pub exit_price: f64,
+    pub entry_time: u64,
+    pub exit_time: u64,
+    pub reason: String,
+    pub pnl: f64,
+    pub fees: f64,
+}
+
+#[derive(SimpleObject)]
+pub struct GqlMetrics {
+    pub permission_active: bool,
+    pub daily_limit: f64,
+    pub spent_today: f64,
+    pub remaining_allowance: f64,
+    pub total_trades: usize,
+    pub win_rate: f64,
+    pub total_pnl: f64,
+    pub open_positions: usize,
+    pub capital_utilization: f64,
+    pub avg_recycle_secs: f64,
+    pub allowance_utilization: f64,
+}
+
+/// Apply `offset`/`limit` pagination to an iterator
+fn paginate<T>(items: impl Iterator<Item = T>, limit: Option<usize>, offset: Option<usize>) -> Vec<T> {
+    items
+        .skip(offset.unwrap_or(0))
+        .take(limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT))
+        .collect()
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Markets from the last scan, optionally filtered by status or question text
+    async fn markets(
+        &self,
+        ctx: &Context<'_>,
+        active: Option<bool>,
+        search: Option<String>,
+        min_liquidity: Option<f64>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Vec<GqlMarket> {
+        let state = ctx.data_unchecked::<ApiState>();
+        let markets = state.markets.read().await;
+        let search = search.map(|s| s.to_lowercase());
+        let filtered = markets.iter()
+            .filter(|m| active.is_none_or(|a| m.active == a))
+            .filter(|m| search.as_ref().is_none_or(|s| m.question.to_lowercase().contains(s)))
+            .filter(|m| min_liquidity.is_none_or(|l| m.liquidity >= l))
+            .map(|m| GqlMarket {
+                id: m.id.clone(),
+                question: m.question.clone(),
+                slug: m.slug.clone(),
+                outcomes: m.outcomes.clone(),
+                outcome_prices: m.outcome_prices.clone(),
+                clob_token_ids: m.clob_token_ids.clone(),
+                spread: m.get_spread(),
+                liquidity: m.liquidity,
+                volume_24hr: m.volume_24hr,
+                active: m.active,
+                accepting_orders: m.accepting_orders,
+            });
+        paginate(filtered, limit, offset)
+    }
+
+    /// Signals from the last scan
+    async fn signals(
+        &self,
+        ctx: &Context<'_>,
+        market_id: Option<String>,
+        min_spread: Option<f64>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Vec<GqlSignal> {
+        let state = ctx.data_unchecked::<ApiState>();
+        let signals = state.signals.read().await;
+        let filtered = signals.iter()
+            .filter(|s| market_id.as_ref().is_none_or(|id| &s.market_id == id))
+            .filter(|s| min_spread.is_none_or(|min| s.spread >= min))
+            .map(|s| GqlSignal {
+                market_id: s.market_id.clone(),
+                spread: s.spread,
+                edge: s.edge,
+                recommended_side: format!("{:?}", s.recommended_side),
+                yes_price: s.yes_price,
+                no_price: s.no_price,
+            });
+        paginate(filtered, limit, offset)
+    }
+
+    /// Open positions
+    async fn positions(
+        &self,
+        ctx: &Context<'_>,
+        market_id: Option<String>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Vec<GqlPosition> {
+        let state = ctx.data_unchecked::<ApiState>();
+        let pm = state.position_manager.read().await;
+        let mut positions = pm.get_positions();
+        positions.sort_by(|a, b| (a.entry_time, &a.market_id, &a.token_id).cmp(&(b.entry_time, &b.market_id, &b.token_id)));
+        let markets = state.markets.read().await;
+        let greeks = crate::greeks::for_positions(positions.iter().copied(), &markets, crate::wallet::Wallet::current_timestamp());
+        let filtered = positions.into_iter()
+            .filter(|p| market_id.as_ref().is_none_or(|id| &p.market_id == id))
+            .map(|p| {
+                let g = greeks.get(&p.token_id);
+                GqlPosition {
+                    market_id: p.market_id.clone(),
+                    token_id: p.token_id.clone(),
+                    side: format!("{:?}", p.side),
+                    size: p.size,
+                    entry_price: p.entry_price,
+                    entry_time: p.entry_time,
+                    breakeven_move: g.map(|g| g.breakeven_move),
+                    delta: g.map(|g| g.delta),
+                    hold_edge: g.map(|g| g.hold_edge),
+                    secs_to_resolution: g.and_then(|g| g.secs_to_resolution),
+                    theta_per_day: g.and_then(|g| g.theta_per_day),
+                }
+            });
+        paginate(filtered, limit, offset)
+    }
+
+    /// Closed trades (newest first)
+    async fn trades(
+        &self,
+        ctx: &Context<'_>,
+        market_id: Option<String>,
+        since: Option<u64>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Vec<GqlTrade> {
+        let state = ctx.data_unchecked::<ApiState>();
+        let pm = state.position_manager.read().await;
+        let filtered = pm.history().iter().rev()
+            .filter(|e| market_id.as_ref().is_none_or(|id| &e.position.market_id == id))
+            .filter(|e| since.is_none_or(|t| e.exit_time >= t))
+            .map(|e| GqlTrade {
+                market_id: e.position.market_id.clone(),
+                token_id: e.position.token_id.clone(),
+                side: format!("{:?}", e.position.side),
+                size: e.position.size,
+                entry_price: e.position.entry_price,
+                exit_price: e.exit_price,
+                entry_time: e.position.entry_time,
+                exit_time: e.exit_time,
+                reason: format!("{:?}", e.reason),
+                pnl: e.pnl,
+                fees: e.fees,
+            });
+        paginate(filtered, limit, offset)
+    }
+
+    /// Live agent metrics
+    async fn metrics(&self, ctx: &Context<'_>) -> GqlMetrics {
+        let state = ctx.data_unchecked::<ApiState>();
+        let perm = state.metamask.get_permission().await;
+        let pm = state.position_manager.read().await;
+        let utilization = state.utilization.read().await.report();
+
+        let (active, limit, spent) = match perm {
+            Some(p) => (!p.revoked, p.daily_limit, p.spent_today),
+            None => (false, 0.0, 0.0),
+        };
+
+        GqlMetrics {
+            permission_active: active,
+            daily_limit: limit,
+            spent_today: spent,
+            remaining_allowance: (limit - spent).max(0.0),
+            total_trades: pm.trade_count(),
+            win_rate: pm.win_rate() * 100.0,
+            total_pnl: pm.total_pnl(),
+            open_positions: pm.get_positions().len(),
+            capital_utilization: utilization.capital_utilization,
+            avg_recycle_secs: utilization.avg_recycle_secs,
+            allowance_utilization: utilization.allowance_utilization,
+        }
+    }
+}
+
+/// Build the dashboard schema over the shared API state
+pub fn build_schema(state: ApiState) -> DashboardSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(state)
+        .finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metamask::MetaMaskClient;
+    use crate::positions::{Position, PositionManager};
+    use crate::types::Side;
+    use std::sync::Arc;
+    use tokio::sync::RwLock;
+
+    #[tokio::test]
+    async fn test_positions_pagination() {
+        let mut pm = PositionManager::new(0.005, 0.02, 3600);
+        for i in 0..3 {
+            pm.open_position(Position {
+                market_id: "m1".to_string(),
+                token_id: format!("t{}", i),
+                side: Side::Buy,
+                size: 1.0,
+                entry_price: 0.5,
+                entry_time: i,
+                entry_spread: 0.03,
+                strategy: "arb".to_string(),
+            });
+        }
+        let state = ApiState {
+            metamask: Arc::new(MetaMaskClient::new()),
+            position_manager: Arc::new(RwLock::new(pm)),
+            markets: Arc::new(RwLock::new(Vec::new())),
+            signals: Arc::new(RwLock::new(Vec::new())),
+            health: Arc::new(crate::health::HealthState::new(60, true)),
+            utilization: Arc::new(RwLock::new(crate::utilization::CapitalTracker::new())),
+            orders: Arc::new(std::sync::Mutex::new(crate::orders::OrderRegistry::new())),
+            strategies: Arc::new(std::sync::Mutex::new(crate::budgets::StrategyBook::default())),
+            approvals: Arc::new(std::sync::Mutex::new(crate::approvals::ApprovalQueue::new(Default::default()))),
+            rate_limiter: Arc::new(std::sync::Mutex::new(crate::ratelimit::RateLimiter::new(Default::default()))),
+            equity: Arc::new(std::sync::Mutex::new(crate::equity::EquityCurve::new(300))),
+            watchlist: Arc::new(std::sync::Mutex::new(crate::watchlist::Watchlist::new(Default::default()))),
+            external: Arc::new(std::sync::Mutex::new(crate::external::ExternalInbox::new(Default::default(), None))),
+            intents: Arc::new(std::sync::Mutex::new(crate::intents::IntentBook::new(Default::default(), None))),
+            preview: crate::preview::channel(1).0,
+            holdings: Arc::new(std::sync::Mutex::new(crate::holdings::Holdings::new(Default::default(), ""))),
+            collateral: Default::default(),
+            router: Arc::new(std::sync::Mutex::new(crate::routing::Router::new(Default::default(), "polymarket", Vec::new()))),
+            reconciler: crate::reconcile::Reconciler::new(Default::default(), None, Default::default()),
+            resolutions: Arc::new(std::sync::Mutex::new(crate::resolution::ResolutionTracker::new(Default::default()))),
+            capture: Arc::new(std::sync::Mutex::new(crate::capture::CaptureTracker::new())),
+            perf: crate::perf::PerfMonitor::new(false),
+            portfolio: Arc::new(std::sync::Mutex::new(crate::portfolio::Portfolio::new(Default::default(), &Default::default(), "polymarket", Default::default()))),
+            quoting: Arc::new(std::sync::Mutex::new(crate::quoting::QuoteGovernor::new(Default::default()))),
+            synthetic: Arc::new(std::sync::Mutex::new(crate::synthetic::SyntheticPricer::new(Default::default(), Vec::new()))),
+            events: crate::events::channel(1).0,
+            finality: Arc::new(std::sync::Mutex::new(crate::finality::FinalityTracker::new(Default::default()))),
+            annotations: Arc::new(std::sync::Mutex::new(crate::annotations::Annotations::default())),
+            order_limits: Arc::new(std::sync::Mutex::new(crate::order_limits::OrderRateLimits::new(Default::default()))),
+            rpc_pool: crate::rpc_pool::RpcPool::new(&Default::default()),
+            metrics: crate::metrics::MetricsCollector::new(),
+        };
+        let schema = build_schema(state);
+
+        let res = schema.execute("{ positions(limit: 2, offset: 1) { tokenId } }").await;
+        assert!(res.errors.is_empty());
+        let json = res.data.into_json().unwrap();
+        assert_eq!(json["positions"].as_array().unwrap().len(), 2);
+        assert_eq!(json["positions"][0]["tokenId"], "t1");
+    }
+}
@@ -0,0 +1,198 @@
+//! Position greeks on binary outcome tokens: break-even move, bundle delta,
+//! and the hold edge and theta of complete sets, with optional decay exits
+
+use crate::config::GreeksConfig;
+use crate::fees::FeeModel;
+use crate::positions::{ExitReason, Position};
+use crate::types::{Market, Side};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Deltas below this (shares) are hedged
+const EPS: f64 = 1e-9;
+
+/// Theta is spread over at least this long, so it stays finite at resolution
+const MIN_THETA_SECS: u64 = 3600;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PositionGreeks {
+    /// Current outcome price of the leg
+    pub mark: f64,
+    /// Price move to break even on an exit now, after the exit fee (positive = must rise)
+    pub breakeven_move: f64,
+    /// Bundle value change per unit of the leg's probability, the other outcomes rebalancing
+    pub delta: f64,
+    /// Complete sets the market's positions form
+    pub complete_sets: f64,
+    /// Shares of the leg beyond those sets: what a resolution-risk exit closes
+    pub naked: f64,
+    /// Per set: the $1 settlement minus what the set sells for now after fees (0 without a complete set)
+    pub hold_edge: f64,
+    /// Until the market's end date, None if unknown
+    pub secs_to_resolution: Option<u64>,
+    /// Hold edge per day left, None without a complete set or an end date
+    pub theta_per_day: Option<f64>,
+}
+
+/// Greeks of `positions` on `markets`, by token id (positions of unknown markets are left out)
+pub fn for_positions<'a>(positions: impl IntoIterator<Item = &'a Position>, markets: &[Market], now: u64) -> HashMap<String, PositionGreeks> {
+    let mut by_market: HashMap<&str, Vec<&Position>> = HashMap::new();
+    for position in positions {
+        by_market.entry(position.market_id.as_str()).or_default().push(position);
+    }
+    let mut greeks = HashMap::new();
+    for (market_id, legs) in by_market {
+        let Some(market) = markets.iter().find(|m| m.id == market_id) else { continue };
+        let fee = FeeModel::from_market(market).taker_rate();
+        let prices = &market.outcome_prices;
+        // Signed exposure per outcome, in the market's outcome order
+        let mut exposure = vec![0.0; market.clob_token_ids.len()];
+        for leg in &legs {
+            if let Some(i) = market.clob_token_ids.iter().position(|t| *t == leg.token_id) {
+                exposure[i] += match leg.side {
+                    Side::Buy => leg.size,
+                    Side::Sell => -leg.size,
+                };
+            }
+        }
+        let complete_sets = exposure.iter().copied().fold(f64::INFINITY, f64::min).max(0.0);
+        let hold_edge = match complete_sets > 0.0 && prices.len() == exposure.len() {
+            true => 1.0 - prices.iter().sum::<f64>() * (1.0 - fee),
+            false => 0.0,
+        };
+        let secs_to_resolution = market.end_date.map(|end| end.saturating_sub(now));
+        let theta_per_day = secs_to_resolution.filter(|_| complete_sets > 0.0)
+            .map(|secs| hold_edge / (secs.max(MIN_THETA_SECS) as f64 / 86_400.0));
+        for leg in legs {
+            let Some(i) = market.clob_token_ids.iter().position(|t| *t == leg.token_id) else { continue };
+            let Some(&mark) = prices.get(i) else { continue };
+            let breakeven = match leg.side {
+                Side::Buy => leg.entry_price / (1.0 - fee),
+                Side::Sell => leg.entry_price / (1.0 + fee),
+            };
+            greeks.insert(leg.token_id.clone(), PositionGreeks {
+                mark,
+                breakeven_move: breakeven - mark,
+                delta: delta(&exposure, prices, i),
+                complete_sets,
+                naked: (exposure[i] - complete_sets).abs().min(leg.size),
+                hold_edge,
+                secs_to_resolution,
+                theta_per_day,
+            });
+        }
+    }
+    greeks
+}
+
+/// Bundle value change when outcome `i` gains one unit of probability and the
+/// others give it up in proportion to their prices
+fn delta(exposure: &[f64], prices: &[f64], i: usize) -> f64 {
+    let others: Vec<usize> = (0..exposure.len()).filter(|j| *j != i).collect();
+    let weight_total: f64 = others.iter().map(|j| prices.get(*j).copied().unwrap_or(0.0)).sum();
+    let offset: f64 = others.iter().map(|j| {
+        let weight = match weight_total > 0.0 {
+            true => prices.get(*j).copied().unwrap_or(0.0) / weight_total,
+            false => 1.0 / others.len() as f64,
+        };
+        exposure[*j] * weight
+    }).sum();
+    exposure[i] - offset
+}
+
+/// Why `greeks` call for an exit, if they do
+pub fn exit_reason(greeks: &PositionGreeks, config: &GreeksConfig) -> Option<ExitReason> {
+    if !config.exit_on_decay {
+        return None;
+    }
+    // An unhedged bundle exits only its naked shares, never the legs of its complete sets
+    if greeks.delta.abs() > EPS {
+        return greeks.secs_to_resolution.filter(|secs| greeks.naked > EPS && *secs <= config.unhedged_exit_secs)
+            .map(|_| ExitReason::ResolutionRisk);
+    }
+    greeks.theta_per_day.filter(|theta| *theta < config.min_edge_per_day).map(|_| ExitReason::EdgeDecay)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn market(prices: [f64; 2], end_date: Option<u64>) -> Market {
+        Market {
+            outcome_prices: prices.to_vec(),
+            clob_token_ids: vec!["yes".to_string(), "no".to_string()],
+            taker_base_fee: 100,
+            end_date,
+            ..testing::market()
+        }
+    }
+
+    fn leg(token_id: &str, size: f64, entry_price: f64) -> Position {
+        Position {
+            market_id: "m1".to_string(),
+            token_id: token_id.to_string(),
+            side: Side::Buy,
+            size,
+            entry_price,
+            entry_time: 0,
+            entry_spread: 0.0,
+            strategy: "arb".to_string(),
+        }
+    }
+
+    const NOW: u64 = 1_000_000;
+    const TEN_DAYS: Option<u64> = Some(NOW + 10 * 86_400);
+
+    fn config() -> GreeksConfig {
+        GreeksConfig { exit_on_decay: true, min_edge_per_day: 0.001, unhedged_exit_secs: 3_600 }
+    }
+
+    /// A complete set of 10, bought at 0.45 + 0.48
+    fn complete_set() -> [Position; 2] {
+        [leg("yes", 10.0, 0.45), leg("no", 10.0, 0.48)]
+    }
+
+    /// 10 YES against 4 NO: 4 complete sets and 6 naked YES
+    fn naked() -> [Position; 2] {
+        [leg("yes", 10.0, 0.45), leg("no", 4.0, 0.48)]
+    }
+
+    #[test]
+    fn test_complete_set_is_hedged() {
+        let greeks = for_positions(&complete_set(), &[market([0.40, 0.58], TEN_DAYS)], NOW);
+        let yes = &greeks["yes"];
+        assert!((yes.breakeven_move - (0.45 / 0.99 - 0.40)).abs() < 1e-12);
+        assert_eq!((yes.delta, greeks["no"].delta, yes.complete_sets), (0.0, 0.0, 10.0));
+        let hold_edge = 1.0 - 0.98 * 0.99;
+        assert!((yes.hold_edge - hold_edge).abs() < 1e-12);
+        assert!((yes.theta_per_day.unwrap() - hold_edge / 10.0).abs() < 1e-12);
+        assert!(exit_reason(yes, &config()).is_none(), "0.3c a day is worth holding for");
+    }
+
+    #[test]
+    fn test_decayed_set_exits() {
+        // The set now sells for all but 0.01c of its $1: not worth the wait
+        let rich = for_positions(&complete_set(), &[market([0.45, 0.56], TEN_DAYS)], NOW);
+        assert!(matches!(exit_reason(&rich["yes"], &config()), Some(ExitReason::EdgeDecay)));
+    }
+
+    #[test]
+    fn test_naked_delta_evaluated_per_bundle() {
+        let greeks = for_positions(&naked(), &[market([0.40, 0.58], TEN_DAYS)], NOW);
+        assert!((greeks["yes"].delta - 6.0).abs() < 1e-12);
+        assert!((greeks["no"].delta + 6.0).abs() < 1e-12);
+        assert_eq!((greeks["yes"].naked, greeks["no"].naked), (6.0, 0.0));
+        assert_eq!(greeks["yes"].theta_per_day, Some(greeks["yes"].hold_edge / 10.0));
+        assert!(for_positions(&naked(), &[market([0.40, 0.58], None)], NOW)["yes"].theta_per_day.is_none());
+    }
+
+    #[test]
+    fn test_only_the_naked_leg_exits_before_resolution() {
+        let close_to_end = for_positions(&naked(), &[market([0.40, 0.58], Some(NOW + 1_800))], NOW);
+        assert!(matches!(exit_reason(&close_to_end["yes"], &config()), Some(ExitReason::ResolutionRisk)));
+        assert!(exit_reason(&close_to_end["no"], &config()).is_none(), "hedged by 4 of the YES shares");
+        let far = for_positions(&naked(), &[market([0.40, 0.58], TEN_DAYS)], NOW);
+        assert!(exit_reason(&far["yes"], &config()).is_none());
+    }
+}
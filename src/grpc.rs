@@ -0,0 +1,111 @@
+//! gRPC API Module
+//!
+//! Optional tonic service (feature `grpc`) exposing the same stats, positions and
+//! permission control operations as the warp API, plus a live event stream.
+
+use crate::api::{subscribe_events, ApiState};
+use crate::metamask;
+use std::pin::Pin;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status};
+
+pub mod proto {
+    tonic::include_proto!("arbishark.v1");
+}
+
+use proto::arbi_shark_server::{ArbiShark, ArbiSharkServer};
+use proto::{Ack, Empty, Event, PermissionGrant, Position, PositionList, Stats};
+
+/// gRPC service backed by the shared API state
+pub struct GrpcService {
+    state: ApiState,
+}
+
+#[tonic::async_trait]
+#[allow(clippy::result_large_err)]
+impl ArbiShark for GrpcService {
+    async fn get_stats(&self, _request: Request<Empty>) -> Result<Response<Stats>, Status> {
+        let perm = self.state.metamask.get_permission().await;
+        let pm = self.state.position_manager.read().await;
+
+        let (active, limit, spent) = match perm {
+            Some(p) => (!p.revoked, p.daily_limit, p.spent_today),
+            None => (false, 0.0, 0.0),
+        };
+
+        Ok(Response::new(Stats {
+            connected: true,
+            permission_active: active,
+            daily_limit: limit,
+            spent_today: spent,
+            total_trades: pm.trade_count() as u64,
+            win_rate: pm.win_rate() * 100.0,
+            total_pnl: pm.total_pnl(),
+            open_positions: pm.get_positions().len() as u64,
+        }))
+    }
+
+    async fn list_positions(&self, _request: Request<Empty>) -> Result<Response<PositionList>, Status> {
+        let pm = self.state.position_manager.read().await;
+        let positions = pm.get_positions().into_iter().map(|pos| Position {
+            market_id: pos.market_id.clone(),
+            token_id: pos.token_id.clone(),
+            side: format!("{:?}", pos.side),
+            size: pos.size,
+            entry_price: pos.entry_price,
+            entry_time: pos.entry_time,
+        }).collect();
+        Ok(Response::new(PositionList { positions }))
+    }
+
+    async fn set_permission(&self, request: Request<PermissionGrant>) -> Result<Response<Ack>, Status> {
+        let g = request.into_inner();
+        crate::api::push_log(&format!("📥 [gRPC] Received permission grant: {}", g.permission_id));
+        crate::api::check_grant_token(&self.state, &g.token).map_err(Status::invalid_argument)?;
+        self.state.metamask.set_permission(metamask::PermissionGrant {
+            permission_id: g.permission_id,
+            token: g.token,
+            daily_limit: g.daily_limit,
+            spent_today: g.spent_today,
+            expires_at: g.expires_at,
+            granted_at: g.granted_at,
+            revoked: g.revoked,
+            scope: Default::default(),
+        }).await;
+        Ok(Response::new(Ack { status: "ok".to_string() }))
+    }
+
+    async fn revoke_permission(&self, _request: Request<Empty>) -> Result<Response<Ack>, Status> {
+        self.state.metamask.revoke_permission().await
+            .map_err(|e| Status::failed_precondition(e.to_string()))?;
+        Ok(Response::new(Ack { status: "ok".to_string() }))
+    }
+
+    type StreamEventsStream = Pin<Box<dyn Stream<Item = Result<Event, Status>> + Send>>;
+
+    async fn stream_events(&self, _request: Request<Empty>) -> Result<Response<Self::StreamEventsStream>, Status> {
+        // Lagged receivers simply skip missed events
+        let stream = BroadcastStream::new(subscribe_events()).filter_map(|msg| {
+            msg.ok().map(|message| Ok(Event {
+                timestamp: crate::wallet::Wallet::current_timestamp(),
+                message,
+            }))
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+/// Start the gRPC server
+pub async fn start_server(state: ApiState, port: u16) {
+    let addr = ([127, 0, 0, 1], port).into();
+    println!("🛰️ [gRPC] Server starting on {}", addr);
+    crate::api::push_log("🛰️ [gRPC] Server started");
+    if let Err(e) = tonic::transport::Server::builder()
+        .add_service(ArbiSharkServer::new(GrpcService { state }))
+        .serve(addr)
+        .await
+    {
+        println!("❌ [gRPC] Server error: {}", e);
+    }
+}
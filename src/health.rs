@@ -0,0 +1,148 @@
+//! Health and readiness probes
+//!
+//! Liveness (`/healthz`): the main loop has ticked recently.
+//! Readiness (`/readyz`): data source healthy, permission present, config valid.
+//! Liveness also reports how often the supervisor had to restart each task.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Shared health state updated by the main loop
+#[derive(Debug)]
+pub struct HealthState {
+    /// Unix timestamp of the last loop tick (0 = never)
+    last_tick: AtomicU64,
+    /// Last market data fetch succeeded
+    data_source_healthy: AtomicBool,
+    /// Configuration passed validation at startup
+    config_valid: AtomicBool,
+    /// Maximum age (seconds) of the last tick before liveness fails
+    max_tick_age_secs: u64,
+    /// Supervisor restarts per task
+    restarts: Mutex<BTreeMap<String, u64>>,
+}
+
+/// Probe response body
+#[derive(Debug, Serialize)]
+pub struct ProbeReport {
+    pub ok: bool,
+    pub checks: Vec<ProbeCheck>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProbeCheck {
+    pub name: &'static str,
+    pub ok: bool,
+    pub detail: String,
+}
+
+impl HealthState {
+    pub fn new(max_tick_age_secs: u64, config_valid: bool) -> Self {
+        Self {
+            last_tick: AtomicU64::new(0),
+            data_source_healthy: AtomicBool::new(false),
+            config_valid: AtomicBool::new(config_valid),
+            max_tick_age_secs,
+            restarts: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Record a main loop tick
+    pub fn tick(&self, now: u64) {
+        self.last_tick.store(now, Ordering::Relaxed);
+    }
+
+    /// Record the outcome of the last data fetch
+    pub fn set_data_source_healthy(&self, healthy: bool) {
+        self.data_source_healthy.store(healthy, Ordering::Relaxed);
+    }
+
+    /// Count a supervisor restart of `task`
+    pub fn record_restart(&self, task: &str) {
+        *self.restarts.lock().unwrap().entry(task.to_string()).or_insert(0) += 1;
+    }
+
+    /// Supervisor restarts per task
+    pub fn restarts(&self) -> BTreeMap<String, u64> {
+        self.restarts.lock().unwrap().clone()
+    }
+
+    /// Liveness: process alive and the event loop ticking
+    pub fn liveness(&self, now: u64) -> ProbeReport {
+        let last = self.last_tick.load(Ordering::Relaxed);
+        let age = now.saturating_sub(last);
+        let ticking = last > 0 && age <= self.max_tick_age_secs;
+        let detail = if last == 0 {
+            "event loop has not ticked yet".to_string()
+        } else {
+            format!("last tick {}s ago (max {}s)", age, self.max_tick_age_secs)
+        };
+        let mut checks = vec![ProbeCheck { name: "event_loop", ok: ticking, detail }];
+        let restarts = self.restarts();
+        if !restarts.is_empty() {
+            // Informational: a restarted task has recovered, the count is the metric
+            let detail = restarts.iter().map(|(task, n)| format!("{}: {}", task, n)).collect::<Vec<_>>().join(", ");
+            checks.push(ProbeCheck { name: "task_restarts", ok: true, detail });
+        }
+        Self::report(checks)
+    }
+
+    /// Readiness: safe to route traffic / consider the agent operational
+    pub fn readiness(&self, permission_present: bool) -> ProbeReport {
+        let data_ok = self.data_source_healthy.load(Ordering::Relaxed);
+        let config_ok = self.config_valid.load(Ordering::Relaxed);
+        Self::report(vec![
+            ProbeCheck {
+                name: "data_source",
+                ok: data_ok,
+                detail: if data_ok { "last fetch succeeded" } else { "last fetch failed or none yet" }.to_string(),
+            },
+            ProbeCheck {
+                name: "permission",
+                ok: permission_present,
+                detail: if permission_present { "valid permission" } else { "no valid permission" }.to_string(),
+            },
+            ProbeCheck {
+                name: "config",
+                ok: config_ok,
+                detail: if config_ok { "valid" } else { "validation failed" }.to_string(),
+            },
+        ])
+    }
+
+    fn report(checks: Vec<ProbeCheck>) -> ProbeReport {
+        ProbeReport {
+            ok: checks.iter().all(|c| c.ok),
+            checks,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_liveness_and_readiness() {
+        let health = HealthState::new(30, true);
+        assert!(!health.liveness(1000).ok);
+
+        health.tick(1000);
+        assert!(health.liveness(1020).ok);
+        assert!(!health.liveness(1031).ok);
+
+        health.record_restart("engine");
+        health.record_restart("engine");
+        assert_eq!(health.restarts()["engine"], 2);
+        let report = health.liveness(1020);
+        assert!(report.ok);
+        assert_eq!(report.checks[1].detail, "engine: 2");
+
+        assert!(!health.readiness(true).ok);
+        health.set_data_source_healthy(true);
+        assert!(health.readiness(true).ok);
+        assert!(!health.readiness(false).ok);
+    }
+}
@@ -0,0 +1,401 @@
+//! Importing existing holdings
+//!
+//! Users may already hold outcome tokens when they start the bot. An import
+//! reads the wallet's Conditional Token (ERC-1155) balances on-chain with
+//! `eth_call balanceOf` and opens a position for whatever the bot does not
+//! already track, at a user-supplied cost basis or, failing that, the
+//! market's current outcome price. Imported positions are ordinary positions
+//! from then on: they count towards open notional and go through the same
+//! exit logic. Imports and their closes are journaled, so open imports are
+//! reopened on restart without reading the chain (or double counting) again.
+
+use crate::config::HoldingsConfig;
+use crate::positions::Position;
+use crate::preflight::selector;
+use crate::rpc_pool::RpcPool;
+use crate::storage::JsonlStore;
+use crate::types::Side;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// ERC-1155 balance query on the Conditional Tokens contract
+pub const BALANCE_OF_SIGNATURE: &str = "balanceOf(address,uint256)";
+
+/// Outcome tokens use USDC's 6 decimals
+const TOKEN_DECIMALS: i32 = 6;
+
+/// Balances below this (shares) are dust and not imported
+const MIN_IMPORT_SIZE: f64 = 1e-6;
+
+/// Where an imported position's cost basis came from
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BasisSource {
+    User,
+    /// Market outcome price (or book midpoint) at import time
+    Oracle,
+}
+
+/// One outcome token imported as a position
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedHolding {
+    pub market_id: String,
+    pub token_id: String,
+    pub outcome: String,
+    pub size: f64,
+    /// Entry price per share
+    pub cost_basis: f64,
+    pub basis_source: BasisSource,
+    pub imported_at: u64,
+    /// Market spread at import, the stop-loss reference
+    pub entry_spread: f64,
+}
+
+impl ImportedHolding {
+    pub fn position(&self, strategy: &str) -> Position {
+        Position {
+            market_id: self.market_id.clone(),
+            token_id: self.token_id.clone(),
+            side: Side::Buy,
+            size: self.size,
+            entry_price: self.cost_basis,
+            entry_time: self.imported_at,
+            entry_spread: self.entry_spread,
+            strategy: strategy.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum HoldingEvent {
+    Imported(ImportedHolding),
+    Closed { token_id: String, closed_at: u64 },
+    /// Partly exited, `size` left open
+    Reduced { token_id: String, size: f64, reduced_at: u64 },
+}
+
+/// `POST /api/positions/import` body
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImportRequest {
+    pub market_id: String,
+    /// Cost basis per token id; tokens without one use the market price
+    #[serde(default)]
+    pub cost_basis: HashMap<String, f64>,
+}
+
+/// One outcome token of a market being imported
+#[derive(Debug, Clone)]
+pub struct ImportLeg {
+    pub token_id: String,
+    pub outcome: String,
+    /// On-chain balance (shares)
+    pub balance: f64,
+    /// Shares of this token the bot already tracks as a position
+    pub tracked: f64,
+    /// Price used when no cost basis is supplied
+    pub oracle_price: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HoldingsError {
+    /// No wallet configured to import from
+    NotConfigured,
+    Rpc(String),
+    Invalid(String),
+}
+
+impl std::fmt::Display for HoldingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotConfigured => write!(f, "holdings.owner is not configured"),
+            Self::Rpc(e) => write!(f, "balance read failed: {}", e),
+            Self::Invalid(e) => write!(f, "invalid import: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for HoldingsError {}
+
+/// Decimal token id as a big-endian uint256
+fn parse_uint256(decimal: &str) -> Option<[u8; 32]> {
+    if decimal.is_empty() || !decimal.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let mut word = [0u8; 32];
+    for digit in decimal.bytes() {
+        let mut carry = (digit - b'0') as u32;
+        for byte in word.iter_mut().rev() {
+            let value = *byte as u32 * 10 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        if carry != 0 {
+            return None;
+        }
+    }
+    Some(word)
+}
+
+/// ABI-encoded `balanceOf(owner, token_id)` calldata
+pub fn balance_of_calldata(owner: &str, token_id: &str) -> Result<String, HoldingsError> {
+    let address = hex::decode(owner.trim_start_matches("0x"))
+        .ok()
+        .filter(|a| a.len() == 20)
+        .ok_or_else(|| HoldingsError::Invalid(format!("bad owner address {}", owner)))?;
+    let id = parse_uint256(token_id).ok_or_else(|| HoldingsError::Invalid(format!("bad token id {}", token_id)))?;
+    let mut data = selector(BALANCE_OF_SIGNATURE).to_vec();
+    data.extend_from_slice(&[0u8; 12]);
+    data.extend_from_slice(&address);
+    data.extend_from_slice(&id);
+    Ok(format!("0x{}", hex::encode(data)))
+}
+
+/// Shares from an `eth_call` uint256 result
+fn decode_balance(result: &str) -> Result<f64, HoldingsError> {
+    let bytes = hex::decode(result.trim_start_matches("0x")).map_err(|e| HoldingsError::Rpc(e.to_string()))?;
+    if bytes.len() != 32 || bytes[..16].iter().any(|b| *b != 0) {
+        return Err(HoldingsError::Rpc(format!("unexpected balance {}", result)));
+    }
+    let raw = u128::from_be_bytes(bytes[16..].try_into().unwrap());
+    Ok(raw as f64 / 10f64.powi(TOKEN_DECIMALS))
+}
+
+/// Reads Conditional Token balances of the configured wallet
+#[derive(Debug, Clone)]
+pub struct HoldingsReader {
+    config: HoldingsConfig,
+    /// Conditional Tokens contract on `config.chain_id`
+    ctf_contract: String,
+    /// Providers of `config.chain_id`; balances are cross-checked between them
+    rpc: RpcPool,
+}
+
+impl HoldingsReader {
+    pub fn new(config: HoldingsConfig, ctf_contract: &str) -> Self {
+        let rpc = RpcPool::new(&Default::default()).with_timeout(Duration::from_millis(config.timeout_ms));
+        rpc.add(config.chain_id, &config.rpc_url);
+        Self { config, ctf_contract: ctf_contract.to_string(), rpc }
+    }
+
+    pub fn strategy(&self) -> &str {
+        &self.config.strategy
+    }
+
+    /// On-chain balance (shares) of one outcome token
+    pub async fn balance(&self, token_id: &str) -> Result<f64, HoldingsError> {
+        if self.config.owner.is_empty() {
+            return Err(HoldingsError::NotConfigured);
+        }
+        let call = json!([{
+            "to": self.ctf_contract,
+            "data": balance_of_calldata(&self.config.owner, token_id)?,
+        }, "latest"]);
+        let result = self.rpc.call_checked(self.config.chain_id, "eth_call", call).await
+            .map_err(|e| HoldingsError::Rpc(e.to_string()))?;
+        decode_balance(result.as_str().unwrap_or_default())
+    }
+}
+
+/// Holdings to import from a market's legs: the untracked part of each
+/// non-zero balance, at the supplied cost basis or the oracle price
+pub fn plan_import(market_id: &str, legs: &[ImportLeg], cost_basis: &HashMap<String, f64>, entry_spread: f64, now: u64)
+    -> Result<Vec<ImportedHolding>, HoldingsError> {
+    if let Some(token_id) = cost_basis.keys().find(|t| !legs.iter().any(|l| l.token_id == **t)) {
+        return Err(HoldingsError::Invalid(format!("token {} is not in market {}", token_id, market_id)));
+    }
+    let mut holdings = Vec::new();
+    for leg in legs {
+        let size = leg.balance - leg.tracked;
+        if size < MIN_IMPORT_SIZE {
+            continue;
+        }
+        let (cost_basis, basis_source) = match cost_basis.get(&leg.token_id) {
+            Some(price) => (*price, BasisSource::User),
+            None => (leg.oracle_price.unwrap_or_default(), BasisSource::Oracle),
+        };
+        if !(cost_basis > 0.0 && cost_basis < 1.0) {
+            return Err(HoldingsError::Invalid(format!("no usable cost basis for token {} ({})", leg.token_id, cost_basis)));
+        }
+        holdings.push(ImportedHolding {
+            market_id: market_id.to_string(),
+            token_id: leg.token_id.clone(),
+            outcome: leg.outcome.clone(),
+            size,
+            cost_basis,
+            basis_source,
+            imported_at: now,
+            entry_spread,
+        });
+    }
+    Ok(holdings)
+}
+
+/// Journal of imported holdings that are still open
+#[derive(Debug)]
+pub struct Holdings {
+    reader: HoldingsReader,
+    open: Vec<ImportedHolding>,
+    /// Imported through the API, not yet opened by the engine
+    pending: Vec<ImportedHolding>,
+    journal: Option<JsonlStore>,
+}
+
+impl Holdings {
+    pub fn new(config: HoldingsConfig, ctf_contract: &str) -> Self {
+        Self { reader: HoldingsReader::new(config, ctf_contract), open: Vec::new(), pending: Vec::new(), journal: None }
+    }
+
+    /// Read balances through `rpc`'s providers instead of `holdings.rpc_url` alone
+    pub fn with_rpc_pool(mut self, rpc: RpcPool) -> Self {
+        self.reader.rpc = rpc.with_timeout(Duration::from_millis(self.reader.config.timeout_ms));
+        self
+    }
+
+    /// Journal to `journal`, restoring the imports still open
+    pub fn with_journal(mut self, journal: JsonlStore) -> Self {
+        let events: Vec<HoldingEvent> = journal.load().unwrap_or_else(|e| {
+            eprintln!("⚠️ [Holdings] Failed to read holdings journal: {}", e);
+            Vec::new()
+        });
+        for event in events {
+            match event {
+                HoldingEvent::Imported(holding) => self.open.push(holding),
+                HoldingEvent::Closed { token_id, .. } => self.open.retain(|h| h.token_id != token_id),
+                HoldingEvent::Reduced { token_id, size, .. } => {
+                    if let Some(holding) = self.open.iter_mut().find(|h| h.token_id == token_id) {
+                        holding.size = size;
+                    }
+                }
+            }
+        }
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Balance reader for building an import
+    pub fn reader(&self) -> HoldingsReader {
+        self.reader.clone()
+    }
+
+    /// Imported positions still open
+    pub fn open_holdings(&self) -> &[ImportedHolding] {
+        &self.open
+    }
+
+    fn record(&self, event: &HoldingEvent) {
+        if let Some(journal) = &self.journal {
+            if let Err(e) = journal.append(event) {
+                eprintln!("⚠️ [Holdings] Failed to journal holdings: {}", e);
+            }
+        }
+    }
+
+    /// Journal new imports and queue them for the engine
+    pub fn import(&mut self, holdings: Vec<ImportedHolding>) -> Result<(), HoldingsError> {
+        if let Some(dup) = holdings.iter().find(|h| self.open.iter().any(|o| o.token_id == h.token_id)) {
+            return Err(HoldingsError::Invalid(format!("token {} is already imported", dup.token_id)));
+        }
+        for holding in holdings {
+            self.record(&HoldingEvent::Imported(holding.clone()));
+            self.open.push(holding.clone());
+            self.pending.push(holding);
+        }
+        Ok(())
+    }
+
+    /// Imports the engine has not opened yet
+    pub fn take_pending(&mut self) -> Vec<ImportedHolding> {
+        std::mem::take(&mut self.pending)
+    }
+
+    /// Record that an imported position was exited; false if it was not imported
+    pub fn close(&mut self, token_id: &str, now: u64) -> bool {
+        let before = self.open.len();
+        self.open.retain(|h| h.token_id != token_id);
+        let closed = self.open.len() < before;
+        if closed {
+            self.record(&HoldingEvent::Closed { token_id: token_id.to_string(), closed_at: now });
+        }
+        closed
+    }
+
+    /// Record that `sold` shares of an imported position were exited, closing it once
+    /// nothing is left; false if it was not imported
+    pub fn reduce(&mut self, token_id: &str, sold: f64, now: u64) -> bool {
+        let Some(holding) = self.open.iter_mut().find(|h| h.token_id == token_id) else { return false };
+        let size = holding.size - sold;
+        if size <= MIN_IMPORT_SIZE {
+            return self.close(token_id, now);
+        }
+        holding.size = size;
+        self.record(&HoldingEvent::Reduced { token_id: token_id.to_string(), size, reduced_at: now });
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leg(token_id: &str, balance: f64, tracked: f64, oracle_price: Option<f64>) -> ImportLeg {
+        ImportLeg { token_id: token_id.to_string(), outcome: "Yes".to_string(), balance, tracked, oracle_price }
+    }
+
+    #[test]
+    fn test_encoding_planning_and_journal() {
+        assert_eq!(parse_uint256("258").unwrap()[30..], [1, 2]);
+        assert!(parse_uint256("12a").is_none());
+        let calldata = balance_of_calldata("0x00000000000000000000000000000000000000aa", "1").unwrap();
+        assert_eq!(&calldata[..10], "0x00fdd58e");
+        assert_eq!(calldata.len(), 2 + 2 * (4 + 32 + 32));
+        assert!(balance_of_calldata("0x12", "1").is_err());
+        assert_eq!(decode_balance(&format!("0x{:064x}", 12_500_000u64)).unwrap(), 12.5);
+
+        // Tracked shares are not imported twice; missing bases fall back to the oracle
+        let legs = [leg("1", 12.5, 2.5, Some(0.4)), leg("2", 0.0, 0.0, Some(0.6)), leg("3", 5.0, 0.0, None)];
+        let basis = HashMap::from([("3".to_string(), 0.55)]);
+        let planned = plan_import("m1", &legs, &basis, 0.02, 100).unwrap();
+        assert_eq!(planned.len(), 2);
+        assert_eq!((planned[0].size, planned[0].cost_basis, planned[0].basis_source), (10.0, 0.4, BasisSource::Oracle));
+        assert_eq!((planned[1].cost_basis, planned[1].basis_source), (0.55, BasisSource::User));
+        assert!(plan_import("m1", &legs, &HashMap::new(), 0.0, 100).is_err());
+        assert!(plan_import("m1", &legs, &HashMap::from([("9".to_string(), 0.5)]), 0.0, 100).is_err());
+
+        let dir = std::env::temp_dir().join(format!("arbishark_holdings_{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+        let journal = JsonlStore::open(dir, "holdings.jsonl").unwrap();
+        let mut holdings = Holdings::new(HoldingsConfig::default(), "").with_journal(journal.clone());
+        holdings.import(planned.clone()).unwrap();
+        assert!(holdings.import(planned[..1].to_vec()).is_err());
+        assert_eq!(holdings.take_pending().len(), 2);
+        assert!(holdings.take_pending().is_empty());
+        assert!(holdings.close("1", 200));
+        assert!(!holdings.close("1", 200));
+
+        let restored = Holdings::new(HoldingsConfig::default(), "").with_journal(journal);
+        assert_eq!(restored.open_holdings().len(), 1);
+        let position = restored.open_holdings()[0].position("imported");
+        assert_eq!((position.token_id.as_str(), position.entry_price), ("3", 0.55));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_partial_exit_keeps_the_rest_open() {
+        let dir = std::env::temp_dir().join(format!("arbishark_holdings_reduce_{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+        let journal = JsonlStore::open(dir, "holdings.jsonl").unwrap();
+        let mut holdings = Holdings::new(HoldingsConfig::default(), "").with_journal(journal.clone());
+        holdings.import(plan_import("m1", &[leg("1", 10.0, 0.0, Some(0.4))], &HashMap::new(), 0.02, 100).unwrap()).unwrap();
+        assert!(holdings.reduce("1", 6.0, 200));
+        assert!(!holdings.reduce("2", 1.0, 200));
+
+        let mut restored = Holdings::new(HoldingsConfig::default(), "").with_journal(journal);
+        assert_eq!(restored.open_holdings()[0].size, 4.0);
+        assert!(restored.reduce("1", 4.0, 300));
+        assert!(restored.open_holdings().is_empty());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,270 @@
+//! Market inspector for `arbishark inspect <market-slug>`
+//!
+//! An operator's view of one market, built from the engine's own pieces:
+//! the market is looked up on Gamma by slug, every outcome book is fetched
+//! from the CLOB and shown as a depth ladder, the bundle is priced at
+//! several sizes with the preview cost breakdown, and the thresholds that
+//! can be judged from config alone (spread floor, regime edge, minimum
+//! profit, fill, compliance rules, trading windows) are run against it.
+//! Checks that depend on the running engine's state - risk, allowance,
+//! entry throttles - are answered by `POST /api/preview` instead.
+
+use crate::arb::ArbitrageDetector;
+use crate::attribution;
+use crate::compliance::Compliance;
+use crate::config::Config;
+use crate::fees::FeeModel;
+use crate::market_client::parse_gamma_market;
+use crate::preview::{self, CostPreview, PreviewRequest};
+use crate::types::{Market, OrderBook, PriceLevel, Side};
+use crate::windows::TradingWindows;
+
+/// Sizes per leg the bundle is priced at, besides `trading.trade_size`
+const QUOTE_SIZES: [f64; 5] = [1.0, 10.0, 50.0, 100.0, 500.0];
+/// Levels shown per side of each ladder
+pub const LADDER_DEPTH: usize = 10;
+const BAR_WIDTH: usize = 16;
+
+/// Look `slug` up as a market slug, then as an event slug (its first tradable market)
+pub async fn fetch_market(client: &reqwest::Client, gamma_url: &str, slug: &str) -> Result<Market, String> {
+    // `gamma_url` is the configured events endpoint; markets live next to it
+    let base = gamma_url.split('?').next().unwrap_or(gamma_url).trim_end_matches('/').trim_end_matches("/events");
+    let get = |url: String| async move {
+        let resp = client.get(&url).send().await.map_err(|e| e.to_string())?;
+        if !resp.status().is_success() {
+            return Err(format!("{} returned {}", url, resp.status()));
+        }
+        resp.json::<serde_json::Value>().await.map_err(|e| e.to_string())
+    };
+    let markets = get(format!("{}/markets?slug={}", base, slug)).await?;
+    if let Some(market) = markets.as_array().and_then(|arr| arr.iter().find_map(|m| parse_gamma_market(m, m))) {
+        return Ok(market);
+    }
+    let events = get(format!("{}/events?slug={}", base, slug)).await?;
+    events.as_array().into_iter().flatten()
+        .flat_map(|event| event["markets"].as_array().into_iter().flatten().map(move |m| (m, event)))
+        .find_map(|(m, event)| parse_gamma_market(m, event))
+        .ok_or_else(|| format!("no tradable market or event with slug {}", slug))
+}
+
+/// Look a market up by its Gamma id
+pub async fn fetch_market_by_id(client: &reqwest::Client, gamma_url: &str, id: &str) -> Result<Market, String> {
+    let base = gamma_url.split('?').next().unwrap_or(gamma_url).trim_end_matches('/').trim_end_matches("/events");
+    let url = format!("{}/markets/{}", base, id);
+    let resp = client.get(&url).send().await.map_err(|e| e.to_string())?;
+    if !resp.status().is_success() {
+        return Err(format!("{} returned {}", url, resp.status()));
+    }
+    let m = resp.json::<serde_json::Value>().await.map_err(|e| e.to_string())?;
+    parse_gamma_market(&m, &m).ok_or_else(|| format!("market {} is not tradable", id))
+}
+
+/// Everything the inspector shows for one market
+#[derive(Debug, Clone)]
+pub struct Inspection {
+    pub market: Market,
+    pub books: Vec<OrderBook>,
+    /// Buy bundle priced at increasing sizes per leg
+    pub quotes: Vec<CostPreview>,
+    /// The quote at `trading.trade_size`, with the threshold checks
+    pub trade: CostPreview,
+}
+
+impl Inspection {
+    /// Price `market` at `books` (one per outcome) and run the config thresholds at `now`
+    pub fn new(mut market: Market, books: Vec<OrderBook>, config: &Config, now: u64) -> Result<Self, String> {
+        // As in the engine's market refresh: outcome prices are the book midpoints
+        for (price, book) in market.outcome_prices.iter_mut().zip(&books) {
+            if let Some(mid) = book.midpoint().filter(|m| *m > 0.0) {
+                *price = mid;
+            }
+        }
+        let fees = FeeModel { maker_fee_bps: 0, taker_fee_bps: 200 };
+        let quote = |size: f64| preview::cost_breakdown(&PreviewRequest { market_id: market.id.clone(), side: Side::Buy, size },
+            &market, &books, &fees, config.trading.gas_per_leg_usd, config.timing.adverse_selection_std);
+        let mut sizes: Vec<f64> = QUOTE_SIZES.iter().copied().chain([config.trading.trade_size]).collect();
+        sizes.sort_by(f64::total_cmp);
+        sizes.dedup();
+        let quotes = sizes.into_iter().map(quote).collect();
+
+        let mut trade = quote(config.trading.trade_size);
+        trade.check("accepting_orders", if market.active && market.accepting_orders {
+            Ok("active".to_string())
+        } else {
+            Err("market is closed or not accepting orders".to_string())
+        });
+        let detector = ArbitrageDetector::new(config.trading.min_spread_threshold, config.trading.min_profit_threshold);
+        let sum: f64 = market.outcome_prices.iter().sum();
+        let signal = detector.scan(std::slice::from_ref(&market)).pop();
+        trade.check("spread", match &signal {
+            Some(s) => Ok(format!("midpoints sum to {:.4}, spread {:.4} > {:.4}", sum, s.spread, config.trading.min_spread_threshold)),
+            None => Err(format!("midpoints sum to {:.4}, spread {:.4} within {:.4}", sum, (sum - 1.0).abs(), config.trading.min_spread_threshold)),
+        });
+        // The live threshold adds a staleness haircut and widens with volatility; calm is its floor
+        let min_edge = config.strategy.regimes.calm.min_edge;
+        trade.check("regime_edge", match &signal {
+            Some(s) if s.edge >= min_edge => Ok(format!("edge ${:.4} >= ${:.4} (calm)", s.edge, min_edge)),
+            Some(s) => Err(format!("edge ${:.4} below ${:.4} (calm)", s.edge, min_edge)),
+            None => Err("no signal".to_string()),
+        });
+        trade.check("side", match signal.as_ref().map(|s| s.recommended_side) {
+            Some(Side::Sell) => Err("signal is a sell bundle; only buy bundles are executed".to_string()),
+            _ => Ok("buy bundle".to_string()),
+        });
+        let fillable = trade.legs.iter().map(|l| l.fillable_size).fold(f64::INFINITY, f64::min);
+        let size = config.trading.trade_size;
+        trade.check("fill", if fillable + 1e-9 >= size {
+            Ok(format!("books fill {:.2} per leg", size))
+        } else {
+            Err(format!("books fill only {:.2} of {:.2} per leg", fillable, size))
+        });
+        let net_edge = trade.net_edge;
+        trade.check("edge", if net_edge >= config.trading.min_profit_threshold {
+            Ok(format!("net edge ${:.4}", net_edge))
+        } else {
+            Err(format!("net edge ${:.4} below ${:.2} minimum", net_edge, config.trading.min_profit_threshold))
+        });
+        let category = attribution::categorize(&market);
+        if config.compliance.enabled {
+            let compliance = Compliance::from_config(&config.compliance).map_err(|e| format!("invalid compliance rules: {}", e))?;
+            let decision = compliance.evaluate(&market, category, 0.0, trade.total_notional, now);
+            trade.check("compliance", match decision.violations().first() {
+                Some(v) => Err(format!("rule {} - {}", v.rule_id, v.detail)),
+                None if decision.checks.is_empty() => Ok("no rules".to_string()),
+                None => Ok(decision.trace()),
+            });
+        }
+        if config.trading_windows.enabled {
+            let windows = TradingWindows::from_config(&config.trading_windows).map_err(|e| e.to_string())?;
+            trade.check("trading_window", windows.check(now, &market.id, category)
+                .map(|()| "open".to_string()).map_err(|e| e.to_string()));
+        }
+        Ok(Self { market, books, quotes, trade })
+    }
+
+    /// The report printed by `arbishark inspect`
+    pub fn lines(&self) -> Vec<String> {
+        let m = &self.market;
+        let mut lines = vec![
+            format!("🔎 {}", m.question),
+            format!("   slug {} | market {} | condition {}", m.slug, m.id, if m.condition_id.is_empty() { "-" } else { &m.condition_id }),
+        ];
+        let scale = self.books.iter()
+            .flat_map(|b| b.bids.iter().take(LADDER_DEPTH).chain(b.asks.iter().take(LADDER_DEPTH)))
+            .map(|l| l.size)
+            .fold(0.0, f64::max);
+        for (i, book) in self.books.iter().enumerate() {
+            lines.push(String::new());
+            lines.extend(ladder(book, m.outcomes.get(i).map_or("?", String::as_str), LADDER_DEPTH, scale));
+        }
+
+        lines.push(String::new());
+        lines.push("   Buy bundle        size   cost/bundle     notional     fees    gas   net edge  net/share".to_string());
+        for q in &self.quotes {
+            let fillable = q.legs.iter().map(|l| l.fillable_size).fold(f64::INFINITY, f64::min);
+            let per_bundle: f64 = q.legs.iter().filter_map(|l| l.executable_price).sum();
+            let short = if fillable + 1e-9 < q.size { format!("  (fills {:.1})", fillable) } else { String::new() };
+            lines.push(format!("   {:>21.1} {:>13.4} {:>12.2} {:>8.2} {:>6.2} {:>+10.4} {:>+10.4}{}",
+                q.size, per_bundle, q.total_notional, q.total_fees, q.total_gas, q.net_edge,
+                if fillable > 0.0 { q.net_edge / fillable } else { 0.0 }, short));
+        }
+
+        lines.push(String::new());
+        lines.push(format!("   Thresholds at trade size {:.1}:", self.trade.size));
+        for c in &self.trade.checks {
+            lines.push(format!("   {} {:<17} {}", if c.passed { "✅" } else { "❌" }, c.check, c.detail));
+        }
+        lines.push(format!("   → {}", if self.trade.would_trade() { "would trade" } else { "would not trade" }));
+        lines
+    }
+}
+
+fn bar(size: f64, scale: f64) -> String {
+    let width = if scale > 0.0 { ((size / scale) * BAR_WIDTH as f64).ceil() as usize } else { 0 };
+    "█".repeat(width.min(BAR_WIDTH))
+}
+
+/// Bids and asks of `book` side by side, best first, with depth bars scaled to `scale`
+pub fn ladder(book: &OrderBook, outcome: &str, depth: usize, scale: f64) -> Vec<String> {
+    let token: String = book.token_id.chars().take(12).collect();
+    let mut lines = vec![
+        format!("   {} ({}…){}", outcome, token,
+            book.midpoint().map(|m| format!(" mid {:.3} spread {:.3}", m, book.best_ask().unwrap_or(m) - book.best_bid().unwrap_or(m))).unwrap_or_default()),
+        format!("   {:>w$} {:>9} {:>6} │ {:<6} {}", "", "bid size", "bid", "ask", "ask size", w = BAR_WIDTH),
+    ];
+    let level = |levels: &[PriceLevel], i: usize| levels.get(i).map(|l| (l.price, l.size));
+    let rows = book.bids.len().max(book.asks.len()).min(depth);
+    for i in 0..rows {
+        let bid = match level(&book.bids, i) {
+            Some((price, size)) => format!("{:>w$} {:>9.1} {:>6.3}", bar(size, scale), size, price, w = BAR_WIDTH),
+            None => format!("{:>w$}", "", w = BAR_WIDTH + 17),
+        };
+        let ask = match level(&book.asks, i) {
+            Some((price, size)) => format!("{:<6.3} {:<9.1} {}", price, size, bar(size, scale)),
+            None => String::new(),
+        };
+        lines.push(format!("   {} │ {}", bid, ask).trim_end().to_string());
+    }
+    if rows == 0 {
+        lines.push("   (empty book)".to_string());
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::book;
+
+    fn market() -> Market {
+        let event = serde_json::json!({"slug": "will-it-rain"});
+        parse_gamma_market(&serde_json::json!({
+            "id": "m1", "question": "Will it rain?", "outcomes": "[\"Yes\", \"No\"]",
+            "clobTokenIds": "[\"yes-token\", \"no-token\"]", "conditionId": "0xc"
+        }), &event).unwrap()
+    }
+
+    /// Asks sum to 0.90 for 20 shares, then the books get expensive
+    fn inspection() -> Inspection {
+        let books = vec![
+            book("yes-token", &[(0.40, 50.0)], &[(0.44, 20.0), (0.60, 100.0)]),
+            book("no-token", &[(0.42, 50.0)], &[(0.46, 20.0), (0.60, 100.0)]),
+        ];
+        let mut config = Config::default_config();
+        config.trading.trade_size = 10.0;
+        config.trading.min_spread_threshold = 0.05;
+        config.trading.min_profit_threshold = 0.10;
+        config.trading.gas_per_leg_usd = 0.0;
+        Inspection::new(market(), books, &config, 0).unwrap()
+    }
+
+    #[test]
+    fn test_gamma_market_parsed_with_its_event_slug() {
+        let market = market();
+        assert_eq!((market.slug.as_str(), market.outcomes.len()), ("will-it-rain", 2));
+    }
+
+    #[test]
+    fn test_quotes_walk_the_books() {
+        let inspection = inspection();
+        assert_eq!(inspection.quotes.iter().map(|q| q.size).collect::<Vec<_>>(), vec![1.0, 10.0, 50.0, 100.0, 500.0]);
+        assert!(inspection.quotes[1].net_edge > 0.0 && inspection.quotes[2].net_edge < 0.0, "edge gone past the top level");
+    }
+
+    #[test]
+    fn test_trade_checks_against_thresholds() {
+        let inspection = inspection();
+        let passed = |name: &str| inspection.trade.checks.iter().find(|c| c.check == name).unwrap().passed;
+        // Midpoints 0.42 + 0.44 = 0.86: a 0.14 spread
+        assert!(passed("spread") && passed("fill") && passed("edge") && passed("side"));
+        assert!(inspection.trade.would_trade() == inspection.trade.checks.iter().all(|c| c.passed));
+    }
+
+    #[test]
+    fn test_ladders_rendered() {
+        let lines = inspection().lines();
+        assert!(lines.iter().any(|l| l.contains("0.400 │ 0.440")), "bid and ask on one row");
+        assert!(lines.iter().any(|l| l.contains("✅ spread")));
+        assert_eq!(ladder(&book("t", &[], &[]), "Yes", LADDER_DEPTH, 1.0).last().unwrap(), "   (empty book)");
+    }
+}
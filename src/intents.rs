@@ -0,0 +1,382 @@
+// Signable trade intents for external execution
+// EIP-712 intents signed by the agent, co-signed and executed by another system
+
+use crate::config::IntentConfig;
+use crate::signer::{self, Signer, SignerError};
+use crate::storage::JsonlStore;
+use crate::types::Side;
+use crate::venue::{keccak, uint256, VenueError};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const INTENT_TYPE: &str = "TradeIntent(string intentId,string marketId,uint256 tokenId,uint8 side,uint256 priceCap,uint256 size,uint256 expiry,uint256 nonce)";
+const DOMAIN_TYPE: &str = "EIP712Domain(string name,string version,uint256 chainId)";
+const DOMAIN_NAME: &str = "ArbiShark Trade Intent";
+const DOMAIN_VERSION: &str = "1";
+/// Prices and sizes are encoded with USDC's 6 decimals
+const AMOUNT_DECIMALS: i32 = 6;
+/// Decided intents kept for `GET /api/intents`
+const MAX_INTENTS: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntentStatus {
+    Proposed,
+    CoSigned,
+    Executed,
+    Rejected,
+    Expired,
+}
+
+/// A proposed trade for an external system to co-sign and execute
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeIntent {
+    pub intent_id: String,
+    pub market_id: String,
+    pub token_id: String,
+    pub side: Side,
+    /// Worst price per share the agent accepts
+    pub price_cap: f64,
+    /// Shares
+    pub size: f64,
+    pub expires_at: u64,
+    pub created_at: u64,
+    pub nonce: u64,
+    pub chain_id: u64,
+    /// EIP-712 digest both parties sign (0x hex)
+    pub digest: String,
+    /// Agent address and signature (None without an EVM signer)
+    pub proposer: Option<String>,
+    pub signature: Option<String>,
+    pub co_signer: Option<String>,
+    pub co_signature: Option<String>,
+    pub status: IntentStatus,
+    /// Executor's order or transaction id
+    pub reference: Option<String>,
+    pub reason: Option<String>,
+}
+
+fn amount(value: f64) -> String {
+    ((value * 10f64.powi(AMOUNT_DECIMALS)).round() as u128).to_string()
+}
+
+impl TradeIntent {
+    fn domain_separator(&self) -> Result<[u8; 32], VenueError> {
+        let mut encoded = keccak(DOMAIN_TYPE.as_bytes()).to_vec();
+        encoded.extend(keccak(DOMAIN_NAME.as_bytes()));
+        encoded.extend(keccak(DOMAIN_VERSION.as_bytes()));
+        encoded.extend(uint256(&self.chain_id.to_string())?);
+        Ok(keccak(&encoded))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], VenueError> {
+        let side = match self.side {
+            Side::Buy => "0",
+            Side::Sell => "1",
+        };
+        let mut encoded = keccak(INTENT_TYPE.as_bytes()).to_vec();
+        encoded.extend(keccak(self.intent_id.as_bytes()));
+        encoded.extend(keccak(self.market_id.as_bytes()));
+        encoded.extend(uint256(&self.token_id)?);
+        encoded.extend(uint256(side)?);
+        encoded.extend(uint256(&amount(self.price_cap))?);
+        encoded.extend(uint256(&amount(self.size))?);
+        encoded.extend(uint256(&self.expires_at.to_string())?);
+        encoded.extend(uint256(&self.nonce.to_string())?);
+        Ok(keccak(&encoded))
+    }
+
+    /// EIP-712 digest of the intent
+    pub fn signing_digest(&self) -> Result<[u8; 32], VenueError> {
+        Ok(signer::eip712_digest(self.domain_separator()?, self.struct_hash()?))
+    }
+}
+
+/// Sign `intent` as the proposer
+pub async fn sign(intent: &mut TradeIntent, signer: &dyn Signer) -> Result<(), VenueError> {
+    let signature = signer.sign_typed_data(intent.domain_separator()?, intent.struct_hash()?).await.map_err(VenueError::Signer)?;
+    intent.proposer = signer.evm_address();
+    intent.signature = Some(format!("0x{}", hex::encode(signature)));
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IntentError {
+    Disabled,
+    Unauthorized,
+    Unknown(String),
+    /// No longer open for this update
+    Decided { intent_id: String, status: IntentStatus },
+    BadSignature(String),
+    /// Recovered co-signer is not allowed
+    NotCoSigner(String),
+    /// Not one of cosign, executed, reject
+    BadAction(String),
+}
+
+impl std::fmt::Display for IntentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Disabled => write!(f, "trade intents are disabled"),
+            Self::Unauthorized => write!(f, "missing or invalid bearer token"),
+            Self::Unknown(id) => write!(f, "unknown intent {}", id),
+            Self::Decided { intent_id, status } => write!(f, "intent {} is already {:?}", intent_id, status),
+            Self::BadSignature(e) => write!(f, "bad co-signature: {}", e),
+            Self::NotCoSigner(address) => write!(f, "{} is not an allowed co-signer", address),
+            Self::BadAction(action) => write!(f, "unknown action '{}' (cosign, executed, reject)", action),
+        }
+    }
+}
+
+/// Update posted by the executing side
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct IntentUpdate {
+    /// 65-byte `r || s || v` signature over the intent digest (0x hex)
+    #[serde(default)]
+    pub signature: Option<String>,
+    #[serde(default)]
+    pub reference: Option<String>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct IntentBook {
+    config: IntentConfig,
+    /// Bearer token the executing side presents
+    token: Option<String>,
+    intents: Vec<TradeIntent>,
+    next_nonce: u64,
+    journal: Option<JsonlStore>,
+}
+
+impl IntentBook {
+    pub fn new(config: IntentConfig, token: Option<String>) -> Self {
+        Self { config, token, intents: Vec::new(), next_nonce: 1, journal: None }
+    }
+
+    /// Token from the env var named in config
+    pub fn from_env(config: IntentConfig) -> Self {
+        let token = std::env::var(&config.token_env).ok();
+        Self::new(config, token)
+    }
+
+    /// Replay intents from `journal` (latest state of each wins) and append updates to it
+    pub fn with_journal(mut self, journal: JsonlStore) -> Self {
+        let records: Vec<TradeIntent> = journal.load().unwrap_or_else(|e| {
+            eprintln!("⚠️ [Intents] Failed to read intent journal: {}", e);
+            Vec::new()
+        });
+        let mut latest: HashMap<String, usize> = HashMap::new();
+        for record in records {
+            self.next_nonce = self.next_nonce.max(record.nonce + 1);
+            match latest.get(&record.intent_id) {
+                Some(&i) => self.intents[i] = record,
+                None => {
+                    latest.insert(record.intent_id.clone(), self.intents.len());
+                    self.intents.push(record);
+                }
+            }
+        }
+        self.journal = Some(journal);
+        self.prune();
+        self
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Check the `Authorization` header value
+    pub fn authenticate(&self, authorization: Option<&str>) -> Result<(), IntentError> {
+        let Some(token) = self.token.as_deref().filter(|_| self.config.enabled) else {
+            return Err(IntentError::Disabled);
+        };
+        let presented = authorization.and_then(|h| h.strip_prefix("Bearer ")).unwrap_or_default();
+        // Constant-time comparison so the token cannot be guessed byte by byte
+        let matches = presented.len() == token.len()
+            && presented.bytes().zip(token.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0;
+        if matches { Ok(()) } else { Err(IntentError::Unauthorized) }
+    }
+
+    /// Build an unsigned intent for one leg; capped at `price * (1 + price_slack)`
+    pub fn propose(&mut self, market_id: &str, token_id: &str, side: Side, price: f64, size: f64, now: u64) -> Result<TradeIntent, VenueError> {
+        let nonce = self.next_nonce;
+        let slack = match side {
+            Side::Buy => 1.0 + self.config.price_slack,
+            Side::Sell => 1.0 - self.config.price_slack,
+        };
+        let mut intent = TradeIntent {
+            intent_id: format!("int-{}-{}", now, nonce),
+            market_id: market_id.to_string(),
+            token_id: token_id.to_string(),
+            side,
+            price_cap: (price * slack).clamp(0.0, 1.0),
+            size,
+            expires_at: now + self.config.ttl_secs,
+            created_at: now,
+            nonce,
+            chain_id: self.config.chain_id,
+            digest: String::new(),
+            proposer: None,
+            signature: None,
+            co_signer: None,
+            co_signature: None,
+            status: IntentStatus::Proposed,
+            reference: None,
+            reason: None,
+        };
+        intent.digest = format!("0x{}", hex::encode(intent.signing_digest()?));
+        self.next_nonce += 1;
+        Ok(intent)
+    }
+
+    fn record(&mut self, intent: &TradeIntent) {
+        if let Some(journal) = &self.journal {
+            if let Err(e) = journal.append(intent) {
+                eprintln!("⚠️ [Intents] Failed to journal intent: {}", e);
+            }
+        }
+    }
+
+    /// Publish a proposed (and usually signed) intent
+    pub fn publish(&mut self, intent: TradeIntent) {
+        self.record(&intent);
+        self.intents.push(intent);
+        self.prune();
+    }
+
+    fn prune(&mut self) {
+        let excess = self.intents.len().saturating_sub(MAX_INTENTS);
+        let mut removed = 0;
+        self.intents.retain(|i| {
+            let open = matches!(i.status, IntentStatus::Proposed | IntentStatus::CoSigned);
+            let keep = open || removed >= excess;
+            if !keep {
+                removed += 1;
+            }
+            keep
+        });
+    }
+
+    fn update(&mut self, intent_id: &str, open: &[IntentStatus], now: u64,
+              apply: impl FnOnce(&mut TradeIntent) -> Result<(), IntentError>) -> Result<TradeIntent, IntentError> {
+        let intent = self.intents.iter_mut().find(|i| i.intent_id == intent_id)
+            .ok_or_else(|| IntentError::Unknown(intent_id.to_string()))?;
+        if !open.contains(&intent.status) || now >= intent.expires_at {
+            return Err(IntentError::Decided { intent_id: intent_id.to_string(), status: intent.status });
+        }
+        apply(intent)?;
+        let updated = intent.clone();
+        self.record(&updated);
+        Ok(updated)
+    }
+
+    /// Attach a co-signature over the intent digest
+    pub fn co_sign(&mut self, intent_id: &str, signature: &str, now: u64) -> Result<TradeIntent, IntentError> {
+        let bytes = hex::decode(signature.trim_start_matches("0x")).map_err(|e| IntentError::BadSignature(e.to_string()))?;
+        let co_signers = self.config.co_signers.clone();
+        self.update(intent_id, &[IntentStatus::Proposed], now, |intent| {
+            let digest = intent.signing_digest().map_err(|e| IntentError::BadSignature(e.to_string()))?;
+            let address = signer::recover_evm_address(digest, &bytes).map_err(|e: SignerError| IntentError::BadSignature(e.to_string()))?;
+            if !co_signers.is_empty() && !co_signers.iter().any(|c| c.eq_ignore_ascii_case(&address)) {
+                return Err(IntentError::NotCoSigner(address));
+            }
+            intent.co_signer = Some(address);
+            intent.co_signature = Some(format!("0x{}", hex::encode(&bytes)));
+            intent.status = IntentStatus::CoSigned;
+            Ok(())
+        })
+    }
+
+    /// The executing side submitted the trade
+    pub fn mark_executed(&mut self, intent_id: &str, reference: Option<String>, now: u64) -> Result<TradeIntent, IntentError> {
+        self.update(intent_id, &[IntentStatus::Proposed, IntentStatus::CoSigned], now, |intent| {
+            intent.status = IntentStatus::Executed;
+            intent.reference = reference;
+            Ok(())
+        })
+    }
+
+    /// The executing side declined the trade
+    pub fn reject(&mut self, intent_id: &str, reason: Option<String>, now: u64) -> Result<TradeIntent, IntentError> {
+        self.update(intent_id, &[IntentStatus::Proposed, IntentStatus::CoSigned], now, |intent| {
+            intent.status = IntentStatus::Rejected;
+            intent.reason = reason;
+            Ok(())
+        })
+    }
+
+    /// Expire open intents past their expiry; returns the newly expired
+    pub fn expire(&mut self, now: u64) -> Vec<TradeIntent> {
+        let mut expired = Vec::new();
+        for intent in &mut self.intents {
+            if matches!(intent.status, IntentStatus::Proposed | IntentStatus::CoSigned) && now >= intent.expires_at {
+                intent.status = IntentStatus::Expired;
+                expired.push(intent.clone());
+            }
+        }
+        for intent in &expired {
+            self.record(intent);
+        }
+        expired
+    }
+
+    /// Newest first
+    pub fn recent(&self) -> Vec<TradeIntent> {
+        self.intents.iter().rev().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signer::LocalKeySigner;
+
+    const AGENT_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const CO_SIGNER_KEY: &str = "59c6995e998f97a5a0044966f0ab6bb2a6a9f6e5c0f3b3a1e1b0a0c0d0e0f101";
+
+    #[tokio::test]
+    async fn test_propose_sign_and_co_sign() {
+        let co_signer = LocalKeySigner::new(Some(CO_SIGNER_KEY), None).unwrap();
+        let config = IntentConfig {
+            enabled: true,
+            co_signers: vec![co_signer.evm_address().unwrap().to_uppercase().replace("0X", "0x")],
+            ..IntentConfig::default()
+        };
+        let mut book = IntentBook::new(config, Some("secret".to_string()));
+        assert_eq!(book.authenticate(Some("Bearer secret")), Ok(()));
+        assert_eq!(book.authenticate(Some("Bearer nope")), Err(IntentError::Unauthorized));
+
+        let mut intent = book.propose("m1", "12345", Side::Buy, 0.45, 10.0, 1_000).unwrap();
+        assert!((intent.price_cap - 0.45 * 1.01).abs() < 1e-9);
+        assert_eq!(intent.expires_at, 1_000 + IntentConfig::default().ttl_secs);
+        let agent = LocalKeySigner::new(Some(AGENT_KEY), None).unwrap();
+        sign(&mut intent, &agent).await.unwrap();
+        let digest = intent.signing_digest().unwrap();
+        let proposer_signature = hex::decode(intent.signature.as_ref().unwrap().trim_start_matches("0x")).unwrap();
+        assert_eq!(signer::recover_evm_address(digest, &proposer_signature).unwrap(), agent.evm_address().unwrap());
+        let id = intent.intent_id.clone();
+        book.publish(intent);
+
+        // The agent is not an allowed co-signer
+        let by_agent = format!("0x{}", hex::encode(agent.sign_typed_data([0; 32], [0; 32]).await.unwrap()));
+        assert!(matches!(book.co_sign(&id, &by_agent, 1_001), Err(IntentError::NotCoSigner(_))));
+
+        let published = book.recent()[0].clone();
+        let co_signature = co_signer.sign_typed_data(published.domain_separator().unwrap(), published.struct_hash().unwrap()).await.unwrap();
+        let signed = book.co_sign(&id, &hex::encode(co_signature), 1_001).unwrap();
+        assert_eq!(signed.status, IntentStatus::CoSigned);
+        assert_eq!(signed.co_signer, co_signer.evm_address());
+
+        let executed = book.mark_executed(&id, Some("0xabc".to_string()), 1_002).unwrap();
+        assert_eq!(executed.status, IntentStatus::Executed);
+        assert!(matches!(book.reject(&id, None, 1_003), Err(IntentError::Decided { status: IntentStatus::Executed, .. })));
+
+        // Unacted intents expire
+        let stale = book.propose("m1", "12345", Side::Buy, 0.45, 10.0, 2_000).unwrap();
+        assert_eq!(stale.nonce, 2);
+        book.publish(stale);
+        assert_eq!(book.expire(2_000 + IntentConfig::default().ttl_secs).len(), 1);
+    }
+}
@@ -0,0 +1,210 @@
+//! Multi-instance coordination
+//!
+//! A lease per permission, kept as a small JSON file in the shared data
+//! directory: the holder renews it every tick, other instances stand by,
+//! and once the holder stops renewing (crash, kill) the lease expires and
+//! the next instance to try takes over. Read-modify-write of a lease is
+//! serialized by an exclusive `.lock` file next to it, so two instances can
+//! never both believe they hold the same permission. A tick can outlast the
+//! TTL, so the holder fences every order on the epoch it acquired.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A lease on one permission
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lease {
+    pub key: String,
+    pub holder: String,
+    pub expires_at: u64,
+    /// Bumped on every change of holder
+    pub epoch: u64,
+}
+
+/// Outcome of a lease attempt
+#[derive(Debug, Clone, PartialEq)]
+pub enum LeaseState {
+    /// This instance now holds the lease; `took_over` if it had expired under another holder
+    Acquired { epoch: u64, took_over: bool },
+    /// This instance already held it and extended it
+    Renewed { epoch: u64 },
+    /// Another live instance holds it
+    HeldBy { holder: String, expires_at: u64 },
+}
+
+/// Lease client for one instance
+#[derive(Debug, Clone)]
+pub struct LeaseManager {
+    dir: PathBuf,
+    holder: String,
+    ttl_secs: u64,
+}
+
+impl LeaseManager {
+    /// Leases live under `<data_dir>/leases`
+    pub fn new(data_dir: &str, holder: &str, ttl_secs: u64) -> io::Result<Self> {
+        let dir = Path::new(data_dir).join("leases");
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir, holder: holder.to_string(), ttl_secs })
+    }
+
+    pub fn holder(&self) -> &str {
+        &self.holder
+    }
+
+    /// Acquire, renew or observe the lease on `key` at `now`
+    pub fn try_acquire(&self, key: &str, now: u64) -> io::Result<LeaseState> {
+        let path = self.lease_path(key);
+        let _guard = self.lock(&path, now)?;
+        let current: Option<Lease> = match fs::read_to_string(&path) {
+            Ok(s) => serde_json::from_str(&s).ok(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+        let expires_at = now + self.ttl_secs;
+        let (lease, state) = match current {
+            Some(l) if l.holder == self.holder => {
+                let epoch = l.epoch;
+                (Lease { expires_at, ..l }, LeaseState::Renewed { epoch })
+            }
+            Some(l) if l.expires_at > now => {
+                return Ok(LeaseState::HeldBy { holder: l.holder, expires_at: l.expires_at });
+            }
+            Some(l) => {
+                let epoch = l.epoch + 1;
+                (Lease { key: key.to_string(), holder: self.holder.clone(), expires_at, epoch },
+                 LeaseState::Acquired { epoch, took_over: true })
+            }
+            None => (Lease { key: key.to_string(), holder: self.holder.clone(), expires_at, epoch: 1 },
+                     LeaseState::Acquired { epoch: 1, took_over: false }),
+        };
+        write_atomic(&path, &lease)?;
+        Ok(state)
+    }
+
+    /// Renew the lease on `key` if this instance still holds it at `epoch`; false if
+    /// another instance has taken it since (the caller must not spend)
+    pub fn fence(&self, key: &str, epoch: u64, now: u64) -> io::Result<bool> {
+        let path = self.lease_path(key);
+        let _guard = self.lock(&path, now)?;
+        let lease = fs::read_to_string(&path).ok().and_then(|s| serde_json::from_str::<Lease>(&s).ok());
+        match lease {
+            // Expired but untaken is still ours: nobody else can have spent under it
+            Some(l) if l.holder == self.holder && l.epoch == epoch => {
+                write_atomic(&path, &Lease { expires_at: now + self.ttl_secs, ..l })?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Give the lease up (clean shutdown) so a standby can take over at once
+    pub fn release(&self, key: &str, now: u64) -> io::Result<()> {
+        let path = self.lease_path(key);
+        let _guard = self.lock(&path, now)?;
+        let lease = fs::read_to_string(&path).ok().and_then(|s| serde_json::from_str::<Lease>(&s).ok());
+        match lease {
+            // Expire rather than delete, so the epoch keeps counting
+            Some(l) if l.holder == self.holder => write_atomic(&path, &Lease { expires_at: now, ..l }),
+            _ => Ok(()),
+        }
+    }
+
+    fn lease_path(&self, key: &str) -> PathBuf {
+        let safe: String = key.chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        self.dir.join(format!("{}.lease", safe))
+    }
+
+    /// Exclusive lock file; one left behind by a crashed instance is broken after a TTL
+    fn lock(&self, lease_path: &Path, now: u64) -> io::Result<LockGuard> {
+        let path = lease_path.with_extension("lease.lock");
+        for _ in 0..2 {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Ok(LockGuard(path)),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    let age = fs::metadata(&path)?.modified()?
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map_or(0, |d| now.saturating_sub(d.as_secs()));
+                    if age < self.ttl_secs {
+                        return Err(io::Error::new(io::ErrorKind::WouldBlock, "lease busy"));
+                    }
+                    let _ = fs::remove_file(&path);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(io::Error::new(io::ErrorKind::WouldBlock, "lease busy"))
+    }
+}
+
+/// Removes the lock file when dropped
+struct LockGuard(PathBuf);
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+fn write_atomic(path: &Path, lease: &Lease) -> io::Result<()> {
+    let tmp = path.with_extension("lease.tmp");
+    let json = serde_json::to_string(lease).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    fs::write(&tmp, json)?;
+    fs::rename(&tmp, path)
+}
+
+/// Default instance name: host plus process id
+pub fn default_instance_id() -> String {
+    let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "local".to_string());
+    format!("{}-{}", host, std::process::id())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_leader_and_takeover() {
+        let dir = std::env::temp_dir().join(format!("arbishark_lease_{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+        let a = LeaseManager::new(dir, "agent-a", 30).unwrap();
+        let b = LeaseManager::new(dir, "agent-b", 30).unwrap();
+
+        assert_eq!(a.try_acquire("perm:1", 1000).unwrap(), LeaseState::Acquired { epoch: 1, took_over: false });
+        assert_eq!(b.try_acquire("perm:1", 1010).unwrap(), LeaseState::HeldBy { holder: "agent-a".to_string(), expires_at: 1030 });
+        assert_eq!(a.try_acquire("perm:1", 1020).unwrap(), LeaseState::Renewed { epoch: 1 });
+
+        // a dies: its lease runs out and b takes over
+        assert_eq!(b.try_acquire("perm:1", 1051).unwrap(), LeaseState::Acquired { epoch: 2, took_over: true });
+        assert!(matches!(a.try_acquire("perm:1", 1052).unwrap(), LeaseState::HeldBy { .. }));
+
+        // Clean release hands over immediately
+        b.release("perm:1", 1053).unwrap();
+        assert_eq!(a.try_acquire("perm:1", 1054).unwrap(), LeaseState::Acquired { epoch: 3, took_over: true });
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_fence_stops_a_slow_leader_after_takeover() {
+        let dir = std::env::temp_dir().join(format!("arbishark_lease_fence_{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+        let a = LeaseManager::new(dir, "agent-a", 30).unwrap();
+        let b = LeaseManager::new(dir, "agent-b", 30).unwrap();
+
+        assert_eq!(a.try_acquire("perm:1", 1000).unwrap(), LeaseState::Acquired { epoch: 1, took_over: false });
+        // A long tick: fencing renews, so the standby cannot take over mid-bundle
+        assert!(a.fence("perm:1", 1, 1025).unwrap());
+        assert!(matches!(b.try_acquire("perm:1", 1040).unwrap(), LeaseState::HeldBy { expires_at: 1055, .. }));
+
+        // a stalls past its TTL and b takes over: a's next order is fenced off
+        assert_eq!(b.try_acquire("perm:1", 1060).unwrap(), LeaseState::Acquired { epoch: 2, took_over: true });
+        assert!(!a.fence("perm:1", 1, 1070).unwrap());
+        assert!(!b.fence("perm:1", 1, 1070).unwrap(), "a stale epoch never passes");
+        assert!(b.fence("perm:1", 2, 1070).unwrap());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,160 @@
+//! Worst-case net risk of a bundle across partial fills
+//!
+//! A bundle's legs execute one after another, so some can fill while the
+//! rest miss. Before executing, every partial outcome is priced on the
+//! current books: the legs that filled are either sold back into their bids
+//! or the missing ones bought after their asks gap up by `gap`, whichever
+//! loses less. The worst of those outcomes must stay within the per-trade
+//! loss cap; if it does not, the bundle is downsized to the largest size
+//! per leg that does, or skipped when that is below the minimum size.
+
+use crate::config::LegRiskConfig;
+use crate::types::{OrderBook, Side};
+use serde::Serialize;
+
+/// Bisection steps when downsizing
+const DOWNSIZE_STEPS: usize = 30;
+
+/// Cheapest way out of a partial fill
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Remedy {
+    /// Sell the filled legs back into their bids
+    Unwind,
+    /// Buy the missing legs at the gapped-up asks and hold the full set
+    Complete,
+}
+
+/// The worst partial fill of a bundle at one size
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NetRisk {
+    /// Size per leg
+    pub size: f64,
+    /// Legs (by index) filled in the worst outcome
+    pub filled: Vec<usize>,
+    pub remedy: Remedy,
+    /// Loss of the worst outcome after its remedy ($, negative = a gain)
+    pub worst_loss: f64,
+}
+
+/// Outcome of the check
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    /// The worst case is within the cap at the requested size
+    Within(NetRisk),
+    /// Only a smaller size keeps the worst case within the cap
+    Downsize { from: f64, risk: NetRisk },
+    /// Not even the minimum size keeps the worst case within the cap
+    Skip(NetRisk),
+}
+
+#[derive(Debug, Clone)]
+pub struct LegRiskCheck {
+    config: LegRiskConfig,
+}
+
+impl LegRiskCheck {
+    pub fn new(config: LegRiskConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Worst partial fill of buying `size` of every leg on `books`.
+    /// None if some leg's ask cannot fill the size (the bundle cannot execute at all).
+    pub fn assess(&self, books: &[OrderBook], size: f64, fee_rate: f64) -> Option<NetRisk> {
+        let asks = books.iter().map(|b| b.execution_price(size, Side::Buy)).collect::<Option<Vec<f64>>>()?;
+        // A bid that cannot absorb the size is worth nothing; a gapped ask is at most par
+        let bids: Vec<f64> = books.iter().map(|b| b.execution_price(size, Side::Sell).unwrap_or(0.0)).collect();
+        let gapped: Vec<f64> = asks.iter().map(|a| (a + self.config.gap).min(1.0)).collect();
+
+        let legs = books.len();
+        let mut worst: Option<NetRisk> = None;
+        // Every non-empty strict subset of legs, as a bitmask of the filled ones
+        for mask in 1..(1u64 << legs).saturating_sub(1) {
+            let filled: Vec<usize> = (0..legs).filter(|i| mask & (1 << i) != 0).collect();
+            let paid: f64 = filled.iter().map(|&i| asks[i] * size * (1.0 + fee_rate)).sum();
+            let unwind = paid - filled.iter().map(|&i| bids[i] * size * (1.0 - fee_rate)).sum::<f64>();
+            let complete = paid - size
+                + (0..legs).filter(|i| mask & (1 << i) == 0).map(|i| gapped[i] * size * (1.0 + fee_rate)).sum::<f64>();
+            let (remedy, loss) = if complete < unwind { (Remedy::Complete, complete) } else { (Remedy::Unwind, unwind) };
+            if worst.as_ref().is_none_or(|w| loss > w.worst_loss) {
+                worst = Some(NetRisk { size, filled, remedy, worst_loss: loss });
+            }
+        }
+        worst
+    }
+
+    /// Check `size` per leg against the loss cap, downsizing if needed.
+    /// None if the books cannot fill the bundle.
+    pub fn check(&self, books: &[OrderBook], size: f64, fee_rate: f64) -> Option<Verdict> {
+        let risk = self.assess(books, size, fee_rate)?;
+        if risk.worst_loss <= self.config.max_loss_usd {
+            return Some(Verdict::Within(risk));
+        }
+        let floor = match self.assess(books, self.config.min_size, fee_rate) {
+            Some(floor) if self.config.min_size <= size && floor.worst_loss <= self.config.max_loss_usd => floor,
+            _ => return Some(Verdict::Skip(risk)),
+        };
+        // Largest size within the cap: the worst loss grows with size
+        let (mut lo, mut hi) = (floor, size);
+        for _ in 0..DOWNSIZE_STEPS {
+            let mid = (lo.size + hi) / 2.0;
+            match self.assess(books, mid, fee_rate) {
+                Some(r) if r.worst_loss <= self.config.max_loss_usd => lo = r,
+                _ => hi = mid,
+            }
+        }
+        Some(Verdict::Downsize { from: size, risk: lo })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::book;
+
+    fn config() -> LegRiskConfig {
+        LegRiskConfig { enabled: true, max_loss_usd: 0.2, gap: 0.10, min_size: 1.0 }
+    }
+
+    /// YES 0.42/0.45, NO 0.20/0.50: the bundle costs 0.95
+    fn books() -> [OrderBook; 2] {
+        [book("y", &[(0.42, 1000.0)], &[(0.45, 1000.0)]), book("n", &[(0.20, 1000.0)], &[(0.50, 1000.0)])]
+    }
+
+    #[test]
+    fn test_worst_partial_fill_found() {
+        // Only YES fills: unwinding loses 0.03/share. Only NO fills: unwinding loses 0.30/share,
+        // completing at the gapped 0.55 loses 0.05 - the worst case
+        let risk = LegRiskCheck::new(config()).assess(&books(), 10.0, 0.0).unwrap();
+        assert_eq!(risk.filled, vec![1]);
+        assert_eq!(risk.remedy, Remedy::Complete);
+        assert!((risk.worst_loss - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_worst_partial_fill_caps_the_size() {
+        match LegRiskCheck::new(config()).check(&books(), 10.0, 0.0).unwrap() {
+            Verdict::Downsize { from, risk } => {
+                assert_eq!(from, 10.0);
+                assert!((risk.size - 4.0).abs() < 1e-6, "0.05/share within $0.20");
+                assert!(risk.worst_loss <= config().max_loss_usd);
+            }
+            other => panic!("expected a downsize, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_small_bundles_within_the_limit() {
+        assert!(matches!(LegRiskCheck::new(config()).check(&books(), 3.0, 0.0), Some(Verdict::Within(_))));
+    }
+
+    #[test]
+    fn test_skipped_below_the_minimum_size() {
+        let strict = LegRiskCheck::new(LegRiskConfig { min_size: 5.0, ..config() });
+        assert!(matches!(strict.check(&books(), 10.0, 0.0), Some(Verdict::Skip(_))));
+    }
+}
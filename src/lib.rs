@@ -0,0 +1,120 @@
+//! ArbiShark library
+//!
+//! The agent's components (market clients, detector, execution, risk,
+//! permissions, storage, API) as a library, so custom agents can be
+//! assembled with [`engine::EngineBuilder`] without forking the binary.
+
+pub mod market_client;
+pub mod envio;
+pub mod freshness;
+pub mod quorum;
+pub mod permission_guard;
+pub mod types;
+pub mod wallet;
+pub mod fees;
+pub mod fee_calibrator;
+pub mod slippage;
+pub mod fills;
+pub mod constraint;
+pub mod arb;
+pub mod execution;
+pub mod engine;
+pub mod simulation;
+pub mod competition;
+pub mod market;
+pub mod latency;
+pub mod solana;
+pub mod metamask;
+pub mod config;
+pub mod websocket;
+pub mod book_delta;
+pub mod positions;
+pub mod api;
+pub mod events;
+pub mod health;
+pub mod storage;
+pub mod graphql;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod tca;
+pub mod signal_queue;
+pub mod utilization;
+pub mod attribution;
+pub mod trade_flow;
+pub mod plugins;
+pub mod scheduler;
+pub mod mapping;
+pub mod mirror;
+pub mod signer;
+pub mod audit;
+pub mod orders;
+pub mod recorder;
+pub mod regime;
+pub mod risk;
+pub mod sizing;
+pub mod preflight;
+pub mod budgets;
+pub mod windows;
+pub mod throttle;
+pub mod lease;
+pub mod sweep;
+pub mod tax;
+pub mod anomaly;
+pub mod spreads;
+pub mod supervisor;
+pub mod approvals;
+pub mod ratelimit;
+pub mod equity;
+pub mod venue;
+pub mod watchlist;
+pub mod compliance;
+pub mod external;
+pub mod treasury;
+pub mod embeddings;
+pub mod preview;
+pub mod holdings;
+pub mod assets;
+pub mod routing;
+pub mod model_ledger;
+pub mod parquet;
+pub mod decisions;
+pub mod reconcile;
+pub mod resolution;
+pub mod volatility;
+pub mod fleet;
+pub mod universe;
+pub mod maintenance;
+pub mod capture;
+pub mod perf;
+pub mod metrics;
+pub mod retention;
+pub mod object_store;
+pub mod inspect;
+pub mod conflicts;
+pub mod pacing;
+pub mod fee_tiers;
+pub mod maker_taker;
+pub mod registry;
+pub mod intents;
+pub mod venue_latency;
+pub mod doctor;
+pub mod replay;
+pub mod portfolio;
+pub mod exit_optimizer;
+pub mod quoting;
+pub mod synthetic;
+pub mod working_capital;
+pub mod remediation;
+pub mod leg_risk;
+pub mod backtest;
+#[cfg(feature = "tui")]
+pub mod backtest_tui;
+pub mod finality;
+pub mod slippage_guard;
+pub mod annotations;
+pub mod order_limits;
+pub mod rpc_pool;
+pub mod greeks;
+
+#[cfg(test)]
+mod testing;
@@ -1,49 +1,289 @@
-use crate::api::push_log;
-mod market_client;
-mod permission_guard;
-use crate::market_client::{MarketClient, ArbitrumMarketClient};
-use crate::market_client::PolymarketClient;
-use crate::permission_guard::PermissionGuard;
-mod types;
-mod wallet;
-mod fees;
-mod fee_calibrator;
-mod slippage;
-mod fills;
-mod constraint;
-mod arb;
-mod execution;
-mod engine;
-mod simulation;
-mod market;
-mod latency;
-mod solana;
-mod metamask;
-mod config;
-mod websocket;
-mod positions;
-mod api;
-
-use crate::wallet::Wallet;
+use arbishark::{annotations, anomaly, api, assets, approvals, attribution, audit, backtest, capture, compliance, conflicts, perf, decisions, doctor, embeddings, equity, external, events, exit_optimizer, fee_tiers, finality, fleet, freshness, health, holdings, inspect, intents, lease, leg_risk, maintenance, maker_taker, mapping, metrics, mirror, model_ledger, object_store, order_limits, pacing, portfolio, preflight, preview, quorum, quoting, ratelimit, reconcile, recorder, regime, remediation, registry, replay, resolution, retention, routing, rpc_pool, signer, slippage_guard, spreads, storage, supervisor, sweep, synthetic, tax, throttle, treasury, universe, utilization, venue, venue_latency, volatility, watchlist, windows, working_capital};
+#[cfg(feature = "grpc")]
+use arbishark::grpc;
+#[cfg(feature = "tui")]
+use arbishark::backtest_tui;
+use arbishark::api::{log_event, push_log};
+use arbishark::events::EventLevel;
+use arbishark::market_client::{self, MarketClient, ArbitrumMarketClient};
+use arbishark::market_client::PolymarketClient;
+use arbishark::permission_guard::PermissionGuard;
+use arbishark::wallet::Wallet;
 // ...existing code...
-use crate::arb::ArbitrageDetector;
-use crate::execution::ExecutionEngine;
-use crate::fees::FeeModel;
-use crate::solana::SolanaManager;
-use crate::latency::LatencyModel;
-use crate::types::Side;
-use crate::config::Config;
-use crate::metamask::MetaMaskClient;
-use crate::positions::{Position, PositionManager};
-use std::time::Duration;
+use arbishark::arb::ArbitrageDetector;
+use arbishark::execution::{ExecutionEngine, RemainderDecision, RemainderPolicy};
+use arbishark::fills::PassiveOrder;
+use arbishark::slippage::SlippageModel;
+use arbishark::solana::SolanaManager;
+use arbishark::latency::LatencyModel;
+use arbishark::types::Side;
+use arbishark::config::Config;
+use arbishark::metamask::{MetaMaskClient, PERMISSION_PERIOD_SECS};
+use arbishark::plugins::PluginManager;
+use arbishark::scheduler::PollScheduler;
+use arbishark::positions::{Position, PositionManager};
+use arbishark::tca::{FillRecord, TcaAnalyzer};
+use arbishark::signal_queue::SignalQueue;
+use arbishark::attribution::{Attribution, TradeTag};
+use arbishark::trade_flow::TradeFlow;
+use arbishark::orders::OrderRegistry;
+use arbishark::regime::RegimeClassifier;
+use arbishark::risk::RiskManager;
+use arbishark::sizing::PositionSizer;
+use arbishark::budgets::{StrategyBook, ARB_STRATEGY};
+use std::time::{Duration, Instant};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use colored::*;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // `arbishark convert <src> <dst>`: convert an order book recording (format from dst extension)
+    let mut args: Vec<String> = std::env::args().collect();
+    let profile = arbishark::config::take_profile_arg(&mut args);
+    if args.get(1).map(String::as_str) == Some("convert") {
+        let (Some(src), Some(dst)) = (args.get(2), args.get(3)) else {
+            eprintln!("usage: arbishark convert <src> <dst.{{jsonl,abk}}>");
+            std::process::exit(2);
+        };
+        let count = recorder::convert(src, dst)?;
+        println!("🎞️ Converted {} snapshots: {} → {}", count, src, dst);
+        return Ok(());
+    }
+    // `arbishark tax-report <year> [out.csv]`: yearly gains/losses from the tax lot journal
+    if args.get(1).map(String::as_str) == Some("tax-report") {
+        let Some(year) = args.get(2).and_then(|y| y.parse::<i32>().ok()) else {
+            eprintln!("usage: arbishark tax-report <year> [out.csv]");
+            std::process::exit(2);
+        };
+        let config = Config::load_profile(profile.as_deref()).unwrap_or_else(|_| Config::default_config());
+        let ledger = tax::TaxLedger::open(storage::JsonlStore::open(&config.storage.data_dir, "tax_lots.jsonl")?)?;
+        let gains = ledger.yearly(year, tax::LotMethod::parse(&config.tax.lot_method));
+        let csv = tax::to_csv(&gains);
+        match args.get(3) {
+            Some(path) => {
+                std::fs::write(path, csv)?;
+                println!("🧾 {} lots realized in {} ({}) → {}", gains.len(), year, config.tax.lot_method, path);
+                if let Some(mut sink) = object_store::ObjectSink::open(&config.object_store, &config.storage.data_dir).filter(|_| config.object_store.exports) {
+                    match sink.upload(std::path::Path::new(path), object_store::ObjectKind::Export, Wallet::current_timestamp()).await {
+                        Ok(key) => println!("☁️ Uploaded → {}", key.unwrap_or_else(|| "(unchanged)".to_string())),
+                        Err(e) => eprintln!("⚠️ [ObjectStore] Upload failed: {}", e),
+                    }
+                }
+            }
+            None => print!("{}", csv),
+        }
+        return Ok(());
+    }
+
+    // `arbishark export-decisions <out.parquet>`: per-scan feature/label rows for training filters
+    if args.get(1).map(String::as_str) == Some("export-decisions") {
+        let Some(out) = args.get(2) else {
+            eprintln!("usage: arbishark export-decisions <out.parquet>");
+            std::process::exit(2);
+        };
+        let config = Config::load_profile(profile.as_deref()).unwrap_or_else(|_| Config::default_config());
+        let journal = storage::JsonlStore::open(&config.storage.data_dir, "decisions.jsonl")?;
+        let rows = decisions::export_parquet(&journal, out)?;
+        println!("🧠 Exported {} decision records → {}", rows, out);
+        if let Some(mut sink) = object_store::ObjectSink::open(&config.object_store, &config.storage.data_dir).filter(|_| config.object_store.exports) {
+            match sink.upload(std::path::Path::new(out), object_store::ObjectKind::Export, Wallet::current_timestamp()).await {
+                Ok(key) => println!("☁️ Uploaded → {}", key.unwrap_or_else(|| "(unchanged)".to_string())),
+                Err(e) => eprintln!("⚠️ [ObjectStore] Upload failed: {}", e),
+            }
+        }
+        return Ok(());
+    }
+
+    // `arbishark restore <from> <to>`: rebuild an archived period (YYYY-MM-DD or unix seconds) for backtests
+    if args.get(1).map(String::as_str) == Some("restore") {
+        let (Some(from), Some(to)) = (args.get(2).and_then(|d| retention::parse_date(d)), args.get(3).and_then(|d| retention::parse_date(d))) else {
+            eprintln!("usage: arbishark restore <from> <to>   (YYYY-MM-DD or unix seconds)");
+            std::process::exit(2);
+        };
+        let config = Config::load_profile(profile.as_deref()).unwrap_or_else(|_| Config::default_config());
+        let archive = retention::Retention::open(&config.storage.data_dir, config.retention.clone())?
+            .with_store(object_store::ObjectStore::from_config(&config.object_store));
+        let restored = archive.restore(from, to).await?;
+        if restored.is_empty() {
+            println!("🗄️ No segments between {} and {}", from, to);
+        }
+        for (path, segments) in restored {
+            println!("🗄️ Restored {} segments → {}", segments, path.display());
+        }
+        return Ok(());
+    }
+
+    // `arbishark pull <prefix> [dir]`: fetch recordings/reports/exports from the object store (e.g. `pull recordings/`)
+    if args.get(1).map(String::as_str) == Some("pull") {
+        let Some(prefix) = args.get(2) else {
+            eprintln!("usage: arbishark pull <prefix> [dir]   (recordings/, reports/, exports/, archive/)");
+            std::process::exit(2);
+        };
+        let config = Config::load_profile(profile.as_deref()).unwrap_or_else(|_| Config::default_config());
+        let Some(store) = object_store::ObjectStore::from_config(&config.object_store) else {
+            eprintln!("[object_store] is not enabled or its endpoint, bucket or credentials are missing");
+            std::process::exit(2);
+        };
+        let dir = args.get(3).cloned().unwrap_or_else(|| format!("{}/pulled", config.storage.data_dir));
+        let pulled = store.pull(prefix, std::path::Path::new(&dir)).await?;
+        println!("☁️ Pulled {} objects → {}", pulled.len(), dir);
+        return Ok(());
+    }
+
+    // `arbishark inspect <market-slug>`: depth ladders, bundle cost by size and threshold checks for one market
+    if args.get(1).map(String::as_str) == Some("inspect") {
+        let Some(slug) = args.get(2) else {
+            eprintln!("usage: arbishark inspect <market-slug>");
+            std::process::exit(2);
+        };
+        let config = Config::load_profile(profile.as_deref()).unwrap_or_else(|_| Config::default_config());
+        let client = reqwest::Client::new();
+        let market = inspect::fetch_market(&client, &config.api.gamma_url, slug).await?;
+        let books_client = PolymarketClient {
+            gamma_url: String::new(),
+            clob_url: format!("{}/book", config.api.clob_url.trim_end_matches('/')),
+            trades_url: String::new(),
+            client,
+        };
+        let mut books = Vec::new();
+        for token_id in &market.clob_token_ids {
+            books.push(books_client.get_order_book(token_id).await.map_err(|e| format!("order book {}: {}", token_id, e))?);
+        }
+        for line in inspect::Inspection::new(market, books, &config, Wallet::current_timestamp())?.lines() {
+            println!("{}", line);
+        }
+        return Ok(());
+    }
+
+    // `arbishark import <market_id> <token_id>[=cost_basis]...`: import outcome tokens already held
+    if args.get(1).map(String::as_str) == Some("import") {
+        let (Some(market_id), Some(tokens)) = (args.get(2), args.get(3..).filter(|t| !t.is_empty())) else {
+            eprintln!("usage: arbishark import <market_id> <token_id>[=cost_basis]...");
+            std::process::exit(2);
+        };
+        let config = Config::load_profile(profile.as_deref()).unwrap_or_else(|_| Config::default_config());
+        let registry = registry::Registry::from_config(&config.registry)?;
+        let ctf_contract = registry.resolve(config.holdings.chain_id, registry::Contract::Ctf)?;
+        let journal = storage::JsonlStore::open(&config.storage.data_dir, "holdings.jsonl")?;
+        let mut ledger = holdings::Holdings::new(config.holdings.clone(), ctf_contract)
+            .with_rpc_pool(rpc_pool::RpcPool::from_config(&config))
+            .with_journal(journal);
+        let reader = ledger.reader();
+        let books = PolymarketClient {
+            gamma_url: String::new(),
+            clob_url: "https://clob.polymarket.com/book".to_string(),
+            trades_url: String::new(),
+            client: market_client::http_client(),
+        };
+        let mut legs = Vec::new();
+        let mut cost_basis = std::collections::HashMap::new();
+        for token in tokens {
+            let (token_id, basis) = match token.split_once('=') {
+                Some((token_id, basis)) => (token_id, Some(basis.parse::<f64>()?)),
+                None => (token.as_str(), None),
+            };
+            if let Some(basis) = basis {
+                cost_basis.insert(token_id.to_string(), basis);
+            }
+            // Without a cost basis the book midpoint is used
+            let oracle_price = match basis {
+                Some(_) => None,
+                None => books.get_order_book(token_id).await.ok().and_then(|b| b.midpoint()),
+            };
+            legs.push(holdings::ImportLeg {
+                token_id: token_id.to_string(),
+                outcome: String::new(),
+                balance: reader.balance(token_id).await?,
+                tracked: 0.0,
+                oracle_price,
+            });
+        }
+        let now = Wallet::current_timestamp();
+        let imported = holdings::plan_import(market_id, &legs, &cost_basis, 0.0, now)?;
+        ledger.import(imported.clone())?;
+        if config.tax.enabled {
+            let mut tax_ledger = tax::TaxLedger::open(storage::JsonlStore::open(&config.storage.data_dir, "tax_lots.jsonl")?)?;
+            for holding in &imported {
+                tax_ledger.acquire(&holding.market_id, &holding.token_id, holding.size, holding.size * holding.cost_basis, now);
+            }
+        }
+        for holding in &imported {
+            println!("📥 Imported {:.2} of {} @ ${:.4} ({:?} basis)", holding.size, holding.token_id, holding.cost_basis, holding.basis_source);
+        }
+        println!("📥 {} holdings imported on {} - opened as positions on the next start", imported.len(), market_id);
+        return Ok(());
+    }
+
+    // `arbishark doctor`: pass/fail self-test of config, connectivity, permission, balances and a dry scan
+    if args.get(1).map(String::as_str) == Some("doctor") {
+        let config = Config::load_profile(profile.as_deref()).unwrap_or_else(|e| {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        });
+        let report = doctor::Doctor::new(&config).run().await;
+        for line in report.lines() {
+            println!("{}", line);
+        }
+        if !report.passed() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // `arbishark replay-signal <market_id>:<unix secs> [window_secs]`: trace the engine's handling of a past signal
+    if args.get(1).map(String::as_str) == Some("replay-signal") {
+        let Some(signal_id) = args.get(2) else {
+            eprintln!("usage: arbishark replay-signal <market_id>:<unix secs> [window_secs]");
+            std::process::exit(2);
+        };
+        let window_secs = args.get(3).and_then(|w| w.parse().ok()).unwrap_or(300);
+        let config = Config::load_profile(profile.as_deref()).unwrap_or_else(|_| Config::default_config());
+        let mut ctx = replay::ReplayContext::load(&config, signal_id, window_secs)?;
+        if ctx.market.is_none() {
+            match inspect::fetch_market_by_id(&reqwest::Client::new(), &config.api.gamma_url, &ctx.market_id).await {
+                Ok(market) => ctx.attach_market(&config, market),
+                Err(e) => eprintln!("⚠️ [Replay] Market lookup failed: {}", e),
+            }
+        }
+        for line in replay::trace(&ctx, &config) {
+            println!("{}", line);
+        }
+        return Ok(());
+    }
+
+    // `arbishark backtest [--tui]`: replay the book recording over the saved universe
+    if args.get(1).map(String::as_str) == Some("backtest") {
+        let config = Config::load_profile(profile.as_deref()).unwrap_or_else(|_| Config::default_config());
+        let data_dir = std::path::Path::new(&config.storage.data_dir);
+        let recording = data_dir.join(recorder::RecordFormat::parse(&config.storage.record_format).file_name());
+        let replayer = recorder::BookReplayer::from_recording(&recording.to_string_lossy())
+            .map_err(|e| format!("book recording {}: {} - was storage.record_books on?", recording.display(), e))?;
+        let universe = std::fs::read_to_string(data_dir.join("universe.json"))
+            .map_err(|e| format!("{}: {} - run the engine with [universe_cache] enabled first", data_dir.join("universe.json").display(), e))?;
+        let snapshot: universe::UniverseSnapshot = serde_json::from_str(&universe)?;
+        let report = backtest::run(&snapshot.markets, &replayer, &config);
+        if args.iter().any(|a| a == "--tui") {
+            #[cfg(feature = "tui")]
+            return Ok(backtest_tui::browse(&report)?);
+            #[cfg(not(feature = "tui"))]
+            eprintln!("⚠️ --tui needs a build with `--features tui`; printing the summary instead");
+        }
+        for line in report.summary_lines() {
+            println!("{}", line);
+        }
+        return Ok(());
+    }
+
     // Load configuration
-    let config = Config::load().unwrap_or_else(|e| {
+    let config = Config::load_profile(profile.as_deref()).unwrap_or_else(|e| {
+        // A requested profile must not silently fall back to defaults
+        if let arbishark::config::ConfigError::Profile(_) = e {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
         println!("⚠️ Config load failed ({}), using defaults", e);
         Config::default_config()
     });
@@ -52,52 +292,490 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!(" {} {}", "🦈".cyan(), "ArbiShark v1.0 (Hackathon Release)".bold().cyan());
     println!("   - {}", "Arbitrum-First Permissioned Agent".white());
     println!("   - Powered by {}", "MetaMask Delegation Toolkit (ERC-7715)".yellow());
-    println!("   - Arbitrum + Polymarket CLOB Pattern".purple());
-    println!("   - Hybrid DApp: {}", "Enabled (API Port 3030)".purple());
+    println!("   - {}", "Arbitrum + Polymarket CLOB Pattern".purple());
+    println!("   - Hybrid DApp: {}", format!("Enabled (API Port {})", config.dashboard.port).purple());
     println!("{}", "=======================================================\n".bright_blue());
 
+    // Events are emitted through `tracing`; the layer queues them for the API's event log
+    let (event_buffer, event_queue) = events::channel(events::QUEUE_CAPACITY);
+    if let Err(e) = tracing_subscriber::registry().with(event_buffer.layer()).try_init() {
+        println!("⚠️ Event log disabled: tracing subscriber already set ({})", e);
+    }
+
+    // Restore the persisted event log
+    if config.storage.persist_events {
+        let attached = match storage::JsonlStore::open(&config.storage.data_dir, "events.jsonl") {
+            Ok(store) => event_buffer.attach_store(store).await,
+            Err(e) => Err(e),
+        };
+        match attached {
+            Ok(count) => println!("🗃️ Event log: restored {} events", count),
+            Err(e) => println!("⚠️ Event log persistence disabled ({})", e),
+        }
+    }
+    tokio::spawn(api::run_event_log(event_buffer.clone(), event_queue));
+
     // Initialize Components (Shared State)
-    let metamask = Arc::new(MetaMaskClient::new());
+    let audit_log = match storage::JsonlStore::open(&config.storage.data_dir, "spend_audit.jsonl")
+        .and_then(audit::AuditLog::with_store)
+    {
+        Ok(log) => log,
+        Err(e) => {
+            println!("⚠️ Spend audit persistence disabled ({})", e);
+            audit::AuditLog::new()
+        }
+    };
+    let metamask = Arc::new(MetaMaskClient::new().with_audit_log(audit_log));
     // Read mode from config.toml (default: polymarket)
-    let mode: String = config.mode.clone();
+    let mode: String = config.mode.clone().unwrap_or_else(|| "polymarket".to_string());
     println!("Running in mode: {}", mode);
 
+    // Contract addresses resolve only through the registry; RPCs in use must serve the chains they are used with
+    let registry = registry::Registry::from_config(&config.registry).unwrap_or_else(|e| {
+        eprintln!("❌ Contract registry: {}", e);
+        std::process::exit(1);
+    });
+    let sepolia_chain_id = config.arbitrum.as_ref().map_or(registry::ARBITRUM_SEPOLIA, |a| a.sepolia_chain_id);
+    // Every chain's RPC providers, failed over between and cross-checked on critical reads
+    let rpc_pool = rpc_pool::RpcPool::from_config(&config);
+    // Exact and rolling win rates and streaks over closed trades, served by the API
+    let trade_metrics = metrics::MetricsCollector::new();
+    if config.registry.check_chain_ids {
+        let client = reqwest::Client::new();
+        let mut rpcs = Vec::new();
+        if !config.holdings.owner.is_empty() {
+            rpcs.extend(rpc_pool.urls(config.holdings.chain_id).into_iter().map(|url| (url, config.holdings.chain_id)));
+        }
+        if config.preflight.enabled {
+            rpcs.extend(rpc_pool.urls(sepolia_chain_id).into_iter().map(|url| (url, sepolia_chain_id)));
+        }
+        for (rpc_url, expected) in rpcs {
+            match registry::check_chain_id(&client, &rpc_url, expected, Duration::from_millis(config.registry.timeout_ms)).await {
+                Ok(()) => {}
+                Err(e @ registry::RegistryError::ChainMismatch { .. }) => {
+                    eprintln!("❌ Contract registry: {}", e);
+                    std::process::exit(1);
+                }
+                Err(e) => println!("⚠️ [Registry] Chain id of {} not checked ({})", rpc_url, e),
+            }
+        }
+    }
+
+    // Collateral: USD limits from the config are converted to it
+    let collateral = config.permission.collateral(&config.assets).unwrap_or_else(|e| {
+        println!("⚠️ {} - falling back to USDC", e);
+        assets::Collateral::default()
+    });
+    let daily_limit = collateral.from_usd(config.permission.daily_limit_usdc);
+    println!("💱 Collateral: {} ({} decimals, ${:.4}) | daily limit {}",
+        collateral.symbol(), collateral.asset.decimals, collateral.usd_price, collateral.format(daily_limit));
+
     // PermissionGuard setup (ERC-7715 mapping)
-    let mut guard = PermissionGuard { daily_limit: config.permission.daily_limit_usdc, spent_today: 0.0 };
+    let guard = PermissionGuard::new(daily_limit, config.permission.scope(&collateral));
 
     // MarketClient selection
-    let market_client: Box<dyn MarketClient + Send + Sync> = match mode.as_str() {
+    let envio_client = || -> Box<dyn MarketClient + Send + Sync> {
+        Box::new(ArbitrumMarketClient::new("https://envio-arbitrum-hyperindex.example/graphql".to_string()))
+    };
+    let clob_client = || -> Box<dyn MarketClient + Send + Sync> {
+        Box::new(PolymarketClient {
+            gamma_url: "https://gamma-api.polymarket.com/events?limit=20&active=true&closed=false".to_string(),
+            clob_url: "https://clob.polymarket.com/book".to_string(),
+            trades_url: "https://data-api.polymarket.com/trades".to_string(),
+            client: market_client::http_client(),
+        })
+    };
+    type ClientFactory = fn() -> Box<dyn MarketClient + Send + Sync>;
+    let (primary, secondary): (_, ClientFactory) = match mode.as_str() {
         "arbitrum_demo" => {
             println!("Using ArbitrumMarketClient (Envio HyperIndex)");
-            Box::new(ArbitrumMarketClient {
-                endpoint: "https://envio-arbitrum-hyperindex.example/graphql".to_string(),
-            })
+            (envio_client(), clob_client)
+        },
+        "sandbox" => {
+            let profile = venue::VenueProfile::sandbox(&config.sandbox, &registry).unwrap_or_else(|e| {
+                eprintln!("❌ Sandbox venue: {}", e);
+                std::process::exit(1);
+            });
+            println!("Using PolymarketClient against the sandbox CLOB ({})", profile.clob_url);
+            let sandbox: Box<dyn MarketClient + Send + Sync> = Box::new(PolymarketClient {
+                gamma_url: profile.gamma_url.clone(),
+                clob_url: profile.book_url(),
+                trades_url: profile.trades_url.clone(),
+                client: market_client::http_client(),
+            });
+            (sandbox, envio_client)
         },
         _ => {
             println!("Using PolymarketClient (CLOB Pattern Example)");
-            Box::new(PolymarketClient {
-                gamma_url: "https://gamma-api.polymarket.com/events?limit=20&active=true&closed=false".to_string(),
-                clob_url: "https://clob.polymarket.com/book".to_string(),
-                client: reqwest::Client::new(),
-            })
+            (clob_client(), envio_client)
         }
     };
+    // Venue names as used in the cross-venue mapping file
+    let (primary_venue, secondary_venue) = match mode.as_str() {
+        "arbitrum_demo" => ("envio", "polymarket"),
+        "sandbox" => ("sandbox", "envio"),
+        _ => ("polymarket", "envio"),
+    };
+    // Best-execution routing: confirmed cross-listed markets are also quoted on the other venue
+    let alt_client = config.routing.enabled.then(secondary);
+    let router = Arc::new(std::sync::Mutex::new({
+        let pairs = match config.routing.enabled {
+            true => mapping::load_confirmed(&config.routing.mapping_file).unwrap_or_else(|e| {
+                println!("⚠️ Routing disabled: cannot read {} ({:?})", config.routing.mapping_file, e);
+                Vec::new()
+            }),
+            false => Vec::new(),
+        };
+        let router = routing::Router::new(config.routing.clone(), primary_venue, pairs)
+            .with_latency(venue_latency::LatencyMonitor::new(config.venue_latency.clone()));
+        match storage::JsonlStore::open(&config.storage.data_dir, "routes.jsonl") {
+            Ok(journal) => router.with_journal(journal),
+            Err(e) => {
+                println!("⚠️ Route decision journal disabled ({})", e);
+                router
+            }
+        }
+    }));
+    if config.routing.enabled {
+        println!("Best-execution routing over {} confirmed pairs with {}", router.lock().unwrap().pair_count(), secondary_venue);
+    }
+    let mut quorum_incidents = None;
+    let market_client: Arc<dyn MarketClient + Send + Sync> = if config.quorum.enabled {
+        println!("Price quorum enabled: books must agree across Envio and CLOB within {:.4}", config.quorum.tolerance);
+        let client = quorum::QuorumClient::new(primary, secondary(), config.quorum.tolerance);
+        quorum_incidents = Some(client.incidents());
+        Arc::new(client)
+    } else {
+        Arc::from(primary)
+    };
     
     // Position manager for exit logic (Shared)
     let position_manager = Arc::new(RwLock::new(PositionManager::new(
         0.005,  // 0.5% profit target spread
         0.02,   // 2% stop loss spread
         config.timing.position_timeout_secs,
-    )));
+    ).with_greeks(config.greeks.clone())));
+
+    // A watchdog that would restart a healthy engine is not started at all
+    if let Err(e) = config.validate_supervisor() {
+        eprintln!("❌ {}", e);
+        std::process::exit(2);
+    }
+    // Liveness / readiness probes
+    let config_valid = match config.validate() {
+        Ok(()) => true,
+        Err(e) => {
+            println!("⚠️ Config validation failed: {}", e);
+            false
+        }
+    };
+    let health = Arc::new(health::HealthState::new(config.health.max_tick_age_secs, config_valid));
+
+    // Signing service (keys come from env / keystore / remote signer, never config.toml)
+    let signer = match &config.signer {
+        Some(signer_config) => match signer::from_config(signer_config).await {
+            Ok(s) => {
+                println!("🔑 [Signer] {} signer ready (evm: {}, solana: {})",
+                    s.kind(),
+                    s.evm_address().unwrap_or_else(|| "-".to_string()),
+                    s.solana_pubkey().unwrap_or_else(|| "-".to_string()));
+                Some(s)
+            }
+            Err(e) => {
+                println!("⚠️ [Signer] Failed to initialize signer: {}", e);
+                None
+            }
+        },
+        None => None,
+    };
+    // Solana wallet, whose balance counts toward the cross-chain portfolio
+    let solana_wallet = signer.as_ref().and_then(|s| s.solana_pubkey()).and_then(|pk| pk.parse::<solana_sdk::pubkey::Pubkey>().ok());
+    // Sandbox: executed legs are also signed and posted to the staging CLOB
+    // Without the sandbox the signer is kept for signing trade intents
+    let (sandbox_orders, intent_signer) = match (mode.as_str(), signer) {
+        ("sandbox", Some(signer)) => {
+            let sandbox = &config.sandbox;
+            match venue::ApiCredentials::from_env(&sandbox.api_key_env, &sandbox.api_secret_env, &sandbox.api_passphrase_env) {
+                Ok(credentials) => {
+                    println!("{} Signed orders go to {} (chain {})", "🧪 [Sandbox]".bold().yellow(), sandbox.clob_url, sandbox.chain_id);
+                    let profile = venue::VenueProfile::sandbox(sandbox, &registry).unwrap_or_else(|e| {
+                        eprintln!("❌ Sandbox venue: {}", e);
+                        std::process::exit(1);
+                    });
+                    (Some(Arc::new(venue::OrderClient::new(profile, credentials, signer))), None)
+                }
+                Err(e) => {
+                    println!("⚠️ [Sandbox] Order submission disabled: {}", e);
+                    (None, None)
+                }
+            }
+        }
+        (_, signer) => (None, signer),
+    };
+
+    // Orders posted to the venue, reconciled against its records via /api/reconcile
+    let venue_orders = Arc::new(std::sync::Mutex::new(
+        match storage::JsonlStore::open(&config.storage.data_dir, "venue_orders.jsonl") {
+            Ok(journal) => reconcile::OrderJournal::new().with_journal(journal),
+            Err(e) => {
+                println!("⚠️ Venue order journal disabled ({})", e);
+                reconcile::OrderJournal::new()
+            }
+        },
+    ));
+
+    // Settlement transactions of venue orders, followed to finality via /api/finality
+    let finality = Arc::new(std::sync::Mutex::new(
+        match storage::JsonlStore::open(&config.storage.data_dir, "finality.jsonl") {
+            Ok(journal) => finality::FinalityTracker::new(config.finality.clone()).with_journal(journal),
+            Err(e) => {
+                println!("⚠️ Finality journal disabled ({})", e);
+                finality::FinalityTracker::new(config.finality.clone())
+            }
+        },
+    ));
+    let receipts = finality::ReceiptReader::new(&config.finality, rpc_pool.clone());
+
+    // Traded markets followed to resolution
+    let resolutions = Arc::new(std::sync::Mutex::new(
+        match storage::JsonlStore::open(&config.storage.data_dir, "resolutions.jsonl") {
+            Ok(journal) => resolution::ResolutionTracker::new(config.resolution.clone()).with_journal(journal),
+            Err(e) => {
+                println!("⚠️ Resolution journal disabled ({})", e);
+                resolution::ResolutionTracker::new(config.resolution.clone())
+            }
+        },
+    ));
+    let resolution_http = reqwest::Client::new();
+
+    // Realized PnL vs the edge promised at signal time
+    let capture_tracker = Arc::new(std::sync::Mutex::new(
+        match storage::JsonlStore::open(&config.storage.data_dir, "capture.jsonl") {
+            Ok(journal) => capture::CaptureTracker::new().with_journal(journal),
+            Err(e) => {
+                println!("⚠️ Capture journal disabled ({})", e);
+                capture::CaptureTracker::new()
+            }
+        },
+    ));
+
+    // Latest scan results shared with the dashboard
+    let shared_markets = Arc::new(RwLock::new(Vec::new()));
+    let shared_signals = Arc::new(RwLock::new(Vec::new()));
+    let utilization = Arc::new(RwLock::new(utilization::CapitalTracker::new()));
+    // Resting orders, journaled so a crashed session's leftovers can be swept
+    let open_orders = Arc::new(std::sync::Mutex::new(
+        match storage::JsonlStore::open(&config.storage.data_dir, "open_orders.jsonl") {
+            Ok(journal) => OrderRegistry::recover(journal),
+            Err(e) => {
+                println!("⚠️ Order journal disabled ({})", e);
+                OrderRegistry::new()
+            }
+        }.with_ttl(config.trading.max_rest_secs),
+    ));
+
+    // Strategy sub-accounts, with kill switches journaled across restarts
+    let strategies = Arc::new(std::sync::Mutex::new({
+        let book = StrategyBook::from_config(&config.strategies, Wallet::current_timestamp());
+        match storage::JsonlStore::open(&config.storage.data_dir, "strategy_switches.jsonl") {
+            Ok(journal) => book.with_journal(journal),
+            Err(e) => {
+                println!("⚠️ Strategy switch journal disabled ({})", e);
+                book
+            }
+        }
+    }));
+    for account in strategies.lock().unwrap().report(Wallet::current_timestamp()).iter().filter(|a| !a.enabled) {
+        println!("🛑 Strategy {} is switched off ({})", account.strategy, account.disabled_reason.as_deref().unwrap_or("no reason"));
+    }
+
+    // Imported holdings still open from previous sessions are positions again
+    let ctf_contract = match registry.resolve(config.holdings.chain_id, registry::Contract::Ctf) {
+        Ok(address) => address,
+        Err(e) if !config.holdings.owner.is_empty() => {
+            eprintln!("❌ Holdings: {}", e);
+            std::process::exit(1);
+        }
+        Err(_) => "",
+    };
+    let holdings = Arc::new(std::sync::Mutex::new(
+        match storage::JsonlStore::open(&config.storage.data_dir, "holdings.jsonl") {
+            Ok(journal) => holdings::Holdings::new(config.holdings.clone(), ctf_contract).with_rpc_pool(rpc_pool.clone()).with_journal(journal),
+            Err(e) => {
+                println!("⚠️ Holdings persistence disabled ({})", e);
+                holdings::Holdings::new(config.holdings.clone(), ctf_contract).with_rpc_pool(rpc_pool.clone())
+            }
+        },
+    ));
+    let open_holdings = holdings.lock().unwrap().open_holdings().to_vec();
+    for holding in open_holdings {
+        position_manager.write().await.open_position(holding.position(&config.holdings.strategy));
+        strategies.lock().unwrap().allocate(&config.holdings.strategy, holding.size * holding.cost_basis);
+    }
+
+    let equity_curve = Arc::new(std::sync::Mutex::new(
+        match storage::JsonlStore::open(&config.storage.data_dir, "equity.jsonl")
+            .and_then(|journal| equity::EquityCurve::open(journal, config.equity.sample_interval_secs))
+        {
+            Ok(curve) => curve,
+            Err(e) => {
+                println!("⚠️ Equity curve persistence disabled ({})", e);
+                equity::EquityCurve::new(config.equity.sample_interval_secs)
+            }
+        },
+    ));
+
+    let approval_queue = Arc::new(std::sync::Mutex::new(approvals::ApprovalQueue::new(config.approval.clone())));
+
+    // Signals from external models, executed through the normal pipeline
+    let external_inbox = Arc::new(std::sync::Mutex::new(external::ExternalInbox::from_env(config.external_signals.clone())));
+    if config.external_signals.enabled && !external_inbox.lock().unwrap().is_open() {
+        println!("⚠️ External signals enabled but ${} is not set - submissions will be refused", config.external_signals.token_env);
+    }
+
+    // Trade intents proposed for an external signer to co-sign and execute
+    let intent_book = Arc::new(std::sync::Mutex::new(
+        match storage::JsonlStore::open(&config.storage.data_dir, "intents.jsonl") {
+            Ok(journal) => intents::IntentBook::from_env(config.intents.clone()).with_journal(journal),
+            Err(e) => {
+                println!("⚠️ Intent journal disabled ({})", e);
+                intents::IntentBook::from_env(config.intents.clone())
+            }
+        },
+    ));
+    if config.intents.enabled {
+        if intent_book.lock().unwrap().authenticate(None) == Err(intents::IntentError::Disabled) {
+            println!("⚠️ Trade intents enabled but ${} is not set - the intent API will refuse requests", config.intents.token_env);
+        }
+        match intent_signer.as_ref().and_then(|s| s.evm_address()) {
+            Some(address) => println!("📝 [Intents] Legs are proposed as intents signed by {}, not executed", address),
+            None => println!("📝 [Intents] Legs are proposed as unsigned intents (no EVM signer), not executed"),
+        }
+    }
+
+    // Markets pinned from the dashboard, journaled across restarts
+    let watchlist = Arc::new(std::sync::Mutex::new(
+        match storage::JsonlStore::open(&config.storage.data_dir, "watchlist.jsonl") {
+            Ok(journal) => watchlist::Watchlist::open(config.watchlist.clone(), journal),
+            Err(e) => {
+                println!("⚠️ Watchlist persistence disabled ({})", e);
+                watchlist::Watchlist::new(config.watchlist.clone())
+            }
+        },
+    ));
+
+    // Market event windows from config and the API; trades entered inside one carry its tag
+    let annotations = Arc::new(std::sync::Mutex::new({
+        let configured = annotations::Annotations::from_config(&config.annotations);
+        match storage::JsonlStore::open(&config.storage.data_dir, "annotations.jsonl") {
+            Ok(journal) => configured.with_journal(journal),
+            Err(e) => {
+                println!("⚠️ Annotation journal disabled ({})", e);
+                configured
+            }
+        }
+    }));
+
+    // Dry-run previews from the API, answered by the engine between ticks
+    let (preview_desk, mut preview_jobs) = preview::channel(8);
+
+    // Component timings for /api/debug/perf
+    let perf = perf::PerfMonitor::new(config.debug.perf);
+
+    // Balances, positions and PnL across chains, with global limits when enabled
+    let portfolio = Arc::new(std::sync::Mutex::new(
+        portfolio::Portfolio::new(config.portfolio.clone(), &config.assets, primary_venue, collateral.clone())));
+    if let Err(e) = portfolio.lock().unwrap().validate() {
+        eprintln!("❌ {}", e);
+        std::process::exit(1);
+    }
+    if config.portfolio.enabled {
+        println!("Cross-chain portfolio limits enabled (primary chain {})", portfolio.lock().unwrap().primary_chain());
+    }
+
+    // Cancel / replace rules for resting quotes
+    let quoting = Arc::new(std::sync::Mutex::new(quoting::QuoteGovernor::new(config.quoting.clone())));
+    // Order / cancel budgets at the venue, so maker mode stays within the key's rate limits
+    let order_limits = Arc::new(std::sync::Mutex::new(order_limits::OrderRateLimits::new(config.order_rate.clone())));
+
+    // Relationships between markets, checked against their implied probability bounds
+    let synthetic = Arc::new(std::sync::Mutex::new(synthetic::SyntheticPricer::from_config(&config.synthetic).unwrap_or_else(|e| {
+        eprintln!("❌ {}", e);
+        std::process::exit(1);
+    })));
+    if config.synthetic.enabled {
+        println!("Synthetic pricing over {} market relationships", synthetic.lock().unwrap().relationship_count());
+    }
 
     // 🚀 Start API Server
     let api_state = api::ApiState {
         metamask: metamask.clone(),
         position_manager: position_manager.clone(),
+        markets: shared_markets.clone(),
+        signals: shared_signals.clone(),
+        health: health.clone(),
+        utilization: utilization.clone(),
+        orders: open_orders.clone(),
+        strategies: strategies.clone(),
+        approvals: approval_queue.clone(),
+        rate_limiter: Arc::new(std::sync::Mutex::new(ratelimit::RateLimiter::new(config.api_rate_limit.clone()))),
+        equity: equity_curve.clone(),
+        watchlist: watchlist.clone(),
+        external: external_inbox.clone(),
+        intents: intent_book.clone(),
+        preview: preview_desk,
+        holdings: holdings.clone(),
+        collateral: collateral.clone(),
+        router: router.clone(),
+        resolutions: resolutions.clone(),
+        capture: capture_tracker.clone(),
+        perf: perf.clone(),
+        portfolio: portfolio.clone(),
+        quoting: quoting.clone(),
+        synthetic: synthetic.clone(),
+        reconciler: reconcile::Reconciler::new(config.reconcile.clone(), sandbox_orders.clone(), venue_orders.clone()),
+        events: event_buffer.clone(),
+        finality: finality.clone(),
+        annotations: annotations.clone(),
+        order_limits: order_limits.clone(),
+        rpc_pool: rpc_pool.clone(),
+        metrics: trade_metrics.clone(),
     };
     
-    tokio::spawn(async move {
-        api::start_server(api_state).await;
+    // Servers run under the watchdog: a dead listener is restarted
+    let supervisor = supervisor::Supervisor::new(config.supervisor.clone(), health.clone());
+
+    #[cfg(feature = "grpc")]
+    {
+        let grpc_state = api_state.clone();
+        let grpc_port = config.grpc.port;
+        let probe = supervisor.clone();
+        supervisor.spawn("grpc", move |heartbeat| {
+            let (state, probe) = (grpc_state.clone(), probe.clone());
+            async move {
+                tokio::select! {
+                    _ = grpc::start_server(state, grpc_port) => {}
+                    _ = probe.probe_listener(heartbeat, ([127, 0, 0, 1], grpc_port).into()) => {}
+                }
+            }
+        });
+    }
+
+    let dashboard_dir = config.dashboard.dir.clone().map(std::path::PathBuf::from);
+    let api_port = config.dashboard.port;
+    let fleet = fleet::Fleet::new(&config.fleet, api_state.clone());
+    if fleet.len() > 1 {
+        println!("{} Agent {} of {} (GET /api/fleet)", "🚢 [Fleet]".bold().yellow(), config.fleet.agent_id, fleet.len());
+    }
+    let probe = supervisor.clone();
+    supervisor.spawn("api", move |heartbeat| {
+        let (fleet, dir, probe) = (fleet.clone(), dashboard_dir.clone(), probe.clone());
+        async move {
+            tokio::select! {
+                _ = api::start_server(fleet, dir, api_port) => {}
+                _ = probe.probe_listener(heartbeat, ([127, 0, 0, 1], api_port).into()) => {}
+            }
+        }
     });
 
     println!("{} Market Data:   Envio Indexer...           {}", "📡 [Init]".bold().yellow(), "Connected.".green());
@@ -111,8 +789,33 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     // Initialize components from config
-    let fee_model = FeeModel { maker_fee_bps: 0, taker_fee_bps: 200 };
-    let mut wallet = Wallet::new(config.permission.daily_limit_usdc);
+    let mut fee_tiers = fee_tiers::FeeTiers::new(config.fee_tiers.clone(), Wallet::current_timestamp());
+    if config.fee_tiers.enabled {
+        fee_tiers = match storage::JsonlStore::open(&config.storage.data_dir, "fee_volume.jsonl") {
+            Ok(journal) => fee_tiers.with_journal(journal),
+            Err(e) => {
+                println!("⚠️ Fee volume journal disabled ({})", e);
+                fee_tiers
+            }
+        };
+        println!("Fee tiers enabled: ${:.2} traded this month (tier {})", fee_tiers.volume(), fee_tiers.tier());
+    }
+    let mut fee_model = fee_tiers.fee_model();
+    // Taker cost rate TCA measures at p95; edge math prices taker fills at no less than this
+    let mut calibrated_taker_rate = 0.0;
+    let mut fee_tier = fee_tiers.tier();
+    let mut maker_taker = {
+        let router = maker_taker::MakerTaker::new(config.maker_taker.clone());
+        match storage::JsonlStore::open(&config.storage.data_dir, "leg_paths.jsonl") {
+            Ok(journal) if config.maker_taker.enabled => router.with_journal(journal),
+            Ok(_) => router,
+            Err(e) => {
+                println!("⚠️ Leg path journal disabled ({})", e);
+                router
+            }
+        }
+    };
+    let mut wallet = Wallet::new(daily_limit);
     // Use the selected market_client for all market data
     let detector = ArbitrageDetector::new(
         config.trading.min_spread_threshold,
@@ -122,125 +825,2117 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         config.timing.latency_base_ms,
         config.timing.adverse_selection_std,
     );
-    let execution_engine = ExecutionEngine::new(fee_model.clone(), latency_model);
+    let venue_contract = registry.resolve(sepolia_chain_id, registry::Contract::Stylus).unwrap_or_default();
+    let mut execution_engine = ExecutionEngine::new(fee_model.clone(), latency_model)
+        .with_scope(guard.scope.clone(), venue_contract)
+        .with_remainder_policy(RemainderPolicy::parse(&config.trading.remainder_policy, config.trading.max_chase_bps))
+        .with_order_registry(open_orders.clone());
+    let preflight = config.preflight.enabled.then(|| {
+        println!("{} Simulating settlements via eth_call on {}", "🧪 [Init]".bold().yellow(), rpc_pool.urls(sepolia_chain_id).join(", "));
+        preflight::SettlementSimulator::new(rpc_pool.clone(), sepolia_chain_id, &config.preflight.smart_account, Duration::from_millis(config.preflight.timeout_ms))
+            .with_decimals(collateral.asset.decimals)
+    });
+    // Startup sweep: nothing from a previous session may stay resting
+    let orphans = open_orders.lock().unwrap().open_count();
+    if orphans > 0 {
+        println!("{} Found {} orphaned orders from a previous session", "🧹 [Init]".bold().yellow(), orphans);
+        execution_engine.cancel_all_orders("startup orphan sweep");
+    }
+    let mut book_recorder = if config.storage.record_books {
+        match recorder::TickRecorder::open(&config.storage.data_dir, recorder::RecordFormat::parse(&config.storage.record_format)) {
+            Ok(r) => {
+                println!("{} Recording order books to {}", "🎞️ [Init]".bold().yellow(), r.path());
+                Some(r)
+            }
+            Err(e) => {
+                println!("⚠️ Order book recording disabled ({})", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    // Recordings and the event log: rotate, archive cold segments, expire old archives
+    if config.retention.enabled {
+        match retention::Retention::open(&config.storage.data_dir, config.retention.clone()) {
+            Ok(archive) => {
+                let archive = archive.with_store(object_store::ObjectStore::from_config(&config.object_store));
+                let archive = Arc::new(archive);
+                let check_secs = config.retention.check_secs.max(60);
+                let idler = supervisor.clone();
+                supervisor.spawn("retention", move |heartbeat| {
+                    let (archive, idler) = (archive.clone(), idler.clone());
+                    async move {
+                        loop {
+                            let report = archive.run(Wallet::current_timestamp()).await;
+                            if !report.is_empty() {
+                                let msg = format!("🗄️ [Retention] rotated {}, archived {}, uploaded {}, deleted {}",
+                                    report.rotated.len(), report.archived.len(), report.uploaded.len(), report.deleted.len());
+                                println!("{}", msg);
+                                log_event(EventLevel::Info, "retention", None, &msg);
+                            }
+                            for error in &report.errors {
+                                log_event(EventLevel::Warn, "retention", None, &format!("⚠️ [Retention] {}", error));
+                            }
+                            idler.idle(&heartbeat, Duration::from_secs(check_secs)).await;
+                        }
+                    }
+                });
+            }
+            Err(e) => println!("⚠️ Retention disabled ({})", e),
+        }
+    }
+    // Object storage: upload rotated recordings, daily reports and exports
+    if config.object_store.enabled {
+        match object_store::ObjectSink::open(&config.object_store, &config.storage.data_dir) {
+            Some(sink) => {
+                let sink = Arc::new(tokio::sync::Mutex::new(sink));
+                let sync_secs = config.object_store.sync_secs.max(60);
+                let idler = supervisor.clone();
+                supervisor.spawn("object_store", move |heartbeat| {
+                    let (sink, idler) = (sink.clone(), idler.clone());
+                    async move {
+                        loop {
+                            let report = sink.lock().await.sync(Wallet::current_timestamp()).await;
+                            if !report.uploaded.is_empty() {
+                                let msg = format!("☁️ [ObjectStore] uploaded {} files", report.uploaded.len());
+                                println!("{}", msg);
+                                log_event(EventLevel::Info, "object_store", None, &msg);
+                            }
+                            for error in &report.errors {
+                                log_event(EventLevel::Warn, "object_store", None, &format!("⚠️ [ObjectStore] {}", error));
+                            }
+                            idler.idle(&heartbeat, Duration::from_secs(sync_secs)).await;
+                        }
+                    }
+                });
+            }
+            None => println!("⚠️ [ObjectStore] Endpoint, bucket or credentials ({} / {}) missing - not uploading",
+                config.object_store.access_key_env, config.object_store.secret_key_env),
+        }
+    }
+    let mut tca = TcaAnalyzer::new();
+    let mut regimes = RegimeClassifier::new();
+    let mut mirrors = mirror::MirrorDetector::new(config.mirror.clone());
+    // Question embeddings for semantic grouping, cached across restarts
+    let embedder = embeddings::from_config(&config.embeddings);
+    let mut semantic = embeddings::SemanticIndex::new(config.embeddings.clone());
+    if config.embeddings.enabled {
+        match storage::JsonlStore::open(&config.storage.data_dir, "embeddings.jsonl") {
+            Ok(journal) => semantic = semantic.with_journal(journal),
+            Err(e) => println!("⚠️ Embedding cache disabled ({})", e),
+        }
+        println!("{} {} question embeddings ({} cached)",
+            "🧭 [Init]".bold().yellow(), embedder.model(), semantic.cached());
+    }
+    // With the volatility response on, elevated volatility throttles entries instead of halting
+    let mut risk_config = config.risk.clone();
+    risk_config.volatility_halt = !config.volatility_response.enabled;
+    let mut risk = RiskManager::new(risk_config, daily_limit);
+    let mut vol_response = volatility::VolatilityResponse::new(config.volatility_response.clone());
+    let sizer = PositionSizer::new(config.trading.trade_size, config.trading.max_position_value);
+    let exit_optimizer = exit_optimizer::ExitOptimizer::new(config.exit_optimizer.clone());
+    let mut trade_flow = TradeFlow::new();
+    let mut tax_ledger = if config.tax.enabled {
+        match storage::JsonlStore::open(&config.storage.data_dir, "tax_lots.jsonl").and_then(tax::TaxLedger::open) {
+            Ok(ledger) => Some(ledger),
+            Err(e) => {
+                println!("⚠️ Tax lot journal disabled ({})", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let mut signal_queue = SignalQueue::new(config.timing.signal_ttl_ms, config.trading.min_spread_threshold);
+    let mut attribution = match storage::JsonlStore::open(&config.storage.data_dir, "attribution.jsonl") {
+        Ok(store) => Attribution::new().with_store(store),
+        Err(e) => {
+            println!("⚠️ Attribution persistence disabled ({})", e);
+            Attribution::new()
+        }
+    };
+    let mut model_ledger = match storage::JsonlStore::open(&config.storage.data_dir, "model_ledger.jsonl") {
+        Ok(store) => model_ledger::ModelLedger::new(config.model_drift.clone()).with_journal(store),
+        Err(e) => {
+            println!("⚠️ Model ledger persistence disabled ({})", e);
+            model_ledger::ModelLedger::new(config.model_drift.clone())
+        }
+    };
+    let mut decision_log = if config.decisions.enabled {
+        match storage::JsonlStore::open(&config.storage.data_dir, "decisions.jsonl") {
+            Ok(store) => Some(decisions::DecisionLog::new(&config.decisions, store)),
+            Err(e) => {
+                println!("⚠️ Decision records disabled ({})", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let trading_windows = if config.trading_windows.enabled {
+        match windows::TradingWindows::from_config(&config.trading_windows) {
+            Ok(w) => {
+                println!("{} Trading hours {:?} UTC, {} calendar blackouts",
+                    "🗓️ [Init]".bold().yellow(), config.trading_windows.hours_utc, w.blackout_count());
+                w
+            }
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        windows::TradingWindows::default()
+    };
+    let compliance = if config.compliance.enabled {
+        match compliance::Compliance::from_config(&config.compliance) {
+            Ok(c) => {
+                println!("{} Jurisdiction {:?}: {} compliance rules",
+                    "⚖️ [Init]".bold().yellow(), c.jurisdiction(), c.rule_count());
+                c
+            }
+            Err(e) => {
+                eprintln!("❌ Invalid compliance rules: {}", e);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        compliance::Compliance::default()
+    };
+    let mut entry_throttle = throttle::EntryThrottle::new(
+        config.trading.max_open_bundles,
+        config.trading.max_entries_per_minute,
+        config.trading.max_entries_per_hour,
+    );
+    let mut pacer = pacing::SpendPacer::new(config.pacing.clone());
+    let leases = if config.coordination.enabled {
+        let instance_id = if config.coordination.instance_id.is_empty() {
+            lease::default_instance_id()
+        } else {
+            config.coordination.instance_id.clone()
+        };
+        match lease::LeaseManager::new(&config.storage.data_dir, &instance_id, config.coordination.lease_ttl_secs) {
+            Ok(l) => {
+                println!("{} Coordinating as {} (lease TTL {}s)", "🤝 [Init]".bold().yellow(), instance_id, config.coordination.lease_ttl_secs);
+                Some(l)
+            }
+            Err(e) => {
+                eprintln!("❌ Coordination lease unavailable ({}); refusing to trade uncoordinated", e);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        None
+    };
+    let mut is_leader = false;
+    // Permission and epoch of the lease this tick trades under; every order is fenced on it
+    let mut lease_epoch: Option<(String, u64)> = None;
+    let mut behavior = anomaly::BehaviorMonitor::new(config.anomaly.clone());
+    // Set while self-monitoring holds the agent in safe mode
+    let mut safe_mode_until: Option<u64> = None;
+    let mut next_balance_refresh = 0;
+    let mut next_finality_poll = 0;
+    // UTC day the next daily report covers
+    let mut report_day = Wallet::current_timestamp() / 86_400;
+    let usdc_contract = registry.resolve(config.arbitrum.as_ref().map_or(registry::ARBITRUM_ONE, |a| a.mainnet_chain_id), registry::Contract::Usdc)
+        .unwrap_or_default();
+    let mut sweeper = sweep::ProfitSweeper::new(config.sweep.clone(), usdc_contract);
+    if config.sweep.enabled {
+        match storage::JsonlStore::open(&config.storage.data_dir, "sweeps.jsonl") {
+            Ok(journal) => sweeper = sweeper.with_journal(journal),
+            Err(e) => println!("⚠️ Sweep journal disabled ({})", e),
+        }
+        println!("{} Sweeping profits above ${:.2} working capital to {}",
+            "🏦 [Init]".bold().yellow(), config.sweep.working_capital_target, config.sweep.cold_address);
+    }
+    let mut treasury = treasury::Treasury::new(config.treasury.clone());
+    if let Err(e) = treasury.validate() {
+        eprintln!("❌ {}", e);
+        std::process::exit(1);
+    }
+    if config.treasury.enabled {
+        match storage::JsonlStore::open(&config.storage.data_dir, "treasury.jsonl") {
+            Ok(journal) => treasury = treasury.with_journal(journal),
+            Err(e) => println!("⚠️ Treasury journal disabled ({})", e),
+        }
+        println!("{} Idle USDC above ${:.2} goes to {} (${:.2} deposited, cap ${:.2})",
+            "🏛️ [Init]".bold().yellow(), config.treasury.liquid_buffer, config.treasury.venue,
+            treasury.deposited(), config.treasury.max_deposited);
+    }
+    // Balance not committed to positions, resting orders, executions, sweeps or the treasury
+    let mut working_capital = working_capital::WorkingCapital::new(config.working_capital.clone());
+    // Failed legs: classified, then retried / repriced / unwound / halted per class
+    let mut remediator = match remediation::Remediator::from_config(&config.remediation) {
+        Ok(remediator) => remediator,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+    };
+    // Legs sold back by an unwind, closed out with the next tick's exits
+    let mut unwound = Vec::new();
+    // Worst partial fill of each bundle kept within the per-trade loss cap
+    let leg_risk = leg_risk::LegRiskCheck::new(config.leg_risk.clone());
+    let plugins = PluginManager::new();
+    let mut scheduler = PollScheduler::new(config.polling.clone());
+    let mut spread_tracker = spreads::SpreadTracker::new();
+    // Size caps of signals queued this tick (external size hints, conflict netting), by market
+    let mut size_caps: std::collections::HashMap<String, f64> = std::collections::HashMap::new();
+    let mut freshness = freshness::FreshnessModel::new(config.freshness.clone());
+    // Set while the daily allowance is exhausted: timestamp of the next period reset
+    let mut allowance_resets_at: Option<u64> = None;
     
     println!("{} Daily Allowance: ${:.2} USDC (Enforced by ERC-7715)", "💸 [Init]".bold().yellow(), wallet.daily_limit);
+    if let Some(profile) = &config.profile {
+        println!("{} Config profile: {}", "🧩 [Init]".bold().yellow(), profile);
+    }
     println!("{} Trade Size: ${:.2} per leg", "📊 [Init]".bold().yellow(), config.trading.trade_size);
     println!();
     println!("⏳ Waiting for MetaMask permission via Dashboard...");
 
-    loop {
-        // Wait for active permission if not present
-        if !metamask.has_valid_permission().await {
-            tokio::time::sleep(Duration::from_secs(1)).await;
-            continue;
+    // Warm start: scan the cached universe while the first live fetch runs in the background
+    let mut universe_cache = match universe::UniverseCache::open(&config.storage.data_dir, &mode, config.universe_cache.clone()) {
+        Ok(cache) => Some(cache),
+        Err(e) => {
+            println!("⚠️ Universe cache disabled ({})", e);
+            None
         }
+    };
+    let mut warm_universe = universe_cache.as_ref().and_then(|cache| cache.load(Wallet::current_timestamp()));
+    let mut universe_refresh = None;
+    if let Some(snapshot) = &warm_universe {
+        println!("{} Warm start from {} cached markets ({}s old), refreshing in the background",
+            "🔥 [Init]".bold().yellow(), snapshot.markets.len(), snapshot.age_secs(Wallet::current_timestamp()));
+        let client = market_client.clone();
+        universe_refresh = Some(tokio::spawn(async move { client.get_markets().await.map_err(|e| e.to_string()) }));
+    }
 
-        let log_msg = format!("📡 Fetching markets...");
-        println!("\n{}", log_msg.cyan());
-        push_log(&log_msg);
-        let mut markets = match market_client.get_markets().await {
-            Ok(m) => m,
-            Err(e) => {
-                println!("⚠️ Failed to fetch markets: {}", e);
-                tokio::time::sleep(Duration::from_secs(config.timing.poll_interval_secs)).await;
-                continue;
-            }
-        };
-        let found_msg = format!("   Found {} active markets", markets.len());
-        println!("{}", found_msg);
-        push_log(&found_msg);
+    // Venue status page / error-rate monitor (pauses are separate from our own safe mode)
+    let mut venue_monitor = maintenance::MaintenanceMonitor::new(config.maintenance.clone());
+    let status_http = reqwest::Client::new();
+
+    let engine_heartbeat = supervisor::Heartbeat::new();
+    loop {
+        // A tick stuck on a hung await stops beating; the watchdog drops it and the loop resumes
+        let engine = async {
+            loop {
+                engine_heartbeat.beat();
+                health.tick(Wallet::current_timestamp());
+                let tick_started = Instant::now();
+
+                // Wait for active permission if not present
+                if !metamask.has_valid_permission().await {
+                    engine_heartbeat.beat();
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    continue;
+                }
+
+                // Only the lease holder trades this permission
+                if let (Some(leases), Some(perm)) = (&leases, metamask.get_permission().await) {
+                    let leading = match leases.try_acquire(&perm.permission_id, Wallet::current_timestamp()) {
+                        Ok(lease::LeaseState::Acquired { epoch, took_over }) => {
+                            let msg = format!("👑 [Lease] {} leads permission {} (epoch {}{})", leases.holder(), perm.permission_id,
+                                epoch, if took_over { ", took over from expired leader" } else { "" });
+                            println!("{}", msg);
+                            log_event(EventLevel::Info, "lease", None, &msg);
+                            lease_epoch = Some((perm.permission_id.clone(), epoch));
+                            true
+                        }
+                        Ok(lease::LeaseState::Renewed { epoch }) => {
+                            lease_epoch = Some((perm.permission_id.clone(), epoch));
+                            true
+                        }
+                        Ok(lease::LeaseState::HeldBy { holder, expires_at }) => {
+                            if is_leader {
+                                log_event(EventLevel::Warn, "lease", None, &format!("Lost lease on {} to {}", perm.permission_id, holder));
+                            }
+                            println!("⏸️ [Lease] Standby: {} trades permission {} (lease until {})", holder, perm.permission_id, expires_at);
+                            false
+                        }
+                        Err(e) => {
+                            println!("⚠️ [Lease] {} - not trading this tick", e);
+                            false
+                        }
+                    };
+                    is_leader = leading;
+                    if !leading {
+                        engine_heartbeat.beat();
+                        tokio::time::sleep(Duration::from_secs(config.timing.poll_interval_secs)).await;
+                        continue;
+                    }
+                }
+
+                // Safe mode after anomalous own behavior: no trading until the cooldown ends
+                let now = Wallet::current_timestamp();
+                if let Some(until) = safe_mode_until {
+                    if now < until {
+                        println!("🛑 Safe mode ({}s left)", until - now);
+                        engine_heartbeat.beat();
+                        tokio::time::sleep(Duration::from_secs(config.timing.poll_interval_secs)).await;
+                        continue;
+                    }
+                    safe_mode_until = None;
+                    behavior.reset();
+                    push_log("🔄 Safe mode cooldown expired - resuming trading");
+                }
+
+                // Venue maintenance / outage: no trading until the recovery is confirmed
+                if venue_monitor.poll_due(now) {
+                    match maintenance::fetch_status(&status_http, &config.maintenance.status_url).await {
+                        Ok(page) => venue_monitor.observe_status(page),
+                        Err(e) => log_event(EventLevel::Debug, "maintenance", None, &format!("Status page check failed: {}", e)),
+                    }
+                }
+                match venue_monitor.update(now) {
+                    Some(maintenance::Transition::Paused(condition)) => {
+                        let msg = format!("🚧 [Maintenance] Venue unavailable ({}) - pausing trading", condition);
+                        println!("{}", msg.yellow());
+                        log_event(EventLevel::Warn, "maintenance", None, &msg);
+                    }
+                    Some(maintenance::Transition::Resumed { paused_secs }) => {
+                        let msg = format!("✅ [Maintenance] Venue recovered after {}s - resuming trading", paused_secs);
+                        println!("{}", msg.green());
+                        log_event(EventLevel::Info, "maintenance", None, &msg);
+                    }
+                    None => {}
+                }
+                if let Some(condition) = venue_monitor.paused().cloned() {
+                    // Keep probing so the error-rate heuristic sees the recovery
+                    let probe_ok = market_client.get_markets().await.is_ok();
+                    venue_monitor.record_request(probe_ok, now);
+                    println!("🚧 Venue paused: {}{}", condition, venue_monitor.recovery_remaining(now)
+                        .map(|s| format!(" (healthy, resuming in {}s)", s)).unwrap_or_default());
+                    engine_heartbeat.beat();
+                    tokio::time::sleep(Duration::from_secs(config.timing.poll_interval_secs)).await;
+                    continue;
+                }
 
-        // Check for position exits FIRST
-        let current_time = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+                // Soft shutdown: allowance exhausted → slow heartbeat until the period resets
+                let required_allowance = config.trading.trade_size * 2.0;
+                if let Some(resets_at) = allowance_resets_at {
+                    if now >= resets_at {
+                        metamask.reset_daily_spend().await;
+                        risk.reset_daily();
+                        pacer.reset();
+                        allowance_resets_at = None;
+                        let msg = "🔄 Allowance period reset - resuming full-rate scanning";
+                        println!("{}", msg.green());
+                        push_log(msg);
+                        plugins.notify_allowance_reset().await;
+                    } else if metamask.get_remaining_allowance().await >= required_allowance {
+                        // A fresh grant arrived via the dashboard
+                        allowance_resets_at = None;
+                        push_log("🔄 New allowance available - resuming full-rate scanning");
+                    } else {
+                        let wait = (resets_at - now).min(config.safety.exhausted_heartbeat_secs.max(1));
+                        println!("💤 Allowance exhausted - heartbeat ({}s until reset)", resets_at - now);
+                        engine_heartbeat.beat();
+                        tokio::time::sleep(Duration::from_secs(wait)).await;
+                        continue;
+                    }
+                } else if metamask.get_remaining_allowance().await < required_allowance {
+                    let resets_at = metamask.next_reset_at().await.unwrap_or(now + PERMISSION_PERIOD_SECS);
+                    let msg = format!("💤 Daily allowance exhausted - slowing to {}s heartbeat until reset in {}s",
+                        config.safety.exhausted_heartbeat_secs, resets_at.saturating_sub(now));
+                    println!("{}", msg.yellow());
+                    push_log(&msg);
+                    plugins.notify_allowance_exhausted(resets_at).await;
+                    allowance_resets_at = Some(resets_at);
+                    continue;
+                }
+
+                let log_msg = "📡 Fetching markets...".to_string();
+                println!("\n{}", log_msg.cyan());
+                push_log(&log_msg);
+                // Until the background refresh lands, scan the warm-start universe (stale)
+                let fetch_started = Instant::now();
+                let fetched = match universe_refresh.take() {
+                    Some(refresh) if !refresh.is_finished() => {
+                        universe_refresh = Some(refresh);
+                        None
+                    }
+                    Some(refresh) => Some(refresh.await.map_err(|e| e.to_string()).and_then(|r| r)),
+                    None => Some(market_client.get_markets().await.map_err(|e| e.to_string())),
+                };
+                let from_cache = fetched.is_none();
+                perf.record("market_fetch", fetch_started.elapsed());
+                let markets = match fetched {
+                    Some(Ok(m)) => {
+                        health.set_data_source_healthy(true);
+                        venue_monitor.record_request(true, now);
+                        if warm_universe.take().is_some() {
+                            println!("   🔥 [Universe] Live universe loaded - leaving warm start");
+                        }
+                        if let Some(cache) = &mut universe_cache {
+                            if let Err(e) = cache.save(&m, now) {
+                                println!("   ⚠️ [Universe] Failed to snapshot universe: {}", e);
+                            }
+                        }
+                        m
+                    }
+                    None => warm_universe.as_ref().map(|snapshot| snapshot.markets.clone()).unwrap_or_default(),
+                    Some(Err(e)) => {
+                        venue_monitor.record_request(false, now);
+                        warm_universe = None;
+                        health.set_data_source_healthy(false);
+                        println!("⚠️ Failed to fetch markets: {}", e);
+                        engine_heartbeat.beat();
+                        tokio::time::sleep(Duration::from_secs(config.timing.poll_interval_secs)).await;
+                        continue;
+                    }
+                };
+                let found_msg = match from_cache {
+                    true => format!("   Found {} cached markets (stale, live refresh pending)", markets.len()),
+                    false => format!("   Found {} active markets", markets.len()),
+                };
+                println!("{}", found_msg);
+                push_log(&found_msg);
+                // Counterpart venue's markets, for routing cross-listed legs
+                let alt_markets = match &alt_client {
+                    Some(client) if router.lock().unwrap().pair_count() > 0 => client.get_markets().await.unwrap_or_else(|e| {
+                        println!("   ⚠️ [Routing] {} markets unavailable: {}", secondary_venue, e);
+                        Vec::new()
+                    }),
+                    _ => Vec::new(),
+                };
+                // Venues not heard from lately are probed with a book fetch, keeping latency estimates current
+                let probes = [
+                    (primary_venue, Some(market_client.as_ref()), markets.first()),
+                    (secondary_venue, alt_client.as_deref(), alt_markets.first()),
+                ];
+                for (venue, client, market) in probes {
+                    let (Some(client), Some(token_id)) = (client, market.and_then(|m| m.clob_token_ids.first())) else { continue };
+                    if router.lock().unwrap().needs_probe(venue, Wallet::current_timestamp()) {
+                        let started = std::time::Instant::now();
+                        if client.get_order_book(token_id).await.is_ok() {
+                            router.lock().unwrap().record_latency(venue, started.elapsed().as_secs_f64() * 1000.0, Wallet::current_timestamp());
+                        }
+                    }
+                }
+
+                // Stale data → back off polling and require more edge
+                if let Some(envio_health) = market_client.data_health().await {
+                    freshness.observe(envio_health);
+                }
+                let cache_haircut = if from_cache { config.universe_cache.stale_edge_haircut } else { 0.0 };
+                let edge_haircut = freshness.quote_haircut(primary_venue, &[]) + cache_haircut;
+                if edge_haircut > 0.0 {
+                    let msg = format!("   🐢 [Freshness] Data {}ms stale - edge haircut ${:.3}, polling every {}s",
+                        freshness.staleness_ms(), edge_haircut, freshness.poll_interval_secs(scheduler.tick_interval_secs()));
+                    println!("{}", msg);
+                    push_log(&msg);
+                }
+
+                // Check for position exits FIRST
+                let current_time = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs();
+                let now_ms = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_millis() as u64;
+
+                // Mid / volume series for regime detection
+                for market in &markets {
+                    if let Some(mid) = market.outcome_prices.first() {
+                        regimes.observe(&market.id, *mid, market.volume_24hr);
+                    }
+                }
+                regimes.retain(&markets.iter().map(|m| m.id.clone()).collect::<Vec<_>>());
+                vol_response.retain(&markets.iter().map(|m| m.id.clone()).collect::<Vec<_>>());
+
+                // Feed post-trade markouts for TCA
+                for market in &markets {
+                    for (token_id, price) in market.clob_token_ids.iter().zip(&market.outcome_prices) {
+                        tca.observe_mid(token_id, *price, now_ms);
+                    }
+                }
         
-        // Lock position manager for updates
-        let mut exits = Vec::new(); // Placeholder to avoid holding lock too long if logic was complex
-        {
-            let mut pm = position_manager.write().await;
-            exits = pm.check_exits(&markets, current_time, fee_model.taker_rate());
-        }
+                // Holdings imported through the API join the open positions
+                let imported = holdings.lock().unwrap().take_pending();
+                for holding in imported {
+                    position_manager.write().await.open_position(holding.position(&config.holdings.strategy));
+                    strategies.lock().unwrap().allocate(&config.holdings.strategy, holding.size * holding.cost_basis);
+                    if let Some(ledger) = &mut tax_ledger {
+                        ledger.acquire(&holding.market_id, &holding.token_id, holding.size, holding.size * holding.cost_basis, holding.imported_at);
+                    }
+                }
 
-        if !exits.is_empty() {
-            println!("📤 Closed {} positions:", exits.len());
-            for exit in &exits {
-                println!("   {} | {:?} | PnL: ${:.4}", 
-                    exit.position.token_id, exit.reason, exit.pnl);
-            }
-        }
+                // Lock position manager for updates
+                let mut exits; // Filled under the lock, used after it is released
+                {
+                    let mut pm = position_manager.write().await;
+                    exits = pm.check_exits(&markets, current_time, fee_model.taker_rate());
+                    exits.append(&mut unwound);
 
-        // Scan for new signals
-        let signals = detector.scan(&markets);
-        if signals.is_empty() {
-            let msg = "   No arbitrage signals found.";
-            println!("{}", msg);
-            push_log(msg);
-        } else {
-            let msg = format!("⚡ Detected {} arbitrage signals!", signals.len());
-            println!("{}", msg);
-            push_log(&msg);
-            for signal in signals {
-                let sig_msg = format!("   Signal on Market {}: Spread {:.2}%, Edge ${:.2}",
-                    signal.market_id, signal.spread * 100.0, signal.edge);
-                println!("{}", sig_msg);
-                push_log(&sig_msg);
-                if let Some(market) = markets.iter().find(|m| m.id == signal.market_id) {
-                    if signal.recommended_side == Side::Buy {
-                        let size_per_leg = config.trading.trade_size;
-                        let remaining = metamask.get_remaining_allowance().await;
-                        let required = size_per_leg * 2.0;
-                        if remaining < required {
-                            let warn_msg = format!("   ⚠️ Insufficient permission allowance (${:.2} < ${:.2})", remaining, required);
-                            println!("{}", warn_msg);
-                            push_log(&warn_msg);
-                            continue;
-                        }
-                        let exec_msg = "   Attempting to execute arb strategy...";
-                        println!("{}", exec_msg);
-                        push_log(exec_msg);
+                    // Capital utilization: deployed notional, bundle recycling, allowance usage
+                    let mut util = utilization.write().await;
+                    for exit in &exits {
+                        util.bundle_leg_closed(&exit.position.market_id, current_time, pm.open_legs(&exit.position.market_id));
+                    }
+                    util.observe(current_time, pm.open_notional(), daily_limit);
+                    if let Some(perm) = metamask.get_permission().await {
+                        util.observe_allowance(perm.spent_today, perm.daily_limit);
+                    }
+                }
+
+                // Exit path: sell the leg or buy the complement and merge the set, whichever leaves more
+                if exit_optimizer.enabled() {
+                    for exit in exits.iter_mut() {
+                        let Some(market) = markets.iter().find(|m| m.id == exit.position.market_id) else { continue };
+                        let mut books = Vec::new();
+                        for token_id in &market.clob_token_ids {
+                            match market_client.get_order_book(token_id).await {
+                                Ok(book) => books.push(book),
+                                Err(_) => break,
+                            }
+                        }
+                        let Some(mut plan) = exit_optimizer.plan(&exit.position, market, &books, fee_model.taker_rate()) else { continue };
+                        if plan.path == exit_optimizer::ExitPath::CompleteBundle {
+                            // The complement is bought like an entry leg: scoped, paced, fitted to free capital
+                            // and charged to the wallet and the grant as one amount; a refusal sells instead
+                            let cost = plan.complement_cost() + plan.bundle_fees;
+                            let remaining = metamask.get_remaining_allowance().await;
+                            let mut refused = metamask.authorize_call(&execution_engine.trade_call(cost)).await.err().map(|e| e.to_string());
+                            if refused.is_none() {
+                                refused = pacer.check(cost, current_time, remaining, metamask.next_reset_at().await).err().map(|e| e.to_string());
+                            }
+                            if refused.is_none() && working_capital.enabled() {
+                                let committed = working_capital::Commitments {
+                                    open_positions: position_manager.read().await.open_notional(),
+                                    resting_orders: working_capital::resting_reserve(&open_orders.lock().unwrap().open_orders()),
+                                    pending_settlements: sweeper.pending().map_or(0.0, |t| t.amount),
+                                    treasury: treasury.deposited(),
+                                };
+                                let capital = working_capital.free(risk.get_status().current_balance, committed, current_time);
+                                if capital.tradable() < cost {
+                                    refused = Some(format!("${:.2} free of ${:.2} needed", capital.tradable(), cost));
+                                }
+                            }
+                            if refused.is_none() {
+                                let spend = audit::SpendContext {
+                                    market_id: market.id.clone(),
+                                    token_id: plan.complement.iter().map(|c| c.token_id.as_str()).collect::<Vec<_>>().join(","),
+                                    tx_hash: None,
+                                };
+                                let spent = metamask.spend_with(spend, |allowance| {
+                                    (cost <= allowance && wallet.record_spend(cost)).then_some(((), cost))
+                                }).await;
+                                refused = match spent {
+                                    Ok(Some(())) => {
+                                        pacer.record(cost, current_time);
+                                        None
+                                    }
+                                    Ok(None) => Some(format!("${:.2} exceeds the wallet or the allowance left", cost)),
+                                    Err(e) => Some(e.to_string()),
+                                };
+                            }
+                            if let Some(reason) = refused {
+                                println!("   ⚠️ [Exit] Complement for {} refused: {}", exit.position.token_id, reason);
+                                if !plan.fall_back_to_sell() {
+                                    continue;
+                                }
+                            }
+                        }
+                        plan.apply(exit);
+                        position_manager.write().await.amend_exit(exit);
+                        let msg = format!("🔀 [Exit] {} {:.2} via {:?}: sell ${} vs complete bundle ${}", exit.position.token_id, plan.size, plan.path,
+                            plan.sell_value.map_or("-".to_string(), |v| format!("{:.4}", v)),
+                            plan.bundle_value.map_or("-".to_string(), |v| format!("{:.4}", v)));
+                        println!("   {}", msg);
+                        log_event(EventLevel::Info, "exit", Some(&market.id), &msg);
+                    }
+                }
+
+                // Resting remainders: fill from trade prints, cancel when stale
+                let resting_orders = open_orders.lock().unwrap().open_orders();
+                for mut resting in resting_orders {
+                    let expired = resting.is_expired(current_time);
+                    let order = &mut resting.order;
+                    let market = markets.iter().find(|m| m.clob_token_ids.contains(&order.token_id));
+                    if let Some(market) = market {
+                        if let Ok(prints) = market_client.get_trades(market).await {
+                            trade_flow.ingest_all(prints);
+                        }
+                    }
+                    let filled = trade_flow.replay_into(order, resting.cursor);
+                    if filled > 0.0 {
+                        let notional = filled * order.price;
+                        let cost = notional + fee_model.calculate(notional, true);
+                        if wallet.record_spend(cost) {
+                            fee_tiers.record(notional, true, current_time);
+                            maker_taker.record_fill(&resting.order_id, filled, order.price, current_time);
+                            // The fill already happened: a refusing grant leaves it in the audit trail as an overdraft
+                            let spend = audit::SpendContext {
+                                market_id: market.map(|m| m.id.clone()).unwrap_or_default(),
+                                token_id: order.token_id.clone(),
+                                tx_hash: None,
+                            };
+                            if let Err(e) = metamask.record_spend_for(cost, spend.clone()).await {
+                                let msg = format!("⚠️ [Audit] Resting fill of ${:.2} on {} not charged to the grant: {}", cost, order.token_id, e);
+                                println!("   {}", msg);
+                                log_event(EventLevel::Warn, "audit", Some(&spend.market_id), &msg);
+                                metamask.record_overdraft(cost, spend, &e).await;
+                            }
+                            if let Some(ledger) = &mut tax_ledger {
+                                ledger.acquire(&market.map(|m| m.id.clone()).unwrap_or_default(), &order.token_id, filled, cost, current_time);
+                            }
+                            let mut pm = position_manager.write().await;
+                            if pm.add_fill(&order.token_id, filled, order.price) {
+                                if let Some(pos) = pm.get_position(&order.token_id) {
+                                    strategies.lock().unwrap().allocate(&pos.strategy, notional);
+                                }
+                            }
+                            println!("   🪤 Resting fill: {:.2} @ ${:.4} on {}", filled, order.price, order.token_id);
+                        }
+                    }
+                    let mut registry = open_orders.lock().unwrap();
+                    if order.is_filled() {
+                        registry.close(&resting.order_id, "filled");
+                        quoting.lock().unwrap().forget(&resting.order_id);
+                        order_limits.lock().unwrap().forget(&resting.order_id);
+                    } else if expired {
+                        println!("   🧹 Resting remainder {:.2} on {} expired", order.remaining(), order.token_id);
+                        registry.close(&resting.order_id, "expired");
+                        quoting.lock().unwrap().forget(&resting.order_id);
+                        order_limits.lock().unwrap().forget(&resting.order_id);
+                    } else {
+                        resting.cursor = current_time + 1;
+                        registry.update(resting);
+                    }
+                }
+                // Near-expiry resting orders: re-quote while the signal holds, cancel once it decays
+                let expiring = open_orders.lock().unwrap().near_expiry(current_time, config.trading.requote_window_secs);
+                for resting in expiring {
+                    let order = &resting.order;
+                    let market = markets.iter().find(|m| m.clob_token_ids.contains(&order.token_id));
+                    let still_signalled = market.is_some_and(|m| !detector.scan(std::slice::from_ref(m)).is_empty());
+                    let requote = match still_signalled {
+                        true => market_client.get_order_book(&order.token_id).await.ok()
+                            .and_then(|book| book.best_bid().map(|bid| PassiveOrder::place(&book, order.side, bid, order.remaining()))),
+                        false => None,
+                    };
+                    let modification = match requote {
+                        Some(fresh) => quoting::Modification::Replace(fresh),
+                        None => quoting::Modification::Cancel("signal decayed".to_string()),
+                    };
+                    // Anti-flicker: held-back changes are retried next tick while the order is still near expiry
+                    let market_id = market.map_or(order.token_id.as_str(), |m| m.id.as_str());
+                    if let Err(hold) = quoting.lock().unwrap().submit(market_id, &resting, modification, current_time) {
+                        log_event(EventLevel::Debug, "quoting", Some(market_id),
+                            &format!("⏸️ [Quoting] Holding change to {}: {}", resting.order_id, hold));
+                    }
+                }
+                // Modifications go out within the venue's order / cancel budgets; the rest wait, coalesced per order
+                let mut modifications: Vec<(String, quoting::Modification)> = Vec::new();
+                {
+                    let mut limits = order_limits.lock().unwrap();
+                    for deferred in limits.release(now_ms) {
+                        log_event(EventLevel::Debug, "order_limits", Some(&deferred.market_id),
+                            &format!("🚦 [OrderLimits] Releasing change to {} held {}ms", deferred.order_id, now_ms.saturating_sub(deferred.deferred_at_ms)));
+                        modifications.push((deferred.order_id, deferred.modification));
+                    }
+                    for batch in quoting.lock().unwrap().due(current_time) {
+                        for (order_id, modification) in batch.modifications {
+                            match limits.acquire(&batch.market_id, order_limits::OrderAction::for_modification(&modification), now_ms) {
+                                Ok(()) => modifications.push((order_id, modification)),
+                                Err(wait_ms) => {
+                                    log_event(EventLevel::Debug, "order_limits", Some(&batch.market_id),
+                                        &format!("🚦 [OrderLimits] Deferring change to {}: no headroom for {}ms", order_id, wait_ms));
+                                    limits.defer(&batch.market_id, &order_id, modification, now_ms);
+                                }
+                            }
+                        }
+                    }
+                }
+                {
+                    let mut registry = open_orders.lock().unwrap();
+                    for (order_id, modification) in modifications {
+                        match modification {
+                            quoting::Modification::Replace(fresh) => {
+                                println!("   🔁 Re-quoted resting {:.2} on {} @ ${:.4}", fresh.size, fresh.token_id, fresh.price);
+                                registry.requote(&order_id, fresh, current_time);
+                            }
+                            quoting::Modification::Cancel(reason) => {
+                                if let Some(closed) = registry.close(&order_id, &reason) {
+                                    println!("   🧹 Cancelled resting {:.2} on {}: {}", closed.order.remaining(), closed.order.token_id, reason);
+                                }
+                            }
+                        }
+                    }
+                }
+
+                for exit in &exits {
+                    risk.record_trade(exit.pnl);
+                    trade_metrics.record_trade(exit.pnl, 0.0).await;
+                    fee_tiers.record(exit.position.size * exit.exit_price, false, current_time);
+                    holdings.lock().unwrap().reduce(&exit.position.token_id, exit.position.size, current_time);
+                    if let Some(log) = &mut decision_log {
+                        log.record_realized(&exit.position.market_id, exit.pnl);
+                    }
+                    resolutions.lock().unwrap().record_exit(&exit.position.market_id, &exit.position.token_id,
+                        exit.position.size, exit.position.size * exit.exit_price - exit.fees, current_time);
+                    if exit.position.size > 0.0 {
+                        model_ledger.record_realized(&exit.position.token_id, exit.pnl / exit.position.size, current_time);
+                    }
+                    if let Some(ledger) = &mut tax_ledger {
+                        ledger.dispose(exit);
+                    }
+                    strategies.lock().unwrap().settle(&exit.position.strategy, exit.position.size * exit.position.entry_price, exit.pnl, current_time);
+                    capture_tracker.lock().unwrap().record_exit(exit);
+                    if let Some(day) = attribution.record_exit(exit) {
+                        println!("🗂️ [Attribution] Persisted {} rows for day {}", day.rows.len(), day.day);
+                    }
+                }
+                // Profit sweep: realized PnL above working capital goes to the cold address
+                let mut resolved_sweeps: Vec<sweep::SweepTransfer> = sweeper.expire_stale(current_time).into_iter().collect();
+                let realized_pnl = position_manager.read().await.total_pnl();
+                // Only the free balance is swept, never capital backing positions or orders
+                let sweepable = if working_capital.enabled() {
+                    let committed = working_capital::Commitments {
+                        open_positions: position_manager.read().await.open_notional(),
+                        resting_orders: working_capital::resting_reserve(&open_orders.lock().unwrap().open_orders()),
+                        pending_settlements: sweeper.pending().map_or(0.0, |t| t.amount),
+                        treasury: treasury.deposited(),
+                    };
+                    working_capital.free(risk.get_status().current_balance, committed, current_time).free
+                } else {
+                    risk.get_status().current_balance
+                };
+                if let Some(transfer) = sweeper.plan(realized_pnl, sweepable, current_time) {
+                    let call = sweeper.call(&transfer);
+                    let resolved = match metamask.authorize_call(&call).await {
+                        // Settlement is simulated: an authorized transfer confirms immediately
+                        Ok(()) => sweeper.confirm(&transfer.sweep_id, None, current_time),
+                        Err(e) => sweeper.fail(&transfer.sweep_id, &e.to_string()),
+                    };
+                    resolved_sweeps.extend(resolved);
+                }
+                for transfer in &resolved_sweeps {
+                    let (level, msg) = match &transfer.status {
+                        sweep::SweepStatus::Failed { reason } => (EventLevel::Warn,
+                            format!("⚠️ [Sweep] ${:.2} to {} failed: {}", transfer.amount, transfer.to, reason)),
+                        _ => (EventLevel::Info,
+                            format!("🏦 [Sweep] Swept ${:.2} to {} (${:.2} total)", transfer.amount, transfer.to, sweeper.total_swept())),
+                    };
+                    println!("{}", msg);
+                    log_event(level, "sweep", None, &msg);
+                    plugins.notify_profit_sweep(transfer).await;
+                }
+
+                // Settlement finality: re-read receipts until final, correcting fills that never settled
+                if config.finality.enabled && current_time >= next_finality_poll {
+                    next_finality_poll = current_time + config.finality.poll_secs;
+                    let open = finality.lock().unwrap().open();
+                    let mut heads: std::collections::HashMap<u64, Option<u64>> = std::collections::HashMap::new();
+                    for tx in open {
+                        let head = match heads.get(&tx.chain_id) {
+                            Some(head) => *head,
+                            None => {
+                                let head = receipts.head(tx.chain_id).await
+                                    .map_err(|e| log_event(EventLevel::Debug, "finality", None, &format!("Chain {} head unavailable: {}", tx.chain_id, e)))
+                                    .ok();
+                                *heads.entry(tx.chain_id).or_insert(head)
+                            }
+                        };
+                        let Some(head) = head else { continue };
+                        let receipt = match receipts.receipt(tx.chain_id, &tx.tx_hash).await {
+                            Ok(receipt) => receipt,
+                            Err(e) => {
+                                log_event(EventLevel::Debug, "finality", Some(&tx.market_id), &format!("Receipt of {} unavailable: {}", tx.tx_hash, e));
+                                continue;
+                            }
+                        };
+                        let Some(transition) = finality.lock().unwrap().observe(&tx.tx_hash, receipt, head, current_time) else { continue };
+                        let (level, msg) = match transition {
+                            finality::Transition::Included { block } =>
+                                (EventLevel::Info, format!("⛓️ [Finality] {} mined in block {} (provisional)", tx.tx_hash, block)),
+                            finality::Transition::Final { block } =>
+                                (EventLevel::Info, format!("⛓️ [Finality] {} final in block {}", tx.tx_hash, block)),
+                            finality::Transition::Reorged { from_block, to_block } =>
+                                (EventLevel::Warn, format!("⚠️ [Finality] {} reorged out of block {} ({}), re-checking",
+                                    tx.tx_hash, from_block, to_block.map_or("not mined".to_string(), |b| format!("now in block {}", b)))),
+                            finality::Transition::Dropped | finality::Transition::Reverted { .. } => {
+                                // Nothing settled: take the transaction's share out of the order's booked fill
+                                let mut journal = venue_orders.lock().unwrap();
+                                let corrected = journal.get(&tx.order_id).map(|o| o.expected_fill - tx.size);
+                                if let Some(expected_fill) = corrected {
+                                    journal.amend_fill(&tx.order_id, expected_fill);
+                                }
+                                (EventLevel::Warn, format!("⚠️ [Finality] {} {:?}: order {} fill corrected by -{:.2}",
+                                    tx.tx_hash, transition, tx.order_id, tx.size))
+                            }
+                        };
+                        println!("{}", msg);
+                        log_event(level, "finality", Some(&tx.market_id), &msg);
+                    }
+                }
+
+                // Treasury: supply idle USDC once nothing has traded for a while
+                let liquid = risk.get_status().current_balance - position_manager.read().await.open_notional() - treasury.deposited();
+                if let Some(deposit) = treasury.plan_deposit(liquid, current_time) {
+                    let call = treasury.call(&deposit);
+                    // Settlement is simulated: an authorized move settles immediately
+                    let outcome = metamask.authorize_call(&call).await.map_err(|e| e.to_string());
+                    let deposit = treasury.settle(deposit, outcome);
+                    let msg = format!("🏛️ [Treasury] {:?} ${:.2} to {}: {:?} (${:.2} deposited)",
+                        deposit.kind, deposit.amount, deposit.venue, deposit.status, treasury.deposited());
+                    println!("{}", msg);
+                    log_event(EventLevel::Info, "treasury", None, &msg);
+                }
+
+                // Equity curve: realized balance plus open positions marked to market
+                let unrealized = position_manager.read().await.unrealized_pnl(&markets);
+
+                // Cross-chain portfolio: free collateral per chain plus positions on the chain they executed on
+                {
+                    let pm = position_manager.read().await;
+                    let mut portfolio = portfolio.lock().unwrap();
+                    let primary_chain = portfolio.primary_chain().to_string();
+                    portfolio.set_balance(&primary_chain, risk.get_status().current_balance - pm.open_notional());
+                    if let Some(wallet) = solana_wallet.as_ref().filter(|_| primary_chain != "solana") {
+                        if config.portfolio.enabled && current_time >= next_balance_refresh {
+                            next_balance_refresh = current_time + config.portfolio.balance_refresh_secs;
+                            match tokio::task::block_in_place(|| sol_manager.balance(wallet)) {
+                                Ok(lamports) => portfolio.set_raw_balance("solana", lamports as u128),
+                                Err(e) => log_event(EventLevel::Debug, "portfolio", None, &format!("Solana balance read failed: {}", e)),
+                            }
+                        }
+                    }
+                    portfolio.refresh(&pm.get_positions(), &markets, current_time);
+                }
+                if let Some(point) = equity_curve.lock().unwrap().sample(current_time, &risk.get_status(), unrealized) {
+                    println!("📈 [Equity] ${:.2} (unrealized {:+.2}, drawdown {:.1}%)",
+                        point.equity, point.unrealized, point.drawdown_percent);
+                }
+
+                if !exits.is_empty() {
+                    println!("📤 Closed {} positions:", exits.len());
+                    for exit in &exits {
+                        println!("   {} | {:?} | PnL: ${:.4}", 
+                            exit.position.token_id, exit.reason, exit.pnl);
+                    }
+                }
+
+                // Follow traded markets to resolution
+                if config.resolution.enabled {
+                    let due = resolutions.lock().unwrap().due(current_time);
+                    for market_id in due {
+                        match resolution::fetch_resolution(&resolution_http, &config.resolution.markets_url, &market_id).await {
+                            Ok(Some(res)) => {
+                                if let Some(outcome) = resolutions.lock().unwrap().record_resolution(&market_id, res, current_time) {
+                                    let msg = format!("🏁 [Resolution] {} resolved {} | realized {:+.2}% vs theoretical {:+.2}% | surprise ${:+.2}",
+                                        market_id,
+                                        if outcome.ambiguous { "ambiguous".to_string() } else { outcome.winner.clone().unwrap_or_default() },
+                                        outcome.realized_return * 100.0, outcome.theoretical_return * 100.0, outcome.surprise);
+                                    println!("{}", msg);
+                                    push_log(&msg);
+                                    log_event(EventLevel::Info, "resolution", Some(&market_id), &msg);
+                                }
+                            }
+                            Ok(None) => {}
+                            Err(e) => log_event(EventLevel::Debug, "resolution", Some(&market_id),
+                                &format!("Resolution check failed: {}", e)),
+                        }
+                    }
+                }
+
+                // Relationships between markets: signal violated bounds (never traded automatically)
+                for signal in synthetic.lock().unwrap().scan(&markets, current_time) {
+                    let legs: Vec<String> = signal.legs.iter().map(|l| format!("{} {} @ {:.3}", l.outcome, l.market_id, l.price)).collect();
+                    let msg = format!("🧩 [Synthetic] {}: {} - buy {} for ${:.3}, pays >= ${:.0} (edge ${:.3}) | {:?} assumption risk: {}",
+                        signal.relationship_id, signal.violation, legs.join(" + "), signal.cost, signal.guaranteed_payout, signal.edge,
+                        signal.assumption_risk, signal.assumption);
+                    println!("   {}", msg);
+                    push_log(&msg);
+                    log_event(EventLevel::Info, "synthetic", signal.legs.first().map(|l| l.market_id.as_str()), &msg);
+                }
+
+                // Sample quoted spreads, then scan due markets best spread/depth first
+                for market in &markets {
+                    spread_tracker.observe_market(market, current_time);
+                }
+                spread_tracker.retain(&markets.iter().map(|m| m.id.clone()).collect::<Vec<_>>());
+                let due = scheduler.select_due(&markets, current_time);
+                let due_count = due.len();
+                let ranked = spread_tracker.rank(due, config.polling.max_scan_markets);
+                // Watched markets are scanned every tick, ahead of (and outside) the scan cap
+                let due_markets: Vec<_> = watchlist.lock().unwrap().prioritize(&markets, ranked).into_iter().cloned().collect();
+                let (fast, medium, slow) = scheduler.tier_counts();
+                println!("   Scanning {} of {} due markets (tiers: {} fast / {} medium / {} slow)",
+                    due_markets.len(), due_count, fast, medium, slow);
+                let scan_started = Instant::now();
+                // A switched-off strategy is not scanned at all
+                let mut signals = if strategies.lock().unwrap().is_enabled(ARB_STRATEGY) {
+                    detector.scan(&due_markets)
+                } else {
+                    println!("   ⏸️ Strategy {} is switched off - skipping its scan", ARB_STRATEGY);
+                    Vec::new()
+                };
+                perf.record("scan", scan_started.elapsed());
+                perf.set_cache("markets", markets.len());
+                perf.set_cache("due_markets", due_markets.len());
+                let detected: Vec<(String, f64)> = signals.iter().map(|s| (s.market_id.clone(), s.edge)).collect();
+                // Per-regime edge threshold plus staleness haircut on top of the detector's floor,
+                // widened (and signals spaced out) while volatility is elevated
+                signals.retain(|s| {
+                    let regime = regimes.classify(&s.market_id);
+                    let throttle = vol_response.throttle(risk.volatility(), config.risk.volatility_threshold, regime);
+                    let min_edge = regime.params(&config.strategy).min_edge;
+                    let haircut = freshness.quote_haircut(primary_venue, &s.quote_ages_ms) + cache_haircut;
+                    let threshold = min_edge + haircut + throttle.extra_edge;
+                    if s.edge < threshold {
+                        println!("   🌪️ [Regime] {} is {} - edge ${:.3} below threshold ${:.3} (regime ${:.3} + haircut ${:.3} + volatility ${:.3})",
+                            s.market_id, regime, s.edge, threshold, min_edge, haircut, throttle.extra_edge);
+                        return false;
+                    }
+                    if let Some(remaining) = vol_response.cooldown_remaining(&s.market_id, &throttle, current_time) {
+                        println!("   🌡️ [Volatility] {} cooling down for {}s{}",
+                            s.market_id, remaining, if throttle.elevated { " (volatility elevated)" } else { "" });
+                        return false;
+                    }
+                    vol_response.record_signal(&s.market_id, current_time);
+                    true
+                });
+                if let Some(log) = &mut decision_log {
+                    log.observe(&markets, current_time);
+                    let poll_interval = freshness.poll_interval_secs(scheduler.tick_interval_secs());
+                    for market in &due_markets {
+                        let edge = detected.iter().find(|(id, _)| *id == market.id).map(|(_, edge)| *edge);
+                        let decision = match (edge, signals.iter().any(|s| s.market_id == market.id)) {
+                            (None, _) => decisions::Decision::NoSignal,
+                            (Some(_), false) => decisions::Decision::Filtered,
+                            (Some(_), true) => decisions::Decision::Signalled,
+                        };
+                        let features = decisions::DecisionFeatures::new(market, spread_tracker.stats(&market.id).as_ref(),
+                            current_time, freshness.staleness_ms(), poll_interval);
+                        log.record_scan(&market.id, features, decision, edge, current_time);
+                    }
+                }
+                // Semantic grouping: embed new questions and re-index when the universe changes
+                if config.embeddings.enabled {
+                    match semantic.refresh(embedder.as_ref(), &markets).await {
+                        Ok(stats) if stats.embedded > 0 => {
+                            let msg = format!("🧭 [Semantic] Embedded {} questions; {} related pairs across {} markets",
+                                stats.embedded, semantic.groups().len(), stats.indexed);
+                            println!("{}", msg);
+                            log_event(EventLevel::Info, "embeddings", None, &msg);
+                        }
+                        Ok(_) => {}
+                        Err(e) => println!("⚠️ [Semantic] {}", e),
+                    }
+                }
+                // Relative-value signals across mirrored markets (distinct from bundle arb)
+                if config.mirror.enabled {
+                    for rv in mirrors.scan(&markets) {
+                        let msg = format!("   🪞 [Mirror] {} YES ${:.3} vs {} (gap ${:.3}, mean ${:.3}) - edge ${:.3}",
+                            rv.cheap_market, rv.yes_price, rv.rich_market, rv.gap, rv.mean_gap, rv.edge);
+                        println!("{}", msg);
+                        log_event(EventLevel::Info, "mirror", Some(&rv.cheap_market), &msg);
+                    }
+                }
+                trade_flow.prune(current_time.saturating_sub(3600));
+                for signal in &signals {
+                    scheduler.record_signal(&signal.market_id, current_time);
+                }
+                *shared_markets.write().await = markets.clone();
+                *shared_signals.write().await = signals.clone();
+                // Watched markets: books and signal always go out on the SSE stream
+                let watched: Vec<_> = watchlist.lock().unwrap().entries().iter()
+                    .filter_map(|e| markets.iter().find(|m| m.id == e.market_id))
+                    .collect();
+                for market in watched {
+                    let mut books = Vec::new();
+                    for token_id in &market.clob_token_ids {
+                        if let Ok(book) = market_client.get_order_book(token_id).await {
+                            spread_tracker.observe_book(&market.id, &book, current_time);
+                            books.push(book);
+                        }
+                    }
+                    api::publish_stream("watch", &watchlist::WatchUpdate {
+                        market_id: market.id.clone(),
+                        question: market.question.clone(),
+                        timestamp: current_time,
+                        books,
+                        signal: signals.iter().find(|s| s.market_id == market.id).cloned(),
+                    });
+                }
+                // Human approvals: expired requests are dropped, approved signals re-enter the queue
+                for expired in approval_queue.lock().unwrap().expire(current_time) {
+                    let msg = format!("⌛ [Approval] {} on {} expired undecided", expired.approval_id, expired.market_id);
+                    println!("{}", msg);
+                    log_event(EventLevel::Warn, "approval", Some(&expired.market_id), &msg);
+                }
+                for expired in intent_book.lock().unwrap().expire(current_time) {
+                    let msg = format!("⌛ [Intents] {} on {} expired without execution", expired.intent_id, expired.market_id);
+                    println!("{}", msg);
+                    log_event(EventLevel::Warn, "intents", Some(&expired.market_id), &msg);
+                }
+                let approved = approval_queue.lock().unwrap().take_approved();
+                let mut approved_markets = std::collections::HashSet::new();
+                // External signals: priced against fresh books, then queued like detected ones
+                let mut external_signals = Vec::new();
+                size_caps.clear();
+                let pending = external_inbox.lock().unwrap().pending();
+                for record in pending {
+                    let size = record.signal.size_hint.unwrap_or(config.trading.trade_size).min(config.trading.trade_size);
+                    let mut books = Vec::new();
+                    if let Some(market) = markets.iter().find(|m| m.id == record.signal.market_id) {
                         for token_id in &market.clob_token_ids {
                             if let Ok(book) = market_client.get_order_book(token_id).await {
-                                if let Some(result) = execution_engine.execute(
-                                    &book, size_per_leg, Side::Buy, &mut wallet
-                                ) {
-                                    let _ = metamask.record_spend(result.total_cost).await;
+                                books.push(book);
+                            }
+                        }
+                        if books.len() != market.clob_token_ids.len() {
+                            books.clear();
+                        }
+                    }
+                    let status = match external::price(&record.signal, &books, size) {
+                        Ok(signal) => {
+                            let status = external::ExternalStatus::Queued { edge: signal.edge };
+                            external_signals.push((record.signal_id.clone(), signal, size, record.signal.size_hint.is_some()));
+                            status
+                        }
+                        Err(reason) => external::ExternalStatus::Rejected { reason },
+                    };
+                    let msg = format!("📨 [External] {} on {}: {:?}", record.signal_id, record.signal.market_id, status);
+                    println!("{}", msg);
+                    log_event(EventLevel::Info, "external", Some(&record.signal.market_id), &msg);
+                    external_inbox.lock().unwrap().resolve(&record.signal_id, status);
+                }
+                if signals.is_empty() && approved.is_empty() && external_signals.is_empty() {
+                    let msg = "   No arbitrage signals found.";
+                    println!("{}", msg);
+                    push_log(msg);
+                } else {
+                    if !signals.is_empty() {
+                        let msg = format!("⚡ Detected {} arbitrage signals!", signals.len());
+                        println!("{}", msg);
+                        push_log(&msg);
+                    }
+                    // Net intended exposures per token across sources before anything is queued
+                    let tokens = |market_id: &str| markets.iter().find(|m| m.id == market_id)
+                        .map(|m| m.clob_token_ids.clone()).unwrap_or_default();
+                    let mut intents = Vec::new();
+                    for signal in signals {
+                        intents.push(conflicts::SignalIntent { source: conflicts::SignalSource::Detected,
+                            tokens: tokens(&signal.market_id), size: config.trading.trade_size, signal });
+                    }
+                    let mut hinted = std::collections::HashSet::new();
+                    for (signal_id, signal, size, has_hint) in external_signals {
+                        if has_hint {
+                            hinted.insert(signal_id.clone());
+                        }
+                        intents.push(conflicts::SignalIntent { source: conflicts::SignalSource::External { signal_id },
+                            tokens: tokens(&signal.market_id), size, signal });
+                    }
+                    for approval in approved {
+                        intents.push(conflicts::SignalIntent { source: conflicts::SignalSource::Approved { approval_id: approval.approval_id },
+                            tokens: tokens(&approval.market_id), size: config.trading.trade_size, signal: approval.signal });
+                    }
+                    let resolution = conflicts::resolve(intents);
+                    for dropped in &resolution.dropped {
+                        let msg = format!("   ⚔️ [Conflict] Dropped {} {:?} signal on {}: {}",
+                            dropped.source, dropped.signal.recommended_side, dropped.signal.market_id, dropped.reason);
+                        println!("{}", msg);
+                        log_event(EventLevel::Info, "conflicts", Some(&dropped.signal.market_id), &msg);
+                        if let Some(log) = &mut decision_log {
+                            log.record_conflict(&dropped.signal.market_id, &format!("{}: {}", dropped.source, dropped.reason));
+                        }
+                    }
+                    for kept in resolution.kept {
+                        let hinted = matches!(&kept.source, conflicts::SignalSource::External { signal_id } if hinted.contains(signal_id));
+                        if kept.capped || hinted {
+                            size_caps.insert(kept.signal.market_id.clone(), kept.size);
+                        }
+                        if let conflicts::SignalSource::Approved { approval_id } = &kept.source {
+                            println!("   ✅ [Approval] {} approved - executing on {}", approval_id, kept.signal.market_id);
+                            approved_markets.insert(kept.signal.market_id.clone());
+                        }
+                        signal_queue.push(kept.signal, now_ms);
+                    }
+                    let execution_started = Instant::now();
+                    while let Some(signal) = signal_queue.pop_live(SignalQueue::now_ms()) {
+                        engine_heartbeat.beat();
+                        let sig_msg = format!("   Signal on Market {}: Spread {:.2}%, Edge ${:.2} (staleness haircut ${:.3})",
+                            signal.market_id, signal.spread * 100.0, signal.edge, edge_haircut);
+                        println!("{}", sig_msg);
+                        push_log(&sig_msg);
+                        if let Some(market) = markets.iter().find(|m| m.id == signal.market_id) {
+                            // Trade prints: flow imbalance + adverse-move estimate for this market
+                            match market_client.get_trades(market).await {
+                                Ok(prints) => {
+                                    trade_flow.ingest_all(prints);
+                                    let window_start = current_time.saturating_sub(300);
+                                    for token_id in &market.clob_token_ids {
+                                        if let Some(imbalance) = trade_flow.imbalance(token_id, window_start) {
+                                            println!("   🧾 [Flow] {}: imbalance {:+.2}, adverse σ {}",
+                                                &token_id[..8.min(token_id.len())], imbalance,
+                                                trade_flow.adverse_move_std(token_id, window_start)
+                                                    .map_or("n/a".to_string(), |s| format!("{:.4}", s)));
+                                        }
+                                    }
+                                }
+                                Err(e) => println!("   ⚠️ [Flow] Trade fetch failed: {}", e),
+                            }
+                            if signal.recommended_side == Side::Buy {
+                                let regime = regimes.classify(&market.id);
+                                // Compliance first: jurisdiction rules at the unscaled bundle size
+                                let intended = sizer.unscaled(regime.params(&config.strategy).size_multiplier) * 2.0;
+                                let market_notional = position_manager.read().await.market_notional(&market.id);
+                                let decision = compliance.evaluate(market, attribution::categorize(market), market_notional, intended, current_time);
+                                if !decision.checks.is_empty() {
+                                    let level = if decision.allowed() { EventLevel::Debug } else { EventLevel::Warn };
+                                    log_event(level, "compliance", Some(&market.id),
+                                        &format!("⚖️ [Compliance] {}: {}", market.id, decision.trace()));
+                                }
+                                if let Some(violation) = decision.violations().first() {
+                                    let warn_msg = format!("   ⚖️ [Compliance] Not trading {}: rule {} - {}", market.id, violation.rule_id, violation.detail);
+                                    println!("{}", warn_msg);
+                                    push_log(&warn_msg);
+                                    continue;
+                                }
+                                let throttle = vol_response.throttle(risk.volatility(), config.risk.volatility_threshold, regime);
+                                if throttle.elevated {
+                                    println!("   🌡️ [Volatility] Elevated - sizing at {:.0}%", throttle.size_multiplier * 100.0);
+                                }
+                                let Some(mut size_per_leg) = sizer.size(regime.params(&config.strategy).size_multiplier * throttle.size_multiplier, &risk) else {
+                                    let warn_msg = match risk.should_halt() {
+                                        (true, Some(reason)) => format!("   🛑 [Risk] Trading halted: {}", reason),
+                                        _ => format!("   📉 [Risk] Size scaled below minimum (scale {:.2})", risk.size_scale()),
+                                    };
+                                    println!("{}", warn_msg);
+                                    push_log(&warn_msg);
+                                    continue;
+                                };
+                                // External signals never trade more than their size hint, netted signals more than the net
+                                if let Some(cap) = size_caps.remove(&market.id) {
+                                    size_per_leg = size_per_leg.min(cap);
+                                }
+                                // Committed capital is not available: fit the bundle to what is free
+                                if working_capital.enabled() {
+                                    let committed = working_capital::Commitments {
+                                        open_positions: position_manager.read().await.open_notional(),
+                                        resting_orders: working_capital::resting_reserve(&open_orders.lock().unwrap().open_orders()),
+                                        pending_settlements: sweeper.pending().map_or(0.0, |t| t.amount),
+                                        treasury: treasury.deposited(),
+                                    };
+                                    let capital = working_capital.free(risk.get_status().current_balance, committed, current_time);
+                                    let Some(fitted) = sizer.fit(size_per_leg, market.clob_token_ids.len().max(2), capital.tradable()) else {
+                                        let warn_msg = format!("   💰 [Capital] ${:.2} free of ${:.2} (${:.2} committed, ${:.2} in flight) - too little for {}",
+                                            capital.tradable(), capital.balance, capital.committed.total(), capital.in_flight, market.id);
+                                        println!("{}", warn_msg);
+                                        push_log(&warn_msg);
+                                        continue;
+                                    };
+                                    if fitted < size_per_leg {
+                                        println!("   💰 [Capital] Sizing {:.2} per leg to fit ${:.2} free", fitted, capital.tradable());
+                                    }
+                                    size_per_leg = fitted;
+                                }
+                                let remaining = metamask.get_remaining_allowance().await;
+                                let mut required = size_per_leg * 2.0;
+                                // The last bundle of the period: downsize it to the allowance left, priced on the signal's quotes
+                                let allowance_set_cost = |yes: f64, no: f64| (yes + no) * (1.0 + fee_tiers.taker_rate(market)) * (1.0 + config.allowance_fit.headroom);
+                                let fitted = match config.allowance_fit.enabled && remaining < required {
+                                    true => sizer.fit_allowance(size_per_leg, remaining, |_| Some(allowance_set_cost(signal.yes_price, signal.no_price))),
+                                    false => None,
+                                };
+                                if let Some(fitted) = fitted {
+                                    println!("   🪙 [Allowance] Sizing {:.2} instead of {:.2} per leg to fit the ${:.2} left", fitted, size_per_leg, remaining);
+                                    size_per_leg = fitted;
+                                    required = remaining;
+                                } else if remaining < required {
+                                    let warn_msg = format!("   ⚠️ Insufficient permission allowance (${:.2} < ${:.2})", remaining, required);
+                                    println!("{}", warn_msg);
+                                    push_log(&warn_msg);
+                                    continue;
+                                }
+                                if let Some(anomaly) = behavior.check(current_time, remaining) {
+                                    let msg = format!("🛑 [Anomaly] {} - entering safe mode for {}s", anomaly, config.safety.safe_mode_cooldown_secs);
+                                    println!("{}", msg.red());
+                                    log_event(EventLevel::Error, "anomaly", Some(&market.id), &msg);
+                                    execution_engine.cancel_all_orders("anomaly safe mode");
+                                    plugins.handle_error(&msg).await;
+                                    safe_mode_until = Some(current_time + config.safety.safe_mode_cooldown_secs);
+                                    break;
+                                }
+                                let exec_msg = "   Attempting to execute arb strategy...";
+                                println!("{}", exec_msg);
+                                push_log(exec_msg);
+                                if let Err(e) = trading_windows.check(current_time, &market.id, attribution::categorize(market)) {
+                                    let warn_msg = format!("   ⏸️ Not trading {}: {}", market.id, e);
+                                    println!("{}", warn_msg);
+                                    push_log(&warn_msg);
+                                    continue;
+                                }
+                                let open_bundles = position_manager.read().await.open_bundle_count();
+                                if let Err(e) = entry_throttle.check(current_time, open_bundles) {
+                                    let warn_msg = format!("   ⏸️ Entry throttled: {}", e);
+                                    println!("{}", warn_msg);
+                                    push_log(&warn_msg);
+                                    continue;
+                                }
+                                if let Err(e) = pacer.check(required, current_time, remaining, metamask.next_reset_at().await) {
+                                    let warn_msg = format!("   ⏳ [Pacing] {}", e);
+                                    println!("{}", warn_msg);
+                                    push_log(&warn_msg);
+                                    continue;
+                                }
+                                if let Err(e) = strategies.lock().unwrap().check(ARB_STRATEGY, required) {
+                                    let warn_msg = format!("   ⚠️ Trade refused: {}", e);
+                                    println!("{}", warn_msg);
+                                    push_log(&warn_msg);
+                                    continue;
+                                }
+                                if let Err(e) = portfolio.lock().unwrap().check_entry(collateral.to_usd(required)) {
+                                    let warn_msg = format!("   🌐 [Portfolio] Trade refused: {}", e);
+                                    println!("{}", warn_msg);
+                                    push_log(&warn_msg);
+                                    continue;
+                                }
+                                if approval_queue.lock().unwrap().requires_approval(required) && !approved_markets.remove(&market.id) {
+                                    let requested = approval_queue.lock().unwrap().request(&signal, required, current_time);
+                                    if let Some(approval) = requested {
+                                        let msg = format!("   🙋 [Approval] ${:.2} bundle on {} awaits approval ({}, expires in {}s)",
+                                            required, market.id, approval.approval_id, config.approval.ttl_secs);
+                                        println!("{}", msg);
+                                        log_event(EventLevel::Info, "approval", Some(&market.id), &msg);
+                                        plugins.notify_approval_requested(&approval).await;
+                                    }
+                                    continue;
+                                }
+                                // Treasury: withdraw just in time if the liquid balance falls short
+                                let liquid = risk.get_status().current_balance - position_manager.read().await.open_notional() - treasury.deposited();
+                                if let Some(withdrawal) = treasury.plan_withdrawal(required, liquid, current_time) {
+                                    let call = treasury.call(&withdrawal);
+                                    let outcome = metamask.authorize_call(&call).await.map_err(|e| e.to_string());
+                                    let withdrawal = treasury.settle(withdrawal, outcome);
+                                    let msg = format!("🏛️ [Treasury] {:?} ${:.2} from {}: {:?} (${:.2} deposited)",
+                                        withdrawal.kind, withdrawal.amount, withdrawal.venue, withdrawal.status, treasury.deposited());
+                                    println!("{}", msg);
+                                    log_event(EventLevel::Info, "treasury", Some(&market.id), &msg);
+                                    if withdrawal.status != treasury::MoveStatus::Settled {
+                                        let warn_msg = format!("   ⚠️ Trade refused: treasury withdrawal of ${:.2} failed", withdrawal.amount);
+                                        println!("{}", warn_msg);
+                                        push_log(&warn_msg);
+                                        continue;
+                                    }
+                                }
+                                // Re-validate against fresh books right before execution
+                                let mut books = Vec::new();
+                                for token_id in &market.clob_token_ids {
+                                    let started = std::time::Instant::now();
+                                    match market_client.get_order_book(token_id).await {
+                                        Ok(book) => {
+                                            router.lock().unwrap().record_latency(primary_venue, started.elapsed().as_secs_f64() * 1000.0, current_time);
+                                            if let Some(rec) = &mut book_recorder {
+                                                if let Err(e) = rec.record(&book) {
+                                                    println!("   ⚠️ [Recorder] {}", e);
+                                                }
+                                            }
+                                            spread_tracker.observe_book(&market.id, &book, current_time);
+                                            books.push(book);
+                                        }
+                                        Err(e) => {
+                                            println!("   ⚠️ Order book fetch failed: {}", e);
+                                            break;
+                                        }
+                                    }
+                                }
+                                if books.len() != market.clob_token_ids.len() {
+                                    continue;
+                                }
+                                // Edge as a function of size: trade the most profitable size up to the risk-scaled one
+                                let signal = match config.edge_curve.enabled {
+                                    true => detector.with_edge_curve(&signal, &books, fee_tiers.taker_rate(market).max(calibrated_taker_rate),
+                                        size_per_leg, config.edge_curve.steps),
+                                    false => signal,
+                                };
+                                if let Some(best) = sizer.best_on_curve(&signal.edge_curve, size_per_leg) {
+                                    if best + 1e-9 < size_per_leg {
+                                        let point = signal.edge_curve.iter().find(|p| p.size == best).copied();
+                                        let msg = format!("   📐 [EdgeCurve] Sizing {:.2} instead of {:.2} per leg (edge ${:.4}, profit ${:.4})",
+                                            best, size_per_leg, point.map_or(0.0, |p| p.edge), point.map_or(0.0, |p| p.profit));
+                                        println!("{}", msg);
+                                        log_event(EventLevel::Info, "sizing", Some(&market.id), &msg);
+                                        size_per_leg = best;
+                                    }
+                                }
+                                let signal_edge = signal.edge;
+                                // Older books need more edge: the oldest quote sets the haircut
+                                let signal = detector.with_quote_ages(&signal, &books, SignalQueue::now_ms(), freshness.staleness_ms());
+                                let quote_haircut = freshness.quote_haircut(primary_venue, &signal.quote_ages_ms) + cache_haircut;
+                                let signal = match signal_queue.revalidate(&signal, &books, size_per_leg, quote_haircut) {
+                                    Some(fresh) => fresh,
+                                    None => {
+                                        let warn_msg = format!("   ⚠️ Signal on {} decayed below threshold (quotes up to {}ms old, haircut ${:.3}), dropped",
+                                            signal.market_id, signal.quote_ages_ms.iter().max().copied().unwrap_or(0), quote_haircut);
+                                        println!("{}", warn_msg);
+                                        push_log(&warn_msg);
+                                        continue;
+                                    }
+                                };
+                                // Worst case if only some legs fill: downsize or skip past the loss cap
+                                if leg_risk.enabled() {
+                                    match leg_risk.check(&books, size_per_leg, fee_tiers.taker_rate(market).max(calibrated_taker_rate)) {
+                                        Some(leg_risk::Verdict::Within(_)) => {}
+                                        Some(leg_risk::Verdict::Downsize { from, risk }) => {
+                                            let msg = format!("   🦵 [LegRisk] Sizing {:.2} instead of {:.2} per leg (worst partial fill: legs {:?} filled, {:?} loses ${:.3})",
+                                                risk.size, from, risk.filled, risk.remedy, risk.worst_loss);
+                                            println!("{}", msg);
+                                            log_event(EventLevel::Info, "leg_risk", Some(&market.id), &msg);
+                                            size_per_leg = risk.size;
+                                        }
+                                        Some(leg_risk::Verdict::Skip(risk)) => {
+                                            let msg = format!("   🦵 [LegRisk] Skipping {}: worst partial fill loses ${:.3} (cap ${:.2}) even at the minimum size",
+                                                market.id, risk.worst_loss, config.leg_risk.max_loss_usd);
+                                            println!("{}", msg);
+                                            log_event(EventLevel::Warn, "leg_risk", Some(&market.id), &msg);
+                                            continue;
+                                        }
+                                        None => continue,
+                                    }
+                                }
+                                // Re-fit a downsized bundle on the fresh books, every leg at the same size
+                                if fitted.is_some() {
+                                    let taker_rate = fee_tiers.taker_rate(market);
+                                    let set_cost = |size: f64| books.iter()
+                                        .map(|b| b.execution_price(size, Side::Buy))
+                                        .sum::<Option<f64>>()
+                                        .map(|cost| cost * (1.0 + taker_rate) * (1.0 + config.allowance_fit.headroom));
+                                    match sizer.fit_allowance(size_per_leg, remaining, set_cost) {
+                                        Some(refit) => size_per_leg = refit,
+                                        None => {
+                                            let warn_msg = format!("   ⚠️ Insufficient permission allowance: ${:.2} left buys less than the minimum of {}", remaining, market.id);
+                                            println!("{}", warn_msg);
+                                            push_log(&warn_msg);
+                                            continue;
+                                        }
+                                    }
+                                    let msg = format!("   🪙 [Allowance] Last bundle of the period: {:.2} per leg for the ${:.2} left", size_per_leg, remaining);
+                                    log_event(EventLevel::Info, "sizing", Some(&market.id), &msg);
+                                }
+                                // Each leg's call carries the USDC it spends at these books, fees included
+                                let taker_rate = fee_tiers.taker_rate(market);
+                                let leg_calls: Option<Vec<_>> = books.iter()
+                                    .map(|b| b.execution_price(size_per_leg, Side::Buy)
+                                        .map(|price| execution_engine.trade_call(price * size_per_leg * (1.0 + taker_rate))))
+                                    .collect();
+                                let Some(leg_calls) = leg_calls else {
+                                    let warn_msg = format!("   ⚠️ Books on {} cannot fill {:.2} per leg, not submitting", market.id, size_per_leg);
+                                    println!("{}", warn_msg);
+                                    push_log(&warn_msg);
+                                    continue;
+                                };
+                                // The scope caps dollars: every leg's priced call must fall within it
+                                let mut refused = None;
+                                for call in &leg_calls {
+                                    if let Err(e) = metamask.authorize_call(call).await {
+                                        refused = Some(remediation::ExecutionFailure::from(e));
+                                        break;
+                                    }
+                                }
+                                // Simulate every leg, then execute: a revert here costs nothing
+                                if let (None, Some(sim)) = (&refused, &preflight) {
+                                    for call in &leg_calls {
+                                        match sim.simulate(call).await {
+                                            Ok(()) => {}
+                                            Err(preflight::SimulationError::Rpc(e)) if config.preflight.fail_open => {
+                                                println!("   ⚠️ [Preflight] Simulation unavailable ({}), trading anyway", e);
+                                                break;
+                                            }
+                                            Err(e) => {
+                                                let failure = remediation::ExecutionFailure::from(e);
+                                                log_event(EventLevel::Warn, "preflight", Some(&market.id), &format!("   ⚠️ Trade refused: {}", failure));
+                                                refused = Some(failure);
+                                                break;
+                                            }
+                                        }
+                                    }
+                                }
+                                if let Some(failure) = refused {
+                                    let warn_msg = format!("   ⚠️ Trade refused: {}", failure);
+                                    println!("{}", warn_msg);
+                                    push_log(&warn_msg);
+                                    // Nothing has filled yet: of the remediations only a halt applies
+                                    if remediator.decide(&failure, 0) == remediation::Remediation::Halt {
+                                        let msg = format!("🛑 [Remediation] {} - entering safe mode for {}s", failure, config.safety.safe_mode_cooldown_secs);
+                                        println!("{}", msg.red());
+                                        log_event(EventLevel::Error, "remediation", Some(&market.id), &msg);
+                                        execution_engine.cancel_all_orders("remediation halt");
+                                        safe_mode_until = Some(current_time + config.safety.safe_mode_cooldown_secs);
+                                        break;
+                                    }
+                                    continue;
+                                }
+                                // Every leg goes out with a limit price the set's economics can afford
+                                let ceilings = slippage_guard::LimitCeilings::for_bundle(&books, size_per_leg, fee_tiers.taker_rate(market).max(calibrated_taker_rate),
+                                    config.trading.gas_per_leg_usd, config.slippage_guard.min_edge);
+                                let Some(mut ceilings) = ceilings else {
+                                    let warn_msg = format!("   ⚠️ No limit price on {} keeps ${:.3} of edge per set at {:.2} per leg, not submitting",
+                                        market.id, config.slippage_guard.min_edge, size_per_leg);
+                                    println!("{}", warn_msg);
+                                    push_log(&warn_msg);
+                                    continue;
+                                };
+                                // One order per leg, reserved up front so a bundle is never cut short by the venue's rate limit
+                                let legs = vec![order_limits::OrderAction::Place; market.clob_token_ids.len()];
+                                let reserved = order_limits.lock().unwrap().acquire(&market.id, &legs, now_ms);
+                                if let Err(wait_ms) = reserved {
+                                    let msg = format!("   🚦 [OrderLimits] Skipping {}: no headroom for {} orders for {}ms", market.id, legs.len(), wait_ms);
+                                    println!("{}", msg);
+                                    log_event(EventLevel::Warn, "order_limits", Some(&market.id), &msg);
+                                    continue;
+                                }
+                                entry_throttle.record_entry(current_time);
+                                treasury.touch(current_time);
+                                behavior.record_entry(current_time);
+                                let counterpart = router.lock().unwrap().counterpart(&market.id)
+                                    .and_then(|(venue, id)| alt_markets.iter().find(|m| m.id == id).map(|m| (venue, m)));
+                                // Intent mode: each leg is proposed for an external signer instead of executed
+                                if config.intents.enabled {
+                                    for (leg, (token_id, book)) in market.clob_token_ids.iter().zip(&books).enumerate() {
+                                        let Some(price) = book.execution_price(size_per_leg, Side::Buy) else { continue };
+                                        let proposed = intent_book.lock().unwrap().propose(&market.id, token_id, Side::Buy, price, size_per_leg, current_time);
+                                        let mut intent = match proposed {
+                                            Ok(intent) => intent,
+                                            Err(e) => {
+                                                println!("   ⚠️ [Intents] {} leg {}: {}", market.id, leg, e);
+                                                continue;
+                                            }
+                                        };
+                                        if let Some(signer) = &intent_signer {
+                                            if let Err(e) = intents::sign(&mut intent, signer.as_ref()).await {
+                                                println!("   ⚠️ [Intents] Signing {} failed, published unsigned: {}", intent.intent_id, e);
+                                            }
+                                        }
+                                        let msg = format!("📝 [Intents] {} leg {}: buy {:.2} @ <= ${:.4} until {} ({})",
+                                            market.id, leg, intent.size, intent.price_cap, intent.expires_at, intent.intent_id);
+                                        println!("   {}", msg);
+                                        log_event(EventLevel::Info, "intents", Some(&market.id), &msg);
+                                        if let Some(url) = config.intents.webhook_url.clone() {
+                                            let payload = intent.clone();
+                                            let http = status_http.clone();
+                                            tokio::spawn(async move {
+                                                if let Err(e) = http.post(&url).json(&payload).send().await {
+                                                    eprintln!("⚠️ [Intents] Webhook for {} failed: {}", payload.intent_id, e);
+                                                }
+                                            });
+                                        }
+                                        working_capital.reserve_until(&intent.intent_id, intent.price_cap * intent.size, intent.expires_at);
+                                        intent_book.lock().unwrap().publish(intent);
+                                    }
+                                    continue;
+                                }
+                                let mut legs_filled = 0usize;
+                                // Legs of this bundle filled so far, and why the bundle was abandoned (if it was)
+                                let mut bundle_legs: Vec<String> = Vec::new();
+                                let mut bundle_failure = None;
+                                working_capital.reserve(&market.id, size_per_leg * market.clob_token_ids.len() as f64, current_time);
+                                for (leg, (token_id, book)) in market.clob_token_ids.iter().zip(&books).enumerate() {
+                                    // A tick can outlast the lease: stop if a standby has taken over since it began
+                                    if let (Some(leases), Some((key, epoch))) = (&leases, &lease_epoch) {
+                                        if !leases.fence(key, *epoch, Wallet::current_timestamp()).unwrap_or(false) {
+                                            let msg = format!("⏸️ [Lease] Lost lease on {} (epoch {}) - stopping {} before leg {}", key, epoch, market.id, leg);
+                                            println!("{}", msg);
+                                            log_event(EventLevel::Warn, "lease", Some(&market.id), &msg);
+                                            is_leader = false;
+                                            break;
+                                        }
+                                    }
+                                    // Best execution: the leg goes to whichever venue prices the size better
+                                    let mut book = book.clone();
+                                    let mut routed_to = None;
+                                    if let (Some((venue, alt)), Some(client)) = (&counterpart, &alt_client) {
+                                        let alt_book = match routing::leg_token(market, alt, leg) {
+                                            Some(alt_token) => {
+                                                let started = std::time::Instant::now();
+                                                let alt_book = client.get_order_book(&alt_token).await.ok();
+                                                if alt_book.is_some() {
+                                                    router.lock().unwrap().record_latency(venue, started.elapsed().as_secs_f64() * 1000.0, current_time);
+                                                }
+                                                alt_book
+                                            }
+                                            None => None,
+                                        };
+                                        if let Some(alt_book) = alt_book {
+                                            let venue_books = [
+                                                routing::VenueBook { venue: primary_venue.to_string(), book: book.clone() },
+                                                routing::VenueBook { venue: venue.clone(), book: alt_book },
+                                            ];
+                                            let decision = router.lock().unwrap().route(&market.id, &venue_books, size_per_leg, Side::Buy, legs_filled > 0, current_time);
+                                            log_event(EventLevel::Info, "routing", Some(&market.id),
+                                                &format!("🧭 [Routing] {} leg {} → {} ({})", market.id, leg, decision.chosen_venue, decision.reason));
+                                            portfolio.lock().unwrap().assign(token_id, &decision.chosen_venue);
+                                            if decision.chosen_index() == 1 {
+                                                let [_, chosen] = venue_books;
+                                                book = chosen.book;
+                                                routed_to = Some(client);
+                                            }
+                                        }
+                                    }
+                                    let book = &book;
+                                    let leg_client = routed_to.map_or(market_client.as_ref(), |c| c.as_ref());
+                                    let arrival_mid = book.midpoint().unwrap_or(0.0);
+                                    let predicted_price = book.execution_price(size_per_leg, Side::Buy).unwrap_or(0.0);
+                                    let predicted = model_ledger::Costs {
+                                        fill_price: predicted_price,
+                                        fee: fee_model.calculate(predicted_price * size_per_leg, false),
+                                        slippage: SlippageModel::calculate(book, size_per_leg, Side::Buy).unwrap_or(0.0),
+                                        edge: Some(signal.edge / market.clob_token_ids.len().max(1) as f64),
+                                    };
+                                    // Cross or post: posted legs rest and fill from trade prints like resting remainders
+                                    let leg_id = format!("{}-{}-{}", market.id, leg, current_time);
+                                    if maker_taker.enabled() {
+                                        let lookback = config.maker_taker.flow_lookback_secs.max(1);
+                                        let leg_context = maker_taker::LegContext {
+                                            side: Side::Buy,
+                                            size: size_per_leg,
+                                            edge: signal.edge / market.clob_token_ids.len().max(1) as f64,
+                                            urgency: legs_filled as f64 / (market.clob_token_ids.len().max(2) - 1) as f64,
+                                            flow_per_sec: trade_flow.volume(token_id, Side::Sell, current_time.saturating_sub(lookback)) / lookback as f64,
+                                        };
+                                        let decision = maker_taker.decide(book, leg_context, &fee_model);
+                                        log_event(EventLevel::Info, "maker_taker", Some(&market.id),
+                                            &format!("🎯 [MakerTaker] {} leg {} → {:?} ({})", market.id, leg, decision.path, decision.reason));
+                                        if let (maker_taker::LegPath::Maker, Some(price)) = (decision.path, decision.maker_price) {
+                                            let order = PassiveOrder::place(book, Side::Buy, price, size_per_leg);
+                                            if let Some(orders) = sandbox_orders.as_ref().filter(|_| routed_to.is_none()) {
+                                                if let Err(e) = orders.post_limit(token_id, Side::Buy, price, size_per_leg, None).await {
+                                                    println!("   ⚠️ [Sandbox] Posted leg failed: {}", e);
+                                                }
+                                            }
+                                            let order_id = open_orders.lock().unwrap().place(order, current_time);
+                                            quoting.lock().unwrap().record_placed(&market.id);
+                                            maker_taker.record_decision(&order_id, &market.id, token_id, &leg_context, &decision, current_time);
+                                            println!("   🪤 Posted leg {} {:.2} @ ${:.4}", leg, size_per_leg, price);
+                                            continue;
+                                        }
+                                        maker_taker.record_decision(&leg_id, &market.id, token_id, &leg_context, &decision, current_time);
+                                    }
+                                    // Charged to the active grant while it is held, so a revoke, a smaller
+                                    // replacement grant or a period reset between legs bounds the next leg
+                                    let spend = audit::SpendContext { market_id: market.id.clone(), token_id: token_id.clone(), tx_hash: None };
+                                    // A failed leg is remediated by class: retried, repriced on a fresh book, or it ends the bundle
+                                    let mut repriced = None;
+                                    let mut attempt = 0;
+                                    let ceiling = ceilings.ceiling(leg);
+                                    let filled = loop {
+                                        let leg_book = repriced.as_ref().unwrap_or(book);
+                                        let mut failure = None;
+                                        let spent = metamask.spend_with(spend.clone(), |remaining| {
+                                            match execution_engine.execute_capped(leg_book, size_per_leg, Side::Buy, &mut wallet, Some(ceiling), remaining) {
+                                                Ok(result) => { let cost = result.total_cost; Some((result, cost)) }
+                                                Err(e) => { failure = Some(e); None }
+                                            }
+                                        }).await;
+                                        let failure = match spent {
+                                            Ok(Some(result)) => break Some(result),
+                                            Ok(None) => failure.expect("execution failed without a failure"),
+                                            Err(e) => remediation::ExecutionFailure::from(e),
+                                        };
+                                        let remedy = remediator.decide(&failure, attempt);
+                                        println!("   ⚠️ Leg {} failed: {} → {:?}", leg, failure, remedy);
+                                        let backoff = remediator.backoff(attempt);
+                                        attempt += 1;
+                                        match remedy {
+                                            remediation::Remediation::Retry => {
+                                                tokio::time::sleep(backoff).await;
+                                                continue;
+                                            }
+                                            remediation::Remediation::Reprice => match leg_client.get_order_book(token_id).await {
+                                                Ok(fresh) => repriced = Some(fresh),
+                                                Err(e) => {
+                                                    println!("   ⚠️ Leg {} reprice failed: {}", leg, e);
+                                                    break None;
+                                                }
+                                            },
+                                            remediation::Remediation::Skip => break None,
+                                            remediation::Remediation::Unwind | remediation::Remediation::Halt => {
+                                                bundle_failure = Some((failure, remedy));
+                                                break None;
+                                            }
+                                        }
+                                    };
+                                    let book = repriced.as_ref().unwrap_or(book);
+                                    if let Some(mut result) = filled {
+                                        // A fill is a position before anything else is awaited, so a stall cancelled
+                                        // by the supervisor after this point cannot lose it
+                                        position_manager.write().await.open_position(Position {
+                                            market_id: market.id.clone(),
+                                            token_id: token_id.clone(),
+                                            side: Side::Buy,
+                                            size: result.filed_size,
+                                            entry_price: result.execution_price,
+                                            entry_time: current_time,
+                                            entry_spread: signal.spread,
+                                            strategy: ARB_STRATEGY.to_string(),
+                                        });
+                                        match execution_engine.decide_remainder(&result, book, Side::Buy) {
+                                            RemainderDecision::Chase { remaining, limit_price } => {
+                                                let limit_price = limit_price.min(ceiling);
+                                                // The chase is one more order, sent only with headroom for it
+                                                let headroom = order_limits.lock().unwrap().acquire(&market.id, &[order_limits::OrderAction::Place], now_ms);
+                                                let fresh = match headroom {
+                                                    Ok(()) => leg_client.get_order_book(&book.token_id).await.ok(),
+                                                    Err(_) => None,
+                                                };
+                                                if let Some(fresh) = fresh {
+                                                    let extra = metamask.spend_with(spend, |allowance| {
+                                                        execution_engine.execute_capped(&fresh, remaining, Side::Buy, &mut wallet, Some(limit_price), allowance)
+                                                            .ok().map(|extra| { let cost = extra.total_cost; (extra, cost) })
+                                                    }).await;
+                                                    if let Ok(Some(extra)) = extra {
+                                                        position_manager.write().await.add_fill(token_id, extra.filed_size, extra.execution_price);
+                                                        result.absorb(&extra);
+                                                    }
+                                                }
+                                                if result.remaining_size > 0.0 {
+                                                    println!("   ↳ Chase left {:.2} unfilled (limit ${:.4})", result.remaining_size, limit_price);
+                                                }
+                                            }
+                                            RemainderDecision::Rest(order) => {
+                                                // A resting order is one more placement; without headroom the remainder is let go
+                                                let acquired = order_limits.lock().unwrap().acquire(&market.id, &[order_limits::OrderAction::Place], now_ms);
+                                                if let Err(wait_ms) = acquired {
+                                                    println!("   ↳ Abandoned unfilled remainder {:.2}: no order headroom for {}ms", order.size, wait_ms);
+                                                } else {
+                                                    println!("   ↳ Resting {:.2} @ ${:.4}", order.size, order.price);
+                                                    if let Some(orders) = sandbox_orders.as_ref().filter(|_| routed_to.is_none()) {
+                                                        let expires_at = (config.trading.max_rest_secs > 0).then(|| current_time + config.trading.max_rest_secs);
+                                                        match orders.post_limit(token_id, Side::Buy, order.price, order.size, expires_at).await {
+                                                            Ok(posted) => {
+                                                                let submitted = reconcile::SubmittedOrder {
+                                                                    order_id: posted.order_id,
+                                                                    market_id: market.id.clone(),
+                                                                    token_id: token_id.clone(),
+                                                                    side: Side::Buy,
+                                                                    price: order.price,
+                                                                    size: order.size,
+                                                                    expected_fill: 0.0,
+                                                                    expires_at,
+                                                                    timestamp: current_time,
+                                                                };
+                                                                finality.lock().unwrap().track_order(&submitted, &posted.tx_hashes, orders.profile().chain_id, current_time);
+                                                                venue_orders.lock().unwrap().record(submitted);
+                                                            }
+                                                            Err(e) => println!("   ⚠️ [Sandbox] Resting order failed: {}", e),
+                                                        }
+                                                    }
+                                                    open_orders.lock().unwrap().place(order, current_time);
+                                                }
+                                            }
+                                            RemainderDecision::Abandon { remaining } => {
+                                                println!("   ↳ Abandoned unfilled remainder {:.2}", remaining);
+                                            }
+                                            RemainderDecision::Complete => {}
+                                        }
+                                        if let Some(orders) = sandbox_orders.as_ref().filter(|_| routed_to.is_none()) {
+                                            match orders.post_limit(token_id, Side::Buy, result.execution_price, result.filed_size, None).await {
+                                                Ok(posted) => {
+                                                    println!("   🧪 [Sandbox] Signed order {} accepted", posted.order_id);
+                                                    let submitted = reconcile::SubmittedOrder {
+                                                        order_id: posted.order_id,
+                                                        market_id: market.id.clone(),
+                                                        token_id: token_id.clone(),
+                                                        side: Side::Buy,
+                                                        price: result.execution_price,
+                                                        size: result.filed_size,
+                                                        expected_fill: result.filed_size,
+                                                        expires_at: None,
+                                                        timestamp: current_time,
+                                                    };
+                                                    // The fill is provisional until its settlement is final
+                                                    finality.lock().unwrap().track_order(&submitted, &posted.tx_hashes, orders.profile().chain_id, current_time);
+                                                    venue_orders.lock().unwrap().record(submitted);
+                                                }
+                                                Err(e) => println!("   ⚠️ [Sandbox] Signed order failed: {}", e),
+                                            }
+                                        }
+                                        ceilings.record_fill(leg, result.execution_price);
+                                        legs_filled += 1;
+                                        bundle_legs.push(token_id.clone());
+                                        maker_taker.record_fill(&leg_id, result.filed_size, result.execution_price, current_time);
+                                        pacer.record(result.total_cost, current_time);
+                                        fee_tiers.record(result.execution_price * result.filed_size, false, current_time);
+                                        model_ledger.record_fill(model_ledger::ModelRecord {
+                                            market_id: market.id.clone(),
+                                            token_id: token_id.clone(),
+                                            size: result.filed_size,
+                                            timestamp: current_time,
+                                            // Scaled to the size that actually filled
+                                            predicted: model_ledger::Costs { fee: predicted.fee * result.filed_size / size_per_leg, ..predicted },
+                                            actual: model_ledger::Costs {
+                                                fill_price: result.execution_price,
+                                                fee: result.fee_paid,
+                                                slippage: result.slippage,
+                                                edge: None,
+                                            },
+                                        });
+                                        if let Some(log) = &mut decision_log {
+                                            log.mark_traded(&market.id, current_time);
+                                        }
+                                        behavior.record_spend(current_time, result.total_cost);
+                                        behavior.record_fill(market.outcome_prices.get(leg).copied().unwrap_or(0.0), result.execution_price);
+                                        tca.record_fill(FillRecord {
+                                            market_id: market.id.clone(),
+                                            token_id: token_id.clone(),
+                                            side: Side::Buy,
+                                            size: result.filed_size,
+                                            fill_price: result.execution_price,
+                                            signal_price: market.outcome_prices.get(leg).copied().unwrap_or(0.0),
+                                            arrival_mid,
+                                            fill_time_ms: now_ms,
+                                            markout_mids: [None; 3],
+                                        });
+                                        let events = annotations.lock().unwrap().tags_at(current_time, &market.id, attribution::categorize(market));
+                                        if !events.is_empty() {
+                                            log_event(EventLevel::Debug, "annotations", Some(&market.id),
+                                                &format!("🏷️ [Annotations] {} leg {} entered during {}", market.id, leg, events.join(", ")));
+                                        }
+                                        attribution.tag_entry(token_id, TradeTag {
+                                            market_id: market.id.clone(),
+                                            category: attribution::categorize(market).to_string(),
+                                            strategy: ARB_STRATEGY.to_string(),
+                                            predicted_edge: signal.edge / market.clob_token_ids.len().max(1) as f64,
+                                            events,
+                                        });
+                                        if let Some(ledger) = &mut tax_ledger {
+                                            ledger.acquire(&market.id, token_id, result.filed_size, result.total_cost, current_time);
+                                        }
+                                        resolutions.lock().unwrap().record_entry(market, token_id, result.filed_size, result.total_cost, current_time);
+                                        let legs = market.clob_token_ids.len().max(1) as f64;
+                                        capture_tracker.lock().unwrap().record_entry(capture::CaptureEntry {
+                                            token_id: token_id.clone(),
+                                            market_id: market.id.clone(),
+                                            strategy: ARB_STRATEGY.to_string(),
+                                            signal_edge: signal_edge * result.filed_size / legs,
+                                            execution_edge: signal.edge * result.filed_size / legs,
+                                            at: current_time,
+                                        });
+                                        utilization.write().await.bundle_opened(&market.id, current_time);
+                                        strategies.lock().unwrap().allocate(ARB_STRATEGY, result.filed_size * result.execution_price);
+                                    }
+                                }
+                                // Filled legs count as open positions from here on
+                                working_capital.release(&market.id);
+                                if let Some((failure, remedy)) = bundle_failure {
+                                    // Sell back the legs already filled rather than hold half a bundle
                                     let mut pm = position_manager.write().await;
-                                    pm.open_position(Position {
-                                        market_id: market.id.clone(),
-                                        token_id: token_id.clone(),
-                                        side: Side::Buy,
-                                        size: result.filed_size,
-                                        entry_price: result.execution_price,
-                                        entry_time: current_time,
-                                        entry_spread: signal.spread,
-                                    });
+                                    for token_id in &bundle_legs {
+                                        let Some(size) = pm.get_position(token_id).map(|p| p.size) else { continue };
+                                        let bid = books.iter().find(|b| b.token_id == *token_id)
+                                            .and_then(|b| b.execution_price(size, Side::Sell).or(b.best_bid()));
+                                        let Some(bid) = bid else {
+                                            println!("   ⚠️ [Remediation] No bid to unwind {} - left open", token_id);
+                                            continue;
+                                        };
+                                        unwound.extend(pm.close_position(token_id, bid, fee_model.taker_rate()));
+                                    }
+                                    drop(pm);
+                                    let msg = format!("↩️ [Remediation] {} on {}: unwound {} filled legs", failure, market.id, bundle_legs.len());
+                                    println!("   {}", msg);
+                                    push_log(&msg);
+                                    log_event(EventLevel::Warn, "remediation", Some(&market.id), &msg);
+                                    if remedy == remediation::Remediation::Halt {
+                                        let msg = format!("🛑 [Remediation] {} - entering safe mode for {}s", failure, config.safety.safe_mode_cooldown_secs);
+                                        println!("{}", msg.red());
+                                        log_event(EventLevel::Error, "remediation", Some(&market.id), &msg);
+                                        execution_engine.cancel_all_orders("remediation halt");
+                                        safe_mode_until = Some(current_time + config.safety.safe_mode_cooldown_secs);
+                                        break;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    perf.record("execution", execution_started.elapsed());
+                }
+
+                perf.record("tick", tick_started.elapsed());
+
+                // Model vs reality: alert when predictions are systematically off
+                let breaches = model_ledger.check(current_time);
+                if !breaches.is_empty() {
+                    let detail = breaches.iter().map(|b| b.to_string()).collect::<Vec<_>>().join("; ");
+                    let msg = format!("📐 [Model] Drift over last {} fills: {}", model_ledger.drift().samples, detail);
+                    println!("{}", msg.yellow());
+                    log_event(EventLevel::Warn, "model", None, &msg);
+                    plugins.handle_error(&msg).await;
+                    if config.model_drift.safe_mode_on_breach && safe_mode_until.is_none() {
+                        println!("🛑 [Model] Entering safe mode for {}s", config.safety.safe_mode_cooldown_secs);
+                        execution_engine.cancel_all_orders("model drift safe mode");
+                        safe_mode_until = Some(current_time + config.safety.safe_mode_cooldown_secs);
+                    }
+                }
+
+                // Fills moved the month's volume into another fee tier
+                if fee_tiers.tier() != fee_tier {
+                    fee_tier = fee_tiers.tier();
+                    fee_model = fee_tiers.fee_model();
+                    execution_engine.fee_model = fee_model.clone();
+                    let msg = format!("💸 [FeeTiers] ${:.2} traded this month - fee tier {} (maker {} bps, taker {} bps)",
+                        fee_tiers.volume(), fee_tier, fee_model.maker_fee_bps, fee_model.taker_fee_bps);
+                    println!("{}", msg);
+                    log_event(EventLevel::Info, "fees", None, &msg);
+                }
+                // Edge math prices taker fills at the p95 cost TCA measures; fees are still booked
+                // and predicted at the tier's rate, which is what the venue charges
+                let calibrated = tca.calibrated_fee_model(fee_model.clone());
+                let rate = match calibrated.taker_fee_bps > fee_model.taker_fee_bps {
+                    true => calibrated.taker_rate(),
+                    false => 0.0,
+                };
+                if rate != calibrated_taker_rate {
+                    calibrated_taker_rate = rate;
+                    if rate > 0.0 {
+                        let msg = format!("💸 [TCA] Calibrated taker cost {} bps (tier fee {} bps)",
+                            calibrated.taker_fee_bps, fee_model.taker_fee_bps);
+                        println!("{}", msg);
+                        log_event(EventLevel::Info, "fees", None, &msg);
+                    }
+                }
+
+                // Daily report for the UTC day that just ended (picked up by the object store sink)
+                if current_time / 86_400 > report_day {
+                    let pm = position_manager.read().await;
+                    let date = chrono::DateTime::from_timestamp((report_day * 86_400) as i64, 0)
+                        .map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default();
+                    let report = serde_json::json!({
+                        "date": date,
+                        "generated_at": current_time,
+                        "trades": pm.trade_count(),
+                        "win_rate": pm.win_rate(),
+                        "pnl": pm.total_pnl(),
+                        "collateral": collateral.symbol(),
+                        "open_positions": pm.get_positions().len(),
+                        "risk": risk.get_status(),
+                        "utilization": utilization.read().await.report(),
+                        "capture": capture_tracker.lock().unwrap().overall(),
+                        "tca": tca.report(),
+                        "fee_tier": fee_tiers.report(current_time),
+                        "leg_paths": maker_taker.report(),
+                        "outcomes": trade_metrics.get_metrics().await,
+                    });
+                    let dir = std::path::Path::new(&config.storage.data_dir).join("reports");
+                    let written = std::fs::create_dir_all(&dir).and_then(|_| {
+                        std::fs::write(dir.join(format!("daily-{}.json", date)), serde_json::to_vec_pretty(&report).unwrap_or_default())
+                    });
+                    match written {
+                        Ok(()) => println!("📰 Daily report for {} written", date),
+                        Err(e) => println!("⚠️ Failed to write daily report: {}", e),
+                    }
+                    trade_metrics.reset_daily().await;
+                    report_day = current_time / 86_400;
+                }
+
+                // Show stats
+                {
+                    let pm = position_manager.read().await;
+                    let stats_msg = format!("📊 Stats: {} trades | Win rate: {:.0}% | PnL: {} | Open: {}",
+                        pm.trade_count(),
+                        pm.win_rate() * 100.0,
+                        collateral.format(pm.total_pnl()),
+                        pm.get_positions().len(),
+                    );
+                    println!("\n{}", stats_msg);
+                    push_log(&stats_msg);
+                    let outcomes = trade_metrics.get_metrics().await;
+                    if outcomes.trades_total > 0 {
+                        let rolling: Vec<String> = outcomes.rolling.iter()
+                            .map(|w| format!("last {}: {:.0}%", w.window, w.win_rate * 100.0))
+                            .collect();
+                        println!("   🎯 {} | streak {:+} (best {}, worst {})",
+                            rolling.join(" | "), outcomes.current_streak, outcomes.longest_win_streak, outcomes.longest_loss_streak);
+                    }
+                    if tca.fill_count() > 0 {
+                        for line in tca.report() {
+                            println!("{}", line);
+                        }
+                    }
+                    let drift = model_ledger.drift();
+                    if drift.samples > 0 {
+                        println!("   📐 Model error ({} fills): price {:+.1}bps | fee {:+.1}bps | slippage {:+.1}bps | edge {:+.4} over {} exits",
+                            drift.samples, drift.price_error_bps, drift.fee_error_bps, drift.slippage_error_bps,
+                            drift.edge_error, drift.edge_samples);
+                    }
+                    let captured = capture_tracker.lock().unwrap().overall();
+                    if let Some(ratio) = captured.capture_ratio {
+                        println!("   🎯 Edge capture: {:.0}% over {} legs (execution {:.0}% × realization {:.0}%)",
+                            ratio * 100.0, captured.trades,
+                            captured.execution_capture.unwrap_or(0.0) * 100.0, captured.realization.unwrap_or(0.0) * 100.0);
+                    }
+                    let res = resolutions.lock().unwrap().report().stats;
+                    if res.resolved > 0 {
+                        println!("   🏁 Resolutions: {}/{} resolved ({} ambiguous) | return {:+.2}% vs theoretical {:+.2}% | surprise ${:+.2} ±{:.2}",
+                            res.resolved, res.tracked, res.ambiguous, res.mean_realized_return * 100.0,
+                            res.mean_theoretical_return * 100.0, res.mean_surprise, res.surprise_std);
+                    }
+                    let q = &signal_queue.stats;
+                    if q.executed + q.expired + q.decayed > 0 {
+                        println!("   🧮 Signals: {} executed | {} expired | {} decayed before execution",
+                            q.executed, q.expired, q.decayed);
+                    }
+                    if let Some(incidents) = &quorum_incidents {
+                        let incidents = incidents.lock().unwrap();
+                        if let Some(last) = incidents.last() {
+                            println!("   🧭 Quorum: {} data-quality incidents (last: {} {})",
+                                incidents.len(), last.token_id, last.field);
+                        }
+                    }
+                    let status = risk.get_status();
+                    println!("   🛡️ Risk: drawdown {:.1}% | volatility {:.1}%{} | size scale {:.2}",
+                        status.drawdown_percent, status.volatility_percent,
+                        if vol_response.throttle(risk.volatility(), config.risk.volatility_threshold, regime::Regime::Calm).elevated { " (elevated - throttling)" } else { "" },
+                        risk.size_scale());
+                    for account in strategies.lock().unwrap().report(current_time) {
+                        println!("   💼 Strategy {}: ${:.2}/${:.2} deployed | PnL ${:.2} over {} trades{}",
+                            account.strategy, account.deployed, account.capital, account.realized_pnl, account.trades,
+                            account.disabled_reason.map(|r| format!(" | DISABLED ({})", r)).unwrap_or_default());
+                    }
+                    let counts = regimes.counts();
+                    println!("   🌡️ Regimes: {} calm | {} trending | {} event-spike",
+                        counts.get(&regime::Regime::Calm).unwrap_or(&0),
+                        counts.get(&regime::Regime::Trending).unwrap_or(&0),
+                        counts.get(&regime::Regime::EventSpike).unwrap_or(&0));
+                    let util = utilization.read().await.report();
+                    println!("   ⚙️ Capital: {:.0}% utilized (avg ${:.2} deployed) | Recycle: {:.0}s avg over {} bundles | Allowance: {:.0}% (peak {:.0}%)",
+                        util.capital_utilization * 100.0, util.avg_deployed,
+                        util.avg_recycle_secs, util.bundles_recycled,
+                        util.allowance_utilization * 100.0, util.peak_allowance_utilization * 100.0);
+                    for row in attribution.report("category") {
+                        println!("   🗂️ {:<10} {} trades | Hit: {:.0}% | PnL: ${:.2} | Fees: ${:.2} | Edge pred/real: ${:.3}/${:.3}",
+                            row.key, row.trades, row.hit_rate() * 100.0, row.pnl, row.fees,
+                            row.avg_predicted_edge(), row.avg_realized_edge());
+                    }
+                    for row in attribution.report("event") {
+                        println!("   🏷️ {:<10} {} trades | Hit: {:.0}% | PnL: ${:.2} | Fees: ${:.2} | Edge pred/real: ${:.3}/${:.3}",
+                            row.key, row.trades, row.hit_rate() * 100.0, row.pnl, row.fees,
+                            row.avg_predicted_edge(), row.avg_realized_edge());
+                    }
+                }
+
+                let interval = freshness.poll_interval_secs(scheduler.tick_interval_secs());
+                let sleep_msg = format!("💤 Sleeping {}s...", interval);
+                println!("{}", sleep_msg);
+                push_log(&sleep_msg);
+                // Answer dry-run cost previews while waiting for the next tick
+                engine_heartbeat.beat();
+                let sleep = tokio::time::sleep(Duration::from_secs(interval));
+                tokio::pin!(sleep);
+                loop {
+                    tokio::select! {
+                        _ = &mut sleep => break,
+                        Some(job) = preview_jobs.recv() => {
+                            let preview_started = Instant::now();
+                            let now = Wallet::current_timestamp();
+                            let request = job.request.clone();
+                            let market = shared_markets.read().await.iter().find(|m| m.id == request.market_id).cloned();
+                            let Some(market) = market else {
+                                job.respond(Err(format!("unknown market {}", request.market_id)));
+                                continue;
+                            };
+                            let mut books = Vec::new();
+                            for token_id in &market.clob_token_ids {
+                                match market_client.get_order_book(token_id).await {
+                                    Ok(book) => books.push(book),
+                                    Err(e) => {
+                                        books.clear();
+                                        println!("   ⚠️ [Preview] Order book {} unavailable: {}", token_id, e);
+                                        break;
+                                    }
                                 }
                             }
+                            if books.is_empty() {
+                                job.respond(Err(format!("order books for {} unavailable", market.id)));
+                                continue;
+                            }
+                            let adverse_std = market.clob_token_ids.iter()
+                                .filter_map(|t| trade_flow.adverse_move_std(t, now.saturating_sub(300)))
+                                .fold(config.timing.adverse_selection_std, f64::max);
+                            let mut cost = preview::cost_breakdown(&request, &market, &books, &fee_model,
+                                config.trading.gas_per_leg_usd, adverse_std);
+                            let required = cost.total_notional + cost.total_fees;
+                            let category = attribution::categorize(&market);
+
+                            cost.check("side", match request.side {
+                                Side::Buy => Ok("buy bundle".to_string()),
+                                Side::Sell => Err("only buy bundles are executed".to_string()),
+                            });
+                            let fillable = cost.legs.iter().map(|l| l.fillable_size).fold(f64::INFINITY, f64::min);
+                            cost.check("fill", if fillable + 1e-9 >= request.size {
+                                Ok(format!("books fill {:.2} per leg", request.size))
+                            } else {
+                                Err(format!("books fill only {:.2} of {:.2} per leg", fillable, request.size))
+                            });
+                            cost.check("edge", if cost.net_edge >= config.trading.min_profit_threshold {
+                                Ok(format!("net edge ${:.4}", cost.net_edge))
+                            } else {
+                                Err(format!("net edge ${:.4} below ${:.2} minimum", cost.net_edge, config.trading.min_profit_threshold))
+                            });
+                            cost.check("safe_mode", match safe_mode_until {
+                                Some(until) if now < until => Err(format!("safe mode for {}s", until - now)),
+                                _ => Ok("off".to_string()),
+                            });
+                            let regime = regimes.classify(&market.id);
+                            let throttle = vol_response.throttle(risk.volatility(), config.risk.volatility_threshold, regime);
+                            let multiplier = regime.params(&config.strategy).size_multiplier * throttle.size_multiplier;
+                            cost.check("risk", match (risk.should_halt(), sizer.size(multiplier, &risk)) {
+                                ((true, Some(reason)), _) => Err(format!("trading halted: {}", reason)),
+                                (_, None) => Err(format!("size scaled below minimum (scale {:.2})", risk.size_scale())),
+                                (_, Some(size)) if request.size > size + 1e-9 => Err(format!("engine sizes at most {:.2} per leg", size)),
+                                (_, Some(size)) => Ok(format!("engine sizes {:.2} per leg (scale {:.2})", size, risk.size_scale())),
+                            });
+                            let market_notional = position_manager.read().await.market_notional(&market.id);
+                            let decision = compliance.evaluate(&market, category, market_notional, cost.total_notional, now);
+                            cost.check("compliance", match decision.violations().first() {
+                                Some(v) => Err(format!("rule {} - {}", v.rule_id, v.detail)),
+                                None if decision.checks.is_empty() => Ok("no rules".to_string()),
+                                None => Ok(decision.trace()),
+                            });
+                            let remaining = metamask.get_remaining_allowance().await;
+                            cost.check("allowance", if remaining >= required {
+                                Ok(format!("${:.2} of ${:.2} remaining", required, remaining))
+                            } else {
+                                Err(format!("${:.2} exceeds ${:.2} remaining", required, remaining))
+                            });
+                            let mut scope = Ok("every leg's call in scope".to_string());
+                            for leg in &cost.legs {
+                                if let Err(e) = metamask.authorize_call(&execution_engine.trade_call(leg.notional + leg.fee)).await {
+                                    scope = Err(format!("{} leg: {}", leg.outcome, e));
+                                    break;
+                                }
+                            }
+                            cost.check("permission_scope", scope);
+                            cost.check("trading_window", trading_windows.check(now, &market.id, category)
+                                .map(|()| "open".to_string()).map_err(|e| e.to_string()));
+                            let open_bundles = position_manager.read().await.open_bundle_count();
+                            cost.check("throttle", entry_throttle.check(now, open_bundles)
+                                .map(|()| format!("{} bundles open", open_bundles)).map_err(|e| e.to_string()));
+                            cost.check("pacing", pacer.check(required, now, remaining, metamask.next_reset_at().await)
+                                .map(|()| format!("${:.2} paced", required)).map_err(|e| e.to_string()));
+                            cost.check("strategy_budget", strategies.lock().unwrap().check(ARB_STRATEGY, required)
+                                .map(|()| format!("${:.2} within {} budget", required, ARB_STRATEGY)).map_err(|e| e.to_string()));
+                            // Approval only delays the trade, so it never fails the preview
+                            cost.check("approval", Ok(match approval_queue.lock().unwrap().requires_approval(required) {
+                                true => "needs human approval".to_string(),
+                                false => "not required".to_string(),
+                            }));
+                            log_event(EventLevel::Debug, "preview", Some(&market.id),
+                                &format!("🔍 [Preview] {:?} {:.2} on {}: net edge ${:.4}, would trade: {}",
+                                    request.side, request.size, market.id, cost.net_edge, cost.would_trade()));
+                            job.respond(Ok(cost));
+                            perf.record("preview", preview_started.elapsed());
                         }
                     }
                 }
             }
+        };
+        if let Err(exit) = supervisor.guard(&engine_heartbeat, engine).await {
+            supervisor.record_restart("engine", &exit);
         }
-
-        // Show stats
-        {
-            let pm = position_manager.read().await;
-            let stats_msg = format!("📊 Stats: {} trades | Win rate: {:.0}% | PnL: ${:.2} | Open: {}",
-                pm.trade_count(),
-                pm.win_rate() * 100.0,
-                pm.total_pnl(),
-                pm.get_positions().len(),
-            );
-            println!("\n{}", stats_msg);
-            push_log(&stats_msg);
-        }
-
-        let sleep_msg = format!("💤 Sleeping {}s...", config.timing.poll_interval_secs);
-        println!("{}", sleep_msg);
-        push_log(&sleep_msg);
-        tokio::time::sleep(Duration::from_secs(config.timing.poll_interval_secs)).await;
     }
 }
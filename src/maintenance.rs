@@ -0,0 +1,251 @@
+//! Venue maintenance and status monitoring
+//!
+//! Polls the venue's status page (Statuspage `summary.json`) and watches our
+//! own request error rate. Trading stops - before the orders start failing -
+//! during an announced maintenance window (from `lead_secs` before it
+//! starts), while the page reports a major outage, or when most recent venue
+//! requests fail. It resumes only after the venue has looked healthy for
+//! `recovery_secs`, so a flapping recovery does not re-enable trading. These
+//! pauses are logged under the `maintenance` component, apart from the
+//! safe-mode triggers caused by our own behavior.
+
+use crate::config::MaintenanceConfig;
+use chrono::DateTime;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fmt;
+
+/// A scheduled or ongoing maintenance from the status page
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MaintenanceWindow {
+    pub name: String,
+    /// scheduled | in_progress | verifying | completed
+    pub status: String,
+    pub starts_at: u64,
+    pub ends_at: Option<u64>,
+}
+
+/// The parts of the status page we act on
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct StatusPage {
+    /// none | minor | major | critical | maintenance
+    pub indicator: String,
+    pub maintenances: Vec<MaintenanceWindow>,
+}
+
+fn timestamp(value: &serde_json::Value) -> Option<u64> {
+    DateTime::parse_from_rfc3339(value.as_str()?).ok().map(|t| t.timestamp().max(0) as u64)
+}
+
+/// Parse a Statuspage `summary.json` (or `scheduled-maintenances.json`)
+pub fn parse_status_page(page: &serde_json::Value) -> Option<StatusPage> {
+    let indicator = page["status"]["indicator"].as_str().unwrap_or("none").to_string();
+    let maintenances = page["scheduled_maintenances"].as_array().map_or_else(Vec::new, |items| {
+        items.iter().filter_map(|m| Some(MaintenanceWindow {
+            name: m["name"].as_str().unwrap_or("maintenance").to_string(),
+            status: m["status"].as_str()?.to_string(),
+            starts_at: timestamp(&m["scheduled_for"])?,
+            ends_at: timestamp(&m["scheduled_until"]),
+        })).collect()
+    });
+    (page.get("status").is_some() || page.get("scheduled_maintenances").is_some())
+        .then_some(StatusPage { indicator, maintenances })
+}
+
+pub async fn fetch_status(client: &reqwest::Client, url: &str) -> Result<StatusPage, String> {
+    let resp = client.get(url).send().await.map_err(|e| e.to_string())?;
+    if !resp.status().is_success() {
+        return Err(format!("status page returned {}", resp.status()));
+    }
+    let page: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+    parse_status_page(&page).ok_or_else(|| "unrecognized status page".to_string())
+}
+
+/// Why the venue is not tradable
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum VenueCondition {
+    Maintenance { name: String, until: Option<u64> },
+    Outage { indicator: String },
+    Degraded { error_rate: f64, requests: usize },
+}
+
+impl fmt::Display for VenueCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Maintenance { name, until: Some(until) } => write!(f, "maintenance \"{}\" until {}", name, until),
+            Self::Maintenance { name, until: None } => write!(f, "maintenance \"{}\"", name),
+            Self::Outage { indicator } => write!(f, "status page reports a {} outage", indicator),
+            Self::Degraded { error_rate, requests } => {
+                write!(f, "{:.0}% of the last {} venue requests failed", error_rate * 100.0, requests)
+            }
+        }
+    }
+}
+
+/// A change of the no-trade state
+#[derive(Debug, Clone, PartialEq)]
+pub enum Transition {
+    Paused(VenueCondition),
+    Resumed { paused_secs: u64 },
+}
+
+/// No-trade state driven by the status page and our own error rate
+#[derive(Debug)]
+pub struct MaintenanceMonitor {
+    config: MaintenanceConfig,
+    page: Option<StatusPage>,
+    last_poll: Option<u64>,
+    /// (time, succeeded) of recent venue requests
+    requests: VecDeque<(u64, bool)>,
+    /// Current cause and when trading paused
+    paused: Option<(VenueCondition, u64)>,
+    /// Healthy since (while paused)
+    healthy_since: Option<u64>,
+}
+
+impl MaintenanceMonitor {
+    pub fn new(config: MaintenanceConfig) -> Self {
+        Self { config, page: None, last_poll: None, requests: VecDeque::new(), paused: None, healthy_since: None }
+    }
+
+    /// Whether the status page should be polled now (marks it polled)
+    pub fn poll_due(&mut self, now: u64) -> bool {
+        let due = self.config.enabled && !self.config.status_url.is_empty()
+            && self.last_poll.is_none_or(|t| now.saturating_sub(t) >= self.config.poll_secs);
+        if due {
+            self.last_poll = Some(now);
+        }
+        due
+    }
+
+    pub fn observe_status(&mut self, page: StatusPage) {
+        self.page = Some(page);
+    }
+
+    /// Outcome of a request to the venue
+    pub fn record_request(&mut self, ok: bool, now: u64) {
+        self.requests.push_back((now, ok));
+        while self.requests.front().is_some_and(|(t, _)| now.saturating_sub(*t) > self.config.error_window_secs) {
+            self.requests.pop_front();
+        }
+    }
+
+    /// What currently makes the venue untradable, if anything
+    pub fn condition(&self, now: u64) -> Option<VenueCondition> {
+        if let Some(page) = &self.page {
+            let active = page.maintenances.iter().find(|m| match m.status.as_str() {
+                "in_progress" | "verifying" => true,
+                "scheduled" => now + self.config.lead_secs >= m.starts_at && m.ends_at.is_none_or(|end| now < end),
+                _ => false,
+            });
+            if let Some(m) = active {
+                return Some(VenueCondition::Maintenance { name: m.name.clone(), until: m.ends_at });
+            }
+            if page.indicator == "maintenance" {
+                return Some(VenueCondition::Maintenance { name: "status page".to_string(), until: None });
+            }
+            if matches!(page.indicator.as_str(), "major" | "critical") {
+                return Some(VenueCondition::Outage { indicator: page.indicator.clone() });
+            }
+        }
+        let recent: Vec<bool> = self.requests.iter()
+            .filter(|(t, _)| now.saturating_sub(*t) <= self.config.error_window_secs)
+            .map(|(_, ok)| *ok)
+            .collect();
+        let error_rate = recent.iter().filter(|ok| !**ok).count() as f64 / recent.len().max(1) as f64;
+        (recent.len() >= self.config.min_requests && error_rate > self.config.max_error_rate)
+            .then_some(VenueCondition::Degraded { error_rate, requests: recent.len() })
+    }
+
+    /// Re-evaluate the no-trade state
+    pub fn update(&mut self, now: u64) -> Option<Transition> {
+        if !self.config.enabled {
+            return None;
+        }
+        match (self.condition(now), &mut self.paused) {
+            (Some(condition), None) => {
+                self.paused = Some((condition.clone(), now));
+                self.healthy_since = None;
+                Some(Transition::Paused(condition))
+            }
+            (Some(condition), Some((cause, _))) => {
+                *cause = condition;
+                self.healthy_since = None;
+                None
+            }
+            (None, Some((_, since))) => {
+                let since = *since;
+                let healthy_since = *self.healthy_since.get_or_insert(now);
+                if now.saturating_sub(healthy_since) < self.config.recovery_secs {
+                    return None;
+                }
+                self.paused = None;
+                self.healthy_since = None;
+                Some(Transition::Resumed { paused_secs: now.saturating_sub(since) })
+            }
+            (None, None) => None,
+        }
+    }
+
+    /// Why trading is paused (None = tradable)
+    pub fn paused(&self) -> Option<&VenueCondition> {
+        self.paused.as_ref().map(|(condition, _)| condition)
+    }
+
+    /// Seconds left before a paused venue counts as recovered
+    pub fn recovery_remaining(&self, now: u64) -> Option<u64> {
+        let since = self.healthy_since?;
+        Some((since + self.config.recovery_secs).saturating_sub(now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> MaintenanceConfig {
+        MaintenanceConfig { lead_secs: 300, recovery_secs: 120, error_window_secs: 60, min_requests: 4, max_error_rate: 0.5, ..Default::default() }
+    }
+
+    #[test]
+    fn test_pauses_for_announced_maintenance_and_confirms_recovery() {
+        let page = parse_status_page(&serde_json::json!({
+            "status": {"indicator": "none"},
+            "scheduled_maintenances": [{
+                "name": "CLOB upgrade", "status": "scheduled",
+                "scheduled_for": "2024-01-01T01:00:00Z", "scheduled_until": "2024-01-01T02:00:00Z"
+            }]
+        })).unwrap();
+        let start = 1_704_070_800; // 01:00
+        assert_eq!(page.maintenances[0].starts_at, start);
+
+        let mut monitor = MaintenanceMonitor::new(config());
+        monitor.observe_status(page);
+        assert_eq!(monitor.update(start - 600), None);
+        // Inside the lead time
+        assert!(matches!(monitor.update(start - 200), Some(Transition::Paused(VenueCondition::Maintenance { .. }))));
+        assert_eq!(monitor.update(start + 1800), None);
+        // Window over, but recovery must hold for recovery_secs
+        assert_eq!(monitor.update(start + 3600), None);
+        assert!(monitor.paused().is_some());
+        assert_eq!(monitor.recovery_remaining(start + 3660), Some(60));
+        assert_eq!(monitor.update(start + 3720), Some(Transition::Resumed { paused_secs: 3920 }));
+        assert!(monitor.paused().is_none());
+    }
+
+    #[test]
+    fn test_error_rate_heuristic() {
+        let mut monitor = MaintenanceMonitor::new(config());
+        for (t, ok) in [(0, true), (10, false), (20, false)] {
+            monitor.record_request(ok, t);
+        }
+        assert_eq!(monitor.update(20), None, "too few requests");
+        monitor.record_request(false, 30);
+        assert!(matches!(monitor.update(30), Some(Transition::Paused(VenueCondition::Degraded { requests: 4, .. }))));
+        // Errors age out of the window, then recovery is confirmed
+        monitor.record_request(true, 100);
+        assert_eq!(monitor.update(100), None);
+        assert_eq!(monitor.update(230), Some(Transition::Resumed { paused_secs: 200 }));
+    }
+}
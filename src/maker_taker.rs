@@ -0,0 +1,289 @@
+// Maker / taker choice per leg
+// Posts a leg when its expected value beats crossing, and journals fills to compare the paths
+
+use crate::config::MakerTakerConfig;
+use crate::fees::FeeModel;
+use crate::storage::JsonlStore;
+use crate::types::{OrderBook, Side};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LegPath {
+    Taker,
+    Maker,
+}
+
+/// What is known about a leg when choosing its path
+#[derive(Debug, Clone, Copy)]
+pub struct LegContext {
+    pub side: Side,
+    pub size: f64,
+    /// The leg's share of the bundle edge, per share, after taker costs
+    pub edge: f64,
+    /// 0 (nothing filled yet) to 1 (every other leg already filled)
+    pub urgency: f64,
+    /// Opposing aggressor volume per second (what would fill a posted order)
+    pub flow_per_sec: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegDecision {
+    pub path: LegPath,
+    /// Price the leg would post at
+    pub maker_price: Option<f64>,
+    /// Executable price for the size when crossing
+    pub taker_price: Option<f64>,
+    /// Chance the posted order fills within the horizon
+    pub fill_probability: f64,
+    /// Per share saved by posting instead of crossing (price and fees)
+    pub saving: f64,
+    pub reason: String,
+}
+
+impl LegDecision {
+    /// Touch on the crossing side when the decision was made
+    fn touch(&self) -> Option<f64> {
+        self.taker_price.or(self.maker_price)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum LegEvent {
+    Decided { leg_id: String, market_id: String, token_id: String, side: Side, size: f64, decision: LegDecision, timestamp: u64 },
+    Filled { leg_id: String, size: f64, price: f64, timestamp: u64 },
+}
+
+/// Totals per path
+#[derive(Debug, Clone, Default)]
+struct PathTotals {
+    legs: usize,
+    requested: f64,
+    filled: f64,
+    /// Price improvement over the touch at decision time, times size filled
+    improvement: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PathReport {
+    pub legs: usize,
+    /// Filled / requested size
+    pub fill_rate: f64,
+    /// Average price improvement per filled share over the crossing price at decision time
+    pub improvement_per_share: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MakerTakerReport {
+    pub taker: PathReport,
+    pub maker: PathReport,
+}
+
+#[derive(Debug)]
+pub struct MakerTaker {
+    config: MakerTakerConfig,
+    /// Side and touch of every decided leg, for pricing its fills
+    legs: HashMap<String, (LegPath, Side, Option<f64>)>,
+    totals: HashMap<LegPath, PathTotals>,
+    journal: Option<JsonlStore>,
+}
+
+impl MakerTaker {
+    pub fn new(config: MakerTakerConfig) -> Self {
+        Self { config, legs: HashMap::new(), totals: HashMap::new(), journal: None }
+    }
+
+    /// Replay past decisions and fills from `journal` and append new ones to it
+    pub fn with_journal(mut self, journal: JsonlStore) -> Self {
+        let events: Vec<LegEvent> = journal.load().unwrap_or_else(|e| {
+            eprintln!("⚠️ [MakerTaker] Failed to read leg journal: {}", e);
+            Vec::new()
+        });
+        for event in &events {
+            self.apply(event);
+        }
+        self.journal = Some(journal);
+        self
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Choose between crossing and posting for one leg
+    pub fn decide(&self, book: &OrderBook, leg: LegContext, fees: &FeeModel) -> LegDecision {
+        let taker_price = book.execution_price(leg.size, leg.side);
+        let (touch, opposite, levels) = match leg.side {
+            Side::Buy => (book.best_bid(), book.best_ask(), &book.bids),
+            Side::Sell => (book.best_ask(), book.best_bid(), &book.asks),
+        };
+        let sign = match leg.side {
+            Side::Buy => 1.0,
+            Side::Sell => -1.0,
+        };
+        // One tick inside the touch when the spread leaves room, else join it
+        let maker_price = touch.map(|p| {
+            let inside = p + sign * self.config.tick;
+            match opposite {
+                Some(o) if self.config.post_inside && (o - inside) * sign > 1e-9 => inside,
+                _ => p,
+            }
+        });
+        let queue_ahead = maker_price.filter(|p| Some(*p) == touch)
+            .map_or(0.0, |p| levels.iter().filter(|l| l.price == p).map(|l| l.size).sum());
+        let expected_flow = leg.flow_per_sec * self.config.horizon_secs as f64;
+        let fill_probability = match expected_flow > 0.0 {
+            true => 1.0 - (-expected_flow / (queue_ahead + leg.size).max(1e-9)).exp(),
+            false => 0.0,
+        };
+        let saving = match (maker_price, taker_price) {
+            (Some(m), Some(t)) => (t - m) * sign + t * fees.taker_rate() - m * fees.maker_fee_bps as f64 / 10_000.0,
+            _ => 0.0,
+        };
+        let decision = |path, reason: String| LegDecision { path, maker_price, taker_price, fill_probability, saving, reason };
+
+        if maker_price.is_none() {
+            return decision(LegPath::Taker, "no touch to post at".to_string());
+        }
+        if leg.urgency >= self.config.max_urgency {
+            return decision(LegPath::Taker, format!("urgency {:.2} - other legs already filled", leg.urgency));
+        }
+        if leg.edge >= self.config.take_edge {
+            return decision(LegPath::Taker, format!("edge {:.4}/share is worth crossing for", leg.edge));
+        }
+        if fill_probability < self.config.min_fill_probability {
+            return decision(LegPath::Taker, format!("fill probability {:.0}% within {}s", fill_probability * 100.0, self.config.horizon_secs));
+        }
+        let maker_value = fill_probability * (leg.edge + saving) - (1.0 - fill_probability) * leg.urgency * leg.edge.abs();
+        if maker_value > leg.edge {
+            decision(LegPath::Maker, format!("posting worth {:.4}/share vs {:.4} crossing ({:.0}% fill, saves {:.4})",
+                maker_value, leg.edge, fill_probability * 100.0, saving))
+        } else {
+            decision(LegPath::Taker, format!("crossing worth {:.4}/share vs {:.4} posting", leg.edge, maker_value))
+        }
+    }
+
+    fn apply(&mut self, event: &LegEvent) {
+        match event {
+            LegEvent::Decided { leg_id, side, size, decision, .. } => {
+                let totals = self.totals.entry(decision.path).or_default();
+                totals.legs += 1;
+                totals.requested += size;
+                self.legs.insert(leg_id.clone(), (decision.path, *side, decision.touch()));
+            }
+            LegEvent::Filled { leg_id, size, price, .. } => {
+                let Some((path, side, touch)) = self.legs.get(leg_id).copied() else { return };
+                let totals = self.totals.entry(path).or_default();
+                totals.filled += size;
+                if let Some(touch) = touch {
+                    totals.improvement += match side {
+                        Side::Buy => touch - price,
+                        Side::Sell => price - touch,
+                    } * size;
+                }
+            }
+        }
+    }
+
+    fn journal(&mut self, event: LegEvent) {
+        if let Some(journal) = &self.journal {
+            if let Err(e) = journal.append(&event) {
+                eprintln!("⚠️ [MakerTaker] Failed to journal leg: {}", e);
+            }
+        }
+        self.apply(&event);
+    }
+
+    /// Record the path taken for leg `leg_id`
+    pub fn record_decision(&mut self, leg_id: &str, market_id: &str, token_id: &str, leg: &LegContext, decision: &LegDecision, now: u64) {
+        self.journal(LegEvent::Decided {
+            leg_id: leg_id.to_string(),
+            market_id: market_id.to_string(),
+            token_id: token_id.to_string(),
+            side: leg.side,
+            size: leg.size,
+            decision: decision.clone(),
+            timestamp: now,
+        });
+    }
+
+    /// Record a fill on a decided leg (ignored for legs decided elsewhere)
+    pub fn record_fill(&mut self, leg_id: &str, size: f64, price: f64, now: u64) {
+        if size > 0.0 && self.legs.contains_key(leg_id) {
+            self.journal(LegEvent::Filled { leg_id: leg_id.to_string(), size, price, timestamp: now });
+        }
+    }
+
+    pub fn report(&self) -> MakerTakerReport {
+        let path = |path| self.totals.get(&path).map_or_else(PathReport::default, |t| PathReport {
+            legs: t.legs,
+            fill_rate: if t.requested > 0.0 { t.filled / t.requested } else { 0.0 },
+            improvement_per_share: if t.filled > 0.0 { t.improvement / t.filled } else { 0.0 },
+        });
+        MakerTakerReport { taker: path(LegPath::Taker), maker: path(LegPath::Maker) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    const FEES: FeeModel = FeeModel { maker_fee_bps: 0, taker_fee_bps: 200 };
+
+    fn book() -> OrderBook {
+        testing::book("yes", &[(0.44, 50.0)], &[(0.48, 500.0)])
+    }
+
+    fn leg(edge: f64, urgency: f64, flow_per_sec: f64) -> LegContext {
+        LegContext { side: Side::Buy, size: 10.0, edge, urgency, flow_per_sec }
+    }
+
+    fn router() -> MakerTaker {
+        MakerTaker::new(MakerTakerConfig { enabled: true, ..MakerTakerConfig::default() })
+    }
+
+    #[test]
+    fn test_small_edge_on_a_busy_tape_posts_inside_the_bid() {
+        let posted = router().decide(&book(), leg(0.005, 0.0, 1.0), &FEES);
+        assert_eq!(posted.path, LegPath::Maker, "{}", posted.reason);
+        assert_eq!(posted.maker_price, Some(0.45));
+        assert!((posted.saving - (0.03 + 0.48 * 0.02)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_urgent_or_large_edges_cross() {
+        assert_eq!(router().decide(&book(), leg(0.005, 1.0, 1.0), &FEES).path, LegPath::Taker);
+        assert_eq!(router().decide(&book(), leg(0.05, 0.0, 1.0), &FEES).path, LegPath::Taker);
+    }
+
+    #[test]
+    fn test_quiet_tape_crosses() {
+        let quiet = router().decide(&book(), leg(0.005, 0.0, 0.0), &FEES);
+        assert_eq!(quiet.path, LegPath::Taker);
+        assert!(quiet.reason.starts_with("fill probability 0%"));
+    }
+
+    #[test]
+    fn test_outcomes_tracked_per_path() {
+        let mut router = router();
+        let (posted_leg, quiet_leg) = (leg(0.005, 0.0, 1.0), leg(0.005, 0.0, 0.0));
+        let posted = router.decide(&book(), posted_leg, &FEES);
+        let quiet = router.decide(&book(), quiet_leg, &FEES);
+        router.record_decision("m1", "m", "yes", &posted_leg, &posted, 0);
+        router.record_fill("m1", 5.0, 0.45, 10);
+        router.record_decision("t1", "m", "no", &quiet_leg, &quiet, 0);
+        router.record_fill("t1", 10.0, 0.48, 0);
+        router.record_fill("unknown", 10.0, 0.48, 0);
+
+        let report = router.report();
+        assert_eq!(report.maker.legs, 1);
+        assert!((report.maker.fill_rate - 0.5).abs() < 1e-9);
+        assert!((report.maker.improvement_per_share - 0.03).abs() < 1e-9);
+        assert_eq!(report.taker.legs, 1);
+        assert!((report.taker.fill_rate - 1.0).abs() < 1e-9);
+        assert!(report.taker.improvement_per_share.abs() < 1e-9);
+    }
+}
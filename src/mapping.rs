@@ -0,0 +1,282 @@
+//! Cross-venue market mapping
+//!
+//! Fuzzy-matches markets across venues by normalized question text,
+//! resolution date and outcome names. Candidate pairs are written to a
+//! mapping file (TOML) with a confidence score; a pair is only used for
+//! cross-venue arb once `confirmed = true` is set in that file.
+//!
+//! Pairs scoring at or above `accept_threshold` are suggested "accept",
+//! pairs between `min_confidence` and `accept_threshold` are suggested
+//! "review", and everything below `min_confidence` is dropped.
+
+#![allow(dead_code)]
+
+use crate::types::Market;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+/// Words that carry no matching signal
+const STOPWORDS: &[&str] = &[
+    "a", "an", "the", "will", "be", "by", "of", "in", "on", "at", "to", "for", "is", "or", "and", "before", "after",
+];
+
+/// Weights of the score components
+const QUESTION_WEIGHT: f64 = 0.6;
+const OUTCOME_WEIGHT: f64 = 0.2;
+const DATE_WEIGHT: f64 = 0.2;
+
+/// Resolution dates further apart than this score zero
+const MAX_DATE_GAP_SECS: u64 = 7 * 86400;
+
+/// A market as seen by the mapper
+#[derive(Debug, Clone)]
+pub struct VenueMarket {
+    pub venue: String,
+    pub market_id: String,
+    pub question: String,
+    pub outcomes: Vec<String>,
+    /// Resolution timestamp (unix seconds), if known
+    pub resolves_at: Option<u64>,
+}
+
+impl VenueMarket {
+    pub fn from_market(venue: &str, market: &Market, resolves_at: Option<u64>) -> Self {
+        Self {
+            venue: venue.to_string(),
+            market_id: market.id.clone(),
+            question: market.question.clone(),
+            outcomes: market.outcomes.clone(),
+            resolves_at,
+        }
+    }
+}
+
+/// One candidate pair in the mapping file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MarketPair {
+    pub venue_a: String,
+    pub market_a: String,
+    pub venue_b: String,
+    pub market_b: String,
+    pub question_a: String,
+    pub question_b: String,
+    pub confidence: f64,
+    /// "accept" or "review"
+    pub suggested: String,
+    /// Set by an operator; only confirmed pairs are traded
+    #[serde(default)]
+    pub confirmed: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct MappingFile {
+    #[serde(default)]
+    pairs: Vec<MarketPair>,
+}
+
+/// Builds candidate pairs between two venues
+#[derive(Debug, Clone)]
+pub struct MappingBuilder {
+    pub min_confidence: f64,
+    pub accept_threshold: f64,
+}
+
+impl Default for MappingBuilder {
+    fn default() -> Self {
+        Self { min_confidence: 0.5, accept_threshold: 0.8 }
+    }
+}
+
+impl MappingBuilder {
+    pub fn new(min_confidence: f64, accept_threshold: f64) -> Self {
+        Self { min_confidence, accept_threshold }
+    }
+
+    /// Score every cross pair and keep the best match per market (greedy, one-to-one)
+    pub fn build(&self, a: &[VenueMarket], b: &[VenueMarket]) -> Vec<MarketPair> {
+        let tokens_b: Vec<HashSet<String>> = b.iter().map(|m| question_tokens(&m.question)).collect();
+        let mut scored = Vec::new();
+        for ma in a {
+            let ta = question_tokens(&ma.question);
+            for (j, mb) in b.iter().enumerate() {
+                let score = QUESTION_WEIGHT * jaccard(&ta, &tokens_b[j])
+                    + OUTCOME_WEIGHT * outcome_similarity(&ma.outcomes, &mb.outcomes)
+                    + DATE_WEIGHT * date_similarity(ma.resolves_at, mb.resolves_at);
+                if score >= self.min_confidence {
+                    scored.push((score, ma, mb));
+                }
+            }
+        }
+        scored.sort_by(|x, y| y.0.total_cmp(&x.0));
+
+        let mut used_a = HashSet::new();
+        let mut used_b = HashSet::new();
+        scored.into_iter()
+            .filter(|(_, ma, mb)| used_a.insert(&ma.market_id) & used_b.insert(&mb.market_id))
+            .map(|(score, ma, mb)| MarketPair {
+                venue_a: ma.venue.clone(),
+                market_a: ma.market_id.clone(),
+                venue_b: mb.venue.clone(),
+                market_b: mb.market_id.clone(),
+                question_a: ma.question.clone(),
+                question_b: mb.question.clone(),
+                confidence: (score * 1000.0).round() / 1000.0,
+                suggested: if score >= self.accept_threshold { "accept" } else { "review" }.to_string(),
+                confirmed: false,
+            })
+            .collect()
+    }
+
+    /// Write candidates to the mapping file, keeping operator decisions
+    /// (`confirmed`) for pairs already present
+    pub fn write(&self, path: &str, candidates: Vec<MarketPair>) -> Result<usize, MappingError> {
+        let existing = read_file(path)?;
+        let decisions: HashMap<(String, String), bool> = existing.pairs.iter()
+            .map(|p| ((p.market_a.clone(), p.market_b.clone()), p.confirmed))
+            .collect();
+        let pairs: Vec<MarketPair> = candidates.into_iter()
+            .map(|mut p| {
+                if let Some(confirmed) = decisions.get(&(p.market_a.clone(), p.market_b.clone())) {
+                    p.confirmed = *confirmed;
+                }
+                p
+            })
+            .collect();
+        let count = pairs.len();
+        let body = toml::to_string_pretty(&MappingFile { pairs })
+            .map_err(|e| MappingError::Serialize(e.to_string()))?;
+        let header = "# Candidate cross-venue market pairs (generated).\n\
+                      # Set `confirmed = true` on pairs that should be traded.\n\n";
+        fs::write(path, format!("{}{}", header, body)).map_err(|e| MappingError::Io(e.to_string()))?;
+        Ok(count)
+    }
+}
+
+/// Load only the pairs an operator has confirmed
+pub fn load_confirmed(path: &str) -> Result<Vec<MarketPair>, MappingError> {
+    Ok(read_file(path)?.pairs.into_iter().filter(|p| p.confirmed).collect())
+}
+
+fn read_file(path: &str) -> Result<MappingFile, MappingError> {
+    match fs::read_to_string(path) {
+        Ok(contents) => toml::from_str(&contents).map_err(|e| MappingError::Parse(e.to_string())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(MappingFile::default()),
+        Err(e) => Err(MappingError::Io(e.to_string())),
+    }
+}
+
+/// Lowercase, strip punctuation and stopwords
+pub fn normalize_question(question: &str) -> String {
+    question_tokens_ordered(question).join(" ")
+}
+
+/// Normalized question words (stopwords dropped, crudely stemmed), in order
+pub fn question_tokens_ordered(question: &str) -> Vec<String> {
+    question
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c.is_whitespace() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .filter(|w| !STOPWORDS.contains(w))
+        // Crude plural/verb stemming: "cuts" ~ "cut"
+        .map(|w| if w.len() > 3 { w.strip_suffix('s').unwrap_or(w) } else { w }.to_string())
+        .collect()
+}
+
+/// Normalized question tokens (see `normalize_question`)
+pub fn question_tokens(question: &str) -> HashSet<String> {
+    question_tokens_ordered(question).into_iter().collect()
+}
+
+/// Jaccard similarity of two token sets
+pub fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+fn outcome_similarity(a: &[String], b: &[String]) -> f64 {
+    let norm = |v: &[String]| v.iter().map(|s| s.trim().to_lowercase()).collect::<HashSet<_>>();
+    let (a, b) = (norm(a), norm(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.5; // Unknown: neither evidence for nor against
+    }
+    jaccard(&a, &b)
+}
+
+fn date_similarity(a: Option<u64>, b: Option<u64>) -> f64 {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            let gap = a.abs_diff(b);
+            1.0 - (gap as f64 / MAX_DATE_GAP_SECS as f64).min(1.0)
+        }
+        _ => 0.5,
+    }
+}
+
+/// Mapping errors
+#[derive(Debug, Clone)]
+pub enum MappingError {
+    Io(String),
+    Parse(String),
+    Serialize(String),
+}
+
+impl std::fmt::Display for MappingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Mapping file I/O error: {}", e),
+            Self::Parse(e) => write!(f, "Mapping file parse error: {}", e),
+            Self::Serialize(e) => write!(f, "Mapping serialize error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for MappingError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vm(venue: &str, id: &str, question: &str, resolves_at: u64) -> VenueMarket {
+        VenueMarket {
+            venue: venue.to_string(),
+            market_id: id.to_string(),
+            question: question.to_string(),
+            outcomes: vec!["Yes".to_string(), "No".to_string()],
+            resolves_at: Some(resolves_at),
+        }
+    }
+
+    #[test]
+    fn test_build_and_confirm() {
+        let a = vec![
+            vm("polymarket", "p1", "Will the Fed cut rates in March 2025?", 1_743_000_000),
+            vm("polymarket", "p2", "Will BTC close above $100k on Dec 31?", 1_735_600_000),
+        ];
+        let b = vec![
+            vm("kalshi", "k1", "Fed cuts rates in March 2025", 1_743_000_000),
+            vm("kalshi", "k2", "Who wins the Super Bowl?", 1_739_000_000),
+        ];
+        let pairs = MappingBuilder::default().build(&a, &b);
+        assert_eq!(pairs.len(), 1);
+        assert_eq!((pairs[0].market_a.as_str(), pairs[0].market_b.as_str()), ("p1", "k1"));
+        assert!(!pairs[0].confirmed);
+
+        let path = std::env::temp_dir().join(format!("arbishark_pairs_{}.toml", std::process::id()));
+        let path = path.to_str().unwrap();
+        MappingBuilder::default().write(path, pairs.clone()).unwrap();
+        assert!(load_confirmed(path).unwrap().is_empty());
+
+        // Operator confirms; a rebuild must keep the decision
+        let edited = fs::read_to_string(path).unwrap().replace("confirmed = false", "confirmed = true");
+        fs::write(path, edited).unwrap();
+        MappingBuilder::default().write(path, pairs).unwrap();
+        assert_eq!(load_confirmed(path).unwrap().len(), 1);
+        let _ = fs::remove_file(path);
+    }
+}
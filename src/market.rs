@@ -4,12 +4,12 @@ use crate::market_client::MarketClient;
 impl MarketClient for MarketDataProvider {
 
     async fn get_markets(&self) -> Result<Vec<Market>, Box<dyn Error + Send + Sync>> {
-        let markets = self.fetch_markets().await.map_err(Box::<dyn Error + Send + Sync>::from)?;
+        let markets = self.fetch_markets().await?;
         Ok(markets)
     }
 
     async fn get_order_book(&self, token_id: &str) -> Result<OrderBook, Box<dyn Error + Send + Sync>> {
-        let ob = self.fetch_order_book(token_id).await.map_err(Box::<dyn Error + Send + Sync>::from)?;
+        let ob = self.fetch_order_book(token_id).await?;
         Ok(ob)
     }
 
@@ -32,7 +32,7 @@ pub struct MarketDataProvider {
 impl MarketDataProvider {
     pub fn new(_envio_url: &str) -> Self {
         Self {
-            client: reqwest::Client::new(),
+            client: crate::market_client::http_client(),
             gamma_url: "https://gamma-api.polymarket.com/events?limit=20&active=true&closed=false".to_string(),
             clob_url: "https://clob.polymarket.com/book".to_string(),
         }
@@ -87,6 +87,7 @@ impl MarketDataProvider {
                             outcomes,
                             outcome_prices: vec![0.5, 0.5], // Will be updated by book fetch
                             clob_token_ids,
+                            condition_id: m["conditionId"].as_str().unwrap_or("").to_string(),
                             best_bid: None,
                             best_ask: None,
                             maker_base_fee: 0,
@@ -95,6 +96,9 @@ impl MarketDataProvider {
                             volume_24hr: 0.0,
                             active: true,
                             accepting_orders: true,
+                            resolution_source: m["resolutionSource"].as_str().or(event["resolutionSource"].as_str()).unwrap_or("").to_string(),
+                            end_date: m["endDate"].as_str().or(event["endDate"].as_str())
+                                .and_then(|d| chrono::DateTime::parse_from_rfc3339(d).ok()).map(|t| t.timestamp().max(0) as u64),
                         });
                     }
                 }
@@ -105,7 +109,7 @@ impl MarketDataProvider {
     }
 
     /// concurrently hydrate prices for all markets (Batch/Parallel)
-    pub async fn hydrate_market_prices(&self, markets: &mut Vec<Market>) {
+    pub async fn hydrate_market_prices(&self, markets: &mut [Market]) {
         use futures_util::stream::{self, StreamExt};
 
         println!("⚡ Hydrating prices concurrently (Concurrency: 50)...");
@@ -1,6 +1,47 @@
+/// A Gamma API list field, sent either as a JSON array or as a stringified one
+fn gamma_list(value: &serde_json::Value) -> Vec<String> {
+    if let Some(s) = value.as_str() {
+        serde_json::from_str(s).unwrap_or_default()
+    } else {
+        value.as_array()
+            .map(|arr| arr.iter().map(|v| v.as_str().unwrap_or("").to_string()).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// A tradable market from a Gamma market object `m` of `event` (None with fewer than two tokens)
+pub fn parse_gamma_market(m: &serde_json::Value, event: &serde_json::Value) -> Option<Market> {
+    let clob_token_ids = gamma_list(&m["clobTokenIds"]);
+    if clob_token_ids.len() < 2 {
+        return None;
+    }
+    Some(Market {
+        id: m["id"].as_str().unwrap_or("").to_string(),
+        question: m["question"].as_str().unwrap_or("").to_string(),
+        slug: event["slug"].as_str().unwrap_or("").to_string(),
+        outcomes: gamma_list(&m["outcomes"]),
+        outcome_prices: vec![0.5, 0.5],
+        clob_token_ids,
+        condition_id: m["conditionId"].as_str().unwrap_or("").to_string(),
+        best_bid: None,
+        best_ask: None,
+        maker_base_fee: 0,
+        taker_base_fee: 200,
+        liquidity: 0.0,
+        volume_24hr: 0.0,
+        active: true,
+        accepting_orders: true,
+        resolution_source: m["resolutionSource"].as_str().or(event["resolutionSource"].as_str()).unwrap_or("").to_string(),
+        end_date: m["endDate"].as_str().or(event["endDate"].as_str())
+            .and_then(|d| chrono::DateTime::parse_from_rfc3339(d).ok()).map(|t| t.timestamp().max(0) as u64),
+    })
+}
+
 pub struct PolymarketClient {
     pub gamma_url: String,
     pub clob_url: String,
+    /// Data API trades endpoint (public trade prints)
+    pub trades_url: String,
     pub client: reqwest::Client,
 }
 
@@ -13,38 +54,7 @@ impl MarketClient for PolymarketClient {
         if let Some(events) = json.as_array() {
             for event in events {
                 if let Some(event_markets) = event["markets"].as_array() {
-                    for m in event_markets {
-                        let id = m["id"].as_str().unwrap_or("").to_string();
-                        let question = m["question"].as_str().unwrap_or("").to_string();
-                        let slug = event["slug"].as_str().unwrap_or("").to_string();
-                        let outcomes: Vec<String> = m["outcomes"].as_array()
-                            .map(|arr| arr.iter().map(|v| v.as_str().unwrap_or("").to_string()).collect())
-                            .unwrap_or_default();
-                        let clob_token_ids: Vec<String> = if let Some(s) = m["clobTokenIds"].as_str() {
-                            serde_json::from_str(s).unwrap_or_default()
-                        } else {
-                            m["clobTokenIds"].as_array()
-                                .map(|arr| arr.iter().map(|v| v.as_str().unwrap_or("").to_string()).collect())
-                                .unwrap_or_default()
-                        };
-                        if clob_token_ids.len() < 2 { continue; }
-                        markets.push(Market {
-                            id,
-                            question,
-                            slug,
-                            outcomes,
-                            outcome_prices: vec![0.5, 0.5],
-                            clob_token_ids,
-                            best_bid: None,
-                            best_ask: None,
-                            maker_base_fee: 0,
-                            taker_base_fee: 200,
-                            liquidity: 0.0,
-                            volume_24hr: 0.0,
-                            active: true,
-                            accepting_orders: true,
-                        });
-                    }
+                    markets.extend(event_markets.iter().filter_map(|m| parse_gamma_market(m, event)));
                 }
             }
         }
@@ -72,17 +82,68 @@ impl MarketClient for PolymarketClient {
     async fn stream_quotes(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
         Ok(())
     }
+    async fn get_trades(&self, market: &Market) -> Result<Vec<Trade>, Box<dyn Error + Send + Sync>> {
+        if market.condition_id.is_empty() {
+            return Ok(Vec::new());
+        }
+        let url = format!("{}?market={}&limit=100", self.trades_url, market.condition_id);
+        let resp = self.client.get(&url).send().await?;
+        if !resp.status().is_success() {
+            return Err(format!("Failed to fetch trades: {}", resp.status()).into());
+        }
+        let json: serde_json::Value = resp.json().await?;
+        let trades = json.as_array().map(|arr| arr.iter().filter_map(|t| {
+            let token_id = t["asset"].as_str()?.to_string();
+            let side = match t["side"].as_str()? {
+                "BUY" => Side::Buy,
+                "SELL" => Side::Sell,
+                _ => return None,
+            };
+            // Data API returns numbers, but tolerate stringified values
+            let num = |v: &serde_json::Value| v.as_f64().or_else(|| v.as_str().and_then(|s| s.parse().ok()));
+            let timestamp = t["timestamp"].as_u64().unwrap_or(0);
+            Some(Trade {
+                id: t["transactionHash"].as_str()
+                    .map(|h| format!("{}:{}", h, token_id))
+                    .unwrap_or_else(|| format!("{}:{}", token_id, timestamp)),
+                token_id,
+                price: num(&t["price"])?,
+                size: num(&t["size"])?,
+                side,
+                timestamp,
+            })
+        }).collect()).unwrap_or_default();
+        Ok(trades)
+    }
 }
 
 use async_trait::async_trait;
-use crate::types::{Market, OrderBook};
+use crate::envio::{self, markets_query, meta_query, order_book_query, MarketsQuery, MetaQuery, OrderBookQuery};
+use crate::types::{Market, OrderBook, Side, Trade};
 use std::error::Error;
+use std::time::Duration;
+
+/// Longest one venue request may take: a dead socket fails the call instead of hanging the engine tick
+pub const VENUE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// HTTP client for venue calls, bounded by `VENUE_TIMEOUT`
+pub fn http_client() -> reqwest::Client {
+    reqwest::Client::builder().timeout(VENUE_TIMEOUT).build().unwrap_or_default()
+}
 
 #[async_trait]
 pub trait MarketClient {
     async fn get_markets(&self) -> Result<Vec<Market>, Box<dyn Error + Send + Sync>>;
     async fn get_order_book(&self, token_id: &str) -> Result<OrderBook, Box<dyn Error + Send + Sync>>;
     async fn stream_quotes(&self) -> Result<(), Box<dyn Error + Send + Sync>>; // Placeholder for streaming
+    /// Recent trade prints for a market (newest first is fine; callers sort)
+    async fn get_trades(&self, _market: &Market) -> Result<Vec<Trade>, Box<dyn Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
+    /// Indexer freshness, for sources that report it (None = unknown)
+    async fn data_health(&self) -> Option<EnvioHealth> {
+        None
+    }
 }
 
 
@@ -96,7 +157,7 @@ impl ArbitrumMarketClient {
     pub fn new(endpoint: String) -> Self {
         Self {
             endpoint,
-            client: reqwest::Client::new(),
+            client: http_client(),
             last_query_time: std::sync::Arc::new(std::sync::Mutex::new(None)),
         }
     }
@@ -105,41 +166,20 @@ impl ArbitrumMarketClient {
     pub async fn health_check(&self) -> Result<EnvioHealth, Box<dyn Error + Send + Sync>> {
         let start = std::time::Instant::now();
         
-        // Simple health query to Envio
-        let query = r#"{
-            _meta {
-                block {
-                    number
-                    timestamp
-                }
-            }
-        }"#;
-        
-        let response = self.client.post(&self.endpoint)
-            .json(&serde_json::json!({"query": query}))
-            .timeout(std::time::Duration::from_secs(5))
-            .send()
-            .await?;
-        
+        let meta = envio::query::<MetaQuery>(
+            &self.client, &self.endpoint, meta_query::Variables, std::time::Duration::from_secs(5),
+        ).await?;
+
         let latency_ms = start.elapsed().as_millis() as u64;
         
-        if !response.status().is_success() {
-            return Err(format!("Envio health check failed: {}", response.status()).into());
-        }
-        
-        let json: serde_json::Value = response.json().await?;
-        let block_number = json["data"]["_meta"]["block"]["number"]
-            .as_u64()
-            .unwrap_or(0);
-        let block_timestamp = json["data"]["_meta"]["block"]["timestamp"]
-            .as_u64()
-            .unwrap_or(0);
+        let block_number = meta.meta.block.number.max(0) as u64;
+        let block_timestamp = meta.meta.block.timestamp.max(0) as u64;
         
         // Calculate data delay
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)?
             .as_secs();
-        let data_delay_ms = ((now - block_timestamp) * 1000) as u64;
+        let data_delay_ms = now.saturating_sub(block_timestamp) * 1000;
         
         Ok(EnvioHealth {
             latency_ms,
@@ -168,119 +208,31 @@ impl MarketClient for ArbitrumMarketClient {
             *last_time = Some(std::time::Instant::now());
         }
 
-        let query = r#"{
-            markets {
-                id
-                question
-                slug
-                outcomes
-                outcomePrices
-                clobTokenIds
-                bestBid
-                bestAsk
-                makerBaseFee
-                takerBaseFee
-                liquidity
-                volume24hr
-                active
-                acceptingOrders
-            }
-        }"#;
-        
-        let response = self.client.post(&self.endpoint)
-            .json(&serde_json::json!({"query": query}))
-            .timeout(std::time::Duration::from_secs(10))
-            .send()
-            .await
-            .map_err(|e| format!("Envio request failed: {}", e))?;
-        
-        if !response.status().is_success() {
-            return Err(format!("Envio returned error: {}", response.status()).into());
-        }
-        
-        let json: serde_json::Value = response.json().await?;
-        
-        // Check for GraphQL errors
-        if let Some(errors) = json.get("errors") {
-            return Err(format!("GraphQL errors: {:?}", errors).into());
-        }
-        
-        let markets_json = &json["data"]["markets"];
-        let mut markets = Vec::new();
-        
-        if let Some(arr) = markets_json.as_array() {
-            for m in arr {
-                let market = Market {
-                    id: m["id"].as_str().unwrap_or("").to_string(),
-                    question: m["question"].as_str().unwrap_or("").to_string(),
-                    slug: m["slug"].as_str().unwrap_or("").to_string(),
-                    outcomes: m["outcomes"].as_array().map(|a| a.iter().map(|v| v.as_str().unwrap_or("").to_string()).collect()).unwrap_or_default(),
-                    outcome_prices: m["outcomePrices"].as_array().map(|a| a.iter().map(|v| v.as_f64().unwrap_or(0.0)).collect()).unwrap_or_default(),
-                    clob_token_ids: m["clobTokenIds"].as_array().map(|a| a.iter().map(|v| v.as_str().unwrap_or("").to_string()).collect()).unwrap_or_default(),
-                    best_bid: m["bestBid"].as_f64(),
-                    best_ask: m["bestAsk"].as_f64(),
-                    maker_base_fee: m["makerBaseFee"].as_u64().unwrap_or(0) as u32,
-                    taker_base_fee: m["takerBaseFee"].as_u64().unwrap_or(0) as u32,
-                    liquidity: m["liquidity"].as_f64().unwrap_or(0.0),
-                    volume_24hr: m["volume24hr"].as_f64().unwrap_or(0.0),
-                    active: m["active"].as_bool().unwrap_or(false),
-                    accepting_orders: m["acceptingOrders"].as_bool().unwrap_or(false),
-                };
-                markets.push(market);
-            }
-        }
+        let data = envio::query::<MarketsQuery>(
+            &self.client, &self.endpoint, markets_query::Variables, std::time::Duration::from_secs(10),
+        ).await?;
+        let markets: Vec<Market> = data.markets.into_iter().map(Market::from).collect();
         
         Ok(markets)
     }
     
     async fn get_order_book(&self, token_id: &str) -> Result<OrderBook, Box<dyn Error + Send + Sync>> {
-        let query = format!(r#"{{
-            orderBook(tokenId: "{}") {{
-                tokenId
-                bids {{ price size }}
-                asks {{ price size }}
-                timestamp
-            }}
-        }}"#, token_id);
-        
-        let response = self.client.post(&self.endpoint)
-            .json(&serde_json::json!({"query": query}))
-            .timeout(std::time::Duration::from_secs(10))
-            .send()
-            .await
-            .map_err(|e| format!("Envio request failed: {}", e))?;
-        
-        if !response.status().is_success() {
-            return Err(format!("Envio returned error: {}", response.status()).into());
-        }
-        
-        let json: serde_json::Value = response.json().await?;
-        
-        // Check for GraphQL errors
-        if let Some(errors) = json.get("errors") {
-            return Err(format!("GraphQL errors: {:?}", errors).into());
-        }
-        
-        let ob = &json["data"]["orderBook"];
-        let bids = ob["bids"].as_array().map(|a| a.iter().map(|v| crate::types::PriceLevel {
-            price: v["price"].as_f64().unwrap_or(0.0),
-            size: v["size"].as_f64().unwrap_or(0.0),
-        }).collect()).unwrap_or_default();
-        let asks = ob["asks"].as_array().map(|a| a.iter().map(|v| crate::types::PriceLevel {
-            price: v["price"].as_f64().unwrap_or(0.0),
-            size: v["size"].as_f64().unwrap_or(0.0),
-        }).collect()).unwrap_or_default();
-        
-        Ok(OrderBook {
-            token_id: ob["tokenId"].as_str().unwrap_or("").to_string(),
-            bids,
-            asks,
-            timestamp: ob["timestamp"].as_u64().unwrap_or(0),
-        })
+        let data = envio::query::<OrderBookQuery>(
+            &self.client,
+            &self.endpoint,
+            order_book_query::Variables { token_id: token_id.to_string() },
+            std::time::Duration::from_secs(10),
+        ).await?;
+        let book = data.order_book.ok_or(envio::EnvioError::MissingData("orderBook"))?;
+        Ok(OrderBook::from(book))
     }
     
     async fn stream_quotes(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
         // Not implemented: would require websocket or polling
         Ok(())
     }
+
+    async fn data_health(&self) -> Option<EnvioHealth> {
+        self.health_check().await.ok()
+    }
 }
@@ -6,6 +6,11 @@
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use serde::{Deserialize, Serialize};
+use crate::audit::{AuditLog, SpendContext, SpendRecord};
+use crate::permission_guard::{ContractCall, PermissionScope};
+
+/// Length of an ERC-7715 permission period (daily limit)
+pub const PERMISSION_PERIOD_SECS: u64 = 86400;
 
 /// Permission grant from MetaMask
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +22,9 @@ pub struct PermissionGrant {
     pub expires_at: u64,
     pub granted_at: u64,
     pub revoked: bool,
+    /// Allowed targets, methods and per-tx cap
+    #[serde(default)]
+    pub scope: PermissionScope,
 }
 
 /// MetaMask connection status
@@ -53,6 +61,8 @@ pub enum AgentStatus {
     SafeMode,
     /// Permission has expired
     PermissionExpired,
+    /// Daily allowance spent - waiting for period reset
+    AllowanceExhausted,
 }
 
 /// MetaMask Smart Account Client
@@ -71,7 +81,10 @@ pub struct MetaMaskClient {
     /// User's wallet address
     wallet_address: Arc<RwLock<Option<String>>>,
     /// Snap ID for communication (demo value)
+    #[allow(dead_code)]
     snap_id: String,
+    /// Every spend, keyed to the grant it was charged against
+    audit: std::sync::Mutex<AuditLog>,
 }
 
 impl MetaMaskClient {
@@ -82,9 +95,21 @@ impl MetaMaskClient {
             permission: Arc::new(RwLock::new(None)),
             wallet_address: Arc::new(RwLock::new(None)),
             snap_id: "npm:polyshark-metamask-snap".to_string(),
+            audit: std::sync::Mutex::new(AuditLog::new()),
         }
     }
 
+    /// Record spends to an (optionally persisted) audit log
+    pub fn with_audit_log(mut self, audit: AuditLog) -> Self {
+        self.audit = std::sync::Mutex::new(audit);
+        self
+    }
+
+    /// Spending audit trail
+    pub fn audit_log(&self) -> std::sync::MutexGuard<'_, AuditLog> {
+        self.audit.lock().unwrap()
+    }
+
     /// Get current connection status
     pub async fn get_status(&self) -> ConnectionStatus {
         self.status.read().await.clone()
@@ -146,6 +171,8 @@ impl MetaMaskClient {
                     AgentStatus::Idle
                 } else if p.expires_at < Self::current_timestamp() {
                     AgentStatus::PermissionExpired
+                } else if p.spent_today >= p.daily_limit {
+                    AgentStatus::AllowanceExhausted
                 } else {
                     AgentStatus::Running
                 }
@@ -172,7 +199,7 @@ impl MetaMaskClient {
         tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
         
         // Demo: Generate a fake address
-        let address = format!("0x{}", hex::encode(&[0xDE, 0xAD, 0xBE, 0xEF, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05,
+        let address = format!("0x{}", hex::encode([0xDE, 0xAD, 0xBE, 0xEF, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05,
                                                     0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F]));
         
         *self.wallet_address.write().await = Some(address.clone());
@@ -215,9 +242,10 @@ impl MetaMaskClient {
             token: token.to_string(),
             daily_limit,
             spent_today: 0.0,
-            expires_at: now + (duration_days as u64 * 86400),
+            expires_at: now + (duration_days as u64 * PERMISSION_PERIOD_SECS),
             granted_at: now,
             revoked: false,
+            scope: PermissionScope::default(),
         };
         
         *self.permission.write().await = Some(grant.clone());
@@ -253,6 +281,101 @@ impl MetaMaskClient {
         }
     }
 
+    /// Record a spend and append it to the audit trail under the active grant
+    pub async fn record_spend_for(&self, amount: f64, context: SpendContext) -> Result<(), MetaMaskError> {
+        self.record_spend(amount).await?;
+        if let Some(p) = &*self.permission.read().await {
+            self.audit_log().record(SpendRecord {
+                timestamp: Self::current_timestamp(),
+                permission_id: p.permission_id.clone(),
+                market_id: context.market_id,
+                token_id: context.token_id,
+                notional: amount,
+                tx_hash: context.tx_hash,
+                spent_in_period: p.spent_today,
+                daily_limit: p.daily_limit,
+                overdraft: None,
+            });
+        }
+        Ok(())
+    }
+
+    /// Audit a fill the grant refused to charge
+    ///
+    /// The fill already happened at the venue, so it is kept in the trail as
+    /// an overdraft with the refusal rather than dropped; the allowance is
+    /// left as it was.
+    pub async fn record_overdraft(&self, amount: f64, context: SpendContext, refusal: &MetaMaskError) {
+        let perm = self.permission.read().await;
+        let (permission_id, spent_in_period, daily_limit) = perm.as_ref()
+            .map_or((String::new(), 0.0, 0.0), |p| (p.permission_id.clone(), p.spent_today, p.daily_limit));
+        self.audit_log().record(SpendRecord {
+            timestamp: Self::current_timestamp(),
+            permission_id,
+            market_id: context.market_id,
+            token_id: context.token_id,
+            notional: amount,
+            tx_hash: context.tx_hash,
+            spent_in_period,
+            daily_limit,
+            overdraft: Some(refusal.to_string()),
+        });
+    }
+
+    /// Spend against the active grant, holding the grant for the whole spend
+    ///
+    /// `spend` is given the allowance left in the current period and returns
+    /// its result together with the amount it actually spent, which must not
+    /// exceed that allowance. Revocation, replacement and period resets wait
+    /// until the spend is charged, so every spend is bounded by - and audited
+    /// under - the grant that was active while it executed.
+    pub async fn spend_with<T>(
+        &self,
+        context: SpendContext,
+        spend: impl FnOnce(f64) -> Option<(T, f64)>,
+    ) -> Result<Option<T>, MetaMaskError> {
+        let mut perm = self.permission.write().await;
+        let p = perm.as_mut().ok_or(MetaMaskError::NoPermission)?;
+        if p.revoked {
+            return Err(MetaMaskError::PermissionRevoked);
+        }
+        if p.expires_at < Self::current_timestamp() {
+            return Err(MetaMaskError::PermissionExpired);
+        }
+        let remaining = (p.daily_limit - p.spent_today).max(0.0);
+        let Some((value, amount)) = spend(remaining) else {
+            return Ok(None);
+        };
+        debug_assert!(amount <= remaining + 1e-9, "spend of {} exceeds remaining allowance {}", amount, remaining);
+        p.spent_today += amount;
+        self.audit_log().record(SpendRecord {
+            timestamp: Self::current_timestamp(),
+            permission_id: p.permission_id.clone(),
+            market_id: context.market_id,
+            token_id: context.token_id,
+            notional: amount,
+            tx_hash: context.tx_hash,
+            spent_in_period: p.spent_today,
+            daily_limit: p.daily_limit,
+            overdraft: None,
+        });
+        Ok(Some(value))
+    }
+
+    /// Check that a call falls within the granted scope
+    pub async fn authorize_call(&self, call: &ContractCall) -> Result<(), MetaMaskError> {
+        let perm = self.permission.read().await;
+        match &*perm {
+            Some(p) => {
+                if p.revoked {
+                    return Err(MetaMaskError::PermissionRevoked);
+                }
+                p.scope.check(call).map_err(|v| MetaMaskError::OutOfScope(v.to_string()))
+            }
+            None => Err(MetaMaskError::NoPermission),
+        }
+    }
+
     /// Reset daily spend (called at midnight UTC)
     pub async fn reset_daily_spend(&self) {
         let mut perm = self.permission.write().await;
@@ -262,6 +385,17 @@ impl MetaMaskClient {
         }
     }
 
+    /// Get timestamp of the next permission period reset
+    ///
+    /// Periods are aligned to `granted_at` in `PERMISSION_PERIOD_SECS` steps.
+    pub async fn next_reset_at(&self) -> Option<u64> {
+        let perm = self.permission.read().await;
+        perm.as_ref().map(|p| {
+            let elapsed = Self::current_timestamp().saturating_sub(p.granted_at);
+            p.granted_at + (elapsed / PERMISSION_PERIOD_SECS + 1) * PERMISSION_PERIOD_SECS
+        })
+    }
+
     /// Revoke the current permission
     pub async fn revoke_permission(&self) -> Result<(), MetaMaskError> {
         let mut perm = self.permission.write().await;
@@ -310,6 +444,7 @@ pub enum MetaMaskError {
     InsufficientAllowance,
     TransactionFailed(String),
     ConnectionFailed(String),
+    OutOfScope(String),
 }
 
 impl std::fmt::Display for MetaMaskError {
@@ -323,6 +458,7 @@ impl std::fmt::Display for MetaMaskError {
             Self::InsufficientAllowance => write!(f, "Insufficient daily allowance"),
             Self::TransactionFailed(msg) => write!(f, "Transaction failed: {}", msg),
             Self::ConnectionFailed(msg) => write!(f, "Connection failed: {}", msg),
+            Self::OutOfScope(msg) => write!(f, "Call outside permission scope: {}", msg),
         }
     }
 }
@@ -332,6 +468,7 @@ impl std::error::Error for MetaMaskError {}
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::audit::AuditQuery;
 
     #[tokio::test]
     async fn test_permission_lifecycle() {
@@ -362,4 +499,20 @@ mod tests {
         client.revoke_permission().await.unwrap();
         assert!(!client.has_valid_permission().await);
     }
+
+    #[tokio::test]
+    async fn test_refused_fill_is_audited_as_overdraft() {
+        let client = MetaMaskClient::new();
+        client.connect().await.unwrap();
+        client.request_permission("USDC", 10.0, 30).await.unwrap();
+        let context = SpendContext { market_id: "m1".to_string(), token_id: "t1".to_string(), tx_hash: None };
+        let refusal = client.record_spend_for(12.0, context.clone()).await.unwrap_err();
+        client.record_overdraft(12.0, context, &refusal).await;
+
+        let records = client.audit_log().query(&AuditQuery::default());
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].notional, 12.0);
+        assert!(records[0].overdraft.is_some());
+        assert_eq!(client.get_remaining_allowance().await, 10.0, "an overdraft is not charged to the allowance");
+    }
 }
@@ -2,16 +2,120 @@
 // Real-time metrics and health monitoring
 
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use chrono::{DateTime, Utc};
 
+/// Rolling windows reported, in trades
+pub const ROLLING_WINDOWS: [usize; 2] = [20, 100];
+/// Outcomes kept for the rolling windows (the longest one)
+const OUTCOME_HISTORY: usize = 100;
+
+/// Win rate and average PnL per trade over the last `window` trades
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WindowStats {
+    pub window: usize,
+    /// Trades in the window so far (< window early on)
+    pub trades: usize,
+    pub wins: usize,
+    pub win_rate: f64,
+    pub avg_pnl: f64,
+}
+
+/// Trade outcomes: exact counters for the totals, a ring buffer for the rolling windows
+#[derive(Debug, Clone, Default)]
+pub struct TradeOutcomes {
+    recent: VecDeque<f64>,
+    trades: u64,
+    wins: u64,
+    /// Positive = consecutive wins, negative = consecutive losses
+    current_streak: i64,
+    longest_win_streak: u64,
+    longest_loss_streak: u64,
+}
+
+impl TradeOutcomes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A closed trade; anything but a profit counts as a loss
+    pub fn record(&mut self, profit: f64) {
+        let win = profit > 0.0;
+        self.trades += 1;
+        if win {
+            self.wins += 1;
+        }
+        self.current_streak = match (win, self.current_streak) {
+            (true, s) if s > 0 => s + 1,
+            (true, _) => 1,
+            (false, s) if s < 0 => s - 1,
+            (false, _) => -1,
+        };
+        match win {
+            true => self.longest_win_streak = self.longest_win_streak.max(self.current_streak as u64),
+            false => self.longest_loss_streak = self.longest_loss_streak.max(self.current_streak.unsigned_abs()),
+        }
+        if self.recent.len() == OUTCOME_HISTORY {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(profit);
+    }
+
+    pub fn trades(&self) -> u64 {
+        self.trades
+    }
+
+    pub fn wins(&self) -> u64 {
+        self.wins
+    }
+
+    /// Exact: wins / trades from integer counts
+    pub fn win_rate(&self) -> f64 {
+        match self.trades {
+            0 => 0.0,
+            n => self.wins as f64 / n as f64,
+        }
+    }
+
+    /// Stats over the last `window` trades (at most `OUTCOME_HISTORY`)
+    pub fn window(&self, window: usize) -> WindowStats {
+        let recent: Vec<f64> = self.recent.iter().rev().take(window).copied().collect();
+        let wins = recent.iter().filter(|p| **p > 0.0).count();
+        let (win_rate, avg_pnl) = match recent.len() {
+            0 => (0.0, 0.0),
+            n => (wins as f64 / n as f64, recent.iter().sum::<f64>() / n as f64),
+        };
+        WindowStats { window, trades: recent.len(), wins, win_rate, avg_pnl }
+    }
+
+    pub fn current_streak(&self) -> i64 {
+        self.current_streak
+    }
+
+    pub fn longest_win_streak(&self) -> u64 {
+        self.longest_win_streak
+    }
+
+    pub fn longest_loss_streak(&self) -> u64 {
+        self.longest_loss_streak
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentMetrics {
     // Performance
     pub trades_today: u32,
     pub trades_total: u64,
+    pub wins_total: u64,
     pub win_rate: f64,
+    /// Over the last `ROLLING_WINDOWS` trades
+    pub rolling: Vec<WindowStats>,
+    /// Positive = consecutive wins, negative = consecutive losses
+    pub current_streak: i64,
+    pub longest_win_streak: u64,
+    pub longest_loss_streak: u64,
     pub avg_profit_per_trade: f64,
     pub total_pnl: f64,
     pub daily_pnl: f64,
@@ -45,7 +149,12 @@ impl Default for AgentMetrics {
         Self {
             trades_today: 0,
             trades_total: 0,
+            wins_total: 0,
             win_rate: 0.0,
+            rolling: Vec::new(),
+            current_streak: 0,
+            longest_win_streak: 0,
+            longest_loss_streak: 0,
             avg_profit_per_trade: 0.0,
             total_pnl: 0.0,
             daily_pnl: 0.0,
@@ -68,20 +177,31 @@ impl Default for AgentMetrics {
     }
 }
 
+#[derive(Clone)]
 pub struct MetricsCollector {
     metrics: Arc<RwLock<AgentMetrics>>,
+    outcomes: Arc<RwLock<TradeOutcomes>>,
     start_time: DateTime<Utc>,
 }
 
+impl Default for MetricsCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MetricsCollector {
     pub fn new() -> Self {
         Self {
             metrics: Arc::new(RwLock::new(AgentMetrics::default())),
+            outcomes: Arc::new(RwLock::new(TradeOutcomes::new())),
             start_time: Utc::now(),
         }
     }
 
     pub async fn record_trade(&self, profit: f64, gas_cost: f64) {
+        let mut outcomes = self.outcomes.write().await;
+        outcomes.record(profit);
         let mut metrics = self.metrics.write().await;
         
         metrics.trades_today += 1;
@@ -91,14 +211,13 @@ impl MetricsCollector {
         metrics.gas_spent_eth += gas_cost;
         metrics.last_trade_time = Some(Utc::now());
         
-        // Calculate win rate
-        if profit > 0.0 {
-            metrics.win_rate = (metrics.win_rate * (metrics.trades_total - 1) as f64 + 1.0) 
-                / metrics.trades_total as f64;
-        } else {
-            metrics.win_rate = (metrics.win_rate * (metrics.trades_total - 1) as f64) 
-                / metrics.trades_total as f64;
-        }
+        // Win rates and streaks from the outcome history (no incremental drift)
+        metrics.wins_total = outcomes.wins();
+        metrics.win_rate = outcomes.win_rate();
+        metrics.rolling = ROLLING_WINDOWS.iter().map(|w| outcomes.window(*w)).collect();
+        metrics.current_streak = outcomes.current_streak();
+        metrics.longest_win_streak = outcomes.longest_win_streak();
+        metrics.longest_loss_streak = outcomes.longest_loss_streak();
         
         // Calculate average profit
         metrics.avg_profit_per_trade = metrics.total_pnl / metrics.trades_total as f64;
@@ -164,6 +283,9 @@ impl MetricsCollector {
     // Export metrics for Prometheus
     pub async fn export_prometheus(&self) -> String {
         let metrics = self.get_metrics().await;
+        let rolling: String = metrics.rolling.iter()
+            .map(|w| format!("arbishark_win_rate_rolling{{window=\"{}\"}} {}\n", w.window, w.win_rate))
+            .collect();
         
         format!(
             "# HELP arbishark_trades_total Total number of trades\n\
@@ -188,13 +310,23 @@ impl MetricsCollector {
              \n\
              # HELP arbishark_safe_mode Safe mode status (1=enabled, 0=disabled)\n\
              # TYPE arbishark_safe_mode gauge\n\
-             arbishark_safe_mode {}\n",
+             arbishark_safe_mode {}\n\
+             \n\
+             # HELP arbishark_win_rate_rolling Win rate over the last N trades\n\
+             # TYPE arbishark_win_rate_rolling gauge\n\
+             {}\
+             \n\
+             # HELP arbishark_streak Current streak (positive = wins, negative = losses)\n\
+             # TYPE arbishark_streak gauge\n\
+             arbishark_streak {}\n",
             metrics.trades_total,
             metrics.win_rate,
             metrics.total_pnl,
             metrics.envio_latency_ms,
             metrics.gas_saved_vs_l1,
-            if metrics.is_safe_mode { 1 } else { 0 }
+            if metrics.is_safe_mode { 1 } else { 0 },
+            rolling,
+            metrics.current_streak
         )
     }
 }
@@ -216,6 +348,29 @@ mod tests {
         assert!(metrics.win_rate > 0.0);
     }
 
+    #[tokio::test]
+    async fn test_exact_and_rolling_win_rates() {
+        let collector = MetricsCollector::new();
+        // 90 trades of win, win, loss, then 10 losses
+        for i in 0..90 {
+            collector.record_trade(if i % 3 == 2 { -1.0 } else { 2.0 }, 0.0).await;
+        }
+        for _ in 0..10 {
+            collector.record_trade(-0.5, 0.0).await;
+        }
+
+        let metrics = collector.get_metrics().await;
+        assert_eq!((metrics.trades_total, metrics.wins_total), (100, 60));
+        assert_eq!(metrics.win_rate, 0.6);
+        let last_20 = &metrics.rolling[0];
+        assert_eq!((last_20.window, last_20.trades, last_20.wins), (20, 20, 6));
+        assert_eq!(last_20.win_rate, 0.3);
+        assert!((last_20.avg_pnl - (6.0 * 2.0 - 4.0 - 5.0) / 20.0).abs() < 1e-12);
+        assert_eq!(metrics.rolling[1].win_rate, 0.6);
+        assert_eq!((metrics.current_streak, metrics.longest_win_streak, metrics.longest_loss_streak), (-11, 2, 11));
+        assert!(collector.export_prometheus().await.contains("arbishark_win_rate_rolling{window=\"20\"} 0.3\n"));
+    }
+
     #[tokio::test]
     async fn test_spending_tracking() {
         let collector = MetricsCollector::new();
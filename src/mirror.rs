@@ -0,0 +1,180 @@
+//! Mirrored market detection: near-identical questions on one resolution
+//! source, signalled when YES on the cheap one plus NO on the rich one costs under $1
+
+use crate::config::MirrorConfig;
+use crate::mapping::{jaccard, question_tokens};
+use crate::types::Market;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+
+/// Gap observations kept per pair
+const GAP_HISTORY: usize = 100;
+
+/// Two markets judged to resolve on the same event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MirrorPair {
+    pub market_a: String,
+    pub market_b: String,
+    /// Question similarity in [0, 1]
+    pub similarity: f64,
+}
+
+/// Relative-value opportunity across a mirrored pair
+#[derive(Debug, Clone, Serialize)]
+pub struct RelativeValueSignal {
+    /// Market to buy YES on
+    pub cheap_market: String,
+    /// Market to buy NO on
+    pub rich_market: String,
+    pub yes_price: f64,
+    pub no_price: f64,
+    /// Current YES price gap (rich - cheap)
+    pub gap: f64,
+    /// Mean gap over the recorded history
+    pub mean_gap: f64,
+    /// Expected profit per unit: 1 - (yes_price + no_price)
+    pub edge: f64,
+    pub similarity: f64,
+}
+
+/// Finds mirrored markets and monitors their price gaps
+#[derive(Debug)]
+pub struct MirrorDetector {
+    config: MirrorConfig,
+    gaps: HashMap<(String, String), VecDeque<f64>>,
+}
+
+impl MirrorDetector {
+    pub fn new(config: MirrorConfig) -> Self {
+        Self { config, gaps: HashMap::new() }
+    }
+
+    fn same_source(&self, a: &Market, b: &Market) -> bool {
+        let (sa, sb) = (a.resolution_source.trim(), b.resolution_source.trim());
+        if sa.is_empty() || sb.is_empty() {
+            return self.config.allow_unknown_source;
+        }
+        sa.eq_ignore_ascii_case(sb)
+    }
+
+    /// Pairs of active binary markets that look like the same event
+    pub fn find_pairs(&self, markets: &[Market]) -> Vec<MirrorPair> {
+        let binary: Vec<&Market> = markets.iter()
+            .filter(|m| m.active && m.accepting_orders && m.outcome_prices.len() == 2)
+            .collect();
+        let tokens: Vec<_> = binary.iter().map(|m| question_tokens(&m.question)).collect();
+        let mut pairs = Vec::new();
+        for i in 0..binary.len() {
+            for j in i + 1..binary.len() {
+                if !self.same_source(binary[i], binary[j]) {
+                    continue;
+                }
+                let similarity = jaccard(&tokens[i], &tokens[j]);
+                if similarity >= self.config.min_similarity {
+                    pairs.push(MirrorPair {
+                        market_a: binary[i].id.clone(),
+                        market_b: binary[j].id.clone(),
+                        similarity,
+                    });
+                }
+            }
+        }
+        pairs
+    }
+
+    /// Record the price gap of every mirrored pair and return the pairs
+    /// whose cross-market bundle clears `min_edge`, best edge first
+    pub fn scan(&mut self, markets: &[Market]) -> Vec<RelativeValueSignal> {
+        let by_id: HashMap<&str, &Market> = markets.iter().map(|m| (m.id.as_str(), m)).collect();
+        let mut signals = Vec::new();
+        for pair in self.find_pairs(markets) {
+            let (a, b) = (by_id[pair.market_a.as_str()], by_id[pair.market_b.as_str()]);
+            let (cheap, rich) = if a.yes_price() <= b.yes_price() { (a, b) } else { (b, a) };
+            let gap = rich.yes_price() - cheap.yes_price();
+
+            let history = self.gaps.entry((pair.market_a.clone(), pair.market_b.clone())).or_default();
+            history.push_back(gap);
+            if history.len() > GAP_HISTORY {
+                history.pop_front();
+            }
+            let mean_gap = history.iter().sum::<f64>() / history.len() as f64;
+
+            let edge = 1.0 - (cheap.yes_price() + rich.no_price());
+            if edge >= self.config.min_edge {
+                signals.push(RelativeValueSignal {
+                    cheap_market: cheap.id.clone(),
+                    rich_market: rich.id.clone(),
+                    yes_price: cheap.yes_price(),
+                    no_price: rich.no_price(),
+                    gap,
+                    mean_gap,
+                    edge,
+                    similarity: pair.similarity,
+                });
+            }
+        }
+        // Forget pairs that are no longer listed
+        self.gaps.retain(|(a, b), _| by_id.contains_key(a.as_str()) && by_id.contains_key(b.as_str()));
+        signals.sort_by(|x, y| y.edge.total_cmp(&x.edge));
+        signals
+    }
+
+    /// Number of pairs whose gap is being monitored
+    pub fn monitored_pairs(&self) -> usize {
+        self.gaps.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn market(id: &str, question: &str, source: &str, yes: f64, no: f64) -> Market {
+        Market {
+            id: id.to_string(),
+            question: question.to_string(),
+            outcome_prices: vec![yes, no],
+            resolution_source: source.to_string(),
+            ..testing::market()
+        }
+    }
+
+    fn fed_markets() -> Vec<Market> {
+        let ap = "https://apnews.com";
+        vec![
+            market("m1", "Will the Fed cut rates in March 2025?", ap, 0.40, 0.61),
+            market("m2", "Will the Fed cut rates in March, 2025?", ap, 0.52, 0.50),
+            // Same question, different resolution source
+            market("m3", "Will the Fed cut rates in March 2025?", "https://reuters.com", 0.20, 0.81),
+            market("m4", "Who wins the Super Bowl?", ap, 0.30, 0.70),
+        ]
+    }
+
+    #[test]
+    fn test_mirrors_share_question_and_source() {
+        let detector = MirrorDetector::new(MirrorConfig::default());
+        let pairs = detector.find_pairs(&fed_markets());
+        assert_eq!(pairs.len(), 1);
+        assert_eq!((pairs[0].market_a.as_str(), pairs[0].market_b.as_str()), ("m1", "m2"));
+    }
+
+    #[test]
+    fn test_mirrored_pair_emits_relative_value_signal() {
+        let mut detector = MirrorDetector::new(MirrorConfig::default());
+        let signals = detector.scan(&fed_markets());
+        assert_eq!(signals.len(), 1);
+        assert_eq!(signals[0].cheap_market, "m1");
+        assert!((signals[0].edge - 0.10).abs() < 1e-9);
+        assert!((signals[0].gap - 0.12).abs() < 1e-9);
+        assert_eq!(detector.monitored_pairs(), 1);
+    }
+
+    #[test]
+    fn test_unknown_sources_pair_only_when_allowed() {
+        let unknown = vec![market("a", "Fed cut in March", "", 0.4, 0.6), market("b", "Fed cut in March", "", 0.5, 0.5)];
+        assert!(MirrorDetector::new(MirrorConfig::default()).find_pairs(&unknown).is_empty());
+        let lenient = MirrorDetector::new(MirrorConfig { allow_unknown_source: true, ..MirrorConfig::default() });
+        assert_eq!(lenient.find_pairs(&unknown).len(), 1);
+    }
+}
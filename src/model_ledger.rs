@@ -0,0 +1,257 @@
+//! Model vs reality ledger
+//!
+//! For every executed leg the engine's prediction (fill price, fee and
+//! slippage from the book it traded against, and the edge the signal
+//! promised) is persisted next to what actually happened; the realized edge
+//! is filled in when the position exits. A drift monitor compares the two
+//! over the most recent fills and alerts when the model is systematically
+//! off - mean signed error beyond configured bounds, so ordinary noise does
+//! not trip it. This is the data the fee calibrator, TCA and the safe-mode
+//! heuristics lean on.
+
+use crate::config::ModelDriftConfig;
+use crate::storage::JsonlStore;
+use serde::{Deserialize, Serialize};
+
+/// Fill price, fee, slippage and per-unit edge of one leg
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Costs {
+    pub fill_price: f64,
+    /// Total fee ($) for the leg
+    pub fee: f64,
+    /// Price impact vs the midpoint (fraction)
+    pub slippage: f64,
+    /// Profit per unit ($); actual is None until the position exits
+    pub edge: Option<f64>,
+}
+
+/// Prediction and outcome of one executed leg
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelRecord {
+    pub market_id: String,
+    pub token_id: String,
+    pub size: f64,
+    pub timestamp: u64,
+    pub predicted: Costs,
+    pub actual: Costs,
+}
+
+impl ModelRecord {
+    /// Actual fill price vs predicted (bps, positive = paid more)
+    pub fn price_error_bps(&self) -> f64 {
+        match self.predicted.fill_price > 0.0 {
+            true => (self.actual.fill_price - self.predicted.fill_price) / self.predicted.fill_price * 10_000.0,
+            false => 0.0,
+        }
+    }
+
+    /// Actual fee rate vs predicted (bps of notional)
+    pub fn fee_error_bps(&self) -> f64 {
+        let rate = |c: &Costs| if c.fill_price * self.size > 0.0 { c.fee / (c.fill_price * self.size) } else { 0.0 };
+        (rate(&self.actual) - rate(&self.predicted)) * 10_000.0
+    }
+
+    pub fn slippage_error_bps(&self) -> f64 {
+        (self.actual.slippage - self.predicted.slippage) * 10_000.0
+    }
+
+    /// Predicted minus realized edge per unit (positive = model too optimistic)
+    pub fn edge_error(&self) -> Option<f64> {
+        Some(self.predicted.edge? - self.actual.edge?)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum LedgerEvent {
+    Fill(ModelRecord),
+    Realized { token_id: String, edge: f64, at: u64 },
+}
+
+/// A metric whose mean error is out of bounds
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DriftBreach {
+    pub metric: &'static str,
+    pub mean_error: f64,
+    pub bound: f64,
+}
+
+impl std::fmt::Display for DriftBreach {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} mean error {:+.4} beyond ±{}", self.metric, self.mean_error, self.bound)
+    }
+}
+
+/// Mean signed model errors over the recent window
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DriftReport {
+    pub samples: usize,
+    pub price_error_bps: f64,
+    pub fee_error_bps: f64,
+    pub slippage_error_bps: f64,
+    /// Over the legs that have exited
+    pub edge_samples: usize,
+    pub edge_error: f64,
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> (usize, f64) {
+    let (n, sum) = values.fold((0, 0.0), |(n, sum), v| (n + 1, sum + v));
+    (n, if n > 0 { sum / n as f64 } else { 0.0 })
+}
+
+/// Predicted vs actual costs of executed legs, with a drift monitor
+#[derive(Debug)]
+pub struct ModelLedger {
+    config: ModelDriftConfig,
+    records: Vec<ModelRecord>,
+    /// Time of the last drift alert (alerts are rate-limited)
+    last_alert: Option<u64>,
+    journal: Option<JsonlStore>,
+}
+
+impl ModelLedger {
+    pub fn new(config: ModelDriftConfig) -> Self {
+        Self { config, records: Vec::new(), last_alert: None, journal: None }
+    }
+
+    /// Journal to `journal`, replaying the records already in it
+    pub fn with_journal(mut self, journal: JsonlStore) -> Self {
+        let events: Vec<LedgerEvent> = journal.load().unwrap_or_else(|e| {
+            eprintln!("⚠️ [Model] Failed to read model ledger: {}", e);
+            Vec::new()
+        });
+        for event in events {
+            self.apply(event);
+        }
+        self.journal = Some(journal);
+        self
+    }
+
+    fn apply(&mut self, event: LedgerEvent) {
+        match event {
+            LedgerEvent::Fill(record) => self.records.push(record),
+            LedgerEvent::Realized { token_id, edge, .. } => {
+                if let Some(record) = self.records.iter_mut().rev().find(|r| r.token_id == token_id && r.actual.edge.is_none()) {
+                    record.actual.edge = Some(edge);
+                }
+            }
+        }
+    }
+
+    fn record(&mut self, event: LedgerEvent) {
+        if let Some(journal) = &self.journal {
+            if let Err(e) = journal.append(&event) {
+                eprintln!("⚠️ [Model] Failed to journal model ledger: {}", e);
+            }
+        }
+        self.apply(event);
+    }
+
+    /// Persist an executed leg's prediction next to its fill
+    pub fn record_fill(&mut self, record: ModelRecord) {
+        self.record(LedgerEvent::Fill(record));
+    }
+
+    /// Realized per-unit edge of the latest open leg on `token_id`
+    pub fn record_realized(&mut self, token_id: &str, edge: f64, now: u64) {
+        if self.records.iter().any(|r| r.token_id == token_id && r.actual.edge.is_none()) {
+            self.record(LedgerEvent::Realized { token_id: token_id.to_string(), edge, at: now });
+        }
+    }
+
+    pub fn records(&self) -> &[ModelRecord] {
+        &self.records
+    }
+
+    /// Mean errors over the last `window` fills
+    pub fn drift(&self) -> DriftReport {
+        let recent = &self.records[self.records.len().saturating_sub(self.config.window)..];
+        let (samples, price_error_bps) = mean(recent.iter().map(ModelRecord::price_error_bps));
+        let (edge_samples, edge_error) = mean(recent.iter().filter_map(ModelRecord::edge_error));
+        DriftReport {
+            samples,
+            price_error_bps,
+            fee_error_bps: mean(recent.iter().map(ModelRecord::fee_error_bps)).1,
+            slippage_error_bps: mean(recent.iter().map(ModelRecord::slippage_error_bps)).1,
+            edge_samples,
+            edge_error,
+        }
+    }
+
+    /// Metrics out of bounds, once there are enough samples
+    pub fn breaches(&self) -> Vec<DriftBreach> {
+        let report = self.drift();
+        let mut breaches = Vec::new();
+        let mut check = |metric, samples: usize, mean_error: f64, bound: f64| {
+            if samples >= self.config.min_samples && mean_error.abs() > bound {
+                breaches.push(DriftBreach { metric, mean_error, bound });
+            }
+        };
+        check("fill_price_bps", report.samples, report.price_error_bps, self.config.max_price_error_bps);
+        check("fee_bps", report.samples, report.fee_error_bps, self.config.max_fee_error_bps);
+        check("slippage_bps", report.samples, report.slippage_error_bps, self.config.max_slippage_error_bps);
+        check("edge", report.edge_samples, report.edge_error, self.config.max_edge_error);
+        breaches
+    }
+
+    /// Breaches to alert on now (at most once per `alert_cooldown_secs`)
+    pub fn check(&mut self, now: u64) -> Vec<DriftBreach> {
+        if !self.config.enabled || self.last_alert.is_some_and(|t| now.saturating_sub(t) < self.config.alert_cooldown_secs) {
+            return Vec::new();
+        }
+        let breaches = self.breaches();
+        if !breaches.is_empty() {
+            self.last_alert = Some(now);
+        }
+        breaches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(token_id: &str, predicted_price: f64, actual_price: f64) -> ModelRecord {
+        ModelRecord {
+            market_id: "m1".to_string(),
+            token_id: token_id.to_string(),
+            size: 10.0,
+            timestamp: 0,
+            predicted: Costs { fill_price: predicted_price, fee: 0.1, slippage: 0.01, edge: Some(0.02) },
+            actual: Costs { fill_price: actual_price, fee: 0.1, slippage: 0.01, edge: None },
+        }
+    }
+
+    #[test]
+    fn test_drift_alerts_and_journal_replay() {
+        let config = ModelDriftConfig { min_samples: 3, max_price_error_bps: 50.0, alert_cooldown_secs: 600, ..Default::default() };
+        let dir = std::env::temp_dir().join(format!("arbishark_model_{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+        let journal = JsonlStore::open(dir, "model_ledger.jsonl").unwrap();
+        let mut ledger = ModelLedger::new(config.clone()).with_journal(journal.clone());
+
+        // Noise around the prediction: no drift
+        ledger.record_fill(record("a", 0.50, 0.503));
+        ledger.record_fill(record("b", 0.50, 0.497));
+        ledger.record_fill(record("c", 0.50, 0.50));
+        assert!(ledger.check(100).is_empty());
+
+        // Systematically paying 2% more than predicted
+        for token in ["d", "e", "f"] {
+            ledger.record_fill(record(token, 0.50, 0.51));
+        }
+        let breaches = ledger.check(200);
+        assert_eq!(breaches.len(), 1);
+        assert_eq!(breaches[0].metric, "fill_price_bps");
+        assert!(ledger.check(300).is_empty(), "cooldown");
+        assert_eq!(ledger.check(900).len(), 1);
+
+        ledger.record_realized("a", -0.01, 400);
+        assert_eq!(ledger.records()[0].edge_error(), Some(0.03));
+        let restored = ModelLedger::new(config).with_journal(journal);
+        assert_eq!(restored.records().len(), 6);
+        assert_eq!(restored.records()[0].actual.edge, Some(-0.01));
+        assert_eq!(restored.drift().edge_samples, 1);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
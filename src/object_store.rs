@@ -0,0 +1,373 @@
+// S3-compatible object storage for recordings, reports and exports
+// Uploads journaled so each version is sent once; `arbishark pull` fetches them back
+
+use crate::config::ObjectStoreConfig;
+use crate::storage::JsonlStore;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const ENDPOINT_ENV: &str = "ARBISHARK_S3_ENDPOINT";
+const BUCKET_ENV: &str = "ARBISHARK_S3_BUCKET";
+const REGION_ENV: &str = "ARBISHARK_S3_REGION";
+const PREFIX_ENV: &str = "ARBISHARK_S3_PREFIX";
+
+/// What an object holds, which decides its key prefix
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectKind {
+    Recording,
+    Report,
+    Export,
+    Archive,
+}
+
+impl ObjectKind {
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Self::Recording => "recordings/",
+            Self::Report => "reports/",
+            Self::Export => "exports/",
+            Self::Archive => "archive/",
+        }
+    }
+}
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// RFC 3986 percent-encoding as SigV4 expects it (`/` kept in paths)
+fn uri_encode(s: &str, keep_slash: bool) -> String {
+    s.bytes().map(|b| match b {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+        b'/' if keep_slash => "/".to_string(),
+        _ => format!("%{:02X}", b),
+    }).collect()
+}
+
+fn xml_unescape(s: &str) -> String {
+    s.replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&apos;", "'").replace("&amp;", "&")
+}
+
+/// Text of every `<tag>` element in `xml`
+fn xml_values(xml: &str, tag: &str) -> Vec<String> {
+    let (open, close) = (format!("<{}>", tag), format!("</{}>", tag));
+    xml.split(&open).skip(1).filter_map(|rest| rest.split_once(&close).map(|(value, _)| xml_unescape(value))).collect()
+}
+
+/// An S3-compatible bucket (AWS, MinIO, R2, ...) addressed path-style, SigV4-signed
+#[derive(Debug, Clone)]
+pub struct ObjectStore {
+    endpoint: String,
+    bucket: String,
+    region: String,
+    prefix: String,
+    access_key: String,
+    secret_key: String,
+    client: reqwest::Client,
+}
+
+impl ObjectStore {
+    /// None unless enabled with an endpoint and bucket, and both credential variables are set
+    pub fn from_config(config: &ObjectStoreConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        let setting = |env: &str, value: &str| std::env::var(env).ok().filter(|v| !v.is_empty()).unwrap_or_else(|| value.to_string());
+        let endpoint = setting(ENDPOINT_ENV, &config.endpoint);
+        let bucket = setting(BUCKET_ENV, &config.bucket);
+        if endpoint.is_empty() || bucket.is_empty() {
+            return None;
+        }
+        Some(Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            bucket,
+            region: setting(REGION_ENV, &config.region),
+            prefix: setting(PREFIX_ENV, &config.prefix),
+            access_key: std::env::var(&config.access_key_env).ok()?,
+            secret_key: std::env::var(&config.secret_key_env).ok()?,
+            client: reqwest::Client::new(),
+        })
+    }
+
+    /// Key of `name` under this agent's prefix
+    pub fn key(&self, kind: ObjectKind, name: &str) -> String {
+        format!("{}{}{}", self.prefix, kind.prefix(), name)
+    }
+
+    /// `Authorization`, `x-amz-date` and `x-amz-content-sha256` for a request (AWS Signature V4);
+    /// `path` and `query` are already canonical (encoded, query parameters sorted)
+    fn sign(&self, method: &str, host: &str, path: &str, query: &str, payload: &[u8], now: DateTime<Utc>) -> Vec<(&'static str, String)> {
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = hex::encode(Sha256::digest(payload));
+        let canonical = format!(
+            "{}\n{}\n{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\nhost;x-amz-content-sha256;x-amz-date\n{}",
+            method, path, query, host, payload_hash, amz_date, payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let to_sign = format!("AWS4-HMAC-SHA256\n{}\n{}\n{}", amz_date, scope, hex::encode(Sha256::digest(canonical.as_bytes())));
+        let key = ["s3", "aws4_request"].iter().fold(
+            hmac(&hmac(format!("AWS4{}", self.secret_key).as_bytes(), &date), &self.region),
+            |key, part| hmac(&key, part),
+        );
+        let signature = hex::encode(hmac(&key, &to_sign));
+        vec![
+            ("authorization", format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={}",
+                self.access_key, scope, signature
+            )),
+            ("x-amz-date", amz_date),
+            ("x-amz-content-sha256", payload_hash),
+        ]
+    }
+
+    /// `key` = None addresses the bucket itself
+    async fn request(&self, method: reqwest::Method, key: Option<&str>, query: &[(&str, &str)], body: Vec<u8>) -> Result<reqwest::Response, String> {
+        let path = match key {
+            Some(key) => format!("/{}/{}", uri_encode(&self.bucket, false), uri_encode(key, true)),
+            None => format!("/{}", uri_encode(&self.bucket, false)),
+        };
+        let mut params: Vec<(String, String)> = query.iter().map(|(k, v)| (uri_encode(k, false), uri_encode(v, false))).collect();
+        params.sort();
+        let query = params.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join("&");
+        let url = reqwest::Url::parse(&format!("{}{}{}{}", self.endpoint, path, if query.is_empty() { "" } else { "?" }, query))
+            .map_err(|e| e.to_string())?;
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+        let mut request = self.client.request(method.clone(), url);
+        for (name, value) in self.sign(method.as_str(), &host, &path, &query, &body, Utc::now()) {
+            request = request.header(name, value);
+        }
+        let resp = request.body(body).send().await.map_err(|e| e.to_string())?;
+        if !resp.status().is_success() {
+            return Err(format!("{} {} returned {}", method, key.unwrap_or(&self.bucket), resp.status()));
+        }
+        Ok(resp)
+    }
+
+    pub async fn put(&self, key: &str, body: Vec<u8>) -> Result<(), String> {
+        self.request(reqwest::Method::PUT, Some(key), &[], body).await.map(|_| ())
+    }
+
+    pub async fn get(&self, key: &str) -> Result<Vec<u8>, String> {
+        let resp = self.request(reqwest::Method::GET, Some(key), &[], Vec::new()).await?;
+        resp.bytes().await.map(|b| b.to_vec()).map_err(|e| e.to_string())
+    }
+
+    pub async fn delete(&self, key: &str) -> Result<(), String> {
+        self.request(reqwest::Method::DELETE, Some(key), &[], Vec::new()).await.map(|_| ())
+    }
+
+    /// Every key starting with `prefix` (ListObjectsV2, all pages)
+    pub async fn list(&self, prefix: &str) -> Result<Vec<String>, String> {
+        let mut keys = Vec::new();
+        let mut token: Option<String> = None;
+        loop {
+            let mut query = vec![("list-type", "2"), ("prefix", prefix)];
+            if let Some(token) = &token {
+                query.push(("continuation-token", token));
+            }
+            let resp = self.request(reqwest::Method::GET, None, &query, Vec::new()).await?;
+            let xml = resp.text().await.map_err(|e| e.to_string())?;
+            keys.extend(xml_values(&xml, "Key"));
+            token = xml_values(&xml, "NextContinuationToken").pop();
+            if token.is_none() {
+                return Ok(keys);
+            }
+        }
+    }
+
+    /// Download every object under `<prefix><relative>` into `dir`, keeping the key layout below the agent prefix
+    pub async fn pull(&self, relative: &str, dir: &Path) -> Result<Vec<PathBuf>, String> {
+        let mut pulled = Vec::new();
+        for key in self.list(&format!("{}{}", self.prefix, relative)).await? {
+            let name = key.strip_prefix(&self.prefix).unwrap_or(&key);
+            if name.ends_with('/') || name.split('/').any(|part| part == "..") {
+                continue;
+            }
+            let path = dir.join(name);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            fs::write(&path, self.get(&key).await?).map_err(|e| e.to_string())?;
+            pulled.push(path);
+        }
+        Ok(pulled)
+    }
+}
+
+/// A file version sent to the bucket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct UploadRecord {
+    path: String,
+    key: String,
+    len: u64,
+    modified: u64,
+    at: u64,
+}
+
+/// One upload pass
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UploadReport {
+    pub uploaded: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+/// Size and modification time of a file, which identify its version
+fn version(path: &Path) -> io::Result<(u64, u64)> {
+    let meta = fs::metadata(path)?;
+    let modified = meta.modified()?.duration_since(std::time::UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    Ok((meta.len(), modified))
+}
+
+/// Uploads the agent's output directories, sending each file version once
+#[derive(Debug)]
+pub struct ObjectSink {
+    store: ObjectStore,
+    /// (directory, kind) pairs to upload
+    sources: Vec<(PathBuf, ObjectKind)>,
+    /// Last uploaded version per local path
+    uploaded: HashMap<String, (u64, u64)>,
+    journal: Option<JsonlStore>,
+}
+
+impl ObjectSink {
+    /// Sink for the directories `config` enables under `data_dir`
+    pub fn new(store: ObjectStore, data_dir: &str, config: &ObjectStoreConfig) -> Self {
+        let data_dir = Path::new(data_dir);
+        let sources = [
+            (config.recordings, "segments", ObjectKind::Recording),
+            (config.reports, "reports", ObjectKind::Report),
+            (config.exports, "exports", ObjectKind::Export),
+        ].into_iter().filter(|(on, _, _)| *on).map(|(_, dir, kind)| (data_dir.join(dir), kind)).collect();
+        Self { store, sources, uploaded: HashMap::new(), journal: None }
+    }
+
+    /// Sink with its upload journal under `data_dir` (None unless the store is configured)
+    pub fn open(config: &ObjectStoreConfig, data_dir: &str) -> Option<Self> {
+        let sink = Self::new(ObjectStore::from_config(config)?, data_dir, config);
+        Some(match JsonlStore::open(data_dir, "uploads.jsonl") {
+            Ok(journal) => sink.with_journal(journal),
+            Err(e) => {
+                eprintln!("⚠️ [ObjectStore] Failed to open upload journal: {}", e);
+                sink
+            }
+        })
+    }
+
+    /// Journal to `journal`, skipping the versions already uploaded in it
+    pub fn with_journal(mut self, journal: JsonlStore) -> Self {
+        let records: Vec<UploadRecord> = journal.load().unwrap_or_else(|e| {
+            eprintln!("⚠️ [ObjectStore] Failed to read upload journal: {}", e);
+            Vec::new()
+        });
+        for record in records {
+            self.uploaded.insert(record.path, (record.len, record.modified));
+        }
+        self.journal = Some(journal);
+        self
+    }
+
+    pub fn store(&self) -> &ObjectStore {
+        &self.store
+    }
+
+    /// Upload `path` as `kind` unless this version was already sent; returns the key if uploaded
+    pub async fn upload(&mut self, path: &Path, kind: ObjectKind, now: u64) -> Result<Option<String>, String> {
+        let local = path.to_string_lossy().to_string();
+        let (len, modified) = version(path).map_err(|e| format!("{}: {}", local, e))?;
+        if self.uploaded.get(&local) == Some(&(len, modified)) {
+            return Ok(None);
+        }
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let key = self.store.key(kind, &name);
+        let body = fs::read(path).map_err(|e| format!("{}: {}", local, e))?;
+        self.store.put(&key, body).await?;
+        if let Some(journal) = &self.journal {
+            let record = UploadRecord { path: local.clone(), key: key.clone(), len, modified, at: now };
+            if let Err(e) = journal.append(&record) {
+                eprintln!("⚠️ [ObjectStore] Failed to journal upload: {}", e);
+            }
+        }
+        self.uploaded.insert(local, (len, modified));
+        Ok(Some(key))
+    }
+
+    /// Upload new and changed files in every source directory
+    pub async fn sync(&mut self, now: u64) -> UploadReport {
+        let mut report = UploadReport::default();
+        for (dir, kind) in self.sources.clone() {
+            let mut files: Vec<PathBuf> = match fs::read_dir(&dir) {
+                Ok(entries) => entries.filter_map(|e| Some(e.ok()?.path())).filter(|p| p.is_file()).collect(),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => {
+                    report.errors.push(format!("{}: {}", dir.display(), e));
+                    continue;
+                }
+            };
+            files.sort();
+            for path in files {
+                match self.upload(&path, kind, now).await {
+                    Ok(Some(key)) => report.uploaded.push(key),
+                    Ok(None) => {}
+                    Err(e) => report.errors.push(e),
+                }
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> ObjectStoreConfig {
+        ObjectStoreConfig {
+            enabled: true,
+            endpoint: "http://127.0.0.1:9000/".to_string(),
+            bucket: "backtests".to_string(),
+            access_key_env: "ARBISHARK_TEST_S3_KEY".to_string(),
+            secret_key_env: "ARBISHARK_TEST_S3_SECRET".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_configuration_keys_and_signing() {
+        assert!(ObjectStore::from_config(&config()).is_none(), "credentials missing");
+        std::env::set_var("ARBISHARK_TEST_S3_KEY", "AKIDEXAMPLE");
+        std::env::set_var("ARBISHARK_TEST_S3_SECRET", "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY");
+        let store = ObjectStore::from_config(&config()).unwrap();
+        assert_eq!(store.endpoint, "http://127.0.0.1:9000");
+        assert_eq!(store.key(ObjectKind::Report, "daily-2024-01-02.json"), "arbishark/reports/daily-2024-01-02.json");
+        assert!(ObjectStore::from_config(&ObjectStoreConfig { enabled: false, ..config() }).is_none());
+
+        let now = DateTime::parse_from_rfc3339("2024-01-02T03:04:05Z").unwrap().with_timezone(&Utc);
+        let headers = store.sign("GET", "127.0.0.1:9000", "/backtests", "list-type=2&prefix=arbishark%2F", b"", now);
+        assert_eq!(headers[1], ("x-amz-date", "20240102T030405Z".to_string()));
+        assert!(headers[0].1.starts_with("AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20240102/us-east-1/s3/aws4_request,"));
+        // Deterministic for the same request, different once anything signed changes
+        assert_eq!(headers, store.sign("GET", "127.0.0.1:9000", "/backtests", "list-type=2&prefix=arbishark%2F", b"", now));
+        assert_ne!(headers[0], store.sign("GET", "127.0.0.1:9000", "/backtests", "list-type=2&prefix=other", b"", now)[0]);
+
+        assert_eq!(uri_encode("reports/a b+c.json", true), "reports/a%20b%2Bc.json");
+        let xml = "<ListBucketResult><Contents><Key>a/1.json</Key></Contents><Contents><Key>a/&amp;2</Key></Contents>\
+                   <NextContinuationToken>t1</NextContinuationToken></ListBucketResult>";
+        assert_eq!(xml_values(xml, "Key"), vec!["a/1.json", "a/&2"]);
+        assert_eq!(xml_values(xml, "NextContinuationToken"), vec!["t1"]);
+    }
+}
@@ -0,0 +1,326 @@
+// Venue order / cancel rate budgets
+// Token buckets per CLOB rate window, global and per market; bundles reserve a placement per leg up front
+
+use crate::config::{OrderRateConfig, RateWindowConfig};
+use crate::quoting::Modification;
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderAction {
+    Place,
+    Cancel,
+}
+
+impl OrderAction {
+    /// What a quote modification costs: a replace is a cancel and a new order
+    pub fn for_modification(modification: &Modification) -> &'static [OrderAction] {
+        match modification {
+            Modification::Replace(_) => &[OrderAction::Cancel, OrderAction::Place],
+            Modification::Cancel(_) => &[OrderAction::Cancel],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Bucket {
+    capacity: f64,
+    refill_per_sec: f64,
+    tokens: f64,
+    updated_ms: u64,
+}
+
+impl Bucket {
+    fn new(window: &RateWindowConfig, utilization: f64) -> Self {
+        let capacity = (window.limit as f64 * utilization).floor().max(1.0);
+        Self { capacity, refill_per_sec: capacity / window.window_secs.max(1) as f64, tokens: capacity, updated_ms: 0 }
+    }
+
+    fn level(&self, now_ms: u64) -> f64 {
+        let elapsed_secs = now_ms.saturating_sub(self.updated_ms) as f64 / 1000.0;
+        (self.tokens + elapsed_secs * self.refill_per_sec).min(self.capacity)
+    }
+
+    /// Wait (ms) until `n` tokens are available
+    fn wait_ms(&self, n: f64, now_ms: u64) -> u64 {
+        let missing = n - self.level(now_ms);
+        if missing <= 0.0 { 0 } else { (missing / self.refill_per_sec * 1000.0).ceil() as u64 }
+    }
+
+    fn spend(&mut self, n: f64, now_ms: u64) {
+        self.tokens = (self.level(now_ms) - n).max(0.0);
+        self.updated_ms = now_ms;
+    }
+}
+
+/// Buckets for every window of one action's limit
+#[derive(Debug, Clone)]
+struct Budget {
+    buckets: Vec<Bucket>,
+}
+
+impl Budget {
+    fn new(windows: &[RateWindowConfig], utilization: f64) -> Self {
+        Self { buckets: windows.iter().map(|w| Bucket::new(w, utilization)).collect() }
+    }
+
+    fn wait_ms(&self, n: f64, now_ms: u64) -> u64 {
+        self.buckets.iter().map(|b| b.wait_ms(n, now_ms)).max().unwrap_or(0)
+    }
+
+    fn spend(&mut self, n: f64, now_ms: u64) {
+        for bucket in &mut self.buckets {
+            bucket.spend(n, now_ms);
+        }
+    }
+
+    fn headroom(&self, now_ms: u64) -> Headroom {
+        let available = self.buckets.iter().map(|b| b.level(now_ms)).fold(f64::INFINITY, f64::min);
+        let fraction = self.buckets.iter().map(|b| b.level(now_ms) / b.capacity).fold(1.0, f64::min);
+        Headroom { available: if available.is_finite() { available.floor() as u64 } else { u64::MAX }, fraction }
+    }
+}
+
+/// Room left in one action's budget
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Headroom {
+    /// Actions that could go out right now
+    pub available: u64,
+    /// Fill of the tightest window (1 = untouched, 0 = exhausted)
+    pub fraction: f64,
+}
+
+/// Order actions of one market
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MarketCounters {
+    pub placed: u64,
+    pub cancelled: u64,
+    /// Bundles skipped or remainders not rested for lack of headroom
+    pub refused: u64,
+    /// Modifications queued until the budgets refill
+    pub deferred: u64,
+    /// Queued modifications superseded by a newer one for the same order
+    pub coalesced: u64,
+}
+
+/// A quote modification waiting for headroom
+#[derive(Debug, Clone)]
+pub struct Deferred {
+    pub market_id: String,
+    pub order_id: String,
+    pub modification: Modification,
+    pub deferred_at_ms: u64,
+}
+
+/// `GET /api/order_limits`
+#[derive(Debug, Clone, Serialize)]
+pub struct OrderLimitsSnapshot {
+    pub enabled: bool,
+    pub utilization: f64,
+    pub global: BTreeMap<OrderAction, Headroom>,
+    pub markets: BTreeMap<String, MarketHeadroom>,
+    /// Modifications waiting for headroom
+    pub queued: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MarketHeadroom {
+    pub headroom: BTreeMap<OrderAction, Headroom>,
+    pub counters: MarketCounters,
+}
+
+#[derive(Debug)]
+pub struct OrderRateLimits {
+    config: OrderRateConfig,
+    global: BTreeMap<OrderAction, Budget>,
+    markets: BTreeMap<(String, OrderAction), Budget>,
+    queue: VecDeque<Deferred>,
+    counters: BTreeMap<String, MarketCounters>,
+}
+
+impl OrderRateLimits {
+    pub fn new(config: OrderRateConfig) -> Self {
+        let global = BTreeMap::from([
+            (OrderAction::Place, Budget::new(&config.place, config.utilization)),
+            (OrderAction::Cancel, Budget::new(&config.cancel, config.utilization)),
+        ]);
+        Self { config, global, markets: BTreeMap::new(), queue: VecDeque::new(), counters: BTreeMap::new() }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Spend a token per action in `actions` for `market_id`, all or none; when
+    /// they don't fit, the wait (ms) until they would
+    pub fn acquire(&mut self, market_id: &str, actions: &[OrderAction], now_ms: u64) -> Result<(), u64> {
+        let wait = self.wait_ms(market_id, actions, now_ms);
+        if wait > 0 {
+            self.counters.entry(market_id.to_string()).or_default().refused += 1;
+            return Err(wait);
+        }
+        self.spend(market_id, actions, now_ms);
+        Ok(())
+    }
+
+    /// Queue a modification that did not fit, replacing one already queued for the same order
+    pub fn defer(&mut self, market_id: &str, order_id: &str, modification: Modification, now_ms: u64) {
+        let counters = self.counters.entry(market_id.to_string()).or_default();
+        if let Some(queued) = self.queue.iter_mut().find(|d| d.order_id == order_id) {
+            queued.modification = modification;
+            counters.coalesced += 1;
+            return;
+        }
+        counters.deferred += 1;
+        self.queue.push_back(Deferred {
+            market_id: market_id.to_string(),
+            order_id: order_id.to_string(),
+            modification,
+            deferred_at_ms: now_ms,
+        });
+    }
+
+    /// Queued modifications that now fit, oldest first, their tokens spent
+    pub fn release(&mut self, now_ms: u64) -> Vec<Deferred> {
+        let mut released = Vec::new();
+        for deferred in std::mem::take(&mut self.queue) {
+            let actions = OrderAction::for_modification(&deferred.modification);
+            if self.wait_ms(&deferred.market_id, actions, now_ms) == 0 {
+                self.spend(&deferred.market_id, actions, now_ms);
+                released.push(deferred);
+            } else {
+                self.queue.push_back(deferred);
+            }
+        }
+        released
+    }
+
+    /// Drop a queued modification of an order that filled or expired
+    pub fn forget(&mut self, order_id: &str) {
+        self.queue.retain(|d| d.order_id != order_id);
+    }
+
+    /// Whole actions `market_id` could send right now
+    pub fn headroom(&mut self, market_id: &str, action: OrderAction, now_ms: u64) -> u64 {
+        if !self.config.enabled {
+            return u64::MAX;
+        }
+        let global = self.global[&action].headroom(now_ms).available;
+        global.min(self.market_budget(market_id, action).headroom(now_ms).available)
+    }
+
+    pub fn snapshot(&self, now_ms: u64) -> OrderLimitsSnapshot {
+        let global = self.global.iter().map(|(action, budget)| (*action, budget.headroom(now_ms))).collect();
+        let mut markets: BTreeMap<String, MarketHeadroom> = self.counters.iter()
+            .map(|(market_id, counters)| (market_id.clone(), MarketHeadroom { headroom: BTreeMap::new(), counters: counters.clone() }))
+            .collect();
+        for ((market_id, action), budget) in &self.markets {
+            markets.entry(market_id.clone())
+                .or_insert_with(|| MarketHeadroom { headroom: BTreeMap::new(), counters: MarketCounters::default() })
+                .headroom.insert(*action, budget.headroom(now_ms));
+        }
+        OrderLimitsSnapshot {
+            enabled: self.config.enabled,
+            utilization: self.config.utilization,
+            global,
+            markets,
+            queued: self.queue.len(),
+        }
+    }
+
+    fn wait_ms(&mut self, market_id: &str, actions: &[OrderAction], now_ms: u64) -> u64 {
+        if !self.config.enabled {
+            return 0;
+        }
+        let mut wait = 0;
+        for action in [OrderAction::Place, OrderAction::Cancel] {
+            let n = actions.iter().filter(|a| **a == action).count() as f64;
+            if n > 0.0 {
+                wait = wait.max(self.global[&action].wait_ms(n, now_ms)).max(self.market_budget(market_id, action).wait_ms(n, now_ms));
+            }
+        }
+        wait
+    }
+
+    fn spend(&mut self, market_id: &str, actions: &[OrderAction], now_ms: u64) {
+        for action in [OrderAction::Place, OrderAction::Cancel] {
+            let n = actions.iter().filter(|a| **a == action).count() as f64;
+            if n > 0.0 {
+                self.global.get_mut(&action).expect("every action has a budget").spend(n, now_ms);
+                self.market_budget(market_id, action).spend(n, now_ms);
+            }
+        }
+        let counters = self.counters.entry(market_id.to_string()).or_default();
+        counters.placed += actions.iter().filter(|a| **a == OrderAction::Place).count() as u64;
+        counters.cancelled += actions.iter().filter(|a| **a == OrderAction::Cancel).count() as u64;
+    }
+
+    fn market_budget(&mut self, market_id: &str, action: OrderAction) -> &mut Budget {
+        let windows = match action {
+            OrderAction::Place => &self.config.market_place,
+            OrderAction::Cancel => &self.config.market_cancel,
+        };
+        self.markets.entry((market_id.to_string(), action)).or_insert_with(|| Budget::new(windows, self.config.utilization))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fills::PassiveOrder;
+    use crate::types::Side;
+
+    fn window(limit: u32, window_secs: u64) -> RateWindowConfig {
+        RateWindowConfig { limit, window_secs }
+    }
+
+    fn replace(price: f64) -> Modification {
+        Modification::Replace(PassiveOrder { token_id: "t1".to_string(), side: Side::Buy, price, size: 10.0, queue_ahead: 0.0, filled: 0.0 })
+    }
+
+    #[test]
+    fn test_budgets_defer_and_coalesce_until_refilled() {
+        let mut limits = OrderRateLimits::new(OrderRateConfig {
+            enabled: true,
+            utilization: 0.5,
+            place: vec![window(8, 10), window(20, 600)],
+            cancel: vec![window(8, 10)],
+            market_place: vec![window(6, 10)],
+            market_cancel: vec![window(4, 10)],
+        });
+        // Market budget: 3 placements at half utilization, refilling 0.3/s
+        assert!(limits.acquire("m1", &[OrderAction::Place; 3], 0).is_ok());
+        assert_eq!(limits.acquire("m1", &[OrderAction::Place], 0), Err(3334));
+        // The global budget (4 in the burst window) still has one for another market
+        assert!(limits.acquire("m2", &[OrderAction::Place], 0).is_ok());
+        assert_eq!(limits.headroom("m3", OrderAction::Place, 0), 0);
+
+        // Cancels are budgeted apart; a replace needs both a cancel and a placement
+        let replace_cost = OrderAction::for_modification(&replace(0.5));
+        assert!(limits.acquire("m2", &[OrderAction::Cancel], 0).is_ok());
+        assert!(limits.acquire("m2", replace_cost, 0).is_err());
+        limits.defer("m2", "o1", replace(0.5), 0);
+        limits.defer("m2", "o1", replace(0.51), 0);
+        limits.defer("m2", "o1", Modification::Cancel("signal decayed".to_string()), 0);
+        limits.defer("m2", "o2", replace(0.4), 0);
+
+        // The cancel that superseded both replaces goes out once cancels refill; o2 still waits for a placement
+        let released = limits.release(1_000);
+        assert_eq!(released.len(), 1);
+        assert_eq!(released[0].order_id, "o1");
+        assert!(matches!(&released[0].modification, Modification::Cancel(_)));
+        assert_eq!(limits.snapshot(1_000).queued, 1);
+        limits.forget("o2");
+        let snapshot = limits.snapshot(1_000);
+        assert_eq!(snapshot.queued, 0);
+        let m2 = &snapshot.markets["m2"].counters;
+        assert_eq!((m2.placed, m2.cancelled, m2.deferred, m2.coalesced, m2.refused), (1, 2, 2, 2, 1));
+        assert_eq!(snapshot.markets["m1"].counters.refused, 1);
+
+        // The sustained window binds once bursts add up: 10 placements per 10 minutes
+        assert!(limits.acquire("m4", &[OrderAction::Place; 3], 60_000).is_ok());
+        assert!(limits.acquire("m5", &[OrderAction::Place; 3], 70_000).is_ok());
+        assert!(limits.acquire("m6", &[OrderAction::Place; 3], 80_000).is_err(), "burst window refilled, sustained one has not");
+    }
+}
@@ -0,0 +1,221 @@
+// Open order registry
+// Journaled resting orders with expiries, swept and cancelled after a crash
+
+use crate::fills::PassiveOrder;
+use crate::storage::JsonlStore;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// A resting order known to the registry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestingOrder {
+    pub order_id: String,
+    pub order: PassiveOrder,
+    pub placed_at: u64,
+    /// Trade replay cursor (prints before this timestamp are already applied)
+    pub cursor: u64,
+    /// Good-til-time expiry (unix seconds, 0 = good-til-cancelled)
+    #[serde(default)]
+    pub expires_at: u64,
+}
+
+impl RestingOrder {
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at > 0 && now >= self.expires_at
+    }
+}
+
+/// Journal record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum OrderEvent {
+    Placed(RestingOrder),
+    Closed { order_id: String, reason: String },
+}
+
+/// Registry of open orders on the execution venue
+#[derive(Debug, Default)]
+pub struct OrderRegistry {
+    orders: Vec<RestingOrder>,
+    journal: Option<JsonlStore>,
+    next_id: u64,
+    /// Expiry given to newly placed orders (seconds, 0 = none)
+    ttl_secs: u64,
+}
+
+impl OrderRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open a journaled registry. Orders placed but never closed in the
+    /// journal (a previous session crashed) are loaded as open orphans.
+    pub fn recover(journal: JsonlStore) -> Self {
+        let events: Vec<OrderEvent> = journal.load().unwrap_or_else(|e| {
+            eprintln!("⚠️ [Orders] Failed to read order journal: {}", e);
+            Vec::new()
+        });
+        let closed: HashSet<String> = events.iter()
+            .filter_map(|e| match e {
+                OrderEvent::Closed { order_id, .. } => Some(order_id.clone()),
+                _ => None,
+            })
+            .collect();
+        let orders = events.into_iter()
+            .filter_map(|e| match e {
+                OrderEvent::Placed(o) if !closed.contains(&o.order_id) => Some(o),
+                _ => None,
+            })
+            .collect();
+        Self { orders, journal: Some(journal), next_id: 0, ttl_secs: 0 }
+    }
+
+    /// Give every order placed from now on an expiry `ttl_secs` after placement
+    pub fn with_ttl(mut self, ttl_secs: u64) -> Self {
+        self.ttl_secs = ttl_secs;
+        self
+    }
+
+    fn record(&self, event: &OrderEvent) {
+        if let Some(journal) = &self.journal {
+            if let Err(e) = journal.append(event) {
+                eprintln!("⚠️ [Orders] Failed to journal order event: {}", e);
+            }
+        }
+    }
+
+    /// Register a newly placed resting order, returning its id
+    pub fn place(&mut self, order: PassiveOrder, now: u64) -> String {
+        self.next_id += 1;
+        let order_id = format!("ord-{}-{}", now, self.next_id);
+        let expires_at = if self.ttl_secs > 0 { now + self.ttl_secs } else { 0 };
+        let resting = RestingOrder { order_id: order_id.clone(), order, placed_at: now, cursor: now, expires_at };
+        self.record(&OrderEvent::Placed(resting.clone()));
+        self.orders.push(resting);
+        order_id
+    }
+
+    /// Open orders (snapshot)
+    pub fn open_orders(&self) -> Vec<RestingOrder> {
+        self.orders.clone()
+    }
+
+    pub fn open_count(&self) -> usize {
+        self.orders.len()
+    }
+
+    /// Store fill / cursor progress; no-op if the order was cancelled meanwhile
+    pub fn update(&mut self, resting: RestingOrder) -> bool {
+        match self.orders.iter_mut().find(|o| o.order_id == resting.order_id) {
+            Some(open) => {
+                *open = resting;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Close one order (filled, expired or cancelled)
+    pub fn close(&mut self, order_id: &str, reason: &str) -> Option<RestingOrder> {
+        let pos = self.orders.iter().position(|o| o.order_id == order_id)?;
+        self.record(&OrderEvent::Closed { order_id: order_id.to_string(), reason: reason.to_string() });
+        Some(self.orders.remove(pos))
+    }
+
+    /// Open orders expiring within `window_secs` (not yet expired)
+    pub fn near_expiry(&self, now: u64, window_secs: u64) -> Vec<RestingOrder> {
+        self.orders.iter()
+            .filter(|o| o.expires_at > now && o.expires_at - now <= window_secs)
+            .cloned()
+            .collect()
+    }
+
+    /// Replace an order with a fresh quote (new id and expiry); None if it was already closed
+    pub fn requote(&mut self, order_id: &str, order: PassiveOrder, now: u64) -> Option<String> {
+        self.close(order_id, "requoted")?;
+        Some(self.place(order, now))
+    }
+
+    /// Cancel every open order on the venue. The journal is compacted once
+    /// nothing in it is live anymore.
+    pub fn cancel_all_orders(&mut self, reason: &str) -> Vec<RestingOrder> {
+        let cancelled: Vec<RestingOrder> = self.orders.drain(..).collect();
+        for order in &cancelled {
+            self.record(&OrderEvent::Closed { order_id: order.order_id.clone(), reason: reason.to_string() });
+        }
+        if let Some(journal) = &self.journal {
+            if let Err(e) = journal.clear() {
+                eprintln!("⚠️ [Orders] Failed to compact order journal: {}", e);
+            }
+        }
+        if !cancelled.is_empty() {
+            println!("🧯 [Orders] Cancelled {} open orders ({})", cancelled.len(), reason);
+        }
+        cancelled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Side;
+
+    fn order(token: &str) -> PassiveOrder {
+        PassiveOrder {
+            token_id: token.to_string(),
+            side: Side::Buy,
+            price: 0.49,
+            size: 10.0,
+            queue_ahead: 50.0,
+            filled: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_orphans_recovered_and_swept() {
+        let dir = std::env::temp_dir().join(format!("arbishark_orders_{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+        let journal = JsonlStore::open(dir, "open_orders.jsonl").unwrap();
+
+        // Session 1 places two orders, one fills, then the process "crashes"
+        let mut session = OrderRegistry::recover(journal.clone());
+        let filled = session.place(order("t1"), 100);
+        session.place(order("t2"), 101);
+        session.close(&filled, "filled");
+        drop(session);
+
+        // Session 2 finds the orphan and sweeps it before trading
+        let mut session = OrderRegistry::recover(journal.clone());
+        assert_eq!(session.open_count(), 1);
+        let orphan = session.open_orders().remove(0);
+        assert_eq!(orphan.order.token_id, "t2");
+        assert_eq!(session.cancel_all_orders("startup sweep").len(), 1);
+        // Progress on a cancelled order is discarded
+        assert!(!session.update(RestingOrder { cursor: 200, ..orphan }));
+
+        assert_eq!(OrderRegistry::recover(journal).open_count(), 0);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_good_til_time_and_requote() {
+        let mut registry = OrderRegistry::new().with_ttl(300);
+        let id = registry.place(order("t1"), 1000);
+        let placed = registry.open_orders().remove(0);
+        assert_eq!(placed.expires_at, 1300);
+        assert!(!placed.is_expired(1299) && placed.is_expired(1300));
+
+        assert!(registry.near_expiry(1200, 30).is_empty());
+        assert_eq!(registry.near_expiry(1280, 30).len(), 1);
+
+        let requoted = registry.requote(&id, order("t1"), 1280).unwrap();
+        assert_ne!(requoted, id);
+        assert_eq!(registry.open_orders()[0].expires_at, 1580);
+        assert!(registry.requote(&id, order("t1"), 1281).is_none());
+
+        // No TTL: good-til-cancelled
+        let mut gtc = OrderRegistry::new();
+        gtc.place(order("t2"), 0);
+        assert!(!gtc.open_orders()[0].is_expired(u64::MAX));
+    }
+}
@@ -0,0 +1,125 @@
+//! Rate-of-spend pacing
+//!
+//! The permission grant only bounds what is spent per period, so a burst of
+//! signals right after a reset can use up the whole allowance and leave
+//! nothing for the rest of the period. The pacer sits on top of the grant
+//! and limits what may be spent in any rolling window to a share of the
+//! allowance that was left at the start of the window. The share never drops
+//! below `max_share`, and grows as the reset approaches (to `window / time
+//! left`), so an allowance that would otherwise go unused is released by the
+//! end of the period.
+
+use crate::config::PacingConfig;
+use std::collections::VecDeque;
+
+/// Spend above what the window allows
+#[derive(Debug, Clone, PartialEq)]
+pub struct PacingError {
+    pub amount: f64,
+    pub available: f64,
+    /// Seconds until the oldest spend leaves the window, if any
+    pub retry_in: Option<u64>,
+}
+
+impl std::fmt::Display for PacingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "${:.2} exceeds ${:.2} paced for this window", self.amount, self.available)?;
+        if let Some(secs) = self.retry_in {
+            write!(f, " (frees up in {}s)", secs)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for PacingError {}
+
+#[derive(Debug, Clone)]
+pub struct SpendPacer {
+    config: PacingConfig,
+    /// (timestamp, amount) of spends within the window
+    spends: VecDeque<(u64, f64)>,
+}
+
+impl SpendPacer {
+    pub fn new(config: PacingConfig) -> Self {
+        Self { config, spends: VecDeque::new() }
+    }
+
+    fn prune(&mut self, now: u64) {
+        while self.spends.front().is_some_and(|(t, _)| now.saturating_sub(*t) >= self.config.window_secs) {
+            self.spends.pop_front();
+        }
+    }
+
+    /// Count a spend against the window
+    pub fn record(&mut self, amount: f64, now: u64) {
+        if self.config.enabled && amount > 0.0 {
+            self.spends.push_back((now, amount));
+        }
+    }
+
+    /// Spent within the window ending at `now`
+    pub fn spent_in_window(&mut self, now: u64) -> f64 {
+        self.prune(now);
+        self.spends.iter().map(|(_, amount)| amount).sum()
+    }
+
+    /// What may still be spent in the window, given the `remaining` allowance
+    /// and when the period resets (unknown = `max_share` only)
+    pub fn available(&mut self, now: u64, remaining: f64, resets_at: Option<u64>) -> f64 {
+        let spent = self.spent_in_window(now);
+        let left = resets_at.map(|r| r.saturating_sub(now)).unwrap_or(0);
+        let share = if left > 0 {
+            self.config.max_share.max(self.config.window_secs as f64 / left as f64)
+        } else {
+            self.config.max_share
+        };
+        ((remaining + spent) * share.min(1.0) - spent).max(0.0)
+    }
+
+    /// Whether `amount` may be spent at `now`
+    pub fn check(&mut self, amount: f64, now: u64, remaining: f64, resets_at: Option<u64>) -> Result<(), PacingError> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+        let available = self.available(now, remaining, resets_at);
+        if amount <= available {
+            return Ok(());
+        }
+        Err(PacingError {
+            amount,
+            available,
+            retry_in: self.spends.front().map(|(t, _)| (t + self.config.window_secs).saturating_sub(now)),
+        })
+    }
+
+    /// Forget the window (the allowance period reset)
+    pub fn reset(&mut self) {
+        self.spends.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paces_spend_over_period() {
+        let mut pacer = SpendPacer::new(PacingConfig { enabled: true, window_secs: 3600, max_share: 0.25 });
+        let resets_at = Some(86_400);
+        // $100 left with 24h to go: a quarter per hour
+        assert!((pacer.available(0, 100.0, resets_at) - 25.0).abs() < 1e-9);
+        assert!(pacer.check(20.0, 0, 100.0, resets_at).is_ok());
+        pacer.record(20.0, 0);
+        let err = pacer.check(10.0, 600, 80.0, resets_at).unwrap_err();
+        assert!((err.available - 5.0).abs() < 1e-9);
+        assert_eq!(err.retry_in, Some(3000));
+        // The spend leaves the window
+        assert!(pacer.check(10.0, 3600, 80.0, resets_at).is_ok());
+        // Within the last window of the period everything left is released
+        assert!(pacer.check(80.0, 84_000, 80.0, resets_at).is_ok());
+        // Disabled pacing never refuses
+        let mut off = SpendPacer::new(PacingConfig::default());
+        assert!(off.check(100.0, 0, 100.0, resets_at).is_ok());
+    }
+}
@@ -0,0 +1,318 @@
+//! Minimal Parquet writer
+//!
+//! Writes flat tables as a single row group of uncompressed, PLAIN-encoded
+//! columns - enough for pandas / polars / DuckDB / Spark to read training
+//! exports without pulling an Arrow stack into the agent. Layout per the
+//! Parquet spec: `PAR1`, one v1 data page per column, then the Thrift
+//! (compact protocol) `FileMetaData`, its length and `PAR1` again.
+//! Optional columns carry definition levels as a bit-packed RLE run.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+const MAGIC: &[u8; 4] = b"PAR1";
+
+// Parquet enums
+const TYPE_BOOLEAN: i32 = 0;
+const TYPE_INT64: i32 = 2;
+const TYPE_DOUBLE: i32 = 5;
+const TYPE_BYTE_ARRAY: i32 = 6;
+const REQUIRED: i32 = 0;
+const OPTIONAL: i32 = 1;
+const CONVERTED_UTF8: i32 = 0;
+const ENCODING_PLAIN: i32 = 0;
+const ENCODING_RLE: i32 = 3;
+const CODEC_UNCOMPRESSED: i32 = 0;
+const PAGE_DATA: i32 = 0;
+
+// Thrift compact protocol field types
+const CT_I32: u8 = 5;
+const CT_I64: u8 = 6;
+const CT_BINARY: u8 = 8;
+const CT_LIST: u8 = 9;
+const CT_STRUCT: u8 = 12;
+
+/// Values of one column, one per row (None = null)
+#[derive(Debug, Clone)]
+pub enum ColumnData {
+    Int64(Vec<i64>),
+    Double(Vec<Option<f64>>),
+    Boolean(Vec<bool>),
+    Utf8(Vec<String>),
+}
+
+impl ColumnData {
+    fn len(&self) -> usize {
+        match self {
+            Self::Int64(v) => v.len(),
+            Self::Double(v) => v.len(),
+            Self::Boolean(v) => v.len(),
+            Self::Utf8(v) => v.len(),
+        }
+    }
+
+    fn physical_type(&self) -> i32 {
+        match self {
+            Self::Int64(_) => TYPE_INT64,
+            Self::Double(_) => TYPE_DOUBLE,
+            Self::Boolean(_) => TYPE_BOOLEAN,
+            Self::Utf8(_) => TYPE_BYTE_ARRAY,
+        }
+    }
+
+    fn optional(&self) -> bool {
+        matches!(self, Self::Double(_))
+    }
+
+    /// Definition levels (if optional) followed by the PLAIN-encoded non-null values
+    fn page_data(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        match self {
+            Self::Int64(values) => values.iter().for_each(|v| out.extend_from_slice(&v.to_le_bytes())),
+            Self::Double(values) => {
+                let levels = bit_packed(&values.iter().map(Option::is_some).collect::<Vec<_>>());
+                out.extend_from_slice(&(levels.len() as u32).to_le_bytes());
+                out.extend_from_slice(&levels);
+                values.iter().flatten().for_each(|v| out.extend_from_slice(&v.to_le_bytes()));
+            }
+            Self::Boolean(values) => {
+                for chunk in values.chunks(8) {
+                    out.push(chunk.iter().enumerate().fold(0u8, |byte, (i, &b)| byte | ((b as u8) << i)));
+                }
+            }
+            Self::Utf8(values) => {
+                for v in values {
+                    out.extend_from_slice(&(v.len() as u32).to_le_bytes());
+                    out.extend_from_slice(v.as_bytes());
+                }
+            }
+        }
+        out
+    }
+}
+
+/// A named column
+#[derive(Debug, Clone)]
+pub struct Column {
+    pub name: String,
+    pub data: ColumnData,
+}
+
+impl Column {
+    pub fn new(name: impl Into<String>, data: ColumnData) -> Self {
+        Self { name: name.into(), data }
+    }
+}
+
+/// RLE/bit-packed hybrid run of 1-bit values (definition levels)
+fn bit_packed(bits: &[bool]) -> Vec<u8> {
+    let groups = bits.len().div_ceil(8);
+    let mut out = Vec::new();
+    varint(&mut out, ((groups as u64) << 1) | 1);
+    for chunk in bits.chunks(8) {
+        out.push(chunk.iter().enumerate().fold(0u8, |byte, (i, &b)| byte | ((b as u8) << i)));
+    }
+    out
+}
+
+fn varint(out: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        out.push((v as u8) | 0x80);
+        v >>= 7;
+    }
+    out.push(v as u8);
+}
+
+/// Thrift compact protocol encoder (just what Parquet metadata needs)
+#[derive(Default)]
+struct Thrift {
+    buf: Vec<u8>,
+    /// Last field id of each open struct
+    last_field: Vec<i16>,
+}
+
+impl Thrift {
+    fn begin(&mut self) {
+        self.last_field.push(0);
+    }
+
+    fn end(&mut self) {
+        self.buf.push(0);
+        self.last_field.pop();
+    }
+
+    fn field(&mut self, id: i16, field_type: u8) {
+        let last = self.last_field.last_mut().expect("field outside a struct");
+        let delta = id - *last;
+        if (1..=15).contains(&delta) {
+            self.buf.push(((delta as u8) << 4) | field_type);
+        } else {
+            self.buf.push(field_type);
+            varint(&mut self.buf, zigzag(id as i64));
+        }
+        *last = id;
+    }
+
+    fn i32(&mut self, id: i16, v: i32) {
+        self.field(id, CT_I32);
+        varint(&mut self.buf, zigzag(v as i64));
+    }
+
+    fn i64(&mut self, id: i16, v: i64) {
+        self.field(id, CT_I64);
+        varint(&mut self.buf, zigzag(v));
+    }
+
+    fn binary(&mut self, id: i16, v: &[u8]) {
+        self.field(id, CT_BINARY);
+        self.raw_binary(v);
+    }
+
+    fn raw_binary(&mut self, v: &[u8]) {
+        varint(&mut self.buf, v.len() as u64);
+        self.buf.extend_from_slice(v);
+    }
+
+    fn list(&mut self, id: i16, element_type: u8, len: usize) {
+        self.field(id, CT_LIST);
+        if len < 15 {
+            self.buf.push(((len as u8) << 4) | element_type);
+        } else {
+            self.buf.push(0xF0 | element_type);
+            varint(&mut self.buf, len as u64);
+        }
+    }
+
+    fn struct_field(&mut self, id: i16) {
+        self.field(id, CT_STRUCT);
+        self.begin();
+    }
+}
+
+fn zigzag(v: i64) -> u64 {
+    ((v << 1) ^ (v >> 63)) as u64
+}
+
+/// Write `columns` (all the same length) to `path` as a Parquet file
+pub fn write(path: &str, columns: &[Column]) -> io::Result<()> {
+    let rows = columns.first().map_or(0, |c| c.data.len());
+    if columns.iter().any(|c| c.data.len() != rows) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "columns differ in length"));
+    }
+    let mut file = BufWriter::new(File::create(path)?);
+    file.write_all(MAGIC)?;
+    let mut offset = MAGIC.len() as i64;
+    // (data page offset, chunk size) per column
+    let mut chunks = Vec::new();
+    if rows > 0 {
+        for column in columns {
+            let data = column.data.page_data();
+            let mut header = Thrift::default();
+            header.begin();
+            header.i32(1, PAGE_DATA);
+            header.i32(2, data.len() as i32);
+            header.i32(3, data.len() as i32);
+            header.struct_field(5);
+            header.i32(1, rows as i32);
+            header.i32(2, ENCODING_PLAIN);
+            header.i32(3, ENCODING_RLE);
+            header.i32(4, ENCODING_RLE);
+            header.end();
+            header.end();
+            file.write_all(&header.buf)?;
+            file.write_all(&data)?;
+            let size = (header.buf.len() + data.len()) as i64;
+            chunks.push((offset, size));
+            offset += size;
+        }
+    }
+
+    let mut meta = Thrift::default();
+    meta.begin();
+    meta.i32(1, 1);
+    meta.list(2, CT_STRUCT, columns.len() + 1);
+    meta.begin();
+    meta.binary(4, b"schema");
+    meta.i32(5, columns.len() as i32);
+    meta.end();
+    for column in columns {
+        meta.begin();
+        meta.i32(1, column.data.physical_type());
+        meta.i32(3, if column.data.optional() { OPTIONAL } else { REQUIRED });
+        meta.binary(4, column.name.as_bytes());
+        if let ColumnData::Utf8(_) = column.data {
+            meta.i32(6, CONVERTED_UTF8);
+        }
+        meta.end();
+    }
+    meta.i64(3, rows as i64);
+    meta.list(4, CT_STRUCT, chunks.len().min(1));
+    if !chunks.is_empty() {
+        meta.begin();
+        meta.list(1, CT_STRUCT, columns.len());
+        for (column, &(page_offset, size)) in columns.iter().zip(&chunks) {
+            meta.begin();
+            meta.i64(2, page_offset);
+            meta.struct_field(3);
+            meta.i32(1, column.data.physical_type());
+            meta.list(2, CT_I32, 2);
+            varint(&mut meta.buf, zigzag(ENCODING_PLAIN as i64));
+            varint(&mut meta.buf, zigzag(ENCODING_RLE as i64));
+            meta.list(3, CT_BINARY, 1);
+            meta.raw_binary(column.name.as_bytes());
+            meta.i32(4, CODEC_UNCOMPRESSED);
+            meta.i64(5, rows as i64);
+            meta.i64(6, size);
+            meta.i64(7, size);
+            meta.i64(9, page_offset);
+            meta.end();
+            meta.end();
+        }
+        meta.i64(2, chunks.iter().map(|&(_, size)| size).sum());
+        meta.i64(3, rows as i64);
+        meta.end();
+    }
+    meta.binary(6, b"arbishark");
+    meta.end();
+    file.write_all(&meta.buf)?;
+    file.write_all(&(meta.buf.len() as u32).to_le_bytes())?;
+    file.write_all(MAGIC)?;
+    file.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_and_encodings() {
+        assert_eq!(bit_packed(&[true, false, true]), vec![0b11, 0b101]);
+        let mut t = Thrift::default();
+        t.begin();
+        t.i32(1, -1);
+        t.i64(20, 300);
+        t.end();
+        // Short-form header, zigzag(-1) = 1; long-form header for a delta > 15
+        assert_eq!(t.buf, vec![0x15, 0x01, 0x06, 0x28, 0xD8, 0x04, 0x00]);
+
+        let path = std::env::temp_dir().join(format!("arbishark_parquet_{}.parquet", std::process::id()));
+        let path = path.to_str().unwrap();
+        let columns = [
+            Column::new("market_id", ColumnData::Utf8(vec!["a".to_string(), "bc".to_string()])),
+            Column::new("timestamp", ColumnData::Int64(vec![1, 2])),
+            Column::new("pnl", ColumnData::Double(vec![None, Some(0.5)])),
+        ];
+        write(path, &columns).unwrap();
+        let bytes = std::fs::read(path).unwrap();
+        assert_eq!(&bytes[..4], MAGIC);
+        assert_eq!(&bytes[bytes.len() - 4..], MAGIC);
+        let footer_len = u32::from_le_bytes(bytes[bytes.len() - 8..bytes.len() - 4].try_into().unwrap()) as usize;
+        let footer = &bytes[bytes.len() - 8 - footer_len..bytes.len() - 8];
+        assert!(footer.windows(9).any(|w| w == b"market_id"));
+        // The optional column's page: levels length, one bit-packed group (0b10), then only the non-null value
+        let pnl_page = [&2u32.to_le_bytes()[..], &[0x03, 0b10], &0.5f64.to_le_bytes()].concat();
+        assert!(bytes.windows(pnl_page.len()).any(|w| w == pnl_page.as_slice()));
+        assert!(write(path, &[Column::new("x", ColumnData::Int64(vec![1])), Column::new("y", ColumnData::Boolean(vec![]))]).is_err());
+        let _ = std::fs::remove_file(path);
+    }
+}
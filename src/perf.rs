@@ -0,0 +1,148 @@
+//! Self-profiling for `/api/debug/perf`
+//!
+//! Production stalls are diagnosed from inside the process instead of by
+//! attaching a profiler: the engine times its components (tick, market
+//! fetch, scan, execution, previews) and reports the size of its caches;
+//! the report adds tokio runtime metrics (workers, alive tasks, queue depths,
+//! per-worker busy time) and channel backlogs. Per-task poll times need a
+//! `tokio_unstable` build, so the engine's own component timings stand in
+//! for them. Everything is off unless `[debug] perf` is set.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Wall time spent in one component
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ComponentStats {
+    pub component: String,
+    pub samples: u64,
+    pub total_ms: f64,
+    pub mean_ms: f64,
+    pub max_ms: f64,
+    pub last_ms: f64,
+}
+
+/// Messages waiting in a channel
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChannelBacklog {
+    pub channel: String,
+    pub queued: usize,
+    pub capacity: usize,
+}
+
+/// Stable tokio runtime metrics
+#[derive(Debug, Clone, Serialize)]
+pub struct RuntimeStats {
+    pub workers: usize,
+    pub alive_tasks: usize,
+    /// Tasks waiting in the shared injection queue
+    pub global_queue_depth: usize,
+    pub worker_busy_ms: Vec<u64>,
+    pub worker_parks: Vec<u64>,
+}
+
+/// Metrics of the runtime the caller runs on (None outside a runtime)
+pub fn runtime_stats() -> Option<RuntimeStats> {
+    let metrics = tokio::runtime::Handle::try_current().ok()?.metrics();
+    let workers = metrics.num_workers();
+    Some(RuntimeStats {
+        workers,
+        alive_tasks: metrics.num_alive_tasks(),
+        global_queue_depth: metrics.global_queue_depth(),
+        worker_busy_ms: (0..workers).map(|w| metrics.worker_total_busy_duration(w).as_millis() as u64).collect(),
+        worker_parks: (0..workers).map(|w| metrics.worker_park_count(w)).collect(),
+    })
+}
+
+/// `GET /api/debug/perf`
+#[derive(Debug, Clone, Serialize)]
+pub struct PerfReport {
+    pub runtime: Option<RuntimeStats>,
+    /// Slowest (by mean) first
+    pub components: Vec<ComponentStats>,
+    /// Entries per cache
+    pub caches: BTreeMap<String, usize>,
+    pub channels: Vec<ChannelBacklog>,
+}
+
+#[derive(Debug, Default)]
+struct PerfState {
+    components: BTreeMap<String, ComponentStats>,
+    caches: BTreeMap<String, usize>,
+}
+
+/// Component timings and cache sizes, shared by the engine and the API
+#[derive(Debug, Clone, Default)]
+pub struct PerfMonitor {
+    enabled: bool,
+    state: Arc<Mutex<PerfState>>,
+}
+
+impl PerfMonitor {
+    /// Recording is a no-op unless enabled
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled, state: Arc::default() }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Time spent in `component`
+    pub fn record(&self, component: &str, elapsed: Duration) {
+        if !self.enabled {
+            return;
+        }
+        let ms = elapsed.as_secs_f64() * 1000.0;
+        let mut state = self.state.lock().unwrap();
+        let stats = state.components.entry(component.to_string())
+            .or_insert_with(|| ComponentStats { component: component.to_string(), ..Default::default() });
+        stats.samples += 1;
+        stats.total_ms += ms;
+        stats.mean_ms = stats.total_ms / stats.samples as f64;
+        stats.max_ms = stats.max_ms.max(ms);
+        stats.last_ms = ms;
+    }
+
+    /// Current number of entries in `cache`
+    pub fn set_cache(&self, cache: &str, entries: usize) {
+        if self.enabled {
+            self.state.lock().unwrap().caches.insert(cache.to_string(), entries);
+        }
+    }
+
+    /// Report with the runtime metrics; `caches` and `channels` are added by the caller
+    pub fn report(&self) -> PerfReport {
+        let state = self.state.lock().unwrap();
+        let mut components: Vec<ComponentStats> = state.components.values().cloned().collect();
+        components.sort_by(|a, b| b.mean_ms.total_cmp(&a.mean_ms));
+        PerfReport { runtime: runtime_stats(), components, caches: state.caches.clone(), channels: Vec::new() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_component_timings_and_runtime_metrics() {
+        let perf = PerfMonitor::new(true);
+        perf.record("scan", Duration::from_millis(10));
+        perf.record("scan", Duration::from_millis(30));
+        perf.record("tick", Duration::from_millis(5));
+        perf.set_cache("markets", 120);
+
+        let report = perf.report();
+        assert_eq!(report.components[0].component, "scan", "slowest first");
+        let scan = &report.components[0];
+        assert_eq!((scan.samples, scan.mean_ms, scan.max_ms, scan.last_ms), (2, 20.0, 30.0, 30.0));
+        assert_eq!(report.caches["markets"], 120);
+        assert!(report.runtime.unwrap().workers >= 1);
+
+        let disabled = PerfMonitor::new(false);
+        disabled.record("scan", Duration::from_millis(10));
+        assert!(disabled.report().components.is_empty());
+    }
+}
@@ -1,11 +1,17 @@
+use serde::{Deserialize, Serialize};
+
 /// PermissionGuard for ERC-7715 mapping
 #[derive(Debug, Clone)]
 pub struct PermissionGuard {
     pub daily_limit: f64,
     pub spent_today: f64,
+    pub scope: PermissionScope,
 }
 
 impl PermissionGuard {
+    pub fn new(daily_limit: f64, scope: PermissionScope) -> Self {
+        Self { daily_limit, spent_today: 0.0, scope }
+    }
     pub fn can_spend(&self, amount: f64) -> bool {
         self.spent_today + amount <= self.daily_limit
     }
@@ -15,9 +21,118 @@ impl PermissionGuard {
     pub fn reset(&mut self) {
         self.spent_today = 0.0;
     }
+    /// Check a call against both the grant scope and the remaining allowance
+    pub fn authorize(&self, call: &ContractCall) -> Result<(), ScopeViolation> {
+        self.scope.check(call)?;
+        if !self.can_spend(call.value) {
+            return Err(ScopeViolation::InsufficientAllowance {
+                amount: call.value,
+                remaining: (self.daily_limit - self.spent_today).max(0.0),
+            });
+        }
+        Ok(())
+    }
 }
 
+/// Targets, methods and per-tx cap granted by an ERC-7715 permission.
+///
+/// Empty `allowed_targets` / `allowed_methods` mean the grant does not
+/// restrict that dimension.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PermissionScope {
+    /// Contract addresses the agent may call
+    #[serde(default)]
+    pub allowed_targets: Vec<String>,
+    /// Method names or 4-byte selectors (0x-prefixed) the agent may call
+    #[serde(default)]
+    pub allowed_methods: Vec<String>,
+    /// Maximum value (USDC) of a single call
+    #[serde(default)]
+    pub max_per_tx: Option<f64>,
+}
+
+impl PermissionScope {
+    /// Check whether a call falls within this scope
+    pub fn check(&self, call: &ContractCall) -> Result<(), ScopeViolation> {
+        if !self.allowed_targets.is_empty()
+            && !self.allowed_targets.iter().any(|t| t.eq_ignore_ascii_case(&call.target))
+        {
+            return Err(ScopeViolation::TargetNotAllowed(call.target.clone()));
+        }
+        if !self.allowed_methods.is_empty()
+            && !self.allowed_methods.iter().any(|m| m.eq_ignore_ascii_case(&call.method))
+        {
+            return Err(ScopeViolation::MethodNotAllowed(call.method.clone()));
+        }
+        if let Some(cap) = self.max_per_tx {
+            if call.value > cap {
+                return Err(ScopeViolation::ExceedsPerTxCap { amount: call.value, cap });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A contract call the agent intends to submit via the Smart Account
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContractCall {
+    pub target: String,
+    pub method: String,
+    /// USDC value moved by the call
+    pub value: f64,
+}
+
+/// Reason a call was refused by the permission scope
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScopeViolation {
+    TargetNotAllowed(String),
+    MethodNotAllowed(String),
+    ExceedsPerTxCap { amount: f64, cap: f64 },
+    InsufficientAllowance { amount: f64, remaining: f64 },
+}
+
+impl std::fmt::Display for ScopeViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TargetNotAllowed(target) => write!(f, "Target {} not in permission scope", target),
+            Self::MethodNotAllowed(method) => write!(f, "Method {} not in permission scope", method),
+            Self::ExceedsPerTxCap { amount, cap } => write!(f, "Call value ${:.2} exceeds per-tx cap ${:.2}", amount, cap),
+            Self::InsufficientAllowance { amount, remaining } => write!(f, "Call value ${:.2} exceeds remaining allowance ${:.2}", amount, remaining),
+        }
+    }
+}
+
+impl std::error::Error for ScopeViolation {}
+
 // Maps to ERC-7715 JSON fields:
 // - daily_limit → limit.amount
 // - spent_today → tracked locally, resets per period
-// - period, scope: see config/MetaMask Delegation Toolkit
+// - scope.allowed_targets / allowed_methods → permission data (targets, selectors)
+// - scope.max_per_tx → per-call value cap
+// - period: see config/MetaMask Delegation Toolkit
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_enforcement() {
+        let scope = PermissionScope {
+            allowed_targets: vec!["0xAbC".to_string()],
+            allowed_methods: vec!["fillOrder".to_string()],
+            max_per_tx: Some(5.0),
+        };
+        let guard = PermissionGuard::new(8.0, scope);
+        let call = |target: &str, method: &str, value: f64| ContractCall {
+            target: target.to_string(),
+            method: method.to_string(),
+            value,
+        };
+
+        assert!(guard.authorize(&call("0xabc", "fillOrder", 4.0)).is_ok());
+        assert!(matches!(guard.authorize(&call("0xdef", "fillOrder", 4.0)), Err(ScopeViolation::TargetNotAllowed(_))));
+        assert!(matches!(guard.authorize(&call("0xabc", "transfer", 4.0)), Err(ScopeViolation::MethodNotAllowed(_))));
+        assert!(matches!(guard.authorize(&call("0xabc", "fillOrder", 6.0)), Err(ScopeViolation::ExceedsPerTxCap { .. })));
+        assert!(PermissionScope::default().check(&call("0xdef", "anything", 100.0)).is_ok());
+    }
+}
@@ -1,8 +1,10 @@
 // Plugin System for ArbiShark
 // Extensible architecture for custom strategies and integrations
+#![allow(dead_code)]
 
+use crate::approvals::PendingApproval;
+use crate::sweep::{SweepStatus, SweepTransfer};
 use async_trait::async_trait;
-use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Plugin decision for trade signals
@@ -42,24 +44,43 @@ pub trait AgentPlugin: Send + Sync {
     /// Trade hooks
     async fn on_trade_signal(
         &self,
-        signal: &ArbitrageSignal,
+        _signal: &ArbitrageSignal,
     ) -> PluginDecision {
         PluginDecision::Continue
     }
 
     async fn on_trade_complete(
         &self,
-        trade: &TradeResult,
+        _trade: &TradeResult,
     ) {
         // Default: do nothing
     }
 
     async fn on_error(
         &self,
-        error: &str,
+        _error: &str,
     ) -> PluginAction {
         PluginAction::Skip
     }
+
+    /// Allowance hooks
+    async fn on_allowance_exhausted(&self, _resets_at: u64) {
+        // Default: do nothing
+    }
+
+    async fn on_allowance_reset(&self) {
+        // Default: do nothing
+    }
+
+    /// Profit sweep confirmed or failed
+    async fn on_profit_sweep(&self, _sweep: &SweepTransfer) {
+        // Default: do nothing
+    }
+
+    /// A signal is waiting for human approval
+    async fn on_approval_requested(&self, _approval: &PendingApproval) {
+        // Default: do nothing
+    }
 }
 
 /// Example: Sentiment Analysis Plugin
@@ -76,7 +97,7 @@ impl SentimentPlugin {
         }
     }
 
-    async fn get_sentiment(&self, market_id: &str) -> f64 {
+    async fn get_sentiment(&self, _market_id: &str) -> f64 {
         // Simulate API call to sentiment analysis service
         // In production: call Twitter API, Reddit API, etc.
         0.0
@@ -126,14 +147,22 @@ impl NotificationPlugin {
     }
 
     async fn send_telegram(&self, message: &str) {
-        if let Some(token) = &self.telegram_token {
+        if let Some(_token) = &self.telegram_token {
             // Send to Telegram
             tracing::info!("📱 Telegram: {}", message);
         }
     }
 
+    /// Telegram message with approve / reject inline buttons; presses come
+    /// back as `approve:<id>` / `reject:<id>` callback data on the API webhook
+    async fn send_telegram_approval(&self, message: &str, approval_id: &str) {
+        if let Some(_token) = &self.telegram_token {
+            tracing::info!("📱 Telegram: {} [✅ approve:{}] [❌ reject:{}]", message, approval_id, approval_id);
+        }
+    }
+
     async fn send_discord(&self, message: &str) {
-        if let Some(webhook) = &self.discord_webhook {
+        if let Some(_webhook) = &self.discord_webhook {
             // Send to Discord
             tracing::info!("💬 Discord: {}", message);
         }
@@ -172,6 +201,34 @@ impl AgentPlugin for NotificationPlugin {
         self.send_discord(&message).await;
         PluginAction::Skip
     }
+
+    async fn on_allowance_exhausted(&self, resets_at: u64) {
+        let message = format!("💤 Daily allowance exhausted - sleeping until reset at {}", resets_at);
+        self.send_telegram(&message).await;
+        self.send_discord(&message).await;
+    }
+
+    async fn on_allowance_reset(&self) {
+        let message = "🔄 Daily allowance reset - resuming full-rate scanning";
+        self.send_telegram(message).await;
+        self.send_discord(message).await;
+    }
+
+    async fn on_profit_sweep(&self, sweep: &SweepTransfer) {
+        let message = match &sweep.status {
+            SweepStatus::Failed { reason } => format!("⚠️ Profit sweep of ${:.2} to {} failed: {}", sweep.amount, sweep.to, reason),
+            _ => format!("🏦 Swept ${:.2} profit to {}", sweep.amount, sweep.to),
+        };
+        self.send_telegram(&message).await;
+        self.send_discord(&message).await;
+    }
+
+    async fn on_approval_requested(&self, approval: &PendingApproval) {
+        let message = format!("🙋 Approve ${:.2} bundle on {} (edge ${:.3})? Expires at {}",
+            approval.notional, approval.market_id, approval.edge, approval.expires_at);
+        self.send_telegram_approval(&message, &approval.approval_id).await;
+        self.send_discord(&format!("{} - POST /api/approvals/{}/approve", message, approval.approval_id)).await;
+    }
 }
 
 /// Plugin Manager
@@ -233,6 +290,36 @@ impl PluginManager {
         }
         PluginAction::Skip
     }
+
+    pub async fn notify_allowance_exhausted(&self, resets_at: u64) {
+        for plugin in self.plugins.values() {
+            plugin.on_allowance_exhausted(resets_at).await;
+        }
+    }
+
+    pub async fn notify_allowance_reset(&self) {
+        for plugin in self.plugins.values() {
+            plugin.on_allowance_reset().await;
+        }
+    }
+
+    pub async fn notify_profit_sweep(&self, sweep: &SweepTransfer) {
+        for plugin in self.plugins.values() {
+            plugin.on_profit_sweep(sweep).await;
+        }
+    }
+
+    pub async fn notify_approval_requested(&self, approval: &PendingApproval) {
+        for plugin in self.plugins.values() {
+            plugin.on_approval_requested(approval).await;
+        }
+    }
+}
+
+impl Default for PluginManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 // Placeholder types (should match your existing types)
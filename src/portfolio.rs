@@ -0,0 +1,244 @@
+// Cross-chain portfolio view
+// Balances, positions and unrealized PnL per chain, normalized to USD, optionally gating entries
+
+use crate::assets::Collateral;
+use crate::config::{AssetsConfig, PortfolioConfig};
+use crate::positions::Position;
+use crate::types::Market;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// One chain's share of the portfolio
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainExposure {
+    pub chain: String,
+    pub collateral: String,
+    /// None when the collateral has no configured USD price (excluded from totals)
+    pub usd_price: Option<f64>,
+    /// Free collateral, in collateral units
+    pub balance: f64,
+    pub balance_usd: f64,
+    pub open_notional_usd: f64,
+    pub unrealized_pnl_usd: f64,
+    pub positions: usize,
+}
+
+/// Consolidated view across chains (`GET /api/portfolio`)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PortfolioSnapshot {
+    pub updated_at: u64,
+    pub chains: Vec<ChainExposure>,
+    pub balance_usd: f64,
+    pub open_notional_usd: f64,
+    pub unrealized_pnl_usd: f64,
+    /// Balances plus open positions marked to market
+    pub equity_usd: f64,
+}
+
+#[derive(Debug)]
+pub struct Portfolio {
+    config: PortfolioConfig,
+    primary_chain: String,
+    /// Chain → its collateral (chains whose collateral is unknown or unpriced are missing)
+    collateral: BTreeMap<String, Collateral>,
+    /// Why a chain's collateral could not be priced
+    errors: Vec<String>,
+    balances: BTreeMap<String, f64>,
+    /// Legs executed off the primary chain: token → chain
+    token_chain: HashMap<String, String>,
+    snapshot: PortfolioSnapshot,
+}
+
+impl Portfolio {
+    /// Portfolio for an engine trading `primary_collateral` on `primary_venue`
+    pub fn new(config: PortfolioConfig, assets: &AssetsConfig, primary_venue: &str, primary_collateral: Collateral) -> Self {
+        let primary_chain = chain_of(&config, primary_venue);
+        let mut collateral = BTreeMap::new();
+        let mut errors = Vec::new();
+        for (chain, symbol) in &config.chain_collateral {
+            match Collateral::from_config(assets, symbol) {
+                Ok(c) => {
+                    collateral.insert(chain.clone(), c);
+                }
+                Err(e) => errors.push(format!("portfolio chain {}: {}", chain, e)),
+            }
+        }
+        collateral.insert(primary_chain.clone(), primary_collateral);
+        errors.sort();
+        Self { config, primary_chain, collateral, errors, balances: BTreeMap::new(), token_chain: HashMap::new(), snapshot: PortfolioSnapshot::default() }
+    }
+
+    /// With global limits on, every configured chain must be priceable
+    pub fn validate(&self) -> Result<(), String> {
+        match self.errors.first() {
+            Some(e) if self.config.enabled => Err(e.clone()),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn primary_chain(&self) -> &str {
+        &self.primary_chain
+    }
+
+    pub fn chain_of_venue(&self, venue: &str) -> String {
+        chain_of(&self.config, venue)
+    }
+
+    /// Free collateral on `chain`, in its collateral units
+    pub fn set_balance(&mut self, chain: &str, amount: f64) {
+        self.balances.insert(chain.to_string(), amount);
+    }
+
+    /// Free collateral on `chain` in on-chain base units (e.g. lamports)
+    pub fn set_raw_balance(&mut self, chain: &str, raw: u128) {
+        if let Some(amount) = self.collateral.get(chain).map(|c| c.asset.from_base_units(raw)) {
+            self.set_balance(chain, amount);
+        }
+    }
+
+    /// Record the venue a token's leg was executed on
+    pub fn assign(&mut self, token_id: &str, venue: &str) {
+        let chain = self.chain_of_venue(venue);
+        if chain == self.primary_chain {
+            self.token_chain.remove(token_id);
+        } else {
+            self.token_chain.insert(token_id.to_string(), chain);
+        }
+    }
+
+    /// Rebuild the view from open positions marked at `markets`' outcome prices
+    pub fn refresh(&mut self, positions: &[&Position], markets: &[Market], now: u64) -> &PortfolioSnapshot {
+        let mut chains: BTreeMap<String, ChainExposure> = BTreeMap::new();
+        exposure(&mut chains, &self.collateral, &self.primary_chain);
+        for (chain, balance) in &self.balances {
+            let row = exposure(&mut chains, &self.collateral, chain);
+            row.balance = *balance;
+            row.balance_usd = balance * row.usd_price.unwrap_or(0.0);
+        }
+        for p in positions {
+            let chain = self.token_chain.get(&p.token_id).unwrap_or(&self.primary_chain);
+            let mark = markets.iter()
+                .find(|m| m.id == p.market_id)
+                .and_then(|m| m.clob_token_ids.iter().position(|t| *t == p.token_id).and_then(|i| m.outcome_prices.get(i)))
+                .copied()
+                .unwrap_or(p.entry_price);
+            let row = exposure(&mut chains, &self.collateral, chain);
+            let usd = row.usd_price.unwrap_or(0.0);
+            row.open_notional_usd += p.size * p.entry_price * usd;
+            row.unrealized_pnl_usd += p.size * (mark - p.entry_price) * usd;
+            row.positions += 1;
+        }
+        let chains: Vec<ChainExposure> = chains.into_values().collect();
+        let balance_usd = chains.iter().map(|c| c.balance_usd).sum();
+        let open_notional_usd = chains.iter().map(|c| c.open_notional_usd).sum();
+        let unrealized_pnl_usd = chains.iter().map(|c| c.unrealized_pnl_usd).sum();
+        self.snapshot = PortfolioSnapshot {
+            updated_at: now,
+            chains,
+            balance_usd,
+            open_notional_usd,
+            unrealized_pnl_usd,
+            equity_usd: balance_usd + open_notional_usd + unrealized_pnl_usd,
+        };
+        &self.snapshot
+    }
+
+    pub fn snapshot(&self) -> PortfolioSnapshot {
+        self.snapshot.clone()
+    }
+
+    /// Refuse an entry of `notional_usd` that would breach a global limit
+    pub fn check_entry(&self, notional_usd: f64) -> Result<(), String> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+        let s = &self.snapshot;
+        if let Some(max) = self.config.max_open_notional_usd {
+            if s.open_notional_usd + notional_usd > max {
+                return Err(format!("open notional ${:.2} + ${:.2} across {} chains exceeds ${:.2}",
+                    s.open_notional_usd, notional_usd, s.chains.len(), max));
+            }
+        }
+        if let Some(max) = self.config.max_unrealized_loss_usd {
+            if -s.unrealized_pnl_usd > max {
+                return Err(format!("unrealized loss ${:.2} across {} chains exceeds ${:.2}", -s.unrealized_pnl_usd, s.chains.len(), max));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `chain`'s row, created empty on first use
+fn exposure<'a>(chains: &'a mut BTreeMap<String, ChainExposure>, collateral: &BTreeMap<String, Collateral>, chain: &str) -> &'a mut ChainExposure {
+    chains.entry(chain.to_string()).or_insert_with(|| {
+        let collateral = collateral.get(chain);
+        ChainExposure {
+            chain: chain.to_string(),
+            collateral: collateral.map_or("?".to_string(), |c| c.symbol().to_string()),
+            usd_price: collateral.map(|c| c.usd_price),
+            balance: 0.0,
+            balance_usd: 0.0,
+            open_notional_usd: 0.0,
+            unrealized_pnl_usd: 0.0,
+            positions: 0,
+        }
+    })
+}
+
+/// Chain a venue settles on (unmapped venues are their own chain)
+fn chain_of(config: &PortfolioConfig, venue: &str) -> String {
+    config.venue_chains.get(venue).cloned().unwrap_or_else(|| venue.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Side;
+
+    fn position(market_id: &str, token_id: &str, size: f64, entry_price: f64) -> Position {
+        Position {
+            market_id: market_id.to_string(),
+            token_id: token_id.to_string(),
+            side: Side::Buy,
+            size,
+            entry_price,
+            entry_time: 0,
+            entry_spread: 0.0,
+            strategy: "arb".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_consolidates_chains_in_usd() {
+        let assets = AssetsConfig { usd_prices: [("SOL".to_string(), 150.0)].into_iter().collect() };
+        let config = PortfolioConfig { enabled: true, max_open_notional_usd: Some(20.0), max_unrealized_loss_usd: Some(1.0), ..Default::default() };
+        let mut portfolio = Portfolio::new(config, &assets, "polymarket", Collateral::default());
+        assert!(portfolio.validate().is_ok());
+        assert_eq!(portfolio.primary_chain(), "polygon");
+
+        portfolio.set_balance("polygon", 100.0);
+        portfolio.set_raw_balance("solana", 2_000_000_000); // 2 SOL
+        portfolio.assign("no", "envio");
+        let market: Market = serde_json::from_value(serde_json::json!({
+            "id": "m1", "question": "?", "slug": "m1", "outcomes": ["Yes", "No"], "outcome_prices": [0.40, 0.50],
+            "clob_token_ids": ["yes", "no"], "best_bid": null, "best_ask": null, "maker_base_fee": 0, "taker_base_fee": 0,
+            "liquidity": 0.0, "volume_24hr": 0.0, "active": true, "accepting_orders": true
+        })).unwrap();
+        let (yes, no) = (position("m1", "yes", 10.0, 0.45), position("m1", "no", 10.0, 0.50));
+        let snapshot = portfolio.refresh(&[&yes, &no], &[market], 7).clone();
+
+        let chain = |name: &str| snapshot.chains.iter().find(|c| c.chain == name).unwrap();
+        assert_eq!((chain("polygon").positions, chain("arbitrum").positions), (1, 1));
+        assert!((chain("polygon").unrealized_pnl_usd + 0.5).abs() < 1e-9);
+        assert!((chain("solana").balance_usd - 300.0).abs() < 1e-9);
+        assert!((snapshot.open_notional_usd - 9.5).abs() < 1e-9);
+        assert!((snapshot.equity_usd - (400.0 + 9.5 - 0.5)).abs() < 1e-9);
+
+        // The cap applies to the sum over chains, not to each chain
+        assert!(portfolio.check_entry(10.0).is_ok());
+        assert!(portfolio.check_entry(11.0).unwrap_err().contains("3 chains"));
+
+        let unpriced = Portfolio::new(PortfolioConfig { enabled: true, ..Default::default() }, &AssetsConfig::default(), "polymarket", Collateral::default());
+        assert!(unpriced.validate().unwrap_err().contains("solana"));
+    }
+}
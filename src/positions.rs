@@ -2,7 +2,10 @@
 //! 
 //! Handles position tracking, mean reversion exits, and PnL calculation.
 
+use crate::config::GreeksConfig;
+use crate::greeks::{self, PositionGreeks};
 use crate::types::{Market, Side};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// An open position in the market
@@ -15,6 +18,7 @@ pub struct Position {
     pub entry_price: f64,
     pub entry_time: u64,
     pub entry_spread: f64,  // Spread at entry for mean reversion tracking
+    pub strategy: String,   // Strategy sub-account the position belongs to
 }
 
 /// Position exit reason
@@ -25,6 +29,8 @@ pub enum ExitReason {
     ProfitTarget,       // Hit profit target
     StopLoss,           // Hit stop loss
     Timeout,            // Position held too long
+    EdgeDecay,          // Complete set earns too little per day left to resolution
+    ResolutionRisk,     // Naked leg close to resolution
     #[allow(dead_code)]
     Manual,             // Manual close
 }
@@ -41,6 +47,81 @@ pub struct ExitResult {
     pub fees: f64,
 }
 
+/// Trade history filter (`/api/trades` query parameters)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TradeQuery {
+    /// Entry time bounds, unix seconds inclusive
+    pub from: Option<u64>,
+    pub to: Option<u64>,
+    pub market: Option<String>,
+    pub strategy: Option<String>,
+    /// "open" or "closed"
+    pub status: Option<String>,
+    /// "asc" or "desc" (default) by entry time
+    pub sort: Option<String>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+/// An open or closed trade as served by the API
+#[derive(Debug, Clone, Serialize)]
+pub struct TradeRecord {
+    pub market_id: String,
+    pub token_id: String,
+    pub side: String,
+    pub size: f64,
+    pub entry_price: f64,
+    pub entry_time: u64,
+    pub strategy: String,
+    pub status: &'static str,
+    pub exit_price: Option<f64>,
+    pub exit_time: Option<u64>,
+    pub pnl: Option<f64>,
+    /// Tags of the market event windows the entry fell in (filled in by the API)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Greeks of an open position on current prices (filled in by the API)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub greeks: Option<PositionGreeks>,
+}
+
+impl TradeRecord {
+    fn open(p: &Position) -> Self {
+        Self {
+            market_id: p.market_id.clone(),
+            token_id: p.token_id.clone(),
+            side: format!("{:?}", p.side),
+            size: p.size,
+            entry_price: p.entry_price,
+            entry_time: p.entry_time,
+            strategy: p.strategy.clone(),
+            status: "open",
+            exit_price: None,
+            exit_time: None,
+            pnl: None,
+            tags: Vec::new(),
+            greeks: None,
+        }
+    }
+
+    fn closed(e: &ExitResult) -> Self {
+        Self {
+            status: "closed",
+            exit_price: Some(e.exit_price),
+            exit_time: Some(e.exit_time),
+            pnl: Some(e.pnl),
+            ..Self::open(&e.position)
+        }
+    }
+}
+
+/// One page of trades plus the number matching before pagination
+#[derive(Debug, Clone)]
+pub struct TradePage {
+    pub total: usize,
+    pub trades: Vec<TradeRecord>,
+}
+
 /// Position manager for tracking and closing positions
 #[derive(Debug)]
 pub struct PositionManager {
@@ -54,6 +135,8 @@ pub struct PositionManager {
     max_hold_time: u64,
     /// Closed positions history
     history: Vec<ExitResult>,
+    /// Exits on position greeks
+    greeks: GreeksConfig,
 }
 
 impl PositionManager {
@@ -64,9 +147,16 @@ impl PositionManager {
             stop_loss_spread,
             max_hold_time,
             history: Vec::new(),
+            greeks: GreeksConfig::default(),
         }
     }
 
+    /// Also exit on decayed edge or resolution risk (`greeks` module)
+    pub fn with_greeks(mut self, config: GreeksConfig) -> Self {
+        self.greeks = config;
+        self
+    }
+
     /// Add a new position
     pub fn open_position(&mut self, position: Position) {
         println!("📈 [Position] Opened: {} @ ${:.4} (spread: {:.2}%)", 
@@ -79,6 +169,52 @@ impl PositionManager {
         self.positions.values().collect()
     }
 
+    /// Add a later fill (e.g. a resting remainder) to an open position
+    pub fn add_fill(&mut self, token_id: &str, size: f64, price: f64) -> bool {
+        match self.positions.get_mut(token_id) {
+            Some(p) => {
+                let total = p.size + size;
+                p.entry_price = (p.entry_price * p.size + price * size) / total;
+                p.size = total;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Notional ($) currently deployed in open positions
+    pub fn open_notional(&self) -> f64 {
+        self.positions.values().map(|p| p.size * p.entry_price).sum()
+    }
+
+    /// Notional ($) deployed in one market's open legs
+    pub fn market_notional(&self, market_id: &str) -> f64 {
+        self.positions.values().filter(|p| p.market_id == market_id).map(|p| p.size * p.entry_price).sum()
+    }
+
+    /// Mark-to-market PnL of open positions at the markets' outcome prices
+    /// (positions whose market is not listed are marked at entry)
+    pub fn unrealized_pnl(&self, markets: &[Market]) -> f64 {
+        self.positions.values().map(|p| {
+            let mark = markets.iter()
+                .find(|m| m.id == p.market_id)
+                .and_then(|m| m.clob_token_ids.iter().position(|t| *t == p.token_id).and_then(|i| m.outcome_prices.get(i)))
+                .copied()
+                .unwrap_or(p.entry_price);
+            p.size * (mark - p.entry_price)
+        }).sum()
+    }
+
+    /// Number of open legs in a market
+    pub fn open_legs(&self, market_id: &str) -> usize {
+        self.positions.values().filter(|p| p.market_id == market_id).count()
+    }
+
+    /// Number of markets with at least one open leg
+    pub fn open_bundle_count(&self) -> usize {
+        self.positions.values().map(|p| &p.market_id).collect::<std::collections::HashSet<_>>().len()
+    }
+
     /// Get position by token_id
     #[allow(dead_code)]
     pub fn get_position(&self, token_id: &str) -> Option<&Position> {
@@ -89,6 +225,8 @@ impl PositionManager {
     pub fn check_exits(&mut self, markets: &[Market], current_time: u64, fee_rate: f64) -> Vec<ExitResult> {
         let mut exits = Vec::new();
         let mut to_remove = Vec::new();
+        let mut to_shrink = Vec::new();
+        let position_greeks = greeks::for_positions(self.positions.values(), markets, current_time);
 
         for (token_id, position) in &self.positions {
             // Find current market state
@@ -113,20 +251,32 @@ impl PositionManager {
                     // Position timeout
                     Some(ExitReason::Timeout)
                 } else {
-                    None
+                    position_greeks.get(token_id).and_then(|g| greeks::exit_reason(g, &self.greeks))
                 };
 
                 if let Some(reason) = exit_reason {
+                    // Resolution risk closes only the shares beyond the market's complete sets
+                    let mut closed = position.clone();
+                    if matches!(reason, ExitReason::ResolutionRisk) {
+                        if let Some(g) = position_greeks.get(token_id) {
+                            closed.size = g.naked.min(position.size);
+                        }
+                    }
+
                     // Calculate PnL
-                    let gross_pnl = match position.side {
-                        Side::Buy => (current_price - position.entry_price) * position.size,
-                        Side::Sell => (position.entry_price - current_price) * position.size,
+                    let gross_pnl = match closed.side {
+                        Side::Buy => (current_price - closed.entry_price) * closed.size,
+                        Side::Sell => (closed.entry_price - current_price) * closed.size,
                     };
-                    let fees = position.size * current_price * fee_rate;
+                    let fees = closed.size * current_price * fee_rate;
                     let net_pnl = gross_pnl - fees;
 
+                    match closed.size < position.size {
+                        true => to_shrink.push((token_id.clone(), position.size - closed.size)),
+                        false => to_remove.push(token_id.clone()),
+                    }
                     let exit_result = ExitResult {
-                        position: position.clone(),
+                        position: closed,
                         exit_price: current_price,
                         exit_time: current_time,
                         reason: reason.clone(),
@@ -138,7 +288,6 @@ impl PositionManager {
                         token_id, reason, net_pnl);
 
                     exits.push(exit_result);
-                    to_remove.push(token_id.clone());
                 }
             }
         }
@@ -151,6 +300,13 @@ impl PositionManager {
             }
         }
 
+        // Keep the hedged remainder of partially closed legs
+        for (token_id, remaining) in to_shrink {
+            if let Some(pos) = self.positions.get_mut(&token_id) {
+                pos.size = remaining;
+            }
+        }
+
         // Add to history
         self.history.extend(exits.clone());
 
@@ -188,6 +344,45 @@ impl PositionManager {
         }
     }
 
+    /// Replace a closed position's history entry after its exit was repriced
+    pub fn amend_exit(&mut self, exit: &ExitResult) {
+        if let Some(entry) = self.history.iter_mut().rev()
+            .find(|h| h.position.token_id == exit.position.token_id && h.exit_time == exit.exit_time) {
+            *entry = exit.clone();
+        }
+    }
+
+    /// Get closed positions history (oldest first)
+    pub fn history(&self) -> &[ExitResult] {
+        &self.history
+    }
+
+    /// Open and closed trades matching the query, sorted by entry time, then market and token
+    pub fn query_trades(&self, q: &TradeQuery) -> TradePage {
+        let open = self.positions.values().map(TradeRecord::open);
+        let closed = self.history.iter().map(TradeRecord::closed);
+        let mut matching: Vec<TradeRecord> = open.chain(closed)
+            .filter(|t| q.status.as_ref().is_none_or(|s| t.status == s))
+            .filter(|t| q.market.as_ref().is_none_or(|m| &t.market_id == m))
+            .filter(|t| q.strategy.as_ref().is_none_or(|s| &t.strategy == s))
+            .filter(|t| q.from.is_none_or(|from| t.entry_time >= from))
+            .filter(|t| q.to.is_none_or(|to| t.entry_time <= to))
+            .collect();
+        // Bundle legs share an entry time: break ties so pages neither repeat nor skip trades
+        matching.sort_by(|a, b| {
+            (a.entry_time, &a.market_id, &a.token_id, a.exit_time).cmp(&(b.entry_time, &b.market_id, &b.token_id, b.exit_time))
+        });
+        if q.sort.as_deref() != Some("asc") {
+            matching.reverse();
+        }
+        let total = matching.len();
+        let trades = matching.into_iter()
+            .skip(q.offset.unwrap_or(0))
+            .take(q.limit.unwrap_or(100).min(1000))
+            .collect();
+        TradePage { total, trades }
+    }
+
     /// Get total PnL from history
     pub fn total_pnl(&self) -> f64 {
         self.history.iter().map(|e| e.pnl).sum()
@@ -211,6 +406,7 @@ impl PositionManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing;
 
     #[test]
     fn test_position_manager() {
@@ -224,9 +420,94 @@ mod tests {
             entry_price: 0.50,
             entry_time: 1000,
             entry_spread: 0.03,
+            strategy: "arb".to_string(),
         };
         
         pm.open_position(pos);
         assert_eq!(pm.get_positions().len(), 1);
     }
+
+    #[test]
+    fn test_resolution_risk_closes_only_the_naked_shares() {
+        let now = 1_000_000;
+        let greeks = GreeksConfig { exit_on_decay: true, min_edge_per_day: 0.0, unhedged_exit_secs: 3600 };
+        let mut pm = PositionManager::new(0.01, 0.05, 3600).with_greeks(greeks);
+        for (token_id, size) in [("y", 10.0), ("n", 4.0)] {
+            pm.open_position(Position {
+                market_id: "m1".to_string(),
+                token_id: token_id.to_string(),
+                side: Side::Buy,
+                size,
+                entry_price: 0.45,
+                entry_time: now,
+                entry_spread: 0.0,
+                strategy: "arb".to_string(),
+            });
+        }
+        let market = Market { outcome_prices: vec![0.40, 0.58], end_date: Some(now + 1_800), ..testing::market() };
+
+        // 6 naked YES are sold; the 4 complete sets stay
+        let exits = pm.check_exits(&[market], now, 0.01);
+        assert_eq!(exits.len(), 1);
+        assert_eq!((exits[0].position.token_id.as_str(), exits[0].position.size), ("y", 6.0));
+        assert_eq!((pm.get_position("y").unwrap().size, pm.get_position("n").unwrap().size), (4.0, 4.0));
+    }
+
+    #[test]
+    fn test_query_trades() {
+        let mut pm = PositionManager::new(0.01, 0.05, 3600);
+        for (i, market) in ["m1", "m2", "m1"].iter().enumerate() {
+            pm.open_position(Position {
+                market_id: market.to_string(),
+                token_id: format!("t{}", i),
+                side: Side::Buy,
+                size: 10.0,
+                entry_price: 0.50,
+                entry_time: 1000 + i as u64 * 100,
+                entry_spread: 0.03,
+                strategy: "arb".to_string(),
+            });
+        }
+        pm.close_position("t0", 0.55, 0.0);
+
+        let all = pm.query_trades(&TradeQuery::default());
+        assert_eq!(all.total, 3);
+        assert_eq!(all.trades[0].entry_time, 1200); // newest first
+
+        let q = TradeQuery { market: Some("m1".to_string()), status: Some("closed".to_string()), ..Default::default() };
+        let closed = pm.query_trades(&q);
+        assert_eq!(closed.total, 1);
+        assert!(closed.trades[0].pnl.unwrap() > 0.0);
+
+        let q = TradeQuery { from: Some(1050), sort: Some("asc".to_string()), limit: Some(1), offset: Some(1), ..Default::default() };
+        let page = pm.query_trades(&q);
+        assert_eq!(page.total, 2);
+        assert_eq!(page.trades.len(), 1);
+        assert_eq!(page.trades[0].token_id, "t2");
+    }
+
+    #[test]
+    fn test_query_trades_pages_bundle_legs_deterministically() {
+        let mut pm = PositionManager::new(0.01, 0.05, 3600);
+        for market in ["m2", "m1"] {
+            for token in ["yes", "no"] {
+                pm.open_position(Position {
+                    market_id: market.to_string(),
+                    token_id: format!("{}-{}", market, token),
+                    side: Side::Buy,
+                    size: 10.0,
+                    entry_price: 0.50,
+                    entry_time: 1000,
+                    entry_spread: 0.03,
+                    strategy: "arb".to_string(),
+                });
+            }
+        }
+        let page = |offset| {
+            let q = TradeQuery { sort: Some("asc".to_string()), limit: Some(1), offset: Some(offset), ..Default::default() };
+            pm.query_trades(&q).trades[0].token_id.clone()
+        };
+        let paged: Vec<String> = (0..4).map(page).collect();
+        assert_eq!(paged, vec!["m1-no", "m1-yes", "m2-no", "m2-yes"]);
+    }
 }
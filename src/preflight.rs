@@ -0,0 +1,179 @@
+//! Pre-trade settlement simulation
+//!
+//! Before a trade is submitted, the Smart Account call it would make is run
+//! through `eth_call` against the latest chain state. A revert there (spent
+//! or revoked delegation, insufficient USDC, paused venue) refuses the trade
+//! up front with the decoded revert reason, instead of paying for a failed
+//! settlement and discovering it afterwards.
+
+use crate::permission_guard::ContractCall;
+use crate::rpc_pool::{RpcError, RpcPool};
+use serde_json::{json, Value};
+use sha3::{Digest, Keccak256};
+use std::time::Duration;
+
+/// Solidity signature of `execution::TRADE_METHOD` on the venue contract
+pub const TRADE_SIGNATURE: &str = "fillOrder(uint256)";
+
+/// Collateral decimals unless configured otherwise (USDC)
+const DEFAULT_DECIMALS: u32 = 6;
+
+/// Selector of `Error(string)` reverts
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// Selector of `Panic(uint256)` reverts
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Simulation failures
+#[derive(Debug)]
+pub enum SimulationError {
+    /// RPC unreachable or malformed response (the trade was not checked)
+    Rpc(String),
+    /// The call would revert on-chain
+    Reverted(String),
+}
+
+impl std::fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Rpc(e) => write!(f, "Simulation RPC failed: {}", e),
+            Self::Reverted(reason) => write!(f, "Settlement would revert: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for SimulationError {}
+
+/// First four bytes of keccak256(signature)
+pub fn selector(signature: &str) -> [u8; 4] {
+    let hash = Keccak256::digest(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// ABI-encoded calldata for a trade of `value` collateral with `decimals`
+pub fn trade_calldata(value: f64, decimals: u32) -> String {
+    let amount = (value * 10f64.powi(decimals as i32)).round() as u128;
+    let mut data = selector(TRADE_SIGNATURE).to_vec();
+    data.extend_from_slice(&[0u8; 16]);
+    data.extend_from_slice(&amount.to_be_bytes());
+    format!("0x{}", hex::encode(data))
+}
+
+/// Human-readable reason from revert return data
+pub fn decode_revert(data: &[u8]) -> String {
+    if data.len() < 4 {
+        return "reverted without reason".to_string();
+    }
+    let (selector, body) = data.split_at(4);
+    if selector == ERROR_SELECTOR && body.len() >= 64 {
+        let len = u64::from_be_bytes(body[56..64].try_into().unwrap()) as usize;
+        if let Some(bytes) = body.get(64..64 + len) {
+            return String::from_utf8_lossy(bytes).into_owned();
+        }
+    }
+    if selector == PANIC_SELECTOR && body.len() >= 32 {
+        return format!("panic 0x{:02x}", body[31]);
+    }
+    format!("custom error 0x{}", hex::encode(selector))
+}
+
+/// Runs settlement calls through `eth_call` before they are submitted
+#[derive(Debug, Clone)]
+pub struct SettlementSimulator {
+    /// Providers calls fail over between
+    rpc: RpcPool,
+    chain_id: u64,
+    /// Smart Account the delegation executes from
+    from: String,
+    /// Collateral decimals the trade amount is encoded with
+    decimals: u32,
+}
+
+impl SettlementSimulator {
+    pub fn new(rpc: RpcPool, chain_id: u64, from: &str, timeout: Duration) -> Self {
+        Self {
+            rpc: rpc.with_timeout(timeout),
+            chain_id,
+            from: from.to_string(),
+            decimals: DEFAULT_DECIMALS,
+        }
+    }
+
+    /// Encode amounts with the collateral's decimals
+    pub fn with_decimals(mut self, decimals: u32) -> Self {
+        self.decimals = decimals;
+        self
+    }
+
+    /// Simulate `call` against the latest block
+    pub async fn simulate(&self, call: &ContractCall) -> Result<(), SimulationError> {
+        let params = json!([{
+            "from": self.from,
+            "to": call.target,
+            "data": trade_calldata(call.value, self.decimals),
+        }, "latest"]);
+        let response = match self.rpc.call(self.chain_id, "eth_call", params).await {
+            Ok(result) => json!({ "result": result }),
+            Err(RpcError::Response(error)) => json!({ "error": error }),
+            Err(e) => return Err(SimulationError::Rpc(e.to_string())),
+        };
+        interpret(&response)
+    }
+}
+
+/// Map a JSON-RPC `eth_call` response to the simulation outcome
+fn interpret(response: &Value) -> Result<(), SimulationError> {
+    let Some(error) = response.get("error") else {
+        return match response.get("result") {
+            Some(_) => Ok(()),
+            None => Err(SimulationError::Rpc("response has neither result nor error".to_string())),
+        };
+    };
+    // Nodes put the revert data in `error.data` (hex string)
+    if let Some(data) = error.get("data").and_then(Value::as_str) {
+        if let Ok(bytes) = hex::decode(data.trim_start_matches("0x")) {
+            return Err(SimulationError::Reverted(decode_revert(&bytes)));
+        }
+    }
+    let message = error.get("message").and_then(Value::as_str).unwrap_or("unknown error");
+    if message.contains("revert") {
+        Err(SimulationError::Reverted(message.to_string()))
+    } else {
+        Err(SimulationError::Rpc(message.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_simulation_outcome() {
+        // Error("allowance exceeded")
+        let reason = "allowance exceeded";
+        let mut data = ERROR_SELECTOR.to_vec();
+        data.extend_from_slice(&[0u8; 31]);
+        data.push(0x20);
+        data.extend_from_slice(&[0u8; 31]);
+        data.push(reason.len() as u8);
+        data.extend_from_slice(reason.as_bytes());
+        data.resize(4 + 96, 0);
+        let response = json!({"jsonrpc": "2.0", "id": 1, "error": {
+            "code": 3, "message": "execution reverted", "data": format!("0x{}", hex::encode(&data)),
+        }});
+        match interpret(&response) {
+            Err(SimulationError::Reverted(r)) => assert_eq!(r, reason),
+            other => panic!("expected revert, got {:?}", other),
+        }
+
+        assert!(interpret(&json!({"jsonrpc": "2.0", "id": 1, "result": "0x"})).is_ok());
+        let unreachable = json!({"error": {"code": -32000, "message": "header not found"}});
+        assert!(matches!(interpret(&unreachable), Err(SimulationError::Rpc(_))));
+
+        // 5 USDC → 5_000_000 in the last word
+        let calldata = trade_calldata(5.0, 6);
+        assert_eq!(calldata.len(), 2 + 8 + 64);
+        assert!(calldata.ends_with("4c4b40"));
+        // 0.5 SOL → 500_000_000 lamports
+        assert!(trade_calldata(0.5, 9).ends_with("1dcd6500"));
+    }
+}
@@ -0,0 +1,244 @@
+//! Dry-run cost preview: `POST /api/preview` prices a hypothetical trade with
+//! the engine's own books, fees and pre-trade checks, between ticks, executing nothing
+
+use crate::fees::FeeModel;
+use crate::fills::FillModel;
+use crate::slippage::SlippageModel;
+use crate::types::{Market, OrderBook, Side};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+/// `POST /api/preview` body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviewRequest {
+    pub market_id: String,
+    pub side: Side,
+    /// Size per leg (shares)
+    pub size: f64,
+}
+
+/// Cost of one leg at the current book
+#[derive(Debug, Clone, Serialize)]
+pub struct LegCost {
+    pub token_id: String,
+    pub outcome: String,
+    /// Size the book can fill now (may be below the request)
+    pub fillable_size: f64,
+    /// Volume-weighted price for the fillable size
+    pub executable_price: Option<f64>,
+    pub midpoint: Option<f64>,
+    /// Price impact vs the midpoint (fraction)
+    pub slippage: Option<f64>,
+    pub notional: f64,
+    pub fee: f64,
+    pub gas: f64,
+}
+
+/// Outcome of one pre-trade check
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PreviewCheck {
+    pub check: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+/// Full cost breakdown of a hypothetical bundle trade
+#[derive(Debug, Clone, Serialize)]
+pub struct CostPreview {
+    pub market_id: String,
+    pub side: Side,
+    pub size: f64,
+    pub legs: Vec<LegCost>,
+    pub total_notional: f64,
+    pub total_fees: f64,
+    pub total_gas: f64,
+    /// Bundle payout minus notional (before fees and gas)
+    pub gross_edge: f64,
+    pub net_edge: f64,
+    /// Std-dev of the adverse price move applied between signal and fill
+    pub adverse_move_std: f64,
+    pub checks: Vec<PreviewCheck>,
+}
+
+impl CostPreview {
+    /// Whether every check passed
+    pub fn would_trade(&self) -> bool {
+        self.checks.iter().all(|c| c.passed)
+    }
+
+    pub fn check(&mut self, check: &str, result: Result<String, String>) {
+        let (passed, detail) = match result {
+            Ok(detail) => (true, detail),
+            Err(detail) => (false, detail),
+        };
+        self.checks.push(PreviewCheck { check: check.to_string(), passed, detail });
+    }
+}
+
+/// Cost breakdown of trading `request.size` on every leg of `market` at
+/// `books` (one per leg). A buy bundle pays $1 per share at resolution;
+/// selling a bundle collects the bids and owes that $1.
+pub fn cost_breakdown(request: &PreviewRequest, market: &Market, books: &[OrderBook], fees: &FeeModel,
+                      gas_per_leg: f64, adverse_move_std: f64) -> CostPreview {
+    let legs: Vec<LegCost> = books.iter().enumerate().map(|(i, book)| {
+        let fillable_size = FillModel::filled_size(book, request.size, request.side);
+        let executable_price = book.execution_price(fillable_size, request.side).filter(|_| fillable_size > 0.0);
+        let notional = executable_price.unwrap_or(0.0) * fillable_size;
+        LegCost {
+            token_id: book.token_id.clone(),
+            outcome: market.outcomes.get(i).cloned().unwrap_or_default(),
+            fillable_size,
+            executable_price,
+            midpoint: book.midpoint(),
+            slippage: SlippageModel::calculate(book, fillable_size, request.side),
+            notional,
+            fee: fees.calculate(notional, false),
+            gas: gas_per_leg,
+        }
+    }).collect();
+    let total_notional: f64 = legs.iter().map(|l| l.notional).sum();
+    let total_fees: f64 = legs.iter().map(|l| l.fee).sum();
+    let total_gas: f64 = legs.iter().map(|l| l.gas).sum();
+    // A bundle only pays out for the size every leg fills
+    let bundle_size = legs.iter().map(|l| l.fillable_size).fold(f64::INFINITY, f64::min);
+    let bundle_size = if bundle_size.is_finite() { bundle_size } else { 0.0 };
+    let gross_edge = match request.side {
+        Side::Buy => bundle_size - total_notional,
+        Side::Sell => total_notional - bundle_size,
+    };
+    CostPreview {
+        market_id: market.id.clone(),
+        side: request.side,
+        size: request.size,
+        legs,
+        total_notional,
+        total_fees,
+        total_gas,
+        gross_edge,
+        net_edge: gross_edge - total_fees - total_gas,
+        adverse_move_std,
+        checks: Vec::new(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PreviewError {
+    Invalid(String),
+    /// The engine is not running or did not answer in time
+    Unavailable,
+    /// The engine could not price the trade (unknown market, book fetch failed)
+    Engine(String),
+}
+
+impl std::fmt::Display for PreviewError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Invalid(e) => write!(f, "invalid preview request: {}", e),
+            Self::Unavailable => write!(f, "engine did not answer the preview"),
+            Self::Engine(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// A preview waiting for the engine
+pub struct PreviewJob {
+    pub request: PreviewRequest,
+    reply: oneshot::Sender<Result<CostPreview, String>>,
+}
+
+impl PreviewJob {
+    pub fn respond(self, result: Result<CostPreview, String>) {
+        let _ = self.reply.send(result);
+    }
+}
+
+/// API side of the preview channel
+#[derive(Clone)]
+pub struct PreviewDesk {
+    tx: mpsc::Sender<PreviewJob>,
+}
+
+/// Desk for the API and the receiver the engine answers from
+pub fn channel(capacity: usize) -> (PreviewDesk, mpsc::Receiver<PreviewJob>) {
+    let (tx, rx) = mpsc::channel(capacity.max(1));
+    (PreviewDesk { tx }, rx)
+}
+
+impl PreviewDesk {
+    /// (queued, capacity) of requests waiting for the engine
+    pub fn backlog(&self) -> (usize, usize) {
+        (self.tx.max_capacity() - self.tx.capacity(), self.tx.max_capacity())
+    }
+
+    /// Ask the engine for a preview, waiting at most `timeout`
+    pub async fn preview(&self, request: PreviewRequest, timeout: Duration) -> Result<CostPreview, PreviewError> {
+        if !(request.size.is_finite() && request.size > 0.0) {
+            return Err(PreviewError::Invalid("size must be positive".to_string()));
+        }
+        let (reply, answer) = oneshot::channel();
+        self.tx.try_send(PreviewJob { request, reply }).map_err(|_| PreviewError::Unavailable)?;
+        match tokio::time::timeout(timeout, answer).await {
+            Ok(Ok(result)) => result.map_err(PreviewError::Engine),
+            _ => Err(PreviewError::Unavailable),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, book};
+
+    fn books() -> [OrderBook; 2] {
+        [book("y", &[(0.46, 100.0)], &[(0.47, 100.0)]), book("n", &[(0.47, 100.0)], &[(0.48, 100.0)])]
+    }
+
+    fn market() -> Market {
+        Market { best_bid: Some(0.46), best_ask: Some(0.48), ..testing::market() }
+    }
+
+    fn request(size: f64) -> PreviewRequest {
+        PreviewRequest { market_id: "m1".to_string(), side: Side::Buy, size }
+    }
+
+    const FEES: FeeModel = FeeModel { maker_fee_bps: 0, taker_fee_bps: 200 };
+
+    #[test]
+    fn test_cost_breakdown() {
+        let preview = cost_breakdown(&request(10.0), &market(), &books(), &FEES, 0.01, 0.0);
+        assert_eq!(preview.legs[1].outcome, "No");
+        assert!((preview.total_notional - 9.5).abs() < 1e-9);
+        assert!((preview.gross_edge - 0.5).abs() < 1e-9);
+        assert!((preview.net_edge - (0.5 - 0.19 - 0.02)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_failed_check_blocks_the_trade() {
+        let mut preview = cost_breakdown(&request(10.0), &market(), &books(), &FEES, 0.01, 0.0);
+        preview.check("risk", Ok("not halted".to_string()));
+        preview.check("allowance", Err("$9.69 exceeds $5.00 remaining".to_string()));
+        assert!(!preview.would_trade());
+    }
+
+    #[tokio::test]
+    async fn test_engine_round_trip() {
+        let (desk, mut rx) = channel(4);
+        let engine = tokio::spawn(async move {
+            let job = rx.recv().await.unwrap();
+            let result = cost_breakdown(&job.request, &market(), &books(), &FEES, 0.0, 0.0);
+            job.respond(Ok(result));
+        });
+        let answered = desk.preview(request(10.0), Duration::from_secs(1)).await.unwrap();
+        assert_eq!(answered.legs.len(), 2);
+        engine.await.unwrap();
+        // Nobody serving the channel any more
+        assert_eq!(desk.preview(request(10.0), Duration::from_millis(10)).await.unwrap_err(), PreviewError::Unavailable);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_request_rejected() {
+        let (desk, _rx) = channel(4);
+        assert!(matches!(desk.preview(request(0.0), Duration::from_millis(10)).await, Err(PreviewError::Invalid(_))));
+    }
+}
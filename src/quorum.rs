@@ -0,0 +1,173 @@
+//! Multi-source price quorum
+//!
+//! Wraps two independent market data sources (e.g. Envio and CLOB REST).
+//! Every order book is fetched from both and only returned when the best
+//! bid / ask agree within a tolerance; divergences are refused and recorded
+//! as data-quality incidents, so a single indexer bug cannot feed bad prices
+//! to the detector. Markets and trades come from the primary source.
+
+use crate::api::log_event;
+use crate::events::EventLevel;
+use crate::market_client::{EnvioHealth, MarketClient};
+use crate::types::{Market, OrderBook, Trade};
+use async_trait::async_trait;
+use serde::Serialize;
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+/// Incidents kept in memory
+const MAX_INCIDENTS: usize = 500;
+
+/// A disagreement between the two sources
+#[derive(Debug, Clone, Serialize)]
+pub struct DataQualityIncident {
+    pub token_id: String,
+    /// "bid", "ask" or "missing"
+    pub field: String,
+    pub primary: Option<f64>,
+    pub secondary: Option<f64>,
+    pub timestamp: u64,
+}
+
+/// Compare the top of book of two snapshots of the same token
+pub fn check_agreement(primary: &OrderBook, secondary: &OrderBook, tolerance: f64) -> Result<(), (String, Option<f64>, Option<f64>)> {
+    for (field, a, b) in [
+        ("bid", primary.best_bid(), secondary.best_bid()),
+        ("ask", primary.best_ask(), secondary.best_ask()),
+    ] {
+        match (a, b) {
+            (Some(x), Some(y)) if (x - y).abs() > tolerance => return Err((field.to_string(), a, b)),
+            (Some(_), None) | (None, Some(_)) => return Err((field.to_string(), a, b)),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Market client that only trusts books both sources agree on
+pub struct QuorumClient {
+    primary: Box<dyn MarketClient + Send + Sync>,
+    secondary: Box<dyn MarketClient + Send + Sync>,
+    /// Max absolute price difference at top of book
+    tolerance: f64,
+    incidents: Arc<Mutex<Vec<DataQualityIncident>>>,
+}
+
+impl QuorumClient {
+    pub fn new(
+        primary: Box<dyn MarketClient + Send + Sync>,
+        secondary: Box<dyn MarketClient + Send + Sync>,
+        tolerance: f64,
+    ) -> Self {
+        Self { primary, secondary, tolerance, incidents: Arc::new(Mutex::new(Vec::new())) }
+    }
+
+    /// Shared handle to the recorded divergences (oldest first); stays
+    /// readable after the client is boxed as a `dyn MarketClient`
+    pub fn incidents(&self) -> Arc<Mutex<Vec<DataQualityIncident>>> {
+        self.incidents.clone()
+    }
+
+    fn record(&self, incident: DataQualityIncident) {
+        let msg = format!("⚠️ [Quorum] Sources disagree on {} {}: primary {:?} vs secondary {:?}",
+            incident.token_id, incident.field, incident.primary, incident.secondary);
+        println!("{}", msg);
+        log_event(EventLevel::Warn, "quorum", None, &msg);
+        let mut incidents = self.incidents.lock().unwrap();
+        incidents.push(incident);
+        if incidents.len() > MAX_INCIDENTS {
+            incidents.remove(0);
+        }
+    }
+}
+
+#[async_trait]
+impl MarketClient for QuorumClient {
+    async fn get_markets(&self) -> Result<Vec<Market>, Box<dyn Error + Send + Sync>> {
+        self.primary.get_markets().await
+    }
+
+    async fn get_order_book(&self, token_id: &str) -> Result<OrderBook, Box<dyn Error + Send + Sync>> {
+        let (primary, secondary) = tokio::join!(
+            self.primary.get_order_book(token_id),
+            self.secondary.get_order_book(token_id),
+        );
+        let primary = primary?;
+        let timestamp = crate::wallet::Wallet::current_timestamp();
+        let secondary = match secondary {
+            Ok(book) => book,
+            Err(e) => {
+                self.record(DataQualityIncident {
+                    token_id: token_id.to_string(),
+                    field: "missing".to_string(),
+                    primary: primary.midpoint(),
+                    secondary: None,
+                    timestamp,
+                });
+                return Err(format!("No quorum for {}: secondary source failed ({})", token_id, e).into());
+            }
+        };
+        if let Err((field, a, b)) = check_agreement(&primary, &secondary, self.tolerance) {
+            self.record(DataQualityIncident {
+                token_id: token_id.to_string(),
+                field,
+                primary: a,
+                secondary: b,
+                timestamp,
+            });
+            return Err(format!("No quorum for {}: sources diverge beyond {:.4}", token_id, self.tolerance).into());
+        }
+        Ok(primary)
+    }
+
+    async fn stream_quotes(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.primary.stream_quotes().await
+    }
+
+    async fn get_trades(&self, market: &Market) -> Result<Vec<Trade>, Box<dyn Error + Send + Sync>> {
+        self.primary.get_trades(market).await
+    }
+
+    async fn data_health(&self) -> Option<EnvioHealth> {
+        self.primary.data_health().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PriceLevel;
+
+    struct FixedBook(f64);
+
+    #[async_trait]
+    impl MarketClient for FixedBook {
+        async fn get_markets(&self) -> Result<Vec<Market>, Box<dyn Error + Send + Sync>> {
+            Ok(Vec::new())
+        }
+        async fn get_order_book(&self, token_id: &str) -> Result<OrderBook, Box<dyn Error + Send + Sync>> {
+            Ok(OrderBook {
+                token_id: token_id.to_string(),
+                bids: vec![PriceLevel { price: self.0, size: 100.0 }],
+                asks: vec![PriceLevel { price: self.0 + 0.02, size: 100.0 }],
+                timestamp: 0,
+            })
+        }
+        async fn stream_quotes(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_quorum_agreement() {
+        let agree = QuorumClient::new(Box::new(FixedBook(0.48)), Box::new(FixedBook(0.485)), 0.01);
+        assert!(agree.get_order_book("t1").await.is_ok());
+        assert!(agree.incidents().lock().unwrap().is_empty());
+
+        let diverge = QuorumClient::new(Box::new(FixedBook(0.48)), Box::new(FixedBook(0.60)), 0.01);
+        assert!(diverge.get_order_book("t1").await.is_err());
+        let incidents = diverge.incidents().lock().unwrap().clone();
+        assert_eq!(incidents.len(), 1);
+        assert_eq!(incidents[0].field, "bid");
+    }
+}
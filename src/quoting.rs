@@ -0,0 +1,209 @@
+// Anti-flicker rules for resting quotes
+// Holds back, dedupes, caps and batches cancels / replaces of resting orders per market
+
+use crate::config::QuotingConfig;
+use crate::fills::PassiveOrder;
+use crate::orders::RestingOrder;
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+
+/// Replacements are rate limited over this window
+const RATE_WINDOW_SECS: u64 = 60;
+
+/// Change to a resting order
+#[derive(Debug, Clone)]
+pub enum Modification {
+    Replace(PassiveOrder),
+    Cancel(String),
+}
+
+/// Why a modification was not accepted
+#[derive(Debug, Clone, PartialEq)]
+pub enum Hold {
+    /// The order has rested for less than the minimum
+    MinRest { rested_secs: u64 },
+    /// The market already used its replacements for this minute
+    RateLimited { recent: usize },
+    /// The replacement would quote the same price and size
+    Unchanged,
+}
+
+impl std::fmt::Display for Hold {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::MinRest { rested_secs } => write!(f, "resting only {}s", rested_secs),
+            Self::RateLimited { recent } => write!(f, "{} re-quotes in the last minute", recent),
+            Self::Unchanged => write!(f, "quote unchanged"),
+        }
+    }
+}
+
+/// Modifications for one market, released together
+#[derive(Debug, Clone)]
+pub struct QuoteBatch {
+    pub market_id: String,
+    /// (order id, modification)
+    pub modifications: Vec<(String, Modification)>,
+}
+
+/// Per-market quoting activity (`GET /api/quoting`)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct QuoteCounters {
+    pub placed: u64,
+    pub replaced: u64,
+    pub cancelled: u64,
+    pub held_min_rest: u64,
+    pub held_rate_limit: u64,
+    pub unchanged: u64,
+    /// Queued modifications superseded by a newer one for the same order
+    pub coalesced: u64,
+    pub batches: u64,
+}
+
+#[derive(Debug)]
+struct Queued {
+    market_id: String,
+    order_id: String,
+    modification: Modification,
+}
+
+#[derive(Debug)]
+pub struct QuoteGovernor {
+    config: QuotingConfig,
+    queue: Vec<Queued>,
+    /// When each market's oldest queued modification was accepted
+    queued_since: BTreeMap<String, u64>,
+    /// Released replacements per market within the rate window
+    replacements: BTreeMap<String, VecDeque<u64>>,
+    counters: BTreeMap<String, QuoteCounters>,
+}
+
+impl QuoteGovernor {
+    pub fn new(config: QuotingConfig) -> Self {
+        Self { config, queue: Vec::new(), queued_since: BTreeMap::new(), replacements: BTreeMap::new(), counters: BTreeMap::new() }
+    }
+
+    pub fn record_placed(&mut self, market_id: &str) {
+        self.counters.entry(market_id.to_string()).or_default().placed += 1;
+    }
+
+    /// Queue a change to `resting`, or say why it is held back (try again later)
+    pub fn submit(&mut self, market_id: &str, resting: &RestingOrder, modification: Modification, now: u64) -> Result<(), Hold> {
+        let counters = self.counters.entry(market_id.to_string()).or_default();
+        if self.config.enabled {
+            let rested_secs = now.saturating_sub(resting.placed_at);
+            if rested_secs < self.config.min_rest_secs {
+                counters.held_min_rest += 1;
+                return Err(Hold::MinRest { rested_secs });
+            }
+            if let Modification::Replace(fresh) = &modification {
+                let current = &resting.order;
+                if (fresh.price - current.price).abs() < 1e-9 && (fresh.size - current.remaining()).abs() < 1e-9 {
+                    counters.unchanged += 1;
+                    return Err(Hold::Unchanged);
+                }
+                let released = self.replacements.entry(market_id.to_string()).or_default();
+                while released.front().is_some_and(|t| now.saturating_sub(*t) >= RATE_WINDOW_SECS) {
+                    released.pop_front();
+                }
+                // Queued replacements count too, except one this would supersede
+                let queued = self.queue.iter()
+                    .filter(|q| q.market_id == market_id && q.order_id != resting.order_id && matches!(q.modification, Modification::Replace(_)))
+                    .count();
+                let recent = released.len() + queued;
+                if self.config.max_requotes_per_min > 0 && recent >= self.config.max_requotes_per_min {
+                    counters.held_rate_limit += 1;
+                    return Err(Hold::RateLimited { recent });
+                }
+            }
+        }
+        if let Some(pos) = self.queue.iter().position(|q| q.order_id == resting.order_id) {
+            counters.coalesced += 1;
+            self.queue.remove(pos);
+        }
+        self.queued_since.entry(market_id.to_string()).or_insert(now);
+        self.queue.push(Queued { market_id: market_id.to_string(), order_id: resting.order_id.clone(), modification });
+        Ok(())
+    }
+
+    /// Batches whose window has passed, oldest market first
+    pub fn due(&mut self, now: u64) -> Vec<QuoteBatch> {
+        let window = if self.config.enabled { self.config.batch_window_secs } else { 0 };
+        let ready: Vec<String> = self.queued_since.iter()
+            .filter(|(_, since)| now.saturating_sub(**since) >= window)
+            .map(|(market, _)| market.clone())
+            .collect();
+        let mut batches = Vec::new();
+        for market_id in ready {
+            self.queued_since.remove(&market_id);
+            let (batch, rest): (Vec<Queued>, Vec<Queued>) = std::mem::take(&mut self.queue).into_iter().partition(|q| q.market_id == market_id);
+            self.queue = rest;
+            let counters = self.counters.entry(market_id.clone()).or_default();
+            counters.batches += 1;
+            for q in &batch {
+                match q.modification {
+                    Modification::Replace(_) => {
+                        counters.replaced += 1;
+                        self.replacements.entry(market_id.clone()).or_default().push_back(now);
+                    }
+                    Modification::Cancel(_) => counters.cancelled += 1,
+                }
+            }
+            batches.push(QuoteBatch { market_id, modifications: batch.into_iter().map(|q| (q.order_id, q.modification)).collect() });
+        }
+        batches
+    }
+
+    /// Drop queued changes to an order that closed meanwhile (filled or expired)
+    pub fn forget(&mut self, order_id: &str) {
+        self.queue.retain(|q| q.order_id != order_id);
+        let live: std::collections::HashSet<&str> = self.queue.iter().map(|q| q.market_id.as_str()).collect();
+        self.queued_since.retain(|market, _| live.contains(market.as_str()));
+    }
+
+    pub fn counters(&self) -> BTreeMap<String, QuoteCounters> {
+        self.counters.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Side;
+
+    fn resting(order_id: &str, price: f64, placed_at: u64) -> RestingOrder {
+        let order = PassiveOrder { token_id: "t1".to_string(), side: Side::Buy, price, size: 10.0, queue_ahead: 0.0, filled: 0.0 };
+        RestingOrder { order_id: order_id.to_string(), order, placed_at, cursor: placed_at, expires_at: 0 }
+    }
+
+    fn quote(price: f64) -> Modification {
+        Modification::Replace(PassiveOrder { token_id: "t1".to_string(), side: Side::Buy, price, size: 10.0, queue_ahead: 0.0, filled: 0.0 })
+    }
+
+    #[test]
+    fn test_holds_rate_limits_and_batches() {
+        let config = QuotingConfig { enabled: true, min_rest_secs: 10, max_requotes_per_min: 1, batch_window_secs: 5 };
+        let mut governor = QuoteGovernor::new(config);
+
+        assert_eq!(governor.submit("m1", &resting("a", 0.40, 100), quote(0.41), 105), Err(Hold::MinRest { rested_secs: 5 }));
+        assert_eq!(governor.submit("m1", &resting("a", 0.40, 100), quote(0.40), 110), Err(Hold::Unchanged));
+        assert!(governor.submit("m1", &resting("a", 0.40, 100), quote(0.41), 110).is_ok());
+        // A newer quote for the same order supersedes the queued one
+        assert!(governor.submit("m1", &resting("a", 0.40, 100), quote(0.42), 111).is_ok());
+        assert_eq!(governor.submit("m1", &resting("b", 0.40, 100), quote(0.43), 112), Err(Hold::RateLimited { recent: 1 }));
+        assert!(governor.submit("m1", &resting("b", 0.40, 100), Modification::Cancel("decayed".to_string()), 112).is_ok());
+
+        assert!(governor.due(114).is_empty(), "batch window still open");
+        let batches = governor.due(115);
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].modifications.len(), 2);
+        assert!(matches!(&batches[0].modifications[0], (id, Modification::Replace(o)) if id == "a" && o.price == 0.42));
+
+        let c = &governor.counters()["m1"];
+        assert_eq!((c.replaced, c.cancelled, c.coalesced, c.batches), (1, 1, 1, 1));
+        assert_eq!((c.held_min_rest, c.held_rate_limit, c.unchanged), (1, 1, 1));
+        // The released re-quote uses up the minute; then the window slides on
+        assert!(governor.submit("m1", &resting("b", 0.40, 100), quote(0.43), 170).is_err());
+        assert!(governor.submit("m1", &resting("b", 0.40, 100), quote(0.43), 175).is_ok());
+    }
+}
@@ -0,0 +1,153 @@
+//! Per-client token buckets for API control actions
+//!
+//! Every write to the API (kill switch, permission grant, strategy toggles,
+//! approvals) spends a token from the bucket of the caller's address, so a
+//! client cannot reset its budget by changing what it calls itself. Buckets
+//! hold `burst` tokens and refill continuously; an empty bucket means the
+//! request is answered with 429 and a retry delay. Allowed and limited
+//! requests are counted per address, labeled with the client id it last
+//! declared, so abusive dashboards or scripts are visible. Idle addresses are
+//! evicted.
+
+use crate::config::ApiRateLimitConfig;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Addresses idle this long are forgotten (their bucket only once it has refilled)
+const IDLE_EVICT_MS: u64 = 10 * 60 * 1000;
+
+#[derive(Debug, Clone)]
+struct Bucket {
+    tokens: f64,
+    updated_ms: u64,
+}
+
+/// Request counters of one client address
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ClientStats {
+    /// `X-Client-Id` of the last request, if it sent one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    pub allowed: u64,
+    pub limited: u64,
+    pub last_request_ms: u64,
+}
+
+/// Token bucket per client address
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: ApiRateLimitConfig,
+    buckets: HashMap<String, Bucket>,
+    stats: HashMap<String, ClientStats>,
+    last_evict_ms: u64,
+}
+
+impl RateLimiter {
+    pub fn new(config: ApiRateLimitConfig) -> Self {
+        Self { config, buckets: HashMap::new(), stats: HashMap::new(), last_evict_ms: 0 }
+    }
+
+    /// Spend a token for the client at `peer` (labeled `client_id` in the stats);
+    /// on an empty bucket, the wait (ms) until one refills
+    pub fn check(&mut self, peer: &str, client_id: Option<&str>, now_ms: u64) -> Result<(), u64> {
+        if now_ms.saturating_sub(self.last_evict_ms) >= IDLE_EVICT_MS {
+            self.evict_idle(now_ms);
+        }
+        let stats = self.stats.entry(peer.to_string()).or_default();
+        stats.last_request_ms = now_ms;
+        if let Some(id) = client_id {
+            stats.client_id = Some(id.to_string());
+        }
+        if !self.config.enabled {
+            stats.allowed += 1;
+            return Ok(());
+        }
+        let burst = self.config.burst.max(1) as f64;
+        let bucket = self.buckets.entry(peer.to_string())
+            .or_insert(Bucket { tokens: burst, updated_ms: now_ms });
+        let elapsed_secs = now_ms.saturating_sub(bucket.updated_ms) as f64 / 1000.0;
+        bucket.tokens = (bucket.tokens + elapsed_secs * self.config.refill_per_sec).min(burst);
+        bucket.updated_ms = now_ms;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            stats.allowed += 1;
+            Ok(())
+        } else {
+            stats.limited += 1;
+            let missing = 1.0 - bucket.tokens;
+            Err((missing / self.config.refill_per_sec.max(1e-9) * 1000.0).ceil() as u64)
+        }
+    }
+
+    /// Counters per client address
+    pub fn stats(&self) -> &HashMap<String, ClientStats> {
+        &self.stats
+    }
+
+    /// Forget addresses idle for `IDLE_EVICT_MS`; a bucket is kept until it has refilled,
+    /// so eviction never hands out a fresh burst early
+    fn evict_idle(&mut self, now_ms: u64) {
+        let burst = self.config.burst.max(1) as f64;
+        let refill_per_sec = self.config.refill_per_sec;
+        self.buckets.retain(|_, bucket| {
+            let idle_ms = now_ms.saturating_sub(bucket.updated_ms);
+            idle_ms < IDLE_EVICT_MS || bucket.tokens + idle_ms as f64 / 1000.0 * refill_per_sec < burst
+        });
+        let buckets = &self.buckets;
+        self.stats.retain(|peer, stats| now_ms.saturating_sub(stats.last_request_ms) < IDLE_EVICT_MS || buckets.contains_key(peer));
+        self.last_evict_ms = now_ms;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter() -> RateLimiter {
+        RateLimiter::new(ApiRateLimitConfig { enabled: true, burst: 3, refill_per_sec: 0.5 })
+    }
+
+    #[test]
+    fn test_bucket_limits_per_client_and_refills() {
+        let mut limiter = limiter();
+        for _ in 0..3 {
+            assert!(limiter.check("10.0.0.1", Some("dash"), 0).is_ok());
+        }
+        // Empty: one token takes 2s at 0.5/s
+        assert_eq!(limiter.check("10.0.0.1", Some("dash"), 0), Err(2000));
+        // Other addresses have their own bucket
+        assert!(limiter.check("10.0.0.2", None, 0).is_ok());
+
+        assert_eq!(limiter.check("10.0.0.1", Some("dash"), 1000), Err(1000));
+        assert!(limiter.check("10.0.0.1", Some("dash"), 2000).is_ok());
+
+        let stats = &limiter.stats()["10.0.0.1"];
+        assert_eq!((stats.allowed, stats.limited), (4, 2));
+        assert_eq!(stats.client_id.as_deref(), Some("dash"));
+    }
+
+    #[test]
+    fn test_rotating_client_id_shares_the_address_bucket() {
+        let mut limiter = limiter();
+        for i in 0..3 {
+            assert!(limiter.check("10.0.0.1", Some(&format!("fake-{}", i)), 0).is_ok());
+        }
+        assert_eq!(limiter.check("10.0.0.1", Some("fake-3"), 0), Err(2000));
+        assert_eq!(limiter.stats().len(), 1);
+    }
+
+    #[test]
+    fn test_idle_addresses_are_evicted_once_refilled() {
+        let mut limiter = RateLimiter::new(ApiRateLimitConfig { enabled: true, burst: 3, refill_per_sec: 0.002 });
+        for _ in 0..3 {
+            assert!(limiter.check("10.0.0.1", None, 0).is_ok());
+        }
+        assert!(limiter.check("10.0.0.2", None, 0).is_ok());
+        // 10 minutes on: .2 has refilled and is dropped, the drained .1 is still refilling
+        assert!(limiter.check("10.0.0.3", None, IDLE_EVICT_MS).is_ok());
+        let mut peers: Vec<&String> = limiter.stats().keys().collect();
+        peers.sort();
+        assert_eq!(peers, vec!["10.0.0.1", "10.0.0.3"]);
+        assert_eq!(limiter.stats()["10.0.0.1"].allowed, 3);
+    }
+}
@@ -0,0 +1,308 @@
+//! Orders / fills reconciliation against the venue
+//!
+//! Every order the engine signs and posts is journaled with what it expects
+//! of it (filled now, or resting until its expiry). `GET /api/reconcile`
+//! fetches the venue's own record - our open orders and our fills in the
+//! window - and diffs the two: orders or fills the venue has that we never
+//! sent, orders we sent that the venue has no trace of, and fills whose size
+//! or average price disagree with our books. Silent accounting drift becomes
+//! a visible report.
+
+use crate::config::ReconcileConfig;
+use crate::storage::JsonlStore;
+use crate::types::Side;
+use crate::venue::{OrderClient, VenueFill, VenueOrder};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+/// An order we posted to the venue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmittedOrder {
+    pub order_id: String,
+    pub market_id: String,
+    pub token_id: String,
+    pub side: Side,
+    pub price: f64,
+    pub size: f64,
+    /// Size our books record as filled (0 for a resting order)
+    pub expected_fill: f64,
+    pub expires_at: Option<u64>,
+    pub timestamp: u64,
+}
+
+/// Journal of orders posted to the venue
+#[derive(Debug, Default)]
+pub struct OrderJournal {
+    orders: Vec<SubmittedOrder>,
+    journal: Option<JsonlStore>,
+}
+
+impl OrderJournal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Persist to `journal`, restoring the orders already in it (an amended order's last record wins)
+    pub fn with_journal(mut self, journal: JsonlStore) -> Self {
+        let records: Vec<SubmittedOrder> = journal.load().unwrap_or_else(|e| {
+            eprintln!("⚠️ [Reconcile] Failed to read order journal: {}", e);
+            Vec::new()
+        });
+        for order in records {
+            match self.orders.iter_mut().find(|o| o.order_id == order.order_id) {
+                Some(existing) => *existing = order,
+                None => self.orders.push(order),
+            }
+        }
+        self.journal = Some(journal);
+        self
+    }
+
+    pub fn record(&mut self, order: SubmittedOrder) {
+        self.persist(&order);
+        self.orders.push(order);
+    }
+
+    /// Correct the fill our books expect of `order_id` (a settlement that never landed);
+    /// false if the order is unknown
+    pub fn amend_fill(&mut self, order_id: &str, expected_fill: f64) -> bool {
+        let Some(order) = self.orders.iter_mut().find(|o| o.order_id == order_id) else { return false };
+        order.expected_fill = expected_fill.max(0.0);
+        let order = order.clone();
+        self.persist(&order);
+        true
+    }
+
+    pub fn get(&self, order_id: &str) -> Option<&SubmittedOrder> {
+        self.orders.iter().find(|o| o.order_id == order_id)
+    }
+
+    fn persist(&self, order: &SubmittedOrder) {
+        if let Some(journal) = &self.journal {
+            if let Err(e) = journal.append(order) {
+                eprintln!("⚠️ [Reconcile] Failed to journal order {}: {}", order.order_id, e);
+            }
+        }
+    }
+
+    pub fn contains(&self, order_id: &str) -> bool {
+        self.orders.iter().any(|o| o.order_id == order_id)
+    }
+
+    /// Orders posted within `[from, to]`
+    pub fn window(&self, from: u64, to: u64) -> Vec<SubmittedOrder> {
+        self.orders.iter().filter(|o| o.timestamp >= from && o.timestamp <= to).cloned().collect()
+    }
+}
+
+/// One disagreement between our books and the venue's
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Mismatch {
+    /// The venue has an order or fills on an order we never posted
+    UnknownOrder { order_id: String, token_id: String, filled: f64 },
+    /// We posted an order the venue neither lists as open nor filled
+    MissingOrder { order_id: String, token_id: String, expected_fill: f64 },
+    /// Venue's open order disagrees with what we posted
+    OrderTerms { order_id: String, detail: String },
+    FillSize { order_id: String, local: f64, venue: f64 },
+    /// Volume-weighted venue price vs the price we booked
+    FillPrice { order_id: String, local: f64, venue: f64 },
+}
+
+/// `GET /api/reconcile` response
+#[derive(Debug, Clone, Serialize)]
+pub struct ReconcileReport {
+    pub venue: String,
+    pub from: u64,
+    pub to: u64,
+    pub local_orders: usize,
+    pub venue_open_orders: usize,
+    pub venue_fills: usize,
+    /// Local orders the venue agrees with
+    pub matched: usize,
+    pub mismatches: Vec<Mismatch>,
+}
+
+/// `GET /api/reconcile` query (unix seconds; defaults to the configured window ending now)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ReconcileQuery {
+    pub from: Option<u64>,
+    pub to: Option<u64>,
+}
+
+/// Diff local orders against the venue's open orders and fills
+pub fn reconcile(local: &[SubmittedOrder], open: &[VenueOrder], fills: &[VenueFill], config: &ReconcileConfig, now: u64) -> Vec<Mismatch> {
+    // (size, notional) filled per order on the venue
+    let mut filled: BTreeMap<&str, (f64, f64)> = BTreeMap::new();
+    for fill in fills {
+        let entry = filled.entry(fill.order_id.as_str()).or_default();
+        entry.0 += fill.size;
+        entry.1 += fill.size * fill.price;
+    }
+    let open_by_id: HashMap<&str, &VenueOrder> = open.iter().map(|o| (o.order_id.as_str(), o)).collect();
+    let ours: HashMap<&str, &SubmittedOrder> = local.iter().map(|o| (o.order_id.as_str(), o)).collect();
+    let mut mismatches = Vec::new();
+
+    for order in local {
+        let venue_fill = filled.get(order.order_id.as_str()).copied().unwrap_or_default();
+        let listed = open_by_id.get(order.order_id.as_str());
+        if let Some(venue) = listed {
+            let mut terms = Vec::new();
+            if venue.token_id != order.token_id {
+                terms.push(format!("token {} vs {}", venue.token_id, order.token_id));
+            }
+            if venue.side != order.side {
+                terms.push(format!("side {:?} vs {:?}", venue.side, order.side));
+            }
+            if (venue.price - order.price).abs() > config.price_tolerance {
+                terms.push(format!("price {:.4} vs {:.4}", venue.price, order.price));
+            }
+            if (venue.original_size - order.size).abs() > config.size_tolerance {
+                terms.push(format!("size {:.4} vs {:.4}", venue.original_size, order.size));
+            }
+            if !terms.is_empty() {
+                mismatches.push(Mismatch::OrderTerms { order_id: order.order_id.clone(), detail: terms.join(", ") });
+                continue;
+            }
+        }
+        let expired = order.expires_at.is_some_and(|t| now > t);
+        if listed.is_none() && venue_fill.0 == 0.0 && (order.expected_fill > 0.0 || !expired) {
+            mismatches.push(Mismatch::MissingOrder {
+                order_id: order.order_id.clone(),
+                token_id: order.token_id.clone(),
+                expected_fill: order.expected_fill,
+            });
+            continue;
+        }
+        // A resting order may have filled since; only fills beyond the order size disagree
+        let size_off = match order.expected_fill > 0.0 {
+            true => (venue_fill.0 - order.expected_fill).abs() > config.size_tolerance,
+            false => venue_fill.0 > order.size + config.size_tolerance,
+        };
+        if size_off {
+            mismatches.push(Mismatch::FillSize { order_id: order.order_id.clone(), local: order.expected_fill, venue: venue_fill.0 });
+            continue;
+        }
+        if venue_fill.0 > 0.0 && (venue_fill.1 / venue_fill.0 - order.price).abs() > config.price_tolerance {
+            mismatches.push(Mismatch::FillPrice { order_id: order.order_id.clone(), local: order.price, venue: venue_fill.1 / venue_fill.0 });
+        }
+    }
+
+    for venue in open.iter().filter(|o| !ours.contains_key(o.order_id.as_str())) {
+        mismatches.push(Mismatch::UnknownOrder { order_id: venue.order_id.clone(), token_id: venue.token_id.clone(), filled: venue.size_matched });
+    }
+    for (order_id, (size, _)) in filled.iter().filter(|(id, _)| !ours.contains_key(*id) && !open_by_id.contains_key(*id)) {
+        let token_id = fills.iter().find(|f| f.order_id == *order_id).map(|f| f.token_id.clone()).unwrap_or_default();
+        mismatches.push(Mismatch::UnknownOrder { order_id: order_id.to_string(), token_id, filled: *size });
+    }
+    mismatches
+}
+
+/// Fetches the venue's records and reconciles them with the order journal
+#[derive(Clone)]
+pub struct Reconciler {
+    pub config: ReconcileConfig,
+    /// None outside sandbox mode (no authenticated venue client)
+    pub client: Option<Arc<OrderClient>>,
+    pub orders: Arc<Mutex<OrderJournal>>,
+}
+
+impl Reconciler {
+    pub fn new(config: ReconcileConfig, client: Option<Arc<OrderClient>>, orders: Arc<Mutex<OrderJournal>>) -> Self {
+        Self { config, client, orders }
+    }
+
+    /// Reconcile the orders posted in the query window (default: the last `window_secs`)
+    pub async fn run(&self, query: &ReconcileQuery, now: u64) -> Result<ReconcileReport, String> {
+        let client = self.client.as_ref().ok_or("no authenticated venue client (reconciliation needs sandbox mode with API credentials)")?;
+        let to = query.to.unwrap_or(now);
+        let from = query.from.unwrap_or(to.saturating_sub(self.config.window_secs));
+        if from > to {
+            return Err("from is after to".to_string());
+        }
+        let local = self.orders.lock().unwrap().window(from, to);
+        let open = client.open_orders().await.map_err(|e| e.to_string())?;
+        // Fills may land after the order was posted, so look to now
+        let fills = client.fills(from, now.max(to)).await.map_err(|e| e.to_string())?;
+        // Venue records of our orders posted outside the window are not this report's business
+        let (open, fills) = {
+            let journal = self.orders.lock().unwrap();
+            let posted = |order_id: &str| local.iter().any(|o| o.order_id == order_id);
+            let open: Vec<VenueOrder> = open.into_iter()
+                .filter(|o| posted(&o.order_id) || (!journal.contains(&o.order_id)
+                    && (o.created_at == 0 || (o.created_at >= from && o.created_at <= to))))
+                .collect();
+            let fills: Vec<VenueFill> = fills.into_iter().filter(|f| posted(&f.order_id) || !journal.contains(&f.order_id)).collect();
+            (open, fills)
+        };
+        let mismatches = reconcile(&local, &open, &fills, &self.config, now);
+        let unmatched = mismatches.iter().filter(|m| !matches!(m, Mismatch::UnknownOrder { .. })).count();
+        Ok(ReconcileReport {
+            venue: client.profile().name.to_string(),
+            from,
+            to,
+            local_orders: local.len(),
+            venue_open_orders: open.len(),
+            venue_fills: fills.len(),
+            matched: local.len() - unmatched,
+            mismatches,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::venue::{parse_fills, parse_orders};
+
+    fn submitted(order_id: &str, price: f64, size: f64, expected_fill: f64) -> SubmittedOrder {
+        SubmittedOrder {
+            order_id: order_id.to_string(),
+            market_id: "m1".to_string(),
+            token_id: "t1".to_string(),
+            side: Side::Buy,
+            price,
+            size,
+            expected_fill,
+            expires_at: Some(1_000),
+            timestamp: 100,
+        }
+    }
+
+    #[test]
+    fn test_diff_against_venue_records() {
+        let open = parse_orders(&serde_json::json!([
+            {"id": "resting", "asset_id": "t1", "side": "BUY", "price": "0.40", "original_size": "10", "size_matched": "0", "status": "LIVE", "created_at": 100},
+            {"id": "stray", "asset_id": "t9", "side": "SELL", "price": "0.70", "original_size": "5", "size_matched": "1", "status": "LIVE", "created_at": 120},
+        ]));
+        let fills = parse_fills(&serde_json::json!({"data": [
+            {"id": "tr1", "taker_order_id": "filled", "asset_id": "t1", "side": "BUY", "price": "0.45", "size": "10", "match_time": "101", "trader_side": "TAKER"},
+            {"id": "tr2", "taker_order_id": "short", "asset_id": "t1", "side": "BUY", "price": "0.45", "size": "4", "match_time": "101", "trader_side": "TAKER"},
+            {"id": "tr3", "taker_order_id": "theirs", "asset_id": "t1", "side": "SELL", "price": "0.48", "size": "5", "match_time": "102", "trader_side": "MAKER",
+             "maker_orders": [{"order_id": "pricey", "owner": "key", "side": "BUY", "price": "0.48", "matched_amount": "5"},
+                              {"order_id": "other", "owner": "someone", "side": "BUY", "price": "0.48", "matched_amount": "2"}]},
+        ], "next_cursor": "LTE="}), "key");
+        assert_eq!(fills.len(), 3);
+        assert_eq!((fills[2].order_id.as_str(), fills[2].size), ("pricey", 5.0));
+
+        let local = [
+            submitted("resting", 0.40, 10.0, 0.0),
+            submitted("filled", 0.45, 10.0, 10.0),
+            submitted("short", 0.45, 10.0, 10.0),
+            submitted("pricey", 0.45, 5.0, 5.0),
+            submitted("lost", 0.45, 5.0, 5.0),
+        ];
+        let config = ReconcileConfig::default();
+        let mismatches = reconcile(&local, &open, &fills, &config, 500);
+        assert_eq!(mismatches, vec![
+            Mismatch::FillSize { order_id: "short".to_string(), local: 10.0, venue: 4.0 },
+            Mismatch::FillPrice { order_id: "pricey".to_string(), local: 0.45, venue: 0.48 },
+            Mismatch::MissingOrder { order_id: "lost".to_string(), token_id: "t1".to_string(), expected_fill: 5.0 },
+            Mismatch::UnknownOrder { order_id: "stray".to_string(), token_id: "t9".to_string(), filled: 1.0 },
+        ]);
+        // An expired resting order that never filled is simply gone
+        assert!(reconcile(&[submitted("expired", 0.40, 10.0, 0.0)], &[], &[], &config, 2_000).is_empty());
+    }
+}
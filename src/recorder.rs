@@ -0,0 +1,341 @@
+// Order book recorder
+// Snapshots as JSON Lines or a compact columnar binary format, for replay and backtests
+
+use crate::storage::JsonlStore;
+use crate::types::{OrderBook, PriceLevel};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+/// Columnar layout: `ABKC` + version, then frames of `[u32 LE length][zstd block]`, each
+/// block up to `BLOCK_SIZE` snapshots stored column by column; a torn last frame is skipped
+const MAGIC: &[u8; 4] = b"ABKC";
+const VERSION: u8 = 1;
+/// Snapshots per compressed block
+const BLOCK_SIZE: usize = 512;
+const ZSTD_LEVEL: i32 = 9;
+
+/// On-disk recording format
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecordFormat {
+    Jsonl,
+    Columnar,
+}
+
+impl RecordFormat {
+    pub fn parse(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "jsonl" | "json" => Self::Jsonl,
+            _ => Self::Columnar,
+        }
+    }
+
+    /// Format implied by a file extension (`.abk` is columnar)
+    pub fn from_path(path: &str) -> Self {
+        if path.ends_with(".abk") { Self::Columnar } else { Self::Jsonl }
+    }
+
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::Jsonl => "books.jsonl",
+            Self::Columnar => "books.abk",
+        }
+    }
+}
+
+/// Appends snapshots to `<data_dir>/books.{jsonl,abk}`
+pub struct TickRecorder {
+    path: String,
+    /// Set for JSON Lines recordings
+    jsonl: Option<JsonlStore>,
+    buffer: Vec<OrderBook>,
+}
+
+impl TickRecorder {
+    pub fn open(data_dir: &str, format: RecordFormat) -> io::Result<Self> {
+        fs::create_dir_all(data_dir)?;
+        let path = Path::new(data_dir).join(format.file_name()).to_string_lossy().to_string();
+        Self::create(&path, format)
+    }
+
+    fn create(path: &str, format: RecordFormat) -> io::Result<Self> {
+        let jsonl = match format {
+            RecordFormat::Jsonl => Some(jsonl_at(path)?),
+            RecordFormat::Columnar => {
+                write_header_if_new(path)?;
+                None
+            }
+        };
+        Ok(Self { path: path.to_string(), jsonl, buffer: Vec::new() })
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn record(&mut self, book: &OrderBook) -> io::Result<()> {
+        if let Some(store) = &self.jsonl {
+            return store.append(book);
+        }
+        self.buffer.push(book.clone());
+        if self.buffer.len() >= BLOCK_SIZE {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Write buffered snapshots as one compressed block
+    pub fn flush(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let block = zstd::encode_all(&encode_block(&self.buffer)[..], ZSTD_LEVEL)?;
+        // Retention may have rotated the file away since the last block
+        write_header_if_new(&self.path)?;
+        let mut file = OpenOptions::new().append(true).open(&self.path)?;
+        file.write_all(&(block.len() as u32).to_le_bytes())?;
+        file.write_all(&block)?;
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+impl Drop for TickRecorder {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            eprintln!("⚠️ [Recorder] Failed to flush {}: {}", self.path, e);
+        }
+    }
+}
+
+fn write_header_if_new(path: &str) -> io::Result<()> {
+    if fs::metadata(path).map_or(true, |m| m.len() == 0) {
+        let mut file = File::create(path)?;
+        file.write_all(MAGIC)?;
+        file.write_all(&[VERSION])?;
+    }
+    Ok(())
+}
+
+/// Read a recording in either format (detected from the file header)
+pub fn read_recording(path: &str) -> io::Result<Vec<OrderBook>> {
+    let mut bytes = Vec::new();
+    File::open(path)?.read_to_end(&mut bytes)?;
+    if !bytes.starts_with(MAGIC) {
+        return jsonl_at(path)?.load();
+    }
+    if bytes.get(4) != Some(&VERSION) {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "unsupported recording version"));
+    }
+    let mut books = Vec::new();
+    let mut pos = 5;
+    while pos + 4 <= bytes.len() {
+        let len = u32::from_le_bytes(bytes[pos..pos + 4].try_into().unwrap()) as usize;
+        let Some(frame) = bytes.get(pos + 4..pos + 4 + len) else { break };
+        let Some(block) = zstd::decode_all(frame).ok().and_then(|raw| decode_block(&raw)) else { break };
+        books.extend(block);
+        pos += 4 + len;
+    }
+    Ok(books)
+}
+
+/// Convert a recording between formats (target format from `dst` extension)
+pub fn convert(src: &str, dst: &str) -> io::Result<usize> {
+    let books = read_recording(src)?;
+    if Path::new(dst).exists() {
+        fs::remove_file(dst)?;
+    }
+    let mut recorder = TickRecorder::create(dst, RecordFormat::from_path(dst))?;
+    for book in &books {
+        recorder.record(book)?;
+    }
+    recorder.flush()?;
+    Ok(books.len())
+}
+
+/// Recorded books indexed by token, for the replayer and backtester
+#[allow(dead_code)]
+#[derive(Debug, Default)]
+pub struct BookReplayer {
+    books: HashMap<String, Vec<OrderBook>>,
+}
+
+#[allow(dead_code)]
+impl BookReplayer {
+    pub fn from_recording(path: &str) -> io::Result<Self> {
+        let mut books: HashMap<String, Vec<OrderBook>> = HashMap::new();
+        for book in read_recording(path)? {
+            books.entry(book.token_id.clone()).or_default().push(book);
+        }
+        for series in books.values_mut() {
+            series.sort_by_key(|b| b.timestamp);
+        }
+        Ok(Self { books })
+    }
+
+    /// Latest snapshot of `token_id` at or before `timestamp`
+    pub fn book_at(&self, token_id: &str, timestamp: u64) -> Option<&OrderBook> {
+        let series = self.books.get(token_id)?;
+        let idx = series.partition_point(|b| b.timestamp <= timestamp);
+        idx.checked_sub(1).map(|i| &series[i])
+    }
+
+    /// All snapshot timestamps in order (the replay clock)
+    pub fn timeline(&self) -> Vec<u64> {
+        let mut ts: Vec<u64> = self.books.values().flatten().map(|b| b.timestamp).collect();
+        ts.sort_unstable();
+        ts.dedup();
+        ts
+    }
+}
+
+/// JSON Lines store for an arbitrary file path
+fn jsonl_at(path: &str) -> io::Result<JsonlStore> {
+    let path = Path::new(path);
+    let dir = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let name = path.file_name().ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "not a file path"))?;
+    JsonlStore::open(&dir.to_string_lossy(), &name.to_string_lossy())
+}
+
+fn encode_block(books: &[OrderBook]) -> Vec<u8> {
+    let mut tokens: Vec<&str> = Vec::new();
+    let mut token_idx: HashMap<&str, u32> = HashMap::new();
+    let mut out = Vec::new();
+    out.extend((books.len() as u32).to_le_bytes());
+
+    let indices: Vec<u32> = books.iter()
+        .map(|b| *token_idx.entry(&b.token_id).or_insert_with(|| {
+            tokens.push(&b.token_id);
+            tokens.len() as u32 - 1
+        }))
+        .collect();
+    out.extend((tokens.len() as u32).to_le_bytes());
+    for token in &tokens {
+        out.extend((token.len() as u16).to_le_bytes());
+        out.extend(token.as_bytes());
+    }
+    for idx in indices {
+        out.extend(idx.to_le_bytes());
+    }
+    let mut prev = 0i64;
+    for book in books {
+        out.extend((book.timestamp as i64 - prev).to_le_bytes());
+        prev = book.timestamp as i64;
+    }
+    for book in books {
+        out.extend((book.bids.len() as u16).to_le_bytes());
+        out.extend((book.asks.len() as u16).to_le_bytes());
+    }
+    let levels = || books.iter().flat_map(|b| b.bids.iter().chain(&b.asks));
+    for level in levels() {
+        out.extend(level.price.to_le_bytes());
+    }
+    for level in levels() {
+        out.extend(level.size.to_le_bytes());
+    }
+    out
+}
+
+/// Little-endian cursor over a decompressed block
+struct Cursor<'a>(&'a [u8]);
+
+impl Cursor<'_> {
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let (head, rest) = self.0.split_at_checked(N)?;
+        self.0 = rest;
+        head.try_into().ok()
+    }
+
+    fn u16(&mut self) -> Option<u16> { self.take().map(u16::from_le_bytes) }
+    fn u32(&mut self) -> Option<u32> { self.take().map(u32::from_le_bytes) }
+    fn i64(&mut self) -> Option<i64> { self.take().map(i64::from_le_bytes) }
+    fn f64(&mut self) -> Option<f64> { self.take().map(f64::from_le_bytes) }
+}
+
+fn decode_block(raw: &[u8]) -> Option<Vec<OrderBook>> {
+    let mut c = Cursor(raw);
+    let n = c.u32()? as usize;
+    let token_count = c.u32()? as usize;
+    let mut tokens = Vec::with_capacity(token_count);
+    for _ in 0..token_count {
+        let len = c.u16()? as usize;
+        let (bytes, rest) = c.0.split_at_checked(len)?;
+        tokens.push(String::from_utf8(bytes.to_vec()).ok()?);
+        c.0 = rest;
+    }
+    let indices: Vec<usize> = (0..n).map(|_| c.u32().map(|i| i as usize)).collect::<Option<_>>()?;
+    let mut ts = 0i64;
+    let timestamps: Vec<u64> = (0..n).map(|_| c.i64().map(|d| { ts += d; ts as u64 })).collect::<Option<_>>()?;
+    let counts: Vec<(usize, usize)> = (0..n)
+        .map(|_| Some((c.u16()? as usize, c.u16()? as usize)))
+        .collect::<Option<_>>()?;
+    let total: usize = counts.iter().map(|(b, a)| b + a).sum();
+    let prices: Vec<f64> = (0..total).map(|_| c.f64()).collect::<Option<_>>()?;
+    let sizes: Vec<f64> = (0..total).map(|_| c.f64()).collect::<Option<_>>()?;
+
+    let mut levels = prices.into_iter().zip(sizes).map(|(price, size)| PriceLevel { price, size });
+    (0..n).map(|i| {
+        let (bids, asks) = counts[i];
+        Some(OrderBook {
+            token_id: tokens.get(indices[i])?.clone(),
+            bids: levels.by_ref().take(bids).collect(),
+            asks: levels.by_ref().take(asks).collect(),
+            timestamp: timestamps[i],
+        })
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn book(token: &str, ts: u64, bid: f64) -> OrderBook {
+        OrderBook { timestamp: ts, ..testing::book(token, &[(bid, 120.0), (bid - 0.01, 300.0)], &[(bid + 0.02, 80.0)]) }
+    }
+
+    /// A columnar recording of one block and 10 books, alternating YES / NO
+    fn record(test: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("arbishark_rec_{}_{}", test, std::process::id()));
+        let dir = dir.to_str().unwrap().to_string();
+        let mut recorder = TickRecorder::open(&dir, RecordFormat::Columnar).unwrap();
+        for i in 0..(BLOCK_SIZE as u64 + 10) {
+            recorder.record(&book(if i % 2 == 0 { "yes" } else { "no" }, 1000 + i, 0.48)).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_columnar_roundtrip() {
+        let dir = record("roundtrip");
+        let books = read_recording(&format!("{}/books.abk", dir)).unwrap();
+        assert_eq!(books.len(), BLOCK_SIZE + 10);
+        assert_eq!(books[3].token_id, "no");
+        assert_eq!(books[3].timestamp, 1003);
+        assert_eq!(books[3].bids[1].size, 300.0);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_convert_through_json_lines_and_back() {
+        let dir = record("convert");
+        let columnar = format!("{}/books.abk", dir);
+        let jsonl = format!("{}/books.jsonl", dir);
+        assert_eq!(convert(&columnar, &jsonl).unwrap(), BLOCK_SIZE + 10);
+        assert!(fs::metadata(&columnar).unwrap().len() < fs::metadata(&jsonl).unwrap().len() / 4);
+        let again = format!("{}/again.abk", dir);
+        assert_eq!(convert(&jsonl, &again).unwrap(), BLOCK_SIZE + 10);
+        assert_eq!(read_recording(&again).unwrap()[3].bids[1].size, 300.0);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_replayer_serves_the_latest_book_at_a_time() {
+        let dir = record("replay");
+        let replayer = BookReplayer::from_recording(&format!("{}/books.abk", dir)).unwrap();
+        assert_eq!(replayer.book_at("yes", 1003).unwrap().timestamp, 1002);
+        assert!(replayer.book_at("yes", 999).is_none());
+        let _ = fs::remove_dir_all(dir);
+    }
+}
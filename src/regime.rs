@@ -0,0 +1,172 @@
+//! Market regime detection
+//!
+//! Labels each market from its recent mid-price and volume series:
+//! - `EventSpike`: the latest move or volume is an outlier vs the window
+//! - `Trending`: moves mostly point one way (high efficiency ratio)
+//! - `Calm`: everything else
+//!
+//! `StrategyConfig::regimes` maps each regime to its own edge and size
+//! parameters, so the detector is not stuck with one static threshold set.
+
+use crate::config::{RegimeParams, StrategyConfig};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+/// Market regime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Regime {
+    Calm,
+    Trending,
+    EventSpike,
+}
+
+impl fmt::Display for Regime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Calm => write!(f, "calm"),
+            Self::Trending => write!(f, "trending"),
+            Self::EventSpike => write!(f, "event-spike"),
+        }
+    }
+}
+
+impl Regime {
+    /// Edge / size parameters configured for this regime
+    pub fn params(self, strategy: &StrategyConfig) -> &RegimeParams {
+        match self {
+            Self::Calm => &strategy.regimes.calm,
+            Self::Trending => &strategy.regimes.trending,
+            Self::EventSpike => &strategy.regimes.event_spike,
+        }
+    }
+}
+
+/// One sample of a market's series
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    mid: f64,
+    volume: f64,
+}
+
+/// Rolling per-market regime classifier
+#[derive(Debug, Clone)]
+pub struct RegimeClassifier {
+    /// Samples kept per market
+    pub window: usize,
+    /// Fewer samples than this classify as calm
+    pub min_samples: usize,
+    /// |net move| / Σ|moves| above this is a trend
+    pub trend_efficiency: f64,
+    /// Latest move beyond this many std devs of the window is a spike
+    pub spike_z: f64,
+    /// Latest volume above this multiple of the window mean is a spike
+    pub spike_volume_mult: f64,
+    series: HashMap<String, VecDeque<Sample>>,
+}
+
+impl Default for RegimeClassifier {
+    fn default() -> Self {
+        Self {
+            window: 30,
+            min_samples: 8,
+            trend_efficiency: 0.6,
+            spike_z: 4.0,
+            spike_volume_mult: 3.0,
+            series: HashMap::new(),
+        }
+    }
+}
+
+impl RegimeClassifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a (mid, volume) sample for a market
+    pub fn observe(&mut self, market_id: &str, mid: f64, volume: f64) {
+        let series = self.series.entry(market_id.to_string()).or_default();
+        series.push_back(Sample { mid, volume });
+        while series.len() > self.window {
+            series.pop_front();
+        }
+    }
+
+    /// Current regime of a market
+    pub fn classify(&self, market_id: &str) -> Regime {
+        let Some(series) = self.series.get(market_id) else { return Regime::Calm };
+        if series.len() < self.min_samples {
+            return Regime::Calm;
+        }
+        let moves: Vec<f64> = series.iter().zip(series.iter().skip(1)).map(|(a, b)| b.mid - a.mid).collect();
+        let (last_move, history) = moves.split_last().expect("min_samples >= 2");
+
+        // Spike: latest move vs the dispersion of the earlier moves
+        let mean = history.iter().sum::<f64>() / history.len() as f64;
+        let std = (history.iter().map(|m| (m - mean).powi(2)).sum::<f64>() / history.len() as f64).sqrt();
+        let move_spike = if std > 0.0 {
+            (last_move - mean).abs() > self.spike_z * std
+        } else {
+            last_move.abs() > 0.0
+        };
+        let samples: Vec<Sample> = series.iter().copied().collect();
+        let (last, earlier) = samples.split_last().expect("min_samples >= 2");
+        let avg_volume = earlier.iter().map(|s| s.volume).sum::<f64>() / earlier.len() as f64;
+        let volume_spike = avg_volume > 0.0 && last.volume > self.spike_volume_mult * avg_volume;
+        if move_spike || volume_spike {
+            return Regime::EventSpike;
+        }
+
+        let path: f64 = moves.iter().map(|m| m.abs()).sum();
+        let net: f64 = moves.iter().sum();
+        if path > 0.0 && net.abs() / path >= self.trend_efficiency {
+            return Regime::Trending;
+        }
+        Regime::Calm
+    }
+
+    /// Number of markets per regime (for status output)
+    pub fn counts(&self) -> HashMap<Regime, usize> {
+        let mut counts = HashMap::new();
+        for market_id in self.series.keys() {
+            *counts.entry(self.classify(market_id)).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Forget markets no longer in the scan set
+    pub fn retain(&mut self, active: &[String]) {
+        self.series.retain(|id, _| active.contains(id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(c: &mut RegimeClassifier, id: &str, mids: &[f64], volumes: &[f64]) {
+        for (mid, volume) in mids.iter().zip(volumes) {
+            c.observe(id, *mid, *volume);
+        }
+    }
+
+    #[test]
+    fn test_classify_regimes() {
+        let mut c = RegimeClassifier::new();
+        let flat_volume = [100.0; 10];
+        feed(&mut c, "calm", &[0.50, 0.51, 0.50, 0.51, 0.50, 0.51, 0.50, 0.51, 0.50, 0.51], &flat_volume);
+        feed(&mut c, "trend", &[0.40, 0.41, 0.42, 0.42, 0.43, 0.44, 0.45, 0.45, 0.46, 0.47], &flat_volume);
+        feed(&mut c, "spike", &[0.50, 0.51, 0.50, 0.51, 0.50, 0.51, 0.50, 0.51, 0.50, 0.80], &flat_volume);
+        let mut volumes = flat_volume;
+        volumes[9] = 1000.0;
+        feed(&mut c, "volume", &[0.50, 0.51, 0.50, 0.51, 0.50, 0.51, 0.50, 0.51, 0.50, 0.51], &volumes);
+
+        assert_eq!(c.classify("calm"), Regime::Calm);
+        assert_eq!(c.classify("trend"), Regime::Trending);
+        assert_eq!(c.classify("spike"), Regime::EventSpike);
+        assert_eq!(c.classify("volume"), Regime::EventSpike);
+        assert_eq!(c.classify("unknown"), Regime::Calm);
+
+        let strategy = StrategyConfig::default();
+        assert!(Regime::EventSpike.params(&strategy).min_edge > Regime::Calm.params(&strategy).min_edge);
+    }
+}
@@ -0,0 +1,236 @@
+// Address book of known contracts
+// Every contract address resolved by chain id, validated, and checked against the RPCs' chain ids
+
+use crate::config::RegistryConfig;
+use serde::Serialize;
+use serde_json::{json, Value};
+use sha3::{Digest, Keccak256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::time::Duration;
+
+pub const POLYGON: u64 = 137;
+pub const POLYGON_AMOY: u64 = 80002;
+pub const ARBITRUM_ONE: u64 = 42161;
+pub const ARBITRUM_SEPOLIA: u64 = 421614;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Contract {
+    /// USDC collateral
+    Usdc,
+    /// Conditional Tokens (ERC-1155 outcome tokens)
+    Ctf,
+    /// CTF Exchange (EIP-712 verifying contract of orders)
+    Exchange,
+    /// Stylus venue contract trades settle through
+    Stylus,
+}
+
+impl Contract {
+    pub const ALL: [Contract; 4] = [Self::Usdc, Self::Ctf, Self::Exchange, Self::Stylus];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Usdc => "usdc",
+            Self::Ctf => "ctf",
+            Self::Exchange => "exchange",
+            Self::Stylus => "stylus",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Built-in addresses: (chain id, contract, address)
+const KNOWN: &[(u64, Contract, &str)] = &[
+    (POLYGON, Contract::Usdc, "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174"),
+    (POLYGON, Contract::Ctf, "0x4D97DCd97eC945f40cF65F87097ACe5EA0476045"),
+    (POLYGON, Contract::Exchange, "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E"),
+    (POLYGON_AMOY, Contract::Exchange, "0xdFE02Eb6733538f8Ea35D585af8DE5958AD99E40"),
+    (ARBITRUM_ONE, Contract::Usdc, "0xFF970A61A04b1cA14834A43f5dE4533eBDDB5CC8"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegistryError {
+    InvalidAddress(String),
+    BadChecksum { address: String, expected: String },
+    UnknownContract(String),
+    BadChainId(String),
+    NotRegistered { chain_id: u64, contract: Contract },
+    ChainMismatch { rpc_url: String, expected: u64, actual: u64 },
+    Rpc(String),
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidAddress(address) => write!(f, "invalid address {}", address),
+            Self::BadChecksum { address, expected } => write!(f, "bad checksum on {} (expected {})", address, expected),
+            Self::UnknownContract(name) => write!(f, "unknown contract '{}' (known: usdc, ctf, exchange, stylus)", name),
+            Self::BadChainId(chain) => write!(f, "invalid chain id '{}'", chain),
+            Self::NotRegistered { chain_id, contract } => write!(f, "no {} address registered for chain {}", contract.name(), chain_id),
+            Self::ChainMismatch { rpc_url, expected, actual } => write!(f, "{} is chain {}, expected {}", rpc_url, actual, expected),
+            Self::Rpc(e) => write!(f, "RPC error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+/// EIP-55 checksummed form of `address`
+pub fn checksum(address: &str) -> Result<String, RegistryError> {
+    let hex = address.strip_prefix("0x").ok_or_else(|| RegistryError::InvalidAddress(address.to_string()))?;
+    if hex.len() != 40 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(RegistryError::InvalidAddress(address.to_string()));
+    }
+    let lower = hex.to_ascii_lowercase();
+    let hash = Keccak256::digest(lower.as_bytes());
+    let checksummed: String = lower.chars().enumerate().map(|(i, c)| {
+        let nibble = (hash[i / 2] >> if i % 2 == 0 { 4 } else { 0 }) & 0x0f;
+        if c.is_ascii_alphabetic() && nibble >= 8 { c.to_ascii_uppercase() } else { c }
+    }).collect();
+    Ok(format!("0x{}", checksummed))
+}
+
+/// Validate `address`; all-lowercase and all-uppercase addresses carry no checksum
+pub fn validate(address: &str) -> Result<String, RegistryError> {
+    let expected = checksum(address)?;
+    let hex = &address[2..];
+    let mixed = hex.chars().any(|c| c.is_ascii_lowercase()) && hex.chars().any(|c| c.is_ascii_uppercase());
+    if mixed && address != expected {
+        return Err(RegistryError::BadChecksum { address: address.to_string(), expected });
+    }
+    Ok(expected)
+}
+
+/// A registered address
+#[derive(Debug, Clone, Serialize)]
+pub struct RegistryEntry {
+    pub chain_id: u64,
+    pub contract: Contract,
+    pub address: String,
+    /// "builtin", "config" or the env var it came from
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Registry {
+    entries: BTreeMap<(u64, Contract), RegistryEntry>,
+}
+
+impl Registry {
+    /// Built-in addresses only
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+        for (chain_id, contract, address) in KNOWN {
+            // Built-ins are checksummed by construction (see tests)
+            registry.entries.insert((*chain_id, *contract), RegistryEntry {
+                chain_id: *chain_id, contract: *contract, address: address.to_string(), source: "builtin".to_string(),
+            });
+        }
+        registry
+    }
+
+    /// Built-ins, then `[registry.overrides]`, then `ARBISHARK_REGISTRY_<CHAIN>_<CONTRACT>` env vars
+    pub fn from_config(config: &RegistryConfig) -> Result<Self, RegistryError> {
+        let mut registry = Self::builtin();
+        for (chain, contracts) in &config.overrides {
+            let chain_id = chain.parse().map_err(|_| RegistryError::BadChainId(chain.clone()))?;
+            for (name, address) in contracts {
+                let contract = Contract::parse(name).ok_or_else(|| RegistryError::UnknownContract(name.clone()))?;
+                registry.set(chain_id, contract, address, "config")?;
+            }
+        }
+        let chains: BTreeSet<u64> = registry.entries.keys().map(|(chain_id, _)| *chain_id).collect();
+        for chain_id in chains {
+            for contract in Contract::ALL {
+                let var = format!("ARBISHARK_REGISTRY_{}_{}", chain_id, contract.name().to_ascii_uppercase());
+                if let Ok(address) = std::env::var(&var) {
+                    registry.set(chain_id, contract, &address, &var)?;
+                }
+            }
+        }
+        Ok(registry)
+    }
+
+    /// Register `address` for `contract` on `chain_id`, validating it
+    pub fn set(&mut self, chain_id: u64, contract: Contract, address: &str, source: &str) -> Result<(), RegistryError> {
+        let address = validate(address.trim())?;
+        self.entries.insert((chain_id, contract), RegistryEntry { chain_id, contract, address, source: source.to_string() });
+        Ok(())
+    }
+
+    /// Checksummed address of `contract` on `chain_id`
+    pub fn resolve(&self, chain_id: u64, contract: Contract) -> Result<&str, RegistryError> {
+        self.entries.get(&(chain_id, contract))
+            .map(|e| e.address.as_str())
+            .ok_or(RegistryError::NotRegistered { chain_id, contract })
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &RegistryEntry> {
+        self.entries.values()
+    }
+}
+
+/// Chain id reported by `rpc_url` (`eth_chainId`)
+pub async fn rpc_chain_id(client: &reqwest::Client, rpc_url: &str, timeout: Duration) -> Result<u64, RegistryError> {
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": "eth_chainId", "params": [] });
+    let response: Value = client.post(rpc_url)
+        .json(&request)
+        .timeout(timeout)
+        .send()
+        .await
+        .map_err(|e| RegistryError::Rpc(e.to_string()))?
+        .json()
+        .await
+        .map_err(|e| RegistryError::Rpc(e.to_string()))?;
+    let hex = response["result"].as_str()
+        .ok_or_else(|| RegistryError::Rpc(format!("{}: {}", rpc_url, response["error"])))?;
+    u64::from_str_radix(hex.trim_start_matches("0x"), 16).map_err(|e| RegistryError::Rpc(e.to_string()))
+}
+
+/// Whether `rpc_url` serves `expected`
+pub async fn check_chain_id(client: &reqwest::Client, rpc_url: &str, expected: u64, timeout: Duration) -> Result<(), RegistryError> {
+    let actual = rpc_chain_id(client, rpc_url, timeout).await?;
+    if actual != expected {
+        return Err(RegistryError::ChainMismatch { rpc_url: rpc_url.to_string(), expected, actual });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_resolves_validated_addresses() {
+        // Built-ins carry valid checksums
+        for (_, _, address) in KNOWN {
+            assert_eq!(validate(address).unwrap(), *address);
+        }
+        let lower = "0x4bfb41d5b3570defd03c39a9a4d8de6bd8b8982e";
+        assert_eq!(validate(lower).unwrap(), "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E");
+        assert!(matches!(validate("0x4BFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E"), Err(RegistryError::BadChecksum { .. })));
+        assert!(matches!(validate("0x1234"), Err(RegistryError::InvalidAddress(_))));
+
+        let config = RegistryConfig {
+            overrides: HashMap::from([("421614".to_string(), HashMap::from([("stylus".to_string(), lower.to_string())]))]),
+            ..RegistryConfig::default()
+        };
+        let registry = Registry::from_config(&config).unwrap();
+        assert_eq!(registry.resolve(ARBITRUM_SEPOLIA, Contract::Stylus).unwrap(), "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E");
+        assert_eq!(registry.resolve(POLYGON, Contract::Ctf).unwrap(), "0x4D97DCd97eC945f40cF65F87097ACe5EA0476045");
+        assert_eq!(registry.resolve(ARBITRUM_SEPOLIA, Contract::Ctf),
+            Err(RegistryError::NotRegistered { chain_id: ARBITRUM_SEPOLIA, contract: Contract::Ctf }));
+
+        let bad = RegistryConfig {
+            overrides: HashMap::from([("137".to_string(), HashMap::from([("vault".to_string(), lower.to_string())]))]),
+            ..RegistryConfig::default()
+        };
+        assert_eq!(Registry::from_config(&bad).unwrap_err(), RegistryError::UnknownContract("vault".to_string()));
+    }
+}
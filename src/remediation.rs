@@ -0,0 +1,335 @@
+//! Execution failure taxonomy and remediation
+//!
+//! Every way a leg can fail to execute - in the engine, at the permission
+//! grant, in settlement simulation or at the venue - is classified into one
+//! `FailureClass`, and each class maps to a remediation from the
+//! `[remediation.policies]` table: retry the leg as it was, reprice it
+//! against a fresh book, unwind the legs of the bundle already filled, or
+//! halt trading (safe mode). Retries back off and, like reprices, are bounded
+//! per leg; past the bound the leg is skipped. With remediation disabled every
+//! failure is skipped, which is how failures were handled before.
+
+use crate::config::RemediationConfig;
+use crate::metamask::MetaMaskError;
+use crate::preflight::SimulationError;
+use crate::venue::VenueError;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Kind of execution failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureClass {
+    /// Not enough balance or allowance left to pay for the leg
+    InsufficientBalance,
+    /// The book no longer offers the size at an acceptable price
+    PriceMoved,
+    /// The venue refused the order
+    OrderRejected,
+    /// The venue or node throttled us
+    RateLimited,
+    /// Nonce too low / already used
+    Nonce,
+    /// The call is outside the permission grant, or would revert on-chain
+    PermissionRevert,
+}
+
+impl FailureClass {
+    pub const ALL: [FailureClass; 6] = [
+        Self::InsufficientBalance,
+        Self::PriceMoved,
+        Self::OrderRejected,
+        Self::RateLimited,
+        Self::Nonce,
+        Self::PermissionRevert,
+    ];
+
+    /// Key in `[remediation.policies]`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::InsufficientBalance => "insufficient_balance",
+            Self::PriceMoved => "price_moved",
+            Self::OrderRejected => "order_rejected",
+            Self::RateLimited => "rate_limited",
+            Self::Nonce => "nonce",
+            Self::PermissionRevert => "permission_revert",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.name() == name)
+    }
+
+    /// Remediation used when the table does not name the class
+    pub fn default_remediation(&self) -> Remediation {
+        match self {
+            Self::InsufficientBalance | Self::OrderRejected => Remediation::Unwind,
+            Self::PriceMoved => Remediation::Reprice,
+            Self::RateLimited | Self::Nonce => Remediation::Retry,
+            Self::PermissionRevert => Remediation::Halt,
+        }
+    }
+
+    /// Classify opaque text from a venue body or a revert reason
+    ///
+    /// Only used where the counterparty gives us nothing but text; errors of
+    /// our own are classified by variant.
+    fn from_message(message: &str, otherwise: Self) -> Self {
+        const BALANCE: [&str; 4] = ["insufficient balance", "insufficient funds", "exceeds balance", "allowance"];
+        const PRICE: [&str; 3] = ["price", "slippage", "liquidity"];
+        let message = message.to_lowercase();
+        if message.contains("nonce") {
+            Self::Nonce
+        } else if message.contains("rate limit") || message.contains("too many requests") {
+            Self::RateLimited
+        } else if BALANCE.iter().any(|p| message.contains(p)) {
+            Self::InsufficientBalance
+        } else if PRICE.iter().any(|p| message.contains(p)) {
+            Self::PriceMoved
+        } else {
+            otherwise
+        }
+    }
+}
+
+/// What to do about a failed leg
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Remediation {
+    /// Send the same leg again
+    Retry,
+    /// Re-fetch the book and send the leg against it
+    Reprice,
+    /// Give up on the bundle and sell back the legs already filled
+    Unwind,
+    /// Stop trading (safe mode)
+    Halt,
+    /// Drop the leg and carry on
+    Skip,
+}
+
+impl Remediation {
+    pub fn parse(policy: &str) -> Option<Self> {
+        match policy.to_lowercase().as_str() {
+            "retry" => Some(Self::Retry),
+            "reprice" => Some(Self::Reprice),
+            "unwind" => Some(Self::Unwind),
+            "halt" => Some(Self::Halt),
+            "skip" => Some(Self::Skip),
+            _ => None,
+        }
+    }
+}
+
+/// A classified execution failure
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutionFailure {
+    pub class: FailureClass,
+    pub detail: String,
+}
+
+impl ExecutionFailure {
+    pub fn new(class: FailureClass, detail: impl Into<String>) -> Self {
+        Self { class, detail: detail.into() }
+    }
+}
+
+impl std::fmt::Display for ExecutionFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}", self.class.name(), self.detail)
+    }
+}
+
+impl std::error::Error for ExecutionFailure {}
+
+impl From<MetaMaskError> for ExecutionFailure {
+    fn from(e: MetaMaskError) -> Self {
+        let class = match &e {
+            MetaMaskError::InsufficientAllowance => FailureClass::InsufficientBalance,
+            MetaMaskError::TransactionFailed(_) | MetaMaskError::ConnectionFailed(_) => FailureClass::OrderRejected,
+            MetaMaskError::NotConnected
+            | MetaMaskError::NoPermission
+            | MetaMaskError::PermissionRevoked
+            | MetaMaskError::PermissionExpired
+            | MetaMaskError::PermissionDenied
+            | MetaMaskError::OutOfScope(_) => FailureClass::PermissionRevert,
+        };
+        Self::new(class, e.to_string())
+    }
+}
+
+impl From<SimulationError> for ExecutionFailure {
+    fn from(e: SimulationError) -> Self {
+        let class = match &e {
+            SimulationError::Reverted(reason) => FailureClass::from_message(reason, FailureClass::PermissionRevert),
+            SimulationError::Rpc(_) => FailureClass::OrderRejected,
+        };
+        Self::new(class, e.to_string())
+    }
+}
+
+impl From<VenueError> for ExecutionFailure {
+    fn from(e: VenueError) -> Self {
+        let class = match &e {
+            VenueError::Rejected { status: 429, .. } => FailureClass::RateLimited,
+            VenueError::Rejected { body, .. } => FailureClass::from_message(body, FailureClass::OrderRejected),
+            VenueError::Http(msg) => FailureClass::from_message(msg, FailureClass::OrderRejected),
+            VenueError::Signer(_) | VenueError::Credentials(_) | VenueError::Encoding(_) => FailureClass::OrderRejected,
+        };
+        Self::new(class, e.to_string())
+    }
+}
+
+/// Remediation table plus failure counts per class
+#[derive(Debug)]
+pub struct Remediator {
+    enabled: bool,
+    max_retries: u32,
+    retry_backoff_ms: u64,
+    max_backoff_ms: u64,
+    policies: HashMap<FailureClass, Remediation>,
+    counts: BTreeMap<FailureClass, u64>,
+}
+
+impl Remediator {
+    /// Table from config over the defaults; unknown classes or policies are an error
+    pub fn from_config(config: &RemediationConfig) -> Result<Self, String> {
+        let mut policies: HashMap<FailureClass, Remediation> = FailureClass::ALL.iter().map(|c| (*c, c.default_remediation())).collect();
+        for (class, policy) in &config.policies {
+            let class = FailureClass::parse(class).ok_or_else(|| format!("remediation: unknown failure class '{}'", class))?;
+            let policy = Remediation::parse(policy).ok_or_else(|| format!("remediation: unknown policy '{}' for {}", policy, class.name()))?;
+            policies.insert(class, policy);
+        }
+        Ok(Self {
+            enabled: config.enabled,
+            max_retries: config.max_retries,
+            retry_backoff_ms: config.retry_backoff_ms,
+            max_backoff_ms: config.max_backoff_ms,
+            policies,
+            counts: BTreeMap::new(),
+        })
+    }
+
+    /// Configured remediation for `class` (Skip while disabled)
+    pub fn policy(&self, class: FailureClass) -> Remediation {
+        if !self.enabled {
+            return Remediation::Skip;
+        }
+        self.policies.get(&class).copied().unwrap_or(Remediation::Skip)
+    }
+
+    /// Remediation for the `attempt`-th failure of a leg (0 = first); retries past the bound skip
+    pub fn decide(&mut self, failure: &ExecutionFailure, attempt: u32) -> Remediation {
+        *self.counts.entry(failure.class).or_default() += 1;
+        match self.policy(failure.class) {
+            Remediation::Retry | Remediation::Reprice if attempt >= self.max_retries => Remediation::Skip,
+            remediation => remediation,
+        }
+    }
+
+    /// Wait before retrying a leg after its `attempt`-th failure (0 = first)
+    pub fn backoff(&self, attempt: u32) -> std::time::Duration {
+        let ms = self.retry_backoff_ms.saturating_mul(1u64 << attempt.min(16)).min(self.max_backoff_ms);
+        std::time::Duration::from_millis(ms)
+    }
+
+    /// Failures seen per class
+    pub fn counts(&self) -> BTreeMap<FailureClass, u64> {
+        self.counts.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remediator(policies: &[(&str, &str)]) -> Remediator {
+        let config = RemediationConfig {
+            enabled: true,
+            max_retries: 1,
+            policies: policies.iter().map(|(c, p)| (c.to_string(), p.to_string())).collect(),
+            ..RemediationConfig::default()
+        };
+        Remediator::from_config(&config).unwrap()
+    }
+
+    fn rejected(status: u16, body: &str) -> ExecutionFailure {
+        ExecutionFailure::from(VenueError::Rejected { status, body: body.to_string() })
+    }
+
+    #[test]
+    fn test_classifies_rate_limited() {
+        assert_eq!(rejected(429, "slow down").class, FailureClass::RateLimited);
+        assert_eq!(rejected(400, "Too Many Requests").class, FailureClass::RateLimited);
+    }
+
+    #[test]
+    fn test_classifies_nonce() {
+        assert_eq!(rejected(400, "invalid nonce").class, FailureClass::Nonce);
+    }
+
+    #[test]
+    fn test_classifies_insufficient_balance() {
+        assert_eq!(ExecutionFailure::from(MetaMaskError::InsufficientAllowance).class, FailureClass::InsufficientBalance);
+        assert_eq!(ExecutionFailure::from(SimulationError::Reverted("transfer amount exceeds balance".to_string())).class,
+            FailureClass::InsufficientBalance);
+    }
+
+    #[test]
+    fn test_classifies_price_moved() {
+        assert_eq!(rejected(400, "insufficient liquidity").class, FailureClass::PriceMoved);
+        assert_eq!(rejected(400, "price out of range").class, FailureClass::PriceMoved);
+    }
+
+    #[test]
+    fn test_classifies_order_rejected() {
+        assert_eq!(rejected(400, "market closed").class, FailureClass::OrderRejected);
+        let own = ExecutionFailure::from(MetaMaskError::TransactionFailed("insufficient balance for gas".to_string()));
+        assert_eq!(own.class, FailureClass::OrderRejected, "our own errors are classified by variant, not text");
+    }
+
+    #[test]
+    fn test_classifies_permission_revert() {
+        assert_eq!(ExecutionFailure::from(MetaMaskError::PermissionRevoked).class, FailureClass::PermissionRevert);
+        assert_eq!(ExecutionFailure::from(SimulationError::Reverted("panic 0x11".to_string())).class, FailureClass::PermissionRevert);
+    }
+
+    #[test]
+    fn test_retry_is_bounded() {
+        let mut remediator = remediator(&[]);
+        let failure = rejected(429, "slow down");
+        assert_eq!(remediator.decide(&failure, 0), Remediation::Retry);
+        assert_eq!(remediator.decide(&failure, 1), Remediation::Skip);
+        assert_eq!(remediator.counts()[&FailureClass::RateLimited], 2);
+    }
+
+    #[test]
+    fn test_reprice_is_default_for_price_moved() {
+        assert_eq!(remediator(&[]).policy(FailureClass::PriceMoved), Remediation::Reprice);
+    }
+
+    #[test]
+    fn test_table_overrides_default() {
+        let mut remediator = remediator(&[("nonce", "halt")]);
+        assert_eq!(remediator.decide(&rejected(400, "invalid nonce"), 0), Remediation::Halt);
+    }
+
+    #[test]
+    fn test_unknown_policy_is_an_error() {
+        let config = RemediationConfig { policies: HashMap::from([("nonce".to_string(), "pray".to_string())]), ..RemediationConfig::default() };
+        assert!(Remediator::from_config(&config).unwrap_err().contains("pray"));
+    }
+
+    #[test]
+    fn test_disabled_skips_everything() {
+        assert_eq!(Remediator::from_config(&RemediationConfig::default()).unwrap().policy(FailureClass::Nonce), Remediation::Skip);
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_the_cap() {
+        let remediator = remediator(&[]);
+        assert_eq!(remediator.backoff(0).as_millis(), 250);
+        assert_eq!(remediator.backoff(2).as_millis(), 1_000);
+        assert_eq!(remediator.backoff(10).as_millis(), 4_000);
+    }
+}
@@ -0,0 +1,255 @@
+// Post-mortem replay of one historical signal (`arbishark replay-signal`)
+// Walks the engine's pipeline again on the recorded books, events and audit trail
+
+use crate::audit::{AuditLog, AuditQuery, SpendRecord};
+use crate::arb::ArbitrageDetector;
+use crate::config::{Config, RegimeParams};
+use crate::decisions::{self, Decision, DecisionRecord};
+use crate::events::LogEvent;
+use crate::fees::FeeModel;
+use crate::freshness::FreshnessModel;
+use crate::inspect::Inspection;
+use crate::recorder::{BookReplayer, RecordFormat};
+use crate::sizing::PositionSizer;
+use crate::storage::JsonlStore;
+use crate::types::{Market, OrderBook};
+use crate::universe::UniverseSnapshot;
+use std::path::Path;
+
+/// Book timestamps above this are milliseconds
+const MS_CLOCK: u64 = 10_000_000_000;
+
+/// Split `<market_id>:<unix secs>` (market ids may themselves contain ':')
+pub fn parse_signal_id(id: &str) -> Option<(String, u64)> {
+    let (market_id, ts) = id.rsplit_once(':')?;
+    if market_id.is_empty() {
+        return None;
+    }
+    Some((market_id.to_string(), ts.parse().ok()?))
+}
+
+/// Everything recorded around one signal
+#[derive(Debug, Clone)]
+pub struct ReplayContext {
+    pub signal_id: String,
+    pub market_id: String,
+    pub timestamp: u64,
+    pub record: Option<DecisionRecord>,
+    pub market: Option<Market>,
+    /// Recorded book per outcome token at the signal (None if never recorded)
+    pub books: Vec<Option<OrderBook>>,
+    /// Events for the market within the window
+    pub events: Vec<LogEvent>,
+    pub spends: Vec<SpendRecord>,
+}
+
+impl ReplayContext {
+    /// Load the journals under `config.storage.data_dir`, `window_secs` either side of the signal
+    pub fn load(config: &Config, signal_id: &str, window_secs: u64) -> Result<Self, String> {
+        let (market_id, timestamp) = parse_signal_id(signal_id)
+            .ok_or_else(|| format!("bad signal id {} (expected <market_id>:<unix secs>)", signal_id))?;
+        let data_dir = &config.storage.data_dir;
+        let journal = JsonlStore::open(data_dir, "decisions.jsonl").map_err(|e| e.to_string())?;
+        let record = decisions::load(&journal).map_err(|e| e.to_string())?
+            .into_iter()
+            .find(|r| r.record_id == signal_id);
+        let market = std::fs::read_to_string(Path::new(data_dir).join("universe.json")).ok()
+            .and_then(|json| serde_json::from_str::<UniverseSnapshot>(&json).ok())
+            .and_then(|snapshot| snapshot.markets.into_iter().find(|m| m.id == market_id));
+        let (from, to) = (timestamp.saturating_sub(window_secs), timestamp + window_secs);
+        let events = JsonlStore::open(data_dir, "events.jsonl").and_then(|s| s.load::<LogEvent>())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|e| e.market_id.as_deref() == Some(&market_id) && (from..=to).contains(&e.timestamp))
+            .collect();
+        let spends = JsonlStore::open(data_dir, "spend_audit.jsonl").and_then(AuditLog::with_store)
+            .map(|log| log.query(&AuditQuery { market: Some(market_id.clone()), from: Some(from), to: Some(to), ..Default::default() }))
+            .unwrap_or_default();
+        let mut ctx = Self { signal_id: signal_id.to_string(), market_id, timestamp, record, market: None, books: Vec::new(), events, spends };
+        if let Some(market) = market {
+            ctx.attach_market(config, market);
+        }
+        Ok(ctx)
+    }
+
+    /// Set the market and pick its recorded books at the signal
+    pub fn attach_market(&mut self, config: &Config, market: Market) {
+        let path = Path::new(&config.storage.data_dir).join(RecordFormat::parse(&config.storage.record_format).file_name());
+        let replayer = BookReplayer::from_recording(&path.to_string_lossy()).unwrap_or_default();
+        let at = match replayer.timeline().last() {
+            Some(&last) if last > MS_CLOCK => self.timestamp * 1000,
+            _ => self.timestamp,
+        };
+        self.books = market.clob_token_ids.iter().map(|t| replayer.book_at(t, at).cloned()).collect();
+        self.market = Some(market);
+    }
+}
+
+/// Step-by-step trace of the engine's handling of the signal
+pub fn trace(ctx: &ReplayContext, config: &Config) -> Vec<String> {
+    let mut lines = vec![format!("🔁 Replaying signal {} (market {} at {})", ctx.signal_id, ctx.market_id, ctx.timestamp)];
+
+    lines.push("1. Recorded decision".to_string());
+    let Some(record) = &ctx.record else {
+        lines.push("   ❌ not in decisions.jsonl - was [decisions] enabled?".to_string());
+        return lines;
+    };
+    let f = &record.features;
+    lines.push(format!("   decision {} | edge {} | staleness {}ms | taker fee {}bps",
+        record.decision.as_str(), record.edge.map_or("-".to_string(), |e| format!("${:.4}", e)), f.staleness_ms, f.taker_fee_bps));
+    if let Some(conflict) = &record.conflict {
+        lines.push(format!("   conflict resolution dropped it: {}", conflict));
+    }
+    if let Some(pnl) = record.realized_pnl {
+        lines.push(format!("   realized PnL ${:.4}", pnl));
+    }
+
+    lines.push("2. Detection".to_string());
+    let threshold = config.trading.min_spread_threshold;
+    let detected = f.deviation > threshold;
+    lines.push(format!("   {} prices sum to {:.4}: spread {:.4} {} {:.4}",
+        if detected { "✅" } else { "❌" }, f.price_sum, f.deviation, if detected { ">" } else { "<=" }, threshold));
+
+    lines.push("3. Regime threshold".to_string());
+    let haircut = FreshnessModel::new(config.freshness.clone()).haircut_at(f.staleness_ms);
+    let edge = record.edge.unwrap_or(f.deviation);
+    let regimes = &config.strategy.regimes;
+    let candidates: [(&str, &RegimeParams); 3] = [("calm", &regimes.calm), ("trending", &regimes.trending), ("event_spike", &regimes.event_spike)];
+    for (name, params) in candidates {
+        let required = params.min_edge + haircut;
+        lines.push(format!("   {} {:<11} edge ${:.4} vs ${:.4} min + ${:.4} haircut",
+            if edge >= required { "✅" } else { "❌" }, name, edge, params.min_edge, haircut));
+    }
+    lines.push("   (the regime in force and its volatility add-on are not journaled)".to_string());
+
+    lines.push("4. Revalidation on recorded books".to_string());
+    let books = ctx.books.iter().cloned().collect::<Option<Vec<OrderBook>>>().filter(|b| !b.is_empty());
+    let inspection = match (&ctx.market, books) {
+        (None, _) => {
+            lines.push("   ❌ market not found in universe.json or Gamma".to_string());
+            None
+        }
+        (Some(_), None) => {
+            lines.push("   ❌ no recorded book for every outcome at the signal - was storage.record_books on?".to_string());
+            None
+        }
+        (Some(market), Some(books)) => {
+            let at = if books.iter().any(|b| b.timestamp > MS_CLOCK) { ctx.timestamp * 1000 } else { ctx.timestamp };
+            for book in &books {
+                lines.push(format!("   book {} recorded {} before the signal", book.token_id, at.saturating_sub(book.timestamp)));
+            }
+            match Inspection::new(market.clone(), books.clone(), config, ctx.timestamp) {
+                Ok(inspection) => {
+                    for check in &inspection.trade.checks {
+                        lines.push(format!("   {} {}: {}", if check.passed { "✅" } else { "❌" }, check.check, check.detail));
+                    }
+                    Some((market, books, inspection))
+                }
+                Err(e) => {
+                    lines.push(format!("   ❌ {}", e));
+                    None
+                }
+            }
+        }
+    };
+
+    lines.push("5. Sizing".to_string());
+    let sizer = PositionSizer::new(config.trading.trade_size, config.trading.max_position_value);
+    for (name, params) in candidates {
+        lines.push(format!("   {:<11} {:.2} per leg before risk scaling", name, sizer.unscaled(params.size_multiplier)));
+    }
+    if let Some((market, books, _)) = &inspection {
+        let detector = ArbitrageDetector::new(threshold, config.trading.min_profit_threshold);
+        match detector.scan(std::slice::from_ref(*market)).pop() {
+            Some(signal) => {
+                let cap = sizer.unscaled(regimes.calm.size_multiplier);
+                let curved = detector.with_edge_curve(&signal, books, FeeModel::from_market(market).taker_rate(), cap, config.edge_curve.steps);
+                match sizer.best_on_curve(&curved.edge_curve, cap) {
+                    Some(size) => lines.push(format!("   edge curve: most profitable at {:.2} per leg (calm cap {:.2})", size, cap)),
+                    None => lines.push(format!("   edge curve: no profitable size up to {:.2}", cap)),
+                }
+            }
+            None => lines.push("   edge curve: detector finds no signal on the recorded books".to_string()),
+        }
+    }
+
+    lines.push("6. Execution".to_string());
+    if let Some((_, _, inspection)) = &inspection {
+        for leg in &inspection.trade.legs {
+            lines.push(format!("   {} fillable {:.2} at {} (slippage {})", leg.outcome, leg.fillable_size,
+                leg.executable_price.map_or("-".to_string(), |p| format!("{:.4}", p)),
+                leg.slippage.map_or("-".to_string(), |s| format!("{:.2}%", s * 100.0))));
+        }
+        lines.push(format!("   net edge ${:.4} on ${:.2} notional", inspection.trade.net_edge, inspection.trade.total_notional));
+    }
+    for event in &ctx.events {
+        lines.push(format!("   [{:+}s] {:?} {}: {}", event.timestamp as i64 - ctx.timestamp as i64, event.level, event.component, event.message));
+    }
+    for spend in &ctx.spends {
+        lines.push(format!("   [{:+}s] spent ${:.2} on {}{}", spend.timestamp as i64 - ctx.timestamp as i64, spend.notional, spend.token_id,
+            spend.tx_hash.as_deref().map(|h| format!(" (tx {})", h)).unwrap_or_default()));
+    }
+    if ctx.events.is_empty() && ctx.spends.is_empty() {
+        lines.push("   no events or spends recorded for this market around the signal".to_string());
+    }
+
+    lines.push("7. Verdict".to_string());
+    let replayed = match &inspection {
+        Some((_, _, i)) if i.trade.would_trade() => "would trade",
+        Some(_) => "would be refused",
+        None if !detected => "no signal",
+        None => "undetermined (no books)",
+    };
+    let explanation = match record.decision {
+        Decision::NoSignal => "the detector found no arbitrage",
+        Decision::Filtered => "the edge was below the regime threshold plus haircut",
+        Decision::Signalled => "it was queued but refused by a check or decayed before execution",
+        Decision::Traded => "it was executed",
+    };
+    lines.push(format!("   recorded {}: {}; replay on recorded books {}", record.decision.as_str(), explanation, replayed));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decisions::DecisionFeatures;
+    use crate::types::PriceLevel;
+
+    #[test]
+    fn test_trace_walks_every_step() {
+        assert_eq!(parse_signal_id("0xabc:12:1700000000"), Some(("0xabc:12".to_string(), 1_700_000_000)));
+        assert_eq!(parse_signal_id("m1"), None);
+
+        let market: Market = serde_json::from_value(serde_json::json!({
+            "id": "m1", "question": "Will it rain?", "slug": "rain", "outcomes": ["Yes", "No"],
+            "outcome_prices": [0.44, 0.46], "clob_token_ids": ["yes", "no"], "best_bid": null, "best_ask": null,
+            "maker_base_fee": 0, "taker_base_fee": 0, "liquidity": 1000.0, "volume_24hr": 0.0,
+            "active": true, "accepting_orders": true
+        })).unwrap();
+        let book = |token: &str, ask: f64| OrderBook {
+            token_id: token.to_string(),
+            bids: vec![PriceLevel { price: ask - 0.02, size: 50.0 }],
+            asks: vec![PriceLevel { price: ask, size: 50.0 }],
+            timestamp: 99,
+        };
+        let features = DecisionFeatures::new(&market, None, 100, 0, 5);
+        let record = DecisionRecord {
+            record_id: "m1:100".to_string(), market_id: "m1".to_string(), timestamp: 100, features,
+            decision: Decision::Traded, edge: Some(0.10), deviation_after: Default::default(), realized_pnl: Some(0.5), conflict: None,
+        };
+        let ctx = ReplayContext {
+            signal_id: record.record_id.clone(), market_id: "m1".to_string(), timestamp: 100, record: Some(record),
+            market: Some(market), books: vec![Some(book("yes", 0.44)), Some(book("no", 0.46))], events: Vec::new(), spends: Vec::new(),
+        };
+        let mut config = Config::default_config();
+        config.trading.gas_per_leg_usd = 0.0;
+        let lines = trace(&ctx, &config);
+        for step in ["1.", "2.", "3.", "4.", "5.", "6.", "7."] {
+            assert!(lines.iter().any(|l| l.starts_with(step)), "missing step {}", step);
+        }
+        assert!(lines.iter().any(|l| l.contains("✅ spread")));
+        assert!(lines.iter().any(|l| l.contains("recorded 1 before the signal")));
+        assert!(lines.last().unwrap().contains("recorded traded"));
+    }
+}
@@ -0,0 +1,388 @@
+//! Resolution outcome tracking: each traded market's realized return against
+//! the theoretical one, and the surprise of its payout, ambiguous outcomes flagged
+
+use crate::config::ResolutionConfig;
+use crate::storage::JsonlStore;
+use crate::types::Market;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Sizes / prices below this are treated as zero
+const EPS: f64 = 1e-9;
+
+/// How a closed market resolved
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Resolution {
+    /// Payout per token, in outcome order
+    pub prices: Vec<f64>,
+}
+
+impl Resolution {
+    /// No outcome paid in full
+    pub fn ambiguous(&self) -> bool {
+        !self.prices.iter().any(|p| (p - 1.0).abs() < EPS)
+    }
+
+    pub fn winner(&self) -> Option<usize> {
+        self.prices.iter().position(|p| (p - 1.0).abs() < EPS)
+    }
+}
+
+/// Resolution of a Gamma `/markets/{id}` response, once the market is closed and settled
+pub fn parse_resolution(market: &serde_json::Value) -> Option<Resolution> {
+    if !market["closed"].as_bool().unwrap_or(false) {
+        return None;
+    }
+    // Proposed or disputed outcomes are not final
+    if market["umaResolutionStatus"].as_str().is_some_and(|s| !s.is_empty() && s != "resolved") {
+        return None;
+    }
+    let prices: Vec<f64> = match &market["outcomePrices"] {
+        serde_json::Value::String(s) => serde_json::from_str::<Vec<String>>(s).ok()?.iter().filter_map(|p| p.parse().ok()).collect(),
+        serde_json::Value::Array(a) => a.iter().filter_map(|p| p.as_f64().or_else(|| p.as_str()?.parse().ok())).collect(),
+        _ => return None,
+    };
+    // Settlement prices split exactly $1 across the outcomes
+    let settled = !prices.is_empty() && (prices.iter().sum::<f64>() - 1.0).abs() < 0.01;
+    settled.then_some(Resolution { prices })
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Leg {
+    size: f64,
+    cost: f64,
+    exited: f64,
+    proceeds: f64,
+}
+
+/// A traded market followed to resolution
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackedMarket {
+    pub market_id: String,
+    pub token_ids: Vec<String>,
+    pub outcomes: Vec<String>,
+    pub first_entry: u64,
+    #[serde(skip)]
+    legs: BTreeMap<String, Leg>,
+    pub resolution: Option<Resolution>,
+    pub resolved_at: Option<u64>,
+    #[serde(skip)]
+    last_checked: u64,
+}
+
+/// Realized vs theoretical outcome of one resolved market
+#[derive(Debug, Clone, Serialize)]
+pub struct MarketOutcome {
+    pub market_id: String,
+    pub resolved_at: u64,
+    pub winner: Option<String>,
+    pub ambiguous: bool,
+    /// All-in cost of every entry (fees included)
+    pub cost: f64,
+    /// Proceeds of exits before resolution
+    pub exit_proceeds: f64,
+    /// Resolution payout on the tokens still held
+    pub payout: f64,
+    pub realized_return: f64,
+    /// Complete bundles at $1 each
+    pub theoretical_return: f64,
+    /// Payout minus expected payout ($)
+    pub surprise: f64,
+}
+
+impl TrackedMarket {
+    fn held(&self, token_id: &str) -> f64 {
+        self.legs.get(token_id).map_or(0.0, |l| (l.size - l.exited).max(0.0))
+    }
+
+    /// Complete bundles bought (every outcome leg filled)
+    fn bundles(&self) -> f64 {
+        let bundles = self.token_ids.iter().map(|t| self.legs.get(t).map_or(0.0, |l| l.size)).fold(f64::INFINITY, f64::min);
+        if bundles.is_finite() { bundles } else { 0.0 }
+    }
+
+    pub fn outcome(&self) -> Option<MarketOutcome> {
+        let resolution = self.resolution.as_ref()?;
+        let cost: f64 = self.legs.values().map(|l| l.cost).sum();
+        let exit_proceeds: f64 = self.legs.values().map(|l| l.proceeds).sum();
+        let payout: f64 = self.token_ids.iter().zip(&resolution.prices).map(|(t, p)| self.held(t) * p).sum();
+        // Expected at entry: complete bundles still held pay $1, leftovers their entry price
+        let held_bundles = self.token_ids.iter().map(|t| self.held(t)).fold(f64::INFINITY, f64::min);
+        let held_bundles = if held_bundles.is_finite() { held_bundles } else { 0.0 };
+        let leftovers: f64 = self.token_ids.iter().filter_map(|t| self.legs.get(t).map(|l| (t, l)))
+            .filter(|(_, l)| l.size > EPS)
+            .map(|(t, l)| (self.held(t) - held_bundles).max(0.0) * l.cost / l.size)
+            .sum();
+        let expected = held_bundles + leftovers;
+        let ret = |value: f64| if cost > EPS { (value - cost) / cost } else { 0.0 };
+        Some(MarketOutcome {
+            market_id: self.market_id.clone(),
+            resolved_at: self.resolved_at.unwrap_or(0),
+            winner: resolution.winner().map(|i| self.outcomes.get(i).cloned().unwrap_or_else(|| i.to_string())),
+            ambiguous: resolution.ambiguous(),
+            cost,
+            exit_proceeds,
+            payout,
+            realized_return: ret(exit_proceeds + payout),
+            theoretical_return: ret(self.bundles()),
+            surprise: payout - expected,
+        })
+    }
+}
+
+/// Aggregate edge realization over resolved markets
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ResolutionStats {
+    pub tracked: usize,
+    pub resolved: usize,
+    pub ambiguous: usize,
+    pub mean_realized_return: f64,
+    pub mean_theoretical_return: f64,
+    /// Mean realized minus theoretical return
+    pub mean_return_gap: f64,
+    /// Mean and std-dev of the resolution surprise ($)
+    pub mean_surprise: f64,
+    pub surprise_std: f64,
+}
+
+/// `GET /api/resolutions` response
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolutionReport {
+    pub stats: ResolutionStats,
+    pub outcomes: Vec<MarketOutcome>,
+    /// Markets still waiting to resolve
+    pub pending: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum ResolutionEvent {
+    Entry { market_id: String, token_ids: Vec<String>, outcomes: Vec<String>, token_id: String, size: f64, cost: f64, at: u64 },
+    Exit { market_id: String, token_id: String, size: f64, proceeds: f64, at: u64 },
+    Resolved { market_id: String, resolution: Resolution, at: u64 },
+}
+
+/// Follows traded markets to resolution
+#[derive(Debug)]
+pub struct ResolutionTracker {
+    config: ResolutionConfig,
+    markets: BTreeMap<String, TrackedMarket>,
+    journal: Option<JsonlStore>,
+}
+
+impl ResolutionTracker {
+    pub fn new(config: ResolutionConfig) -> Self {
+        Self { config, markets: BTreeMap::new(), journal: None }
+    }
+
+    /// Journal to `journal`, replaying the markets already tracked there
+    pub fn with_journal(mut self, journal: JsonlStore) -> Self {
+        let events: Vec<ResolutionEvent> = journal.load().unwrap_or_else(|e| {
+            eprintln!("⚠️ [Resolution] Failed to read resolution journal: {}", e);
+            Vec::new()
+        });
+        for event in events {
+            self.apply(event);
+        }
+        self.journal = Some(journal);
+        self
+    }
+
+    fn apply(&mut self, event: ResolutionEvent) {
+        match event {
+            ResolutionEvent::Entry { market_id, token_ids, outcomes, token_id, size, cost, at } => {
+                let market = self.markets.entry(market_id.clone()).or_insert_with(|| TrackedMarket {
+                    market_id,
+                    token_ids,
+                    outcomes,
+                    first_entry: at,
+                    legs: BTreeMap::new(),
+                    resolution: None,
+                    resolved_at: None,
+                    last_checked: 0,
+                });
+                let leg = market.legs.entry(token_id).or_default();
+                leg.size += size;
+                leg.cost += cost;
+            }
+            ResolutionEvent::Exit { market_id, token_id, size, proceeds, .. } => {
+                if let Some(leg) = self.markets.get_mut(&market_id).and_then(|m| m.legs.get_mut(&token_id)) {
+                    leg.exited += size;
+                    leg.proceeds += proceeds;
+                }
+            }
+            ResolutionEvent::Resolved { market_id, resolution, at } => {
+                if let Some(market) = self.markets.get_mut(&market_id) {
+                    market.resolution = Some(resolution);
+                    market.resolved_at = Some(at);
+                }
+            }
+        }
+    }
+
+    fn record(&mut self, event: ResolutionEvent) {
+        if let Some(journal) = &self.journal {
+            if let Err(e) = journal.append(&event) {
+                eprintln!("⚠️ [Resolution] Failed to journal resolution event: {}", e);
+            }
+        }
+        self.apply(event);
+    }
+
+    /// A leg filled: `cost` is all-in (notional plus fees)
+    pub fn record_entry(&mut self, market: &Market, token_id: &str, size: f64, cost: f64, now: u64) {
+        self.record(ResolutionEvent::Entry {
+            market_id: market.id.clone(),
+            token_ids: market.clob_token_ids.clone(),
+            outcomes: market.outcomes.clone(),
+            token_id: token_id.to_string(),
+            size,
+            cost,
+            at: now,
+        });
+    }
+
+    /// A leg was sold before resolution for `proceeds` (after fees)
+    pub fn record_exit(&mut self, market_id: &str, token_id: &str, size: f64, proceeds: f64, now: u64) {
+        if self.markets.get(market_id).is_some_and(|m| m.resolution.is_none()) {
+            self.record(ResolutionEvent::Exit { market_id: market_id.to_string(), token_id: token_id.to_string(), size, proceeds, at: now });
+        }
+    }
+
+    pub fn record_resolution(&mut self, market_id: &str, resolution: Resolution, now: u64) -> Option<MarketOutcome> {
+        if self.markets.get(market_id).is_none_or(|m| m.resolution.is_some()) {
+            return None;
+        }
+        self.record(ResolutionEvent::Resolved { market_id: market_id.to_string(), resolution, at: now });
+        self.markets.get(market_id).and_then(TrackedMarket::outcome)
+    }
+
+    /// Unresolved markets due a resolution check (at most `max_checks_per_tick`), marked as checked
+    pub fn due(&mut self, now: u64) -> Vec<String> {
+        let mut due = Vec::new();
+        for market in self.markets.values_mut().filter(|m| m.resolution.is_none()) {
+            if due.len() >= self.config.max_checks_per_tick {
+                break;
+            }
+            if now >= market.last_checked + self.config.poll_secs {
+                market.last_checked = now;
+                due.push(market.market_id.clone());
+            }
+        }
+        due
+    }
+
+    pub fn report(&self) -> ResolutionReport {
+        let outcomes: Vec<MarketOutcome> = self.markets.values().filter_map(TrackedMarket::outcome).collect();
+        let n = outcomes.len();
+        let mean = |f: fn(&MarketOutcome) -> f64| if n > 0 { outcomes.iter().map(f).sum::<f64>() / n as f64 } else { 0.0 };
+        let mean_surprise = mean(|o| o.surprise);
+        let surprise_var = if n > 0 { outcomes.iter().map(|o| (o.surprise - mean_surprise).powi(2)).sum::<f64>() / n as f64 } else { 0.0 };
+        let stats = ResolutionStats {
+            tracked: self.markets.len(),
+            resolved: n,
+            ambiguous: outcomes.iter().filter(|o| o.ambiguous).count(),
+            mean_realized_return: mean(|o| o.realized_return),
+            mean_theoretical_return: mean(|o| o.theoretical_return),
+            mean_return_gap: mean(|o| o.realized_return - o.theoretical_return),
+            mean_surprise,
+            surprise_std: surprise_var.sqrt(),
+        };
+        let pending = self.markets.values().filter(|m| m.resolution.is_none()).map(|m| m.market_id.clone()).collect();
+        ResolutionReport { stats, outcomes, pending }
+    }
+}
+
+/// Final resolution of `market_id` from the Gamma markets endpoint, if it has resolved
+pub async fn fetch_resolution(client: &reqwest::Client, markets_url: &str, market_id: &str) -> Result<Option<Resolution>, String> {
+    let url = format!("{}/{}", markets_url.trim_end_matches('/'), market_id);
+    let resp = client.get(&url).send().await.map_err(|e| e.to_string())?;
+    if !resp.status().is_success() {
+        return Err(format!("{} returned {}", url, resp.status()));
+    }
+    let market: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+    Ok(parse_resolution(&market))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn market(id: &str) -> Market {
+        Market { id: id.to_string(), ..testing::market() }
+    }
+
+    fn yes() -> Resolution {
+        parse_resolution(&serde_json::json!({"closed": true, "umaResolutionStatus": "resolved", "outcomePrices": "[\"1\", \"0\"]"})).unwrap()
+    }
+
+    fn config() -> ResolutionConfig {
+        ResolutionConfig { poll_secs: 600, ..Default::default() }
+    }
+
+    /// m1: complete bundle, 10 YES @ 0.45 + 10 NO @ 0.50, $0.10 fees → $9.60 for a $10 payoff;
+    /// m2: unhedged, only the YES leg filled
+    fn record_entries(tracker: &mut ResolutionTracker) {
+        tracker.record_entry(&market("m1"), "y", 10.0, 4.55, 0);
+        tracker.record_entry(&market("m1"), "n", 10.0, 5.05, 0);
+        tracker.record_entry(&market("m2"), "y", 10.0, 4.0, 0);
+    }
+
+    #[test]
+    fn test_only_final_resolutions_parsed() {
+        let open = serde_json::json!({"closed": false, "outcomePrices": "[\"0.6\", \"0.4\"]"});
+        assert_eq!(parse_resolution(&open), None);
+        let disputed = serde_json::json!({"closed": true, "umaResolutionStatus": "disputed", "outcomePrices": "[\"1\", \"0\"]"});
+        assert_eq!(parse_resolution(&disputed), None);
+        assert_eq!((yes().winner(), yes().ambiguous()), (Some(0), false));
+    }
+
+    #[test]
+    fn test_markets_polled_every_poll_interval() {
+        let mut tracker = ResolutionTracker::new(config());
+        record_entries(&mut tracker);
+        assert!(tracker.due(100).is_empty());
+        assert_eq!(tracker.due(600).len(), 2);
+        assert!(tracker.due(700).is_empty());
+    }
+
+    #[test]
+    fn test_complete_bundle_realizes_theoretical_return() {
+        let mut tracker = ResolutionTracker::new(config());
+        record_entries(&mut tracker);
+        let m1 = tracker.record_resolution("m1", yes(), 1_000).unwrap();
+        assert_eq!(m1.winner.as_deref(), Some("Yes"));
+        assert!((m1.realized_return - 0.4 / 9.6).abs() < 1e-9);
+        assert!((m1.realized_return - m1.theoretical_return).abs() < 1e-9);
+        assert!(m1.surprise.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_unhedged_leg_surprises() {
+        let mut tracker = ResolutionTracker::new(config());
+        record_entries(&mut tracker);
+        // The unhedged leg was expected to be worth its $4 cost but paid $10
+        let m2 = tracker.record_resolution("m2", yes(), 1_000).unwrap();
+        assert!((m2.surprise - 6.0).abs() < 1e-9);
+        assert!(m2.theoretical_return < 0.0);
+        assert!(tracker.record_resolution("m2", Resolution { prices: vec![0.0, 1.0] }, 2_000).is_none());
+    }
+
+    #[test]
+    fn test_report_restored_from_journal() {
+        let dir = std::env::temp_dir().join(format!("arbishark_resolution_{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+        let journal = JsonlStore::open(dir, "resolutions.jsonl").unwrap();
+        let mut tracker = ResolutionTracker::new(config()).with_journal(journal.clone());
+        record_entries(&mut tracker);
+        tracker.record_resolution("m1", yes(), 1_000);
+        tracker.record_resolution("m2", yes(), 1_000);
+
+        let report = ResolutionTracker::new(config()).with_journal(journal).report();
+        assert_eq!((report.stats.tracked, report.stats.resolved, report.stats.ambiguous), (2, 2, 0));
+        assert!((report.stats.mean_surprise - 3.0).abs() < 1e-9);
+        assert!(report.pending.is_empty());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,388 @@
+// Retention and cold-path archival of recordings and time-series journals
+// Rotated into dated segments, compressed and uploaded after `hot_days`, restorable by period
+
+use crate::config::RetentionConfig;
+use crate::object_store::{ObjectKind, ObjectStore};
+use crate::storage::JsonlStore;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+const DAY_SECS: u64 = 86_400;
+const ZSTD_LEVEL: i32 = 9;
+/// Header of columnar recordings (see `recorder`), kept once when segments are joined
+const COLUMNAR_HEADER_LEN: usize = 5;
+
+/// A rotated segment of `file`, closed at `end`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Segment {
+    /// Source file name (`books.abk`)
+    pub file: String,
+    pub end: u64,
+}
+
+impl Segment {
+    /// `books.abk` closed at 1700000000 → `books.1700000000.abk`
+    pub fn name(&self) -> String {
+        match self.file.rsplit_once('.') {
+            Some((stem, ext)) => format!("{}.{}.{}", stem, self.end, ext),
+            None => format!("{}.{}", self.file, self.end),
+        }
+    }
+
+    /// Inverse of `name` for one of the retained `files`
+    pub fn parse(name: &str, files: &[String]) -> Option<Self> {
+        files.iter().find_map(|file| {
+            let (stem, ext) = file.rsplit_once('.').unwrap_or((file.as_str(), ""));
+            let end = name.strip_prefix(stem)?.strip_prefix('.')?;
+            let end = match ext {
+                "" => end,
+                ext => end.strip_suffix(ext)?.strip_suffix('.')?,
+            };
+            Some(Self { file: file.clone(), end: end.parse().ok()? })
+        })
+    }
+
+    fn archive_name(&self) -> String {
+        format!("{}.zst", self.name())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum ManifestEvent {
+    Archived { segment: Segment, local: bool, object: Option<String>, at: u64 },
+    Deleted { segment: Segment, at: u64 },
+}
+
+/// Where an archived segment can be read from
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchivedSegment {
+    pub segment: Segment,
+    pub local: Option<PathBuf>,
+    pub object: Option<String>,
+}
+
+/// Outcome of one retention pass
+#[derive(Debug, Clone, Default)]
+pub struct RetentionReport {
+    pub rotated: Vec<String>,
+    pub archived: Vec<String>,
+    pub uploaded: Vec<String>,
+    pub deleted: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+impl RetentionReport {
+    pub fn is_empty(&self) -> bool {
+        self.rotated.is_empty() && self.archived.is_empty() && self.deleted.is_empty() && self.errors.is_empty()
+    }
+}
+
+/// Rotation, archival and expiry of the retained files under a data directory
+#[derive(Debug)]
+pub struct Retention {
+    config: RetentionConfig,
+    data_dir: PathBuf,
+    segments_dir: PathBuf,
+    archive_dir: PathBuf,
+    manifest: JsonlStore,
+    store: Option<ObjectStore>,
+}
+
+impl Retention {
+    pub fn open(data_dir: &str, config: RetentionConfig) -> io::Result<Self> {
+        let segments_dir = Path::new(data_dir).join("segments");
+        fs::create_dir_all(&segments_dir)?;
+        let manifest = JsonlStore::open(&config.archive_dir, "manifest.jsonl")?;
+        Ok(Self {
+            store: None,
+            data_dir: PathBuf::from(data_dir),
+            segments_dir,
+            archive_dir: PathBuf::from(&config.archive_dir),
+            manifest,
+            config,
+        })
+    }
+
+    /// Upload archives to `store` (and fetch them back from it on restore)
+    pub fn with_store(mut self, store: Option<ObjectStore>) -> Self {
+        self.store = store;
+        self
+    }
+
+    /// Hot segments on disk, oldest first
+    pub fn hot_segments(&self) -> io::Result<Vec<Segment>> {
+        let mut segments: Vec<Segment> = fs::read_dir(&self.segments_dir)?
+            .filter_map(|entry| Segment::parse(&entry.ok()?.file_name().to_string_lossy(), &self.config.files))
+            .collect();
+        segments.sort_by_key(|s| (s.end, s.file.clone()));
+        Ok(segments)
+    }
+
+    /// Archived segments not yet deleted, oldest first
+    pub fn archived(&self) -> io::Result<Vec<ArchivedSegment>> {
+        let mut archived: BTreeMap<(u64, String), ArchivedSegment> = BTreeMap::new();
+        for event in self.manifest.load::<ManifestEvent>()? {
+            match event {
+                ManifestEvent::Archived { segment, local, object, .. } => {
+                    let local = local.then(|| self.archive_dir.join(segment.archive_name()));
+                    archived.insert((segment.end, segment.file.clone()), ArchivedSegment { segment, local, object });
+                }
+                ManifestEvent::Deleted { segment, .. } => {
+                    archived.remove(&(segment.end, segment.file));
+                }
+            }
+        }
+        Ok(archived.into_values().collect())
+    }
+
+    /// When `file` was last rotated (its newest segment, else when the file was created)
+    fn last_rotation(&self, file: &str, path: &Path) -> Option<u64> {
+        let newest_hot = self.hot_segments().ok()?.into_iter().filter(|s| s.file == file).map(|s| s.end).max();
+        let newest_archived = self.archived().ok()?.into_iter().filter(|a| a.segment.file == file).map(|a| a.segment.end).max();
+        newest_hot.max(newest_archived).or_else(|| {
+            let meta = fs::metadata(path).ok()?;
+            let created = meta.created().or_else(|_| meta.modified()).ok()?;
+            created.duration_since(std::time::UNIX_EPOCH).ok().map(|d| d.as_secs())
+        })
+    }
+
+    /// Close every retained file whose segment is `rotate_secs` old; returns the new segment names
+    pub fn rotate(&self, now: u64) -> io::Result<Vec<String>> {
+        let mut rotated = Vec::new();
+        for file in &self.config.files {
+            let path = self.data_dir.join(file);
+            let len = fs::metadata(&path).map_or(0, |m| m.len());
+            let empty = len == 0 || (file.ends_with(".abk") && len as usize <= COLUMNAR_HEADER_LEN);
+            let due = self.last_rotation(file, &path).is_some_and(|t| now.saturating_sub(t) >= self.config.rotate_secs);
+            if empty || !due {
+                continue;
+            }
+            let segment = Segment { file: file.clone(), end: now };
+            // Writers append by path, so the next write starts a fresh hot file
+            fs::rename(&path, self.segments_dir.join(segment.name()))?;
+            rotated.push(segment.name());
+        }
+        Ok(rotated)
+    }
+
+    /// Compress segments older than `hot_days` into the archive directory
+    pub fn archive(&self, now: u64) -> io::Result<Vec<Segment>> {
+        let cutoff = now.saturating_sub(self.config.hot_days * DAY_SECS);
+        let mut archived = Vec::new();
+        for segment in self.hot_segments()?.into_iter().filter(|s| s.end < cutoff) {
+            let source = self.segments_dir.join(segment.name());
+            let compressed = zstd::encode_all(fs::File::open(&source)?, ZSTD_LEVEL)?;
+            let target = self.archive_dir.join(segment.archive_name());
+            let tmp = target.with_extension("zst.tmp");
+            fs::write(&tmp, compressed)?;
+            fs::rename(&tmp, &target)?;
+            self.manifest.append(&ManifestEvent::Archived { segment: segment.clone(), local: true, object: None, at: now })?;
+            fs::remove_file(&source)?;
+            archived.push(segment);
+        }
+        Ok(archived)
+    }
+
+    /// One pass: rotate, archive (and upload), then delete expired archives
+    pub async fn run(&self, now: u64) -> RetentionReport {
+        let mut report = RetentionReport::default();
+        match self.rotate(now) {
+            Ok(rotated) => report.rotated = rotated,
+            Err(e) => report.errors.push(format!("rotate: {}", e)),
+        }
+        let archived = self.archive(now).unwrap_or_else(|e| {
+            report.errors.push(format!("archive: {}", e));
+            Vec::new()
+        });
+        for segment in archived {
+            report.archived.push(segment.name());
+            let Some(store) = &self.store else { continue };
+            let local = self.archive_dir.join(segment.archive_name());
+            let key = store.key(ObjectKind::Archive, &segment.archive_name());
+            let uploaded = match fs::read(&local) {
+                Ok(body) => store.put(&key, body).await,
+                Err(e) => Err(e.to_string()),
+            };
+            if let Err(e) = uploaded {
+                report.errors.push(format!("upload {}: {}", key, e));
+                continue;
+            }
+            let keep_local = self.config.keep_local || fs::remove_file(&local).is_err();
+            let event = ManifestEvent::Archived { segment, local: keep_local, object: Some(key.clone()), at: now };
+            if let Err(e) = self.manifest.append(&event) {
+                report.errors.push(format!("manifest: {}", e));
+            }
+            report.uploaded.push(key);
+        }
+        if self.config.delete_after_days > 0 {
+            let cutoff = now.saturating_sub(self.config.delete_after_days * DAY_SECS);
+            let expired = self.archived().unwrap_or_else(|e| {
+                report.errors.push(format!("manifest: {}", e));
+                Vec::new()
+            });
+            for archived in expired.into_iter().filter(|a| a.segment.end < cutoff) {
+                if let Some(local) = &archived.local {
+                    if let Err(e) = fs::remove_file(local).or_else(|e| if e.kind() == io::ErrorKind::NotFound { Ok(()) } else { Err(e) }) {
+                        report.errors.push(format!("delete {}: {}", local.display(), e));
+                        continue;
+                    }
+                }
+                if let (Some(store), Some(key)) = (&self.store, &archived.object) {
+                    if let Err(e) = store.delete(key).await {
+                        report.errors.push(format!("delete {}: {}", key, e));
+                        continue;
+                    }
+                }
+                if let Err(e) = self.manifest.append(&ManifestEvent::Deleted { segment: archived.segment.clone(), at: now }) {
+                    report.errors.push(format!("manifest: {}", e));
+                }
+                report.deleted.push(archived.segment.name());
+            }
+        }
+        report
+    }
+
+    /// Rebuild `[from, to]` (unix seconds) into `<data_dir>/restored/`, one file per retained source
+    pub async fn restore(&self, from: u64, to: u64) -> io::Result<Vec<(PathBuf, usize)>> {
+        // A segment closed at `end` holds (at most) the preceding `rotate_secs`
+        let overlaps = |s: &Segment| s.end >= from && s.end.saturating_sub(self.config.rotate_secs) <= to;
+        let mut parts: BTreeMap<String, Vec<(u64, Vec<u8>)>> = BTreeMap::new();
+        for segment in self.hot_segments()?.into_iter().filter(|s| overlaps(s)) {
+            let bytes = fs::read(self.segments_dir.join(segment.name()))?;
+            parts.entry(segment.file.clone()).or_default().push((segment.end, bytes));
+        }
+        for archived in self.archived()?.into_iter().filter(|a| overlaps(&a.segment)) {
+            let compressed = match (&archived.local, &archived.object, &self.store) {
+                (Some(local), _, _) if local.exists() => fs::read(local)?,
+                (_, Some(key), Some(store)) => store.get(key).await.map_err(io::Error::other)?,
+                _ => {
+                    eprintln!("⚠️ [Retention] {} is not available locally or remotely", archived.segment.name());
+                    continue;
+                }
+            };
+            let bytes = zstd::decode_all(&compressed[..])?;
+            parts.entry(archived.segment.file.clone()).or_default().push((archived.segment.end, bytes));
+        }
+        let out_dir = self.data_dir.join("restored");
+        fs::create_dir_all(&out_dir)?;
+        let mut restored = Vec::new();
+        for (file, mut segments) in parts {
+            segments.sort_by_key(|(end, _)| *end);
+            let (stem, ext) = file.rsplit_once('.').unwrap_or((file.as_str(), "jsonl"));
+            let path = out_dir.join(format!("{}.{}-{}.{}", stem, from, to, ext));
+            let mut out = fs::File::create(&path)?;
+            for (i, (_, bytes)) in segments.iter().enumerate() {
+                // Columnar segments each start with the recording header; the frames that follow concatenate
+                let skip = if ext == "abk" && i > 0 { COLUMNAR_HEADER_LEN.min(bytes.len()) } else { 0 };
+                out.write_all(&bytes[skip..])?;
+            }
+            restored.push((path, segments.len()));
+        }
+        Ok(restored)
+    }
+}
+
+/// `2024-01-31` (UTC midnight) or unix seconds
+pub fn parse_date(s: &str) -> Option<u64> {
+    s.parse::<u64>().ok().or_else(|| {
+        let date = chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()?;
+        Some(date.and_hms_opt(0, 0, 0)?.and_utc().timestamp().max(0) as u64)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recorder::{read_recording, RecordFormat, TickRecorder};
+    use crate::testing;
+    use crate::types::OrderBook;
+    use chrono::Utc;
+
+    fn book(timestamp: u64) -> OrderBook {
+        OrderBook { timestamp, ..testing::book("t1", &[(0.48, 10.0)], &[(0.52, 10.0)]) }
+    }
+
+    /// Retention over a fresh data dir, with three daily segments of recordings and events rotated
+    fn rotated(test: &str) -> (Retention, std::path::PathBuf, [u64; 3]) {
+        let dir = std::env::temp_dir().join(format!("arbishark_retention_{}_{}", test, std::process::id()));
+        let data_dir = dir.to_str().unwrap().to_string();
+        let config = RetentionConfig {
+            enabled: true,
+            files: vec!["books.abk".to_string(), "events.jsonl".to_string()],
+            rotate_secs: DAY_SECS,
+            hot_days: 2,
+            delete_after_days: 10,
+            archive_dir: dir.join("archive").to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        let retention = Retention::open(&data_dir, config).unwrap();
+        let now = Utc::now().timestamp() as u64;
+        let mut recorder = TickRecorder::open(&data_dir, RecordFormat::Columnar).unwrap();
+        let events = JsonlStore::open(&data_dir, "events.jsonl").unwrap();
+        let days = [now + DAY_SECS, now + 2 * DAY_SECS, now + 3 * DAY_SECS];
+        for (i, day) in days.iter().enumerate() {
+            recorder.record(&book(i as u64)).unwrap();
+            recorder.flush().unwrap();
+            events.append(&serde_json::json!({"day": i})).unwrap();
+            assert_eq!(retention.rotate(*day).unwrap().len(), 2, "day {}", i);
+            assert!(retention.rotate(*day + 60).unwrap().is_empty(), "not due again");
+        }
+        (retention, dir, days)
+    }
+
+    #[test]
+    fn test_segments_rotated_daily() {
+        let (retention, dir, _) = rotated("rotate");
+        assert_eq!(retention.hot_segments().unwrap().len(), 6);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_cold_segments_archived() {
+        let (retention, dir, days) = rotated("archive");
+        // Two days later the first day is cold, the rest still hot
+        let report = retention.run(days[0] + 2 * DAY_SECS + 1).await;
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert_eq!(report.archived.len(), 2);
+        assert_eq!(retention.hot_segments().unwrap().len(), 4);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_restore_spans_archived_and_hot_segments() {
+        let (retention, dir, days) = rotated("restore");
+        retention.run(days[0] + 2 * DAY_SECS + 1).await;
+        let restored = retention.restore(days[0], days[2]).await.unwrap();
+        let books = restored.iter().find(|(p, _)| p.extension().unwrap() == "abk").unwrap();
+        assert_eq!(books.1, 3);
+        let timestamps: Vec<u64> = read_recording(books.0.to_str().unwrap()).unwrap().iter().map(|b| b.timestamp).collect();
+        assert_eq!(timestamps, vec![0, 1, 2]);
+        let restored_events = restored.iter().find(|(p, _)| p.extension().unwrap() == "jsonl").unwrap();
+        assert_eq!(fs::read_to_string(&restored_events.0).unwrap().lines().count(), 3);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_archive_deleted_after_retention() {
+        let (retention, dir, days) = rotated("delete");
+        retention.run(days[0] + 2 * DAY_SECS + 1).await;
+        let report = retention.run(days[0] + 11 * DAY_SECS).await;
+        assert!(report.deleted.contains(&format!("books.{}.abk", days[0])));
+        assert!(retention.archived().unwrap().iter().all(|a| a.segment.end != days[0]));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_segment_names() {
+        let files = vec!["books.abk".to_string(), "events.jsonl".to_string()];
+        let segment = Segment { file: "books.abk".to_string(), end: 1_700_000_000 };
+        assert_eq!(segment.name(), "books.1700000000.abk");
+        assert_eq!(Segment::parse(&segment.name(), &files), Some(segment));
+        assert_eq!(Segment::parse("events.jsonl", &files), None);
+        assert_eq!(parse_date("2024-01-02"), Some(1_704_153_600));
+    }
+}
@@ -12,6 +12,27 @@ pub struct RiskConfig {
     pub volatility_threshold: f64,   // Pause if volatility > threshold
     pub min_liquidity: f64,          // Min market liquidity required
     pub max_position_size: f64,      // Max $ per position
+    /// Size starts shrinking once drawdown / volatility reach this share of their limit
+    #[serde(default = "default_scale_start")]
+    pub scale_start: f64,
+    /// Smallest size multiplier before the hard halt takes over
+    #[serde(default = "default_min_scale")]
+    pub min_scale: f64,
+    /// Halt when volatility exceeds the threshold (off when `[volatility_response]` throttles instead)
+    #[serde(default = "default_volatility_halt")]
+    pub volatility_halt: bool,
+}
+
+fn default_scale_start() -> f64 {
+    0.5
+}
+
+fn default_min_scale() -> f64 {
+    0.1
+}
+
+fn default_volatility_halt() -> bool {
+    true
 }
 
 impl Default for RiskConfig {
@@ -23,6 +44,9 @@ impl Default for RiskConfig {
             volatility_threshold: 0.15, // 15% volatility
             min_liquidity: 1000.0,   // $1000 min liquidity
             max_position_size: 100.0, // $100 max position
+            scale_start: default_scale_start(),
+            min_scale: default_min_scale(),
+            volatility_halt: default_volatility_halt(),
         }
     }
 }
@@ -38,6 +62,7 @@ pub struct RiskManager {
     circuit_breaker: bool,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 struct TradeResult {
     pnl: f64,
@@ -94,7 +119,7 @@ impl RiskManager {
 
         // Check volatility
         let volatility = self.calculate_volatility();
-        if volatility > self.config.volatility_threshold {
+        if self.config.volatility_halt && volatility > self.config.volatility_threshold {
             return (true, Some(format!(
                 "Market too volatile: {:.1}% (limit: {:.1}%)",
                 volatility * 100.0,
@@ -105,7 +130,32 @@ impl RiskManager {
         (false, None)
     }
 
+    /// Size multiplier in [min_scale, 1]: shrinks linearly as drawdown or
+    /// recent volatility move from `scale_start` of their limit to the limit,
+    /// and grows back as they recover. 0 when trading is halted.
+    pub fn size_scale(&self) -> f64 {
+        if self.should_halt().0 {
+            return 0.0;
+        }
+        let ramp = |value: f64, limit: f64| {
+            if limit <= 0.0 {
+                return 1.0;
+            }
+            let start = limit * self.config.scale_start;
+            if value <= start {
+                1.0
+            } else {
+                1.0 - ((value - start) / (limit - start).max(f64::EPSILON)).min(1.0)
+            }
+        };
+        let drawdown = (self.peak_balance - self.current_balance) / self.peak_balance;
+        let scale = ramp(drawdown, self.config.max_drawdown)
+            .min(ramp(self.calculate_volatility(), self.config.volatility_threshold));
+        scale.max(self.config.min_scale)
+    }
+
     /// Validate if a trade is allowed
+    #[allow(dead_code)]
     pub fn validate_trade(&self, trade_size: f64, market_liquidity: f64) -> Result<(), String> {
         // Check position size
         if trade_size > self.config.max_position_size {
@@ -162,6 +212,11 @@ impl RiskManager {
         }
     }
 
+    /// Recent PnL volatility (std dev of trade returns vs balance)
+    pub fn volatility(&self) -> f64 {
+        self.calculate_volatility()
+    }
+
     /// Calculate recent volatility
     fn calculate_volatility(&self) -> f64 {
         if self.recent_trades.len() < 2 {
@@ -188,12 +243,14 @@ impl RiskManager {
     }
 
     /// Activate emergency circuit breaker
+    #[allow(dead_code)]
     pub fn activate_circuit_breaker(&mut self) {
         self.circuit_breaker = true;
         tracing::error!("🚨 Circuit breaker activated!");
     }
 
     /// Deactivate circuit breaker
+    #[allow(dead_code)]
     pub fn deactivate_circuit_breaker(&mut self) {
         self.circuit_breaker = false;
         tracing::info!("✅ Circuit breaker deactivated");
@@ -269,6 +326,30 @@ mod tests {
         assert!(should_halt);
     }
 
+    #[test]
+    fn test_size_scales_with_drawdown() {
+        let config = RiskConfig {
+            max_drawdown: 0.20,
+            volatility_threshold: 1.0,
+            max_consecutive_losses: 100,
+            max_daily_loss: 1000.0,
+            ..Default::default()
+        };
+        let mut manager = RiskManager::new(config, 100.0);
+        assert_eq!(manager.size_scale(), 1.0);
+
+        // 15% drawdown: halfway between 10% (scale start) and the 20% limit
+        manager.record_trade(-15.0);
+        assert!((manager.size_scale() - 0.5).abs() < 1e-9);
+
+        // Recovering restores size
+        manager.record_trade(10.0);
+        assert_eq!(manager.size_scale(), 1.0);
+
+        manager.record_trade(-20.0);
+        assert_eq!(manager.size_scale(), 0.0);
+    }
+
     #[test]
     fn test_trade_validation() {
         let manager = RiskManager::new(RiskConfig::default(), 100.0);
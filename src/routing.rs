@@ -0,0 +1,353 @@
+// Consolidated quotes and best-execution routing
+// Each leg goes to the venue with the best executable price after fees, time-critical legs to the fastest
+
+use crate::config::RoutingConfig;
+use crate::fills::FillModel;
+use crate::mapping::MarketPair;
+use crate::storage::JsonlStore;
+use crate::types::{Market, OrderBook, Side};
+use crate::venue_latency::{LatencyMonitor, VenueLatency};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Decisions kept for `GET /api/routes`
+const MAX_DECISIONS: usize = 200;
+
+/// One venue's book for a leg
+#[derive(Debug, Clone)]
+pub struct VenueBook {
+    pub venue: String,
+    pub book: OrderBook,
+}
+
+/// What one venue offers for the requested size
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VenueQuote {
+    pub venue: String,
+    pub token_id: String,
+    pub bid: Option<f64>,
+    pub ask: Option<f64>,
+    /// Size the book fills now
+    pub fillable: f64,
+    /// VWAP for the fillable size
+    pub executable_price: Option<f64>,
+    /// Executable price after the venue's taker fee (paid on buys, deducted on sells)
+    pub effective_price: Option<f64>,
+    /// Smoothed round trip to the venue when quoted
+    #[serde(default)]
+    pub latency_ms: Option<f64>,
+}
+
+/// Best bid / offer across venues
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsolidatedQuote {
+    pub best_bid: Option<f64>,
+    pub best_bid_venue: Option<String>,
+    pub best_ask: Option<f64>,
+    pub best_ask_venue: Option<String>,
+}
+
+/// One venue-selection decision
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteDecision {
+    pub market_id: String,
+    /// Leg token on the primary venue
+    pub token_id: String,
+    pub side: Side,
+    pub size: f64,
+    pub timestamp: u64,
+    /// Placed after the pair's first leg filled, so routed for speed
+    #[serde(default)]
+    pub time_critical: bool,
+    pub consolidated: ConsolidatedQuote,
+    pub quotes: Vec<VenueQuote>,
+    pub chosen_venue: String,
+    pub reason: String,
+}
+
+impl RouteDecision {
+    /// Index of the chosen venue's book among those routed over
+    pub fn chosen_index(&self) -> usize {
+        self.quotes.iter().position(|q| q.venue == self.chosen_venue).unwrap_or(0)
+    }
+}
+
+/// Best bid / offer over `books`
+pub fn consolidate(books: &[VenueBook]) -> ConsolidatedQuote {
+    let best_bid = books.iter()
+        .filter_map(|b| b.book.best_bid().map(|p| (p, &b.venue)))
+        .max_by(|x, y| x.0.total_cmp(&y.0));
+    let best_ask = books.iter()
+        .filter_map(|b| b.book.best_ask().map(|p| (p, &b.venue)))
+        .min_by(|x, y| x.0.total_cmp(&y.0));
+    ConsolidatedQuote {
+        best_bid: best_bid.map(|(p, _)| p),
+        best_bid_venue: best_bid.map(|(_, v)| v.clone()),
+        best_ask: best_ask.map(|(p, _)| p),
+        best_ask_venue: best_ask.map(|(_, v)| v.clone()),
+    }
+}
+
+/// Token of `alt` for leg `leg` of `primary`: same outcome name, else same position
+pub fn leg_token(primary: &Market, alt: &Market, leg: usize) -> Option<String> {
+    let index = primary.outcomes.get(leg)
+        .and_then(|name| alt.outcomes.iter().position(|o| o.eq_ignore_ascii_case(name)))
+        .unwrap_or(leg);
+    alt.clob_token_ids.get(index).cloned()
+}
+
+/// Routes legs of cross-listed markets to the venue with the best executable price
+#[derive(Debug)]
+pub struct Router {
+    config: RoutingConfig,
+    /// Venue the engine trades on by default
+    primary_venue: String,
+    pairs: Vec<MarketPair>,
+    decisions: VecDeque<RouteDecision>,
+    latency: Option<LatencyMonitor>,
+    journal: Option<JsonlStore>,
+}
+
+impl Router {
+    /// Router over the confirmed `pairs` involving `primary_venue`
+    pub fn new(config: RoutingConfig, primary_venue: &str, pairs: Vec<MarketPair>) -> Self {
+        let pairs = pairs.into_iter().filter(|p| p.venue_a == primary_venue || p.venue_b == primary_venue).collect();
+        Self { config, primary_venue: primary_venue.to_string(), pairs, decisions: VecDeque::new(), latency: None, journal: None }
+    }
+
+    /// Journal decisions to `journal`
+    pub fn with_journal(mut self, journal: JsonlStore) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Route time-critical legs by the latencies `monitor` measures
+    pub fn with_latency(mut self, monitor: LatencyMonitor) -> Self {
+        self.latency = Some(monitor).filter(|m| m.enabled());
+        self
+    }
+
+    /// Record a round trip to `venue`
+    pub fn record_latency(&mut self, venue: &str, rtt_ms: f64, now: u64) {
+        if let Some(monitor) = &mut self.latency {
+            monitor.record(venue, rtt_ms, now);
+        }
+    }
+
+    /// Whether `venue` is due a latency probe
+    pub fn needs_probe(&self, venue: &str, now: u64) -> bool {
+        self.latency.as_ref().is_some_and(|m| m.needs_probe(venue, now))
+    }
+
+    /// Current latency estimates per venue
+    pub fn latencies(&self) -> Vec<VenueLatency> {
+        self.latency.as_ref().map(|m| m.snapshot()).unwrap_or_default()
+    }
+
+    pub fn pair_count(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// (venue, market id) of the same market on the other venue
+    pub fn counterpart(&self, market_id: &str) -> Option<(String, String)> {
+        self.pairs.iter().find_map(|p| {
+            if p.venue_a == self.primary_venue && p.market_a == market_id {
+                Some((p.venue_b.clone(), p.market_b.clone()))
+            } else if p.venue_b == self.primary_venue && p.market_b == market_id {
+                Some((p.venue_a.clone(), p.market_a.clone()))
+            } else {
+                None
+            }
+        })
+    }
+
+    fn fee_rate(&self, venue: &str) -> f64 {
+        self.config.fee_bps.get(venue).copied().unwrap_or(0) as f64 / 10_000.0
+    }
+
+    fn quote(&self, venue_book: &VenueBook, size: f64, side: Side, now: u64) -> VenueQuote {
+        let book = &venue_book.book;
+        let fillable = FillModel::filled_size(book, size, side);
+        let executable_price = book.execution_price(fillable, side).filter(|_| fillable > 0.0);
+        let fee = self.fee_rate(&venue_book.venue);
+        VenueQuote {
+            venue: venue_book.venue.clone(),
+            token_id: book.token_id.clone(),
+            bid: book.best_bid(),
+            ask: book.best_ask(),
+            fillable,
+            executable_price,
+            effective_price: executable_price.map(|p| match side {
+                Side::Buy => p * (1.0 + fee),
+                Side::Sell => p * (1.0 - fee),
+            }),
+            latency_ms: self.latency.as_ref().and_then(|m| m.estimate(&venue_book.venue, now)),
+        }
+    }
+
+    /// Pick the venue for `size` of one leg; `books[0]` is the primary venue's
+    pub fn route(&mut self, market_id: &str, books: &[VenueBook], size: f64, side: Side, time_critical: bool, now: u64) -> RouteDecision {
+        let quotes: Vec<VenueQuote> = books.iter().map(|b| self.quote(b, size, side, now)).collect();
+        // Full fills first, then the better effective price; ties stay on the primary venue
+        let better = |a: &VenueQuote, b: &VenueQuote| {
+            let full = |q: &VenueQuote| q.fillable + 1e-9 >= size;
+            match (full(a), full(b)) {
+                (true, false) => true,
+                (false, true) => false,
+                _ => match (a.effective_price, b.effective_price) {
+                    (Some(pa), Some(pb)) => match side {
+                        Side::Buy => pa < pb - 1e-12,
+                        Side::Sell => pa > pb + 1e-12,
+                    },
+                    (Some(_), None) => true,
+                    _ => false,
+                },
+            }
+        };
+        let mut chosen = 0;
+        for (i, quote) in quotes.iter().enumerate().skip(1) {
+            if better(quote, &quotes[chosen]) {
+                chosen = i;
+            }
+        }
+        // Time-critical legs: the fastest venue filling the size within the price tolerance of the best
+        let fastest = match (&self.latency, time_critical) {
+            (Some(monitor), true) => quotes[chosen].effective_price.and_then(|best| {
+                quotes.iter().enumerate()
+                    .filter(|(_, q)| q.fillable + 1e-9 >= size)
+                    .filter(|(_, q)| q.effective_price.is_some_and(|p| match side {
+                        Side::Buy => p - best <= monitor.price_tolerance() + 1e-12,
+                        Side::Sell => best - p <= monitor.price_tolerance() + 1e-12,
+                    }))
+                    .filter_map(|(i, q)| q.latency_ms.map(|ms| (i, ms)))
+                    .min_by(|a, b| a.1.total_cmp(&b.1))
+            }),
+            _ => None,
+        };
+        let by_latency = match fastest {
+            Some((i, ms)) if i != chosen && quotes[chosen].latency_ms.is_none_or(|c| ms < c) => {
+                let reason = format!("time-critical: {:.0}ms vs {} on {}", ms,
+                    quotes[chosen].latency_ms.map_or("unmeasured".to_string(), |c| format!("{:.0}ms", c)), quotes[chosen].venue);
+                chosen = i;
+                Some(reason)
+            }
+            _ => None,
+        };
+        let reason = by_latency.unwrap_or_else(|| match (chosen, quotes.len()) {
+            (_, 1) => "single venue".to_string(),
+            (0, _) => "primary venue at least as good".to_string(),
+            _ => {
+                let (alt, primary) = (&quotes[chosen], &quotes[0]);
+                match (alt.effective_price, primary.effective_price) {
+                    (Some(a), Some(p)) if primary.fillable + 1e-9 >= size => format!("${:.4} vs ${:.4} on {}", a, p, primary.venue),
+                    _ => format!("{} fills {:.2} of {:.2}", primary.venue, primary.fillable, size),
+                }
+            }
+        });
+        let decision = RouteDecision {
+            market_id: market_id.to_string(),
+            token_id: quotes.first().map(|q| q.token_id.clone()).unwrap_or_default(),
+            side,
+            size,
+            timestamp: now,
+            time_critical,
+            consolidated: consolidate(books),
+            chosen_venue: quotes.get(chosen).map(|q| q.venue.clone()).unwrap_or_default(),
+            quotes,
+            reason,
+        };
+        if let Some(journal) = &self.journal {
+            if let Err(e) = journal.append(&decision) {
+                eprintln!("⚠️ [Routing] Failed to journal route decision: {}", e);
+            }
+        }
+        self.decisions.push_back(decision.clone());
+        while self.decisions.len() > MAX_DECISIONS {
+            self.decisions.pop_front();
+        }
+        decision
+    }
+
+    /// Most recent first
+    pub fn recent(&self) -> Vec<RouteDecision> {
+        self.decisions.iter().rev().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::VenueLatencyConfig;
+    use crate::types::PriceLevel;
+    use std::collections::HashMap;
+
+    fn venue_book(venue: &str, token_id: &str, bid: f64, ask: f64, ask_size: f64) -> VenueBook {
+        VenueBook {
+            venue: venue.to_string(),
+            book: OrderBook {
+                token_id: token_id.to_string(),
+                bids: vec![PriceLevel { price: bid, size: 100.0 }],
+                asks: vec![PriceLevel { price: ask, size: ask_size }],
+                timestamp: 0,
+            },
+        }
+    }
+
+    fn pair() -> MarketPair {
+        MarketPair {
+            venue_a: "polymarket".to_string(),
+            market_a: "pm-1".to_string(),
+            venue_b: "envio".to_string(),
+            market_b: "ev-9".to_string(),
+            question_a: String::new(),
+            question_b: String::new(),
+            confidence: 0.9,
+            suggested: "accept".to_string(),
+            confirmed: true,
+        }
+    }
+
+    #[test]
+    fn test_consolidated_quote_and_routing() {
+        let config = RoutingConfig { fee_bps: HashMap::from([("envio".to_string(), 100)]), ..Default::default() };
+        let mut router = Router::new(config, "envio", vec![pair()]);
+        assert_eq!(router.counterpart("ev-9"), Some(("polymarket".to_string(), "pm-1".to_string())));
+        assert!(router.counterpart("pm-1").is_none());
+
+        let books = [venue_book("envio", "e", 0.44, 0.47, 100.0), venue_book("polymarket", "p", 0.45, 0.465, 100.0)];
+        let consolidated = consolidate(&books);
+        assert_eq!((consolidated.best_bid, consolidated.best_bid_venue.as_deref()), (Some(0.45), Some("polymarket")));
+        assert_eq!((consolidated.best_ask, consolidated.best_ask_venue.as_deref()), (Some(0.465), Some("polymarket")));
+
+        // Cheaper after envio's 1% fee
+        let decision = router.route("ev-9", &books, 10.0, Side::Buy, false, 100);
+        assert_eq!((decision.chosen_venue.as_str(), decision.chosen_index()), ("polymarket", 1));
+        assert_eq!(decision.token_id, "e");
+
+        // A better price that cannot fill the size loses
+        let thin = [venue_book("envio", "e", 0.44, 0.47, 100.0), venue_book("polymarket", "p", 0.45, 0.40, 2.0)];
+        assert_eq!(router.route("ev-9", &thin, 10.0, Side::Buy, false, 101).chosen_venue, "envio");
+        assert_eq!(router.recent().len(), 2);
+        assert_eq!(router.recent()[0].reason, "primary venue at least as good");
+    }
+
+    #[test]
+    fn test_time_critical_legs_go_to_fastest_venue() {
+        let latency = VenueLatencyConfig { enabled: true, ..Default::default() };
+        let mut router = Router::new(RoutingConfig::default(), "envio", vec![pair()]).with_latency(LatencyMonitor::new(latency));
+        router.record_latency("envio", 400.0, 100);
+        router.record_latency("polymarket", 80.0, 100);
+
+        // Polymarket is $0.004 worse: within tolerance for the time-critical leg only
+        let books = [venue_book("envio", "e", 0.44, 0.466, 100.0), venue_book("polymarket", "p", 0.44, 0.47, 100.0)];
+        assert_eq!(router.route("ev-9", &books, 10.0, Side::Buy, false, 100).chosen_venue, "envio");
+        let decision = router.route("ev-9", &books, 10.0, Side::Buy, true, 100);
+        assert_eq!(decision.chosen_venue, "polymarket");
+        assert_eq!(decision.reason, "time-critical: 80ms vs 400ms on envio");
+        assert_eq!(decision.quotes[1].latency_ms, Some(80.0));
+
+        // Too much worse to give up
+        let wide = [venue_book("envio", "e", 0.44, 0.46, 100.0), venue_book("polymarket", "p", 0.44, 0.47, 100.0)];
+        assert_eq!(router.route("ev-9", &wide, 10.0, Side::Buy, true, 100).chosen_venue, "envio");
+    }
+}
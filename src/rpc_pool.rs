@@ -0,0 +1,290 @@
+// Failover pool of JSON-RPC providers per EVM chain
+// Health-scored failover, with critical reads cross-checked across providers
+
+use crate::config::{Config, RpcPoolConfig};
+use crate::registry;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// JSON-RPC error code of a provider's own rate limit
+const LIMIT_EXCEEDED: i64 = -32005;
+
+/// Weight of the latest call in the health score and latency averages
+const SCORE_ALPHA: f64 = 0.2;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RpcError {
+    /// No provider configured for the chain
+    NoProvider(u64),
+    /// Every provider failed; the last failure
+    Unavailable(String),
+    /// The node answered with a JSON-RPC error (a revert, a bad request)
+    Response(Value),
+    /// Providers disagreed on a cross-checked read
+    Mismatch { method: String, answers: usize },
+}
+
+impl std::fmt::Display for RpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoProvider(chain_id) => write!(f, "no RPC for chain {}", chain_id),
+            Self::Unavailable(e) => write!(f, "every RPC failed, last: {}", e),
+            Self::Response(error) => write!(f, "{}", error),
+            Self::Mismatch { method, answers } => write!(f, "{}: no majority among {} RPC answers", method, answers),
+        }
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+/// Health of one provider (`GET /api/rpc`)
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderHealth {
+    pub url: String,
+    /// Moving average of call success, 1 = every recent call succeeded
+    pub score: f64,
+    /// Moving average of successful call latency
+    pub latency_ms: f64,
+    pub calls: u64,
+    pub failures: u64,
+    /// Cross-checked answers outvoted by the other providers
+    pub mismatches: u64,
+    /// Sitting out after a failure until (unix seconds, 0 = available)
+    pub down_until: u64,
+    pub last_error: Option<String>,
+}
+
+impl ProviderHealth {
+    fn new(url: &str) -> Self {
+        Self { url: url.to_string(), score: 1.0, latency_ms: 0.0, calls: 0, failures: 0, mismatches: 0, down_until: 0, last_error: None }
+    }
+}
+
+/// Outcome of one call, for scoring
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Ok { latency_ms: f64 },
+    Failed(String),
+    Outvoted,
+}
+
+/// Providers per chain id, shared by every clone
+#[derive(Debug, Clone)]
+pub struct RpcPool {
+    client: reqwest::Client,
+    chains: Arc<Mutex<BTreeMap<u64, Vec<ProviderHealth>>>>,
+    timeout: Duration,
+    cross_check: usize,
+    failure_cooldown_secs: u64,
+}
+
+impl RpcPool {
+    pub fn new(config: &RpcPoolConfig) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            chains: Arc::new(Mutex::new(BTreeMap::new())),
+            timeout: Duration::from_millis(config.timeout_ms),
+            cross_check: config.cross_check.max(1),
+            failure_cooldown_secs: config.failure_cooldown_secs,
+        }
+    }
+
+    /// The providers of every chain in `config`, each section's own URL first
+    pub fn from_config(config: &Config) -> Self {
+        let pool = Self::new(&config.rpc_pool);
+        pool.add(config.holdings.chain_id, &config.holdings.rpc_url);
+        pool.add(config.arbitrum.as_ref().map_or(registry::ARBITRUM_SEPOLIA, |a| a.sepolia_chain_id), &config.preflight.rpc_url);
+        if let Some(arbitrum) = &config.arbitrum {
+            pool.add(arbitrum.sepolia_chain_id, &arbitrum.sepolia_rpc);
+            pool.add(arbitrum.mainnet_chain_id, &arbitrum.mainnet_rpc);
+            for url in &arbitrum.sepolia_fallback_rpcs {
+                pool.add(arbitrum.sepolia_chain_id, url);
+            }
+            for url in &arbitrum.mainnet_fallback_rpcs {
+                pool.add(arbitrum.mainnet_chain_id, url);
+            }
+        }
+        let configured = config.finality.rpc_urls.iter().map(|(chain, url)| (chain, std::slice::from_ref(url)))
+            .chain(config.rpc_pool.providers.iter().map(|(chain, urls)| (chain, urls.as_slice())));
+        for (chain, urls) in configured {
+            match chain.parse() {
+                Ok(chain_id) => urls.iter().for_each(|url| pool.add(chain_id, url)),
+                Err(_) => eprintln!("⚠️ [RpcPool] Ignoring providers of chain {:?}: not a chain id", chain),
+            }
+        }
+        pool
+    }
+
+    /// Same providers and health, calls bounded by `timeout`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Add `url` to `chain_id`'s providers (empty or known URLs are ignored)
+    pub fn add(&self, chain_id: u64, url: &str) {
+        if url.is_empty() {
+            return;
+        }
+        let mut chains = self.chains.lock().unwrap();
+        let providers = chains.entry(chain_id).or_default();
+        if !providers.iter().any(|p| p.url == url) {
+            providers.push(ProviderHealth::new(url));
+        }
+    }
+
+    /// Every provider URL of `chain_id`, in configured order
+    pub fn urls(&self, chain_id: u64) -> Vec<String> {
+        self.chains.lock().unwrap().get(&chain_id).map_or_else(Vec::new, |p| p.iter().map(|p| p.url.clone()).collect())
+    }
+
+    /// Providers of `chain_id` in the order they are tried: available ones by score, then those sitting out
+    pub fn ranked(&self, chain_id: u64, now: u64) -> Vec<String> {
+        let chains = self.chains.lock().unwrap();
+        let mut providers: Vec<&ProviderHealth> = chains.get(&chain_id).map_or_else(Vec::new, |p| p.iter().collect());
+        // Stable: equally healthy providers keep their configured order
+        providers.sort_by(|a, b| (a.down_until > now).cmp(&(b.down_until > now)).then(b.score.total_cmp(&a.score)));
+        providers.into_iter().map(|p| p.url.clone()).collect()
+    }
+
+    pub fn record(&self, chain_id: u64, url: &str, outcome: Outcome, now: u64) {
+        let mut chains = self.chains.lock().unwrap();
+        let Some(provider) = chains.get_mut(&chain_id).and_then(|p| p.iter_mut().find(|p| p.url == url)) else { return };
+        match outcome {
+            Outcome::Ok { latency_ms } => {
+                provider.calls += 1;
+                provider.score += SCORE_ALPHA * (1.0 - provider.score);
+                provider.latency_ms = if provider.latency_ms == 0.0 { latency_ms } else { provider.latency_ms + SCORE_ALPHA * (latency_ms - provider.latency_ms) };
+                provider.down_until = 0;
+            }
+            Outcome::Failed(error) => {
+                provider.calls += 1;
+                provider.failures += 1;
+                provider.score *= 1.0 - SCORE_ALPHA;
+                provider.down_until = now + self.failure_cooldown_secs;
+                provider.last_error = Some(error);
+            }
+            Outcome::Outvoted => {
+                provider.mismatches += 1;
+                provider.score *= 1.0 - SCORE_ALPHA;
+            }
+        }
+    }
+
+    /// `method` on the healthiest provider of `chain_id`, failing over on transport errors
+    pub async fn call(&self, chain_id: u64, method: &str, params: Value) -> Result<Value, RpcError> {
+        let now = crate::wallet::Wallet::current_timestamp();
+        let ranked = self.ranked(chain_id, now);
+        if ranked.is_empty() {
+            return Err(RpcError::NoProvider(chain_id));
+        }
+        let mut last = String::new();
+        for url in ranked {
+            match self.call_one(chain_id, &url, method, &params, now).await {
+                Ok(result) => return Ok(result),
+                Err(RpcError::Unavailable(e)) => last = format!("{}: {}", url, e),
+                Err(e) => return Err(e),
+            }
+        }
+        Err(RpcError::Unavailable(last))
+    }
+
+    /// `method` answered identically by `cross_check` providers of `chain_id`
+    pub async fn call_checked(&self, chain_id: u64, method: &str, params: Value) -> Result<Value, RpcError> {
+        let now = crate::wallet::Wallet::current_timestamp();
+        let ranked = self.ranked(chain_id, now);
+        if ranked.is_empty() {
+            return Err(RpcError::NoProvider(chain_id));
+        }
+        let needed = self.cross_check.min(ranked.len());
+        let mut answers: Vec<(String, Value)> = Vec::new();
+        let mut last = String::new();
+        for url in ranked {
+            match self.call_one(chain_id, &url, method, &params, now).await {
+                Ok(result) => answers.push((url, result)),
+                Err(RpcError::Unavailable(e)) => last = format!("{}: {}", url, e),
+                Err(e) => return Err(e),
+            }
+            if let Some(agreed) = majority(&answers, needed) {
+                for (url, _) in answers.iter().filter(|(_, answer)| *answer != agreed) {
+                    self.record(chain_id, url, Outcome::Outvoted, now);
+                }
+                return Ok(agreed);
+            }
+        }
+        match answers.is_empty() {
+            true => Err(RpcError::Unavailable(last)),
+            false => Err(RpcError::Mismatch { method: method.to_string(), answers: answers.len() }),
+        }
+    }
+
+    /// Health of every provider, by chain id
+    pub fn snapshot(&self) -> BTreeMap<u64, Vec<ProviderHealth>> {
+        self.chains.lock().unwrap().clone()
+    }
+
+    async fn call_one(&self, chain_id: u64, url: &str, method: &str, params: &Value, now: u64) -> Result<Value, RpcError> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let started = Instant::now();
+        let response: Result<Value, String> = async {
+            self.client.post(url).json(&request).timeout(self.timeout)
+                .send().await.map_err(|e| e.to_string())?
+                .json().await.map_err(|e| e.to_string())
+        }.await;
+        let outcome = match response {
+            Err(e) => Err(RpcError::Unavailable(e)),
+            Ok(response) => match response.get("error") {
+                Some(error) if error["code"].as_i64() == Some(LIMIT_EXCEEDED) => Err(RpcError::Unavailable(error.to_string())),
+                Some(error) => Err(RpcError::Response(error.clone())),
+                None => Ok(response["result"].clone()),
+            },
+        };
+        match &outcome {
+            Err(RpcError::Unavailable(e)) => self.record(chain_id, url, Outcome::Failed(e.clone()), now),
+            _ => self.record(chain_id, url, Outcome::Ok { latency_ms: started.elapsed().as_secs_f64() * 1000.0 }, now),
+        }
+        outcome
+    }
+}
+
+/// The answer given by at least `needed` providers, if any
+fn majority(answers: &[(String, Value)], needed: usize) -> Option<Value> {
+    answers.iter()
+        .map(|(_, answer)| answer)
+        .find(|answer| answers.iter().filter(|(_, other)| other == *answer).count() >= needed)
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failover_order_and_majority() {
+        let pool = RpcPool::new(&RpcPoolConfig { cross_check: 2, failure_cooldown_secs: 30, ..Default::default() });
+        for url in ["https://primary", "https://backup", "https://third", "https://primary", ""] {
+            pool.add(137, url);
+        }
+        assert_eq!(pool.urls(137), vec!["https://primary", "https://backup", "https://third"]);
+        assert_eq!(pool.ranked(137, 0), pool.urls(137), "healthy providers keep their configured order");
+
+        // A failure sits the primary out for the cooldown, then it is tried last among the healthy
+        pool.record(137, "https://primary", Outcome::Failed("timeout".to_string()), 100);
+        assert_eq!(pool.ranked(137, 110), vec!["https://backup", "https://third", "https://primary"]);
+        pool.record(137, "https://third", Outcome::Outvoted, 110);
+        assert_eq!(pool.ranked(137, 129), vec!["https://backup", "https://third", "https://primary"]);
+        pool.record(137, "https://primary", Outcome::Ok { latency_ms: 40.0 }, 131);
+        assert_eq!(pool.ranked(137, 131), vec!["https://backup", "https://primary", "https://third"]);
+        let primary = &pool.snapshot()[&137][0];
+        assert_eq!((primary.calls, primary.failures, primary.down_until), (2, 1, 0));
+        assert!(pool.ranked(10, 0).is_empty());
+
+        let answer = |url: &str, balance: &str| (url.to_string(), json!(balance));
+        assert_eq!(majority(&[answer("a", "0x10")], 2), None);
+        assert_eq!(majority(&[answer("a", "0x10"), answer("b", "0x0f")], 2), None, "a lagging node blocks the read");
+        assert_eq!(majority(&[answer("a", "0x10"), answer("b", "0x0f"), answer("c", "0x0f")], 2), Some(json!("0x0f")));
+    }
+}
@@ -0,0 +1,168 @@
+//! Tiered polling scheduler
+//!
+//! Classifies markets into fast/medium/slow tiers by recent signal activity
+//! and liquidity, and decides which markets are due for a scan on each tick.
+
+use crate::config::PollingConfig;
+use crate::types::Market;
+use std::collections::HashMap;
+
+/// Market hotness tier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PollTier {
+    /// Recently active markets - polled every tick
+    Fast,
+    /// Liquid or occasionally active markets
+    Medium,
+    /// Quiet markets
+    Slow,
+}
+
+/// Per-market scheduling state
+#[derive(Debug, Clone)]
+struct MarketSchedule {
+    tier: PollTier,
+    last_polled: Option<u64>,
+    signal_times: Vec<u64>,
+    liquidity: f64,
+}
+
+/// Scheduler deciding which markets to scan on each tick
+#[derive(Debug)]
+pub struct PollScheduler {
+    config: PollingConfig,
+    markets: HashMap<String, MarketSchedule>,
+    last_rebalance: u64,
+}
+
+impl PollScheduler {
+    pub fn new(config: PollingConfig) -> Self {
+        Self {
+            config,
+            markets: HashMap::new(),
+            last_rebalance: 0,
+        }
+    }
+
+    /// Base loop interval (the fast tier interval)
+    pub fn tick_interval_secs(&self) -> u64 {
+        self.config.fast_interval_secs.max(1)
+    }
+
+    /// Poll interval for a tier
+    pub fn interval_for(&self, tier: PollTier) -> u64 {
+        match tier {
+            PollTier::Fast => self.config.fast_interval_secs,
+            PollTier::Medium => self.config.medium_interval_secs,
+            PollTier::Slow => self.config.slow_interval_secs,
+        }
+    }
+
+    /// Get tier for a market (unknown markets start in the medium tier)
+    pub fn tier_of(&self, market_id: &str) -> PollTier {
+        self.markets.get(market_id).map_or(PollTier::Medium, |m| m.tier)
+    }
+
+    /// Record that a signal fired on a market
+    pub fn record_signal(&mut self, market_id: &str, now: u64) {
+        let entry = self.markets.entry(market_id.to_string()).or_insert_with(|| MarketSchedule {
+            tier: PollTier::Medium,
+            last_polled: None,
+            signal_times: Vec::new(),
+            liquidity: 0.0,
+        });
+        entry.signal_times.push(now);
+        // Promote immediately so the next tick picks it up
+        entry.tier = PollTier::Fast;
+    }
+
+    /// Select markets whose tier interval has elapsed, and mark them polled.
+    ///
+    /// Also rebalances tiers when the rebalance interval has passed.
+    pub fn select_due<'a>(&mut self, markets: &'a [Market], now: u64) -> Vec<&'a Market> {
+        for m in markets {
+            let entry = self.markets.entry(m.id.clone()).or_insert_with(|| MarketSchedule {
+                tier: PollTier::Medium,
+                last_polled: None,
+                signal_times: Vec::new(),
+                liquidity: m.liquidity,
+            });
+            entry.liquidity = m.liquidity;
+        }
+
+        if now.saturating_sub(self.last_rebalance) >= self.config.rebalance_interval_secs {
+            self.rebalance(now);
+        }
+
+        let mut due = Vec::new();
+        for m in markets {
+            let interval = self.interval_for(self.tier_of(&m.id));
+            if let Some(entry) = self.markets.get_mut(&m.id) {
+                let is_due = entry.last_polled.is_none_or(|last| now.saturating_sub(last) >= interval);
+                if is_due {
+                    entry.last_polled = Some(now);
+                    due.push(m);
+                }
+            }
+        }
+        due
+    }
+
+    /// Reclassify all known markets by signal activity and liquidity
+    pub fn rebalance(&mut self, now: u64) {
+        let window_start = now.saturating_sub(self.config.signal_window_secs);
+        for entry in self.markets.values_mut() {
+            entry.signal_times.retain(|t| *t >= window_start);
+            let recent = entry.signal_times.len() as u32;
+
+            entry.tier = if recent >= self.config.hot_signal_count {
+                PollTier::Fast
+            } else if recent > 0 || entry.liquidity >= self.config.medium_liquidity {
+                PollTier::Medium
+            } else {
+                PollTier::Slow
+            };
+        }
+        self.last_rebalance = now;
+    }
+
+    /// Number of markets in each tier (fast, medium, slow)
+    pub fn tier_counts(&self) -> (usize, usize, usize) {
+        let count = |tier| self.markets.values().filter(|m| m.tier == tier).count();
+        (count(PollTier::Fast), count(PollTier::Medium), count(PollTier::Slow))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn market(id: &str) -> Market {
+        Market { id: id.to_string(), liquidity: 0.0, ..testing::market() }
+    }
+
+    #[test]
+    fn test_everything_due_on_first_tick() {
+        let mut scheduler = PollScheduler::new(PollingConfig::default());
+        assert_eq!(scheduler.select_due(&[market("hot"), market("quiet")], 1000).len(), 2);
+    }
+
+    #[test]
+    fn test_active_markets_polled_fast() {
+        let mut scheduler = PollScheduler::new(PollingConfig::default());
+        let markets = vec![market("hot"), market("quiet")];
+        scheduler.select_due(&markets, 1000);
+
+        scheduler.record_signal("hot", 1000);
+        scheduler.record_signal("hot", 1001);
+        scheduler.rebalance(1002);
+        assert_eq!(scheduler.tier_of("hot"), PollTier::Fast);
+        assert_eq!(scheduler.tier_of("quiet"), PollTier::Slow);
+
+        // One fast interval later only the hot market is due
+        let due = scheduler.select_due(&markets, 1000 + scheduler.interval_for(PollTier::Fast));
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].id, "hot");
+    }
+}
@@ -0,0 +1,176 @@
+//! Signal priority queue
+//!
+//! Signals detected at the start of a tick can be stale by the time they
+//! are executed. Queued signals are popped highest-edge first, carry a TTL,
+//! and are re-validated against freshly fetched books right before
+//! execution; signals whose edge has decayed below threshold are dropped.
+
+use crate::types::{ArbitrageSignal, OrderBook, Side};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+#[derive(Debug, Clone)]
+struct QueuedSignal {
+    signal: ArbitrageSignal,
+    expires_at_ms: u64,
+    /// Generation for this market; older generations are superseded
+    seq: u64,
+}
+
+impl PartialEq for QueuedSignal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueuedSignal {}
+
+impl PartialOrd for QueuedSignal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueuedSignal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Highest edge first, then oldest first
+        self.signal.edge.total_cmp(&other.signal.edge)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+/// Counters for queue outcomes
+#[derive(Debug, Clone, Default)]
+pub struct QueueStats {
+    pub expired: u64,
+    pub decayed: u64,
+    pub executed: u64,
+}
+
+/// Priority queue of signals with expiry and pre-execution re-validation
+#[derive(Debug)]
+pub struct SignalQueue {
+    heap: BinaryHeap<QueuedSignal>,
+    /// Latest generation per market
+    latest: HashMap<String, u64>,
+    next_seq: u64,
+    ttl_ms: u64,
+    min_edge: f64,
+    pub stats: QueueStats,
+}
+
+impl SignalQueue {
+    pub fn new(ttl_ms: u64, min_edge: f64) -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+            latest: HashMap::new(),
+            next_seq: 0,
+            ttl_ms,
+            min_edge,
+            stats: QueueStats::default(),
+        }
+    }
+
+    /// Enqueue a signal; a newer signal for the same market supersedes older ones
+    pub fn push(&mut self, signal: ArbitrageSignal, now_ms: u64) {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.latest.insert(signal.market_id.clone(), seq);
+        self.heap.push(QueuedSignal { signal, expires_at_ms: now_ms + self.ttl_ms, seq });
+    }
+
+    /// Pop the highest-edge live signal, discarding expired and superseded ones
+    pub fn pop_live(&mut self, now_ms: u64) -> Option<ArbitrageSignal> {
+        while let Some(queued) = self.heap.pop() {
+            if self.latest.get(&queued.signal.market_id) != Some(&queued.seq) {
+                continue;
+            }
+            self.latest.remove(&queued.signal.market_id);
+            if queued.expires_at_ms <= now_ms {
+                self.stats.expired += 1;
+                continue;
+            }
+            return Some(queued.signal);
+        }
+        None
+    }
+
+    /// Recompute the edge from fresh books (one per leg) at the executable
+    /// price for `size`. Returns the updated signal, or None if it decayed
+    /// below the minimum edge plus `haircut` (e.g. for the books' staleness).
+    pub fn revalidate(&mut self, signal: &ArbitrageSignal, books: &[OrderBook], size: f64, haircut: f64) -> Option<ArbitrageSignal> {
+        let prices: Option<Vec<f64>> = books.iter()
+            .map(|b| b.execution_price(size, signal.recommended_side))
+            .collect();
+        let edge = match (prices, signal.recommended_side) {
+            (Some(p), Side::Buy) if !p.is_empty() => 1.0 - p.iter().sum::<f64>(),
+            (Some(p), Side::Sell) if !p.is_empty() => p.iter().sum::<f64>() - 1.0,
+            _ => f64::NEG_INFINITY,
+        };
+        if edge < self.min_edge + haircut {
+            self.stats.decayed += 1;
+            return None;
+        }
+        self.stats.executed += 1;
+        Some(ArbitrageSignal { edge, spread: edge.abs(), ..signal.clone() })
+    }
+
+    /// Current wall-clock time in milliseconds
+    pub fn now_ms() -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::book;
+
+    fn signal(market_id: &str, edge: f64) -> ArbitrageSignal {
+        ArbitrageSignal {
+            market_id: market_id.to_string(),
+            spread: edge,
+            edge,
+            recommended_side: Side::Buy,
+            yes_price: 0.45,
+            no_price: 0.45,
+            edge_curve: Vec::new(),
+            quote_ages_ms: Vec::new(),
+        }
+    }
+
+    fn books(yes: f64, no: f64) -> [OrderBook; 2] {
+        [book("y", &[], &[(yes, 100.0)]), book("n", &[], &[(no, 100.0)])]
+    }
+
+    #[test]
+    fn test_highest_edge_first_and_expired_dropped() {
+        let mut queue = SignalQueue::new(1000, 0.02);
+        queue.push(signal("m1", 0.03), 0);
+        queue.push(signal("m2", 0.08), 0);
+        queue.push(signal("m3", 0.05), 500);
+        queue.push(signal("m1", 0.04), 600); // supersedes the first m1
+
+        assert_eq!(queue.pop_live(1200).unwrap().market_id, "m3"); // m2 expired at 1000
+        assert_eq!(queue.pop_live(1200).unwrap().market_id, "m1");
+        assert!(queue.pop_live(1200).is_none());
+        assert_eq!(queue.stats.expired, 1);
+    }
+
+    #[test]
+    fn test_revalidated_against_fresh_books() {
+        let mut queue = SignalQueue::new(1000, 0.02);
+        let fresh = queue.revalidate(&signal("m1", 0.05), &books(0.48, 0.49), 10.0, 0.0).unwrap();
+        assert!((fresh.edge - 0.03).abs() < 1e-9);
+        assert!(queue.revalidate(&signal("m1", 0.05), &books(0.49, 0.50), 10.0, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_stale_books_need_the_haircut() {
+        let mut queue = SignalQueue::new(1000, 0.02);
+        assert!(queue.revalidate(&signal("m1", 0.05), &books(0.48, 0.49), 10.0, 0.02).is_none());
+    }
+}
@@ -0,0 +1,400 @@
+//! Signing Service Module
+//!
+//! `Signer` abstracts where signatures come from, so execution code never
+//! touches raw keys and private keys never live in config.toml.
+//!
+//! Implementations:
+//! - `LocalKeySigner`: raw keys read from environment variables
+//! - `KeystoreSigner`: Ethereum V3 encrypted keystore (scrypt / pbkdf2 + aes-128-ctr)
+//! - `RemoteSigner`: HTTP signing service or hardware wallet bridge
+
+#![allow(dead_code)]
+
+use crate::config::SignerConfig;
+use async_trait::async_trait;
+use serde::Deserialize;
+use sha3::{Digest, Keccak256};
+use solana_sdk::signature::{Keypair, Signer as SolanaSigner};
+
+/// Signature source used by execution and on-chain modules
+#[async_trait]
+pub trait Signer: Send + Sync {
+    /// Human readable signer kind (for logs)
+    fn kind(&self) -> &str;
+
+    /// EVM address (0x-prefixed) if this signer holds an EVM key
+    fn evm_address(&self) -> Option<String>;
+
+    /// Solana public key (base58) if this signer holds a Solana key
+    fn solana_pubkey(&self) -> Option<String>;
+
+    /// Sign EIP-712 typed data given its domain separator and struct hash.
+    /// Returns a 65-byte `r || s || v` signature.
+    async fn sign_typed_data(&self, domain_separator: [u8; 32], struct_hash: [u8; 32]) -> Result<Vec<u8>, SignerError>;
+
+    /// Sign a transaction signing hash (keccak256 of the unsigned RLP payload).
+    /// Returns a 65-byte `r || s || v` signature.
+    async fn sign_transaction(&self, tx_hash: [u8; 32]) -> Result<Vec<u8>, SignerError>;
+
+    /// Sign a serialized Solana message. Returns a 64-byte ed25519 signature.
+    async fn sign_solana_message(&self, message: &[u8]) -> Result<Vec<u8>, SignerError>;
+}
+
+/// EIP-712 digest: keccak256(0x19 0x01 || domainSeparator || structHash)
+pub fn eip712_digest(domain_separator: [u8; 32], struct_hash: [u8; 32]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update([0x19, 0x01]);
+    hasher.update(domain_separator);
+    hasher.update(struct_hash);
+    hasher.finalize().into()
+}
+
+/// Signer holding keys in process memory
+pub struct LocalKeySigner {
+    evm_key: Option<libsecp256k1::SecretKey>,
+    solana_key: Option<Keypair>,
+    kind: &'static str,
+}
+
+impl LocalKeySigner {
+    /// Build from a hex EVM key and/or a base58 Solana keypair
+    pub fn new(evm_key_hex: Option<&str>, solana_keypair_b58: Option<&str>) -> Result<Self, SignerError> {
+        let evm_key = evm_key_hex.map(parse_evm_key).transpose()?;
+        let solana_key = solana_keypair_b58
+            .map(|s| {
+                let bytes = solana_sdk::bs58::decode(s.trim()).into_vec()
+                    .map_err(|e| SignerError::InvalidKey(format!("solana keypair: {}", e)))?;
+                Keypair::from_bytes(&bytes).map_err(|e| SignerError::InvalidKey(format!("solana keypair: {}", e)))
+            })
+            .transpose()?;
+        Ok(Self { evm_key, solana_key, kind: "local" })
+    }
+
+    /// Build from environment variables (names come from config, values never do)
+    pub fn from_env(evm_key_env: Option<&str>, solana_key_env: Option<&str>) -> Result<Self, SignerError> {
+        let evm = evm_key_env.map(read_env).transpose()?;
+        let sol = solana_key_env.map(read_env).transpose()?;
+        Self::new(evm.as_deref(), sol.as_deref())
+    }
+
+    fn sign_evm_hash(&self, hash: [u8; 32]) -> Result<Vec<u8>, SignerError> {
+        let key = self.evm_key.as_ref().ok_or(SignerError::Unsupported("no EVM key loaded"))?;
+        let (sig, recovery_id) = libsecp256k1::sign(&libsecp256k1::Message::parse(&hash), key);
+        let mut out = sig.serialize().to_vec();
+        out.push(27 + recovery_id.serialize());
+        Ok(out)
+    }
+}
+
+#[async_trait]
+impl Signer for LocalKeySigner {
+    fn kind(&self) -> &str {
+        self.kind
+    }
+
+    fn evm_address(&self) -> Option<String> {
+        self.evm_key.as_ref().map(evm_address)
+    }
+
+    fn solana_pubkey(&self) -> Option<String> {
+        self.solana_key.as_ref().map(|k| k.pubkey().to_string())
+    }
+
+    async fn sign_typed_data(&self, domain_separator: [u8; 32], struct_hash: [u8; 32]) -> Result<Vec<u8>, SignerError> {
+        self.sign_evm_hash(eip712_digest(domain_separator, struct_hash))
+    }
+
+    async fn sign_transaction(&self, tx_hash: [u8; 32]) -> Result<Vec<u8>, SignerError> {
+        self.sign_evm_hash(tx_hash)
+    }
+
+    async fn sign_solana_message(&self, message: &[u8]) -> Result<Vec<u8>, SignerError> {
+        let key = self.solana_key.as_ref().ok_or(SignerError::Unsupported("no Solana key loaded"))?;
+        Ok(key.sign_message(message).as_ref().to_vec())
+    }
+}
+
+/// Ethereum V3 keystore file (subset of fields we need)
+#[derive(Debug, Deserialize)]
+struct KeystoreFile {
+    crypto: KeystoreCrypto,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeystoreCrypto {
+    cipher: String,
+    cipherparams: CipherParams,
+    ciphertext: String,
+    kdf: String,
+    kdfparams: serde_json::Value,
+    mac: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CipherParams {
+    iv: String,
+}
+
+/// Signer backed by an encrypted V3 keystore, decrypted once at startup
+pub struct KeystoreSigner;
+
+impl KeystoreSigner {
+    /// Decrypt a V3 keystore file with the given password
+    pub fn open(path: &str, password: &str, solana_keypair_path: Option<&str>) -> Result<LocalKeySigner, SignerError> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| SignerError::Keystore(format!("read {}: {}", path, e)))?;
+        let secret = decrypt_keystore(&json, password)?;
+        let evm_key = libsecp256k1::SecretKey::parse_slice(&secret)
+            .map_err(|e| SignerError::InvalidKey(format!("{:?}", e)))?;
+        let solana_key = solana_keypair_path
+            .map(|p| solana_sdk::signature::read_keypair_file(p)
+                .map_err(|e| SignerError::Keystore(format!("read {}: {}", p, e))))
+            .transpose()?;
+        Ok(LocalKeySigner { evm_key: Some(evm_key), solana_key, kind: "keystore" })
+    }
+}
+
+/// Decrypt the private key from a V3 keystore JSON document
+pub fn decrypt_keystore(json: &str, password: &str) -> Result<Vec<u8>, SignerError> {
+    use ctr::cipher::{NewCipher, StreamCipher};
+
+    let file: KeystoreFile = serde_json::from_str(json)
+        .map_err(|e| SignerError::Keystore(format!("parse: {}", e)))?;
+    let c = file.crypto;
+    if c.cipher != "aes-128-ctr" {
+        return Err(SignerError::Keystore(format!("unsupported cipher {}", c.cipher)));
+    }
+
+    let p = &c.kdfparams;
+    let salt = decode_hex(p["salt"].as_str().unwrap_or(""))?;
+    let dklen = p["dklen"].as_u64().unwrap_or(32) as usize;
+    if dklen < 32 {
+        return Err(SignerError::Keystore("dklen must be >= 32".to_string()));
+    }
+    let mut derived = vec![0u8; dklen];
+    match c.kdf.as_str() {
+        "scrypt" => {
+            let n = p["n"].as_u64().unwrap_or(0);
+            if !n.is_power_of_two() {
+                return Err(SignerError::Keystore("scrypt n must be a power of two".to_string()));
+            }
+            let params = scrypt::Params::new(
+                n.trailing_zeros() as u8,
+                p["r"].as_u64().unwrap_or(8) as u32,
+                p["p"].as_u64().unwrap_or(1) as u32,
+            ).map_err(|e| SignerError::Keystore(format!("scrypt params: {}", e)))?;
+            scrypt::scrypt(password.as_bytes(), &salt, &params, &mut derived)
+                .map_err(|e| SignerError::Keystore(format!("scrypt: {}", e)))?;
+        }
+        "pbkdf2" => {
+            let rounds = p["c"].as_u64().unwrap_or(0) as u32;
+            pbkdf2::pbkdf2::<hmac::Hmac<sha2::Sha256>>(password.as_bytes(), &salt, rounds, &mut derived);
+        }
+        other => return Err(SignerError::Keystore(format!("unsupported kdf {}", other))),
+    }
+
+    let ciphertext = decode_hex(&c.ciphertext)?;
+    let mut mac = Keccak256::new();
+    mac.update(&derived[16..32]);
+    mac.update(&ciphertext);
+    if mac.finalize().as_slice() != decode_hex(&c.mac)?.as_slice() {
+        return Err(SignerError::Keystore("MAC mismatch (wrong password?)".to_string()));
+    }
+
+    let iv = decode_hex(&c.cipherparams.iv)?;
+    if iv.len() != 16 {
+        return Err(SignerError::Keystore("iv must be 16 bytes".to_string()));
+    }
+    let mut secret = ciphertext;
+    let mut cipher = ctr::Ctr128BE::<aes::Aes128>::new(derived[..16].into(), iv.as_slice().into());
+    cipher.apply_keystream(&mut secret);
+    Ok(secret)
+}
+
+/// Signer delegating to an HTTP signing service / hardware wallet bridge
+///
+/// Protocol: `POST {url}/sign` with `{"kind": ..., "payload": "<hex>"}`,
+/// responding `{"signature": "<hex>"}`. Addresses come from `GET {url}/address`
+/// at startup.
+pub struct RemoteSigner {
+    url: String,
+    auth_token: Option<String>,
+    client: reqwest::Client,
+    evm_address: Option<String>,
+    solana_pubkey: Option<String>,
+}
+
+impl RemoteSigner {
+    pub async fn connect(url: &str, auth_token: Option<String>) -> Result<Self, SignerError> {
+        let mut signer = Self {
+            url: url.trim_end_matches('/').to_string(),
+            auth_token,
+            client: reqwest::Client::new(),
+            evm_address: None,
+            solana_pubkey: None,
+        };
+        let json: serde_json::Value = signer.request(signer.client.get(format!("{}/address", signer.url))).await?;
+        signer.evm_address = json["evm"].as_str().map(|s| s.to_string());
+        signer.solana_pubkey = json["solana"].as_str().map(|s| s.to_string());
+        Ok(signer)
+    }
+
+    async fn request(&self, req: reqwest::RequestBuilder) -> Result<serde_json::Value, SignerError> {
+        let req = match &self.auth_token {
+            Some(token) => req.bearer_auth(token),
+            None => req,
+        };
+        let resp = req
+            .timeout(std::time::Duration::from_secs(10))
+            .send()
+            .await
+            .map_err(|e| SignerError::Remote(e.to_string()))?;
+        if !resp.status().is_success() {
+            return Err(SignerError::Remote(format!("signer returned {}", resp.status())));
+        }
+        resp.json().await.map_err(|e| SignerError::Remote(e.to_string()))
+    }
+
+    async fn sign_remote(&self, kind: &str, payload: &[u8]) -> Result<Vec<u8>, SignerError> {
+        let body = serde_json::json!({ "kind": kind, "payload": hex::encode(payload) });
+        let json = self.request(self.client.post(format!("{}/sign", self.url)).json(&body)).await?;
+        let sig = json["signature"].as_str()
+            .ok_or_else(|| SignerError::Remote("missing signature in response".to_string()))?;
+        decode_hex(sig)
+    }
+}
+
+#[async_trait]
+impl Signer for RemoteSigner {
+    fn kind(&self) -> &str {
+        "remote"
+    }
+
+    fn evm_address(&self) -> Option<String> {
+        self.evm_address.clone()
+    }
+
+    fn solana_pubkey(&self) -> Option<String> {
+        self.solana_pubkey.clone()
+    }
+
+    async fn sign_typed_data(&self, domain_separator: [u8; 32], struct_hash: [u8; 32]) -> Result<Vec<u8>, SignerError> {
+        self.sign_remote("typed_data", &eip712_digest(domain_separator, struct_hash)).await
+    }
+
+    async fn sign_transaction(&self, tx_hash: [u8; 32]) -> Result<Vec<u8>, SignerError> {
+        self.sign_remote("transaction", &tx_hash).await
+    }
+
+    async fn sign_solana_message(&self, message: &[u8]) -> Result<Vec<u8>, SignerError> {
+        self.sign_remote("solana_message", message).await
+    }
+}
+
+/// Build the configured signer
+pub async fn from_config(config: &SignerConfig) -> Result<Box<dyn Signer>, SignerError> {
+    match config.kind.as_str() {
+        "local" => Ok(Box::new(LocalKeySigner::from_env(
+            config.evm_key_env.as_deref(),
+            config.solana_key_env.as_deref(),
+        )?)),
+        "keystore" => {
+            let path = config.keystore_path.as_deref()
+                .ok_or(SignerError::Unsupported("keystore_path required for keystore signer"))?;
+            let password = read_env(config.password_env.as_deref().unwrap_or("KEYSTORE_PASSWORD"))?;
+            Ok(Box::new(KeystoreSigner::open(path, &password, config.solana_keypair_path.as_deref())?))
+        }
+        "remote" => {
+            let url = config.remote_url.as_deref()
+                .ok_or(SignerError::Unsupported("remote_url required for remote signer"))?;
+            let token = config.auth_token_env.as_deref().map(read_env).transpose()?;
+            Ok(Box::new(RemoteSigner::connect(url, token).await?))
+        }
+        other => Err(SignerError::InvalidConfig(format!("unknown signer kind '{}'", other))),
+    }
+}
+
+fn read_env(name: &str) -> Result<String, SignerError> {
+    std::env::var(name).map_err(|_| SignerError::MissingEnv(name.to_string()))
+}
+
+fn parse_evm_key(hex_key: &str) -> Result<libsecp256k1::SecretKey, SignerError> {
+    let bytes = decode_hex(hex_key)?;
+    libsecp256k1::SecretKey::parse_slice(&bytes).map_err(|e| SignerError::InvalidKey(format!("{:?}", e)))
+}
+
+fn evm_address(key: &libsecp256k1::SecretKey) -> String {
+    public_address(&libsecp256k1::PublicKey::from_secret_key(key))
+}
+
+fn public_address(public: &libsecp256k1::PublicKey) -> String {
+    let hash = Keccak256::digest(&public.serialize()[1..]);
+    format!("0x{}", hex::encode(&hash[12..]))
+}
+
+/// EVM address that produced a 65-byte `r || s || v` signature over `hash`
+pub fn recover_evm_address(hash: [u8; 32], signature: &[u8]) -> Result<String, SignerError> {
+    if signature.len() != 65 {
+        return Err(SignerError::InvalidKey(format!("signature is {} bytes, expected 65", signature.len())));
+    }
+    let invalid = |e: libsecp256k1::Error| SignerError::InvalidKey(format!("{:?}", e));
+    let sig = libsecp256k1::Signature::parse_standard_slice(&signature[..64]).map_err(invalid)?;
+    let v = signature[64];
+    let recovery_id = libsecp256k1::RecoveryId::parse(if v >= 27 { v - 27 } else { v }).map_err(invalid)?;
+    let public = libsecp256k1::recover(&libsecp256k1::Message::parse(&hash), &sig, &recovery_id).map_err(invalid)?;
+    Ok(public_address(&public))
+}
+
+fn decode_hex(s: &str) -> Result<Vec<u8>, SignerError> {
+    hex::decode(s.trim().trim_start_matches("0x")).map_err(|e| SignerError::InvalidKey(format!("hex: {}", e)))
+}
+
+/// Signer errors
+#[derive(Debug, Clone)]
+pub enum SignerError {
+    MissingEnv(String),
+    InvalidKey(String),
+    InvalidConfig(String),
+    Keystore(String),
+    Remote(String),
+    Unsupported(&'static str),
+}
+
+impl std::fmt::Display for SignerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingEnv(name) => write!(f, "Environment variable {} not set", name),
+            Self::InvalidKey(msg) => write!(f, "Invalid key: {}", msg),
+            Self::InvalidConfig(msg) => write!(f, "Invalid signer config: {}", msg),
+            Self::Keystore(msg) => write!(f, "Keystore error: {}", msg),
+            Self::Remote(msg) => write!(f, "Remote signer error: {}", msg),
+            Self::Unsupported(msg) => write!(f, "Unsupported: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SignerError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Well-known test key (Anvil/Hardhat account #0)
+    const TEST_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    #[tokio::test]
+    async fn test_local_signer() {
+        let signer = LocalKeySigner::new(Some(TEST_KEY), None).unwrap();
+        assert_eq!(
+            signer.evm_address().unwrap(),
+            "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266"
+        );
+
+        let sig = signer.sign_transaction([7u8; 32]).await.unwrap();
+        assert_eq!(sig.len(), 65);
+        assert!(sig[64] == 27 || sig[64] == 28);
+
+        // No Solana key loaded
+        assert!(signer.sign_solana_message(b"hello").await.is_err());
+    }
+}
@@ -5,15 +5,18 @@ use crate::arb::ArbitrageDetector;
 use crate::execution::ExecutionEngine;
 use crate::fees::FeeModel;
 use crate::latency::LatencyModel;
+use crate::competition::{CompetitionStats, CompetitorModel};
+use crate::config::CompetitionConfig;
 // use crate::types::Side; // Unused import
 
 #[allow(dead_code)]
-pub async fn run_monte_carlo(iterations: usize) {
+pub async fn run_monte_carlo(iterations: usize, competition: &CompetitionConfig) {
     println!("🎲 Starting Monte Carlo Simulation ({} runs)...", iterations);
 
     let mut total_pnl = 0.0;
     let mut wins = 0;
     let mut losses = 0;
+    let mut race_stats = CompetitionStats::default();
 
     for i in 0..iterations {
         // Setup fresh environment for each run
@@ -30,6 +33,9 @@ pub async fn run_monte_carlo(iterations: usize) {
         let execution_engine = ExecutionEngine::new(fee_model, latency_model);
 
         let mut engine = TradingEngine::new(wallet, market_provider, detector, execution_engine);
+        if competition.enabled {
+            engine = engine.with_competitor(CompetitorModel::new(competition));
+        }
         
         // Run for 10 ticks
         engine.run(10).await;
@@ -37,6 +43,12 @@ pub async fn run_monte_carlo(iterations: usize) {
         let pnl = engine.wallet.spent_today; // simplified "pnl" as "money deployed" for this demo
                                              // Real PnL requires closing positions which we haven't implemented logic for
         
+        if let Some(competitor) = engine.competitor() {
+            race_stats.opportunities += competitor.stats.opportunities;
+            race_stats.contested += competitor.stats.contested;
+            race_stats.lost += competitor.stats.lost;
+        }
+
         total_pnl += pnl;
         if pnl > 0.0 { wins += 1; } else { losses += 1; }
         
@@ -49,4 +61,9 @@ pub async fn run_monte_carlo(iterations: usize) {
     println!("   Total Runs: {}", iterations);
     println!("   Total Volume: ${:.2}", total_pnl);
     println!("   Active runs: {} | Inactive runs: {}", wins, losses);
+    if competition.enabled {
+        println!("   Competition: {} edges, {} contested, {} lost | Capture rate: {:.1}%",
+            race_stats.opportunities, race_stats.contested, race_stats.lost,
+            race_stats.capture_rate() * 100.0);
+    }
 }
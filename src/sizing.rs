@@ -0,0 +1,132 @@
+// Position sizing
+// Base size scaled by regime and risk, capped, then fitted to free capital and the day's allowance
+
+use crate::risk::RiskManager;
+use crate::types::EdgePoint;
+
+/// Smallest order worth sending (shares per leg, the unit every size here is in)
+const MIN_ORDER_SHARES: f64 = 1.0;
+
+/// Bisection steps when fitting the allowance
+const ALLOWANCE_FIT_STEPS: usize = 30;
+
+#[derive(Debug, Clone)]
+pub struct PositionSizer {
+    pub base_size: f64,
+    pub max_position_value: f64,
+}
+
+impl PositionSizer {
+    pub fn new(base_size: f64, max_position_value: f64) -> Self {
+        Self { base_size, max_position_value }
+    }
+
+    /// Size per leg before risk scaling (what compliance limits are checked against)
+    pub fn unscaled(&self, regime_multiplier: f64) -> f64 {
+        (self.base_size * regime_multiplier).min(self.max_position_value)
+    }
+
+    /// Size per leg, or None if risk scaling shrank it below the minimum
+    pub fn size(&self, regime_multiplier: f64, risk: &RiskManager) -> Option<f64> {
+        let size = (self.base_size * regime_multiplier * risk.size_scale()).min(self.max_position_value);
+        (size >= MIN_ORDER_SHARES).then_some(size)
+    }
+
+    /// `size` per leg shrunk so `legs` legs fit in `free_capital`, or None if that is below the minimum
+    pub fn fit(&self, size: f64, legs: usize, free_capital: f64) -> Option<f64> {
+        let size = size.min(free_capital / legs.max(1) as f64);
+        (size >= MIN_ORDER_SHARES).then_some(size)
+    }
+
+    /// Largest size up to `size` per leg whose full set costs at most `allowance`, or None if
+    /// that is below the minimum. `set_cost(size)` prices one share of every leg at that size
+    /// (fees included, None if the books cannot fill it); it only grows with size.
+    pub fn fit_allowance(&self, size: f64, allowance: f64, set_cost: impl Fn(f64) -> Option<f64>) -> Option<f64> {
+        let fits = |size: f64| set_cost(size).is_some_and(|cost| cost * size <= allowance + 1e-9);
+        let fitted = if fits(size) {
+            size
+        } else {
+            // Books too thin for the size: the largest size they can fill bounds the search
+            let fillable = match set_cost(size) {
+                Some(_) => size,
+                None => bisect(0.0, size, |s| set_cost(s).is_some()),
+            };
+            // Shrinking to the allowance at that size's set cost always fits; bisect up from there
+            let lo = (allowance / set_cost(fillable)?).min(fillable);
+            bisect(lo, fillable, fits)
+        };
+        (fitted >= MIN_ORDER_SHARES && fits(fitted)).then_some(fitted)
+    }
+
+    /// Most profitable size on a signal's edge curve, at most `cap` (the size from `size`)
+    pub fn best_on_curve(&self, curve: &[EdgePoint], cap: f64) -> Option<f64> {
+        curve.iter()
+            .filter(|p| p.size >= MIN_ORDER_SHARES && p.size <= cap + 1e-9 && p.edge > 0.0)
+            .max_by(|a, b| a.profit.total_cmp(&b.profit))
+            .map(|p| p.size)
+    }
+}
+
+/// Largest size in [lo, hi] passing `ok`, given `ok(lo)` and that `ok` only fails above some size
+fn bisect(mut lo: f64, mut hi: f64, ok: impl Fn(f64) -> bool) -> f64 {
+    if ok(hi) {
+        return hi;
+    }
+    for _ in 0..ALLOWANCE_FIT_STEPS {
+        let mid = (lo + hi) / 2.0;
+        if ok(mid) { lo = mid } else { hi = mid }
+    }
+    lo
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::risk::RiskConfig;
+
+    #[test]
+    fn test_size_combines_regime_and_risk() {
+        let mut risk = RiskManager::new(RiskConfig { max_drawdown: 0.2, ..Default::default() }, 100.0);
+        let sizer = PositionSizer::new(10.0, 8.0);
+        assert_eq!(sizer.size(1.0, &risk), Some(8.0));
+        assert_eq!(sizer.size(0.5, &risk), Some(5.0));
+
+        risk.record_trade(-15.0); // halfway into the drawdown ramp
+        assert!((sizer.size(0.5, &risk).unwrap() - 2.5).abs() < 1e-9);
+        assert_eq!(sizer.size(0.1, &risk), None);
+
+        let point = |size: f64, edge: f64| EdgePoint { size, edge, profit: size * edge };
+        let curve = [point(1.0, 0.10), point(4.0, 0.08), point(16.0, 0.015), point(64.0, -0.01)];
+        assert_eq!(sizer.best_on_curve(&curve, 100.0), Some(4.0));
+        assert_eq!(sizer.best_on_curve(&curve, 2.0), Some(1.0));
+        assert_eq!(sizer.best_on_curve(&curve[3..], 100.0), None);
+
+        // Two legs sharing $9 of free capital
+        assert_eq!(sizer.fit(8.0, 2, 9.0), Some(4.5));
+        assert_eq!(sizer.fit(8.0, 2, 1.5), None);
+    }
+
+    #[test]
+    fn test_fit_allowance_spends_the_rest() {
+        let sizer = PositionSizer::new(10.0, 100.0);
+        // A set costs 0.90 plus 0.01 per share of size (the books thin out)
+        let set_cost = |size: f64| (size <= 50.0).then_some(0.90 + 0.01 * size);
+        assert_eq!(sizer.fit_allowance(10.0, 20.0, set_cost), Some(10.0), "fits as it is");
+        let fitted = sizer.fit_allowance(10.0, 5.0, set_cost).unwrap();
+        assert!((set_cost(fitted).unwrap() * fitted - 5.0).abs() < 1e-6, "spends exactly what is left");
+        assert!(fitted < 10.0);
+        assert_eq!(sizer.fit_allowance(10.0, 0.5, set_cost), None, "below the minimum size");
+    }
+
+    #[test]
+    fn test_fit_allowance_searches_below_what_the_books_fill() {
+        let sizer = PositionSizer::new(10.0, 100.0);
+        // The books hold 50 per leg: 60 cannot be priced, smaller sizes can
+        let set_cost = |size: f64| (size <= 50.0).then_some(0.90 + 0.01 * size);
+        let fitted = sizer.fit_allowance(60.0, 5.0, set_cost).unwrap();
+        assert!((set_cost(fitted).unwrap() * fitted - 5.0).abs() < 1e-6);
+        let capped = sizer.fit_allowance(60.0, 100.0, set_cost).unwrap();
+        assert!((capped - 50.0).abs() < 1e-6, "as much as the books fill, {}", capped);
+        assert_eq!(sizer.fit_allowance(60.0, 100.0, |_| None), None);
+    }
+}
@@ -0,0 +1,121 @@
+//! Limit price ceilings for bundle legs
+//!
+//! A bundle pays $1 per set at settlement, so what its legs may cost per
+//! share is bounded by the signal's economics: $1 less the floor edge and
+//! the gas per set, before taker fees. Each leg is submitted with a limit
+//! price at its planned fill plus an even share of the slack that budget
+//! leaves; as legs fill, their actual prices replace the plan and the slack
+//! left is shared among the legs still to go. A leg never fills above its
+//! ceiling, so however the books move between detection and submission a
+//! bundle whose legs all fill keeps at least the floor edge.
+
+use crate::types::{OrderBook, Side};
+
+#[derive(Debug, Clone)]
+pub struct LimitCeilings {
+    /// What one share of every leg may cost in total, before fees
+    budget: f64,
+    /// Planned fill price per leg on the books the bundle was sized on
+    planned: Vec<f64>,
+    /// Actual average fill price of the legs filled so far
+    filled: Vec<Option<f64>>,
+}
+
+impl LimitCeilings {
+    /// Ceilings for buying `size` of every leg on `books`. None if the books cannot
+    /// fill the size, or the set already costs more than the budget (no edge left).
+    pub fn for_bundle(books: &[OrderBook], size: f64, fee_rate: f64, gas_per_leg: f64, min_edge: f64) -> Option<Self> {
+        let planned = books.iter().map(|b| b.execution_price(size, Side::Buy)).collect::<Option<Vec<f64>>>()?;
+        let gas_per_set = if size > 0.0 { gas_per_leg * books.len() as f64 / size } else { f64::INFINITY };
+        let budget = (1.0 - min_edge - gas_per_set) / (1.0 + fee_rate);
+        if planned.iter().sum::<f64>() > budget {
+            return None;
+        }
+        let legs = planned.len();
+        Some(Self { budget, planned, filled: vec![None; legs] })
+    }
+
+    /// Per-share budget not yet taken by fills or planned for the open legs
+    pub fn slack(&self) -> f64 {
+        let committed: f64 = self.planned.iter().zip(&self.filled).map(|(planned, filled)| filled.unwrap_or(*planned)).sum();
+        self.budget - committed
+    }
+
+    /// Highest price `leg` may fill at: its plan plus an even share of the slack
+    pub fn ceiling(&self, leg: usize) -> f64 {
+        let open = self.filled.iter().filter(|f| f.is_none()).count().max(1);
+        let planned = self.planned.get(leg).copied().unwrap_or(0.0);
+        (planned + self.slack().max(0.0) / open as f64).min(1.0)
+    }
+
+    /// `leg` filled at an average of `price`
+    pub fn record_fill(&mut self, leg: usize, price: f64) {
+        if let Some(filled) = self.filled.get_mut(leg) {
+            *filled = Some(price);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::ExecutionEngine;
+    use crate::fees::FeeModel;
+    use crate::latency::LatencyModel;
+    use crate::testing::book;
+    use crate::wallet::Wallet;
+
+    const FEE_RATE: f64 = 0.01;
+    const SIZE: f64 = 10.0;
+
+    /// Detection: YES 0.45, NO 0.48 - a set costs 0.9393 with fees
+    fn detected() -> [OrderBook; 2] {
+        [book("y", &[], &[(0.45, 100.0)]), book("n", &[], &[(0.48, 100.0)])]
+    }
+
+    #[test]
+    fn test_ceilings_hand_out_all_the_slack() {
+        let ceilings = LimitCeilings::for_bundle(&detected(), SIZE, FEE_RATE, 0.0, 0.0).unwrap();
+        assert!((ceilings.ceiling(0) + ceilings.ceiling(1) - 1.0 / 1.01).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_shifted_books_never_fill_below_zero_edge() {
+        let engine = ExecutionEngine::new(FeeModel { maker_fee_bps: 0, taker_fee_bps: 100 }, LatencyModel::new(0, 0.0));
+        // Submission: books moved against us in every way between detection and the legs
+        let shifted = [
+            [book("y", &[], &[(0.45, 100.0)]), book("n", &[], &[(0.60, 100.0)])],
+            [book("y", &[], &[(0.50, 4.0), (0.53, 100.0)]), book("n", &[], &[(0.48, 100.0)])],
+            [book("y", &[], &[(0.47, 100.0)]), book("n", &[], &[(0.51, 100.0)])],
+            [book("y", &[], &[(0.53, 100.0)]), book("n", &[], &[(0.44, 100.0)])],
+        ];
+        for books in &shifted {
+            let mut ceilings = LimitCeilings::for_bundle(&detected(), SIZE, FEE_RATE, 0.0, 0.0).unwrap();
+            let mut wallet = Wallet::new(1_000.0);
+            let mut cost = 0.0;
+            let mut filled = Vec::new();
+            for (leg, book) in books.iter().enumerate() {
+                if let Ok(result) = engine.execute_capped(book, SIZE, Side::Buy, &mut wallet, Some(ceilings.ceiling(leg)), f64::INFINITY) {
+                    assert!(result.execution_price <= ceilings.ceiling(leg) + 1e-12);
+                    ceilings.record_fill(leg, result.execution_price);
+                    cost += result.total_cost / result.filed_size;
+                    filled.push(result.filed_size);
+                }
+            }
+            if filled.len() == books.len() {
+                assert!(1.0 - cost >= -1e-9, "a set of full legs costs {:.4} per share", cost);
+            }
+        }
+    }
+
+    #[test]
+    fn test_no_ceilings_without_edge() {
+        let books = [book("y", &[], &[(0.52, 100.0)]), book("n", &[], &[(0.49, 100.0)])];
+        assert!(LimitCeilings::for_bundle(&books, SIZE, FEE_RATE, 0.0, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_gas_eats_into_the_budget() {
+        assert!(LimitCeilings::for_bundle(&detected(), SIZE, FEE_RATE, 0.35, 0.0).is_none());
+    }
+}
@@ -1,7 +1,16 @@
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::system_transaction;
 use std::error::Error;
+use std::time::{Duration, Instant};
 
+/// Public devnet RPC
+pub const DEVNET_URL: &str = "https://api.devnet.solana.com";
+
+/// How long to wait for an airdrop or transaction to confirm
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
 
 pub struct SolanaManager {
     client: RpcClient,
@@ -10,11 +19,14 @@ pub struct SolanaManager {
 impl SolanaManager {
     /// Connect to Solana Devnet
     pub fn new() -> Self {
-        // Use standard Devnet URL
-        let url = "https://api.devnet.solana.com".to_string();
+        Self::with_url(DEVNET_URL)
+    }
+
+    /// Connect to another cluster (e.g. a local test validator)
+    pub fn with_url(url: &str) -> Self {
         // Commitment: confirmed is usually good balance of speed/safety for bots
-        let client = RpcClient::new_with_commitment(url, CommitmentConfig::confirmed());
-        
+        let client = RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed());
+
         Self { client }
     }
 
@@ -34,4 +46,66 @@ impl SolanaManager {
         // For now, let's just return a placeholder or 0.0 if not funded.
         Ok(0.0)
     }
+
+    /// Balance of an account in lamports
+    pub fn balance(&self, pubkey: &Pubkey) -> Result<u64, Box<dyn Error>> {
+        Ok(self.client.get_balance(pubkey)?)
+    }
+
+    /// Request a devnet airdrop and wait until it confirms
+    pub fn request_airdrop(&self, pubkey: &Pubkey, lamports: u64) -> Result<Signature, Box<dyn Error>> {
+        let signature = self.client.request_airdrop(pubkey, lamports)?;
+        self.wait_for_confirmation(&signature)?;
+        Ok(signature)
+    }
+
+    /// Transfer lamports, returning once the transaction is confirmed
+    pub fn transfer(&self, from: &Keypair, to: &Pubkey, lamports: u64) -> Result<Signature, Box<dyn Error>> {
+        let blockhash = self.client.get_latest_blockhash()?;
+        let tx = system_transaction::transfer(from, to, lamports, blockhash);
+        Ok(self.client.send_and_confirm_transaction(&tx)?)
+    }
+
+    fn wait_for_confirmation(&self, signature: &Signature) -> Result<(), Box<dyn Error>> {
+        let started = Instant::now();
+        while !self.client.confirm_transaction(signature)? {
+            if started.elapsed() > CONFIRM_TIMEOUT {
+                return Err(format!("{} not confirmed after {}s", signature, CONFIRM_TIMEOUT.as_secs()).into());
+            }
+            std::thread::sleep(Duration::from_millis(500));
+        }
+        Ok(())
+    }
+}
+
+/// Devnet test harness: a throwaway keypair funded from the faucet
+///
+/// Drives the Solana path end to end (airdrop, transfer, balance checks)
+/// against a real cluster; used by the `solana-devnet` integration test.
+pub struct DevnetHarness {
+    pub manager: SolanaManager,
+    /// Fresh keypair, funded on creation
+    pub payer: Keypair,
+}
+
+impl DevnetHarness {
+    /// Create a throwaway keypair and airdrop `lamports` to it
+    pub fn funded(manager: SolanaManager, lamports: u64) -> Result<Self, Box<dyn Error>> {
+        let payer = Keypair::new();
+        manager.request_airdrop(&payer.pubkey(), lamports)?;
+        Ok(Self { manager, payer })
+    }
+
+    /// Send `lamports` from the payer to a new throwaway account; returns its pubkey
+    pub fn transfer_to_new_account(&self, lamports: u64) -> Result<Pubkey, Box<dyn Error>> {
+        let recipient = Keypair::new().pubkey();
+        self.manager.transfer(&self.payer, &recipient, lamports)?;
+        Ok(recipient)
+    }
+}
+
+impl Default for SolanaManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
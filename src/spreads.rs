@@ -0,0 +1,155 @@
+//! Quoted spread statistics per market: min / median / p90 spread and touch
+//! depth over a rolling window, used to pre-rank due markets for the scan
+
+use crate::types::{Market, OrderBook};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+
+/// Samples kept per market
+const MAX_SAMPLES: usize = 500;
+
+#[derive(Debug, Clone, Copy)]
+struct SpreadSample {
+    timestamp: u64,
+    spread: f64,
+    /// Size at best bid + best ask (None when only prices were known)
+    touch_depth: Option<f64>,
+}
+
+/// Spread distribution of one market
+#[derive(Debug, Clone, Serialize)]
+pub struct SpreadStats {
+    pub market_id: String,
+    pub samples: usize,
+    pub min: f64,
+    pub median: f64,
+    pub p90: f64,
+    /// Average size at the touch over samples that carried depth
+    pub avg_touch_depth: f64,
+    pub last_seen: u64,
+}
+
+impl SpreadStats {
+    /// Ranking score: depth available per unit of typical spread (higher is better)
+    pub fn score(&self) -> f64 {
+        (1.0 + self.avg_touch_depth) / self.median.max(0.001)
+    }
+}
+
+/// Rolling spread samples per market
+#[derive(Debug, Default)]
+pub struct SpreadTracker {
+    samples: HashMap<String, VecDeque<SpreadSample>>,
+}
+
+impl SpreadTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn push(&mut self, market_id: &str, sample: SpreadSample) {
+        let samples = self.samples.entry(market_id.to_string()).or_default();
+        samples.push_back(sample);
+        if samples.len() > MAX_SAMPLES {
+            samples.pop_front();
+        }
+    }
+
+    /// Sample a market's quoted best bid / ask (no depth)
+    pub fn observe_market(&mut self, market: &Market, now: u64) {
+        if let (Some(bid), Some(ask)) = (market.best_bid, market.best_ask) {
+            if ask >= bid {
+                self.push(&market.id, SpreadSample { timestamp: now, spread: ask - bid, touch_depth: None });
+            }
+        }
+    }
+
+    /// Sample one outcome token's book of a market
+    pub fn observe_book(&mut self, market_id: &str, book: &OrderBook, now: u64) {
+        if let Some(spread) = book.spred() {
+            let touch = book.bids.first().map_or(0.0, |l| l.size) + book.asks.first().map_or(0.0, |l| l.size);
+            self.push(market_id, SpreadSample { timestamp: now, spread, touch_depth: Some(touch) });
+        }
+    }
+
+    /// Summary for a market, if it has been sampled
+    pub fn stats(&self, market_id: &str) -> Option<SpreadStats> {
+        let samples = self.samples.get(market_id).filter(|s| !s.is_empty())?;
+        let mut spreads: Vec<f64> = samples.iter().map(|s| s.spread).collect();
+        spreads.sort_by(f64::total_cmp);
+        let pct = |p: f64| spreads[((spreads.len() - 1) as f64 * p).round() as usize];
+        let depths: Vec<f64> = samples.iter().filter_map(|s| s.touch_depth).collect();
+        Some(SpreadStats {
+            market_id: market_id.to_string(),
+            samples: samples.len(),
+            min: spreads[0],
+            median: pct(0.5),
+            p90: pct(0.9),
+            avg_touch_depth: if depths.is_empty() { 0.0 } else { depths.iter().sum::<f64>() / depths.len() as f64 },
+            last_seen: samples.back().map_or(0, |s| s.timestamp),
+        })
+    }
+
+    /// Summaries of every sampled market
+    pub fn all_stats(&self) -> Vec<SpreadStats> {
+        self.samples.keys().filter_map(|id| self.stats(id)).collect()
+    }
+
+    /// Order markets by how worth hydrating they are and keep at most `limit`
+    /// (0 = all). Unsampled markets go first so every market gets measured.
+    pub fn rank<'a>(&self, markets: Vec<&'a Market>, limit: usize) -> Vec<&'a Market> {
+        let mut scored: Vec<(f64, &Market)> = markets.into_iter()
+            .map(|m| (self.stats(&m.id).map_or(f64::INFINITY, |s| s.score()), m))
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let limit = if limit == 0 { scored.len() } else { limit };
+        scored.into_iter().take(limit).map(|(_, m)| m).collect()
+    }
+
+    /// Drop markets that are no longer listed
+    pub fn retain(&mut self, market_ids: &[String]) {
+        self.samples.retain(|id, _| market_ids.contains(id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, book};
+
+    fn market(id: &str) -> Market {
+        Market { id: id.to_string(), best_bid: Some(0.48), best_ask: Some(0.52), ..testing::market() }
+    }
+
+    fn tracker() -> SpreadTracker {
+        let mut tracker = SpreadTracker::new();
+        for (i, spread) in [0.01, 0.02, 0.03, 0.04, 0.10].iter().enumerate() {
+            tracker.observe_book("tight", &book("t", &[(0.50, 100.0)], &[(0.50 + spread, 100.0)]), i as u64);
+        }
+        tracker.observe_book("wide", &book("t", &[(0.40, 10.0)], &[(0.60, 10.0)]), 5);
+        tracker.observe_market(&market("wide"), 6);
+        tracker
+    }
+
+    #[test]
+    fn test_spread_distribution() {
+        let tight = tracker().stats("tight").unwrap();
+        assert_eq!(tight.samples, 5);
+        assert!((tight.min - 0.01).abs() < 1e-9);
+        assert!((tight.median - 0.03).abs() < 1e-9);
+        assert!((tight.p90 - 0.10).abs() < 1e-9);
+        assert_eq!(tight.avg_touch_depth, 200.0);
+    }
+
+    #[test]
+    fn test_price_only_samples_skip_depth() {
+        assert_eq!(tracker().stats("wide").unwrap().avg_touch_depth, 20.0);
+    }
+
+    #[test]
+    fn test_unseen_then_tight_markets_rank_first() {
+        let (new, wide, tight) = (market("new"), market("wide"), market("tight"));
+        let ranked = tracker().rank(vec![&wide, &tight, &new], 2);
+        assert_eq!(ranked.iter().map(|m| m.id.as_str()).collect::<Vec<_>>(), vec!["new", "tight"]);
+    }
+}
@@ -0,0 +1,63 @@
+//! Storage layer
+//!
+//! Append-only JSON Lines files under a configurable data directory.
+//! Each record is one serialized line, so files survive crashes mid-write
+//! (a torn last line is skipped on load).
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// A single JSON Lines file
+#[derive(Debug, Clone)]
+pub struct JsonlStore {
+    path: PathBuf,
+}
+
+impl JsonlStore {
+    /// Open (and create the directory for) `<data_dir>/<name>`
+    pub fn open(data_dir: &str, name: &str) -> io::Result<Self> {
+        fs::create_dir_all(data_dir)?;
+        Ok(Self {
+            path: Path::new(data_dir).join(name),
+        })
+    }
+
+    /// Append a record
+    pub fn append<T: Serialize>(&self, record: &T) -> io::Result<()> {
+        let line = serde_json::to_string(record)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", line)
+    }
+
+    /// Remove all records (compaction once nothing in the file is live)
+    pub fn clear(&self) -> io::Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Load all records, skipping lines that fail to parse
+    pub fn load<T: DeserializeOwned>(&self) -> io::Result<Vec<T>> {
+        let file = match File::open(&self.path) {
+            Ok(f) => f,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let mut records = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Ok(record) = serde_json::from_str(&line) {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+}
@@ -0,0 +1,227 @@
+//! Task supervision
+//!
+//! Long-running work (the engine loop, the API and gRPC servers, the
+//! retention and upload loops) reports liveness through a [`Heartbeat`].
+//! A hung await on a dead socket never returns on its own, so the watchdog
+//! drops any task whose heartbeat goes stale - cancelling whatever it was
+//! awaiting - and restarts it after a short backoff, as it does for tasks
+//! that exit or panic. Every restart is counted in the health state and
+//! shows up on `/healthz`, so a stall turns into a visible restart instead
+//! of the bot silently no longer trading.
+
+use crate::api::log_event;
+use crate::config::SupervisorConfig;
+use crate::events::EventLevel;
+use crate::health::HealthState;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+/// Liveness signal a supervised task refreshes while it makes progress
+#[derive(Debug, Clone)]
+pub struct Heartbeat {
+    last: Arc<Mutex<Instant>>,
+}
+
+impl Heartbeat {
+    pub fn new() -> Self {
+        Self { last: Arc::new(Mutex::new(Instant::now())) }
+    }
+
+    pub fn beat(&self) {
+        *self.last.lock().unwrap() = Instant::now();
+    }
+
+    /// Time since the last beat
+    pub fn age(&self) -> Duration {
+        self.last.lock().unwrap().elapsed()
+    }
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Why a supervised task was restarted
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskExit {
+    /// No heartbeat for this long; the task was cancelled
+    Stalled { silent_secs: u64 },
+    /// The task returned although it should run forever
+    Exited,
+    Panicked(String),
+}
+
+impl std::fmt::Display for TaskExit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Stalled { silent_secs } => write!(f, "stalled (no heartbeat for {}s)", silent_secs),
+            Self::Exited => write!(f, "exited"),
+            Self::Panicked(e) => write!(f, "panicked: {}", e),
+        }
+    }
+}
+
+/// Heartbeat watchdog that cancels and restarts stalled tasks
+#[derive(Clone)]
+pub struct Supervisor {
+    config: SupervisorConfig,
+    health: Arc<HealthState>,
+}
+
+impl Supervisor {
+    pub fn new(config: SupervisorConfig, health: Arc<HealthState>) -> Self {
+        Self { config, health }
+    }
+
+    fn check_interval(&self) -> Duration {
+        Duration::from_secs(self.config.check_interval_secs.max(1))
+    }
+
+    /// Run `fut` until it completes or `heartbeat` goes stale. A stalled
+    /// future is dropped, which cancels the await it was stuck on.
+    pub async fn guard<F: Future>(&self, heartbeat: &Heartbeat, fut: F) -> Result<F::Output, TaskExit> {
+        if !self.config.enabled {
+            return Ok(fut.await);
+        }
+        let timeout = Duration::from_secs(self.config.stall_timeout_secs);
+        tokio::pin!(fut);
+        let mut check = tokio::time::interval(self.check_interval());
+        loop {
+            tokio::select! {
+                out = &mut fut => return Ok(out),
+                _ = check.tick() => {
+                    let age = heartbeat.age();
+                    if age > timeout {
+                        return Err(TaskExit::Stalled { silent_secs: age.as_secs() });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Count and report a restart of `task`
+    pub fn record_restart(&self, task: &str, exit: &TaskExit) {
+        self.health.record_restart(task);
+        let msg = format!("🐕 [Supervisor] {} {} - restarting (restart #{})",
+            task, exit, self.health.restarts().get(task).copied().unwrap_or(0));
+        println!("{}", msg);
+        log_event(EventLevel::Error, "supervisor", None, &msg);
+    }
+
+    /// Spawn a task built by `factory`, restarting it with a fresh heartbeat
+    /// whenever it stalls, exits or panics. Disabled supervision runs it once.
+    pub fn spawn<F, Fut>(&self, name: &'static str, factory: F) -> JoinHandle<()>
+    where
+        F: Fn(Heartbeat) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let supervisor = self.clone();
+        tokio::spawn(async move {
+            if !supervisor.config.enabled {
+                factory(Heartbeat::new()).await;
+                return;
+            }
+            loop {
+                let heartbeat = Heartbeat::new();
+                let mut task = tokio::spawn(factory(heartbeat.clone()));
+                let exit = match supervisor.guard(&heartbeat, &mut task).await {
+                    Ok(Ok(())) => TaskExit::Exited,
+                    Ok(Err(e)) => TaskExit::Panicked(e.to_string()),
+                    Err(stalled) => {
+                        task.abort();
+                        stalled
+                    }
+                };
+                supervisor.record_restart(name, &exit);
+                tokio::time::sleep(Duration::from_secs(supervisor.config.restart_backoff_secs)).await;
+            }
+        })
+    }
+
+    /// Wait `duration` between runs of a periodic task, beating `heartbeat`
+    /// so a wait longer than the stall timeout is not taken for a stall
+    pub async fn idle(&self, heartbeat: &Heartbeat, duration: Duration) {
+        let until = Instant::now() + duration;
+        loop {
+            heartbeat.beat();
+            let left = until.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return;
+            }
+            tokio::time::sleep(left.min(self.check_interval())).await;
+        }
+    }
+
+    /// Beat `heartbeat` for as long as a server accepts connections on `addr`
+    /// (for servers whose request loop cannot beat itself). Never returns.
+    pub async fn probe_listener(&self, heartbeat: Heartbeat, addr: SocketAddr) {
+        let mut check = tokio::time::interval(self.check_interval());
+        loop {
+            check.tick().await;
+            if tokio::net::TcpStream::connect(addr).await.is_ok() {
+                heartbeat.beat();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stalled_and_panicking_tasks_restart() {
+        let health = Arc::new(HealthState::new(30, true));
+        let supervisor = Supervisor::new(SupervisorConfig::default(), health.clone());
+
+        // A beating future completes; a silent one is cancelled
+        let heartbeat = Heartbeat::new();
+        assert_eq!(supervisor.guard(&heartbeat, async { 7 }).await, Ok(7));
+        let hung = supervisor.guard(&heartbeat, std::future::pending::<()>()).await;
+        assert!(matches!(hung, Err(TaskExit::Stalled { silent_secs }) if silent_secs > 120));
+
+        // First run hangs, second panics, third beats forever
+        let runs = Arc::new(AtomicU32::new(0));
+        let counter = runs.clone();
+        supervisor.spawn("worker", move |heartbeat| {
+            let run = counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                match run {
+                    0 => std::future::pending().await,
+                    1 => panic!("boom"),
+                    _ => loop {
+                        heartbeat.beat();
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    },
+                }
+            }
+        });
+        tokio::time::sleep(Duration::from_secs(600)).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        assert_eq!(health.restarts()["worker"], 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_idle_task_is_not_restarted() {
+        let health = Arc::new(HealthState::new(30, true));
+        let supervisor = Supervisor::new(SupervisorConfig::default(), health.clone());
+        let idler = supervisor.clone();
+        supervisor.spawn("periodic", move |heartbeat| {
+            let idler = idler.clone();
+            async move {
+                loop {
+                    idler.idle(&heartbeat, Duration::from_secs(3_600)).await;
+                }
+            }
+        });
+        tokio::time::sleep(Duration::from_secs(7_200)).await;
+        assert!(!health.restarts().contains_key("periodic"));
+    }
+}
@@ -0,0 +1,222 @@
+//! Profit sweep to a cold address
+//!
+//! Once realized PnL not yet swept crosses a threshold, USDC above the
+//! working-capital target is transferred to a configured cold address. The
+//! transfer is a regular Smart Account call (`transfer` on the USDC
+//! contract), so it must pass the permission scope like any trade. Sweeps
+//! are journaled (requested / confirmed / failed) and only one may be in
+//! flight at a time; a sweep that is not confirmed in time is failed and its
+//! amount becomes sweepable again.
+
+use crate::config::SweepConfig;
+use crate::permission_guard::ContractCall;
+use crate::storage::JsonlStore;
+use serde::{Deserialize, Serialize};
+
+/// Method invoked on the USDC contract for a sweep
+pub const SWEEP_METHOD: &str = "transfer";
+
+/// Lifecycle of a sweep transfer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum SweepStatus {
+    Pending,
+    Confirmed { tx_hash: Option<String>, confirmed_at: u64 },
+    Failed { reason: String },
+}
+
+/// A transfer of profits to the cold address
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepTransfer {
+    pub sweep_id: String,
+    pub to: String,
+    /// USDC moved
+    pub amount: f64,
+    pub requested_at: u64,
+    pub status: SweepStatus,
+}
+
+/// Journal record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum SweepEvent {
+    Requested(SweepTransfer),
+    Resolved { sweep_id: String, status: SweepStatus },
+}
+
+/// Decides, tracks and journals profit sweeps
+#[derive(Debug)]
+pub struct ProfitSweeper {
+    config: SweepConfig,
+    /// USDC contract the transfer is called on
+    token_contract: String,
+    transfers: Vec<SweepTransfer>,
+    journal: Option<JsonlStore>,
+}
+
+impl ProfitSweeper {
+    pub fn new(config: SweepConfig, token_contract: &str) -> Self {
+        Self { config, token_contract: token_contract.to_string(), transfers: Vec::new(), journal: None }
+    }
+
+    /// Journal sweeps to `journal`, replaying the sweeps already recorded there
+    pub fn with_journal(mut self, journal: JsonlStore) -> Self {
+        let events: Vec<SweepEvent> = journal.load().unwrap_or_else(|e| {
+            eprintln!("⚠️ [Sweep] Failed to read sweep journal: {}", e);
+            Vec::new()
+        });
+        for event in events {
+            match event {
+                SweepEvent::Requested(t) => self.transfers.push(t),
+                SweepEvent::Resolved { sweep_id, status } => {
+                    if let Some(t) = self.transfers.iter_mut().find(|t| t.sweep_id == sweep_id) {
+                        t.status = status;
+                    }
+                }
+            }
+        }
+        self.journal = Some(journal);
+        self
+    }
+
+    fn record(&self, event: &SweepEvent) {
+        if let Some(journal) = &self.journal {
+            if let Err(e) = journal.append(event) {
+                eprintln!("⚠️ [Sweep] Failed to journal sweep event: {}", e);
+            }
+        }
+    }
+
+    /// USDC swept or in flight (failed sweeps excluded)
+    pub fn total_swept(&self) -> f64 {
+        self.transfers.iter()
+            .filter(|t| !matches!(t.status, SweepStatus::Failed { .. }))
+            .map(|t| t.amount)
+            .sum()
+    }
+
+    /// The sweep awaiting confirmation, if any
+    pub fn pending(&self) -> Option<&SweepTransfer> {
+        self.transfers.iter().find(|t| t.status == SweepStatus::Pending)
+    }
+
+    /// All sweeps, oldest first
+    pub fn history(&self) -> &[SweepTransfer] {
+        &self.transfers
+    }
+
+    /// Request a sweep if realized PnL not yet swept exceeds the threshold.
+    ///
+    /// Sweeps the smaller of the unswept profit and the balance above the
+    /// working-capital target, so trading capital is never withdrawn.
+    pub fn plan(&mut self, realized_pnl: f64, balance: f64, now: u64) -> Option<SweepTransfer> {
+        if !self.config.enabled || self.config.cold_address.is_empty() || self.pending().is_some() {
+            return None;
+        }
+        let unswept = realized_pnl - self.total_swept();
+        if unswept < self.config.pnl_threshold {
+            return None;
+        }
+        let amount = unswept.min(balance - self.config.working_capital_target);
+        if amount <= 0.0 {
+            return None;
+        }
+        let transfer = SweepTransfer {
+            sweep_id: format!("sweep-{}-{}", now, self.transfers.len() + 1),
+            to: self.config.cold_address.clone(),
+            amount,
+            requested_at: now,
+            status: SweepStatus::Pending,
+        };
+        self.record(&SweepEvent::Requested(transfer.clone()));
+        self.transfers.push(transfer.clone());
+        Some(transfer)
+    }
+
+    /// The Smart Account call that executes a sweep
+    pub fn call(&self, transfer: &SweepTransfer) -> ContractCall {
+        ContractCall {
+            target: self.token_contract.clone(),
+            method: SWEEP_METHOD.to_string(),
+            value: transfer.amount,
+        }
+    }
+
+    fn resolve(&mut self, sweep_id: &str, status: SweepStatus) -> Option<SweepTransfer> {
+        let transfer = self.transfers.iter_mut()
+            .find(|t| t.sweep_id == sweep_id && t.status == SweepStatus::Pending)?;
+        transfer.status = status.clone();
+        let resolved = transfer.clone();
+        self.record(&SweepEvent::Resolved { sweep_id: sweep_id.to_string(), status });
+        Some(resolved)
+    }
+
+    /// Mark a pending sweep as confirmed on-chain
+    pub fn confirm(&mut self, sweep_id: &str, tx_hash: Option<String>, now: u64) -> Option<SweepTransfer> {
+        self.resolve(sweep_id, SweepStatus::Confirmed { tx_hash, confirmed_at: now })
+    }
+
+    /// Mark a pending sweep as failed; its amount becomes sweepable again
+    pub fn fail(&mut self, sweep_id: &str, reason: &str) -> Option<SweepTransfer> {
+        self.resolve(sweep_id, SweepStatus::Failed { reason: reason.to_string() })
+    }
+
+    /// Fail the pending sweep if it has gone unconfirmed for too long
+    pub fn expire_stale(&mut self, now: u64) -> Option<SweepTransfer> {
+        let pending = self.pending()?;
+        if now.saturating_sub(pending.requested_at) < self.config.confirm_timeout_secs {
+            return None;
+        }
+        let sweep_id = pending.sweep_id.clone();
+        self.fail(&sweep_id, "confirmation timed out")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> SweepConfig {
+        SweepConfig {
+            enabled: true,
+            cold_address: "0xC01d".to_string(),
+            pnl_threshold: 20.0,
+            working_capital_target: 100.0,
+            confirm_timeout_secs: 600,
+        }
+    }
+
+    #[test]
+    fn test_sweep_lifecycle_and_journal() {
+        let dir = std::env::temp_dir().join(format!("arbishark_sweep_{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+        let journal = JsonlStore::open(dir, "sweeps.jsonl").unwrap();
+
+        let mut sweeper = ProfitSweeper::new(config(), "0xUSDC").with_journal(journal.clone());
+        assert!(sweeper.plan(10.0, 110.0, 0).is_none());
+
+        // Never dips below working capital
+        let sweep = sweeper.plan(30.0, 115.0, 100).unwrap();
+        assert_eq!(sweep.amount, 15.0);
+        assert_eq!(sweeper.call(&sweep).method, SWEEP_METHOD);
+        // One in flight at a time, and a restart still sees it
+        assert!(sweeper.plan(60.0, 160.0, 101).is_none());
+        let restored = ProfitSweeper::new(config(), "0xUSDC").with_journal(journal.clone());
+        assert_eq!(restored.pending().map(|t| t.sweep_id.clone()), Some(sweep.sweep_id.clone()));
+
+        sweeper.confirm(&sweep.sweep_id, Some("0xabc".to_string()), 120).unwrap();
+        assert_eq!(sweeper.total_swept(), 15.0);
+        // 15 of the 30 profit is still unswept, below threshold
+        assert!(sweeper.plan(30.0, 200.0, 130).is_none());
+
+        let second = sweeper.plan(50.0, 200.0, 200).unwrap();
+        assert_eq!(second.amount, 35.0);
+        assert!(sweeper.expire_stale(500).is_none());
+        assert!(matches!(sweeper.expire_stale(800).unwrap().status, SweepStatus::Failed { .. }));
+
+        let restored = ProfitSweeper::new(config(), "0xUSDC").with_journal(journal);
+        assert!(restored.pending().is_none());
+        assert_eq!(restored.total_swept(), 15.0);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,306 @@
+//! Synthetic pricing from declared logical relationships between markets
+//! (`implies`, `exclusive`, `conjunction`), signalled with their assumption's risk
+
+use crate::config::SyntheticConfig;
+use crate::types::Market;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RelationKind {
+    Implies,
+    Exclusive,
+    Conjunction,
+}
+
+/// How likely the relationship is to break at resolution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssumptionRisk {
+    /// Holds by definition (same resolution source and wording)
+    Low,
+    /// Holds unless resolution rules diverge
+    Medium,
+    /// A judgement call (e.g. a nominee could be replaced)
+    #[default]
+    High,
+}
+
+/// One declared relationship
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Relationship {
+    pub id: String,
+    pub kind: RelationKind,
+    /// implies: [A, B] for A ⇒ B; exclusive: the markets; conjunction: the legs
+    pub markets: Vec<String>,
+    /// conjunction only: the market equal to all legs resolving YES
+    #[serde(default)]
+    pub target: Option<String>,
+    /// What must be true at resolution for the relationship to hold
+    pub assumption: String,
+    #[serde(default)]
+    pub risk: AssumptionRisk,
+}
+
+#[derive(Debug, Deserialize)]
+struct RelationshipFile {
+    #[serde(default)]
+    relationship: Vec<Relationship>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyntheticError {
+    Io(String),
+    Parse(String),
+    Invalid(String),
+}
+
+impl std::fmt::Display for SyntheticError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Relationship file I/O error: {}", e),
+            Self::Parse(e) => write!(f, "Relationship file parse error: {}", e),
+            Self::Invalid(e) => write!(f, "Invalid relationship: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SyntheticError {}
+
+/// Parse and check a relationship file
+pub fn parse_relationships(content: &str) -> Result<Vec<Relationship>, SyntheticError> {
+    let file: RelationshipFile = toml::from_str(content).map_err(|e| SyntheticError::Parse(e.to_string()))?;
+    for r in &file.relationship {
+        let ok = match r.kind {
+            RelationKind::Implies => r.markets.len() == 2 && r.target.is_none(),
+            RelationKind::Exclusive => r.markets.len() >= 2 && r.target.is_none(),
+            RelationKind::Conjunction => !r.markets.is_empty() && r.target.is_some(),
+        };
+        if !ok {
+            return Err(SyntheticError::Invalid(format!("{}: implies takes 2 markets, exclusive 2 or more, conjunction a target and its legs", r.id)));
+        }
+        if r.assumption.trim().is_empty() {
+            return Err(SyntheticError::Invalid(format!("{}: the assumption must be stated", r.id)));
+        }
+    }
+    Ok(file.relationship)
+}
+
+/// One leg of a synthetic trade
+#[derive(Debug, Clone, Serialize)]
+pub struct SyntheticLeg {
+    pub market_id: String,
+    pub token_id: String,
+    /// "YES" or "NO"
+    pub outcome: String,
+    pub price: f64,
+}
+
+/// A violated inequality and the trade that locks it in
+#[derive(Debug, Clone, Serialize)]
+pub struct SyntheticSignal {
+    pub relationship_id: String,
+    pub kind: RelationKind,
+    /// Which inequality is violated, with the prices
+    pub violation: String,
+    pub legs: Vec<SyntheticLeg>,
+    pub cost: f64,
+    /// Least the legs pay together if the relationship holds
+    pub guaranteed_payout: f64,
+    /// guaranteed_payout - cost, per unit
+    pub edge: f64,
+    pub assumption: String,
+    pub assumption_risk: AssumptionRisk,
+    pub detected_at: u64,
+}
+
+/// Binary market prices: (YES, NO)
+fn prices(market: &Market) -> Option<(f64, f64)> {
+    let yes = *market.outcome_prices.first()?;
+    let no = market.outcome_prices.get(1).copied().unwrap_or(1.0 - yes);
+    Some((yes, no))
+}
+
+fn leg(market: &Market, yes: bool) -> Option<SyntheticLeg> {
+    let (p_yes, p_no) = prices(market)?;
+    let (index, outcome, price) = if yes { (0, "YES", p_yes) } else { (1, "NO", p_no) };
+    Some(SyntheticLeg { market_id: market.id.clone(), token_id: market.clob_token_ids.get(index)?.clone(), outcome: outcome.to_string(), price })
+}
+
+/// Evaluates the declared relationships against live prices
+#[derive(Debug)]
+pub struct SyntheticPricer {
+    config: SyntheticConfig,
+    relationships: Vec<Relationship>,
+    /// Currently violated relationships (`GET /api/synthetic`)
+    active: BTreeMap<String, SyntheticSignal>,
+}
+
+impl SyntheticPricer {
+    pub fn new(config: SyntheticConfig, relationships: Vec<Relationship>) -> Self {
+        Self { config, relationships, active: BTreeMap::new() }
+    }
+
+    /// Load `config.relationships_file` (none when disabled)
+    pub fn from_config(config: &SyntheticConfig) -> Result<Self, SyntheticError> {
+        if !config.enabled {
+            return Ok(Self::new(config.clone(), Vec::new()));
+        }
+        let content = std::fs::read_to_string(&config.relationships_file)
+            .map_err(|e| SyntheticError::Io(format!("{}: {}", config.relationships_file, e)))?;
+        Ok(Self::new(config.clone(), parse_relationships(&content)?))
+    }
+
+    pub fn relationship_count(&self) -> usize {
+        self.relationships.len()
+    }
+
+    /// Price every relationship whose markets are all listed; returns the newly violated ones
+    pub fn scan(&mut self, markets: &[Market], now: u64) -> Vec<SyntheticSignal> {
+        let by_id: HashMap<&str, &Market> = markets.iter().map(|m| (m.id.as_str(), m)).collect();
+        let mut fresh = Vec::new();
+        for r in &self.relationships {
+            match evaluate(r, &by_id, now).filter(|s| s.edge >= self.config.min_edge) {
+                Some(signal) => {
+                    if !self.active.contains_key(&r.id) {
+                        fresh.push(signal.clone());
+                    }
+                    self.active.insert(r.id.clone(), signal);
+                }
+                None => {
+                    self.active.remove(&r.id);
+                }
+            }
+        }
+        fresh
+    }
+
+    pub fn active(&self) -> Vec<SyntheticSignal> {
+        self.active.values().cloned().collect()
+    }
+}
+
+/// The most profitable violated inequality of `r`, if any
+pub fn evaluate(r: &Relationship, markets: &HashMap<&str, &Market>, now: u64) -> Option<SyntheticSignal> {
+    let get = |id: &str| markets.get(id).copied();
+    let legs: Vec<&Market> = r.markets.iter().map(|id| get(id)).collect::<Option<_>>()?;
+    let yes = |m: &Market| prices(m).map(|(y, _)| y);
+    // (violation, legs bought, guaranteed payout)
+    let mut candidates: Vec<(String, Vec<SyntheticLeg>, f64)> = Vec::new();
+    match r.kind {
+        RelationKind::Implies => {
+            let (a, b) = (legs[0], legs[1]);
+            candidates.push((format!("P({}) {:.3} > P({}) {:.3}", a.id, yes(a)?, b.id, yes(b)?), vec![leg(a, false)?, leg(b, true)?], 1.0));
+        }
+        RelationKind::Exclusive => {
+            let sum: f64 = legs.iter().map(|m| yes(m)).sum::<Option<f64>>()?;
+            let no_legs = legs.iter().map(|m| leg(m, false)).collect::<Option<Vec<_>>>()?;
+            candidates.push((format!("ΣP {:.3} > 1 over {} exclusive markets", sum, legs.len()), no_legs, (legs.len() - 1) as f64));
+        }
+        RelationKind::Conjunction => {
+            let target = get(r.target.as_deref()?)?;
+            let p_target = yes(target)?;
+            let cheapest = legs.iter().copied().min_by(|a, b| yes(a).unwrap_or(1.0).total_cmp(&yes(b).unwrap_or(1.0)))?;
+            candidates.push((format!("P({}) {:.3} > P({}) {:.3}", target.id, p_target, cheapest.id, yes(cheapest)?),
+                vec![leg(target, false)?, leg(cheapest, true)?], 1.0));
+            let sum: f64 = legs.iter().map(|m| yes(m)).sum::<Option<f64>>()?;
+            let lower = sum - (legs.len() - 1) as f64;
+            let mut buy = vec![leg(target, true)?];
+            buy.extend(legs.iter().map(|m| leg(m, false)).collect::<Option<Vec<_>>>()?);
+            candidates.push((format!("P({}) {:.3} < lower bound {:.3}", target.id, p_target, lower), buy, 1.0));
+        }
+    }
+    candidates.into_iter()
+        .map(|(violation, legs, guaranteed_payout)| {
+            let cost: f64 = legs.iter().map(|l| l.price).sum();
+            SyntheticSignal {
+                relationship_id: r.id.clone(),
+                kind: r.kind,
+                violation,
+                legs,
+                cost,
+                guaranteed_payout,
+                edge: guaranteed_payout - cost,
+                assumption: r.assumption.clone(),
+                assumption_risk: r.risk,
+                detected_at: now,
+            }
+        })
+        .filter(|s| s.edge > 0.0)
+        .max_by(|a, b| a.edge.total_cmp(&b.edge))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn market(id: &str, yes: f64) -> Market {
+        Market {
+            id: id.to_string(),
+            outcome_prices: vec![yes, 1.0 - yes],
+            clob_token_ids: vec![format!("{}-yes", id), format!("{}-no", id)],
+            taker_base_fee: 0,
+            ..testing::market()
+        }
+    }
+
+    fn relationships() -> Vec<Relationship> {
+        parse_relationships(r#"
+            [[relationship]]
+            id = "president"
+            kind = "conjunction"
+            target = "president"
+            markets = ["nomination", "general"]
+            assumption = "only the nominee can win the general election"
+
+            [[relationship]]
+            id = "nominee-implies"
+            kind = "implies"
+            markets = ["president", "nomination"]
+            assumption = "the president must have been the nominee"
+            risk = "medium"
+        "#).unwrap()
+    }
+
+    fn pricer() -> SyntheticPricer {
+        SyntheticPricer::new(SyntheticConfig { enabled: true, min_edge: 0.02, ..Default::default() }, relationships())
+    }
+
+    #[test]
+    fn test_relationships_parsed() {
+        assert_eq!(relationships()[0].risk, AssumptionRisk::High);
+        assert!(parse_relationships("[[relationship]]\nid = \"x\"\nkind = \"implies\"\nmarkets = [\"a\"]\nassumption = \"a\"").is_err());
+    }
+
+    #[test]
+    fn test_consistent_prices_signal_nothing() {
+        // 0.30 between max(0, 0.60 + 0.40 - 1) and min(0.60, 0.40)
+        assert!(pricer().scan(&[market("president", 0.30), market("nomination", 0.60), market("general", 0.40)], 1).is_empty());
+    }
+
+    #[test]
+    fn test_detects_conditional_violations() {
+        // The presidency trades above the nomination: both relationships flag it
+        let signals = pricer().scan(&[market("president", 0.70), market("nomination", 0.60), market("general", 0.75)], 2);
+        assert_eq!(signals.len(), 2);
+        let implied = signals.iter().find(|s| s.relationship_id == "nominee-implies").unwrap();
+        assert_eq!(implied.legs.iter().map(|l| l.token_id.as_str()).collect::<Vec<_>>(), vec!["president-no", "nomination-yes"]);
+        assert!((implied.edge - 0.10).abs() < 1e-9);
+        assert_eq!(implied.assumption_risk, AssumptionRisk::Medium);
+    }
+
+    #[test]
+    fn test_conjunction_below_lower_bound() {
+        // Below the lower bound 0.60 + 0.75 - 1 = 0.35: buy the target and NO on each leg
+        let mut pricer = pricer();
+        let markets = [market("president", 0.20), market("nomination", 0.60), market("general", 0.75)];
+        let signals = pricer.scan(&markets, 3);
+        assert_eq!(signals.len(), 1);
+        assert_eq!(signals[0].legs.len(), 3);
+        assert!((signals[0].edge - 0.15).abs() < 1e-9);
+        assert!(pricer.scan(&markets, 4).is_empty(), "still active, not re-emitted");
+        assert_eq!(pricer.active().len(), 1);
+    }
+}
@@ -0,0 +1,284 @@
+//! Tax lot accounting
+//!
+//! Every fill that opens or adds to a position is journaled as an acquired
+//! lot, and every exit as a disposal. Disposals are matched against the
+//! token's open lots FIFO or LIFO when the ledger is replayed, producing
+//! per-lot realized gains with cost basis, proceeds and holding period.
+//! `arbishark tax-report <year>` turns one calendar year (UTC) of those into
+//! a CSV laid out like Form 8949 for import into tax software.
+
+use crate::positions::ExitResult;
+use crate::storage::JsonlStore;
+use chrono::{DateTime, Datelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::io;
+
+/// Holding period (seconds) from which a gain is long-term
+const LONG_TERM_SECS: u64 = 365 * 86400;
+
+/// Order in which lots are consumed by a disposal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LotMethod {
+    /// Oldest lot first
+    Fifo,
+    /// Newest lot first
+    Lifo,
+}
+
+impl LotMethod {
+    pub fn parse(method: &str) -> Self {
+        match method.to_lowercase().as_str() {
+            "lifo" => Self::Lifo,
+            _ => Self::Fifo,
+        }
+    }
+}
+
+/// Journal record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum LotEvent {
+    Acquired {
+        market_id: String,
+        token_id: String,
+        size: f64,
+        /// Price paid plus fees (USDC)
+        cost: f64,
+        time: u64,
+    },
+    Disposed {
+        market_id: String,
+        token_id: String,
+        size: f64,
+        /// Sale value net of fees (USDC)
+        proceeds: f64,
+        time: u64,
+    },
+}
+
+/// An open lot
+#[derive(Debug, Clone)]
+struct Lot {
+    size: f64,
+    cost: f64,
+    time: u64,
+}
+
+/// Gain or loss on (part of) one lot
+#[derive(Debug, Clone, PartialEq)]
+pub struct RealizedGain {
+    pub market_id: String,
+    pub token_id: String,
+    pub size: f64,
+    /// None when the disposal exceeded the journaled lots (basis unknown, 0)
+    pub acquired_at: Option<u64>,
+    pub disposed_at: u64,
+    pub proceeds: f64,
+    pub cost_basis: f64,
+    pub gain: f64,
+    pub long_term: bool,
+}
+
+/// Journaled acquisitions and disposals
+#[derive(Debug, Default)]
+pub struct TaxLedger {
+    events: Vec<LotEvent>,
+    journal: Option<JsonlStore>,
+}
+
+impl TaxLedger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open a journaled ledger, loading the events already recorded
+    pub fn open(journal: JsonlStore) -> io::Result<Self> {
+        Ok(Self { events: journal.load()?, journal: Some(journal) })
+    }
+
+    fn record(&mut self, event: LotEvent) {
+        if let Some(journal) = &self.journal {
+            if let Err(e) = journal.append(&event) {
+                eprintln!("⚠️ [Tax] Failed to journal lot event: {}", e);
+            }
+        }
+        self.events.push(event);
+    }
+
+    /// Record a fill that opened or added to a position
+    pub fn acquire(&mut self, market_id: &str, token_id: &str, size: f64, cost: f64, time: u64) {
+        if size > 0.0 {
+            self.record(LotEvent::Acquired {
+                market_id: market_id.to_string(),
+                token_id: token_id.to_string(),
+                size,
+                cost,
+                time,
+            });
+        }
+    }
+
+    /// Record a closed position
+    pub fn dispose(&mut self, exit: &ExitResult) {
+        let p = &exit.position;
+        self.record(LotEvent::Disposed {
+            market_id: p.market_id.clone(),
+            token_id: p.token_id.clone(),
+            size: p.size,
+            proceeds: p.size * exit.exit_price - exit.fees,
+            time: exit.exit_time,
+        });
+    }
+
+    /// Match every disposal against the open lots of its token
+    pub fn realized(&self, method: LotMethod) -> Vec<RealizedGain> {
+        let mut lots: HashMap<&str, VecDeque<Lot>> = HashMap::new();
+        let mut gains = Vec::new();
+        for event in &self.events {
+            match event {
+                LotEvent::Acquired { token_id, size, cost, time, .. } => {
+                    lots.entry(token_id.as_str()).or_default().push_back(Lot { size: *size, cost: *cost, time: *time });
+                }
+                LotEvent::Disposed { market_id, token_id, size, proceeds, time } => {
+                    let open = lots.entry(token_id.as_str()).or_default();
+                    let mut remaining = *size;
+                    while remaining > 1e-9 {
+                        let lot = match method {
+                            LotMethod::Fifo => open.front_mut(),
+                            LotMethod::Lifo => open.back_mut(),
+                        };
+                        let Some(lot) = lot else { break };
+                        let take = remaining.min(lot.size);
+                        let cost_basis = lot.cost * take / lot.size;
+                        let share = proceeds * take / size;
+                        gains.push(RealizedGain {
+                            market_id: market_id.clone(),
+                            token_id: token_id.clone(),
+                            size: take,
+                            acquired_at: Some(lot.time),
+                            disposed_at: *time,
+                            proceeds: share,
+                            cost_basis,
+                            gain: share - cost_basis,
+                            long_term: time.saturating_sub(lot.time) >= LONG_TERM_SECS,
+                        });
+                        lot.cost -= cost_basis;
+                        lot.size -= take;
+                        remaining -= take;
+                        if lot.size <= 1e-9 {
+                            match method {
+                                LotMethod::Fifo => open.pop_front(),
+                                LotMethod::Lifo => open.pop_back(),
+                            };
+                        }
+                    }
+                    if remaining > 1e-9 {
+                        let share = proceeds * remaining / size;
+                        gains.push(RealizedGain {
+                            market_id: market_id.clone(),
+                            token_id: token_id.clone(),
+                            size: remaining,
+                            acquired_at: None,
+                            disposed_at: *time,
+                            proceeds: share,
+                            cost_basis: 0.0,
+                            gain: share,
+                            long_term: false,
+                        });
+                    }
+                }
+            }
+        }
+        gains
+    }
+
+    /// Realized gains disposed of in a calendar year (UTC)
+    pub fn yearly(&self, year: i32, method: LotMethod) -> Vec<RealizedGain> {
+        self.realized(method).into_iter()
+            .filter(|g| year_of(g.disposed_at) == year)
+            .collect()
+    }
+}
+
+fn year_of(ts: u64) -> i32 {
+    DateTime::<Utc>::from_timestamp(ts as i64, 0).unwrap_or_default().year()
+}
+
+fn date(ts: u64) -> String {
+    DateTime::<Utc>::from_timestamp(ts as i64, 0).unwrap_or_default().format("%m/%d/%Y").to_string()
+}
+
+/// Form 8949-style CSV, one row per lot, with a totals row
+pub fn to_csv(gains: &[RealizedGain]) -> String {
+    let mut csv = String::from("Description,Date Acquired,Date Sold,Proceeds,Cost Basis,Gain or Loss,Term\n");
+    for g in gains {
+        csv.push_str(&format!("{:.4} shares {} ({}),{},{},{:.2},{:.2},{:.2},{}\n",
+            g.size, g.token_id, g.market_id,
+            g.acquired_at.map_or("UNKNOWN".to_string(), date),
+            date(g.disposed_at),
+            g.proceeds, g.cost_basis, g.gain,
+            if g.long_term { "Long" } else { "Short" }));
+    }
+    let total = |f: fn(&RealizedGain) -> f64| gains.iter().map(f).sum::<f64>();
+    csv.push_str(&format!("Total,,,{:.2},{:.2},{:.2},\n",
+        total(|g| g.proceeds), total(|g| g.cost_basis), total(|g| g.gain)));
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::positions::{ExitReason, Position};
+    use crate::types::Side;
+
+    fn exit(size: f64, price: f64, time: u64) -> ExitResult {
+        ExitResult {
+            position: Position {
+                market_id: "m1".to_string(),
+                token_id: "t1".to_string(),
+                side: Side::Buy,
+                size,
+                entry_price: 0.0,
+                entry_time: 0,
+                entry_spread: 0.0,
+                strategy: "arb".to_string(),
+            },
+            exit_price: price,
+            exit_time: time,
+            reason: ExitReason::Manual,
+            pnl: 0.0,
+            fees: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_fifo_lifo_matching_and_yearly_csv() {
+        // 2024-01-01 and 2025-06-01 UTC
+        let (jan_2024, jun_2025) = (1_704_067_200, 1_748_736_000);
+        let mut ledger = TaxLedger::new();
+        ledger.acquire("m1", "t1", 10.0, 4.0, jan_2024);
+        ledger.acquire("m1", "t1", 10.0, 6.0, jan_2024 + 60);
+        ledger.dispose(&exit(15.0, 0.5, jun_2025));
+
+        let fifo = ledger.realized(LotMethod::Fifo);
+        assert_eq!(fifo.len(), 2);
+        assert!((fifo[0].cost_basis - 4.0).abs() < 1e-9);
+        assert!((fifo[1].cost_basis - 3.0).abs() < 1e-9);
+        assert!(fifo[0].long_term);
+
+        let lifo = ledger.realized(LotMethod::Lifo);
+        assert!((lifo[0].cost_basis - 6.0).abs() < 1e-9);
+        assert!((lifo[1].cost_basis - 2.0).abs() < 1e-9);
+
+        let total = |g: &[RealizedGain]| g.iter().map(|g| g.gain).sum::<f64>();
+        assert!((total(&fifo) - (7.5 - 7.0)).abs() < 1e-9);
+        assert!((total(&lifo) - (7.5 - 8.0)).abs() < 1e-9);
+
+        assert!(ledger.yearly(2024, LotMethod::Fifo).is_empty());
+        let csv = to_csv(&ledger.yearly(2025, LotMethod::Fifo));
+        assert_eq!(csv.lines().count(), 4);
+        assert!(csv.lines().nth(1).unwrap().contains(",01/01/2024,06/01/2025,"));
+        assert!(csv.ends_with("Total,,,7.50,7.00,0.50,\n"));
+    }
+}
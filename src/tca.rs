@@ -0,0 +1,327 @@
+//! Transaction Cost Analysis (TCA) module
+//!
+//! Compares each fill against the signal-time quote, the arrival midpoint and
+//! the post-trade midpoint drift (markouts) to measure execution quality.
+
+use crate::fee_calibrator::FeeCalibrator;
+use crate::fees::FeeModel;
+use crate::types::Side;
+use std::collections::{HashMap, VecDeque};
+
+/// Markout horizons in milliseconds (1s / 10s / 60s); each is sampled at the first
+/// mid observed past it, so the actual lag is reported alongside
+pub const MARKOUT_HORIZONS_MS: [u64; 3] = [1_000, 10_000, 60_000];
+
+/// Recent fills whose implied cost rates feed the fee calibration
+const COST_RATE_WINDOW: usize = 1_000;
+
+/// Fills needed before the calibrated cost rate replaces the fee model's
+const MIN_CALIBRATION_FILLS: usize = 20;
+
+/// A single fill with its reference prices
+#[derive(Debug, Clone)]
+pub struct FillRecord {
+    pub market_id: String,
+    pub token_id: String,
+    pub side: Side,
+    pub size: f64,
+    pub fill_price: f64,
+    pub signal_price: f64,   // Quote seen when the signal fired
+    pub arrival_mid: f64,    // Book midpoint when the order arrived
+    pub fill_time_ms: u64,
+    /// Midpoint observed at each horizon in `MARKOUT_HORIZONS_MS`
+    pub markout_mids: [Option<f64>; 3],
+}
+
+impl FillRecord {
+    /// Signed cost vs a reference price (positive = paid worse than reference)
+    fn cost_vs(&self, reference: f64) -> f64 {
+        if reference <= 0.0 {
+            return 0.0;
+        }
+        match self.side {
+            Side::Buy => (self.fill_price - reference) / reference,
+            Side::Sell => (reference - self.fill_price) / reference,
+        }
+    }
+
+    /// Slippage vs the signal-time quote
+    pub fn signal_slippage(&self) -> f64 {
+        self.cost_vs(self.signal_price)
+    }
+
+    /// Slippage vs the arrival midpoint (implementation shortfall)
+    pub fn arrival_slippage(&self) -> f64 {
+        self.cost_vs(self.arrival_mid)
+    }
+
+    /// Markout at horizon index (positive = price moved in our favour)
+    pub fn markout(&self, horizon_idx: usize) -> Option<f64> {
+        let mid = self.markout_mids.get(horizon_idx).copied().flatten()?;
+        if self.fill_price <= 0.0 {
+            return None;
+        }
+        Some(match self.side {
+            Side::Buy => (mid - self.fill_price) / self.fill_price,
+            Side::Sell => (self.fill_price - mid) / self.fill_price,
+        })
+    }
+}
+
+/// Aggregated execution quality statistics
+#[derive(Debug, Clone, Default)]
+pub struct TcaStats {
+    pub fills: usize,
+    pub volume: f64,
+    pub avg_signal_slippage: f64,
+    pub avg_arrival_slippage: f64,
+    /// Average markout per horizon (None if no samples yet)
+    pub avg_markouts: [Option<f64>; 3],
+    /// How long after the fill each horizon was actually sampled, on average (mids are
+    /// observed once per tick, so a horizon is recorded at the first tick past it)
+    pub avg_markout_lag_ms: [Option<u64>; 3],
+}
+
+/// Running sums behind `TcaStats`
+#[derive(Debug, Clone, Default)]
+struct Totals {
+    fills: usize,
+    volume: f64,
+    signal_slippage: f64,
+    arrival_slippage: f64,
+    markouts: [f64; 3],
+    markout_lag_ms: [u64; 3],
+    markout_samples: [usize; 3],
+}
+
+impl Totals {
+    fn add(&mut self, other: &Totals) {
+        self.fills += other.fills;
+        self.volume += other.volume;
+        self.signal_slippage += other.signal_slippage;
+        self.arrival_slippage += other.arrival_slippage;
+        for i in 0..MARKOUT_HORIZONS_MS.len() {
+            self.markouts[i] += other.markouts[i];
+            self.markout_lag_ms[i] += other.markout_lag_ms[i];
+            self.markout_samples[i] += other.markout_samples[i];
+        }
+    }
+
+    fn stats(&self) -> TcaStats {
+        let mut stats = TcaStats { fills: self.fills, volume: self.volume, ..Default::default() };
+        if self.fills > 0 {
+            stats.avg_signal_slippage = self.signal_slippage / self.fills as f64;
+            stats.avg_arrival_slippage = self.arrival_slippage / self.fills as f64;
+        }
+        for i in 0..MARKOUT_HORIZONS_MS.len() {
+            let samples = self.markout_samples[i];
+            if samples > 0 {
+                stats.avg_markouts[i] = Some(self.markouts[i] / samples as f64);
+                stats.avg_markout_lag_ms[i] = Some(self.markout_lag_ms[i] / samples as u64);
+            }
+        }
+        stats
+    }
+}
+
+/// Collects fills and computes TCA statistics. A fill is only kept until every
+/// markout horizon has been sampled; statistics are kept as running totals.
+#[derive(Debug, Default)]
+pub struct TcaAnalyzer {
+    /// Fills still waiting for a markout horizon
+    pending: Vec<FillRecord>,
+    totals: HashMap<String, Totals>,
+    /// Implied cost rates of the most recent fills, for `FeeCalibrator`
+    cost_rates: VecDeque<f64>,
+}
+
+impl TcaAnalyzer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a new fill
+    pub fn record_fill(&mut self, fill: FillRecord) {
+        let totals = self.totals.entry(fill.market_id.clone()).or_default();
+        totals.fills += 1;
+        totals.volume += fill.fill_price * fill.size;
+        totals.signal_slippage += fill.signal_slippage();
+        totals.arrival_slippage += fill.arrival_slippage();
+        if fill.arrival_mid > 0.0 {
+            if self.cost_rates.len() == COST_RATE_WINDOW {
+                self.cost_rates.pop_front();
+            }
+            self.cost_rates.push_back(FeeCalibrator::derive_rate(fill.arrival_mid, fill.fill_price));
+        }
+        self.pending.push(fill);
+    }
+
+    /// Feed a midpoint observation for a token, filling any markout horizons
+    /// that have elapsed since the fill; fills with every horizon sampled are dropped.
+    pub fn observe_mid(&mut self, token_id: &str, mid: f64, now_ms: u64) {
+        if mid <= 0.0 {
+            return;
+        }
+        for fill in self.pending.iter_mut().filter(|f| f.token_id == token_id) {
+            let elapsed = now_ms.saturating_sub(fill.fill_time_ms);
+            for (i, horizon) in MARKOUT_HORIZONS_MS.iter().enumerate() {
+                if fill.markout_mids[i].is_none() && elapsed >= *horizon {
+                    fill.markout_mids[i] = Some(mid);
+                    if let (Some(markout), Some(totals)) = (fill.markout(i), self.totals.get_mut(&fill.market_id)) {
+                        totals.markouts[i] += markout;
+                        totals.markout_lag_ms[i] += elapsed;
+                        totals.markout_samples[i] += 1;
+                    }
+                }
+            }
+        }
+        self.pending.retain(|f| f.markout_mids.iter().any(Option::is_none));
+    }
+
+    /// Fills recorded so far
+    pub fn fill_count(&self) -> usize {
+        self.totals.values().map(|t| t.fills).sum()
+    }
+
+    /// Fills still waiting for a markout horizon
+    pub fn pending(&self) -> &[FillRecord] {
+        &self.pending
+    }
+
+    /// Statistics for a single market
+    pub fn market_stats(&self, market_id: &str) -> TcaStats {
+        self.totals.get(market_id).map(Totals::stats).unwrap_or_default()
+    }
+
+    /// Statistics per market
+    pub fn stats_by_market(&self) -> HashMap<String, TcaStats> {
+        self.totals.iter().map(|(id, totals)| (id.clone(), totals.stats())).collect()
+    }
+
+    /// Statistics across all fills
+    pub fn aggregate(&self) -> TcaStats {
+        let mut all = Totals::default();
+        for totals in self.totals.values() {
+            all.add(totals);
+        }
+        all.stats()
+    }
+
+    /// Implied cost rates vs arrival mid of the most recent fills, suitable for `FeeCalibrator`
+    pub fn implied_fee_rates(&self) -> Vec<f64> {
+        self.cost_rates.iter().copied().collect()
+    }
+
+    /// Calibrated p95 cost rate from observed fills
+    pub fn calibrated_fee_p95(&self) -> f64 {
+        FeeCalibrator::calibration_fee_p95(&self.implied_fee_rates())
+    }
+
+    /// `base` with its taker fee raised to the calibrated p95 cost rate, once enough
+    /// fills back it; edge math then prices taker fills at what they actually cost
+    pub fn calibrated_fee_model(&self, base: FeeModel) -> FeeModel {
+        if self.cost_rates.len() < MIN_CALIBRATION_FILLS {
+            return base;
+        }
+        let taker_fee_bps = (self.calibrated_fee_p95() * 10_000.0).round() as u32;
+        FeeModel { taker_fee_bps: taker_fee_bps.max(base.taker_fee_bps), ..base }
+    }
+
+    /// Human readable lines for the daily report
+    pub fn report(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        let agg = self.aggregate();
+        lines.push(format!("TCA: {} fills | Vol ${:.2} | Slip vs signal {:.2}% | vs arrival {:.2}%",
+            agg.fills, agg.volume, agg.avg_signal_slippage * 100.0, agg.avg_arrival_slippage * 100.0));
+        lines.push(format!("     Markouts 1s/10s/60s (sampled at {} / {} / {}): {} / {} / {}",
+            fmt_lag(agg.avg_markout_lag_ms[0]), fmt_lag(agg.avg_markout_lag_ms[1]), fmt_lag(agg.avg_markout_lag_ms[2]),
+            fmt_pct(agg.avg_markouts[0]), fmt_pct(agg.avg_markouts[1]), fmt_pct(agg.avg_markouts[2])));
+        lines.push(format!("     Calibrated p95 cost rate: {:.2}%", self.calibrated_fee_p95() * 100.0));
+
+        let mut by_market: Vec<_> = self.stats_by_market().into_iter().collect();
+        by_market.sort_by(|a, b| a.0.cmp(&b.0));
+        for (market_id, s) in by_market {
+            lines.push(format!("     {} | {} fills | Slip {:.2}% | 60s markout {}",
+                market_id, s.fills, s.avg_arrival_slippage * 100.0, fmt_pct(s.avg_markouts[2])));
+        }
+        lines
+    }
+}
+
+fn fmt_pct(v: Option<f64>) -> String {
+    v.map_or("n/a".to_string(), |v| format!("{:.2}%", v * 100.0))
+}
+
+fn fmt_lag(v: Option<u64>) -> String {
+    v.map_or("n/a".to_string(), |ms| format!("~{:.0}s", ms as f64 / 1000.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(token_id: &str, fill_price: f64, arrival_mid: f64) -> FillRecord {
+        FillRecord {
+            market_id: "m1".to_string(),
+            token_id: token_id.to_string(),
+            side: Side::Buy,
+            size: 10.0,
+            fill_price,
+            signal_price: 0.50,
+            arrival_mid,
+            fill_time_ms: 0,
+            markout_mids: [None; 3],
+        }
+    }
+
+    #[test]
+    fn test_slippage_and_markouts() {
+        let mut tca = TcaAnalyzer::new();
+        tca.record_fill(fill("t1", 0.51, 0.50));
+
+        // Only the 1s and 10s horizons have elapsed
+        tca.observe_mid("t1", 0.52, 10_000);
+
+        let stats = tca.market_stats("m1");
+        assert_eq!(stats.fills, 1);
+        assert!((stats.avg_arrival_slippage - 0.02).abs() < 1e-9);
+        assert!(stats.avg_markouts[0].unwrap() > 0.0);
+        assert!(stats.avg_markouts[2].is_none());
+        // Both were sampled on the one observation, 10s after the fill
+        assert_eq!(stats.avg_markout_lag_ms[..2], [Some(10_000), Some(10_000)]);
+    }
+
+    #[test]
+    fn test_fills_are_dropped_once_every_horizon_is_sampled() {
+        let mut tca = TcaAnalyzer::new();
+        tca.record_fill(fill("t1", 0.51, 0.50));
+        tca.observe_mid("t1", 0.52, 10_000);
+        assert_eq!(tca.pending().len(), 1);
+        tca.observe_mid("t1", 0.49, 65_000);
+        assert!(tca.pending().is_empty());
+
+        // The statistics outlive the fill
+        let stats = tca.aggregate();
+        assert_eq!((stats.fills, tca.fill_count()), (1, 1));
+        assert!(stats.avg_markouts[2].unwrap() < 0.0);
+    }
+
+    #[test]
+    fn test_calibrated_fee_model_needs_enough_fills() {
+        let mut tca = TcaAnalyzer::new();
+        let base = FeeModel { maker_fee_bps: 0, taker_fee_bps: 100 };
+        for _ in 0..MIN_CALIBRATION_FILLS - 1 {
+            tca.record_fill(fill("t1", 0.515, 0.50));
+        }
+        assert_eq!(tca.calibrated_fee_model(base.clone()).taker_fee_bps, 100);
+        tca.record_fill(fill("t1", 0.515, 0.50));
+        assert_eq!(tca.calibrated_fee_model(base.clone()).taker_fee_bps, 300, "3% paid over the arrival mid");
+
+        // Never below the venue's own fee
+        let mut cheap = TcaAnalyzer::new();
+        for _ in 0..MIN_CALIBRATION_FILLS {
+            cheap.record_fill(fill("t1", 0.5, 0.50));
+        }
+        assert_eq!(cheap.calibrated_fee_model(base).taker_fee_bps, 100);
+    }
+}
@@ -0,0 +1,33 @@
+// Test fixtures shared by the module tests
+// Override fields with struct update syntax, e.g. `Market { id: .., ..market() }`
+
+use crate::types::{Market, OrderBook, PriceLevel};
+
+/// A live Yes/No market "m1" at even odds, tokens "y" / "n", 2% taker fee and $1000 of liquidity
+pub fn market() -> Market {
+    Market {
+        id: "m1".to_string(),
+        question: String::new(),
+        slug: String::new(),
+        outcomes: vec!["Yes".to_string(), "No".to_string()],
+        outcome_prices: vec![0.5, 0.5],
+        clob_token_ids: vec!["y".to_string(), "n".to_string()],
+        condition_id: String::new(),
+        best_bid: None,
+        best_ask: None,
+        maker_base_fee: 0,
+        taker_base_fee: 200,
+        liquidity: 1000.0,
+        volume_24hr: 0.0,
+        active: true,
+        accepting_orders: true,
+        resolution_source: String::new(),
+        end_date: None,
+    }
+}
+
+/// A book on `token_id` with (price, size) levels, best first
+pub fn book(token_id: &str, bids: &[(f64, f64)], asks: &[(f64, f64)]) -> OrderBook {
+    let levels = |l: &[(f64, f64)]| l.iter().map(|&(price, size)| PriceLevel { price, size }).collect();
+    OrderBook { token_id: token_id.to_string(), bids: levels(bids), asks: levels(asks), timestamp: 0 }
+}
@@ -0,0 +1,90 @@
+//! Entry throttling
+//!
+//! Caps the number of concurrently open bundles and the rate of new entries
+//! (per minute and per hour), checked before execution, so a burst of
+//! correlated signals in one tick cannot deploy the whole bankroll at once.
+
+use std::collections::VecDeque;
+
+const MINUTE_SECS: u64 = 60;
+const HOUR_SECS: u64 = 3600;
+
+/// Reason an entry was throttled
+#[derive(Debug, Clone, PartialEq)]
+pub enum ThrottleError {
+    MaxOpenBundles(usize),
+    PerMinute(usize),
+    PerHour(usize),
+}
+
+impl std::fmt::Display for ThrottleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MaxOpenBundles(max) => write!(f, "{} bundles already open", max),
+            Self::PerMinute(max) => write!(f, "{} entries in the last minute", max),
+            Self::PerHour(max) => write!(f, "{} entries in the last hour", max),
+        }
+    }
+}
+
+impl std::error::Error for ThrottleError {}
+
+/// Open-bundle and entry-rate limits (0 = unlimited)
+#[derive(Debug, Clone, Default)]
+pub struct EntryThrottle {
+    pub max_open_bundles: usize,
+    pub max_per_minute: usize,
+    pub max_per_hour: usize,
+    /// Entry timestamps within the last hour
+    entries: VecDeque<u64>,
+}
+
+impl EntryThrottle {
+    pub fn new(max_open_bundles: usize, max_per_minute: usize, max_per_hour: usize) -> Self {
+        Self { max_open_bundles, max_per_minute, max_per_hour, entries: VecDeque::new() }
+    }
+
+    /// Whether a new bundle may open at `now` with `open_bundles` already open
+    pub fn check(&mut self, now: u64, open_bundles: usize) -> Result<(), ThrottleError> {
+        while self.entries.front().is_some_and(|t| now.saturating_sub(*t) >= HOUR_SECS) {
+            self.entries.pop_front();
+        }
+        if self.max_open_bundles > 0 && open_bundles >= self.max_open_bundles {
+            return Err(ThrottleError::MaxOpenBundles(self.max_open_bundles));
+        }
+        let last_minute = self.entries.iter().filter(|t| now.saturating_sub(**t) < MINUTE_SECS).count();
+        if self.max_per_minute > 0 && last_minute >= self.max_per_minute {
+            return Err(ThrottleError::PerMinute(self.max_per_minute));
+        }
+        if self.max_per_hour > 0 && self.entries.len() >= self.max_per_hour {
+            return Err(ThrottleError::PerHour(self.max_per_hour));
+        }
+        Ok(())
+    }
+
+    /// Count a bundle entry
+    pub fn record_entry(&mut self, now: u64) {
+        self.entries.push_back(now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_limits() {
+        let mut throttle = EntryThrottle::new(3, 2, 4);
+        assert!(throttle.check(0, 0).is_ok());
+        throttle.record_entry(0);
+        throttle.record_entry(10);
+        assert_eq!(throttle.check(20, 2), Err(ThrottleError::PerMinute(2)));
+        assert_eq!(throttle.check(70, 3), Err(ThrottleError::MaxOpenBundles(3)));
+        assert!(throttle.check(70, 2).is_ok());
+        throttle.record_entry(70);
+        throttle.record_entry(200);
+        assert_eq!(throttle.check(300, 0), Err(ThrottleError::PerHour(4)));
+        // The first entries age out of the hour window
+        assert!(throttle.check(3610, 0).is_ok());
+    }
+}
@@ -0,0 +1,135 @@
+//! Trade Flow Module
+//!
+//! Recent trade prints per token, fed by REST polling (`MarketClient::get_trades`)
+//! and the WebSocket trade channel. Consumed by:
+//! - the fill simulator (`PassiveOrder::on_trade` replay)
+//! - microstructure signals (order flow imbalance, traded volume)
+//! - adverse-selection estimation (print-to-print price volatility)
+
+use crate::fills::PassiveOrder;
+use crate::types::{Side, Trade};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Maximum prints kept per token
+const MAX_PRINTS_PER_TOKEN: usize = 1000;
+
+/// Rolling store of recent prints
+#[derive(Debug, Default)]
+pub struct TradeFlow {
+    prints: HashMap<String, VecDeque<Trade>>,
+    seen: HashSet<String>,
+}
+
+impl TradeFlow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ingest a print; duplicates (same id) are ignored. Returns true if new.
+    pub fn ingest(&mut self, trade: Trade) -> bool {
+        if !trade.id.is_empty() && !self.seen.insert(trade.id.clone()) {
+            return false;
+        }
+        let queue = self.prints.entry(trade.token_id.clone()).or_default();
+        // Keep prints ordered by time; REST pages may arrive newest-first
+        let pos = queue.iter().rposition(|t| t.timestamp <= trade.timestamp).map_or(0, |p| p + 1);
+        queue.insert(pos, trade);
+        if queue.len() > MAX_PRINTS_PER_TOKEN {
+            if let Some(old) = queue.pop_front() {
+                self.seen.remove(&old.id);
+            }
+        }
+        true
+    }
+
+    /// Ingest a batch, returning the number of new prints
+    pub fn ingest_all(&mut self, trades: Vec<Trade>) -> usize {
+        trades.into_iter().map(|t| self.ingest(t)).filter(|new| *new).count()
+    }
+
+    /// Prints for a token at or after `since` (oldest first)
+    pub fn recent(&self, token_id: &str, since: u64) -> impl Iterator<Item = &Trade> {
+        self.prints.get(token_id)
+            .into_iter()
+            .flat_map(|q| q.iter())
+            .filter(move |t| t.timestamp >= since)
+    }
+
+    /// Traded volume by aggressor side since `since`
+    pub fn volume(&self, token_id: &str, side: Side, since: u64) -> f64 {
+        self.recent(token_id, since).filter(|t| t.side == side).map(|t| t.size).sum()
+    }
+
+    /// Order flow imbalance in [-1, 1]: (buy - sell) / (buy + sell) since `since`
+    pub fn imbalance(&self, token_id: &str, since: u64) -> Option<f64> {
+        let buys = self.volume(token_id, Side::Buy, since);
+        let sells = self.volume(token_id, Side::Sell, since);
+        let total = buys + sells;
+        (total > 0.0).then(|| (buys - sells) / total)
+    }
+
+    /// Std dev of relative price changes between consecutive prints since `since`.
+    ///
+    /// Used as the adverse-move estimate for `LatencyModel::adverse_move_std`.
+    pub fn adverse_move_std(&self, token_id: &str, since: u64) -> Option<f64> {
+        let prices: Vec<f64> = self.recent(token_id, since).map(|t| t.price).filter(|p| *p > 0.0).collect();
+        if prices.len() < 3 {
+            return None;
+        }
+        let moves: Vec<f64> = prices.windows(2).map(|w| (w[1] - w[0]) / w[0]).collect();
+        let mean = moves.iter().sum::<f64>() / moves.len() as f64;
+        let var = moves.iter().map(|m| (m - mean).powi(2)).sum::<f64>() / (moves.len() - 1) as f64;
+        Some(var.sqrt())
+    }
+
+    /// Replay prints since `since` into a resting order; returns size filled
+    #[allow(dead_code)]
+    pub fn replay_into(&self, order: &mut PassiveOrder, since: u64) -> f64 {
+        let prints: Vec<&Trade> = self.recent(&order.token_id, since).collect();
+        prints.into_iter().map(|t| order.on_trade(t)).sum()
+    }
+
+    /// Drop prints older than `cutoff`
+    pub fn prune(&mut self, cutoff: u64) {
+        for queue in self.prints.values_mut() {
+            while queue.front().is_some_and(|t| t.timestamp < cutoff) {
+                if let Some(old) = queue.pop_front() {
+                    self.seen.remove(&old.id);
+                }
+            }
+        }
+        self.prints.retain(|_, q| !q.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn print(id: &str, price: f64, size: f64, side: Side, ts: u64) -> Trade {
+        Trade { id: id.to_string(), token_id: "t1".to_string(), price, size, side, timestamp: ts }
+    }
+
+    #[test]
+    fn test_flow_metrics() {
+        let mut flow = TradeFlow::new();
+        // Newest-first page, with a duplicate
+        let added = flow.ingest_all(vec![
+            print("c", 0.52, 30.0, Side::Buy, 30),
+            print("b", 0.51, 10.0, Side::Sell, 20),
+            print("a", 0.50, 60.0, Side::Buy, 10),
+            print("a", 0.50, 60.0, Side::Buy, 10),
+        ]);
+        assert_eq!(added, 3);
+        let ts: Vec<u64> = flow.recent("t1", 0).map(|t| t.timestamp).collect();
+        assert_eq!(ts, vec![10, 20, 30]);
+
+        assert_eq!(flow.volume("t1", Side::Buy, 0), 90.0);
+        assert!((flow.imbalance("t1", 0).unwrap() - 0.8).abs() < 1e-9);
+        assert!((flow.imbalance("t1", 15).unwrap() - 0.5).abs() < 1e-9);
+        assert!(flow.adverse_move_std("t1", 0).is_some());
+
+        flow.prune(25);
+        assert_eq!(flow.recent("t1", 0).count(), 1);
+    }
+}
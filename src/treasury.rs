@@ -0,0 +1,227 @@
+//! Idle balance treasury
+//!
+//! USDC sitting between trades earns nothing. With the treasury enabled,
+//! liquid balance above a buffer is supplied to a whitelisted yield venue
+//! once the agent has been idle for a while, and withdrawn just in time when
+//! a trade needs more than the liquid balance. Deposits and withdrawals are
+//! Smart Account calls on the venue's pool, so they must pass the permission
+//! scope like any trade; total deposits and each move are capped, and every
+//! move is journaled (the treasury's audit trail) and replayed on restart.
+
+use crate::config::TreasuryConfig;
+use crate::permission_guard::ContractCall;
+use crate::storage::JsonlStore;
+use serde::{Deserialize, Serialize};
+
+/// Yield venues the treasury may use: (config name, pool contract)
+pub const WHITELISTED_VENUES: &[(&str, &str)] = &[
+    // Aave v3 Pool on Polygon PoS
+    ("aave-v3-polygon", "0x794a61358D6845594F94dc1DB02A252b5b4814aD"),
+];
+
+/// Pool methods for each direction (Aave v3 `supply` / `withdraw`)
+pub const DEPOSIT_METHOD: &str = "supply";
+pub const WITHDRAW_METHOD: &str = "withdraw";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MoveKind {
+    Deposit,
+    Withdraw,
+}
+
+/// Outcome of a treasury move
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum MoveStatus {
+    Planned,
+    Settled,
+    Failed { reason: String },
+}
+
+/// One deposit into or withdrawal from the yield venue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreasuryMove {
+    pub move_id: String,
+    pub kind: MoveKind,
+    pub venue: String,
+    pub amount: f64,
+    pub requested_at: u64,
+    /// What triggered it ("idle balance", "trade needs $x")
+    pub reason: String,
+    pub status: MoveStatus,
+}
+
+/// Plans, settles and journals moves to and from the yield venue
+#[derive(Debug)]
+pub struct Treasury {
+    config: TreasuryConfig,
+    pool: Option<&'static str>,
+    deposited: f64,
+    last_activity: u64,
+    next_id: u64,
+    journal: Option<JsonlStore>,
+}
+
+impl Treasury {
+    pub fn new(config: TreasuryConfig) -> Self {
+        let pool = WHITELISTED_VENUES.iter().find(|(name, _)| *name == config.venue).map(|(_, pool)| *pool);
+        Self { config, pool, deposited: 0.0, last_activity: 0, next_id: 0, journal: None }
+    }
+
+    /// Journal moves to `journal`, restoring the deposited balance from it
+    pub fn with_journal(mut self, journal: JsonlStore) -> Self {
+        let moves: Vec<TreasuryMove> = journal.load().unwrap_or_else(|e| {
+            eprintln!("⚠️ [Treasury] Failed to read treasury journal: {}", e);
+            Vec::new()
+        });
+        for mv in moves.iter().filter(|m| m.status == MoveStatus::Settled) {
+            self.apply(mv);
+        }
+        self.next_id = moves.len() as u64;
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Whether the configured venue is whitelisted (disabled treasuries are valid)
+    pub fn validate(&self) -> Result<(), String> {
+        if self.config.enabled && self.pool.is_none() {
+            return Err(format!("treasury venue {:?} is not whitelisted (known: {})", self.config.venue,
+                WHITELISTED_VENUES.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", ")));
+        }
+        Ok(())
+    }
+
+    /// USDC currently supplied to the venue
+    pub fn deposited(&self) -> f64 {
+        self.deposited
+    }
+
+    /// Record trading activity; deposits wait `idle_secs` after the last one
+    pub fn touch(&mut self, now: u64) {
+        self.last_activity = now;
+    }
+
+    fn plan(&mut self, kind: MoveKind, amount: f64, reason: String, now: u64) -> TreasuryMove {
+        self.next_id += 1;
+        TreasuryMove {
+            move_id: format!("treasury-{}-{}", now, self.next_id),
+            kind,
+            venue: self.config.venue.clone(),
+            amount,
+            requested_at: now,
+            reason,
+            status: MoveStatus::Planned,
+        }
+    }
+
+    /// Deposit liquid USDC above the buffer once idle, within the caps
+    pub fn plan_deposit(&mut self, liquid: f64, now: u64) -> Option<TreasuryMove> {
+        if !self.config.enabled || self.pool.is_none() || now.saturating_sub(self.last_activity) < self.config.idle_secs {
+            return None;
+        }
+        let amount = (liquid - self.config.liquid_buffer)
+            .min(self.config.max_deposited - self.deposited)
+            .min(self.config.max_move);
+        (amount >= self.config.min_move).then(|| self.plan(MoveKind::Deposit, amount, "idle balance".to_string(), now))
+    }
+
+    /// Withdraw just enough for a trade needing `required` when `liquid` falls short
+    pub fn plan_withdrawal(&mut self, required: f64, liquid: f64, now: u64) -> Option<TreasuryMove> {
+        self.touch(now);
+        let shortfall = required - liquid;
+        if self.pool.is_none() || shortfall <= 0.0 || self.deposited <= 0.0 {
+            return None;
+        }
+        let amount = shortfall.min(self.deposited);
+        Some(self.plan(MoveKind::Withdraw, amount, format!("trade needs ${:.2}", required), now))
+    }
+
+    /// The Smart Account call that executes a move on the venue's pool
+    pub fn call(&self, mv: &TreasuryMove) -> ContractCall {
+        ContractCall {
+            target: self.pool.unwrap_or_default().to_string(),
+            method: match mv.kind {
+                MoveKind::Deposit => DEPOSIT_METHOD,
+                MoveKind::Withdraw => WITHDRAW_METHOD,
+            }.to_string(),
+            value: mv.amount,
+        }
+    }
+
+    fn apply(&mut self, mv: &TreasuryMove) {
+        match mv.kind {
+            MoveKind::Deposit => self.deposited += mv.amount,
+            MoveKind::Withdraw => self.deposited = (self.deposited - mv.amount).max(0.0),
+        }
+    }
+
+    /// Record the outcome of a planned move and journal it
+    pub fn settle(&mut self, mut mv: TreasuryMove, outcome: Result<(), String>) -> TreasuryMove {
+        mv.status = match outcome {
+            Ok(()) => {
+                self.apply(&mv);
+                MoveStatus::Settled
+            }
+            Err(reason) => MoveStatus::Failed { reason },
+        };
+        if let Some(journal) = &self.journal {
+            if let Err(e) = journal.append(&mv) {
+                eprintln!("⚠️ [Treasury] Failed to journal treasury move: {}", e);
+            }
+        }
+        mv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> TreasuryConfig {
+        TreasuryConfig {
+            enabled: true,
+            venue: "aave-v3-polygon".to_string(),
+            liquid_buffer: 20.0,
+            idle_secs: 300,
+            min_move: 5.0,
+            max_move: 50.0,
+            max_deposited: 60.0,
+        }
+    }
+
+    #[test]
+    fn test_deposit_idle_and_withdraw_just_in_time() {
+        let dir = std::env::temp_dir().join(format!("arbishark_treasury_{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+        let journal = JsonlStore::open(dir, "treasury.jsonl").unwrap();
+        let mut treasury = Treasury::new(config()).with_journal(journal.clone());
+        assert!(treasury.validate().is_ok());
+
+        // Recent activity: not idle yet
+        treasury.touch(1000);
+        assert!(treasury.plan_deposit(100.0, 1200).is_none());
+
+        // Capped per move, then by the total cap
+        let deposit = treasury.plan_deposit(100.0, 1300).unwrap();
+        assert_eq!((deposit.kind, deposit.amount), (MoveKind::Deposit, 50.0));
+        assert_eq!(treasury.call(&deposit).method, "supply");
+        treasury.settle(deposit, Ok(()));
+        let second = treasury.plan_deposit(50.0, 1300).unwrap();
+        assert_eq!(second.amount, 10.0);
+        treasury.settle(second, Err("Target not in permission scope".to_string()));
+        assert_eq!(treasury.deposited(), 50.0);
+
+        // Withdraw only the shortfall, which also resets the idle timer
+        assert!(treasury.plan_withdrawal(10.0, 30.0, 2000).is_none());
+        let withdrawal = treasury.plan_withdrawal(40.0, 30.0, 2000).unwrap();
+        assert_eq!((withdrawal.kind, withdrawal.amount), (MoveKind::Withdraw, 10.0));
+        treasury.settle(withdrawal, Ok(()));
+        assert!(treasury.plan_deposit(100.0, 2100).is_none());
+
+        let restored = Treasury::new(config()).with_journal(journal);
+        assert_eq!(restored.deposited(), 40.0);
+        assert!(Treasury::new(TreasuryConfig { venue: "degen-farm".to_string(), ..config() }).validate().is_err());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -11,6 +11,8 @@ pub struct Market {
     pub outcomes : Vec<String> , // ["yes" , "no"]
     pub outcome_prices : Vec<f64> , // [0.5 , 0.5]
     pub clob_token_ids : Vec<String> ,  // Token Ids for trading 
+    #[serde(default)]
+    pub condition_id : String , // CTF condition ID (keys trade prints in the data API)
     pub best_bid : Option<f64> , // highest by price across outcomes 
     pub best_ask : Option<f64> ,  // lowest sell price across outcomes
     pub maker_base_fee : u32 ,   // In basis points (eg : 0) -> fees if you add liquidity 
@@ -18,7 +20,11 @@ pub struct Market {
     pub liquidity : f64 ,  // Depth of the market 
     pub volume_24hr : f64 , // trading activity 
     pub active : bool ,  /// is market live ? 
-    pub accepting_orders : bool  // can you trade right now ? 
+    pub accepting_orders : bool ,  // can you trade right now ? 
+    #[serde(default)]
+    pub resolution_source : String , // where the outcome is decided (URL / authority), empty if unknown
+    #[serde(default)]
+    pub end_date : Option<u64> , // unix seconds the market is scheduled to resolve, None if unknown
 }
 
 // Single price level in order book 
@@ -80,20 +86,39 @@ pub enum Side {
 // core invariant -> YES_price + NO_price ≈ 1
 // example arbitrage _> yes = 0.48 , no = 0.47 -> Sum = 0.95 -> one of them settles at $1
 // guarenteed profit = 0.05 - fees 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ArbitrageSignal {
     pub market_id : String , 
     pub spread : f64 ,  // how much the price deviates from 1 
     pub edge : f64 , // Expected profit per unit 
     pub recommended_side : Side , 
     pub yes_price : f64 , 
-    pub no_price : f64 
+    pub no_price : f64 ,
+    /// Edge at increasing sizes (from the books; empty until they are walked)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub edge_curve : Vec<EdgePoint> ,
+    /// Age (ms) of the quote behind each leg when the signal was priced (empty until books are fetched)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub quote_ages_ms : Vec<u64> ,
+}
+
+/// Edge of the bundle at one size, from walking the books
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct EdgePoint {
+    /// Size per leg
+    pub size: f64,
+    /// Profit per unit after taker fees
+    pub edge: f64,
+    /// edge × size
+    pub profit: f64,
 }
 
 // Execution resutl 
 #[derive(Debug, Clone)]
 pub struct ExecutionResult {
     pub filed_size : f64  , 
+    pub requested_size : f64 , 
+    pub remaining_size : f64 ,  // unfilled part of requested_size
     pub execution_price : f64 , 
     pub fee_paid : f64 , 
     pub slippage : f64 , 
@@ -102,6 +127,22 @@ pub struct ExecutionResult {
 }
 
 
+impl ExecutionResult {
+    /// Fold a follow-up fill (e.g. a chased remainder) into this result
+    pub fn absorb(&mut self, other: &ExecutionResult) {
+        let filled = self.filed_size + other.filed_size;
+        if filled > 0.0 {
+            self.execution_price = (self.execution_price * self.filed_size
+                + other.execution_price * other.filed_size) / filled;
+            self.slippage = (self.slippage * self.filed_size + other.slippage * other.filed_size) / filled;
+        }
+        self.filed_size = filled;
+        self.remaining_size = (self.requested_size - filled).max(0.0);
+        self.fee_paid += other.fee_paid;
+        self.total_cost += other.total_cost;
+    }
+}
+
 // Implementaion for Market 
 
 impl Market {
@@ -120,7 +161,7 @@ impl Market {
 
     // get YES token price (assumes binary market)
     pub fn yes_price(&self) -> f64 {
-        self.outcome_prices.get(0).copied().unwrap_or(0.0)
+        self.outcome_prices.first().copied().unwrap_or(0.0)
     }
 
 
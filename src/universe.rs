@@ -0,0 +1,122 @@
+//! Warm-start cache of the market universe: scanned as stale right after a
+//! restart while the first live fetch runs, ignored if too old or from another source
+
+use crate::config::UniverseCacheConfig;
+use crate::types::Market;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The universe as last fetched
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UniverseSnapshot {
+    pub saved_at: u64,
+    /// Data source mode the markets came from
+    pub source: String,
+    pub markets: Vec<Market>,
+}
+
+impl UniverseSnapshot {
+    pub fn age_secs(&self, now: u64) -> u64 {
+        now.saturating_sub(self.saved_at)
+    }
+}
+
+/// Snapshot file of the market universe
+#[derive(Debug)]
+pub struct UniverseCache {
+    config: UniverseCacheConfig,
+    path: PathBuf,
+    source: String,
+    last_saved: Option<u64>,
+}
+
+impl UniverseCache {
+    /// Cache for markets from `source` under `data_dir`
+    pub fn open(data_dir: &str, source: &str, config: UniverseCacheConfig) -> io::Result<Self> {
+        fs::create_dir_all(data_dir)?;
+        Ok(Self { config, path: Path::new(data_dir).join("universe.json"), source: source.to_string(), last_saved: None })
+    }
+
+    /// The cached universe, if there is a usable one
+    pub fn load(&self, now: u64) -> Option<UniverseSnapshot> {
+        if !self.config.enabled {
+            return None;
+        }
+        let json = fs::read_to_string(&self.path).ok()?;
+        let snapshot: UniverseSnapshot = serde_json::from_str(&json)
+            .map_err(|e| eprintln!("⚠️ [Universe] Ignoring unreadable cache: {}", e))
+            .ok()?;
+        let usable = snapshot.source == self.source
+            && !snapshot.markets.is_empty()
+            && snapshot.age_secs(now) <= self.config.max_age_secs;
+        usable.then_some(snapshot)
+    }
+
+    /// Snapshot a freshly fetched universe (at most every `save_interval_secs`); true if written
+    pub fn save(&mut self, markets: &[Market], now: u64) -> io::Result<bool> {
+        let due = self.last_saved.is_none_or(|t| now.saturating_sub(t) >= self.config.save_interval_secs);
+        if !self.config.enabled || markets.is_empty() || !due {
+            return Ok(false);
+        }
+        let snapshot = UniverseSnapshot { saved_at: now, source: self.source.clone(), markets: markets.to_vec() };
+        let json = serde_json::to_string(&snapshot).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        // Write-then-rename so a crash never leaves a torn snapshot
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, json)?;
+        fs::rename(&tmp, &self.path)?;
+        self.last_saved = Some(now);
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn market(id: &str) -> Market {
+        Market { id: id.to_string(), question: format!("Question {}?", id), outcome_prices: vec![0.48, 0.49], ..testing::market() }
+    }
+
+    fn config() -> UniverseCacheConfig {
+        UniverseCacheConfig { max_age_secs: 3600, save_interval_secs: 300, ..Default::default() }
+    }
+
+    fn dir(test: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("arbishark_universe_{}_{}", test, std::process::id()));
+        dir.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let dir = dir("roundtrip");
+        let mut cache = UniverseCache::open(&dir, "polymarket", config()).unwrap();
+        assert!(cache.load(0).is_none());
+        assert!(cache.save(&[market("m1"), market("m2")], 1_000).unwrap());
+        assert!(!cache.save(&[market("m1")], 1_100).unwrap(), "rate limited");
+        let snapshot = cache.load(2_000).unwrap();
+        assert_eq!(snapshot.markets.len(), 2);
+        assert_eq!(snapshot.markets[1].question, "Question m2?");
+        assert_eq!(snapshot.age_secs(2_000), 1_000);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_snapshot_expires() {
+        let dir = dir("expiry");
+        let mut cache = UniverseCache::open(&dir, "polymarket", config()).unwrap();
+        cache.save(&[market("m1")], 1_000).unwrap();
+        assert!(cache.load(5_000).is_none(), "older than max_age_secs");
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_other_sources_snapshot_ignored() {
+        let dir = dir("source");
+        UniverseCache::open(&dir, "polymarket", config()).unwrap().save(&[market("m1")], 1_000).unwrap();
+        assert!(UniverseCache::open(&dir, "sandbox", config()).unwrap().load(2_000).is_none());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
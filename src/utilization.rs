@@ -0,0 +1,121 @@
+//! Capital utilization metrics
+//!
+//! Tracks deployed vs idle capital over time (area under the open-notional
+//! curve), time-to-recycle per bundle (first leg opened → last leg closed)
+//! and allowance utilization, to guide sizing and limit configuration.
+
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Snapshot served by `/api/stats`, GraphQL and the daily report
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UtilizationReport {
+    /// Time-weighted share of capital deployed in open positions (0-1)
+    pub capital_utilization: f64,
+    /// Time-weighted average open notional ($)
+    pub avg_deployed: f64,
+    /// Average seconds from bundle open to bundle fully closed
+    pub avg_recycle_secs: f64,
+    pub bundles_recycled: usize,
+    /// Share of the daily allowance spent so far this period (0-1)
+    pub allowance_utilization: f64,
+    /// Highest allowance utilization seen in any period (0-1)
+    pub peak_allowance_utilization: f64,
+}
+
+/// Capital utilization tracker
+#[derive(Debug, Default)]
+pub struct CapitalTracker {
+    /// Last observation (timestamp, open notional, capital base)
+    last: Option<(u64, f64, f64)>,
+    /// ∫ open notional dt ($·s)
+    deployed_area: f64,
+    /// ∫ capital base dt ($·s)
+    capital_area: f64,
+    elapsed_secs: u64,
+    /// Open bundles by market_id → opened_at
+    open_bundles: HashMap<String, u64>,
+    recycle_secs: Vec<u64>,
+    allowance_utilization: f64,
+    peak_allowance_utilization: f64,
+}
+
+impl CapitalTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sample open notional and capital base (step function between samples)
+    pub fn observe(&mut self, now: u64, open_notional: f64, capital: f64) {
+        if let Some((t, notional, base)) = self.last {
+            let dt = now.saturating_sub(t);
+            self.deployed_area += notional * dt as f64;
+            self.capital_area += base * dt as f64;
+            self.elapsed_secs += dt;
+        }
+        self.last = Some((now, open_notional, capital));
+    }
+
+    /// Record allowance usage for the current period
+    pub fn observe_allowance(&mut self, spent: f64, limit: f64) {
+        self.allowance_utilization = if limit > 0.0 { (spent / limit).clamp(0.0, 1.0) } else { 0.0 };
+        self.peak_allowance_utilization = self.peak_allowance_utilization.max(self.allowance_utilization);
+    }
+
+    /// A leg of a bundle was opened (first leg starts the clock)
+    pub fn bundle_opened(&mut self, market_id: &str, now: u64) {
+        self.open_bundles.entry(market_id.to_string()).or_insert(now);
+    }
+
+    /// Legs of a bundle closed; `still_open` = legs of that market left open
+    pub fn bundle_leg_closed(&mut self, market_id: &str, now: u64, still_open: usize) {
+        if still_open > 0 {
+            return;
+        }
+        if let Some(opened) = self.open_bundles.remove(market_id) {
+            self.recycle_secs.push(now.saturating_sub(opened));
+        }
+    }
+
+    pub fn report(&self) -> UtilizationReport {
+        UtilizationReport {
+            capital_utilization: if self.capital_area > 0.0 { self.deployed_area / self.capital_area } else { 0.0 },
+            avg_deployed: if self.elapsed_secs > 0 { self.deployed_area / self.elapsed_secs as f64 } else { 0.0 },
+            avg_recycle_secs: if self.recycle_secs.is_empty() {
+                0.0
+            } else {
+                self.recycle_secs.iter().sum::<u64>() as f64 / self.recycle_secs.len() as f64
+            },
+            bundles_recycled: self.recycle_secs.len(),
+            allowance_utilization: self.allowance_utilization,
+            peak_allowance_utilization: self.peak_allowance_utilization,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_weighted_utilization() {
+        let mut tracker = CapitalTracker::new();
+        tracker.observe(0, 0.0, 10.0);
+        tracker.observe(100, 5.0, 10.0); // idle for 100s
+        tracker.observe(200, 0.0, 10.0); // half deployed for 100s
+        let report = tracker.report();
+        assert!((report.capital_utilization - 0.25).abs() < 1e-9);
+        assert!((report.avg_deployed - 2.5).abs() < 1e-9);
+
+        tracker.bundle_opened("m1", 100);
+        tracker.bundle_opened("m1", 105);
+        tracker.bundle_leg_closed("m1", 150, 1);
+        tracker.bundle_leg_closed("m1", 200, 0);
+        assert_eq!(tracker.report().bundles_recycled, 1);
+        assert_eq!(tracker.report().avg_recycle_secs, 100.0);
+
+        tracker.observe_allowance(8.0, 10.0);
+        tracker.observe_allowance(0.0, 10.0);
+        assert_eq!(tracker.report().peak_allowance_utilization, 0.8);
+    }
+}
@@ -0,0 +1,527 @@
+//! Execution venue profiles and signed order submission
+//!
+//! A `VenueProfile` bundles everything that differs between Polymarket
+//! production and its staging sandbox: Gamma / CLOB / data API base URLs,
+//! the chain and CTF Exchange contract orders are signed against (resolved
+//! through the contract registry), and the L2 API credentials (read from environment variables named in config).
+//! `OrderClient` builds a CTF Exchange order, signs it as EIP-712 typed data
+//! through the configured `Signer`, and posts it to the profile's CLOB with
+//! HMAC-authenticated headers, so `mode = "sandbox"` exercises the full
+//! signed-order path against test infrastructure without mainnet funds.
+
+use crate::config::SandboxConfig;
+use crate::registry::{self, Contract, Registry, RegistryError};
+use crate::signer::{Signer, SignerError};
+use crate::types::Side;
+use base64::engine::general_purpose::URL_SAFE;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use sha3::{Digest, Keccak256};
+
+/// USDC and outcome tokens both use 6 decimals
+const AMOUNT_DECIMALS: i32 = 6;
+const ORDER_TYPE: &str = "Order(uint256 salt,address maker,address signer,address taker,uint256 tokenId,uint256 makerAmount,uint256 takerAmount,uint256 expiration,uint256 nonce,uint256 feeRateBps,uint8 side,uint8 signatureType)";
+const DOMAIN_TYPE: &str = "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+const DOMAIN_NAME: &str = "Polymarket CTF Exchange";
+const DOMAIN_VERSION: &str = "1";
+/// The CLOB only honours a GTD expiration after a one-minute security buffer
+const GTD_BUFFER_SECS: u64 = 60;
+
+/// L2 API credentials for authenticated CLOB endpoints
+#[derive(Debug, Clone)]
+pub struct ApiCredentials {
+    pub api_key: String,
+    /// Base64 (URL-safe) HMAC secret
+    pub secret: String,
+    pub passphrase: String,
+}
+
+impl ApiCredentials {
+    /// Read credentials from the named environment variables
+    pub fn from_env(key_env: &str, secret_env: &str, passphrase_env: &str) -> Result<Self, VenueError> {
+        let read = |name: &str| std::env::var(name).map_err(|_| VenueError::Signer(SignerError::MissingEnv(name.to_string())));
+        Ok(Self { api_key: read(key_env)?, secret: read(secret_env)?, passphrase: read(passphrase_env)? })
+    }
+
+    /// POLY_* headers for a request; the signature is HMAC-SHA256 over
+    /// `timestamp + method + path + body`, URL-safe base64 encoded
+    pub fn l2_headers(&self, address: &str, timestamp: u64, method: &str, path: &str, body: &str) -> Result<Vec<(&'static str, String)>, VenueError> {
+        let secret = URL_SAFE.decode(&self.secret).map_err(|e| VenueError::Credentials(e.to_string()))?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(&secret).map_err(|e| VenueError::Credentials(e.to_string()))?;
+        mac.update(format!("{}{}{}{}", timestamp, method, path, body).as_bytes());
+        Ok(vec![
+            ("POLY_ADDRESS", address.to_string()),
+            ("POLY_SIGNATURE", URL_SAFE.encode(mac.finalize().into_bytes())),
+            ("POLY_TIMESTAMP", timestamp.to_string()),
+            ("POLY_API_KEY", self.api_key.clone()),
+            ("POLY_PASSPHRASE", self.passphrase.clone()),
+        ])
+    }
+}
+
+/// Endpoints and signing domain of one Polymarket environment
+#[derive(Debug, Clone)]
+pub struct VenueProfile {
+    pub name: &'static str,
+    pub gamma_url: String,
+    pub clob_url: String,
+    pub trades_url: String,
+    pub chain_id: u64,
+    pub exchange_address: String,
+}
+
+impl VenueProfile {
+    pub fn mainnet(registry: &Registry) -> Result<Self, RegistryError> {
+        Ok(Self {
+            name: "mainnet",
+            gamma_url: "https://gamma-api.polymarket.com/events?limit=20&active=true&closed=false".to_string(),
+            clob_url: "https://clob.polymarket.com".to_string(),
+            trades_url: "https://data-api.polymarket.com/trades".to_string(),
+            chain_id: registry::POLYGON,
+            exchange_address: registry.resolve(registry::POLYGON, Contract::Exchange)?.to_string(),
+        })
+    }
+
+    pub fn sandbox(config: &SandboxConfig, registry: &Registry) -> Result<Self, RegistryError> {
+        Ok(Self {
+            name: "sandbox",
+            gamma_url: config.gamma_url.clone(),
+            clob_url: config.clob_url.trim_end_matches('/').to_string(),
+            trades_url: config.trades_url.clone(),
+            chain_id: config.chain_id,
+            exchange_address: registry.resolve(config.chain_id, Contract::Exchange)?.to_string(),
+        })
+    }
+
+    /// Order book endpoint used by the market data client
+    pub fn book_url(&self) -> String {
+        format!("{}/book", self.clob_url)
+    }
+
+    /// EIP-712 domain separator of the CTF Exchange
+    pub fn domain_separator(&self) -> Result<[u8; 32], VenueError> {
+        let mut encoded = keccak(DOMAIN_TYPE.as_bytes()).to_vec();
+        encoded.extend(keccak(DOMAIN_NAME.as_bytes()));
+        encoded.extend(keccak(DOMAIN_VERSION.as_bytes()));
+        encoded.extend(uint256(&self.chain_id.to_string())?);
+        encoded.extend(address(&self.exchange_address)?);
+        Ok(keccak(&encoded))
+    }
+}
+
+/// CTF Exchange limit order (EOA signature type)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Order {
+    pub salt: u64,
+    pub maker: String,
+    pub token_id: String,
+    /// Amount the maker gives (USDC for a buy, shares for a sell), 6 decimals
+    pub maker_amount: u128,
+    /// Amount the maker receives, 6 decimals
+    pub taker_amount: u128,
+    pub expiration: u64,
+    pub nonce: u64,
+    pub fee_rate_bps: u32,
+    pub side: Side,
+}
+
+impl Order {
+    /// Limit order for `size` shares at `price`
+    pub fn limit(maker: &str, token_id: &str, side: Side, price: f64, size: f64, salt: u64) -> Self {
+        let scale = 10f64.powi(AMOUNT_DECIMALS);
+        let shares = (size * scale).round() as u128;
+        let usdc = (size * price * scale).round() as u128;
+        let (maker_amount, taker_amount) = match side {
+            Side::Buy => (usdc, shares),
+            Side::Sell => (shares, usdc),
+        };
+        Self {
+            salt,
+            maker: maker.to_string(),
+            token_id: token_id.to_string(),
+            maker_amount,
+            taker_amount,
+            expiration: 0,
+            nonce: 0,
+            fee_rate_bps: 0,
+            side,
+        }
+    }
+
+    fn side_index(&self) -> u8 {
+        match self.side {
+            Side::Buy => 0,
+            Side::Sell => 1,
+        }
+    }
+
+    /// EIP-712 struct hash (maker signs for itself, open taker)
+    pub fn struct_hash(&self) -> Result<[u8; 32], VenueError> {
+        let mut encoded = keccak(ORDER_TYPE.as_bytes()).to_vec();
+        encoded.extend(uint256(&self.salt.to_string())?);
+        encoded.extend(address(&self.maker)?);
+        encoded.extend(address(&self.maker)?);
+        encoded.extend([0u8; 32]);
+        encoded.extend(uint256(&self.token_id)?);
+        encoded.extend(uint256(&self.maker_amount.to_string())?);
+        encoded.extend(uint256(&self.taker_amount.to_string())?);
+        encoded.extend(uint256(&self.expiration.to_string())?);
+        encoded.extend(uint256(&self.nonce.to_string())?);
+        encoded.extend(uint256(&self.fee_rate_bps.to_string())?);
+        encoded.extend(uint256(&self.side_index().to_string())?);
+        encoded.extend([0u8; 32]);
+        Ok(keccak(&encoded))
+    }
+}
+
+/// `POST /order` request body
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SignedOrderBody<'a> {
+    order: SignedOrder<'a>,
+    owner: &'a str,
+    order_type: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SignedOrder<'a> {
+    salt: u64,
+    maker: &'a str,
+    signer: &'a str,
+    taker: &'static str,
+    token_id: &'a str,
+    maker_amount: String,
+    taker_amount: String,
+    expiration: String,
+    nonce: String,
+    fee_rate_bps: String,
+    side: &'static str,
+    signature_type: u8,
+    signature: String,
+}
+
+#[derive(Debug)]
+pub enum VenueError {
+    Signer(SignerError),
+    Credentials(String),
+    Encoding(String),
+    Http(String),
+    Rejected { status: u16, body: String },
+}
+
+impl std::fmt::Display for VenueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Signer(e) => write!(f, "signer: {}", e),
+            Self::Credentials(e) => write!(f, "invalid API credentials: {}", e),
+            Self::Encoding(e) => write!(f, "order encoding: {}", e),
+            Self::Http(e) => write!(f, "request failed: {}", e),
+            Self::Rejected { status, body } => write!(f, "CLOB rejected order ({}): {}", status, body),
+        }
+    }
+}
+
+impl std::error::Error for VenueError {}
+
+/// An order as the venue records it (`GET /data/orders`)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VenueOrder {
+    pub order_id: String,
+    pub token_id: String,
+    pub side: Side,
+    pub price: f64,
+    pub original_size: f64,
+    pub size_matched: f64,
+    pub status: String,
+    pub created_at: u64,
+}
+
+/// One of our fills as the venue records it (`GET /data/trades`)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VenueFill {
+    pub trade_id: String,
+    /// Our order that filled (taker order, or our maker order in the match)
+    pub order_id: String,
+    pub token_id: String,
+    pub side: Side,
+    pub price: f64,
+    pub size: f64,
+    pub timestamp: u64,
+    /// Settlement transaction of the match
+    pub tx_hash: Option<String>,
+}
+
+/// A signed order the venue accepted
+#[derive(Debug, Clone, PartialEq)]
+pub struct PostedOrder {
+    pub order_id: String,
+    /// Settlement transactions of whatever matched on arrival
+    pub tx_hashes: Vec<String>,
+}
+
+impl PostedOrder {
+    fn parse(json: &serde_json::Value) -> Self {
+        Self {
+            order_id: json["orderID"].as_str().unwrap_or_default().to_string(),
+            tx_hashes: json["transactionsHashes"].as_array().into_iter().flatten()
+                .filter_map(|h| h.as_str().filter(|h| !h.is_empty()).map(str::to_string))
+                .collect(),
+        }
+    }
+}
+
+/// Decimal strings or numbers, as the CLOB mixes both
+fn number(value: &serde_json::Value) -> f64 {
+    value.as_f64().or_else(|| value.as_str().and_then(|s| s.parse().ok())).unwrap_or(0.0)
+}
+
+fn side(value: &serde_json::Value) -> Side {
+    if value.as_str().is_some_and(|s| s.eq_ignore_ascii_case("sell")) { Side::Sell } else { Side::Buy }
+}
+
+/// Items of a bare array or a paginated `{"data": [...]}` page
+fn items(page: &serde_json::Value) -> &[serde_json::Value] {
+    page.get("data").unwrap_or(page).as_array().map_or(&[], Vec::as_slice)
+}
+
+/// Orders from a `/data/orders` page
+pub fn parse_orders(page: &serde_json::Value) -> Vec<VenueOrder> {
+    items(page).iter().map(|o| VenueOrder {
+        order_id: o["id"].as_str().unwrap_or_default().to_string(),
+        token_id: o["asset_id"].as_str().unwrap_or_default().to_string(),
+        side: side(&o["side"]),
+        price: number(&o["price"]),
+        original_size: number(&o["original_size"]),
+        size_matched: number(&o["size_matched"]),
+        status: o["status"].as_str().unwrap_or_default().to_string(),
+        created_at: number(&o["created_at"]) as u64,
+    }).collect()
+}
+
+/// Our fills from a `/data/trades` page: the taker order, or when we were
+/// the maker, our orders among the trade's `maker_orders`
+pub fn parse_fills(page: &serde_json::Value, api_key: &str) -> Vec<VenueFill> {
+    let mut fills = Vec::new();
+    for trade in items(page) {
+        let trade_id = trade["id"].as_str().unwrap_or_default();
+        let timestamp = number(&trade["match_time"]) as u64;
+        let tx_hash = trade["transaction_hash"].as_str().filter(|h| !h.is_empty()).map(str::to_string);
+        if trade["trader_side"].as_str() == Some("MAKER") {
+            let ours = trade["maker_orders"].as_array().into_iter().flatten().filter(|m| m["owner"].as_str() == Some(api_key));
+            for maker in ours {
+                fills.push(VenueFill {
+                    trade_id: trade_id.to_string(),
+                    order_id: maker["order_id"].as_str().unwrap_or_default().to_string(),
+                    token_id: maker["asset_id"].as_str().or(trade["asset_id"].as_str()).unwrap_or_default().to_string(),
+                    side: side(&maker["side"]),
+                    price: number(&maker["price"]),
+                    size: number(&maker["matched_amount"]),
+                    timestamp,
+                    tx_hash: tx_hash.clone(),
+                });
+            }
+        } else {
+            fills.push(VenueFill {
+                trade_id: trade_id.to_string(),
+                order_id: trade["taker_order_id"].as_str().unwrap_or_default().to_string(),
+                token_id: trade["asset_id"].as_str().unwrap_or_default().to_string(),
+                side: side(&trade["side"]),
+                price: number(&trade["price"]),
+                size: number(&trade["size"]),
+                timestamp,
+                tx_hash,
+            });
+        }
+    }
+    fills
+}
+
+/// Cursor the CLOB returns on the last page
+const END_CURSOR: &str = "LTE=";
+/// Upper bound on pages fetched per listing
+const MAX_PAGES: usize = 50;
+
+/// Signs and submits orders to a venue's CLOB
+pub struct OrderClient {
+    profile: VenueProfile,
+    credentials: ApiCredentials,
+    signer: Box<dyn Signer>,
+    client: reqwest::Client,
+}
+
+impl OrderClient {
+    pub fn new(profile: VenueProfile, credentials: ApiCredentials, signer: Box<dyn Signer>) -> Self {
+        Self { profile, credentials, signer, client: crate::market_client::http_client() }
+    }
+
+    pub fn profile(&self) -> &VenueProfile {
+        &self.profile
+    }
+
+    /// Sign a limit order and post it; returns the venue's order id and the
+    /// settlement transactions of anything it matched on arrival. With
+    /// `expires_at` the order is good-til-date, so the venue cancels it even
+    /// if the agent is no longer around to; otherwise it is GTC.
+    pub async fn post_limit(&self, token_id: &str, side: Side, price: f64, size: f64, expires_at: Option<u64>) -> Result<PostedOrder, VenueError> {
+        let maker = self.signer.evm_address()
+            .ok_or(VenueError::Signer(SignerError::Unsupported("no EVM key loaded")))?;
+        let now = crate::wallet::Wallet::current_timestamp();
+        let mut order = Order::limit(&maker, token_id, side, price, size, rand::random::<u32>() as u64);
+        if let Some(expires_at) = expires_at {
+            order.expiration = expires_at + GTD_BUFFER_SECS;
+        }
+        let signature = self.signer
+            .sign_typed_data(self.profile.domain_separator()?, order.struct_hash()?)
+            .await
+            .map_err(VenueError::Signer)?;
+
+        let body = serde_json::to_string(&SignedOrderBody {
+            order: SignedOrder {
+                salt: order.salt,
+                maker: &maker,
+                signer: &maker,
+                taker: "0x0000000000000000000000000000000000000000",
+                token_id,
+                maker_amount: order.maker_amount.to_string(),
+                taker_amount: order.taker_amount.to_string(),
+                expiration: order.expiration.to_string(),
+                nonce: order.nonce.to_string(),
+                fee_rate_bps: order.fee_rate_bps.to_string(),
+                side: if side == Side::Buy { "BUY" } else { "SELL" },
+                signature_type: 0,
+                signature: format!("0x{}", hex::encode(signature)),
+            },
+            owner: &self.credentials.api_key,
+            order_type: if expires_at.is_some() { "GTD" } else { "GTC" },
+        }).map_err(|e| VenueError::Encoding(e.to_string()))?;
+
+        let mut request = self.client.post(format!("{}/order", self.profile.clob_url))
+            .header("content-type", "application/json")
+            .body(body.clone());
+        for (name, value) in self.credentials.l2_headers(&maker, now, "POST", "/order", &body)? {
+            request = request.header(name, value);
+        }
+        let resp = request.send().await.map_err(|e| VenueError::Http(e.to_string()))?;
+        let status = resp.status();
+        let text = resp.text().await.map_err(|e| VenueError::Http(e.to_string()))?;
+        if !status.is_success() {
+            return Err(VenueError::Rejected { status: status.as_u16(), body: text });
+        }
+        let json: serde_json::Value = serde_json::from_str(&text).unwrap_or_default();
+        Ok(PostedOrder::parse(&json))
+    }
+
+    /// Authenticated GET of every page of a CLOB listing (the signature covers the path only)
+    async fn get_pages(&self, path: &str, query: &[(&str, String)]) -> Result<Vec<serde_json::Value>, VenueError> {
+        let maker = self.signer.evm_address()
+            .ok_or(VenueError::Signer(SignerError::Unsupported("no EVM key loaded")))?;
+        let mut pages = Vec::new();
+        let mut cursor: Option<String> = None;
+        while pages.len() < MAX_PAGES {
+            let now = crate::wallet::Wallet::current_timestamp();
+            let mut request = self.client.get(format!("{}{}", self.profile.clob_url, path)).query(query);
+            if let Some(cursor) = &cursor {
+                request = request.query(&[("next_cursor", cursor)]);
+            }
+            for (name, value) in self.credentials.l2_headers(&maker, now, "GET", path, "")? {
+                request = request.header(name, value);
+            }
+            let resp = request.send().await.map_err(|e| VenueError::Http(e.to_string()))?;
+            let status = resp.status();
+            let text = resp.text().await.map_err(|e| VenueError::Http(e.to_string()))?;
+            if !status.is_success() {
+                return Err(VenueError::Rejected { status: status.as_u16(), body: text });
+            }
+            let page: serde_json::Value = serde_json::from_str(&text).map_err(|e| VenueError::Http(e.to_string()))?;
+            cursor = page["next_cursor"].as_str().filter(|c| !c.is_empty() && *c != END_CURSOR).map(str::to_string);
+            pages.push(page);
+            if cursor.is_none() {
+                break;
+            }
+        }
+        Ok(pages)
+    }
+
+    /// Our orders the venue still lists as open
+    pub async fn open_orders(&self) -> Result<Vec<VenueOrder>, VenueError> {
+        Ok(self.get_pages("/data/orders", &[]).await?.iter().flat_map(parse_orders).collect())
+    }
+
+    /// Our fills matched between `after` and `before` (unix seconds)
+    pub async fn fills(&self, after: u64, before: u64) -> Result<Vec<VenueFill>, VenueError> {
+        let query = [("after", after.to_string()), ("before", before.to_string())];
+        let pages = self.get_pages("/data/trades", &query).await?;
+        Ok(pages.iter().flat_map(|page| parse_fills(page, &self.credentials.api_key)).collect())
+    }
+}
+
+pub(crate) fn keccak(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+/// ABI-encode a decimal string (token ids exceed u128) as uint256
+pub(crate) fn uint256(decimal: &str) -> Result<[u8; 32], VenueError> {
+    let mut out = [0u8; 32];
+    if decimal.is_empty() {
+        return Err(VenueError::Encoding("empty integer".to_string()));
+    }
+    for c in decimal.chars() {
+        let digit = c.to_digit(10).ok_or_else(|| VenueError::Encoding(format!("not a decimal integer: {}", decimal)))?;
+        let mut carry = digit;
+        for byte in out.iter_mut().rev() {
+            let v = *byte as u32 * 10 + carry;
+            *byte = (v & 0xff) as u8;
+            carry = v >> 8;
+        }
+        if carry != 0 {
+            return Err(VenueError::Encoding(format!("overflows uint256: {}", decimal)));
+        }
+    }
+    Ok(out)
+}
+
+/// ABI-encode a 0x address (left-padded to 32 bytes)
+fn address(addr: &str) -> Result<[u8; 32], VenueError> {
+    let bytes = hex::decode(addr.trim_start_matches("0x")).map_err(|e| VenueError::Encoding(e.to_string()))?;
+    if bytes.len() != 20 {
+        return Err(VenueError::Encoding(format!("bad address {}", addr)));
+    }
+    let mut out = [0u8; 32];
+    out[12..].copy_from_slice(&bytes);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_encoding_and_l2_headers() {
+        assert_eq!(uint256("258").unwrap()[30..], [1, 2]);
+        let big = "71321045679252212594626385532706912750332728571942532289631379312455583992563";
+        assert!(uint256(big).is_ok());
+        assert!(uint256(&format!("{}0", big)).is_err());
+
+        let order = Order::limit("0x00000000000000000000000000000000000000aa", big, Side::Buy, 0.45, 10.0, 1);
+        assert_eq!((order.maker_amount, order.taker_amount), (4_500_000, 10_000_000));
+        assert!(order.struct_hash().is_ok());
+
+        let registry = Registry::builtin();
+        let sandbox = VenueProfile::sandbox(&SandboxConfig::default(), &registry).unwrap();
+        assert_ne!(sandbox.domain_separator().unwrap(), VenueProfile::mainnet(&registry).unwrap().domain_separator().unwrap());
+
+        let creds = ApiCredentials {
+            api_key: "key".to_string(),
+            secret: URL_SAFE.encode(b"secret"),
+            passphrase: "pass".to_string(),
+        };
+        let headers = creds.l2_headers("0xabc", 1_700_000_000, "POST", "/order", "{}").unwrap();
+        let again = creds.l2_headers("0xabc", 1_700_000_000, "POST", "/order", "{}").unwrap();
+        assert_eq!(headers, again);
+        assert_eq!(headers[1].0, "POLY_SIGNATURE");
+        assert_ne!(headers[1].1, creds.l2_headers("0xabc", 1_700_000_001, "POST", "/order", "{}").unwrap()[1].1);
+    }
+}
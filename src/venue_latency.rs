@@ -0,0 +1,104 @@
+//! Per-venue round-trip latency
+//!
+//! Every book fetch the engine makes is timed and attributed to the venue it
+//! went to, and venues that have not been heard from for `probe_interval_secs`
+//! are probed with a book fetch of their own, so the estimate stays current
+//! between trades. Each venue keeps an exponentially smoothed round trip
+//! plus its last and worst samples; estimates older than `max_age_secs` are
+//! treated as unknown. The router uses the estimates to send the
+//! time-critical leg of a pair (the one placed after the first has filled)
+//! to the fastest venue that fills the size at a price close to the best.
+
+use crate::config::VenueLatencyConfig;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Latency estimate for one venue
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VenueLatency {
+    pub venue: String,
+    /// Smoothed round trip
+    pub rtt_ms: f64,
+    pub last_ms: f64,
+    pub max_ms: f64,
+    pub samples: u64,
+    pub measured_at: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct LatencyMonitor {
+    config: VenueLatencyConfig,
+    venues: BTreeMap<String, VenueLatency>,
+}
+
+impl LatencyMonitor {
+    pub fn new(config: VenueLatencyConfig) -> Self {
+        Self { config, venues: BTreeMap::new() }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Record one round trip to `venue`
+    pub fn record(&mut self, venue: &str, rtt_ms: f64, now: u64) {
+        let smoothing = self.config.smoothing.clamp(0.0, 1.0);
+        let entry = self.venues.entry(venue.to_string()).or_insert_with(|| VenueLatency {
+            venue: venue.to_string(),
+            rtt_ms,
+            last_ms: rtt_ms,
+            max_ms: rtt_ms,
+            samples: 0,
+            measured_at: now,
+        });
+        if entry.samples > 0 {
+            entry.rtt_ms += smoothing * (rtt_ms - entry.rtt_ms);
+        }
+        entry.last_ms = rtt_ms;
+        entry.max_ms = entry.max_ms.max(rtt_ms);
+        entry.samples += 1;
+        entry.measured_at = now;
+    }
+
+    /// Smoothed round trip to `venue`, if measured recently
+    pub fn estimate(&self, venue: &str, now: u64) -> Option<f64> {
+        self.venues.get(venue)
+            .filter(|v| now.saturating_sub(v.measured_at) <= self.config.max_age_secs)
+            .map(|v| v.rtt_ms)
+    }
+
+    /// Whether `venue` is due a probe
+    pub fn needs_probe(&self, venue: &str, now: u64) -> bool {
+        self.config.enabled && self.venues.get(venue)
+            .is_none_or(|v| now.saturating_sub(v.measured_at) >= self.config.probe_interval_secs)
+    }
+
+    /// Largest price per share given up to reach a faster venue
+    pub fn price_tolerance(&self) -> f64 {
+        self.config.price_tolerance
+    }
+
+    pub fn snapshot(&self) -> Vec<VenueLatency> {
+        self.venues.values().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smooths_and_ages_estimates() {
+        let config = VenueLatencyConfig { enabled: true, smoothing: 0.5, probe_interval_secs: 30, max_age_secs: 300, price_tolerance: 0.005 };
+        let mut monitor = LatencyMonitor::new(config);
+        assert!(monitor.needs_probe("envio", 0));
+        monitor.record("envio", 100.0, 0);
+        monitor.record("envio", 200.0, 10);
+        assert_eq!(monitor.estimate("envio", 10), Some(150.0));
+        assert!(!monitor.needs_probe("envio", 20));
+        assert!(monitor.needs_probe("envio", 40));
+        assert_eq!(monitor.estimate("envio", 311), None);
+        let snapshot = monitor.snapshot();
+        assert_eq!((snapshot[0].samples, snapshot[0].max_ms, snapshot[0].last_ms), (2, 200.0, 200.0));
+    }
+}
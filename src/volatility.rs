@@ -0,0 +1,102 @@
+//! Volatility response
+//!
+//! Instead of hard-halting when volatility rises, new entries are throttled
+//! while it is elevated: sizes shrink, the required edge widens and a market
+//! that just signalled is left alone for longer. Volatility counts as
+//! elevated when the risk manager's PnL volatility reaches `elevated_at` of
+//! its halt threshold, or (optionally) when the market's regime is an event
+//! spike. The throttle lifts as soon as neither holds.
+
+use crate::config::VolatilityResponseConfig;
+use crate::regime::Regime;
+use std::collections::HashMap;
+
+/// Adjustments applied to an entry
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Throttle {
+    pub elevated: bool,
+    /// Multiplies the regime size multiplier
+    pub size_multiplier: f64,
+    /// Added to the required edge ($)
+    pub extra_edge: f64,
+    /// Minimum time between signals on one market
+    pub cooldown_secs: u64,
+}
+
+/// Volatility-dependent entry throttle with per-market signal cooldowns
+#[derive(Debug)]
+pub struct VolatilityResponse {
+    config: VolatilityResponseConfig,
+    /// Last accepted signal per market
+    last_signal: HashMap<String, u64>,
+}
+
+impl VolatilityResponse {
+    pub fn new(config: VolatilityResponseConfig) -> Self {
+        Self { config, last_signal: HashMap::new() }
+    }
+
+    /// Throttle for a market in `regime`, given PnL volatility and the risk halt threshold
+    pub fn throttle(&self, volatility: f64, threshold: f64, regime: Regime) -> Throttle {
+        let elevated = self.config.enabled
+            && ((threshold > 0.0 && volatility >= threshold * self.config.elevated_at)
+                || (self.config.on_event_spike && regime == Regime::EventSpike));
+        match elevated {
+            true => Throttle {
+                elevated,
+                size_multiplier: self.config.size_multiplier,
+                extra_edge: self.config.extra_edge,
+                cooldown_secs: self.config.elevated_cooldown_secs,
+            },
+            false => Throttle { elevated, size_multiplier: 1.0, extra_edge: 0.0, cooldown_secs: self.config.signal_cooldown_secs },
+        }
+    }
+
+    /// Seconds left before `market_id` may signal again under `throttle`
+    pub fn cooldown_remaining(&self, market_id: &str, throttle: &Throttle, now: u64) -> Option<u64> {
+        let last = self.last_signal.get(market_id)?;
+        let until = last + throttle.cooldown_secs;
+        (now < until).then(|| until - now)
+    }
+
+    /// A signal on `market_id` was accepted
+    pub fn record_signal(&mut self, market_id: &str, now: u64) {
+        self.last_signal.insert(market_id.to_string(), now);
+    }
+
+    /// Forget markets no longer scanned
+    pub fn retain(&mut self, active: &[String]) {
+        self.last_signal.retain(|id, _| active.contains(id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_throttle_when_elevated() {
+        let mut response = VolatilityResponse::new(VolatilityResponseConfig {
+            elevated_at: 0.5,
+            size_multiplier: 0.5,
+            extra_edge: 0.01,
+            signal_cooldown_secs: 0,
+            elevated_cooldown_secs: 300,
+            ..Default::default()
+        });
+        let calm = response.throttle(0.05, 0.15, Regime::Calm);
+        assert_eq!((calm.elevated, calm.size_multiplier, calm.extra_edge), (false, 1.0, 0.0));
+        let high = response.throttle(0.10, 0.15, Regime::Calm);
+        assert_eq!((high.elevated, high.size_multiplier, high.extra_edge), (true, 0.5, 0.01));
+        assert!(response.throttle(0.0, 0.15, Regime::EventSpike).elevated);
+
+        response.record_signal("m1", 1_000);
+        assert_eq!(response.cooldown_remaining("m1", &calm, 1_000), None);
+        assert_eq!(response.cooldown_remaining("m1", &high, 1_100), Some(200));
+        assert_eq!(response.cooldown_remaining("m1", &high, 1_300), None);
+        assert_eq!(response.cooldown_remaining("m2", &high, 1_100), None);
+
+        let disabled = VolatilityResponse::new(VolatilityResponseConfig { enabled: false, ..Default::default() });
+        assert!(!disabled.throttle(1.0, 0.15, Regime::EventSpike).elevated);
+    }
+}
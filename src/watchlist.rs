@@ -0,0 +1,186 @@
+//! Dashboard watchlist: pinned markets are scanned every tick ahead of the
+//! scan cap, always streamed, and journaled across restarts
+
+use crate::config::WatchlistConfig;
+use crate::storage::JsonlStore;
+use crate::types::{ArbitrageSignal, Market, OrderBook};
+use serde::{Deserialize, Serialize};
+
+/// A pinned market
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchEntry {
+    pub market_id: String,
+    pub added_at: u64,
+}
+
+/// `watch` event on the SSE stream: a watched market's books and signal this tick
+#[derive(Debug, Clone, Serialize)]
+pub struct WatchUpdate {
+    pub market_id: String,
+    pub question: String,
+    pub timestamp: u64,
+    pub books: Vec<OrderBook>,
+    pub signal: Option<ArbitrageSignal>,
+}
+
+/// Journal record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum WatchEvent {
+    Pinned(WatchEntry),
+    Unpinned { market_id: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatchlistError {
+    /// The watchlist already holds `max_markets` markets
+    Full(usize),
+}
+
+impl std::fmt::Display for WatchlistError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Full(max) => write!(f, "watchlist is full ({} markets)", max),
+        }
+    }
+}
+
+/// Pinned markets in pin order
+#[derive(Debug)]
+pub struct Watchlist {
+    config: WatchlistConfig,
+    entries: Vec<WatchEntry>,
+    journal: Option<JsonlStore>,
+}
+
+impl Watchlist {
+    pub fn new(config: WatchlistConfig) -> Self {
+        Self { config, entries: Vec::new(), journal: None }
+    }
+
+    /// Open a journaled watchlist, replaying earlier pins and unpins
+    pub fn open(config: WatchlistConfig, journal: JsonlStore) -> Self {
+        let events: Vec<WatchEvent> = journal.load().unwrap_or_else(|e| {
+            eprintln!("⚠️ [Watchlist] Failed to read watchlist journal: {}", e);
+            Vec::new()
+        });
+        let mut entries: Vec<WatchEntry> = Vec::new();
+        for event in events {
+            match event {
+                WatchEvent::Pinned(entry) => {
+                    if !entries.iter().any(|e| e.market_id == entry.market_id) {
+                        entries.push(entry);
+                    }
+                }
+                WatchEvent::Unpinned { market_id } => entries.retain(|e| e.market_id != market_id),
+            }
+        }
+        Self { config, entries, journal: Some(journal) }
+    }
+
+    fn record(&self, event: &WatchEvent) {
+        if let Some(journal) = &self.journal {
+            if let Err(e) = journal.append(event) {
+                eprintln!("⚠️ [Watchlist] Failed to journal watchlist event: {}", e);
+            }
+        }
+    }
+
+    /// Pin a market; Ok(false) if it was already pinned
+    pub fn pin(&mut self, market_id: &str, now: u64) -> Result<bool, WatchlistError> {
+        if self.contains(market_id) {
+            return Ok(false);
+        }
+        if self.config.max_markets > 0 && self.entries.len() >= self.config.max_markets {
+            return Err(WatchlistError::Full(self.config.max_markets));
+        }
+        let entry = WatchEntry { market_id: market_id.to_string(), added_at: now };
+        self.record(&WatchEvent::Pinned(entry.clone()));
+        self.entries.push(entry);
+        Ok(true)
+    }
+
+    /// Unpin a market; false if it was not pinned
+    pub fn unpin(&mut self, market_id: &str) -> bool {
+        if !self.contains(market_id) {
+            return false;
+        }
+        self.entries.retain(|e| e.market_id != market_id);
+        self.record(&WatchEvent::Unpinned { market_id: market_id.to_string() });
+        true
+    }
+
+    pub fn contains(&self, market_id: &str) -> bool {
+        self.entries.iter().any(|e| e.market_id == market_id)
+    }
+
+    pub fn entries(&self) -> &[WatchEntry] {
+        &self.entries
+    }
+
+    /// Scan list for this tick: every listed watched market first (due or
+    /// not, and outside any scan cap), then the scheduler's due markets
+    pub fn prioritize<'a>(&self, markets: &'a [Market], due: Vec<&'a Market>) -> Vec<&'a Market> {
+        let mut scan: Vec<&Market> = self.entries.iter()
+            .filter_map(|e| markets.iter().find(|m| m.id == e.market_id))
+            .collect();
+        scan.extend(due.into_iter().filter(|m| !self.contains(&m.id)));
+        scan
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn market(id: &str) -> Market {
+        Market { id: id.to_string(), ..testing::market() }
+    }
+
+    fn journal(test: &str) -> (JsonlStore, std::path::PathBuf) {
+        let dir = std::env::temp_dir().join(format!("arbishark_watchlist_{}_{}", test, std::process::id()));
+        (JsonlStore::open(dir.to_str().unwrap(), "watchlist.jsonl").unwrap(), dir)
+    }
+
+    #[test]
+    fn test_pins_are_capped_and_deduplicated() {
+        let (journal, dir) = journal("cap");
+        let mut watchlist = Watchlist::open(WatchlistConfig { max_markets: 2 }, journal);
+        assert_eq!(watchlist.pin("m3", 100), Ok(true));
+        assert_eq!(watchlist.pin("m3", 101), Ok(false));
+        assert_eq!(watchlist.pin("m1", 102), Ok(true));
+        assert_eq!(watchlist.pin("m2", 103), Err(WatchlistError::Full(2)));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_pins_prioritized() {
+        let (journal, dir) = journal("prioritize");
+        let mut watchlist = Watchlist::open(WatchlistConfig::default(), journal);
+        watchlist.pin("m3", 100).unwrap();
+        watchlist.pin("m1", 101).unwrap();
+
+        // Watched markets come first even when not due, without duplicates
+        let markets = vec![market("m1"), market("m2"), market("m3")];
+        let due = vec![&markets[0], &markets[1]];
+        let ids: Vec<&str> = watchlist.prioritize(&markets, due).iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["m3", "m1", "m2"]);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_pins_persisted() {
+        let (journal, dir) = journal("persist");
+        let mut watchlist = Watchlist::open(WatchlistConfig::default(), journal.clone());
+        watchlist.pin("m3", 100).unwrap();
+        watchlist.pin("m1", 102).unwrap();
+        assert!(watchlist.unpin("m3"));
+        assert!(!watchlist.unpin("m3"));
+
+        let restored = Watchlist::open(WatchlistConfig::default(), journal);
+        assert_eq!(restored.entries().len(), 1);
+        assert_eq!(restored.entries()[0], WatchEntry { market_id: "m1".to_string(), added_at: 102 });
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -7,6 +7,9 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::{broadcast, RwLock};
 use tokio_tungstenite::{connect_async, tungstenite::Message};
+use crate::book_delta::{BookCache, BookDelta, LevelChange, QuoteRates};
+use crate::trade_flow::TradeFlow;
+use crate::types::{OrderBook, PriceLevel, Side, Trade};
 
 /// WebSocket message types from Polymarket
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,14 +25,31 @@ pub enum WsMessage {
     #[serde(rename = "trade")]
     Trade {
         market_id: String,
+        #[serde(default)]
+        token_id: String,
         price: f64,
         size: f64,
         side: String,
         timestamp: u64,
     },
+    /// Full snapshot of one token's book
     #[serde(rename = "book_update")]
     BookUpdate {
         market_id: String,
+        #[serde(default)]
+        token_id: String,
+        #[serde(default)]
+        bids: Vec<PriceLevel>,
+        #[serde(default)]
+        asks: Vec<PriceLevel>,
+        timestamp: u64,
+    },
+    /// Incremental level changes to one token's book
+    #[serde(rename = "book_delta")]
+    BookDelta {
+        market_id: String,
+        token_id: String,
+        changes: Vec<LevelChange>,
         timestamp: u64,
     },
     #[serde(other)]
@@ -73,8 +93,12 @@ pub struct WebSocketClient {
     url: String,
     status: Arc<RwLock<WsStatus>>,
     price_cache: Arc<RwLock<PriceCache>>,
+    /// Books maintained from streamed snapshots and deltas
+    book_cache: Arc<RwLock<BookCache>>,
     /// Broadcast channel for price updates
     tx: broadcast::Sender<WsMessage>,
+    /// Trade prints store fed by the trade channel
+    trade_flow: Option<Arc<RwLock<TradeFlow>>>,
 }
 
 impl WebSocketClient {
@@ -85,10 +109,19 @@ impl WebSocketClient {
             url: url.to_string(),
             status: Arc::new(RwLock::new(WsStatus::Disconnected)),
             price_cache: Arc::new(RwLock::new(PriceCache::default())),
+            book_cache: Arc::new(RwLock::new(BookCache::new())),
             tx,
+            trade_flow: None,
         }
     }
 
+    /// Record streamed trade prints into a shared trade flow store
+    #[allow(dead_code)]
+    pub fn with_trade_flow(mut self, trade_flow: Arc<RwLock<TradeFlow>>) -> Self {
+        self.trade_flow = Some(trade_flow);
+        self
+    }
+
     /// Get current connection status
     #[allow(dead_code)]
     pub async fn get_status(&self) -> WsStatus {
@@ -107,6 +140,18 @@ impl WebSocketClient {
         self.price_cache.read().await.prices.get(token_id).copied()
     }
 
+    /// Get the streamed order book of a token
+    #[allow(dead_code)]
+    pub async fn get_book(&self, token_id: &str) -> Option<OrderBook> {
+        self.book_cache.read().await.get(token_id).cloned()
+    }
+
+    /// Quote add / cancel rates of a token over the last `window_secs`
+    #[allow(dead_code)]
+    pub async fn quote_rates(&self, token_id: &str, now: u64, window_secs: u64) -> QuoteRates {
+        self.book_cache.read().await.quote_rates(token_id, now, window_secs)
+    }
+
     /// Connect and start streaming
     #[allow(dead_code)]
     pub async fn connect(&self, market_ids: Vec<String>) -> Result<(), WsError> {
@@ -141,7 +186,9 @@ impl WebSocketClient {
         // Start reading messages
         let tx = self.tx.clone();
         let price_cache = self.price_cache.clone();
+        let book_cache = self.book_cache.clone();
         let status = self.status.clone();
+        let trade_flow = self.trade_flow.clone();
         
         tokio::spawn(async move {
             while let Some(msg) = read.next().await {
@@ -154,6 +201,35 @@ impl WebSocketClient {
                                 cache.prices.insert(token_id.clone(), price);
                                 cache.last_update = timestamp;
                             }
+                            match &ws_msg {
+                                WsMessage::BookUpdate { token_id, bids, asks, timestamp, .. } if !token_id.is_empty() => {
+                                    book_cache.write().await.update_snapshot(OrderBook {
+                                        token_id: token_id.clone(),
+                                        bids: bids.clone(),
+                                        asks: asks.clone(),
+                                        timestamp: *timestamp,
+                                    });
+                                }
+                                WsMessage::BookDelta { token_id, changes, timestamp, .. } => {
+                                    book_cache.write().await.apply_delta(&BookDelta {
+                                        token_id: token_id.clone(),
+                                        timestamp: *timestamp,
+                                        changes: changes.clone(),
+                                    });
+                                }
+                                _ => {}
+                            }
+                            if let (Some(flow), WsMessage::Trade { token_id, price, size, side, timestamp, .. }) = (&trade_flow, &ws_msg) {
+                                let side = if side.eq_ignore_ascii_case("sell") { Side::Sell } else { Side::Buy };
+                                flow.write().await.ingest(Trade {
+                                    id: format!("ws:{}:{}:{}:{}", token_id, timestamp, price, size),
+                                    token_id: token_id.clone(),
+                                    price: *price,
+                                    size: *size,
+                                    side,
+                                    timestamp: *timestamp,
+                                });
+                            }
                             
                             // Broadcast to subscribers
                             let _ = tx.send(ws_msg);
@@ -0,0 +1,194 @@
+//! Trading windows
+//!
+//! Time-of-day hours (UTC) during which the engine may open trades, plus
+//! calendar blackouts loaded from a TOML file, scoped to a market category
+//! (sports games, Fed announcements) or a single market. Outside a window
+//! signals are still detected but not executed.
+
+use crate::config::TradingWindowConfig;
+use chrono::{DateTime, Timelike, Utc};
+use serde::Deserialize;
+
+/// Minutes in a day
+const DAY_MINUTES: u32 = 24 * 60;
+
+/// Daily UTC range, "HH:MM-HH:MM"; the end is exclusive and may wrap midnight
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HourRange {
+    start: u32,
+    end: u32,
+}
+
+impl HourRange {
+    pub fn parse(range: &str) -> Result<Self, WindowError> {
+        let invalid = || WindowError::InvalidRange(range.to_string());
+        let (start, end) = range.split_once('-').ok_or_else(invalid)?;
+        let minutes = |t: &str| -> Option<u32> {
+            let (h, m) = t.trim().split_once(':')?;
+            let (h, m) = (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?);
+            (h <= 24 && m < 60 && h * 60 + m <= DAY_MINUTES).then_some(h * 60 + m)
+        };
+        Ok(Self { start: minutes(start).ok_or_else(invalid)?, end: minutes(end).ok_or_else(invalid)? })
+    }
+
+    /// Whether a minute of the day falls in the range
+    pub fn contains(&self, minute: u32) -> bool {
+        if self.start <= self.end {
+            minute >= self.start && minute < self.end
+        } else {
+            minute >= self.start || minute < self.end
+        }
+    }
+}
+
+/// One calendar exception from the calendar file
+#[derive(Debug, Clone, Deserialize)]
+struct BlackoutEntry {
+    #[serde(default)]
+    category: Option<String>,
+    #[serde(default)]
+    market_id: Option<String>,
+    /// RFC 3339 timestamps
+    start: String,
+    end: String,
+    reason: String,
+}
+
+/// A period during which matching markets may not be traded
+#[derive(Debug, Clone)]
+pub struct Blackout {
+    /// Market category (see `attribution::categorize`); None = every category
+    pub category: Option<String>,
+    /// Restrict to one market
+    pub market_id: Option<String>,
+    /// Unix seconds, end exclusive
+    pub start: u64,
+    pub end: u64,
+    pub reason: String,
+}
+
+impl Blackout {
+    fn applies(&self, now: u64, market_id: &str, category: &str) -> bool {
+        now >= self.start
+            && now < self.end
+            && self.category.as_deref().is_none_or(|c| c == category)
+            && self.market_id.as_deref().is_none_or(|m| m == market_id)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CalendarFile {
+    #[serde(default)]
+    blackout: Vec<BlackoutEntry>,
+}
+
+/// Reason trading is closed, or the schedule failed to load
+#[derive(Debug, Clone, PartialEq)]
+pub enum WindowError {
+    InvalidRange(String),
+    Calendar(String),
+    OutsideHours,
+    Blackout(String),
+}
+
+impl std::fmt::Display for WindowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidRange(r) => write!(f, "Invalid trading hours {:?} (expected HH:MM-HH:MM)", r),
+            Self::Calendar(e) => write!(f, "Failed to load trading calendar: {}", e),
+            Self::OutsideHours => write!(f, "Outside trading hours"),
+            Self::Blackout(reason) => write!(f, "Calendar blackout: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for WindowError {}
+
+/// Trading hours plus calendar exceptions
+#[derive(Debug, Clone, Default)]
+pub struct TradingWindows {
+    /// Empty = trade around the clock
+    hours: Vec<HourRange>,
+    blackouts: Vec<Blackout>,
+}
+
+impl TradingWindows {
+    pub fn from_config(config: &TradingWindowConfig) -> Result<Self, WindowError> {
+        let hours = config.hours_utc.iter().map(|r| HourRange::parse(r)).collect::<Result<_, _>>()?;
+        let blackouts = match &config.calendar_file {
+            Some(path) => {
+                let content = std::fs::read_to_string(path)
+                    .map_err(|e| WindowError::Calendar(format!("{}: {}", path, e)))?;
+                parse_calendar(&content)?
+            }
+            None => Vec::new(),
+        };
+        Ok(Self { hours, blackouts })
+    }
+
+    pub fn blackout_count(&self) -> usize {
+        self.blackouts.len()
+    }
+
+    /// Whether a trade on `market_id` (in `category`) may open at `now`
+    pub fn check(&self, now: u64, market_id: &str, category: &str) -> Result<(), WindowError> {
+        if !self.hours.is_empty() {
+            let time = DateTime::<Utc>::from_timestamp(now as i64, 0).unwrap_or_default();
+            let minute = time.hour() * 60 + time.minute();
+            if !self.hours.iter().any(|h| h.contains(minute)) {
+                return Err(WindowError::OutsideHours);
+            }
+        }
+        match self.blackouts.iter().find(|b| b.applies(now, market_id, category)) {
+            Some(b) => Err(WindowError::Blackout(b.reason.clone())),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parse a calendar file (`[[blackout]]` tables)
+pub fn parse_calendar(content: &str) -> Result<Vec<Blackout>, WindowError> {
+    let file: CalendarFile = toml::from_str(content).map_err(|e| WindowError::Calendar(e.to_string()))?;
+    let time = |t: &str| {
+        DateTime::parse_from_rfc3339(t)
+            .map(|d| d.timestamp().max(0) as u64)
+            .map_err(|e| WindowError::Calendar(format!("{:?}: {}", t, e)))
+    };
+    file.blackout.into_iter().map(|b| Ok(Blackout {
+        start: time(&b.start)?,
+        end: time(&b.end)?,
+        category: b.category,
+        market_id: b.market_id,
+        reason: b.reason,
+    })).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hours_and_blackouts() {
+        let calendar = r#"
+            [[blackout]]
+            category = "economics"
+            start = "2026-03-18T17:45:00Z"
+            end = "2026-03-18T19:00:00Z"
+            reason = "FOMC decision"
+        "#;
+        let windows = TradingWindows {
+            hours: vec![HourRange::parse("13:00-01:00").unwrap()],
+            blackouts: parse_calendar(calendar).unwrap(),
+        };
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().timestamp() as u64;
+
+        assert_eq!(windows.check(at("2026-03-18T12:00:00Z"), "m1", "crypto"), Err(WindowError::OutsideHours));
+        assert!(windows.check(at("2026-03-18T14:00:00Z"), "m1", "crypto").is_ok());
+        assert!(windows.check(at("2026-03-19T00:30:00Z"), "m1", "crypto").is_ok()); // wraps midnight
+        assert_eq!(windows.check(at("2026-03-18T18:00:00Z"), "m1", "economics"),
+            Err(WindowError::Blackout("FOMC decision".to_string())));
+        assert!(windows.check(at("2026-03-18T18:00:00Z"), "m1", "sports").is_ok());
+
+        assert!(HourRange::parse("25:00-01:00").is_err());
+    }
+}
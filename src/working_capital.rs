@@ -0,0 +1,146 @@
+//! Withdrawal-safe working capital
+//!
+//! The wallet balance overstates what can be spent: part of it already
+//! backs open positions, buy orders resting on the book, a bundle whose
+//! legs are still executing, intents awaiting an external signer, a sweep
+//! not yet confirmed and USDC supplied to the treasury venue. The free
+//! balance is what is left once all of that (and a configured buffer) is
+//! taken out. The sizer caps each entry at it and the profit sweep only
+//! withdraws from it, so neither counts committed capital twice.
+
+use crate::config::WorkingCapitalConfig;
+use crate::orders::RestingOrder;
+use crate::types::Side;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Capital already spoken for, by what holds it ($)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Commitments {
+    /// Notional of open positions
+    pub open_positions: f64,
+    /// Unfilled remainder of resting buy orders
+    pub resting_orders: f64,
+    /// Transfers out not yet confirmed (profit sweeps)
+    pub pending_settlements: f64,
+    /// Supplied to the treasury venue
+    pub treasury: f64,
+}
+
+impl Commitments {
+    pub fn total(&self) -> f64 {
+        self.open_positions + self.resting_orders + self.pending_settlements + self.treasury
+    }
+}
+
+/// Balance broken down into committed and free
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FreeCapital {
+    pub balance: f64,
+    pub committed: Commitments,
+    /// Reserved by bundles and intents still executing
+    pub in_flight: f64,
+    pub buffer: f64,
+    /// What may still be spent or withdrawn (never negative)
+    pub free: f64,
+}
+
+impl FreeCapital {
+    /// What an entry may use: the free balance plus treasury deposits, recalled just in time
+    pub fn tradable(&self) -> f64 {
+        self.free + self.committed.treasury
+    }
+}
+
+/// Capital reserved for an execution still in progress
+#[derive(Debug, Clone)]
+struct Reservation {
+    amount: f64,
+    expires_at: u64,
+}
+
+#[derive(Debug)]
+pub struct WorkingCapital {
+    config: WorkingCapitalConfig,
+    /// Key (market or intent id) → reservation
+    in_flight: BTreeMap<String, Reservation>,
+}
+
+impl WorkingCapital {
+    pub fn new(config: WorkingCapitalConfig) -> Self {
+        Self { config, in_flight: BTreeMap::new() }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Hold `amount` for an execution until released or the in-flight TTL passes
+    pub fn reserve(&mut self, key: &str, amount: f64, now: u64) {
+        self.reserve_until(key, amount, now + self.config.in_flight_ttl_secs);
+    }
+
+    /// Hold `amount` until `expires_at` (e.g. an intent's expiry) unless released first
+    pub fn reserve_until(&mut self, key: &str, amount: f64, expires_at: u64) {
+        self.in_flight.insert(key.to_string(), Reservation { amount: amount.max(0.0), expires_at });
+    }
+
+    pub fn release(&mut self, key: &str) {
+        self.in_flight.remove(key);
+    }
+
+    /// Free balance out of `balance` given what is `committed`
+    pub fn free(&mut self, balance: f64, committed: Commitments, now: u64) -> FreeCapital {
+        self.in_flight.retain(|_, r| now < r.expires_at);
+        let in_flight: f64 = self.in_flight.values().map(|r| r.amount).sum();
+        let buffer = self.config.buffer_usd;
+        FreeCapital {
+            balance,
+            free: (balance - committed.total() - in_flight - buffer).max(0.0),
+            committed,
+            in_flight,
+            buffer,
+        }
+    }
+}
+
+/// Capital backing the unfilled part of resting buy orders
+pub fn resting_reserve(orders: &[RestingOrder]) -> f64 {
+    orders.iter()
+        .filter(|o| o.order.side == Side::Buy)
+        .map(|o| o.order.price * o.order.remaining())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fills::PassiveOrder;
+
+    #[test]
+    fn test_committed_capital_is_not_free() {
+        let config = WorkingCapitalConfig { enabled: true, buffer_usd: 5.0, in_flight_ttl_secs: 60 };
+        let mut capital = WorkingCapital::new(config);
+        let order = |side, filled| RestingOrder {
+            order_id: "o1".to_string(),
+            order: PassiveOrder { token_id: "t1".to_string(), side, price: 0.40, size: 50.0, queue_ahead: 0.0, filled },
+            placed_at: 0, cursor: 0, expires_at: 0,
+        };
+        // Only the unfilled part of buys holds capital
+        let resting = resting_reserve(&[order(Side::Buy, 25.0), order(Side::Sell, 0.0)]);
+        assert!((resting - 10.0).abs() < 1e-9);
+
+        let committed = || Commitments { open_positions: 40.0, resting_orders: resting, pending_settlements: 15.0, treasury: 20.0 };
+        capital.reserve("m1", 8.0, 100);
+        let view = capital.free(100.0, committed(), 100);
+        assert!((view.free - 2.0).abs() < 1e-9, "100 - 85 committed - 8 in flight - 5 buffer");
+        assert!((view.tradable() - 22.0).abs() < 1e-9);
+
+        // A released or lapsed reservation frees its capital; nothing goes below zero
+        capital.release("m1");
+        assert!((capital.free(100.0, committed(), 100).free - 10.0).abs() < 1e-9);
+        capital.reserve("m2", 50.0, 100);
+        assert_eq!(capital.free(100.0, committed(), 159).free, 0.0);
+        assert!((capital.free(100.0, committed(), 160).free - 10.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,190 @@
+//! ERC-7715 permission exhaustion scenarios
+//!
+//! Drives bundle legs through the engine's grant-held spend path
+//! (`MetaMaskClient::spend_with` + `ExecutionEngine::execute_capped`) while
+//! the grant changes under it: the limit running out mid-bundle, a revoke
+//! between legs, a period rollover during execution and a replacement grant
+//! with a smaller limit. Each grant change is injected at every point of the
+//! bundle, and once more concurrently from another task, and the audit trail
+//! must show that no spend ever exceeded the grant active when it executed.
+
+use arbishark::audit::{AuditQuery, SpendContext};
+use arbishark::execution::ExecutionEngine;
+use arbishark::fees::FeeModel;
+use arbishark::latency::LatencyModel;
+use arbishark::metamask::{MetaMaskClient, PermissionGrant};
+use arbishark::types::{OrderBook, PriceLevel, Side};
+use arbishark::wallet::Wallet;
+use std::sync::Arc;
+
+const EPS: f64 = 1e-9;
+
+/// A change to the grant between (or concurrently with) legs
+#[derive(Debug, Clone, Copy)]
+enum GrantChange {
+    Revoke,
+    Rollover,
+    Replace { daily_limit: f64 },
+}
+
+fn grant(permission_id: &str, daily_limit: f64) -> PermissionGrant {
+    let now = Wallet::current_timestamp();
+    PermissionGrant {
+        permission_id: permission_id.to_string(),
+        token: "USDC".to_string(),
+        daily_limit,
+        spent_today: 0.0,
+        expires_at: now + 86_400,
+        granted_at: now,
+        revoked: false,
+        scope: Default::default(),
+    }
+}
+
+fn book(token_id: &str, ask: f64) -> OrderBook {
+    OrderBook {
+        token_id: token_id.to_string(),
+        bids: vec![],
+        asks: vec![PriceLevel { price: ask, size: 1_000.0 }],
+        timestamp: 0,
+    }
+}
+
+fn engine() -> ExecutionEngine {
+    ExecutionEngine::new(FeeModel { maker_fee_bps: 0, taker_fee_bps: 0 }, LatencyModel::new(0, 0.0))
+}
+
+async fn apply(metamask: &MetaMaskClient, change: GrantChange) {
+    match change {
+        GrantChange::Revoke => metamask.revoke_permission().await.unwrap(),
+        GrantChange::Rollover => metamask.reset_daily_spend().await,
+        GrantChange::Replace { daily_limit } => metamask.set_permission(grant("replacement", daily_limit)).await,
+    }
+}
+
+/// One leg through the engine's spend path; true if it filled
+async fn leg(metamask: &MetaMaskClient, engine: &ExecutionEngine, wallet: &mut Wallet, book: &OrderBook, size: f64) -> bool {
+    let context = SpendContext { market_id: "m1".to_string(), token_id: book.token_id.clone(), tx_hash: None };
+    let filled = metamask.spend_with(context, |remaining| {
+        engine.execute_capped(book, size, Side::Buy, wallet, None, remaining).ok().map(|r| {
+            let cost = r.total_cost;
+            (r, cost)
+        })
+    }).await;
+    matches!(filled, Ok(Some(_)))
+}
+
+/// No audited spend took its grant past the limit of its period, and the
+/// active grant never shows more spent than it allows
+async fn assert_within_grant(metamask: &MetaMaskClient, scenario: &str) {
+    for record in metamask.audit_log().query(&AuditQuery::default()) {
+        assert!(record.spent_in_period <= record.daily_limit + EPS,
+            "{}: {} spent {:.2} of {:.2}", scenario, record.permission_id, record.spent_in_period, record.daily_limit);
+    }
+    if let Some(p) = metamask.get_permission().await {
+        assert!(p.spent_today <= p.daily_limit + EPS, "{}: active grant overspent", scenario);
+    }
+}
+
+fn spent_under(metamask: &MetaMaskClient, permission_id: &str) -> f64 {
+    metamask.audit_log().query(&AuditQuery::default()).iter()
+        .filter(|r| r.permission_id == permission_id)
+        .map(|r| r.notional)
+        .sum()
+}
+
+/// Run a bundle of `legs` with `change` injected before leg `at` (`legs.len()` = after the last)
+async fn run_bundle(limit: f64, legs: &[(&str, f64, f64)], change: Option<(usize, GrantChange)>) -> (MetaMaskClient, Vec<bool>) {
+    let metamask = MetaMaskClient::new();
+    metamask.set_permission(grant("original", limit)).await;
+    let engine = engine();
+    // The local wallet never binds here: only the grant may stop a leg
+    let mut wallet = Wallet::new(f64::MAX);
+    let mut filled = Vec::new();
+    for (i, (token_id, ask, size)) in legs.iter().enumerate() {
+        if let Some((_, c)) = change.filter(|(at, _)| *at == i) {
+            apply(&metamask, c).await;
+        }
+        filled.push(leg(&metamask, &engine, &mut wallet, &book(token_id, *ask), *size).await);
+        assert_within_grant(&metamask, &format!("{:?} before leg {}", change, i)).await;
+    }
+    if let Some((_, c)) = change.filter(|(at, _)| *at == legs.len()) {
+        apply(&metamask, c).await;
+    }
+    assert_within_grant(&metamask, &format!("{:?}", change)).await;
+    (metamask, filled)
+}
+
+/// Three $4 legs
+const BUNDLE: [(&str, f64, f64); 3] = [("yes", 0.40, 10.0), ("no", 0.40, 10.0), ("other", 0.40, 10.0)];
+
+#[tokio::test]
+async fn limit_reached_mid_bundle() {
+    let (metamask, filled) = run_bundle(10.0, &BUNDLE, None).await;
+    assert_eq!(filled, vec![true, true, false], "third leg would take the grant to $12");
+    assert!((spent_under(&metamask, "original") - 8.0).abs() < EPS);
+}
+
+#[tokio::test]
+async fn revoked_between_legs() {
+    for at in 0..=BUNDLE.len() {
+        let (metamask, filled) = run_bundle(100.0, &BUNDLE, Some((at, GrantChange::Revoke))).await;
+        assert!(filled[at..].iter().all(|f| !f), "no leg fills after a revoke at {}", at);
+        assert!((spent_under(&metamask, "original") - 4.0 * at as f64).abs() < EPS);
+    }
+}
+
+#[tokio::test]
+async fn period_rollover_during_execution() {
+    for at in 0..=BUNDLE.len() {
+        let (metamask, filled) = run_bundle(10.0, &BUNDLE, Some((at, GrantChange::Rollover))).await;
+        // Legs before the rollover are bounded by the old period, legs after it by the new one
+        let expected = match at {
+            0 | 3 => vec![true, true, false],
+            _ => vec![true, true, true],
+        };
+        assert_eq!(filled, expected, "rollover before leg {}", at);
+        assert!(metamask.get_permission().await.unwrap().spent_today <= 10.0);
+    }
+}
+
+#[tokio::test]
+async fn grant_replaced_with_smaller_limit() {
+    for at in 0..=BUNDLE.len() {
+        let (metamask, filled) = run_bundle(100.0, &BUNDLE, Some((at, GrantChange::Replace { daily_limit: 5.0 }))).await;
+        // Legs after the replacement are charged to it: one $4 leg fits in $5
+        let after = filled[at.min(BUNDLE.len())..].iter().filter(|f| **f).count();
+        assert_eq!(after, usize::from(at < BUNDLE.len()), "replacement before leg {}", at);
+        assert!(spent_under(&metamask, "replacement") <= 5.0 + EPS);
+        assert!((spent_under(&metamask, "original") - 4.0 * at as f64).abs() < EPS);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_grant_changes_never_overspend() {
+    for round in 0..50 {
+        let metamask = Arc::new(MetaMaskClient::new());
+        metamask.set_permission(grant("original", 10.0)).await;
+        let changer = {
+            let metamask = metamask.clone();
+            tokio::spawn(async move {
+                for change in [GrantChange::Replace { daily_limit: 6.0 }, GrantChange::Rollover, GrantChange::Revoke] {
+                    for _ in 0..round % 5 {
+                        tokio::task::yield_now().await;
+                    }
+                    apply(&metamask, change).await;
+                }
+            })
+        };
+        let engine = engine();
+        let mut wallet = Wallet::new(f64::MAX);
+        for i in 0..10 {
+            let (token_id, ask, size) = BUNDLE[i % BUNDLE.len()];
+            leg(&metamask, &engine, &mut wallet, &book(token_id, ask), size).await;
+            tokio::task::yield_now().await;
+        }
+        changer.await.unwrap();
+        assert_within_grant(&metamask, &format!("concurrent round {}", round)).await;
+        assert!(!leg(&metamask, &engine, &mut wallet, &book("yes", 0.40), 10.0).await, "revoked grant still spends");
+    }
+}
@@ -0,0 +1,26 @@
+//! End-to-end Solana devnet test
+//!
+//! Needs network access and a working devnet faucet, so it only builds with
+//! `cargo test --features solana-devnet`. Set `SOLANA_RPC_URL` to run it
+//! against a local `solana-test-validator` instead.
+#![cfg(feature = "solana-devnet")]
+
+use arbishark::solana::{DevnetHarness, SolanaManager, DEVNET_URL};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::signature::Signer;
+
+#[test]
+fn devnet_airdrop_and_transfer() {
+    let url = std::env::var("SOLANA_RPC_URL").unwrap_or_else(|_| DEVNET_URL.to_string());
+    let manager = SolanaManager::with_url(&url);
+    manager.check_connection().expect("cluster reachable");
+
+    let harness = DevnetHarness::funded(manager, LAMPORTS_PER_SOL).expect("airdrop confirmed");
+    assert_eq!(harness.manager.balance(&harness.payer.pubkey()).unwrap(), LAMPORTS_PER_SOL);
+
+    let amount = LAMPORTS_PER_SOL / 10;
+    let recipient = harness.transfer_to_new_account(amount).expect("transfer confirmed");
+    assert_eq!(harness.manager.balance(&recipient).unwrap(), amount);
+    // Payer covered the amount plus the fee
+    assert!(harness.manager.balance(&harness.payer.pubkey()).unwrap() < LAMPORTS_PER_SOL - amount);
+}